@@ -10,12 +10,21 @@ const SCHEMA_PATH: &str = "../types.t";
 fn main() {
     let out_dir = env::var_os("OUT_DIR").unwrap();
 
+    generate(&Path::new(&out_dir).join("types.rs"), &[]);
+    generate(
+        &Path::new(&out_dir).join("types_borrowed.rs"),
+        &["--rust-borrowed"],
+    );
+}
+
+fn generate(path: &Path, flags: &[&str]) {
     let output = Command::new("typical")
         .arg("generate")
         .arg(SCHEMA_PATH)
         .arg("--list-schemas")
         .arg("--rust")
-        .arg(Path::new(&out_dir).join("types.rs"))
+        .arg(path)
+        .args(flags)
         .output()
         .expect("Failed to run Typical. Is it installed?");
 
@@ -1,4 +1,5 @@
 mod types;
+mod types_borrowed;
 
 use {
     std::{f64::consts::PI, io, mem::forget, time::Instant},
@@ -6,6 +7,10 @@ use {
         types::{ChoiceOut, MessageIn, MessageOut, StructIn, StructOut},
         Deserialize, Serialize,
     },
+    types_borrowed::{
+        types::{MessageIn as BorrowedMessageIn, StructIn as BorrowedStructIn},
+        DeserializeBorrowed,
+    },
 };
 
 const PATHOLOGICAL_ITERATIONS: usize = 300_000;
@@ -69,7 +74,7 @@ const S64_TEST_VALUES: &[i64] = &[
 ];
 
 #[allow(clippy::cast_precision_loss)]
-fn benchmark<T: Serialize, U: Deserialize>(message: &T, iterations: usize) -> io::Result<()> {
+fn benchmark<T: Serialize, U: Deserialize>(message: &T, iterations: usize) -> io::Result<Vec<u8>> {
     let message_size = message.size();
     let mut buffer = Vec::with_capacity(message_size);
 
@@ -109,6 +114,34 @@ fn benchmark<T: Serialize, U: Deserialize>(message: &T, iterations: usize) -> io
         (buffer.len() as f64) / deserialization_duration.as_secs_f64(),
     );
 
+    Ok(buffer)
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn benchmark_borrowed<'a, U: DeserializeBorrowed<'a>>(
+    buffer: &'a [u8],
+    iterations: usize,
+) -> io::Result<()> {
+    let message_size = buffer.len() / iterations;
+    let deserialization_instant = Instant::now();
+
+    for i in 0..iterations {
+        let offset = message_size * i;
+        let message = U::deserialize_borrowed(&buffer[offset..offset + message_size])?;
+
+        // Don't deallocate the memory in this loop, since that isn't what the benchmark is
+        // intended to measure.
+        forget(message);
+    }
+
+    let deserialization_duration = deserialization_instant.elapsed();
+
+    println!("Borrowed deserialization duration: {deserialization_duration:?}");
+    println!(
+        "Borrowed deserialization rate: {} bytes/second",
+        (buffer.len() as f64) / deserialization_duration.as_secs_f64(),
+    );
+
     Ok(())
 }
 
@@ -116,17 +149,19 @@ fn benchmark<T: Serialize, U: Deserialize>(message: &T, iterations: usize) -> io
 fn main() -> io::Result<()> {
     println!("Massive message test.");
 
-    benchmark::<StructOut, StructIn>(
+    let buffer = benchmark::<StructOut, StructIn>(
         &StructOut {
             x: "a".repeat(MASSIVE_STRING_SIZE),
         },
         1,
     )?;
+    benchmark_borrowed::<BorrowedStructIn>(&buffer, 1)?;
+    drop(buffer);
 
     println!();
     println!("Pathological message test.");
 
-    benchmark::<MessageOut, MessageIn>(
+    let buffer = benchmark::<MessageOut, MessageIn>(
         &MessageOut {
             a: (),
             b: PI,
@@ -249,5 +284,6 @@ fn main() -> io::Result<()> {
             ],
         },
         PATHOLOGICAL_ITERATIONS,
-    )
+    )?;
+    benchmark_borrowed::<BorrowedMessageIn>(&buffer, PATHOLOGICAL_ITERATIONS)
 }
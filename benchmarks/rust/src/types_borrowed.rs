@@ -0,0 +1,3 @@
+#![allow(clippy::all, clippy::pedantic, clippy::nursery, warnings)]
+
+include!(concat!(env!("OUT_DIR"), "/types_borrowed.rs"));
@@ -10,12 +10,34 @@ const SCHEMA_PATH: &str = "../types/types.t";
 fn main() {
     let out_dir = env::var_os("OUT_DIR").unwrap();
 
+    generate(
+        &Path::new(&out_dir).join("types.rs"),
+        &[
+            "--rust-derive",
+            "PartialEq",
+            "--rust-async",
+            "--rust-builders",
+        ],
+    );
+    generate(
+        &Path::new(&out_dir).join("types_borrowed.rs"),
+        &["--rust-borrowed"],
+    );
+    generate(
+        &Path::new(&out_dir).join("types_no_std.rs"),
+        &["--rust-no-std"],
+    );
+}
+
+fn generate(path: &Path, flags: &[&str]) {
     let output = Command::new("typical")
         .arg("generate")
         .arg(SCHEMA_PATH)
         .arg("--list-schemas")
         .arg("--rust")
-        .arg(Path::new(&out_dir).join("types.rs"))
+        .arg(path)
+        .arg("--rust-uuid")
+        .args(flags)
         .output()
         .expect("Failed to run Typical. Is it installed?");
 
@@ -1,11 +1,12 @@
 use {
-    crate::types::{Deserialize, Serialize},
+    crate::types::{Deserialize, DeserializeAsync, Serialize, SerializeAsync},
     std::{
         fmt::Debug,
         fs::{remove_file, OpenOptions},
         io::{self, Error, ErrorKind, Write},
         mem::drop,
     },
+    tokio::{io::BufReader, runtime::Builder},
 };
 
 const OMNIFILE_PATH: &str = "/tmp/omnifile-rust";
@@ -14,7 +15,10 @@ pub fn start() {
     drop(remove_file(OMNIFILE_PATH));
 }
 
-pub fn assert_match<T: Debug + Serialize, U: Debug + Deserialize>(
+pub fn assert_match<
+    T: Debug + Serialize + SerializeAsync,
+    U: Debug + Deserialize + DeserializeAsync,
+>(
     actual: &T,
     expected: &U,
 ) -> io::Result<()> {
@@ -45,10 +49,40 @@ pub fn assert_match<T: Debug + Serialize, U: Debug + Deserialize>(
         return Err(Error::new(ErrorKind::Other, "Mismatch!"));
     }
 
+    assert_async_match(actual, &buffer, &replica)
+}
+
+// Check that the asynchronous serialization and deserialization logic agrees with the synchronous
+// logic.
+fn assert_async_match<T: SerializeAsync, U: Debug + Deserialize + DeserializeAsync>(
+    actual: &T,
+    bytes: &[u8],
+    replica: &U,
+) -> io::Result<()> {
+    let runtime = Builder::new_current_thread().build()?;
+
+    let mut buffer = Vec::<u8>::new();
+    runtime.block_on(actual.serialize_async(&mut buffer))?;
+    println!("Bytes from asynchronous serialization: {buffer:?}");
+    if buffer != bytes {
+        return Err(Error::new(ErrorKind::Other, "Mismatch!"));
+    }
+
+    // Reading one byte at a time exercises the logic which spans multiple reads.
+    let async_replica =
+        runtime.block_on(U::deserialize_async(BufReader::with_capacity(1, bytes)))?;
+    println!("Message deserialized asynchronously from those bytes: {async_replica:?}");
+    if format!("{async_replica:?}") != format!("{replica:?}") {
+        return Err(Error::new(ErrorKind::Other, "Mismatch!"));
+    }
+
     Ok(())
 }
 
-pub fn assert_round_trip<T: Debug + Serialize + Clone, U: Debug + Deserialize + From<T>>(
+pub fn assert_round_trip<
+    T: Debug + Serialize + SerializeAsync + Clone,
+    U: Debug + Deserialize + DeserializeAsync + From<T>,
+>(
     value: &T,
 ) -> io::Result<()> {
     assert_match(value, &U::from(value.clone()))
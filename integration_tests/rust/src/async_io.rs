@@ -0,0 +1,61 @@
+use {
+    crate::types::{
+        comprehensive::types::{BarIn, BarOut},
+        Deserialize, DeserializeAsync, Serialize, SerializeAsync,
+    },
+    std::io::{self, Error, ErrorKind},
+    tokio::{io::BufReader, runtime::Builder},
+};
+
+pub fn run() -> io::Result<()> {
+    let runtime = Builder::new_current_thread().build()?;
+
+    let message = BarOut::PRequired(vec![
+        String::new(),
+        "=8 bytes".to_owned(),
+        "Hello, \u{5e78}\u{798f}!".to_owned(),
+    ]);
+    let mut buffer = Vec::<u8>::new();
+    message.serialize(&mut buffer)?;
+    println!("Bytes from serialization: {buffer:?}");
+
+    // The futures can be spawned, which requires them to be `Send`.
+    let async_buffer = runtime
+        .block_on(runtime.spawn(async move {
+            let mut buffer = Vec::<u8>::new();
+            message.serialize_async(&mut buffer).await?;
+            Ok::<_, Error>(buffer)
+        }))
+        .map_err(|error| Error::new(ErrorKind::Other, error))??;
+    assert_eq!(buffer, async_buffer);
+
+    let bytes = buffer.clone();
+    let replica = runtime
+        .block_on(runtime.spawn(async move {
+            BarIn::deserialize_async(BufReader::with_capacity(1, bytes.as_slice())).await
+        }))
+        .map_err(|error| Error::new(ErrorKind::Other, error))??;
+    println!("Message deserialized asynchronously from those bytes: {replica:?}");
+    assert_eq!(
+        format!("{replica:?}"),
+        format!("{:?}", BarIn::deserialize(buffer.as_slice())?),
+    );
+
+    // Truncated messages are rejected with the same kinds of errors as they are synchronously.
+    for length in 0..buffer.len() {
+        assert_eq!(
+            format!(
+                "{:?}",
+                BarIn::deserialize(&buffer[..length]).map_err(|error| error.kind()),
+            ),
+            format!(
+                "{:?}",
+                runtime
+                    .block_on(BarIn::deserialize_async(&buffer[..length]))
+                    .map_err(|error| error.kind()),
+            ),
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+// The deprecated fields and types still need to be tested.
+#![allow(deprecated)]
+
+use {
+    crate::{
+        assertions::{assert_match, assert_round_trip},
+        types::attributes::types::{
+            AccountIn, AccountOut, LegacyAccountIn, LegacyAccountOut, MeasurementOut, StatusIn,
+            StatusOut,
+        },
+    },
+    std::{collections::HashSet, io},
+};
+
+pub fn run() -> io::Result<()> {
+    let account = AccountOut {
+        kind: "administrator".to_owned(),
+        nickname: Some("Al".to_owned()),
+        display_name: "Alice".to_owned(),
+    };
+
+    assert_round_trip::<AccountOut, AccountIn>(&account)?;
+    assert_round_trip::<AccountOut, AccountIn>(&AccountOut {
+        kind: String::new(),
+        nickname: None,
+        display_name: String::new(),
+    })?;
+
+    assert_round_trip::<StatusOut, StatusIn>(&StatusOut::Active)?;
+    assert_round_trip::<StatusOut, StatusIn>(&StatusOut::Suspended("Spam.".to_owned()))?;
+
+    // Attributes don't affect the encoding.
+    assert_match(
+        &account,
+        &LegacyAccountIn {
+            kind: "administrator".to_owned(),
+            nickname: Some("Al".to_owned()),
+            display_name: "Alice".to_owned(),
+        },
+    )?;
+
+    // The `@rust.derive` attribute adds traits to the generated types.
+    assert_eq!(HashSet::from([account.clone(), account]).len(), 1);
+    assert_ne!(StatusOut::Active, StatusOut::Suspended(String::new()));
+
+    // Floating-point fields can be compared, and the generated types work with Serde. This type is
+    // only checked here rather than round-tripped, since every message which is round-tripped also
+    // ends up in the omnifile, which the TypeScript integration tests must reproduce exactly.
+    let measurement = MeasurementOut {
+        value: 1.5_f64,
+        samples: 3,
+    };
+    assert!(
+        measurement
+            < MeasurementOut {
+                value: 2.0_f64,
+                samples: 0,
+            },
+    );
+    let json = serde_json::to_string(&measurement)?;
+    assert_eq!(json, r#"{"value":1.5,"samples":3}"#);
+    assert_eq!(serde_json::from_str::<MeasurementOut>(&json)?, measurement);
+
+    // The `--rust-derive` option adds traits to all the generated types.
+    assert_eq!(
+        LegacyAccountOut {
+            kind: "administrator".to_owned(),
+            nickname: None,
+            display_name: "Alice".to_owned(),
+        },
+        LegacyAccountOut {
+            kind: "administrator".to_owned(),
+            nickname: None,
+            display_name: "Alice".to_owned(),
+        },
+    );
+
+    Ok(())
+}
@@ -0,0 +1,189 @@
+// The maps test message includes a map with `Unit` values.
+#![allow(clippy::zero_sized_map_values)]
+
+use {
+    crate::{
+        types::{
+            comprehensive::types::{BarIn, BarOut, LocalStructOut},
+            fixed_length::types::{ColorOut, FixedIn, FixedOut, VariableIn, VariableOut},
+            maps::types::{EntryOut, FooIn, FooOut},
+            Deserialize, Serialize,
+        },
+        types_borrowed::{
+            comprehensive::types::BarIn as BorrowedBarIn,
+            fixed_length::types::{FixedIn as BorrowedFixedIn, VariableIn as BorrowedVariableIn},
+            maps::types::FooIn as BorrowedFooIn,
+            DeserializeBorrowed,
+        },
+    },
+    std::{
+        collections::BTreeMap,
+        f64::consts::PI,
+        fmt::Debug,
+        io::{self, Error, ErrorKind},
+    },
+};
+
+fn serialize<T: Debug + Serialize>(message: &T) -> io::Result<Vec<u8>> {
+    println!("Message to be serialized: {message:?}");
+
+    let mut buffer = Vec::<u8>::new();
+    message.serialize(&mut buffer)?;
+    println!("Bytes from serialization: {buffer:?}");
+
+    Ok(buffer)
+}
+
+// Check that a borrowed type deserializes the same message as its owned counterpart.
+fn assert_borrowed_match<'a, T: Debug + Deserialize, U: Debug + DeserializeBorrowed<'a>>(
+    bytes: &'a [u8],
+) -> io::Result<()> {
+    let expected = T::deserialize(bytes)?;
+    let replica = U::deserialize_borrowed(bytes)?;
+    println!("Message borrowed from those bytes: {replica:?}");
+
+    if format!("{replica:?}") != format!("{expected:?}") {
+        return Err(Error::new(ErrorKind::Other, "Mismatch!"));
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_lines)]
+pub fn run() -> io::Result<()> {
+    let buffer = serialize(&BarOut::GRequired("Hello, \u{5e78}\u{798f}!".to_owned()))?;
+    assert_borrowed_match::<BarIn, BorrowedBarIn>(&buffer)?;
+    match BorrowedBarIn::deserialize_borrowed(&buffer)? {
+        BorrowedBarIn::GRequired(payload) => {
+            // The string points into the serialized message rather than being copied.
+            assert!(buffer.as_ptr_range().contains(&payload.as_ptr()));
+        }
+        _ => return Err(Error::new(ErrorKind::Other, "Mismatch!")),
+    }
+
+    let buffer = serialize(&BarOut::FRequired(vec![0, 42, 255]))?;
+    assert_borrowed_match::<BarIn, BorrowedBarIn>(&buffer)?;
+
+    let buffer = serialize(&BarOut::MRequired(vec![i64::MIN, 0, i64::MAX]))?;
+    assert_borrowed_match::<BarIn, BorrowedBarIn>(&buffer)?;
+    match BorrowedBarIn::deserialize_borrowed(&buffer)? {
+        BorrowedBarIn::MRequired(payload) => {
+            assert_eq!(payload.len(), 3);
+            assert_eq!(
+                payload.iter().collect::<Vec<_>>(),
+                vec![i64::MIN, 0, i64::MAX],
+            );
+        }
+        _ => return Err(Error::new(ErrorKind::Other, "Mismatch!")),
+    }
+
+    let buffer = serialize(&BarOut::ORequired(vec![
+        vec![],
+        vec![0, 42, 255],
+        vec![7, 6, 5, 4, 3, 2, 1, 0],
+    ]))?;
+    assert_borrowed_match::<BarIn, BorrowedBarIn>(&buffer)?;
+
+    let buffer = serialize(&BarOut::PRequired(vec![
+        String::new(),
+        "=8 bytes".to_owned(),
+        "Hello, \u{5e78}\u{798f}!".to_owned(),
+    ]))?;
+    assert_borrowed_match::<BarIn, BorrowedBarIn>(&buffer)?;
+
+    let buffer = serialize(&BarOut::QRequired(vec![
+        LocalStructOut {},
+        LocalStructOut {},
+    ]))?;
+    assert_borrowed_match::<BarIn, BorrowedBarIn>(&buffer)?;
+
+    let buffer = serialize(&BarOut::SRequired(vec![
+        vec![],
+        vec![()],
+        vec![(), ()],
+        vec![(), (), ()],
+    ]))?;
+    assert_borrowed_match::<BarIn, BorrowedBarIn>(&buffer)?;
+
+    let buffer = serialize(&BarOut::TRequired(vec![
+        vec![],
+        vec![0.0_f64],
+        vec![0.0_f64, PI],
+        vec![0.0_f64, PI, f64::EPSILON],
+    ]))?;
+    assert_borrowed_match::<BarIn, BorrowedBarIn>(&buffer)?;
+
+    let buffer = serialize(&FooOut {
+        a: BTreeMap::from([(false, ()), (true, ())]),
+        b: BTreeMap::from([(i64::MIN, 0.0_f64), (0, PI), (i64::MAX, f64::INFINITY)]),
+        c: BTreeMap::from([
+            (String::new(), 0),
+            ("=8 bytes".to_owned(), 42),
+            ("Hello, \u{5e78}\u{798f}!".to_owned(), u64::MAX),
+        ]),
+        d: BTreeMap::from([(0, String::new()), (u64::MAX, "Hello, World!".to_owned())]),
+        e: BTreeMap::from([(
+            "foo".to_owned(),
+            EntryOut {
+                key: "bar".to_owned(),
+                value: 42,
+            },
+        )]),
+        f: BTreeMap::from([(0, vec![]), (1, vec![i64::MIN, 0, i64::MAX])]),
+        g: BTreeMap::from([
+            (String::new(), BTreeMap::new()),
+            (
+                "foo".to_owned(),
+                BTreeMap::from([(false, vec![]), (true, vec![0, 42, 255])]),
+            ),
+        ]),
+        h: BTreeMap::from([(-1, true)]),
+        i: Some(BTreeMap::from([("foo".to_owned(), "bar".to_owned())])),
+    })?;
+    assert_borrowed_match::<FooIn, BorrowedFooIn>(&buffer)?;
+
+    let buffer = serialize(&FixedOut {
+        hash: [0xde, 0xad, 0xbe, 0xef],
+        point: [1.5_f64, -2.0_f64, 0.0_f64],
+        colors: [ColorOut { rgb: [255, 0, 0] }, ColorOut { rgb: [0, 0, 255] }],
+        names: ["Alice".to_owned(), String::new()],
+        matrix: Some([[1_i32, -1_i32], [i32::MAX, i32::MIN]]),
+        flags: [true, false, false, true],
+    })?;
+    assert_borrowed_match::<FixedIn, BorrowedFixedIn>(&buffer)?;
+    assert_borrowed_match::<VariableIn, BorrowedVariableIn>(&buffer)?;
+
+    let buffer = serialize(&VariableOut {
+        hash: vec![0xde, 0xad, 0xbe, 0xef],
+        point: vec![1.5_f64, -2.0_f64, 0.0_f64],
+        colors: vec![ColorOut { rgb: [255, 0, 0] }],
+        names: vec!["Alice".to_owned()],
+        matrix: None,
+        flags: vec![true],
+    })?;
+    assert_borrowed_match::<VariableIn, BorrowedVariableIn>(&buffer)?;
+    assert_eq!(
+        BorrowedFixedIn::deserialize_borrowed(&buffer)
+            .unwrap_err()
+            .to_string(),
+        "Array or bytes of the wrong length.",
+    );
+
+    // Truncated messages are rejected just like they are for the owned types.
+    let buffer = serialize(&BarOut::PRequired(vec!["Hello, World!".to_owned()]))?;
+    for length in 0..buffer.len() {
+        assert_eq!(
+            format!(
+                "{:?}",
+                BarIn::deserialize(&buffer[..length]).map_err(|error| error.kind())
+            ),
+            format!(
+                "{:?}",
+                BorrowedBarIn::deserialize_borrowed(&buffer[..length])
+                    .map_err(|error| error.kind()),
+            ),
+        );
+    }
+
+    Ok(())
+}
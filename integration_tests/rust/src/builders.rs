@@ -0,0 +1,87 @@
+use crate::types::{
+    nested::{
+        network::{NetworkKindOut, NetworkKindWirelessOut},
+        types::{DeviceIdOut, DeviceIpAddressOut, DeviceIpAddressV4Out, DeviceOut},
+    },
+    schema_evolution::after::ExampleStructOut,
+};
+
+pub fn run() {
+    // The fields can be given in any order, and the optional ones default to `None`.
+    let device = DeviceOut::builder()
+        .network(NetworkKindOut::Wired)
+        .address(DeviceIpAddressOut::V4(
+            DeviceIpAddressV4Out::builder()
+                .octets(vec![192, 168, 0, 1])
+                .build(),
+        ))
+        .id(DeviceIdOut(0))
+        .build();
+    println!("Message built: {device:?}");
+    assert_eq!(
+        device,
+        DeviceOut {
+            id: DeviceIdOut(0),
+            address: DeviceIpAddressOut::V4(DeviceIpAddressV4Out {
+                octets: vec![192, 168, 0, 1],
+            }),
+            network: NetworkKindOut::Wired,
+            peers: None,
+        },
+    );
+
+    // Optional fields can be given at any point.
+    let device = DeviceOut::builder()
+        .peers(vec![DeviceIpAddressOut::V6(vec![0xfe, 0x80])])
+        .id(DeviceIdOut(u64::MAX))
+        .address(DeviceIpAddressOut::V6(vec![0; 16]))
+        .network(NetworkKindOut::Wireless(
+            NetworkKindWirelessOut::builder()
+                .ssid("Home".to_owned())
+                .channel(11)
+                .build(),
+        ))
+        .build();
+    println!("Message built: {device:?}");
+    assert_eq!(
+        device,
+        DeviceOut {
+            id: DeviceIdOut(u64::MAX),
+            address: DeviceIpAddressOut::V6(vec![0; 16]),
+            network: NetworkKindOut::Wireless(NetworkKindWirelessOut {
+                ssid: "Home".to_owned(),
+                channel: Some(11),
+            }),
+            peers: Some(vec![DeviceIpAddressOut::V6(vec![0xfe, 0x80])]),
+        },
+    );
+
+    // Asymmetric fields must be given just like required ones.
+    let message = ExampleStructOut::builder()
+        .required_to_required("a".to_owned())
+        .required_to_asymmetric("b".to_owned())
+        .asymmetric_to_required("c".to_owned())
+        .asymmetric_to_asymmetric("d".to_owned())
+        .optional_to_required("e".to_owned())
+        .optional_to_asymmetric("f".to_owned())
+        .nonexistent_to_asymmetric(())
+        .optional_to_optional("g".to_owned())
+        .build();
+    println!("Message built: {message:?}");
+    assert_eq!(
+        message,
+        ExampleStructOut {
+            required_to_required: "a".to_owned(),
+            required_to_asymmetric: "b".to_owned(),
+            required_to_optional: None,
+            asymmetric_to_required: "c".to_owned(),
+            asymmetric_to_asymmetric: "d".to_owned(),
+            asymmetric_to_optional: None,
+            optional_to_required: "e".to_owned(),
+            optional_to_asymmetric: "f".to_owned(),
+            optional_to_optional: Some("g".to_owned()),
+            nonexistent_to_asymmetric: (),
+            nonexistent_to_optional: None,
+        },
+    );
+}
@@ -0,0 +1,38 @@
+use {
+    crate::{
+        assertions::assert_round_trip,
+        types::constants::types::{
+            SettingsIn, SettingsOut, EMPTY, ENABLED, GREETING, MAGIC, MAX_MEDIUM, MAX_SMALL,
+            MIN_MEDIUM, MIN_OFFSET, PROTOCOL_VERSION, RATIO, SCALE,
+        },
+    },
+    std::io,
+};
+
+pub fn run() -> io::Result<()> {
+    assert_round_trip::<SettingsOut, SettingsIn>(&SettingsOut {
+        version: PROTOCOL_VERSION,
+        offset: MIN_OFFSET,
+        small: MAX_SMALL,
+        medium: MAX_MEDIUM,
+        signed_medium: MIN_MEDIUM,
+        ratio: RATIO,
+        scale: SCALE,
+        enabled: ENABLED,
+        greeting: GREETING.to_owned(),
+        magic: MAGIC.to_vec(),
+    })?;
+
+    assert_round_trip::<SettingsOut, SettingsIn>(&SettingsOut {
+        version: 0,
+        offset: 0,
+        small: 0,
+        medium: 0,
+        signed_medium: 0,
+        ratio: 0.0_f32,
+        scale: 0.0_f64,
+        enabled: false,
+        greeting: String::new(),
+        magic: EMPTY.to_vec(),
+    })
+}
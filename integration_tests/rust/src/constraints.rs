@@ -0,0 +1,209 @@
+use {
+    crate::{
+        assertions::assert_round_trip,
+        types::{
+            constraints::types::{
+                GaugeIn, GaugeOut, HouseholdIn, HouseholdOut, PersonIn, PersonOut, ReadingIn,
+                ReadingOut, UsernameOut,
+            },
+            Deserialize, Serialize,
+        },
+    },
+    std::{collections::BTreeMap, fmt::Debug, io},
+};
+
+fn person() -> PersonOut {
+    PersonOut {
+        name: UsernameOut("Zoë".to_owned()),
+        age: 150,
+        temperature: Some(-40.5_f64),
+        nicknames: vec!["Z".to_owned(), "Zo".to_owned(), "Zozo".to_owned()],
+        balance: -1000,
+        scores: BTreeMap::from([("chess".to_owned(), 1200)]),
+        avatar: vec![0xff, 0xd8, 0xff, 0xe0],
+    }
+}
+
+// Check that a message which violates a constraint is rejected by both `validate` and
+// deserialization. Serialization doesn't check constraints, so such messages can still be encoded.
+fn assert_violation<T: Serialize, U: Debug + Deserialize>(
+    message: &T,
+    validate: fn(&T) -> io::Result<()>,
+    error_message: &str,
+) -> io::Result<()> {
+    assert_eq!(validate(message).unwrap_err().to_string(), error_message);
+
+    let mut buffer = Vec::<u8>::new();
+    message.serialize(&mut buffer)?;
+    let error = U::deserialize(buffer.as_slice()).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(error.to_string(), error_message);
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_lines)]
+pub fn run() -> io::Result<()> {
+    person().validate()?;
+    assert_round_trip::<PersonOut, PersonIn>(&person())?;
+    assert_round_trip::<PersonOut, PersonIn>(&PersonOut {
+        name: UsernameOut("\u{e9}".repeat(16)),
+        age: 0,
+        temperature: None,
+        nicknames: vec![],
+        balance: i64::MAX,
+        scores: BTreeMap::from([(String::new(), 0)]),
+        avatar: vec![],
+    })?;
+
+    let reading = ReadingOut::Label(
+        "Summit".to_owned(),
+        Box::new(ReadingOut::Latitude(-90.0_f32)),
+    );
+    reading.validate()?;
+    assert_round_trip::<ReadingOut, ReadingIn>(&reading)?;
+
+    let household = HouseholdOut {
+        members: vec![person(), person()],
+        reading: ReadingOut::Latitude(45.5_f32),
+    };
+    household.validate()?;
+    assert_round_trip::<HouseholdOut, HouseholdIn>(&household)?;
+
+    assert_violation::<PersonOut, PersonIn>(
+        &PersonOut {
+            name: UsernameOut(String::new()),
+            ..person()
+        },
+        PersonOut::validate,
+        "Field `name` of `Person` must have a length of at least 1.",
+    )?;
+    assert_violation::<PersonOut, PersonIn>(
+        &PersonOut {
+            name: UsernameOut("\u{e9}".repeat(17)),
+            ..person()
+        },
+        PersonOut::validate,
+        "Field `name` of `Person` must have a length of at most 16.",
+    )?;
+    assert_violation::<PersonOut, PersonIn>(
+        &PersonOut {
+            age: 151,
+            ..person()
+        },
+        PersonOut::validate,
+        "Field `age` of `Person` must be at most 150.",
+    )?;
+    assert_violation::<PersonOut, PersonIn>(
+        &PersonOut {
+            temperature: Some(f64::NAN),
+            ..person()
+        },
+        PersonOut::validate,
+        "Field `temperature` of `Person` must be at least -40.5.",
+    )?;
+    assert_violation::<PersonOut, PersonIn>(
+        &PersonOut {
+            temperature: Some(60.5_f64),
+            ..person()
+        },
+        PersonOut::validate,
+        "Field `temperature` of `Person` must be at most 60.",
+    )?;
+    assert_violation::<PersonOut, PersonIn>(
+        &PersonOut {
+            nicknames: vec![String::new(); 4],
+            ..person()
+        },
+        PersonOut::validate,
+        "Field `nicknames` of `Person` must have a length of at most 3.",
+    )?;
+    assert_violation::<PersonOut, PersonIn>(
+        &PersonOut {
+            balance: -1001,
+            ..person()
+        },
+        PersonOut::validate,
+        "Field `balance` of `Person` must be at least -1000.",
+    )?;
+    assert_violation::<PersonOut, PersonIn>(
+        &PersonOut {
+            scores: BTreeMap::new(),
+            ..person()
+        },
+        PersonOut::validate,
+        "Field `scores` of `Person` must have a length of at least 1.",
+    )?;
+    assert_violation::<PersonOut, PersonIn>(
+        &PersonOut {
+            avatar: vec![0; 5],
+            ..person()
+        },
+        PersonOut::validate,
+        "Field `avatar` of `Person` must have a length of at most 4.",
+    )?;
+
+    assert_violation::<ReadingOut, ReadingIn>(
+        &ReadingOut::Latitude(90.5_f32),
+        ReadingOut::validate,
+        "Field `latitude` of `Reading` must be at most 90.",
+    )?;
+    assert_violation::<ReadingOut, ReadingIn>(
+        &ReadingOut::Label("S".to_owned(), Box::new(ReadingOut::Latitude(0.0_f32))),
+        ReadingOut::validate,
+        "Field `label` of `Reading` must have a length of at least 2.",
+    )?;
+    assert_violation::<ReadingOut, ReadingIn>(
+        &ReadingOut::Label(
+            "Summit".to_owned(),
+            Box::new(ReadingOut::Latitude(-90.5_f32)),
+        ),
+        ReadingOut::validate,
+        "Field `latitude` of `Reading` must be at least -90.",
+    )?;
+
+    // Messages within other messages are checked too.
+    assert_violation::<HouseholdOut, HouseholdIn>(
+        &HouseholdOut {
+            members: vec![person(), person(), person()],
+            reading: ReadingOut::Latitude(0.0_f32),
+        },
+        HouseholdOut::validate,
+        "Field `members` of `Household` must have a length of at most 2.",
+    )?;
+    assert_violation::<HouseholdOut, HouseholdIn>(
+        &HouseholdOut {
+            members: vec![PersonOut {
+                age: 200,
+                ..person()
+            }],
+            reading: ReadingOut::Latitude(0.0_f32),
+        },
+        HouseholdOut::validate,
+        "Field `age` of `Person` must be at most 150.",
+    )?;
+    assert_violation::<HouseholdOut, HouseholdIn>(
+        &HouseholdOut {
+            members: vec![],
+            reading: ReadingOut::Latitude(f32::INFINITY),
+        },
+        HouseholdOut::validate,
+        "Field `latitude` of `Reading` must be at most 90.",
+    )?;
+
+    // A bound which isn't exactly representable as an `F32` is rounded just like the values are.
+    // This message isn't written to the omnifile, since the TypeScript integration tests must
+    // produce the same omnifile.
+    let gauge = GaugeOut { level: 0.1_f32 };
+    gauge.validate()?;
+    let mut buffer = Vec::<u8>::new();
+    gauge.serialize(&mut buffer)?;
+    GaugeIn::deserialize(buffer.as_slice())?;
+    assert_violation::<GaugeOut, GaugeIn>(
+        &GaugeOut {
+            level: 0.100_000_1_f32,
+        },
+        GaugeOut::validate,
+        "Field `level` of `Gauge` must be at most 0.1.",
+    )
+}
@@ -0,0 +1,79 @@
+use {
+    crate::{
+        assertions::{assert_match, assert_round_trip},
+        types::{
+            fixed_length::types::{
+                ChecksumIn, ChecksumOut, ColorIn, ColorOut, FixedIn, FixedOut, VariableIn,
+                VariableOut,
+            },
+            Deserialize, Serialize,
+        },
+    },
+    std::{fmt::Debug, io},
+};
+
+fn fixed() -> FixedOut {
+    FixedOut {
+        hash: [0xde, 0xad, 0xbe, 0xef],
+        point: [1.5_f64, -2.0_f64, 0.0_f64],
+        colors: [ColorOut { rgb: [255, 0, 0] }, ColorOut { rgb: [0, 0, 255] }],
+        names: ["Alice".to_owned(), String::new()],
+        matrix: Some([[1, -1], [i32::MAX, i32::MIN]]),
+        flags: [true, false, false, true],
+    }
+}
+
+fn variable() -> VariableOut {
+    VariableOut {
+        hash: vec![0xde, 0xad, 0xbe, 0xef],
+        point: vec![1.5_f64, -2.0_f64, 0.0_f64],
+        colors: vec![ColorOut { rgb: [255, 0, 0] }, ColorOut { rgb: [0, 0, 255] }],
+        names: vec!["Alice".to_owned(), String::new()],
+        matrix: Some(vec![vec![1, -1], vec![i32::MAX, i32::MIN]]),
+        flags: vec![true, false, false, true],
+    }
+}
+
+// Check that a message is rejected when decoded as a type which requires different lengths.
+fn assert_wrong_length<T: Serialize, U: Debug + Deserialize>(message: &T) -> io::Result<()> {
+    let mut buffer = Vec::<u8>::new();
+    message.serialize(&mut buffer)?;
+    let error = U::deserialize(buffer.as_slice()).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(error.to_string(), "Array or bytes of the wrong length.");
+
+    Ok(())
+}
+
+pub fn run() -> io::Result<()> {
+    assert_round_trip::<ColorOut, ColorIn>(&ColorOut { rgb: [1, 2, 3] })?;
+    assert_round_trip::<FixedOut, FixedIn>(&fixed())?;
+    assert_round_trip::<FixedOut, FixedIn>(&FixedOut {
+        matrix: None,
+        ..fixed()
+    })?;
+
+    assert_round_trip::<ChecksumOut, ChecksumIn>(&ChecksumOut::Crc32([0; 4]))?;
+    assert_round_trip::<ChecksumOut, ChecksumIn>(&ChecksumOut::Sha256([0xff; 32]))?;
+    assert_round_trip::<ChecksumOut, ChecksumIn>(&ChecksumOut::Unknown(vec![1, 2, 3]))?;
+
+    // Fixed-length values are encoded like variable-length ones.
+    assert_match(&fixed(), &VariableIn::from(variable()))?;
+    assert_match(&variable(), &FixedIn::from(fixed()))?;
+
+    // Decoding fails if a length doesn't match.
+    assert_wrong_length::<VariableOut, FixedIn>(&VariableOut {
+        hash: vec![0xde, 0xad, 0xbe],
+        ..variable()
+    })?;
+    assert_wrong_length::<VariableOut, FixedIn>(&VariableOut {
+        matrix: Some(vec![vec![1_i32, -1_i32], vec![0_i32]]),
+        ..variable()
+    })?;
+    assert_wrong_length::<VariableOut, FixedIn>(&VariableOut {
+        colors: vec![],
+        ..variable()
+    })?;
+
+    Ok(())
+}
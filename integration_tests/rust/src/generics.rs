@@ -0,0 +1,97 @@
+use {
+    crate::{
+        assertions::assert_round_trip,
+        types::generics::{
+            containers::{
+                MaybeArrayBoolOut, MaybeMaybeArrayBoolOut, MaybeStringOut, PageUserOut,
+                PagePairU64MapStringUserOut, PairStringBytesOut, PairStringUnitOut,
+                PairStringUserOut, PairU64MapStringUserOut, PairUserUserOut,
+            },
+            types::{BarIn, BarOut, FooIn, FooOut, TreeUnitOut, TreeUserOut, UserOut},
+        },
+    },
+    std::{collections::BTreeMap, io},
+};
+
+fn user(name: &str, nickname: Option<&str>) -> UserOut {
+    UserOut {
+        name: name.to_owned(),
+        nickname: nickname.map_or(MaybeStringOut::None, |nickname| {
+            MaybeStringOut::Some(nickname.to_owned())
+        }),
+    }
+}
+
+pub fn run() -> io::Result<()> {
+    assert_round_trip::<FooOut, FooIn>(&FooOut {
+        users: PageUserOut {
+            items: vec![],
+            next: String::new(),
+        },
+        pairs: PagePairU64MapStringUserOut {
+            items: vec![],
+            next: String::new(),
+        },
+        nested: MaybeMaybeArrayBoolOut::None,
+        pair: None,
+        local: TreeUserOut {
+            value: user("", None),
+            leaves: vec![],
+        },
+    })?;
+
+    assert_round_trip::<FooOut, FooIn>(&FooOut {
+        users: PageUserOut {
+            items: vec![user("Alice", Some("Al")), user("Bob", None)],
+            next: "cursor".to_owned(),
+        },
+        pairs: PagePairU64MapStringUserOut {
+            items: vec![
+                PairU64MapStringUserOut {
+                    first: 0,
+                    second: BTreeMap::new(),
+                },
+                PairU64MapStringUserOut {
+                    first: u64::MAX,
+                    second: BTreeMap::from([
+                        ("alice".to_owned(), user("Alice", None)),
+                        ("bob".to_owned(), user("Bob", Some("Bobby"))),
+                    ]),
+                },
+            ],
+            next: String::new(),
+        },
+        nested: MaybeMaybeArrayBoolOut::Some(MaybeArrayBoolOut::Some(vec![false, true])),
+        pair: Some(PairStringBytesOut {
+            first: "Hello, World!".to_owned(),
+            second: vec![0, 42, 255],
+        }),
+        local: TreeUserOut {
+            value: user("root", None),
+            leaves: vec![
+                PairStringUserOut {
+                    first: "left".to_owned(),
+                    second: user("Carol", None),
+                },
+                PairStringUserOut {
+                    first: "right".to_owned(),
+                    second: user("Dave", Some("D")),
+                },
+            ],
+        },
+    })?;
+
+    assert_round_trip::<BarOut, BarIn>(&BarOut::A(PairUserUserOut {
+        first: user("Alice", None),
+        second: user("Bob", None),
+    }))?;
+    assert_round_trip::<BarOut, BarIn>(&BarOut::B(TreeUnitOut {
+        value: (),
+        leaves: vec![PairStringUnitOut {
+            first: "leaf".to_owned(),
+            second: (),
+        }],
+    }))?;
+
+    Ok(())
+}
@@ -1,24 +1,89 @@
+// The `no_std` types [ref:no_std] refer to `alloc` directly.
+extern crate alloc;
+
 mod assertions;
+mod async_io;
+mod attributes;
+mod borrowed;
+mod builders;
 mod circular_dependency;
 mod comprehensive;
+mod constants;
+mod constraints;
 mod degenerate;
+mod fixed_length;
+mod generics;
+mod maps;
+mod nested;
+mod newtypes;
+mod no_std;
+mod numbers;
 mod schema_evolution;
+mod services;
 mod types;
+mod types_borrowed;
+mod types_no_std;
+mod well_known;
 
 use std::io;
 
 fn main() -> io::Result<()> {
     assertions::start();
 
-    println!("Running circular dependency integration test\u{2026}\n");
+    println!("Running asynchronous I/O integration test\u{2026}\n");
+    async_io::run()?;
+
+    println!("\nRunning attributes integration test\u{2026}\n");
+    attributes::run()?;
+
+    println!("\nRunning borrowed integration test\u{2026}\n");
+    borrowed::run()?;
+
+    println!("\nRunning builders integration test\u{2026}\n");
+    builders::run();
+
+    println!("\nRunning circular dependency integration test\u{2026}\n");
     circular_dependency::run()?;
 
     println!("\nRunning comprehensive integration test\u{2026}\n");
     comprehensive::run()?;
 
+    println!("\nRunning constants integration test\u{2026}\n");
+    constants::run()?;
+
+    println!("\nRunning constraints integration test\u{2026}\n");
+    constraints::run()?;
+
     println!("\nRunning degenerate integration test\u{2026}\n");
     degenerate::run()?;
 
+    println!("\nRunning fixed-length integration test\u{2026}\n");
+    fixed_length::run()?;
+
+    println!("\nRunning generics integration test\u{2026}\n");
+    generics::run()?;
+
+    println!("\nRunning maps integration test\u{2026}\n");
+    maps::run()?;
+
+    println!("\nRunning nested integration test\u{2026}\n");
+    nested::run()?;
+
+    println!("\nRunning newtypes integration test\u{2026}\n");
+    newtypes::run()?;
+
+    println!("\nRunning `no_std` integration test\u{2026}\n");
+    no_std::run()?;
+
+    println!("\nRunning numbers integration test\u{2026}\n");
+    numbers::run()?;
+
     println!("\nRunning schema evolution integration test\u{2026}\n");
-    schema_evolution::run()
+    schema_evolution::run()?;
+
+    println!("\nRunning services integration test\u{2026}\n");
+    services::run()?;
+
+    println!("\nRunning well-known types integration test\u{2026}\n");
+    well_known::run()
 }
@@ -0,0 +1,97 @@
+// Maps with `Unit` values are exercised deliberately, since they're how sets are represented.
+#![allow(clippy::zero_sized_map_values)]
+
+use {
+    crate::{
+        assertions::{assert_match, assert_round_trip},
+        types::maps::types::{BarIn, BarOut, EntryListOut, EntryMapIn, EntryOut, FooIn, FooOut},
+    },
+    std::{collections::BTreeMap, f64::consts::PI, io},
+};
+
+pub fn run() -> io::Result<()> {
+    assert_round_trip::<FooOut, FooIn>(&FooOut {
+        a: BTreeMap::new(),
+        b: BTreeMap::new(),
+        c: BTreeMap::new(),
+        d: BTreeMap::new(),
+        e: BTreeMap::new(),
+        f: BTreeMap::new(),
+        g: BTreeMap::new(),
+        h: BTreeMap::new(),
+        i: None,
+    })?;
+
+    assert_round_trip::<FooOut, FooIn>(&FooOut {
+        a: BTreeMap::from([(false, ()), (true, ())]),
+        b: BTreeMap::from([(i64::MIN, 0.0_f64), (0, PI), (i64::MAX, f64::INFINITY)]),
+        c: BTreeMap::from([
+            (String::new(), 0),
+            ("=8 bytes".to_owned(), 42),
+            ("Hello, \u{5e78}\u{798f}!".to_owned(), u64::MAX),
+        ]),
+        d: BTreeMap::from([(0, String::new()), (u64::MAX, "Hello, World!".to_owned())]),
+        e: BTreeMap::from([(
+            "foo".to_owned(),
+            EntryOut {
+                key: "bar".to_owned(),
+                value: 42,
+            },
+        )]),
+        f: BTreeMap::from([(0, vec![]), (1, vec![i64::MIN, 0, i64::MAX])]),
+        g: BTreeMap::from([
+            (String::new(), BTreeMap::new()),
+            (
+                "foo".to_owned(),
+                BTreeMap::from([(false, vec![]), (true, vec![0, 42, 255])]),
+            ),
+        ]),
+        h: BTreeMap::from([(-1, true)]),
+        i: Some(BTreeMap::from([("foo".to_owned(), "bar".to_owned())])),
+    })?;
+
+    assert_round_trip::<BarOut, BarIn>(&BarOut::A(BTreeMap::new()))?;
+    assert_round_trip::<BarOut, BarIn>(&BarOut::A(BTreeMap::from([
+        ("foo".to_owned(), 0),
+        ("bar".to_owned(), u64::MAX),
+    ])))?;
+    assert_round_trip::<BarOut, BarIn>(&BarOut::B(BTreeMap::from([(
+        42,
+        EntryOut {
+            key: "foo".to_owned(),
+            value: 42,
+        },
+    )])))?;
+
+    let fallback = BarOut::A(BTreeMap::new());
+
+    assert_round_trip::<BarOut, BarIn>(&BarOut::C(
+        BTreeMap::from([(true, vec!["foo".to_owned(), "bar".to_owned()])]),
+        Box::new(fallback.clone()),
+    ))?;
+    assert_round_trip::<BarOut, BarIn>(&BarOut::D(
+        BTreeMap::from([(-1, ()), (1, ())]),
+        Box::new(fallback),
+    ))?;
+
+    // A map is encoded like an array of entries, so the two are interchangeable.
+    assert_match(
+        &EntryListOut {
+            entries: vec![
+                EntryOut {
+                    key: "bar".to_owned(),
+                    value: 1,
+                },
+                EntryOut {
+                    key: "foo".to_owned(),
+                    value: 2,
+                },
+            ],
+        },
+        &EntryMapIn {
+            entries: BTreeMap::from([("bar".to_owned(), 1), ("foo".to_owned(), 2)]),
+        },
+    )?;
+
+    Ok(())
+}
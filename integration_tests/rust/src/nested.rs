@@ -0,0 +1,61 @@
+use {
+    crate::{
+        assertions::{assert_match, assert_round_trip},
+        types::nested::{
+            network::{NetworkIn, NetworkKindOut, NetworkKindWirelessOut, NetworkOut},
+            types::{
+                DeviceIdOut, DeviceIn, DeviceIpAddressIn, DeviceIpAddressOut,
+                DeviceIpAddressV4Out, DeviceOut, PlainV4In,
+            },
+        },
+    },
+    std::io,
+};
+
+fn v4() -> DeviceIpAddressV4Out {
+    DeviceIpAddressV4Out {
+        octets: vec![192, 168, 0, 1],
+    }
+}
+
+pub fn run() -> io::Result<()> {
+    assert_round_trip::<DeviceOut, DeviceIn>(&DeviceOut {
+        id: DeviceIdOut(0),
+        address: DeviceIpAddressOut::V6(vec![0; 16]),
+        network: NetworkKindOut::Wired,
+        peers: None,
+    })?;
+
+    assert_round_trip::<DeviceOut, DeviceIn>(&DeviceOut {
+        id: DeviceIdOut(u64::MAX),
+        address: DeviceIpAddressOut::V4(v4()),
+        network: NetworkKindOut::Wireless(NetworkKindWirelessOut {
+            ssid: "Home".to_owned(),
+            channel: Some(11),
+        }),
+        peers: Some(vec![
+            DeviceIpAddressOut::V4(DeviceIpAddressV4Out { octets: vec![] }),
+            DeviceIpAddressOut::V6(vec![0xfe, 0x80]),
+        ]),
+    })?;
+
+    assert_round_trip::<DeviceIpAddressOut, DeviceIpAddressIn>(&DeviceIpAddressOut::V4(v4()))?;
+
+    assert_round_trip::<NetworkOut, NetworkIn>(&NetworkOut {
+        name: "Office".to_owned(),
+        kind: NetworkKindOut::Wireless(NetworkKindWirelessOut {
+            ssid: String::new(),
+            channel: None,
+        }),
+    })?;
+
+    // Nesting doesn't affect the encoding.
+    assert_match(
+        &v4(),
+        &PlainV4In {
+            octets: vec![192, 168, 0, 1],
+        },
+    )?;
+
+    Ok(())
+}
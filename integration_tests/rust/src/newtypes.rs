@@ -0,0 +1,64 @@
+use {
+    crate::{
+        assertions::{assert_match, assert_round_trip},
+        types::newtypes::{
+            ids::{EmailOut, UserIdOut},
+            types::{
+                AccountIn, AccountOut, AdministratorOut, PlainAccountIn, PrincipalIn,
+                PrincipalOut, PrimaryEmailOut, ScoresOut, TaggedUserIdOut,
+            },
+        },
+    },
+    std::{collections::BTreeMap, io},
+};
+
+fn account() -> AccountOut {
+    AccountOut {
+        id: UserIdOut(42),
+        email: PrimaryEmailOut(EmailOut("alice@example.com".to_owned())),
+        backup_emails: Some(vec![
+            EmailOut(String::new()),
+            EmailOut("alice@example.org".to_owned()),
+        ]),
+        scores: ScoresOut(vec![0.0_f64, 1.5_f64, f64::INFINITY]),
+        tags: TaggedUserIdOut(BTreeMap::from([
+            ("manager".to_owned(), UserIdOut(u64::MAX)),
+            ("mentor".to_owned(), UserIdOut(0)),
+        ])),
+        friends: vec![UserIdOut(0), UserIdOut(1), UserIdOut(u64::MAX)],
+    }
+}
+
+pub fn run() -> io::Result<()> {
+    assert_round_trip::<AccountOut, AccountIn>(&AccountOut {
+        id: UserIdOut(0),
+        email: PrimaryEmailOut(EmailOut(String::new())),
+        backup_emails: None,
+        scores: ScoresOut(vec![]),
+        tags: TaggedUserIdOut(BTreeMap::new()),
+        friends: vec![],
+    })?;
+
+    assert_round_trip::<AccountOut, AccountIn>(&account())?;
+
+    assert_round_trip::<PrincipalOut, PrincipalIn>(&PrincipalOut::User(UserIdOut(u64::MAX)))?;
+    assert_round_trip::<PrincipalOut, PrincipalIn>(&PrincipalOut::Administrator(
+        AdministratorOut(account()),
+    ))?;
+    assert_round_trip::<PrincipalOut, PrincipalIn>(&PrincipalOut::Anonymous)?;
+
+    // Newtypes are encoded like their underlying types.
+    assert_match(
+        &account(),
+        &PlainAccountIn {
+            id: 42,
+            email: "alice@example.com".to_owned(),
+            backup_emails: Some(vec![String::new(), "alice@example.org".to_owned()]),
+            scores: vec![0.0_f64, 1.5_f64, f64::INFINITY],
+            tags: BTreeMap::from([("manager".to_owned(), u64::MAX), ("mentor".to_owned(), 0)]),
+            friends: vec![0, 1, u64::MAX],
+        },
+    )?;
+
+    Ok(())
+}
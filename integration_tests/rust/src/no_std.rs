@@ -0,0 +1,210 @@
+// The maps test message includes a map with `Unit` values.
+#![allow(clippy::zero_sized_map_values)]
+
+use {
+    crate::{
+        types::{
+            comprehensive::types::{BarIn, BarOut, LocalStructOut},
+            constraints::types::ReadingOut,
+            fixed_length::types::{ColorOut, FixedIn, FixedOut},
+            maps::types::{EntryOut, FooIn, FooOut},
+            Deserialize, Serialize,
+        },
+        types_no_std::{
+            self,
+            comprehensive::types::{BarIn as NoStdBarIn, BarOut as NoStdBarOut},
+            constraints::types::ReadingOut as NoStdReadingOut,
+            fixed_length::types::FixedIn as NoStdFixedIn,
+            maps::types::FooIn as NoStdFooIn,
+            Deserialize as _,
+        },
+    },
+    std::{
+        collections::BTreeMap,
+        f64::consts::PI,
+        fmt::Debug,
+        io::{self, Error, ErrorKind},
+    },
+};
+
+// The `no_std` types report errors with their own error type.
+fn convert_error(error: &types_no_std::io::Error) -> Error {
+    Error::new(ErrorKind::Other, error.to_string())
+}
+
+// Check that a message serialized with the standard types deserializes the same way with the
+// `no_std` types.
+fn assert_no_std_match<
+    T: Debug + Serialize,
+    U: Debug + Deserialize,
+    V: Debug + types_no_std::Deserialize,
+>(
+    message: &T,
+) -> io::Result<Vec<u8>> {
+    println!("Message to be serialized: {message:?}");
+
+    let mut buffer = Vec::<u8>::new();
+    message.serialize(&mut buffer)?;
+    println!("Bytes from serialization: {buffer:?}");
+
+    let expected = U::deserialize(buffer.as_slice())?;
+    let replica = V::deserialize(buffer.as_slice()).map_err(|error| convert_error(&error))?;
+    println!("Message deserialized by the `no_std` types: {replica:?}");
+
+    if format!("{replica:?}") != format!("{expected:?}") {
+        return Err(Error::new(ErrorKind::Other, "Mismatch!"));
+    }
+
+    Ok(buffer)
+}
+
+// Serialize a message with the `no_std` types.
+fn serialize_no_std<T: Debug + types_no_std::Serialize>(message: &T) -> io::Result<Vec<u8>> {
+    println!("Message to be serialized by the `no_std` types: {message:?}");
+
+    let mut buffer = Vec::<u8>::new();
+    message
+        .serialize(&mut buffer)
+        .map_err(|error| convert_error(&error))?;
+    println!("Bytes from serialization: {buffer:?}");
+
+    if buffer.len() != message.size() {
+        return Err(Error::new(ErrorKind::Other, "Mismatch!"));
+    }
+
+    Ok(buffer)
+}
+
+#[allow(clippy::too_many_lines)]
+pub fn run() -> io::Result<()> {
+    let buffer = assert_no_std_match::<_, BarIn, NoStdBarIn>(&BarOut::PRequired(vec![
+        String::new(),
+        "=8 bytes".to_owned(),
+        "Hello, \u{5e78}\u{798f}!".to_owned(),
+    ]))?;
+    let no_std_buffer = serialize_no_std(&NoStdBarOut::PRequired(vec![
+        String::new(),
+        "=8 bytes".to_owned(),
+        "Hello, \u{5e78}\u{798f}!".to_owned(),
+    ]))?;
+    assert_eq!(buffer, no_std_buffer);
+
+    assert_no_std_match::<_, BarIn, NoStdBarIn>(&BarOut::MRequired(vec![i64::MIN, 0, i64::MAX]))?;
+    assert_no_std_match::<_, BarIn, NoStdBarIn>(&BarOut::QRequired(vec![
+        LocalStructOut {},
+        LocalStructOut {},
+    ]))?;
+    assert_no_std_match::<_, BarIn, NoStdBarIn>(&BarOut::TRequired(vec![
+        vec![],
+        vec![0.0_f64, PI, f64::EPSILON],
+    ]))?;
+
+    assert_no_std_match::<_, FooIn, NoStdFooIn>(&FooOut {
+        a: BTreeMap::from([(false, ()), (true, ())]),
+        b: BTreeMap::from([(i64::MIN, 0.0_f64), (0, PI), (i64::MAX, f64::INFINITY)]),
+        c: BTreeMap::from([("Hello, \u{5e78}\u{798f}!".to_owned(), u64::MAX)]),
+        d: BTreeMap::from([(0, String::new()), (u64::MAX, "Hello, World!".to_owned())]),
+        e: BTreeMap::from([(
+            "foo".to_owned(),
+            EntryOut {
+                key: "bar".to_owned(),
+                value: 42,
+            },
+        )]),
+        f: BTreeMap::from([(0, vec![]), (1, vec![i64::MIN, 0, i64::MAX])]),
+        g: BTreeMap::from([(
+            "foo".to_owned(),
+            BTreeMap::from([(false, vec![]), (true, vec![0, 42, 255])]),
+        )]),
+        h: BTreeMap::from([(-1, true)]),
+        i: None,
+    })?;
+
+    assert_no_std_match::<_, FixedIn, NoStdFixedIn>(&FixedOut {
+        hash: [0xde, 0xad, 0xbe, 0xef],
+        point: [1.5_f64, -2.0_f64, 0.0_f64],
+        colors: [ColorOut { rgb: [255, 0, 0] }, ColorOut { rgb: [0, 0, 255] }],
+        names: ["Alice".to_owned(), String::new()],
+        matrix: Some([[1_i32, -1_i32], [i32::MAX, i32::MIN]]),
+        flags: [true, false, false, true],
+    })?;
+
+    // Messages serialized by the `no_std` types can be deserialized by the standard ones.
+    let buffer = serialize_no_std(&NoStdBarOut::FRequired(vec![0, 42, 255]))?;
+    let replica = BarIn::deserialize(buffer.as_slice())?;
+    if !matches!(replica, BarIn::FRequired(ref payload) if payload == &[0, 42, 255]) {
+        return Err(Error::new(ErrorKind::Other, "Mismatch!"));
+    }
+
+    // Errors have the same kinds as they do with the standard types.
+    for length in 0..buffer.len() {
+        assert_eq!(
+            format!(
+                "{:?}",
+                BarIn::deserialize(&buffer[..length]).map_err(|error| error.kind()),
+            ),
+            format!(
+                "{:?}",
+                NoStdBarIn::deserialize(&buffer[..length]).map_err(|error| error.kind()),
+            ),
+        );
+    }
+    assert_eq!(
+        ReadingOut::Latitude(100.0_f32)
+            .validate()
+            .unwrap_err()
+            .to_string(),
+        NoStdReadingOut::Latitude(100.0_f32)
+            .validate()
+            .unwrap_err()
+            .to_string(),
+    );
+
+    // The crate which includes the `no_std` types can enable its `std` feature to use them with
+    // `std::io` as well.
+    #[cfg(feature = "std")]
+    run_std()?;
+
+    Ok(())
+}
+
+// With the `std` feature, the `no_std` types work with any `std::io` reader or writer, their errors
+// convert to and from `std::io::Error`, and the well-known types convert to and from `SystemTime`.
+#[cfg(feature = "std")]
+fn run_std() -> io::Result<()> {
+    use {
+        crate::types_no_std::{
+            typical::time::{TimestampIn, TimestampOut},
+            Serialize as _,
+        },
+        std::{
+            io::{BufReader, Cursor},
+            time::{Duration, SystemTime, UNIX_EPOCH},
+        },
+    };
+
+    // The errors of the `no_std` types convert to `std::io::Error`, so `?` works here.
+    let message = NoStdBarOut::FRequired(vec![0, 42, 255]);
+    let mut writer = Cursor::new(Vec::<u8>::new());
+    message.serialize(&mut writer)?;
+    let buffer = writer.into_inner();
+    assert_eq!(buffer, serialize_no_std(&message)?);
+
+    let replica = NoStdBarIn::deserialize(BufReader::new(buffer.as_slice()))?;
+    if !matches!(replica, NoStdBarIn::FRequired(ref payload) if payload == &[0, 42, 255]) {
+        return Err(Error::new(ErrorKind::Other, "Mismatch!"));
+    }
+
+    let error = io::Error::from(NoStdBarIn::deserialize(&buffer[..1]).unwrap_err());
+    assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+
+    let error = types_no_std::io::Error::from(io::Error::new(ErrorKind::InvalidData, "Bad."));
+    assert_eq!(error.kind(), types_no_std::io::ErrorKind::InvalidData);
+    assert_eq!((&error as &dyn std::error::Error).to_string(), "Bad.");
+
+    let time = UNIX_EPOCH - Duration::from_millis(1500);
+    let timestamp = TimestampOut::try_from(time)?;
+    assert_eq!(SystemTime::try_from(TimestampIn::from(timestamp))?, time);
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use {
+    crate::{
+        assertions::{assert_match, assert_round_trip},
+        types::{
+            numbers::types::{
+                BarIn, BarOut, FooIn, FooOut, NarrowIn, NarrowOut, WideIn, WideOut,
+            },
+            Deserialize, Serialize,
+        },
+    },
+    std::{
+        collections::BTreeMap,
+        io::{self, Error, ErrorKind},
+    },
+};
+
+pub fn run() -> io::Result<()> {
+    assert_round_trip::<FooOut, FooIn>(&FooOut {
+        a: 0,
+        b: 0,
+        c: 0,
+        d: 0.0_f32,
+        e: vec![],
+        f: vec![],
+        g: vec![],
+        h: vec![],
+        i: BTreeMap::new(),
+        j: BTreeMap::new(),
+        k: 0,
+        l: None,
+    })?;
+
+    assert_round_trip::<FooOut, FooIn>(&FooOut {
+        a: u8::MAX,
+        b: u32::MAX,
+        c: i32::MIN,
+        d: 1.5_f32,
+        e: vec![0, 42, u8::MAX],
+        f: vec![0, 42, u32::MAX],
+        g: vec![i32::MIN, -1_i32, 0_i32, 1_i32, i32::MAX],
+        h: vec![0.0_f32, -2.0_f32, f32::MAX, f32::INFINITY],
+        i: BTreeMap::from([(0, f32::MIN), (u8::MAX, 0.5_f32)]),
+        j: BTreeMap::from([(i32::MIN, 0), (i32::MAX, u32::MAX)]),
+        k: 42,
+        l: Some(-0.25_f32),
+    })?;
+
+    assert_round_trip::<BarOut, BarIn>(&BarOut::A(u8::MAX))?;
+    assert_round_trip::<BarOut, BarIn>(&BarOut::B(u32::MAX))?;
+    assert_round_trip::<BarOut, BarIn>(&BarOut::C(i32::MIN))?;
+    assert_round_trip::<BarOut, BarIn>(&BarOut::D(f32::MIN))?;
+
+    let fallback = BarOut::A(42);
+
+    assert_round_trip::<BarOut, BarIn>(&BarOut::E(vec![1, 2, 3], Box::new(fallback.clone())))?;
+    assert_round_trip::<BarOut, BarIn>(&BarOut::F(-42, Box::new(fallback)))?;
+
+    // The narrow integer types are encoded like their 64-bit counterparts, so they can be widened.
+    assert_match(
+        &NarrowOut {
+            x: u8::MAX,
+            y: u32::MAX,
+            z: i32::MIN,
+        },
+        &WideIn {
+            x: u64::from(u8::MAX),
+            y: u64::from(u32::MAX),
+            z: i64::from(i32::MIN),
+        },
+    )?;
+
+    // Values which don't fit in the narrow types are rejected rather than truncated.
+    let mut buffer = Vec::<u8>::new();
+    WideOut {
+        x: u64::from(u8::MAX) + 1,
+        y: 0,
+        z: 0,
+    }
+    .serialize(&mut buffer)?;
+    if NarrowIn::deserialize(buffer.as_slice()).is_ok() {
+        return Err(Error::new(ErrorKind::Other, "Mismatch!"));
+    }
+
+    Ok(())
+}
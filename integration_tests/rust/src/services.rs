@@ -0,0 +1,114 @@
+use {
+    crate::{
+        assertions::assert_round_trip,
+        types::{
+            degenerate::types::{EmptyStructIn, EmptyStructOut},
+            services::types::{
+                dispatch_email, dispatch_retired, EmailClient, EmailHandler, ListEmailsRequestIn,
+                ListEmailsRequestOut, PageSendEmailRequestIn, PageSendEmailRequestOut,
+                RetiredHandler, SendEmailRequestIn, SendEmailRequestOut, SendEmailResponseIn,
+                SendEmailResponseOut,
+            },
+            Transport,
+        },
+    },
+    std::io::{self, ErrorKind},
+};
+
+struct Server {
+    sent: Vec<SendEmailRequestIn>,
+}
+
+impl EmailHandler for Server {
+    fn send(&mut self, request: SendEmailRequestIn) -> SendEmailResponseOut {
+        if request.to.is_empty() {
+            SendEmailResponseOut::Error("No recipient.".to_owned())
+        } else {
+            self.sent.push(request);
+            SendEmailResponseOut::Success
+        }
+    }
+
+    fn list(&mut self, request: ListEmailsRequestIn) -> PageSendEmailRequestOut {
+        PageSendEmailRequestOut {
+            items: self
+                .sent
+                .iter()
+                .take(usize::try_from(request.limit).unwrap_or(usize::MAX))
+                .map(|email| SendEmailRequestOut {
+                    to: email.to.clone(),
+                    subject: email.subject.clone(),
+                    body: email.body.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    fn ping(&mut self, _request: EmptyStructIn) -> EmptyStructOut {
+        EmptyStructOut {}
+    }
+}
+
+struct RetiredServer;
+
+impl RetiredHandler for RetiredServer {}
+
+// This transport delivers requests directly to a server in the same process.
+struct Loopback(Server);
+
+impl Transport for Loopback {
+    fn call(&mut self, method: u64, request: Vec<u8>) -> io::Result<Vec<u8>> {
+        dispatch_email(&mut self.0, method, &request)
+    }
+}
+
+pub fn run() -> io::Result<()> {
+    let email = SendEmailRequestOut {
+        to: "typical@example.com".to_owned(),
+        subject: "I love Typical!".to_owned(),
+        body: "It makes serialization easy and safe.".to_owned(),
+    };
+
+    assert_round_trip::<SendEmailRequestOut, SendEmailRequestIn>(&email)?;
+    assert_round_trip::<SendEmailResponseOut, SendEmailResponseIn>(&SendEmailResponseOut::Error(
+        "No recipient.".to_owned(),
+    ))?;
+
+    let mut client = EmailClient {
+        transport: Loopback(Server { sent: vec![] }),
+    };
+
+    let response = client.send(&email)?;
+    assert!(matches!(response, SendEmailResponseIn::Success));
+
+    let response = client.send(&SendEmailRequestOut {
+        to: String::new(),
+        subject: String::new(),
+        body: String::new(),
+    })?;
+    assert!(matches!(
+        response,
+        SendEmailResponseIn::Error(message) if message == "No recipient.",
+    ));
+
+    let response = client.list(&ListEmailsRequestOut { limit: 10 })?;
+    assert_eq!(
+        format!("{response:?}"),
+        format!(
+            "{:?}",
+            PageSendEmailRequestIn {
+                items: vec![SendEmailRequestIn::from(email)],
+            },
+        ),
+    );
+
+    client.ping(&EmptyStructOut {})?;
+
+    let error = dispatch_email(&mut client.transport.0, 1, &[]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+    let error = dispatch_retired(&mut RetiredServer, 0, &[]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+    Ok(())
+}
@@ -19,10 +19,13 @@
     clippy::type_complexity,
     clippy::unit_arg,
     clippy::unnecessary_wraps,
+    clippy::unused_async,
     clippy::unused_self,
     clippy::used_underscore_binding,
     clippy::useless_conversion,
+    clippy::zero_sized_map_values,
     dead_code,
+    deprecated,
     unreachable_code,
     unreachable_patterns,
     unused_mut,
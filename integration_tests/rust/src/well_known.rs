@@ -0,0 +1,93 @@
+use {
+    crate::{
+        assertions::assert_round_trip,
+        types::{
+            typical::{
+                decimal::DecimalOut,
+                time::{DurationIn, DurationOut, TimestampIn, TimestampOut},
+                uuid::{UuidIn, UuidOut},
+            },
+            well_known::types::{EventIn, EventOut},
+        },
+    },
+    std::{
+        io,
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    },
+    uuid::Uuid,
+};
+
+const UUID: u128 = 0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8;
+
+fn event() -> EventOut {
+    EventOut {
+        id: UuidOut::from(Uuid::from_u128(UUID)),
+        time: TimestampOut {
+            seconds: 1_700_000_000,
+            nanos: 123_456_789,
+        },
+        timeout: Some(DurationOut {
+            seconds: 30,
+            nanos: 0,
+        }),
+        price: DecimalOut {
+            significand: 1234,
+            exponent: -2,
+        },
+    }
+}
+
+pub fn run() -> io::Result<()> {
+    assert_round_trip::<EventOut, EventIn>(&event())?;
+    assert_round_trip::<EventOut, EventIn>(&EventOut {
+        timeout: None,
+        ..event()
+    })?;
+    assert_round_trip::<TimestampOut, TimestampIn>(&TimestampOut {
+        seconds: -1,
+        nanos: 500_000_000,
+    })?;
+
+    // Timestamps are converted to and from `SystemTime` losslessly, including times before the
+    // epoch.
+    for (time, seconds, nanos) in [
+        (UNIX_EPOCH, 0, 0),
+        (
+            UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789),
+            1_700_000_000,
+            123_456_789,
+        ),
+        (UNIX_EPOCH - Duration::new(0, 500_000_000), -1, 500_000_000),
+        (UNIX_EPOCH - Duration::new(86_400, 1), -86_401, 999_999_999),
+    ] {
+        let timestamp = TimestampOut::try_from(time)?;
+        assert_eq!((timestamp.seconds, timestamp.nanos), (seconds, nanos));
+        assert_eq!(SystemTime::try_from(TimestampIn::from(timestamp))?, time);
+    }
+    assert!(SystemTime::try_from(TimestampIn {
+        seconds: 0,
+        nanos: 1_000_000_000,
+    })
+    .is_err());
+
+    // Durations are converted to and from `Duration` losslessly.
+    let duration = DurationOut::from(Duration::new(5, 250));
+    assert_eq!((duration.seconds, duration.nanos), (5, 250));
+    assert_eq!(
+        Duration::try_from(DurationIn::from(duration))?,
+        Duration::new(5, 250),
+    );
+    assert!(Duration::try_from(DurationIn {
+        seconds: u64::MAX,
+        nanos: 1_000_000_000,
+    })
+    .is_err());
+
+    // UUIDs are converted to and from `Uuid` losslessly.
+    assert_eq!(
+        Uuid::from(UuidIn::from(event().id)),
+        Uuid::from_u128(UUID),
+    );
+
+    Ok(())
+}
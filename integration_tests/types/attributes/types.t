@@ -0,0 +1,40 @@
+# This file exercises attributes, which customize the generated code without
+# affecting the encoding.
+
+@rust.derive(Hash, PartialEq, Eq)
+struct Account {
+    @rust.rename(kind)
+    @typescript.rename(category)
+    type: String = 0
+
+    # The name that was shown before `display_name` was introduced
+    @deprecated("Use `display_name` instead.")
+    optional nickname: String = 1
+
+    display_name: String = 2
+}
+
+@rust.derive(Hash, PartialEq, Eq)
+choice Status {
+    @typescript.rename(live)
+    active = 0
+
+    suspended: String = 1
+}
+
+# This struct has the same encoding as `Account`, since attributes only affect
+# the generated code.
+@deprecated
+struct LegacyAccount {
+    kind: String = 0
+    optional nickname: String = 1
+    display_name: String = 2
+}
+
+# Floating-point numbers can be compared but not hashed, and the generated
+# types can be converted to and from other formats with Serde.
+@rust.derive(PartialEq, PartialOrd, Serialize, Deserialize)
+struct Measurement {
+    value: F64 = 0
+    samples: U64 = 1
+}
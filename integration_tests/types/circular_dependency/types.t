@@ -2,7 +2,11 @@ import 'dependency/types.t'
 
 # This struct is imported from below.
 struct StructFromAbove {
+    # This field has a comment.
+    #
+    # The comment has a second paragraph.
     field: String = 0
+
     size: String = 1
     elements: String = 2
     fallback: String = 3
@@ -89,8 +89,11 @@ struct Foo {
     deleted 27 55 83
 }
 
+# This choice has a comment.
 choice Bar {
+    # This field has a comment.
     a_required = 0
+
     b_required: F64 = 1
     c_required: U64 = 2
     d_required: S64 = 3
@@ -0,0 +1,28 @@
+# This file demonstrates constants.
+
+# The version of the protocol described by this schema.
+const PROTOCOL_VERSION: U64 = 3
+
+const MIN_OFFSET: S64 = -9223372036854775808
+const MAX_SMALL: U8 = 255
+const MAX_MEDIUM: U32 = 4294967295
+const MIN_MEDIUM: S32 = -2147483648
+const RATIO: F32 = 0.5
+const SCALE: F64 = -0.00125
+const ENABLED: Bool = true
+const GREETING: String = "Hello, \"World\"!\n"
+const MAGIC: Bytes = 0xdeadbeef
+const EMPTY: Bytes = 0x
+
+struct Settings {
+    version: U64 = 0
+    offset: S64 = 1
+    small: U8 = 2
+    medium: U32 = 3
+    signed_medium: S32 = 4
+    ratio: F32 = 5
+    scale: F64 = 6
+    enabled: Bool = 7
+    greeting: String = 8
+    magic: Bytes = 9
+}
@@ -0,0 +1,52 @@
+# This file exercises constraints, which restrict the values of fields without
+# affecting the encoding.
+
+newtype Username = String
+
+struct Person {
+    @min_length(1)
+    @max_length(16)
+    name: Username = 0
+
+    @max(150)
+    age: U8 = 1
+
+    @min(-40.5)
+    @max(60)
+    optional temperature: F64 = 2
+
+    @max_length(3)
+    nicknames: [String] = 3
+
+    @min(-1000)
+    balance: S64 = 4
+
+    @min_length(1)
+    scores: {String: U64} = 5
+
+    @max_length(4)
+    asymmetric avatar: Bytes = 6
+}
+
+choice Reading {
+    @min(-90)
+    @max(90)
+    latitude: F32 = 0
+
+    @min_length(2)
+    optional label: String = 1
+}
+
+struct Household {
+    @max_length(2)
+    members: [Person] = 0
+
+    reading: Reading = 1
+}
+
+# The bound isn't exactly representable as an `F32`, so it's rounded to the
+# nearest value which is.
+struct Gauge {
+    @max(0.1)
+    level: F32 = 0
+}
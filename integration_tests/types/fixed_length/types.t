@@ -0,0 +1,31 @@
+# This file exercises fixed-length arrays and bytes, which are encoded like
+# their variable-length counterparts.
+
+struct Color {
+    rgb: [U8; 3] = 0
+}
+
+struct Fixed {
+    hash: Bytes(4) = 0
+    point: [F64; 3] = 1
+    colors: [Color; 2] = 2
+    names: [String; 2] = 3
+    optional matrix: [[S32; 2]; 2] = 4
+    flags: [Bool; 4] = 5
+}
+
+# This struct has the same encoding as `Fixed`, but without the lengths.
+struct Variable {
+    hash: Bytes = 0
+    point: [F64] = 1
+    colors: [Color] = 2
+    names: [String] = 3
+    optional matrix: [[S32]] = 4
+    flags: [Bool] = 5
+}
+
+choice Checksum {
+    crc32: Bytes(4) = 0
+    sha256: Bytes(32) = 1
+    unknown: Bytes = 2
+}
@@ -0,0 +1,18 @@
+# This file declares generic types, which are instantiated in `types.t` with
+# types from that file. Each instantiation is generated in this file.
+
+# A page of results
+struct Page<T> {
+    items: [T] = 0
+    next: String = 1
+}
+
+struct Pair<A, B> {
+    first: A = 0
+    second: B = 1
+}
+
+choice Maybe<T> {
+    some: T = 0
+    none = 1
+}
@@ -0,0 +1,26 @@
+import 'containers.t'
+
+struct User {
+    name: String = 0
+    nickname: containers.Maybe<String> = 1
+}
+
+struct Foo {
+    users: containers.Page<User> = 0
+    pairs: containers.Page<containers.Pair<U64, {String: User}>> = 1
+    nested: containers.Maybe<containers.Maybe<[Bool]>> = 2
+    optional pair: containers.Pair<String, Bytes> = 3
+    local: Tree<User> = 4
+}
+
+choice Bar {
+    a: containers.Pair<User, User> = 0
+    b: Tree<Unit> = 1
+}
+
+# A generic declaration can also be instantiated in the file where it's
+# declared.
+struct Tree<T> {
+    value: T = 0
+    leaves: [containers.Pair<String, T>] = 1
+}
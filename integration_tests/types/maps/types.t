@@ -0,0 +1,31 @@
+struct Entry {
+    key: String = 0
+    value: U64 = 1
+}
+
+struct EntryList {
+    entries: [Entry] = 0
+}
+
+struct EntryMap {
+    entries: {String: U64} = 0
+}
+
+struct Foo {
+    a: {Bool: Unit} = 0
+    b: {S64: F64} = 1
+    c: {String: U64} = 2
+    d: {U64: String} = 3
+    e: {String: Entry} = 4
+    f: {U64: [S64]} = 5
+    g: {String: {Bool: Bytes}} = 6
+    asymmetric h: {S64: Bool} = 7
+    optional i: {String: String} = 8
+}
+
+choice Bar {
+    a: {String: U64} = 0
+    b: {U64: Entry} = 1
+    asymmetric c: {Bool: [String]} = 2
+    optional d: {S64: Unit} = 3
+}
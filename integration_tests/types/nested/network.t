@@ -0,0 +1,16 @@
+# Nested declarations can be referred to from other files.
+
+struct Network {
+    name: String = 0
+    kind: Network.Kind = 1
+
+    choice Kind {
+        wired = 0
+        wireless: Network.Kind.Wireless = 1
+
+        struct Wireless {
+            ssid: String = 0
+            optional channel: U8 = 1
+        }
+    }
+}
@@ -0,0 +1,29 @@
+# This file exercises nested declarations, which are referred to by their
+# qualified names.
+
+import 'network.t'
+
+struct Device {
+    id: Device.Id = 0
+    address: Device.IpAddress = 1
+    network: network.Network.Kind = 2
+    optional peers: [Device.IpAddress] = 3
+
+    newtype Id = U64
+
+    # An IP address
+    choice IpAddress {
+        v4: Device.IpAddress.V4 = 0
+        v6: Bytes = 1
+
+        struct V4 {
+            octets: [U8] = 0
+        }
+    }
+}
+
+# This struct has the same encoding as `Device.IpAddress.V4`, since nesting
+# doesn't affect the encoding.
+struct PlainV4 {
+    octets: [U8] = 0
+}
@@ -0,0 +1,6 @@
+# This file declares newtypes which are used in `types.t`.
+
+# A unique identifier for a user
+newtype UserId = U64
+
+newtype Email = String
@@ -0,0 +1,38 @@
+import 'ids.t'
+
+newtype Scores = [F64]
+
+# A newtype can wrap another newtype.
+newtype PrimaryEmail = ids.Email
+
+# A newtype can wrap a user-defined type.
+newtype Administrator = Account
+
+# A newtype can be generic.
+newtype Tagged<T> = {String: T}
+
+struct Account {
+    id: ids.UserId = 0
+    email: PrimaryEmail = 1
+    optional backup_emails: [ids.Email] = 2
+    scores: Scores = 3
+    tags: Tagged<ids.UserId> = 4
+    friends: [ids.UserId] = 5
+}
+
+choice Principal {
+    user: ids.UserId = 0
+    administrator: Administrator = 1
+    anonymous = 2
+}
+
+# This struct has the same encoding as `Account`, since newtypes are encoded
+# like their underlying types.
+struct PlainAccount {
+    id: U64 = 0
+    email: String = 1
+    optional backup_emails: [String] = 2
+    scores: [F64] = 3
+    tags: {String: U64} = 4
+    friends: [U64] = 5
+}
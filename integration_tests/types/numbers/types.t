@@ -0,0 +1,35 @@
+struct Foo {
+    a: U8 = 0
+    b: U32 = 1
+    c: S32 = 2
+    d: F32 = 3
+    e: [U8] = 4
+    f: [U32] = 5
+    g: [S32] = 6
+    h: [F32] = 7
+    i: {U8: F32} = 8
+    j: {S32: U32} = 9
+    asymmetric k: U32 = 10
+    optional l: F32 = 11
+}
+
+choice Bar {
+    a: U8 = 0
+    b: U32 = 1
+    c: S32 = 2
+    d: F32 = 3
+    asymmetric e: [U8] = 4
+    optional f: S32 = 5
+}
+
+struct Narrow {
+    x: U8 = 0
+    y: U32 = 1
+    z: S32 = 2
+}
+
+struct Wide {
+    x: U64 = 0
+    y: U64 = 1
+    z: S64 = 2
+}
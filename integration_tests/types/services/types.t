@@ -0,0 +1,40 @@
+# This file demonstrates services.
+
+import '../degenerate/types.t' as degenerate
+
+struct SendEmailRequest {
+    to: String = 0
+    subject: String = 1
+    body: String = 2
+}
+
+choice SendEmailResponse {
+    success = 0
+    error: String = 1
+}
+
+struct ListEmailsRequest {
+    limit: U64 = 0
+}
+
+struct Page<T> {
+    items: [T] = 0
+}
+
+# A service for sending emails
+service Email {
+    # Send an email.
+    send: SendEmailRequest -> SendEmailResponse = 0
+
+    # List the emails which have been sent.
+    list: ListEmailsRequest -> Page<SendEmailRequest> = 2
+
+    ping: degenerate.EmptyStruct -> degenerate.EmptyStruct = 3
+
+    deleted 1
+}
+
+# A service with no methods
+service Retired {
+    deleted 0
+}
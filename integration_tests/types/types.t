@@ -1,6 +1,17 @@
 # This schema imports all the others, either directly or indirectly.
 
+import 'attributes/types.t' as attributes
 import 'circular_dependency/types.t' as circular_dependency
 import 'comprehensive/types.t' as comprehensive
+import 'constants/types.t' as constants
+import 'constraints/types.t' as constraints
 import 'degenerate/types.t' as degenerate
+import 'fixed_length/types.t' as fixed_length
+import 'generics/types.t' as generics
+import 'maps/types.t' as maps
+import 'nested/types.t' as nested
+import 'newtypes/types.t' as newtypes
+import 'numbers/types.t' as numbers
 import 'schema_evolution/types.t' as schema_evolution
+import 'services/types.t' as services
+import 'well_known/types.t' as well_known
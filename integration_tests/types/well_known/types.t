@@ -0,0 +1,13 @@
+# This file exercises the well-known types, which are imported by the same path
+# from any schema.
+
+import 'typical/decimal.t'
+import 'typical/time.t'
+import 'typical/uuid.t'
+
+struct Event {
+    id: uuid.Uuid = 0
+    time: time.Timestamp = 1
+    optional timeout: time.Duration = 2
+    price: decimal.Decimal = 3
+}
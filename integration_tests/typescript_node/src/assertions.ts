@@ -48,3 +48,7 @@ export function assertRoundTrip<O, I, V extends O>(
 ): void {
   assertMatch(size, serialize, deserialize, message, message);
 }
+
+export function assertEqual(actual: unknown, expected: unknown): void {
+  deepStrictEqual(actual, expected);
+}
@@ -0,0 +1,61 @@
+import { Attributes } from '../generated/types';
+import { assertMatch, assertRoundTrip } from './assertions';
+
+const account: Attributes.Types.AccountOut = {
+  category: 'administrator',
+  nickname: 'Al',
+  displayName: 'Alice',
+};
+
+export default function run(): void {
+  assertRoundTrip(
+    Attributes.Types.Account.size,
+    Attributes.Types.Account.serialize,
+    Attributes.Types.Account.deserialize,
+    account,
+  );
+
+  assertRoundTrip(
+    Attributes.Types.Account.size,
+    Attributes.Types.Account.serialize,
+    Attributes.Types.Account.deserialize,
+    {
+      category: '',
+      nickname: undefined,
+      displayName: '',
+    },
+  );
+
+  assertRoundTrip(
+    Attributes.Types.Status.size,
+    Attributes.Types.Status.serialize,
+    Attributes.Types.Status.deserialize,
+    {
+      $field: 'live',
+      live: null,
+    },
+  );
+
+  assertRoundTrip(
+    Attributes.Types.Status.size,
+    Attributes.Types.Status.serialize,
+    Attributes.Types.Status.deserialize,
+    {
+      $field: 'suspended',
+      suspended: 'Spam.',
+    },
+  );
+
+  // Attributes don't affect the encoding.
+  assertMatch(
+    Attributes.Types.Account.size,
+    Attributes.Types.Account.serialize,
+    Attributes.Types.LegacyAccount.deserialize,
+    account,
+    {
+      kind: 'administrator',
+      nickname: 'Al',
+      displayName: 'Alice',
+    },
+  );
+}
@@ -0,0 +1,82 @@
+/* eslint-disable @typescript-eslint/no-magic-numbers -- Allowed just for this file. */
+
+import { Nested, SchemaEvolution } from '../generated/types';
+import { assertEqual } from './assertions';
+
+function deviceId(id: bigint): Nested.Types.DeviceIdOut {
+  return id as Nested.Types.DeviceIdOut;
+}
+
+export default function run(): void {
+  // The fields can be given in any order, and the optional ones default to `undefined`.
+  assertEqual(
+    Nested.Types.Device.builder()
+      .network({ $field: 'wired', wired: null })
+      .address({
+        $field: 'v4',
+        v4: Nested.Types.DeviceIpAddressV4.builder()
+          .octets([192, 168, 0, 1])
+          .build(),
+      })
+      .id(deviceId(0n))
+      .build(),
+    {
+      id: deviceId(0n),
+      address: { $field: 'v4', v4: { octets: [192, 168, 0, 1] } },
+      network: { $field: 'wired', wired: null },
+      peers: undefined,
+    },
+  );
+
+  // Optional fields can be given at any point.
+  assertEqual(
+    Nested.Types.Device.builder()
+      .peers([{ $field: 'v6', v6: new Uint8Array([0xfe, 0x80]).buffer }])
+      .id(deviceId(1n))
+      .address({ $field: 'v6', v6: new Uint8Array(16).buffer })
+      .network({
+        $field: 'wireless',
+        wireless: Nested.Network.NetworkKindWireless.builder()
+          .ssid('Home')
+          .channel(11)
+          .build(),
+      })
+      .build(),
+    {
+      id: deviceId(1n),
+      address: { $field: 'v6', v6: new Uint8Array(16).buffer },
+      network: {
+        $field: 'wireless',
+        wireless: { ssid: 'Home', channel: 11 },
+      },
+      peers: [{ $field: 'v6', v6: new Uint8Array([0xfe, 0x80]).buffer }],
+    },
+  );
+
+  // Asymmetric fields must be given just like required ones.
+  assertEqual(
+    SchemaEvolution.After.ExampleStruct.builder()
+      .requiredToRequired('a')
+      .requiredToAsymmetric('b')
+      .asymmetricToRequired('c')
+      .asymmetricToAsymmetric('d')
+      .optionalToRequired('e')
+      .optionalToAsymmetric('f')
+      .nonexistentToAsymmetric(null)
+      .optionalToOptional('g')
+      .build(),
+    {
+      requiredToRequired: 'a',
+      requiredToAsymmetric: 'b',
+      requiredToOptional: undefined,
+      asymmetricToRequired: 'c',
+      asymmetricToAsymmetric: 'd',
+      asymmetricToOptional: undefined,
+      optionalToRequired: 'e',
+      optionalToAsymmetric: 'f',
+      optionalToOptional: 'g',
+      nonexistentToAsymmetric: null,
+      nonexistentToOptional: undefined,
+    },
+  );
+}
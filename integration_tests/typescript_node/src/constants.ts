@@ -0,0 +1,42 @@
+/* eslint-disable @typescript-eslint/no-magic-numbers -- Allowed just for this file. */
+
+import { Constants } from '../generated/types';
+import { assertRoundTrip } from './assertions';
+
+export default function run(): void {
+  assertRoundTrip(
+    Constants.Types.Settings.size,
+    Constants.Types.Settings.serialize,
+    Constants.Types.Settings.deserialize,
+    {
+      version: Constants.Types.PROTOCOL_VERSION,
+      offset: Constants.Types.MIN_OFFSET,
+      small: Constants.Types.MAX_SMALL,
+      medium: Constants.Types.MAX_MEDIUM,
+      signedMedium: Constants.Types.MIN_MEDIUM,
+      ratio: Constants.Types.RATIO,
+      scale: Constants.Types.SCALE,
+      enabled: Constants.Types.ENABLED,
+      greeting: Constants.Types.GREETING,
+      magic: Constants.Types.MAGIC,
+    },
+  );
+
+  assertRoundTrip(
+    Constants.Types.Settings.size,
+    Constants.Types.Settings.serialize,
+    Constants.Types.Settings.deserialize,
+    {
+      version: 0n,
+      offset: 0n,
+      small: 0,
+      medium: 0,
+      signedMedium: 0,
+      ratio: 0.0,
+      scale: 0.0,
+      enabled: false,
+      greeting: '',
+      magic: Constants.Types.EMPTY,
+    },
+  );
+}
@@ -0,0 +1,111 @@
+/* eslint-disable @typescript-eslint/no-magic-numbers -- Allowed just for this file. */
+
+import { FixedLength } from '../generated/types';
+import { assertEqual, assertMatch, assertRoundTrip } from './assertions';
+
+const s32Max = 2_147_483_647;
+const s32Min = -2_147_483_648;
+
+function fixed(): FixedLength.Types.FixedOut {
+  return {
+    hash: new Uint8Array([0xde, 0xad, 0xbe, 0xef]).buffer,
+    point: [1.5, -2.0, 0.0],
+    colors: [{ rgb: [255, 0, 0] }, { rgb: [0, 0, 255] }],
+    names: ['Alice', ''],
+    matrix: [
+      [1, -1],
+      [s32Max, s32Min],
+    ],
+    flags: [true, false, false, true],
+  };
+}
+
+// Check that a message is rejected when decoded as a type which requires different lengths.
+function assertWrongLength(message: FixedLength.Types.VariableOut): void {
+  const replica = FixedLength.Types.Fixed.deserialize(
+    FixedLength.Types.Variable.serialize(message),
+  );
+  assertEqual(
+    replica instanceof Error ? replica.message : replica,
+    'Array or bytes of the wrong length.',
+  );
+}
+
+export default function run(): void {
+  assertRoundTrip(
+    FixedLength.Types.Color.size,
+    FixedLength.Types.Color.serialize,
+    FixedLength.Types.Color.deserialize,
+    { rgb: [1, 2, 3] },
+  );
+
+  assertRoundTrip(
+    FixedLength.Types.Fixed.size,
+    FixedLength.Types.Fixed.serialize,
+    FixedLength.Types.Fixed.deserialize,
+    fixed(),
+  );
+
+  assertRoundTrip(
+    FixedLength.Types.Fixed.size,
+    FixedLength.Types.Fixed.serialize,
+    FixedLength.Types.Fixed.deserialize,
+    { ...fixed(), matrix: undefined },
+  );
+
+  assertRoundTrip(
+    FixedLength.Types.Checksum.size,
+    FixedLength.Types.Checksum.serialize,
+    FixedLength.Types.Checksum.deserialize,
+    { $field: 'crc32', crc32: new Uint8Array(4).buffer },
+  );
+
+  assertRoundTrip(
+    FixedLength.Types.Checksum.size,
+    FixedLength.Types.Checksum.serialize,
+    FixedLength.Types.Checksum.deserialize,
+    { $field: 'sha256', sha256: new Uint8Array(32).fill(0xff).buffer },
+  );
+
+  assertRoundTrip(
+    FixedLength.Types.Checksum.size,
+    FixedLength.Types.Checksum.serialize,
+    FixedLength.Types.Checksum.deserialize,
+    { $field: 'unknown', unknown: new Uint8Array([1, 2, 3]).buffer },
+  );
+
+  // Fixed-length values are encoded like variable-length ones.
+  assertMatch(
+    FixedLength.Types.Fixed.size,
+    FixedLength.Types.Fixed.serialize,
+    FixedLength.Types.Variable.deserialize,
+    fixed(),
+    fixed(),
+  );
+
+  assertMatch(
+    FixedLength.Types.Variable.size,
+    FixedLength.Types.Variable.serialize,
+    FixedLength.Types.Fixed.deserialize,
+    fixed(),
+    fixed(),
+  );
+
+  // Decoding fails if a length doesn't match.
+  assertWrongLength({
+    ...fixed(),
+    hash: new Uint8Array([0xde, 0xad, 0xbe]).buffer,
+  });
+  assertWrongLength({
+    ...fixed(),
+    matrix: [[1, -1], [0]],
+  });
+  assertWrongLength({ ...fixed(), colors: [] });
+
+  // TypeScript types can't express fixed lengths, so `validate` checks them.
+  assertEqual(FixedLength.Types.Fixed.validate(fixed()), undefined);
+  assertEqual(
+    FixedLength.Types.Fixed.validate({ ...fixed(), names: ['Alice'] })?.message,
+    'Field `names` of `Fixed` must have a length of exactly 2.',
+  );
+}
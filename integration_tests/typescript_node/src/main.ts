@@ -1,20 +1,71 @@
+import runAttributes from './attributes';
+import runBuilders from './builders';
 import runCircularDependency from './circular-dependency';
 import runComprehensive from './comprehensive';
+import runConstants from './constants';
+import runConstraints from './constraints';
 import runDegenerate from './degenerate';
+import runFixedLength from './fixed_length';
+import runGenerics from './generics';
+import runMaps from './maps';
+import runNested from './nested';
+import runNewtypes from './newtypes';
+import runNumbers from './numbers';
 import runSchemaEvolution from './schema-evolution';
+import runServices from './services';
+import runWellKnown from './well_known';
 
 /* eslint-disable no-console -- Allow logging for this file. */
 
-console.log('Running circular dependency integration test\u2026\n');
+console.log('Running attributes integration test\u2026\n');
+runAttributes();
+
+console.log('\nRunning builders integration test\u2026\n');
+runBuilders();
+
+console.log('\nRunning circular dependency integration test\u2026\n');
 runCircularDependency();
 
 console.log('\nRunning comprehensive integration test\u2026\n');
 runComprehensive();
 
+console.log('\nRunning constants integration test\u2026\n');
+runConstants();
+
+console.log('\nRunning constraints integration test\u2026\n');
+runConstraints();
+
 console.log('\nRunning degenerate integration test\u2026\n');
 runDegenerate();
 
+console.log('\nRunning fixed-length integration test\u2026\n');
+runFixedLength();
+
+console.log('\nRunning generics integration test\u2026\n');
+runGenerics();
+
+console.log('\nRunning maps integration test\u2026\n');
+runMaps();
+
+console.log('\nRunning nested integration test\u2026\n');
+runNested();
+
+console.log('\nRunning newtypes integration test\u2026\n');
+runNewtypes();
+
+console.log('\nRunning numbers integration test\u2026\n');
+runNumbers();
+
 console.log('\nRunning schema evolution integration test\u2026\n');
 runSchemaEvolution();
 
+console.log('\nRunning services integration test\u2026\n');
+runServices().catch((error: unknown) => {
+  console.error(error);
+  process.exitCode = 1;
+});
+
+console.log('\nRunning well-known types integration test\u2026\n');
+runWellKnown();
+
 /* eslint-enable no-console -- Re-enable this rule. */
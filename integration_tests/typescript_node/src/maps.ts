@@ -0,0 +1,169 @@
+/* eslint-disable @typescript-eslint/no-magic-numbers -- Allowed just for this file. */
+
+import { Maps } from '../generated/types';
+import { assertMatch, assertRoundTrip } from './assertions';
+
+const u64Max = 18_446_744_073_709_551_615n;
+const s64Min = -9_223_372_036_854_775_808n;
+const s64Max = 9_223_372_036_854_775_807n;
+
+// The entries below are listed in ascending order of their keys so the encoded bytes are the same
+// as those of the other code generators, which sort the entries.
+export default function run(): void {
+  assertRoundTrip(
+    Maps.Types.Foo.size,
+    Maps.Types.Foo.serialize,
+    Maps.Types.Foo.deserialize,
+    {
+      a: new Map(),
+      b: new Map(),
+      c: new Map(),
+      d: new Map(),
+      e: new Map(),
+      f: new Map(),
+      g: new Map(),
+      h: new Map(),
+      i: undefined,
+    },
+  );
+
+  assertRoundTrip(
+    Maps.Types.Foo.size,
+    Maps.Types.Foo.serialize,
+    Maps.Types.Foo.deserialize,
+    {
+      a: new Map([
+        [false, null],
+        [true, null],
+      ]),
+      b: new Map([
+        [s64Min, 0.0],
+        [0n, Math.PI],
+        [s64Max, Number.POSITIVE_INFINITY],
+      ]),
+      c: new Map([
+        ['', 0n],
+        ['=8 bytes', 42n],
+        ['Hello, 幸福!', u64Max],
+      ]),
+      d: new Map([
+        [0n, ''],
+        [u64Max, 'Hello, World!'],
+      ]),
+      e: new Map([['foo', { key: 'bar', value: 42n }]]),
+      f: new Map([
+        [0n, []],
+        [1n, [s64Min, 0n, s64Max]],
+      ]),
+      g: new Map([
+        ['', new Map()],
+        [
+          'foo',
+          new Map([
+            [false, new Uint8Array([]).buffer],
+            [true, new Uint8Array([0, 42, 255]).buffer],
+          ]),
+        ],
+      ]),
+      h: new Map([[-1n, true]]),
+      i: new Map([['foo', 'bar']]),
+    },
+  );
+
+  // eslint-disable-next-line no-console -- Allow us to separate the test groups with a line break.
+  console.log();
+
+  assertRoundTrip(
+    Maps.Types.Bar.size,
+    Maps.Types.Bar.serialize,
+    Maps.Types.Bar.deserialize,
+    {
+      $field: 'a',
+      a: new Map(),
+    },
+  );
+
+  assertRoundTrip(
+    Maps.Types.Bar.size,
+    Maps.Types.Bar.serialize,
+    Maps.Types.Bar.deserialize,
+    {
+      $field: 'a',
+      a: new Map([
+        ['bar', u64Max],
+        ['foo', 0n],
+      ]),
+    },
+  );
+
+  assertRoundTrip(
+    Maps.Types.Bar.size,
+    Maps.Types.Bar.serialize,
+    Maps.Types.Bar.deserialize,
+    {
+      $field: 'b',
+      b: new Map([[42n, { key: 'foo', value: 42n }]]),
+    },
+  );
+
+  const $fallback = { $field: 'a', a: new Map() };
+
+  assertMatch(
+    Maps.Types.Bar.size,
+    Maps.Types.Bar.serialize,
+    Maps.Types.Bar.deserialize,
+    {
+      $field: 'c',
+      c: new Map([[true, ['foo', 'bar']]]),
+      $fallback,
+    },
+    {
+      $field: 'c',
+      c: new Map([[true, ['foo', 'bar']]]),
+    },
+  );
+
+  assertMatch(
+    Maps.Types.Bar.size,
+    Maps.Types.Bar.serialize,
+    Maps.Types.Bar.deserialize,
+    {
+      $field: 'd',
+      d: new Map([
+        [-1n, null],
+        [1n, null],
+      ]),
+      $fallback,
+    },
+    {
+      $field: 'd',
+      d: new Map([
+        [-1n, null],
+        [1n, null],
+      ]),
+      $fallback,
+    },
+  );
+
+  // eslint-disable-next-line no-console -- Allow us to separate the test groups with a line break.
+  console.log();
+
+  // A map is encoded like an array of entries, so the two are interchangeable.
+  assertMatch(
+    Maps.Types.EntryList.size,
+    Maps.Types.EntryList.serialize,
+    Maps.Types.EntryMap.deserialize,
+    {
+      entries: [
+        { key: 'bar', value: 1n },
+        { key: 'foo', value: 2n },
+      ],
+    },
+    {
+      entries: new Map([
+        ['bar', 1n],
+        ['foo', 2n],
+      ]),
+    },
+  );
+}
@@ -0,0 +1,60 @@
+/* eslint-disable @typescript-eslint/no-magic-numbers -- Allowed just for this file. */
+
+import { Typical, WellKnown } from '../generated/types';
+import { assertEqual, assertRoundTrip } from './assertions';
+
+function event(): WellKnown.Types.EventOut {
+  return {
+    id: new Uint8Array([
+      0x67, 0xe5, 0x50, 0x44, 0x10, 0xb1, 0x42, 0x6f, 0x92, 0x47, 0xbb, 0x68, 0x0e, 0x5f, 0xe0,
+      0xc8,
+    ]).buffer as Typical.Uuid.UuidOut,
+    time: { seconds: 1_700_000_000n, nanos: 123_456_789 },
+    timeout: { seconds: 30n, nanos: 0 },
+    price: { significand: 1234n, exponent: -2 },
+  };
+}
+
+export default function run(): void {
+  assertRoundTrip(
+    WellKnown.Types.Event.size,
+    WellKnown.Types.Event.serialize,
+    WellKnown.Types.Event.deserialize,
+    event(),
+  );
+
+  assertRoundTrip(
+    WellKnown.Types.Event.size,
+    WellKnown.Types.Event.serialize,
+    WellKnown.Types.Event.deserialize,
+    { ...event(), timeout: undefined },
+  );
+
+  assertRoundTrip(
+    Typical.Time.Timestamp.size,
+    Typical.Time.Timestamp.serialize,
+    Typical.Time.Timestamp.deserialize,
+    { seconds: -1n, nanos: 500_000_000 },
+  );
+
+  // Timestamps are converted to and from dates losslessly, including dates before the epoch.
+  for (const [date, seconds, nanos] of [
+    [new Date(0), 0n, 0],
+    [new Date(1_700_000_000_123), 1_700_000_000n, 123_000_000],
+    [new Date(-500), -1n, 500_000_000],
+    [new Date(-86_400_001), -86_401n, 999_000_000],
+  ] as const) {
+    const timestamp = Typical.Time.Timestamp.fromDate(date);
+    assertEqual(timestamp, { seconds, nanos });
+    if (!(timestamp instanceof Error)) {
+      assertEqual(Typical.Time.Timestamp.toDate(timestamp), date);
+    }
+  }
+
+  // Invalid dates and timestamps which are too precise for a date are rejected.
+  assertEqual(Typical.Time.Timestamp.fromDate(new Date(Number.NaN)) instanceof Error, true);
+  assertEqual(
+    Typical.Time.Timestamp.toDate({ seconds: 0n, nanos: 1 }) instanceof Error,
+    true,
+  );
+}
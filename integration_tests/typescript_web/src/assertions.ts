@@ -5,8 +5,8 @@ import { sha256 } from 'js-sha256';
 // It's used to validate that the TypeScript code generator encodes data identically to other code
 // generators.
 const omnifileHash =
-  'cfe2ae57b1bb6467c87f11d50829d0b8301566f645dabc19b38c861cd577b0a1';
-const omnifileSize = 80_395;
+  '10cce043e2375813051469f96f110e3d9285616f6594abdc663c7db5142383f7';
+const omnifileSize = 82_467;
 const omnifileBuffer = new ArrayBuffer(omnifileSize);
 const omnifileArray = new Uint8Array(omnifileBuffer);
 let omnifileOffset = 0;
@@ -56,6 +56,10 @@ export function assertRoundTrip<O, I, V extends O>(
   assertMatch(size, serialize, deserialize, message, message);
 }
 
+export function assertEqual(actual: unknown, expected: unknown): void {
+  deepStrictEqual(actual, expected);
+}
+
 export function verifyOmnifile(): void {
   deepStrictEqual(omnifileOffset, omnifileBuffer.byteLength);
   deepStrictEqual(sha256(omnifileBuffer), omnifileHash);
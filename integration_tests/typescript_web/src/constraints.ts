@@ -0,0 +1,203 @@
+/* eslint-disable @typescript-eslint/no-magic-numbers -- Allowed just for this file. */
+
+import { Constraints } from '../generated/types';
+import { assertEqual, assertRoundTrip } from './assertions';
+
+function username(name: string): Constraints.Types.UsernameOut {
+  return name as Constraints.Types.UsernameOut;
+}
+
+function person(): Constraints.Types.PersonOut {
+  return {
+    name: username('Zoë'),
+    age: 150,
+    temperature: -40.5,
+    nicknames: ['Z', 'Zo', 'Zozo'],
+    balance: -1000n,
+    scores: new Map([['chess', 1200n]]),
+    avatar: new Uint8Array([0xff, 0xd8, 0xff, 0xe0]).buffer,
+  };
+}
+
+// Check that a message which violates a constraint is rejected by both `validate` and
+// deserialization. Serialization doesn't check constraints, so such messages can still be encoded.
+function assertViolation<O, I>(
+  serialize: (message: O) => ArrayBuffer,
+  deserialize: (bytes: ArrayBuffer) => I | Error,
+  validate: (message: O) => Error | undefined,
+  message: O,
+  errorMessage: string,
+): void {
+  assertEqual(validate(message)?.message, errorMessage);
+
+  const replica = deserialize(serialize(message));
+  assertEqual(replica instanceof Error ? replica.message : replica, errorMessage);
+}
+
+function assertPersonViolation(
+  message: Constraints.Types.PersonOut,
+  errorMessage: string,
+): void {
+  assertViolation(
+    Constraints.Types.Person.serialize,
+    Constraints.Types.Person.deserialize,
+    Constraints.Types.Person.validate,
+    message,
+    errorMessage,
+  );
+}
+
+function assertReadingViolation(
+  message: Constraints.Types.ReadingOut,
+  errorMessage: string,
+): void {
+  assertViolation(
+    Constraints.Types.Reading.serialize,
+    Constraints.Types.Reading.deserialize,
+    Constraints.Types.Reading.validate,
+    message,
+    errorMessage,
+  );
+}
+
+function assertHouseholdViolation(
+  message: Constraints.Types.HouseholdOut,
+  errorMessage: string,
+): void {
+  assertViolation(
+    Constraints.Types.Household.serialize,
+    Constraints.Types.Household.deserialize,
+    Constraints.Types.Household.validate,
+    message,
+    errorMessage,
+  );
+}
+
+export default function run(): void {
+  assertEqual(Constraints.Types.Person.validate(person()), undefined);
+  assertRoundTrip(
+    Constraints.Types.Person.size,
+    Constraints.Types.Person.serialize,
+    Constraints.Types.Person.deserialize,
+    person(),
+  );
+  assertRoundTrip(
+    Constraints.Types.Person.size,
+    Constraints.Types.Person.serialize,
+    Constraints.Types.Person.deserialize,
+    {
+      name: username('é'.repeat(16)),
+      age: 0,
+      temperature: undefined,
+      nicknames: [],
+      balance: 9_223_372_036_854_775_807n,
+      scores: new Map([['', 0n]]),
+      avatar: new ArrayBuffer(0),
+    },
+  );
+
+  const reading = {
+    $field: 'label' as const,
+    label: 'Summit',
+    $fallback: { $field: 'latitude' as const, latitude: -90 },
+  };
+  assertEqual(Constraints.Types.Reading.validate(reading), undefined);
+  assertRoundTrip(
+    Constraints.Types.Reading.size,
+    Constraints.Types.Reading.serialize,
+    Constraints.Types.Reading.deserialize,
+    reading,
+  );
+
+  const household = {
+    members: [person(), person()],
+    reading: { $field: 'latitude' as const, latitude: 45.5 },
+  };
+  assertEqual(Constraints.Types.Household.validate(household), undefined);
+  assertRoundTrip(
+    Constraints.Types.Household.size,
+    Constraints.Types.Household.serialize,
+    Constraints.Types.Household.deserialize,
+    household,
+  );
+
+  assertPersonViolation(
+    { ...person(), name: username('') },
+    'Field `name` of `Person` must have a length of at least 1.',
+  );
+  assertPersonViolation(
+    { ...person(), name: username('é'.repeat(17)) },
+    'Field `name` of `Person` must have a length of at most 16.',
+  );
+  assertPersonViolation(
+    { ...person(), age: 151 },
+    'Field `age` of `Person` must be at most 150.',
+  );
+  assertPersonViolation(
+    { ...person(), temperature: Number.NaN },
+    'Field `temperature` of `Person` must be at least -40.5.',
+  );
+  assertPersonViolation(
+    { ...person(), temperature: 60.5 },
+    'Field `temperature` of `Person` must be at most 60.',
+  );
+  assertPersonViolation(
+    { ...person(), nicknames: ['', '', '', ''] },
+    'Field `nicknames` of `Person` must have a length of at most 3.',
+  );
+  assertPersonViolation(
+    { ...person(), balance: -1001n },
+    'Field `balance` of `Person` must be at least -1000.',
+  );
+  assertPersonViolation(
+    { ...person(), scores: new Map() },
+    'Field `scores` of `Person` must have a length of at least 1.',
+  );
+  assertPersonViolation(
+    { ...person(), avatar: new ArrayBuffer(5) },
+    'Field `avatar` of `Person` must have a length of at most 4.',
+  );
+
+  assertReadingViolation(
+    { latitude: 90.5 },
+    'Field `latitude` of `Reading` must be at most 90.',
+  );
+  assertReadingViolation(
+    { label: 'S', $fallback: { latitude: 0 } },
+    'Field `label` of `Reading` must have a length of at least 2.',
+  );
+  assertReadingViolation(
+    { label: 'Summit', $fallback: { latitude: -90.5 } },
+    'Field `latitude` of `Reading` must be at least -90.',
+  );
+
+  // Messages within other messages are checked too.
+  assertHouseholdViolation(
+    { members: [person(), person(), person()], reading: { latitude: 0 } },
+    'Field `members` of `Household` must have a length of at most 2.',
+  );
+  assertHouseholdViolation(
+    { members: [{ ...person(), age: 200 }], reading: { latitude: 0 } },
+    'Field `age` of `Person` must be at most 150.',
+  );
+  assertHouseholdViolation(
+    { members: [], reading: { latitude: Number.POSITIVE_INFINITY } },
+    'Field `latitude` of `Reading` must be at most 90.',
+  );
+
+  // A bound which isn't exactly representable as an `F32` is rounded just like the values are. This
+  // message isn't written to the omnifile, since the Rust integration tests don't write it either.
+  const gauge = { level: 0.1 };
+  assertEqual(Constraints.Types.Gauge.validate(gauge), undefined);
+  assertEqual(
+    Constraints.Types.Gauge.deserialize(Constraints.Types.Gauge.serialize(gauge)),
+    { level: Math.fround(0.1) },
+  );
+  assertViolation(
+    Constraints.Types.Gauge.serialize,
+    Constraints.Types.Gauge.deserialize,
+    Constraints.Types.Gauge.validate,
+    { level: 0.1000001 },
+    'Field `level` of `Gauge` must be at most 0.1.',
+  );
+}
@@ -0,0 +1,128 @@
+/* eslint-disable @typescript-eslint/no-magic-numbers -- Allowed just for this file. */
+
+import { Generics } from '../generated/types';
+import { assertRoundTrip } from './assertions';
+
+const u64Max = 18_446_744_073_709_551_615n;
+
+function user(
+  name: string,
+  nickname: string | undefined,
+): Generics.Types.UserOut & Generics.Types.UserIn {
+  return {
+    name,
+    nickname:
+      nickname === undefined
+        ? { $field: 'none', none: null }
+        : { $field: 'some', some: nickname },
+  };
+}
+
+// The entries below are listed in ascending order of their keys so the encoded bytes are the same
+// as those of the other code generators, which sort the entries.
+export default function run(): void {
+  assertRoundTrip(
+    Generics.Types.Foo.size,
+    Generics.Types.Foo.serialize,
+    Generics.Types.Foo.deserialize,
+    {
+      users: {
+        items: [],
+        next: '',
+      },
+      pairs: {
+        items: [],
+        next: '',
+      },
+      nested: { $field: 'none', none: null },
+      pair: undefined,
+      local: {
+        value: user('', undefined),
+        leaves: [],
+      },
+    },
+  );
+
+  assertRoundTrip(
+    Generics.Types.Foo.size,
+    Generics.Types.Foo.serialize,
+    Generics.Types.Foo.deserialize,
+    {
+      users: {
+        items: [user('Alice', 'Al'), user('Bob', undefined)],
+        next: 'cursor',
+      },
+      pairs: {
+        items: [
+          {
+            first: 0n,
+            second: new Map(),
+          },
+          {
+            first: u64Max,
+            second: new Map([
+              ['alice', user('Alice', undefined)],
+              ['bob', user('Bob', 'Bobby')],
+            ]),
+          },
+        ],
+        next: '',
+      },
+      nested: {
+        $field: 'some',
+        some: { $field: 'some', some: [false, true] },
+      },
+      pair: {
+        first: 'Hello, World!',
+        second: new Uint8Array([0, 42, 255]).buffer,
+      },
+      local: {
+        value: user('root', undefined),
+        leaves: [
+          {
+            first: 'left',
+            second: user('Carol', undefined),
+          },
+          {
+            first: 'right',
+            second: user('Dave', 'D'),
+          },
+        ],
+      },
+    },
+  );
+
+  // eslint-disable-next-line no-console -- Allow us to separate the test groups with a line break.
+  console.log();
+
+  assertRoundTrip(
+    Generics.Types.Bar.size,
+    Generics.Types.Bar.serialize,
+    Generics.Types.Bar.deserialize,
+    {
+      $field: 'a',
+      a: {
+        first: user('Alice', undefined),
+        second: user('Bob', undefined),
+      },
+    },
+  );
+
+  assertRoundTrip(
+    Generics.Types.Bar.size,
+    Generics.Types.Bar.serialize,
+    Generics.Types.Bar.deserialize,
+    {
+      $field: 'b',
+      b: {
+        value: null,
+        leaves: [
+          {
+            first: 'leaf',
+            second: null,
+          },
+        ],
+      },
+    },
+  );
+}
@@ -1,43 +1,96 @@
+import runAttributes from './attributes';
+import runBuilders from './builders';
 import runCircularDependency from './circular-dependency';
 import runComprehensive from './comprehensive';
+import runConstants from './constants';
+import runConstraints from './constraints';
 import runDegenerate from './degenerate';
+import runFixedLength from './fixed_length';
+import runGenerics from './generics';
+import runMaps from './maps';
+import runNested from './nested';
+import runNewtypes from './newtypes';
+import runNumbers from './numbers';
 import runSchemaEvolution from './schema-evolution';
+import runServices from './services';
+import runWellKnown from './well_known';
 import { verifyOmnifile } from './assertions';
 
 const startingParagraph = document.createElement('p');
 startingParagraph.innerHTML = 'Running integration tests\u2026';
 document.body.appendChild(startingParagraph);
 
-window.requestAnimationFrame(() => {
-  try {
-    /* eslint-disable no-console -- Allow logging for this file. */
+async function runIntegrationTests(): Promise<void> {
+  /* eslint-disable no-console -- Allow logging for this function. */
+
+  console.log('Running attributes integration test\u2026\n');
+  runAttributes();
+
+  console.log('\nRunning builders integration test\u2026\n');
+  runBuilders();
+
+  console.log('\nRunning circular dependency integration test\u2026\n');
+  runCircularDependency();
+
+  console.log('\nRunning comprehensive integration test\u2026\n');
+  runComprehensive();
+
+  console.log('\nRunning constants integration test\u2026\n');
+  runConstants();
+
+  console.log('\nRunning constraints integration test\u2026\n');
+  runConstraints();
 
-    console.log('Running circular dependency integration test\u2026\n');
-    runCircularDependency();
+  console.log('\nRunning degenerate integration test\u2026\n');
+  runDegenerate();
 
-    console.log('\nRunning comprehensive integration test\u2026\n');
-    runComprehensive();
+  console.log('\nRunning fixed-length integration test\u2026\n');
+  runFixedLength();
 
-    console.log('\nRunning degenerate integration test\u2026\n');
-    runDegenerate();
+  console.log('\nRunning generics integration test\u2026\n');
+  runGenerics();
 
-    console.log('\nRunning schema evolution integration test\u2026\n');
-    runSchemaEvolution();
+  console.log('\nRunning maps integration test\u2026\n');
+  runMaps();
 
-    console.log('\nVerifying omnifile\u2026\n');
-    verifyOmnifile();
+  console.log('\nRunning nested integration test\u2026\n');
+  runNested();
 
-    /* eslint-enable no-console -- Re-enable this rule. */
-  } catch (e) {
-    const failureParagraph = document.createElement('p');
-    failureParagraph.innerHTML =
-      'Integration tests failed. See the console for details.';
-    document.body.appendChild(failureParagraph);
+  console.log('\nRunning newtypes integration test\u2026\n');
+  runNewtypes();
 
-    throw e;
-  }
+  console.log('\nRunning numbers integration test\u2026\n');
+  runNumbers();
+
+  console.log('\nRunning schema evolution integration test\u2026\n');
+  runSchemaEvolution();
+
+  console.log('\nRunning services integration test\u2026\n');
+  await runServices();
+
+  console.log('\nRunning well-known types integration test\u2026\n');
+  runWellKnown();
+
+  console.log('\nVerifying omnifile\u2026\n');
+  verifyOmnifile();
+
+  /* eslint-enable no-console -- Re-enable this rule. */
+}
+
+window.requestAnimationFrame(() => {
+  runIntegrationTests().then(
+    () => {
+      const successParagraph = document.createElement('p');
+      successParagraph.innerHTML = 'Integration tests passed.';
+      document.body.appendChild(successParagraph);
+    },
+    (e: unknown) => {
+      const failureParagraph = document.createElement('p');
+      failureParagraph.innerHTML =
+        'Integration tests failed. See the console for details.';
+      document.body.appendChild(failureParagraph);
 
-  const successParagraph = document.createElement('p');
-  successParagraph.innerHTML = 'Integration tests passed.';
-  document.body.appendChild(successParagraph);
+      throw e;
+    },
+  );
 });
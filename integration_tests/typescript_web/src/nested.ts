@@ -0,0 +1,75 @@
+/* eslint-disable @typescript-eslint/no-magic-numbers -- Allowed just for this file. */
+
+import { Nested } from '../generated/types';
+import { assertMatch, assertRoundTrip } from './assertions';
+
+const u64Max = 18_446_744_073_709_551_615n;
+
+function deviceId(id: bigint): Nested.Types.DeviceIdOut {
+  return id as Nested.Types.DeviceIdOut;
+}
+
+const v4: Nested.Types.DeviceIpAddressV4Out = {
+  octets: [192, 168, 0, 1],
+};
+
+export default function run(): void {
+  assertRoundTrip(
+    Nested.Types.Device.size,
+    Nested.Types.Device.serialize,
+    Nested.Types.Device.deserialize,
+    {
+      id: deviceId(0n),
+      address: { $field: 'v6', v6: new Uint8Array(16).buffer },
+      network: { $field: 'wired', wired: null },
+      peers: undefined,
+    },
+  );
+
+  assertRoundTrip(
+    Nested.Types.Device.size,
+    Nested.Types.Device.serialize,
+    Nested.Types.Device.deserialize,
+    {
+      id: deviceId(u64Max),
+      address: { $field: 'v4', v4 },
+      network: {
+        $field: 'wireless',
+        wireless: { ssid: 'Home', channel: 11 },
+      },
+      peers: [
+        { $field: 'v4', v4: { octets: [] } },
+        { $field: 'v6', v6: new Uint8Array([0xfe, 0x80]).buffer },
+      ],
+    },
+  );
+
+  assertRoundTrip(
+    Nested.Types.DeviceIpAddress.size,
+    Nested.Types.DeviceIpAddress.serialize,
+    Nested.Types.DeviceIpAddress.deserialize,
+    { $field: 'v4', v4 },
+  );
+
+  assertRoundTrip(
+    Nested.Network.Network.size,
+    Nested.Network.Network.serialize,
+    Nested.Network.Network.deserialize,
+    {
+      name: 'Office',
+      kind: {
+        $field: 'wireless',
+        wireless: { ssid: '', channel: undefined },
+      },
+    },
+  );
+
+  // Nesting doesn't affect the encoding.
+  assertMatch(
+    Nested.Types.DeviceIpAddressV4.size,
+    Nested.Types.DeviceIpAddressV4.serialize,
+    Nested.Types.PlainV4.deserialize,
+    v4,
+    { octets: [192, 168, 0, 1] },
+  );
+}
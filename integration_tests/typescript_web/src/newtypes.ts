@@ -0,0 +1,92 @@
+/* eslint-disable @typescript-eslint/no-magic-numbers -- Allowed just for this file. */
+
+import { Newtypes } from '../generated/types';
+import { assertMatch, assertRoundTrip } from './assertions';
+
+const u64Max = 18_446_744_073_709_551_615n;
+
+function userId(id: bigint): Newtypes.Ids.UserIdOut {
+  return id as Newtypes.Ids.UserIdOut;
+}
+
+function email(address: string): Newtypes.Ids.EmailOut {
+  return address as Newtypes.Ids.EmailOut;
+}
+
+const account: Newtypes.Types.AccountOut = {
+  id: userId(42n),
+  email: email('alice@example.com') as Newtypes.Types.PrimaryEmailOut,
+  backupEmails: [email(''), email('alice@example.org')],
+  scores: [0.0, 1.5, Number.POSITIVE_INFINITY] as Newtypes.Types.ScoresOut,
+  tags: new Map([
+    ['manager', userId(u64Max)],
+    ['mentor', userId(0n)],
+  ]) as Newtypes.Types.TaggedUserIdOut,
+  friends: [userId(0n), userId(1n), userId(u64Max)],
+};
+
+export default function run(): void {
+  assertRoundTrip(
+    Newtypes.Types.Account.size,
+    Newtypes.Types.Account.serialize,
+    Newtypes.Types.Account.deserialize,
+    {
+      id: userId(0n),
+      email: email('') as Newtypes.Types.PrimaryEmailOut,
+      backupEmails: undefined,
+      scores: [] as Newtypes.Types.ScoresOut,
+      tags: new Map() as Newtypes.Types.TaggedUserIdOut,
+      friends: [],
+    },
+  );
+
+  assertRoundTrip(
+    Newtypes.Types.Account.size,
+    Newtypes.Types.Account.serialize,
+    Newtypes.Types.Account.deserialize,
+    account,
+  );
+
+  assertRoundTrip(
+    Newtypes.Types.Principal.size,
+    Newtypes.Types.Principal.serialize,
+    Newtypes.Types.Principal.deserialize,
+    { $field: 'user', user: userId(u64Max) },
+  );
+
+  assertRoundTrip(
+    Newtypes.Types.Principal.size,
+    Newtypes.Types.Principal.serialize,
+    Newtypes.Types.Principal.deserialize,
+    {
+      $field: 'administrator',
+      administrator: account as Newtypes.Types.AdministratorOut,
+    },
+  );
+
+  assertRoundTrip(
+    Newtypes.Types.Principal.size,
+    Newtypes.Types.Principal.serialize,
+    Newtypes.Types.Principal.deserialize,
+    { $field: 'anonymous', anonymous: null },
+  );
+
+  // Newtypes are encoded like their underlying types.
+  assertMatch(
+    Newtypes.Types.Account.size,
+    Newtypes.Types.Account.serialize,
+    Newtypes.Types.PlainAccount.deserialize,
+    account,
+    {
+      id: 42n,
+      email: 'alice@example.com',
+      backupEmails: ['', 'alice@example.org'],
+      scores: [0.0, 1.5, Number.POSITIVE_INFINITY],
+      tags: new Map([
+        ['manager', u64Max],
+        ['mentor', 0n],
+      ]),
+      friends: [0n, 1n, u64Max],
+    },
+  );
+}
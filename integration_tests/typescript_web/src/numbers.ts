@@ -0,0 +1,161 @@
+/* eslint-disable @typescript-eslint/no-magic-numbers -- Allowed just for this file. */
+
+import { Numbers } from '../generated/types';
+import { assertMatch, assertRoundTrip } from './assertions';
+
+const u8Max = 255;
+const u32Max = 4_294_967_295;
+const s32Min = -2_147_483_648;
+const s32Max = 2_147_483_647;
+const f32Max = 3.4028234663852886e38;
+
+// The entries below are listed in ascending order of their keys so the encoded bytes are the same
+// as those of the other code generators, which sort the entries.
+export default function run(): void {
+  assertRoundTrip(
+    Numbers.Types.Foo.size,
+    Numbers.Types.Foo.serialize,
+    Numbers.Types.Foo.deserialize,
+    {
+      a: 0,
+      b: 0,
+      c: 0,
+      d: 0,
+      e: [],
+      f: [],
+      g: [],
+      h: [],
+      i: new Map(),
+      j: new Map(),
+      k: 0,
+      l: undefined,
+    },
+  );
+
+  assertRoundTrip(
+    Numbers.Types.Foo.size,
+    Numbers.Types.Foo.serialize,
+    Numbers.Types.Foo.deserialize,
+    {
+      a: u8Max,
+      b: u32Max,
+      c: s32Min,
+      d: 1.5,
+      e: [0, 42, u8Max],
+      f: [0, 42, u32Max],
+      g: [s32Min, -1, 0, 1, s32Max],
+      h: [0, -2, f32Max, Number.POSITIVE_INFINITY],
+      i: new Map([
+        [0, -f32Max],
+        [u8Max, 0.5],
+      ]),
+      j: new Map([
+        [s32Min, 0],
+        [s32Max, u32Max],
+      ]),
+      k: 42,
+      l: -0.25,
+    },
+  );
+
+  // eslint-disable-next-line no-console -- Allow us to separate the test groups with a line break.
+  console.log();
+
+  assertRoundTrip(
+    Numbers.Types.Bar.size,
+    Numbers.Types.Bar.serialize,
+    Numbers.Types.Bar.deserialize,
+    {
+      $field: 'a',
+      a: u8Max,
+    },
+  );
+
+  assertRoundTrip(
+    Numbers.Types.Bar.size,
+    Numbers.Types.Bar.serialize,
+    Numbers.Types.Bar.deserialize,
+    {
+      $field: 'b',
+      b: u32Max,
+    },
+  );
+
+  assertRoundTrip(
+    Numbers.Types.Bar.size,
+    Numbers.Types.Bar.serialize,
+    Numbers.Types.Bar.deserialize,
+    {
+      $field: 'c',
+      c: s32Min,
+    },
+  );
+
+  assertRoundTrip(
+    Numbers.Types.Bar.size,
+    Numbers.Types.Bar.serialize,
+    Numbers.Types.Bar.deserialize,
+    {
+      $field: 'd',
+      d: -f32Max,
+    },
+  );
+
+  const $fallback = { $field: 'a', a: 42 };
+
+  assertMatch(
+    Numbers.Types.Bar.size,
+    Numbers.Types.Bar.serialize,
+    Numbers.Types.Bar.deserialize,
+    {
+      $field: 'e',
+      e: [1, 2, 3],
+      $fallback,
+    },
+    {
+      $field: 'e',
+      e: [1, 2, 3],
+    },
+  );
+
+  assertRoundTrip(
+    Numbers.Types.Bar.size,
+    Numbers.Types.Bar.serialize,
+    Numbers.Types.Bar.deserialize,
+    {
+      $field: 'f',
+      f: -42,
+      $fallback,
+    },
+  );
+
+  // eslint-disable-next-line no-console -- Allow us to separate the test groups with a line break.
+  console.log();
+
+  // The narrow integer types are encoded like their 64-bit counterparts, so they can be widened.
+  assertMatch(
+    Numbers.Types.Narrow.size,
+    Numbers.Types.Narrow.serialize,
+    Numbers.Types.Wide.deserialize,
+    {
+      x: u8Max,
+      y: u32Max,
+      z: s32Min,
+    },
+    {
+      x: BigInt(u8Max),
+      y: BigInt(u32Max),
+      z: BigInt(s32Min),
+    },
+  );
+
+  // Values which don't fit in the narrow types are rejected rather than truncated.
+  const bytes = Numbers.Types.Wide.serialize({
+    x: BigInt(u8Max) + 1n,
+    y: 0n,
+    z: 0n,
+  });
+  if (!(Numbers.Types.Narrow.deserialize(bytes) instanceof Error)) {
+    throw new Error('Mismatch!');
+  }
+}
@@ -0,0 +1,98 @@
+/* eslint-disable @typescript-eslint/no-magic-numbers -- Allowed just for this file. */
+
+import { Degenerate, Services } from '../generated/types';
+import { assertEqual, assertMatch, assertRoundTrip } from './assertions';
+
+const sent: Services.Types.SendEmailRequestIn[] = [];
+
+const handler: Services.Types.EmailHandler = {
+  send(
+    request: Services.Types.SendEmailRequestIn,
+  ): Services.Types.SendEmailResponseOut {
+    if (request.to === '') {
+      return { error: 'No recipient.' };
+    }
+
+    sent.push(request);
+
+    return { success: null };
+  },
+
+  list(
+    request: Services.Types.ListEmailsRequestIn,
+  ): Services.Types.PageSendEmailRequestOut {
+    return { items: sent.slice(0, Number(request.limit)) };
+  },
+
+  ping(): Degenerate.Types.EmptyStructOut {
+    return {};
+  },
+};
+
+// This transport delivers requests directly to a handler in the same process.
+async function transport(
+  method: number,
+  request: ArrayBuffer,
+): Promise<ArrayBuffer> {
+  const response = await Services.Types.Email.dispatch(
+    handler,
+    method,
+    request,
+  );
+
+  if (response instanceof Error) {
+    throw response;
+  }
+
+  return response;
+}
+
+export default async function run(): Promise<void> {
+  const email = {
+    to: 'typical@example.com',
+    subject: 'I love Typical!',
+    body: 'It makes serialization easy and safe.',
+  };
+
+  assertRoundTrip(
+    Services.Types.SendEmailRequest.size,
+    Services.Types.SendEmailRequest.serialize,
+    Services.Types.SendEmailRequest.deserialize,
+    email,
+  );
+
+  assertMatch(
+    Services.Types.SendEmailResponse.size,
+    Services.Types.SendEmailResponse.serialize,
+    Services.Types.SendEmailResponse.deserialize,
+    { error: 'No recipient.' },
+    { $field: 'error', error: 'No recipient.' },
+  );
+
+  const client = Services.Types.Email.client(transport);
+
+  assertEqual(await client.send(email), { $field: 'success', success: null });
+
+  assertEqual(await client.send({ to: '', subject: '', body: '' }), {
+    $field: 'error',
+    error: 'No recipient.',
+  });
+
+  assertEqual(await client.list({ limit: 10n }), { items: [email] });
+
+  assertEqual(await client.ping({}), {});
+
+  const deletedMethodResponse = await Services.Types.Email.dispatch(
+    handler,
+    1,
+    new ArrayBuffer(0),
+  );
+  assertEqual(deletedMethodResponse instanceof Error, true);
+
+  const retiredResponse = await Services.Types.Retired.dispatch(
+    {},
+    0,
+    new ArrayBuffer(0),
+  );
+  assertEqual(retiredResponse instanceof Error, true);
+}
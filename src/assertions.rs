@@ -58,10 +58,7 @@ macro_rules! assert_same {
 
 #[cfg(test)]
 mod tests {
-    use {
-        crate::{assert_fails, assert_same, error::Error},
-        std::fmt::Write,
-    };
+    use {crate::error::Error, std::fmt::Write};
 
     #[test]
     #[should_panic(expected = "The expression was supposed to fail, but it succeeded.")]
@@ -74,18 +71,9 @@ mod tests {
     #[test]
     fn assert_fails_match() {
         let success: Result<usize, Vec<Error>> = Err(vec![
-            Error {
-                message: "foo bar".to_owned(),
-                reason: None,
-            },
-            Error {
-                message: "foo search string bar".to_owned(),
-                reason: None,
-            },
-            Error {
-                message: "foo bar".to_owned(),
-                reason: None,
-            },
+            Error::from_message("foo bar".to_owned()),
+            Error::from_message("foo search string bar".to_owned()),
+            Error::from_message("foo bar".to_owned()),
         ]);
 
         assert_fails!(success, "search string");
@@ -98,26 +86,20 @@ mod tests {
         expected = "\
             The expression failed as expected, but the expected message was not found in any of \
             the errors: [\
-                Error { message: \"foo\", reason: None }, \
-                Error { message: \"bar\", reason: None }, \
-                Error { message: \"baz\", reason: None }\
+                Error { message: \"foo\", description: \"foo\", source_path: None, \
+                    source_range: None, reason: None }, \
+                Error { message: \"bar\", description: \"bar\", source_path: None, \
+                    source_range: None, reason: None }, \
+                Error { message: \"baz\", description: \"baz\", source_path: None, \
+                    source_range: None, reason: None }\
             ].\
         "
     )]
     fn assert_fails_mismatch() {
         let success: Result<usize, Vec<Error>> = Err(vec![
-            Error {
-                message: "foo".to_owned(),
-                reason: None,
-            },
-            Error {
-                message: "bar".to_owned(),
-                reason: None,
-            },
-            Error {
-                message: "baz".to_owned(),
-                reason: None,
-            },
+            Error::from_message("foo".to_owned()),
+            Error::from_message("bar".to_owned()),
+            Error::from_message("baz".to_owned()),
         ]);
 
         assert_fails!(success, "search string");
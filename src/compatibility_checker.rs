@@ -0,0 +1,1614 @@
+use {
+    crate::{
+        error::{listing, throw, Error},
+        format::CodeStr,
+        identifier::Identifier,
+        monomorphizer::monomorphize,
+        schema,
+    },
+    std::{
+        cmp::Ordering,
+        collections::{BTreeMap, HashMap, HashSet},
+        path::{Path, PathBuf},
+    },
+};
+
+// This struct bundles a schema with its namespace, which is needed to resolve the type references
+// within it.
+#[derive(Clone, Copy)]
+struct Scope<'a> {
+    namespace: &'a schema::Namespace,
+    schema: &'a schema::Schema,
+}
+
+// This struct bundles a declaration with the information needed to resolve the types of its fields
+// and to report errors about it.
+#[derive(Clone, Copy)]
+struct DeclarationContext<'a> {
+    scope: Scope<'a>,
+    declaration: &'a schema::Declaration,
+    source_path: &'a Path,
+    source_contents: &'a str,
+}
+
+// This struct bundles a service with the information needed to resolve the types of its methods and
+// to report errors about it.
+#[derive(Clone, Copy)]
+struct ServiceContext<'a> {
+    scope: Scope<'a>,
+    service: &'a schema::Service,
+    source_path: &'a Path,
+    source_contents: &'a str,
+}
+
+// This type maps each (namespace, name) to the corresponding declaration.
+type DeclarationMap<'a> = HashMap<(schema::Namespace, Identifier), DeclarationContext<'a>>;
+
+// This type maps each (namespace, name) to the corresponding service.
+type ServiceMap<'a> = HashMap<(schema::Namespace, Identifier), ServiceContext<'a>>;
+
+// This type is used to remember which (old, new) pairs of declarations have already been checked.
+type PairSet = HashSet<(
+    (schema::Namespace, Identifier),
+    (schema::Namespace, Identifier),
+)>;
+
+// This function checks that a schema and its transitive dependencies can safely replace an older
+// version of the same. The rules are those from the "Summary of what kinds of schema changes are
+// safe" section of the README. Types are matched by namespace and name, except that the types in
+// the two root schemas are matched with each other even if the root schemas have different names.
+// Both sets of schemas are assumed to be valid.
+pub fn check_compatibility(
+    old_root_namespace: &schema::Namespace,
+    old_schemas: &BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>,
+    new_root_namespace: &schema::Namespace,
+    new_schemas: &BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>,
+) -> Result<(), Vec<Error>> {
+    // Replace the generic declarations with their instantiations [ref:monomorphization]. The
+    // `unwrap`s are safe since the schemas are assumed to be valid.
+    let old_schemas = &monomorphize(old_schemas).unwrap();
+    let new_schemas = &monomorphize(new_schemas).unwrap();
+
+    // We'll add any errors to this.
+    let mut errors: Vec<Error> = vec![];
+
+    // Construct a map from (namespace, name) to declaration for each version.
+    let old_types = declaration_map(old_schemas);
+    let new_types = declaration_map(new_schemas);
+
+    // Construct a map from (namespace, name) to service for each version.
+    let old_services = service_map(old_schemas);
+    let new_services = service_map(new_schemas);
+
+    // Keep track of which pairs of declarations have been checked to avoid duplicate reports.
+    let mut pairs_checked = HashSet::new();
+
+    // Check each declaration which exists in both versions, and each service of the old version.
+    for (namespace, (schema, _, _)) in old_schemas {
+        let new_namespace = if namespace == old_root_namespace {
+            new_root_namespace
+        } else {
+            namespace
+        };
+
+        for declaration in &schema.declarations {
+            let old_qualified_type = (namespace.clone(), declaration.name.clone());
+            let new_qualified_type = (new_namespace.clone(), declaration.name.clone());
+
+            if new_types.contains_key(&new_qualified_type) {
+                check_declaration_pair(
+                    &old_types,
+                    &new_types,
+                    &mut pairs_checked,
+                    &mut errors,
+                    &old_qualified_type,
+                    &new_qualified_type,
+                );
+            }
+        }
+
+        for service in &schema.services {
+            // The `unwrap` is safe since the map was constructed from the same schemas.
+            let old = old_services
+                .get(&(namespace.clone(), service.name.clone()))
+                .unwrap();
+
+            if let Some(new) = new_services.get(&(new_namespace.clone(), service.name.clone())) {
+                check_service_pair(
+                    &old_types,
+                    &new_types,
+                    &mut pairs_checked,
+                    &mut errors,
+                    old,
+                    new,
+                );
+            } else {
+                // Clients built from the old schema may still call the methods of the service.
+                errors.push(throw::<Error>(
+                    &format!(
+                        "Service {} was removed, but clients built from the old schema may still \
+                            call its methods.",
+                        service.name.code_str(),
+                    ),
+                    Some(old.source_path),
+                    Some(&listing(old.source_contents, service.source_range)),
+                    None,
+                ));
+            }
+        }
+    }
+
+    // Return a success or report any errors.
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+// Construct a map from (namespace, name) to declaration.
+fn declaration_map(
+    schemas: &BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>,
+) -> DeclarationMap<'_> {
+    let mut declarations = HashMap::new();
+
+    for (namespace, (schema, source_path, source_contents)) in schemas {
+        for declaration in &schema.declarations {
+            declarations.insert(
+                (namespace.clone(), declaration.name.clone()),
+                DeclarationContext {
+                    scope: Scope { namespace, schema },
+                    declaration,
+                    source_path,
+                    source_contents,
+                },
+            );
+        }
+    }
+
+    declarations
+}
+
+// Construct a map from (namespace, name) to service.
+fn service_map(
+    schemas: &BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>,
+) -> ServiceMap<'_> {
+    let mut services = HashMap::new();
+
+    for (namespace, (schema, source_path, source_contents)) in schemas {
+        for service in &schema.services {
+            services.insert(
+                (namespace.clone(), service.name.clone()),
+                ServiceContext {
+                    scope: Scope { namespace, schema },
+                    service,
+                    source_path,
+                    source_contents,
+                },
+            );
+        }
+    }
+
+    services
+}
+
+// This function checks that a service can safely replace another one. Methods are matched by index,
+// just like fields.
+fn check_service_pair(
+    old_types: &DeclarationMap,
+    new_types: &DeclarationMap,
+    pairs_checked: &mut PairSet,
+    errors: &mut Vec<Error>,
+    old: &ServiceContext,
+    new: &ServiceContext,
+) {
+    // Index the methods by their indices.
+    let old_methods = old
+        .service
+        .methods
+        .iter()
+        .map(|method| (method.index, method))
+        .collect::<BTreeMap<_, _>>();
+    let new_methods = new
+        .service
+        .methods
+        .iter()
+        .map(|method| (method.index, method))
+        .collect::<BTreeMap<_, _>>();
+
+    // Check the methods that were removed. Clients built from the old schema may still call them.
+    for (index, old_method) in &old_methods {
+        if !new_methods.contains_key(index) {
+            errors.push(throw::<Error>(
+                &format!(
+                    "Method {} of {} was removed, but clients built from the old schema may still \
+                        call it.",
+                    old_method.name.code_str(),
+                    old.service.name.code_str(),
+                ),
+                Some(old.source_path),
+                Some(&listing(old.source_contents, old_method.source_range)),
+                None,
+            ));
+        }
+    }
+
+    // Check the methods that were added or changed.
+    for (index, new_method) in &new_methods {
+        if let Some(old_method) = old_methods.get(index) {
+            // Check that the request and response types didn't change.
+            for (role, old_type, new_type) in [
+                ("request", &old_method.input_type, &new_method.input_type),
+                ("response", &old_method.output_type, &new_method.output_type),
+            ] {
+                if !check_type_pair(
+                    old_types,
+                    new_types,
+                    pairs_checked,
+                    errors,
+                    old.scope,
+                    new.scope,
+                    old_type,
+                    new_type,
+                ) {
+                    errors.push(throw::<Error>(
+                        &format!(
+                            "The {role} type of method {} of {} was changed from {} to {}.",
+                            new_method.name.code_str(),
+                            new.service.name.code_str(),
+                            describe_type(old_type),
+                            describe_type(new_type),
+                        ),
+                        Some(new.source_path),
+                        Some(&listing(new.source_contents, new_type.source_range)),
+                        None,
+                    ));
+                }
+            }
+        } else if old.service.deleted.contains(index) {
+            errors.push(throw::<Error>(
+                &format!(
+                    "Method {} of {} reuses index {}, which was marked as deleted.",
+                    new_method.name.code_str(),
+                    new.service.name.code_str(),
+                    index.to_string().code_str(),
+                ),
+                Some(new.source_path),
+                Some(&listing(new.source_contents, new_method.source_range)),
+                None,
+            ));
+        }
+    }
+}
+
+// This function checks that a declaration can safely replace another one.
+#[allow(clippy::too_many_lines)]
+fn check_declaration_pair(
+    old_types: &DeclarationMap,
+    new_types: &DeclarationMap,
+    pairs_checked: &mut PairSet,
+    errors: &mut Vec<Error>,
+    old_qualified_type: &(schema::Namespace, Identifier),
+    new_qualified_type: &(schema::Namespace, Identifier),
+) {
+    // Stop if we've already checked this pair.
+    if !pairs_checked.insert((old_qualified_type.clone(), new_qualified_type.clone())) {
+        return;
+    }
+
+    // The `unwrap`s are safe because the callers only construct pairs of declarations that exist.
+    let old = old_types.get(old_qualified_type).unwrap();
+    let new = new_types.get(new_qualified_type).unwrap();
+
+    // Check that structs remain structs and choices remain choices, except for the special case
+    // of a single required field.
+    match (&old.declaration.variant, &new.declaration.variant) {
+        (schema::DeclarationVariant::Newtype(_), _)
+        | (_, schema::DeclarationVariant::Newtype(_)) => {
+            // Newtypes have no encoding of their own [ref:newtype_encoding], so they're checked
+            // wherever they're used instead.
+            return;
+        }
+        (schema::DeclarationVariant::Struct, schema::DeclarationVariant::Struct)
+        | (schema::DeclarationVariant::Choice, schema::DeclarationVariant::Choice) => {}
+        (schema::DeclarationVariant::Struct, schema::DeclarationVariant::Choice)
+        | (schema::DeclarationVariant::Choice, schema::DeclarationVariant::Struct) => {
+            let single_required_field = |declaration: &schema::Declaration| {
+                declaration.fields.len() == 1
+                    && matches!(declaration.fields[0].rule, schema::Rule::Required)
+            };
+
+            if !single_required_field(old.declaration)
+                || !single_required_field(new.declaration)
+                || old.declaration.fields[0].index != new.declaration.fields[0].index
+            {
+                errors.push(throw::<Error>(
+                    &format!(
+                        "{} was converted from a {} into a {}. This is only safe when both \
+                            have exactly one field, which must be required and have the same \
+                            index.",
+                        new.declaration.name.code_str(),
+                        variant_name(&old.declaration.variant),
+                        variant_name(&new.declaration.variant),
+                    ),
+                    Some(new.source_path),
+                    Some(&listing(new.source_contents, new.declaration.source_range)),
+                    None,
+                ));
+
+                return;
+            }
+        }
+    }
+
+    // Index the fields by their indices.
+    let old_fields = old
+        .declaration
+        .fields
+        .iter()
+        .map(|field| (field.index, field))
+        .collect::<BTreeMap<_, _>>();
+    let new_fields = new
+        .declaration
+        .fields
+        .iter()
+        .map(|field| (field.index, field))
+        .collect::<BTreeMap<_, _>>();
+
+    // Check the fields that were removed.
+    for (index, old_field) in &old_fields {
+        if !new_fields.contains_key(index) {
+            if let schema::Rule::Required = old_field.rule {
+                errors.push(throw::<Error>(
+                    &format!(
+                        "Required field {} of {} was removed. It must be made asymmetric before \
+                            it can be removed.",
+                        old_field.name.code_str(),
+                        old.declaration.name.code_str(),
+                    ),
+                    Some(old.source_path),
+                    Some(&listing(old.source_contents, old_field.source_range)),
+                    None,
+                ));
+            }
+        }
+    }
+
+    // Check the fields that were added or changed.
+    for (index, new_field) in &new_fields {
+        if let Some(old_field) = old_fields.get(index) {
+            // Check that the rule didn't jump between required and optional.
+            if let (schema::Rule::Required, schema::Rule::Optional)
+            | (schema::Rule::Optional, schema::Rule::Required) =
+                (&old_field.rule, &new_field.rule)
+            {
+                errors.push(throw::<Error>(
+                    &format!(
+                        "Field {} of {} was changed from {} to {}. It must be made asymmetric \
+                            in between.",
+                        new_field.name.code_str(),
+                        new.declaration.name.code_str(),
+                        rule_name(&old_field.rule),
+                        rule_name(&new_field.rule),
+                    ),
+                    Some(new.source_path),
+                    Some(&listing(new.source_contents, new_field.source_range)),
+                    None,
+                ));
+            }
+
+            // Check that the type didn't change.
+            if !check_type_pair(
+                old_types,
+                new_types,
+                pairs_checked,
+                errors,
+                old.scope,
+                new.scope,
+                &old_field.r#type,
+                &new_field.r#type,
+            ) {
+                errors.push(throw::<Error>(
+                    &format!(
+                        "The type of field {} of {} was changed from {} to {}.",
+                        new_field.name.code_str(),
+                        new.declaration.name.code_str(),
+                        describe_type(&old_field.r#type),
+                        describe_type(&new_field.r#type),
+                    ),
+                    Some(new.source_path),
+                    Some(&listing(new.source_contents, new_field.r#type.source_range)),
+                    None,
+                ));
+            }
+
+            // Check that the constraints weren't tightened [ref:constraints].
+            for attribute in tightened_constraints(
+                &schema::constraints(&old_field.attributes),
+                &schema::constraints(&new_field.attributes),
+            ) {
+                errors.push(throw::<Error>(
+                    &format!(
+                        "The {} constraint of field {} of {} was tightened. The new readers \
+                            would reject messages written by the old writers.",
+                        format!("@{attribute}").code_str(),
+                        new_field.name.code_str(),
+                        new.declaration.name.code_str(),
+                    ),
+                    Some(new.source_path),
+                    Some(&listing(new.source_contents, new_field.source_range)),
+                    None,
+                ));
+            }
+        } else if old.declaration.deleted.contains(index) {
+            errors.push(throw::<Error>(
+                &format!(
+                    "Field {} of {} reuses index {}, which was marked as deleted.",
+                    new_field.name.code_str(),
+                    new.declaration.name.code_str(),
+                    index.to_string().code_str(),
+                ),
+                Some(new.source_path),
+                Some(&listing(new.source_contents, new_field.source_range)),
+                None,
+            ));
+        } else if let schema::Rule::Required = new_field.rule {
+            errors.push(throw::<Error>(
+                &format!(
+                    "Required field {} was added to {}. It must be added as asymmetric first.",
+                    new_field.name.code_str(),
+                    new.declaration.name.code_str(),
+                ),
+                Some(new.source_path),
+                Some(&listing(new.source_contents, new_field.source_range)),
+                None,
+            ));
+        }
+    }
+}
+
+// This function determines whether two types have the same encoding. User-defined types are
+// compared recursively, and any incompatibilities found within them are reported separately.
+#[allow(clippy::too_many_arguments, clippy::too_many_lines)]
+fn check_type_pair(
+    old_types: &DeclarationMap,
+    new_types: &DeclarationMap,
+    pairs_checked: &mut PairSet,
+    errors: &mut Vec<Error>,
+    old: Scope,
+    new: Scope,
+    old_type: &schema::Type,
+    new_type: &schema::Type,
+) -> bool {
+    match (&old_type.variant, &new_type.variant) {
+        // Fixed-length arrays are encoded like variable-length ones [tag:fixed_length_encoding], so
+        // removing a length is like widening an integer type. Adding or changing a length is not
+        // allowed, since the new readers would reject the other lengths written by the old
+        // writers.
+        (
+            schema::TypeVariant::Array(old_inner_type, old_length),
+            schema::TypeVariant::Array(new_inner_type, new_length),
+        ) => {
+            (new_length.is_none() || old_length == new_length)
+                && check_type_pair(
+                    old_types,
+                    new_types,
+                    pairs_checked,
+                    errors,
+                    old,
+                    new,
+                    old_inner_type,
+                    new_inner_type,
+                )
+        }
+        (
+            schema::TypeVariant::Map(old_key_type, old_value_type),
+            schema::TypeVariant::Map(new_key_type, new_value_type),
+        ) => {
+            check_type_pair(
+                old_types,
+                new_types,
+                pairs_checked,
+                errors,
+                old,
+                new,
+                old_key_type,
+                new_key_type,
+            ) && check_type_pair(
+                old_types,
+                new_types,
+                pairs_checked,
+                errors,
+                old,
+                new,
+                old_value_type,
+                new_value_type,
+            )
+        }
+        (
+            schema::TypeVariant::Array(old_inner_type, _),
+            schema::TypeVariant::Map(new_key_type, new_value_type),
+        ) => {
+            // This is the migration from an array of entries to a map [ref:map_encoding].
+            let schema::TypeVariant::Custom(old_import, old_name, _) = &old_inner_type.variant
+            else {
+                return false;
+            };
+
+            // The `unwrap` is safe since the schemas are valid.
+            let old_entry = old_types
+                .get(&(resolve_import(old, old_import.as_ref()), old_name.clone()))
+                .unwrap();
+
+            entry_fields(old_entry.declaration).is_some_and(|(old_key_type, old_value_type)| {
+                check_type_pair(
+                    old_types,
+                    new_types,
+                    pairs_checked,
+                    errors,
+                    old_entry.scope,
+                    new,
+                    old_key_type,
+                    new_key_type,
+                ) && check_type_pair(
+                    old_types,
+                    new_types,
+                    pairs_checked,
+                    errors,
+                    old_entry.scope,
+                    new,
+                    old_value_type,
+                    new_value_type,
+                )
+            })
+        }
+        (
+            schema::TypeVariant::Map(old_key_type, old_value_type),
+            schema::TypeVariant::Array(new_inner_type, None),
+        ) => {
+            // This is the migration from a map to an array of entries [ref:map_encoding].
+            let schema::TypeVariant::Custom(new_import, new_name, _) = &new_inner_type.variant
+            else {
+                return false;
+            };
+
+            // The `unwrap` is safe since the schemas are valid.
+            let new_entry = new_types
+                .get(&(resolve_import(new, new_import.as_ref()), new_name.clone()))
+                .unwrap();
+
+            entry_fields(new_entry.declaration).is_some_and(|(new_key_type, new_value_type)| {
+                check_type_pair(
+                    old_types,
+                    new_types,
+                    pairs_checked,
+                    errors,
+                    old,
+                    new_entry.scope,
+                    old_key_type,
+                    new_key_type,
+                ) && check_type_pair(
+                    old_types,
+                    new_types,
+                    pairs_checked,
+                    errors,
+                    old,
+                    new_entry.scope,
+                    old_value_type,
+                    new_value_type,
+                )
+            })
+        }
+        (schema::TypeVariant::Bytes(old_length), schema::TypeVariant::Bytes(new_length)) => {
+            // [ref:fixed_length_encoding]
+            new_length.is_none() || old_length == new_length
+        }
+        (schema::TypeVariant::Bool, schema::TypeVariant::Bool)
+        | (schema::TypeVariant::F32, schema::TypeVariant::F32)
+        | (schema::TypeVariant::F64, schema::TypeVariant::F64)
+        | (schema::TypeVariant::S64, schema::TypeVariant::S64)
+        | (schema::TypeVariant::String, schema::TypeVariant::String)
+        | (schema::TypeVariant::U64, schema::TypeVariant::U64)
+        | (schema::TypeVariant::Unit, schema::TypeVariant::Unit)
+        // The narrower integer types are encoded exactly like their 64-bit counterparts
+        // [tag:narrow_integer_encoding], so widening one preserves every value. Narrowing is not
+        // allowed, since the new readers would reject large values written by the old writers.
+        | (schema::TypeVariant::S32, schema::TypeVariant::S32 | schema::TypeVariant::S64)
+        | (schema::TypeVariant::U32, schema::TypeVariant::U32 | schema::TypeVariant::U64)
+        | (
+            schema::TypeVariant::U8,
+            schema::TypeVariant::U8 | schema::TypeVariant::U32 | schema::TypeVariant::U64,
+        ) => true,
+        (
+            schema::TypeVariant::Custom(old_import, old_name, _),
+            schema::TypeVariant::Custom(new_import, new_name, _),
+        ) => {
+            check_declaration_pair(
+                old_types,
+                new_types,
+                pairs_checked,
+                errors,
+                &(resolve_import(old, old_import.as_ref()), old_name.clone()),
+                &(resolve_import(new, new_import.as_ref()), new_name.clone()),
+            );
+
+            true
+        }
+        (schema::TypeVariant::Newtype(_, _, old_underlying_type), _) => {
+            // Wrapping or unwrapping a type in a newtype doesn't change its encoding
+            // [ref:newtype_encoding].
+            check_type_pair(
+                old_types,
+                new_types,
+                pairs_checked,
+                errors,
+                old,
+                new,
+                old_underlying_type,
+                new_type,
+            )
+        }
+        (_, schema::TypeVariant::Newtype(_, _, new_underlying_type)) => check_type_pair(
+            old_types,
+            new_types,
+            pairs_checked,
+            errors,
+            old,
+            new,
+            old_type,
+            new_underlying_type,
+        ),
+        _ => false,
+    }
+}
+
+// If a declaration has the same encoding as the entries of a map, return the types of its key and
+// value fields. That requires a struct whose fields at indices `0` and `1` are always present and
+// whose other fields are all allowed to be missing.
+fn entry_fields(declaration: &schema::Declaration) -> Option<(&schema::Type, &schema::Type)> {
+    if !matches!(declaration.variant, schema::DeclarationVariant::Struct) {
+        return None;
+    }
+
+    let mut key_type = None;
+    let mut value_type = None;
+
+    for field in &declaration.fields {
+        match (field.index, &field.rule) {
+            (0, schema::Rule::Required | schema::Rule::Asymmetric) => {
+                key_type = Some(&field.r#type);
+            }
+            (1, schema::Rule::Required | schema::Rule::Asymmetric) => {
+                value_type = Some(&field.r#type);
+            }
+            (0 | 1, schema::Rule::Optional) | (_, schema::Rule::Required) => return None,
+            (_, schema::Rule::Optional | schema::Rule::Asymmetric) => {}
+        }
+    }
+
+    key_type.zip(value_type)
+}
+
+// Return the names of the constraints which reject values that were previously allowed. Loosening a
+// constraint is allowed, since the old readers only reject the newly allowed values until they're
+// updated.
+fn tightened_constraints(
+    old: &schema::Constraints,
+    new: &schema::Constraints,
+) -> Vec<&'static str> {
+    let mut tightened = vec![];
+
+    if let Some(new_min) = &new.min {
+        if old.min.as_ref().is_none_or(|old_min| {
+            schema::compare_numbers(new_min, old_min) == Some(Ordering::Greater)
+        }) {
+            tightened.push("min");
+        }
+    }
+
+    if let Some(new_max) = &new.max {
+        if old
+            .max
+            .as_ref()
+            .is_none_or(|old_max| schema::compare_numbers(new_max, old_max) == Some(Ordering::Less))
+        {
+            tightened.push("max");
+        }
+    }
+
+    if new.min_length.unwrap_or(0) > old.min_length.unwrap_or(0) {
+        tightened.push("min_length");
+    }
+
+    if let Some(new_max_length) = new.max_length {
+        if old
+            .max_length
+            .is_none_or(|old_max_length| new_max_length < old_max_length)
+        {
+            tightened.push("max_length");
+        }
+    }
+
+    tightened
+}
+
+// Determine the namespace that a type reference refers to.
+fn resolve_import(scope: Scope, import: Option<&Identifier>) -> schema::Namespace {
+    import.map_or_else(
+        || scope.namespace.clone(),
+        // The first `unwrap` is safe since the schemas are valid. The second `unwrap` is safe due
+        // to [ref:namespace_populated].
+        |import| {
+            scope
+                .schema
+                .imports
+                .get(import)
+                .unwrap()
+                .namespace
+                .clone()
+                .unwrap()
+        },
+    )
+}
+
+// Render a type for error messages. Newtypes are followed by the types they're encoded as, since
+// the names alone may not reveal what changed [ref:newtype_encoding].
+fn describe_type(r#type: &schema::Type) -> String {
+    let description = r#type.to_string().code_str().to_string();
+
+    if let schema::TypeVariant::Newtype(_, _, _) = r#type.variant {
+        format!(
+            "{description} (encoded as {})",
+            schema::Type {
+                source_range: r#type.source_range,
+                variant: schema::encoding_type(&r#type.variant).clone(),
+            }
+            .to_string()
+            .code_str(),
+        )
+    } else {
+        description
+    }
+}
+
+// Render the kind of a declaration for error messages.
+fn variant_name(variant: &schema::DeclarationVariant) -> &'static str {
+    match variant {
+        schema::DeclarationVariant::Struct => "struct",
+        schema::DeclarationVariant::Choice => "choice",
+        schema::DeclarationVariant::Newtype(_) => "newtype",
+    }
+}
+
+// Render a rule for error messages.
+fn rule_name(rule: &schema::Rule) -> &'static str {
+    match rule {
+        schema::Rule::Asymmetric => "asymmetric",
+        schema::Rule::Optional => "optional",
+        schema::Rule::Required => "required",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::{
+            assert_fails, assert_same,
+            compatibility_checker::check_compatibility,
+            parser::parse,
+            schema::{Namespace, Schema},
+            schema_loader::load_schemas,
+            tokenizer::tokenize,
+        },
+        std::{
+            collections::BTreeMap,
+            fmt::Write,
+            path::{Path, PathBuf},
+        },
+    };
+
+    fn load(contents: &str) -> BTreeMap<Namespace, (Schema, PathBuf, String)> {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let tokens = tokenize(&path, contents).unwrap();
+        let schema = parse(&path, contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents.to_owned()));
+        schemas
+    }
+
+    fn check(old_contents: &str, new_contents: &str) -> Result<(), Vec<crate::error::Error>> {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+
+        check_compatibility(
+            &namespace,
+            &load(old_contents),
+            &namespace,
+            &load(new_contents),
+        )
+    }
+
+    #[test]
+    fn check_compatibility_identical() {
+        let contents = "
+            struct Foo {
+                x: Bool = 0
+                asymmetric y: [String] = 1
+                optional z: Bar = 2
+            }
+
+            choice Bar {
+                x = 0
+                optional y: F64 = 1
+            }
+        ";
+
+        assert_same!(check(contents, contents), Ok(()));
+    }
+
+    #[test]
+    fn check_compatibility_safe_changes() {
+        let old_contents = "
+            struct Foo {
+                x: Bool = 0
+                asymmetric y: String = 1
+                optional z: U64 = 2
+            }
+        ";
+        let new_contents = "
+            struct Foo {
+                renamed: Bool = 0
+                optional y: String = 1
+                asymmetric w: Bytes = 3
+
+                deleted 2
+            }
+        ";
+
+        assert_same!(check(old_contents, new_contents), Ok(()));
+    }
+
+    #[test]
+    fn check_compatibility_required_to_optional() {
+        let old_contents = "
+            struct Foo {
+                x: Bool = 0
+            }
+        ";
+        let new_contents = "
+            struct Foo {
+                optional x: Bool = 0
+            }
+        ";
+
+        assert_fails!(
+            check(old_contents, new_contents),
+            "Field `x` of `Foo` was changed from required to optional.",
+        );
+    }
+
+    #[test]
+    fn check_compatibility_optional_to_required() {
+        let old_contents = "
+            choice Foo {
+                x = 0
+                optional y = 1
+            }
+        ";
+        let new_contents = "
+            choice Foo {
+                x = 0
+                y = 1
+            }
+        ";
+
+        assert_fails!(
+            check(old_contents, new_contents),
+            "Field `y` of `Foo` was changed from optional to required.",
+        );
+    }
+
+    #[test]
+    fn check_compatibility_required_field_removed() {
+        let old_contents = "
+            struct Foo {
+                x: Bool = 0
+                y: Bool = 1
+            }
+        ";
+        let new_contents = "
+            struct Foo {
+                x: Bool = 0
+
+                deleted 1
+            }
+        ";
+
+        assert_fails!(
+            check(old_contents, new_contents),
+            "Required field `y` of `Foo` was removed.",
+        );
+    }
+
+    #[test]
+    fn check_compatibility_required_field_added() {
+        let old_contents = "
+            struct Foo {
+                x: Bool = 0
+            }
+        ";
+        let new_contents = "
+            struct Foo {
+                x: Bool = 0
+                y: Bool = 1
+            }
+        ";
+
+        assert_fails!(
+            check(old_contents, new_contents),
+            "Required field `y` was added to `Foo`.",
+        );
+    }
+
+    #[test]
+    fn check_compatibility_deleted_index_reused() {
+        let old_contents = "
+            struct Foo {
+                x: Bool = 0
+
+                deleted 1
+            }
+        ";
+        let new_contents = "
+            struct Foo {
+                x: Bool = 0
+                optional y: String = 1
+            }
+        ";
+
+        assert_fails!(
+            check(old_contents, new_contents),
+            "Field `y` of `Foo` reuses index `1`, which was marked as deleted.",
+        );
+    }
+
+    #[test]
+    fn check_compatibility_type_changed() {
+        let old_contents = "
+            struct Foo {
+                x: [U64] = 0
+            }
+        ";
+        let new_contents = "
+            struct Foo {
+                x: [S64] = 0
+            }
+        ";
+
+        assert_fails!(
+            check(old_contents, new_contents),
+            "The type of field `x` of `Foo` was changed from `[U64]` to `[S64]`.",
+        );
+    }
+
+    #[test]
+    fn check_compatibility_map_changed() {
+        let old_contents = "
+            struct Foo {
+                x: {String: U64} = 0
+            }
+        ";
+        let new_contents = "
+            struct Foo {
+                x: {String: S64} = 0
+            }
+        ";
+
+        assert_fails!(
+            check(old_contents, new_contents),
+            "The type of field `x` of `Foo` was changed from `{String: U64}` to `{String: S64}`.",
+        );
+    }
+
+    #[test]
+    fn check_compatibility_integer_widened() {
+        let old_contents = "
+            struct Foo {
+                w: U8 = 0
+                x: [U8] = 1
+                y: U32 = 2
+                z: {S32: Bool} = 3
+            }
+        ";
+        let new_contents = "
+            struct Foo {
+                w: U32 = 0
+                x: [U64] = 1
+                y: U64 = 2
+                z: {S64: Bool} = 3
+            }
+        ";
+
+        assert_same!(check(old_contents, new_contents), Ok(()));
+    }
+
+    #[test]
+    fn check_compatibility_integer_narrowed() {
+        let old_contents = "
+            struct Foo {
+                x: U64 = 0
+            }
+        ";
+        let new_contents = "
+            struct Foo {
+                x: U32 = 0
+            }
+        ";
+
+        assert_fails!(
+            check(old_contents, new_contents),
+            "The type of field `x` of `Foo` was changed from `U64` to `U32`.",
+        );
+    }
+
+    #[test]
+    fn check_compatibility_float_narrowed() {
+        let old_contents = "
+            struct Foo {
+                x: F64 = 0
+            }
+        ";
+        let new_contents = "
+            struct Foo {
+                x: F32 = 0
+            }
+        ";
+
+        assert_fails!(
+            check(old_contents, new_contents),
+            "The type of field `x` of `Foo` was changed from `F64` to `F32`.",
+        );
+    }
+
+    #[test]
+    fn check_compatibility_fixed_length_removed() {
+        let old_contents = "
+            struct Foo {
+                x: [U8; 4] = 0
+                y: Bytes(32) = 1
+                z: [[U64; 2]; 2] = 2
+            }
+        ";
+        let new_contents = "
+            struct Foo {
+                x: [U64] = 0
+                y: Bytes = 1
+                z: [[U64]] = 2
+            }
+        ";
+
+        assert_same!(check(old_contents, new_contents), Ok(()));
+    }
+
+    #[test]
+    fn check_compatibility_fixed_length_added() {
+        let old_contents = "
+            struct Foo {
+                x: Bytes = 0
+            }
+        ";
+        let new_contents = "
+            struct Foo {
+                x: Bytes(32) = 0
+            }
+        ";
+
+        assert_fails!(
+            check(old_contents, new_contents),
+            "The type of field `x` of `Foo` was changed from `Bytes` to `Bytes(32)`.",
+        );
+    }
+
+    #[test]
+    fn check_compatibility_fixed_length_changed() {
+        let old_contents = "
+            struct Foo {
+                x: [F64; 3] = 0
+            }
+        ";
+        let new_contents = "
+            struct Foo {
+                x: [F64; 4] = 0
+            }
+        ";
+
+        assert_fails!(
+            check(old_contents, new_contents),
+            "The type of field `x` of `Foo` was changed from `[F64; 3]` to `[F64; 4]`.",
+        );
+    }
+
+    #[test]
+    fn check_compatibility_constraints_loosened() {
+        let old_contents = "
+            struct Foo {
+                @min(0) @max(10) x: F64 = 0
+                @min_length(2) @max_length(4) y: String = 1
+            }
+        ";
+        let new_contents = "
+            struct Foo {
+                @min(-1.5) x: F64 = 0
+                @min_length(1) @max_length(8) y: String = 1
+            }
+        ";
+
+        assert_same!(check(old_contents, new_contents), Ok(()));
+    }
+
+    #[test]
+    fn check_compatibility_min_tightened() {
+        let old_contents = "
+            struct Foo {
+                @min(0) x: F64 = 0
+            }
+        ";
+        let new_contents = "
+            struct Foo {
+                @min(0.5) x: F64 = 0
+            }
+        ";
+
+        assert_fails!(
+            check(old_contents, new_contents),
+            "The `@min` constraint of field `x` of `Foo` was tightened.",
+        );
+    }
+
+    #[test]
+    fn check_compatibility_max_length_added() {
+        let old_contents = "
+            struct Foo {
+                x: [String] = 0
+            }
+        ";
+        let new_contents = "
+            struct Foo {
+                @max_length(3) x: [String] = 0
+            }
+        ";
+
+        assert_fails!(
+            check(old_contents, new_contents),
+            "The `@max_length` constraint of field `x` of `Foo` was tightened.",
+        );
+    }
+
+    #[test]
+    fn check_compatibility_entries_to_map() {
+        let old_contents = "
+            struct Foo {
+                x: [Entry] = 0
+            }
+
+            struct Entry {
+                key: String = 0
+                asymmetric value: [U64] = 1
+                optional note: String = 2
+            }
+        ";
+        let new_contents = "
+            struct Foo {
+                x: {String: [U64]} = 0
+            }
+        ";
+
+        assert_same!(check(old_contents, new_contents), Ok(()));
+        assert_same!(check(new_contents, old_contents), Ok(()));
+    }
+
+    #[test]
+    fn check_compatibility_entries_to_map_optional_value() {
+        let old_contents = "
+            struct Foo {
+                x: [Entry] = 0
+            }
+
+            struct Entry {
+                key: String = 0
+                optional value: U64 = 1
+            }
+        ";
+        let new_contents = "
+            struct Foo {
+                x: {String: U64} = 0
+            }
+        ";
+
+        assert_fails!(
+            check(old_contents, new_contents),
+            "The type of field `x` of `Foo` was changed from `[Entry]` to `{String: U64}`.",
+        );
+    }
+
+    #[test]
+    fn check_compatibility_nested_type_changed() {
+        let old_contents = "
+            struct Foo {
+                x: Bar = 0
+            }
+
+            struct Bar {
+                y: String = 0
+            }
+        ";
+        let new_contents = "
+            struct Foo {
+                x: Baz = 0
+            }
+
+            struct Baz {
+                y: Bytes = 0
+            }
+        ";
+
+        assert_fails!(
+            check(old_contents, new_contents),
+            "The type of field `y` of `Baz` was changed from `String` to `Bytes`.",
+        );
+    }
+
+    #[test]
+    fn check_compatibility_struct_to_choice_single_required_field() {
+        let old_contents = "
+            struct Foo {
+                x: String = 0
+            }
+        ";
+        let new_contents = "
+            choice Foo {
+                x: String = 0
+            }
+        ";
+
+        assert_same!(check(old_contents, new_contents), Ok(()));
+    }
+
+    #[test]
+    fn check_compatibility_struct_to_choice_multiple_fields() {
+        let old_contents = "
+            struct Foo {
+                x: String = 0
+                asymmetric y: String = 1
+            }
+        ";
+        let new_contents = "
+            choice Foo {
+                x: String = 0
+                asymmetric y: String = 1
+            }
+        ";
+
+        assert_fails!(
+            check(old_contents, new_contents),
+            "`Foo` was converted from a struct into a choice.",
+        );
+    }
+
+    #[test]
+    fn check_compatibility_newtype_wrapped_and_unwrapped() {
+        let old_contents = "
+            newtype Name = String
+
+            struct Foo {
+                x: U64 = 0
+                y: Name = 1
+                z: [U32] = 2
+            }
+        ";
+        let new_contents = "
+            newtype Id = U64
+            newtype Ids = [U64]
+
+            struct Foo {
+                x: Id = 0
+                y: String = 1
+                z: Ids = 2
+            }
+        ";
+
+        assert_same!(check(old_contents, new_contents), Ok(()));
+    }
+
+    #[test]
+    fn check_compatibility_newtype_underlying_type_changed() {
+        let old_contents = "
+            newtype Id = U64
+
+            struct Foo {
+                x: Id = 0
+            }
+        ";
+        let new_contents = "
+            newtype Id = String
+
+            struct Foo {
+                x: Id = 0
+            }
+        ";
+
+        assert_fails!(
+            check(old_contents, new_contents),
+            "The type of field `x` of `Foo` was changed from `Id` (encoded as `U64`) to `Id` \
+                (encoded as `String`).",
+        );
+    }
+
+    #[test]
+    fn check_compatibility_declaration_nested() {
+        let old_contents = "
+            struct Device {
+                address: DeviceIpAddress = 0
+            }
+
+            choice DeviceIpAddress {
+                v4: U32 = 0
+            }
+        ";
+        let new_contents = "
+            struct Device {
+                address: Device.IpAddress = 0
+
+                choice IpAddress {
+                    v4: U32 = 0
+                }
+            }
+        ";
+
+        assert_same!(check(old_contents, new_contents), Ok(()));
+    }
+
+    #[test]
+    fn check_compatibility_nested_declaration_changed() {
+        let old_contents = "
+            struct Device {
+                address: Device.IpAddress = 0
+
+                choice IpAddress {
+                    v4: U32 = 0
+                }
+            }
+        ";
+        let new_contents = "
+            struct Device {
+                address: Device.IpAddress = 0
+
+                choice IpAddress {
+                    v4: String = 0
+                }
+            }
+        ";
+
+        assert_fails!(
+            check(old_contents, new_contents),
+            "The type of field `v4` of `Device.IpAddress` was changed from `U32` to `String`.",
+        );
+    }
+
+    #[test]
+    fn check_compatibility_service_safe_changes() {
+        let old_contents = "
+            struct Request {
+                x: U32 = 0
+            }
+
+            struct Response {
+            }
+
+            service Foo {
+                send: Request -> Response = 0
+            }
+        ";
+        let new_contents = "
+            struct Request {
+                x: U64 = 0
+            }
+
+            struct Response {
+                optional y: String = 0
+            }
+
+            service Foo {
+                renamed: Request -> Response = 0
+                list: Request -> Response = 1
+            }
+        ";
+
+        assert_same!(check(old_contents, new_contents), Ok(()));
+    }
+
+    #[test]
+    fn check_compatibility_method_removed() {
+        let old_contents = "
+            struct Request {
+            }
+
+            service Foo {
+                send: Request -> Request = 0
+                list: Request -> Request = 1
+            }
+        ";
+        let new_contents = "
+            struct Request {
+            }
+
+            service Foo {
+                send: Request -> Request = 0
+
+                deleted 1
+            }
+        ";
+
+        assert_fails!(
+            check(old_contents, new_contents),
+            "Method `list` of `Foo` was removed, but clients built from the old schema may still \
+                call it.",
+        );
+    }
+
+    #[test]
+    fn check_compatibility_method_deleted_index_reused() {
+        let old_contents = "
+            struct Request {
+            }
+
+            service Foo {
+                send: Request -> Request = 0
+
+                deleted 1
+            }
+        ";
+        let new_contents = "
+            struct Request {
+            }
+
+            service Foo {
+                send: Request -> Request = 0
+                list: Request -> Request = 1
+            }
+        ";
+
+        assert_fails!(
+            check(old_contents, new_contents),
+            "Method `list` of `Foo` reuses index `1`, which was marked as deleted.",
+        );
+    }
+
+    #[test]
+    fn check_compatibility_method_request_type_changed() {
+        let old_contents = "
+            struct Request {
+                x: U64 = 0
+            }
+
+            struct OtherRequest {
+                x: String = 0
+            }
+
+            service Foo {
+                send: Request -> Request = 0
+            }
+        ";
+        let new_contents = "
+            struct Request {
+                x: U64 = 0
+            }
+
+            struct OtherRequest {
+                x: String = 0
+            }
+
+            service Foo {
+                send: OtherRequest -> Request = 0
+            }
+        ";
+
+        assert_fails!(
+            check(old_contents, new_contents),
+            "The type of field `x` of `OtherRequest` was changed from `U64` to `String`.",
+        );
+    }
+
+    #[test]
+    fn check_compatibility_method_response_type_changed() {
+        let old_contents = "
+            struct Request {
+            }
+
+            struct Response {
+                x: Bool = 0
+            }
+
+            service Foo {
+                send: Request -> Response = 0
+            }
+        ";
+        let new_contents = "
+            struct Request {
+            }
+
+            struct Response {
+                x: Bool = 0
+                y: Bool = 1
+            }
+
+            service Foo {
+                send: Request -> Response = 0
+            }
+        ";
+
+        assert_fails!(
+            check(old_contents, new_contents),
+            "Required field `y` was added to `Response`. It must be added as asymmetric first.",
+        );
+    }
+
+    #[test]
+    fn check_compatibility_service_removed() {
+        let old_contents = "
+            struct Request {
+            }
+
+            service Foo {
+                send: Request -> Request = 0
+            }
+        ";
+        let new_contents = "
+            struct Request {
+            }
+        ";
+
+        assert_fails!(
+            check(old_contents, new_contents),
+            "Service `Foo` was removed, but clients built from the old schema may still call its \
+                methods.",
+        );
+    }
+
+    #[test]
+    fn check_compatibility_schema_evolution_example() {
+        let old_path = Path::new("integration_tests/types/schema_evolution/before.t");
+        let new_path = Path::new("integration_tests/types/schema_evolution/after.t");
+
+        let result = check_compatibility(
+            &Namespace {
+                components: vec!["before".into()],
+            },
+            &load_schemas(old_path).unwrap(),
+            &Namespace {
+                components: vec!["after".into()],
+            },
+            &load_schemas(new_path).unwrap(),
+        );
+
+        assert_fails!(
+            result.clone(),
+            "Field `required_to_optional` of `ExampleStruct` was changed from required to \
+                optional.",
+        );
+        assert_fails!(
+            result.clone(),
+            "Required field `required_to_nonexistent` of `ExampleStruct` was removed.",
+        );
+        assert_fails!(
+            result,
+            "Field `nonexistent_to_required` of `ExampleChoice` reuses index `12`, which was \
+                marked as deleted.",
+        );
+    }
+}
@@ -0,0 +1,1196 @@
+use {
+    crate::{
+        count::count,
+        error::{throw, Error},
+        format::CodeStr,
+        identifier::Identifier,
+        monomorphizer::monomorphize,
+        schema::{self, encoding_type},
+    },
+    std::{
+        collections::{BTreeMap, BTreeSet},
+        fmt::Display,
+        path::PathBuf,
+    },
+};
+
+// This is the indentation used for each level of the rendered tree.
+const INDENTATION: &str = "  ";
+
+// A cursor over a region of the message being decoded. It remembers the absolute offset of the
+// region within the message so errors can point to the byte where decoding went wrong.
+#[derive(Clone, Copy)]
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    // Split off the next `size` bytes into their own reader.
+    fn take(&mut self, size: usize) -> Result<Reader<'a>, Error> {
+        if size > self.bytes.len() {
+            return Err(throw::<Error>(
+                &format!(
+                    "Unexpected end of input. Expected {} starting at byte {}, but the input \
+                        ends at byte {}.",
+                    count(size, "byte"),
+                    self.offset.to_string().code_str(),
+                    (self.offset + self.bytes.len()).to_string().code_str(),
+                ),
+                None,
+                None,
+                None,
+            ));
+        }
+
+        let (taken, rest) = self.bytes.split_at(size);
+        let reader = Reader {
+            bytes: taken,
+            offset: self.offset,
+        };
+        self.bytes = rest;
+        self.offset += size;
+
+        Ok(reader)
+    }
+
+    // Determine the size of the variable-width integer at the current position from its first
+    // byte, without consuming anything.
+    fn peek_varint_size(&self) -> Result<usize, Error> {
+        self.bytes.first().map_or_else(
+            || {
+                Err(throw::<Error>(
+                    &format!(
+                        "Unexpected end of input at byte {}. Expected a variable-width integer.",
+                        self.offset.to_string().code_str(),
+                    ),
+                    None,
+                    None,
+                    None,
+                ))
+            },
+            |first_byte| Ok(first_byte.trailing_zeros() as usize + 1),
+        )
+    }
+
+    // Read an unsigned variable-width integer.
+    fn read_varint(&mut self) -> Result<u64, Error> {
+        let size = self.peek_varint_size()?;
+        let bytes = self.take(size)?.bytes;
+        let first_byte = bytes[0];
+
+        let mut remaining_bytes_buffer = [0; 8];
+        remaining_bytes_buffer[0..size - 1].copy_from_slice(&bytes[1..]);
+        let remaining_bytes_value = u64::from_le_bytes(remaining_bytes_buffer);
+
+        Ok(match size - 1 {
+            0 => u64::from(first_byte >> 1_u32),
+            1 => 128_u64 + u64::from(first_byte >> 2_u32) + (remaining_bytes_value << 6_u32),
+            2 => 16_512_u64 + u64::from(first_byte >> 3_u32) + (remaining_bytes_value << 5_u32),
+            3 => 2_113_664_u64 + u64::from(first_byte >> 4_u32) + (remaining_bytes_value << 4_u32),
+            4 => {
+                270_549_120_u64 + u64::from(first_byte >> 5_u32) + (remaining_bytes_value << 3_u32)
+            }
+            5 => {
+                34_630_287_488_u64
+                    + u64::from(first_byte >> 6_u32)
+                    + (remaining_bytes_value << 2_u32)
+            }
+            6 => {
+                4_432_676_798_592_u64
+                    + u64::from(first_byte >> 7_u32)
+                    + (remaining_bytes_value << 1_u32)
+            }
+            7 => 567_382_630_219_904_u64 + remaining_bytes_value,
+            _ => 72_624_976_668_147_840_u64.wrapping_add(remaining_bytes_value),
+        })
+    }
+
+    // Read a fixed-width little-endian value.
+    fn read_fixed<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let mut buffer = [0; N];
+        buffer.copy_from_slice(self.take(N)?.bytes);
+        Ok(buffer)
+    }
+
+    // Read a field header and the payload it describes. The returned tuple contains the field
+    // index and a reader for the payload.
+    fn read_field(&mut self) -> Result<(u64, Reader<'a>), Error> {
+        let tag = self.read_varint()?;
+
+        let index = tag >> 2_u32;
+
+        let size = match tag & 0b11 {
+            0b00 => 0,
+            0b01 => 8,
+            0b10 => self.peek_varint_size()?,
+            _ => {
+                // If the size doesn't fit in a `usize`, it certainly exceeds the remaining input.
+                usize::try_from(self.read_varint()?).unwrap_or(usize::MAX)
+            }
+        };
+
+        Ok((index, self.take(size)?))
+    }
+}
+
+// Decode a message of the given type and render it as a human-readable tree. The type is named
+// relative to the root schema, e.g., `Foo` or `bar.Bar` for a type from an imported schema.
+pub fn decode(
+    schemas: &BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>,
+    root_namespace: &schema::Namespace,
+    type_name: &str,
+    bytes: &[u8],
+) -> Result<String, Error> {
+    // Replace the generic declarations with their instantiations [ref:monomorphization]. The
+    // `unwrap` is safe since the schemas are assumed to be valid.
+    let schemas = &monomorphize(schemas).unwrap();
+
+    // Find the declaration of the requested type.
+    let (namespace, declaration) = find_declaration(schemas, root_namespace, type_name)?;
+
+    // Render the message.
+    let mut buffer = String::new();
+    write_line(
+        &mut buffer,
+        0,
+        &format!(
+            "{} {} ({})",
+            variant_name(&declaration.variant),
+            type_name,
+            count(bytes.len(), "byte"),
+        ),
+    );
+    write_message(
+        &mut buffer,
+        1,
+        schemas,
+        &namespace,
+        declaration,
+        Reader { bytes, offset: 0 },
+    )?;
+
+    Ok(buffer)
+}
+
+// Find the declaration referred to by a type name relative to the root schema.
+pub fn find_declaration<'a>(
+    schemas: &'a BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>,
+    root_namespace: &schema::Namespace,
+    type_name: &str,
+) -> Result<(schema::Namespace, &'a schema::Declaration), Error> {
+    // The `unwrap` is safe since the root schema is always loaded.
+    let (root_schema, root_source_path, _) = schemas.get(root_namespace).unwrap();
+
+    // Resolve the import, if there is one. A qualifier which isn't an import may instead name a
+    // declaration containing a nested one [ref:nested_declarations].
+    let (namespace, name) = match type_name.split_once('.') {
+        Some((import, name)) if root_schema.imports.contains_key(&import.into()) => {
+            // The `unwrap`s are safe due to the guard and [ref:namespace_populated].
+            let import = root_schema.imports.get(&import.into()).unwrap();
+            (import.namespace.clone().unwrap(), name)
+        }
+        Some((import, _))
+            if !root_schema
+                .declarations
+                .iter()
+                .any(|declaration| declaration.name == import.into()) =>
+        {
+            return Err(throw::<Error>(
+                &format!(
+                    "There is no import named {} in {}.",
+                    import.code_str(),
+                    root_source_path.to_string_lossy().code_str(),
+                ),
+                None,
+                None,
+                None,
+            ));
+        }
+        _ => (root_namespace.clone(), type_name),
+    };
+
+    // Look up the declaration. The `unwrap` is safe since imported schemas are always loaded.
+    let name: Identifier = name.into();
+    let (schema, source_path, _) = schemas.get(&namespace).unwrap();
+    schema
+        .declarations
+        .iter()
+        .find(|declaration| declaration.name == name)
+        .map_or_else(
+            || {
+                Err(throw::<Error>(
+                    &format!(
+                        "There is no type named {} in {}.",
+                        name.code_str(),
+                        source_path.to_string_lossy().code_str(),
+                    ),
+                    None,
+                    None,
+                    None,
+                ))
+            },
+            |declaration| {
+                // Newtypes aren't messages, since they only wrap the types of fields
+                // [tag:messages_are_not_newtypes].
+                if let schema::DeclarationVariant::Newtype(_) = declaration.variant {
+                    Err(throw::<Error>(
+                        &format!(
+                            "{} is a newtype. Only structs and choices can be encoded or decoded.",
+                            name.code_str(),
+                        ),
+                        None,
+                        None,
+                        None,
+                    ))
+                } else {
+                    Ok((namespace.clone(), declaration))
+                }
+            },
+        )
+}
+
+// Find the declaration referred to by a type reference within the schema with the given namespace.
+#[must_use]
+pub fn resolve_custom_type<'a>(
+    schemas: &'a BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>,
+    namespace: &schema::Namespace,
+    import: Option<&Identifier>,
+    name: &Identifier,
+) -> (schema::Namespace, &'a schema::Declaration) {
+    // The `unwrap`s are safe since the schemas are valid.
+    let (schema, _, _) = schemas.get(namespace).unwrap();
+    let type_namespace = import.map_or_else(
+        || namespace.clone(),
+        // The `unwrap` is safe due to [ref:namespace_populated].
+        |import| {
+            schema
+                .imports
+                .get(import)
+                .unwrap()
+                .namespace
+                .clone()
+                .unwrap()
+        },
+    );
+    let declaration = schemas
+        .get(&type_namespace)
+        .unwrap()
+        .0
+        .declarations
+        .iter()
+        .find(|declaration| declaration.name == *name)
+        .unwrap();
+
+    (type_namespace, declaration)
+}
+
+// Render the fields of a struct or choice.
+fn write_message(
+    buffer: &mut String,
+    indentation: usize,
+    schemas: &BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>,
+    namespace: &schema::Namespace,
+    declaration: &schema::Declaration,
+    mut reader: Reader,
+) -> Result<(), Error> {
+    match declaration.variant {
+        schema::DeclarationVariant::Struct => {
+            // Keep track of which fields were present so we can report missing required fields.
+            let mut present_indices = BTreeSet::new();
+
+            while !reader.is_empty() {
+                let (index, payload) = reader.read_field()?;
+
+                if let Some(field) = find_field(declaration, index) {
+                    write_field(buffer, indentation, schemas, namespace, field, payload, "")?;
+                    present_indices.insert(field.index);
+                } else {
+                    write_unrecognized_field(buffer, indentation, declaration, index, payload);
+                }
+            }
+
+            for field in &declaration.fields {
+                if matches!(field.rule, schema::Rule::Required)
+                    && !present_indices.contains(&field.index)
+                {
+                    write_line(
+                        buffer,
+                        indentation,
+                        &format!(
+                            "{} (index {}): missing, but the field is required",
+                            field.name.snake_case(),
+                            field.index,
+                        ),
+                    );
+                }
+            }
+        }
+        schema::DeclarationVariant::Choice => {
+            // The reader uses the first field it recognizes. Optional fields are followed by a
+            // fallback, so decoding continues until a required or asymmetric field is found.
+            loop {
+                if reader.is_empty() {
+                    write_line(
+                        buffer,
+                        indentation,
+                        "missing a required or asymmetric field to decode the choice",
+                    );
+                    break;
+                }
+
+                let (index, payload) = reader.read_field()?;
+
+                if let Some(field) = find_field(declaration, index) {
+                    match field.rule {
+                        schema::Rule::Optional => {
+                            write_field(
+                                buffer,
+                                indentation,
+                                schemas,
+                                namespace,
+                                field,
+                                payload,
+                                " [fallback follows]",
+                            )?;
+                        }
+                        schema::Rule::Asymmetric | schema::Rule::Required => {
+                            write_field(
+                                buffer,
+                                indentation,
+                                schemas,
+                                namespace,
+                                field,
+                                payload,
+                                "",
+                            )?;
+
+                            // Writers may still include a fallback (e.g., for an asymmetric
+                            // field), but readers ignore it. Show it if it can be decoded.
+                            if !reader.is_empty() {
+                                write_line(
+                                    buffer,
+                                    indentation,
+                                    &format!(
+                                        "ignored fallback ({})",
+                                        count(reader.bytes.len(), "byte"),
+                                    ),
+                                );
+
+                                let mut fallback_buffer = String::new();
+                                if write_message(
+                                    &mut fallback_buffer,
+                                    indentation + 1,
+                                    schemas,
+                                    namespace,
+                                    declaration,
+                                    reader,
+                                )
+                                .is_ok()
+                                {
+                                    buffer.push_str(&fallback_buffer);
+                                }
+                            }
+
+                            break;
+                        }
+                    }
+                } else {
+                    write_unrecognized_field(buffer, indentation, declaration, index, payload);
+                }
+            }
+        }
+        schema::DeclarationVariant::Newtype(_) => {
+            // Impossible due to [ref:messages_are_not_newtypes] and the fact that references to
+            // newtypes are resolved by [ref:monomorphization].
+            panic!()
+        }
+    }
+
+    Ok(())
+}
+
+// Find the field with the given index, if there is one.
+fn find_field(declaration: &schema::Declaration, index: u64) -> Option<&schema::Field> {
+    declaration
+        .fields
+        .iter()
+        .find(|field| field.index as u64 == index)
+}
+
+// Render a field which the schema doesn't know about.
+fn write_unrecognized_field(
+    buffer: &mut String,
+    indentation: usize,
+    declaration: &schema::Declaration,
+    index: u64,
+    payload: Reader,
+) {
+    let deleted = usize::try_from(index).is_ok_and(|index| declaration.deleted.contains(&index));
+
+    write_line(
+        buffer,
+        indentation,
+        &format!(
+            "unrecognized field (index {}{}, {}) skipped",
+            index,
+            if deleted { ", deleted" } else { "" },
+            count(payload.bytes.len(), "byte"),
+        ),
+    );
+}
+
+// Render a field and its value.
+fn write_field(
+    buffer: &mut String,
+    indentation: usize,
+    schemas: &BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>,
+    namespace: &schema::Namespace,
+    field: &schema::Field,
+    payload: Reader,
+    suffix: &str,
+) -> Result<(), Error> {
+    let rule = match field.rule {
+        schema::Rule::Asymmetric => ", asymmetric",
+        schema::Rule::Optional => ", optional",
+        schema::Rule::Required => "",
+    };
+
+    write_value(
+        buffer,
+        indentation,
+        schemas,
+        namespace,
+        &format!(
+            "{} (index {}{}): {}",
+            field.name.snake_case(),
+            field.index,
+            rule,
+            field.r#type,
+        ),
+        suffix,
+        &field.r#type.variant,
+        payload,
+        true,
+    )
+}
+
+// Render a value of the given type. The label is written at the beginning of the line, and nested
+// values (if any) are rendered on subsequent lines.
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::too_many_lines)]
+fn write_value(
+    buffer: &mut String,
+    indentation: usize,
+    schemas: &BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>,
+    namespace: &schema::Namespace,
+    label: &str,
+    suffix: &str,
+    type_variant: &schema::TypeVariant,
+    mut reader: Reader,
+    is_field: bool,
+) -> Result<(), Error> {
+    match type_variant {
+        schema::TypeVariant::Array(inner_type, length) => {
+            match encoding_type(&inner_type.variant) {
+                schema::TypeVariant::Array(_, _)
+                | schema::TypeVariant::Bytes(_)
+                | schema::TypeVariant::Custom(_, _, _)
+                | schema::TypeVariant::Map(_, _)
+                | schema::TypeVariant::String => {
+                    // Each element is prefixed by its size.
+                    let mut elements = vec![];
+                    while !reader.is_empty() {
+                        let size = usize::try_from(reader.read_varint()?).unwrap_or(usize::MAX);
+                        elements.push(reader.take(size)?);
+                    }
+
+                    write_line(
+                        buffer,
+                        indentation,
+                        &format!(
+                            "{} ({}){}{}",
+                            label,
+                            count(elements.len(), "element"),
+                            length_note(elements.len() as u64, *length, "element"),
+                            suffix,
+                        ),
+                    );
+
+                    for (i, element) in elements.into_iter().enumerate() {
+                        write_value(
+                            buffer,
+                            indentation + 1,
+                            schemas,
+                            namespace,
+                            &format!("[{i}]"),
+                            "",
+                            &inner_type.variant,
+                            element,
+                            false,
+                        )?;
+                    }
+                }
+                schema::TypeVariant::Bool
+                | schema::TypeVariant::F32
+                | schema::TypeVariant::F64
+                | schema::TypeVariant::S32
+                | schema::TypeVariant::S64
+                | schema::TypeVariant::U32
+                | schema::TypeVariant::U64
+                | schema::TypeVariant::U8 => {
+                    // The elements are arranged contiguously.
+                    let mut elements = vec![];
+                    while !reader.is_empty() {
+                        elements.push(read_scalar(&mut reader, &inner_type.variant, false)?);
+                    }
+
+                    write_line(
+                        buffer,
+                        indentation,
+                        &format!(
+                            "{} = [{}]{}{}",
+                            label,
+                            elements.join(", "),
+                            length_note(elements.len() as u64, *length, "element"),
+                            suffix,
+                        ),
+                    );
+                }
+                schema::TypeVariant::Unit => {
+                    // Only the number of elements is encoded.
+                    let size = read_u64(&mut reader, is_field)?;
+
+                    write_line(
+                        buffer,
+                        indentation,
+                        &format!(
+                            "{} ({}){}{}",
+                            label,
+                            usize::try_from(size).map_or_else(
+                                |_| format!("{size} elements"),
+                                |size| count(size, "element"),
+                            ),
+                            length_note(size, *length, "element"),
+                            suffix,
+                        ),
+                    );
+                }
+                schema::TypeVariant::Newtype(_, _, _) => {
+                    // Impossible since `encoding_type` strips away newtypes.
+                    panic!()
+                }
+            }
+        }
+        schema::TypeVariant::Map(key_type, value_type) => {
+            // Each entry is prefixed by its size and encoded as a struct [ref:map_encoding].
+            let mut entries = vec![];
+            while !reader.is_empty() {
+                let size = usize::try_from(reader.read_varint()?).unwrap_or(usize::MAX);
+                entries.push(reader.take(size)?);
+            }
+
+            write_line(
+                buffer,
+                indentation,
+                &format!(
+                    "{} ({} {}){}",
+                    label,
+                    entries.len(),
+                    if entries.len() == 1 {
+                        "entry"
+                    } else {
+                        "entries"
+                    },
+                    suffix,
+                ),
+            );
+
+            let entry_declaration = schema::map_entry(key_type, value_type);
+            for (i, entry) in entries.into_iter().enumerate() {
+                write_line(buffer, indentation + 1, &format!("[{i}]"));
+                write_message(
+                    buffer,
+                    indentation + 2,
+                    schemas,
+                    namespace,
+                    &entry_declaration,
+                    entry,
+                )?;
+            }
+        }
+        schema::TypeVariant::Custom(import, name, _) => {
+            let (type_namespace, declaration) =
+                resolve_custom_type(schemas, namespace, import.as_ref(), name);
+
+            write_line(buffer, indentation, &format!("{label}{suffix}"));
+            write_message(
+                buffer,
+                indentation + 1,
+                schemas,
+                &type_namespace,
+                declaration,
+                reader,
+            )?;
+        }
+        schema::TypeVariant::Newtype(_, _, underlying_type) => {
+            // Newtypes are encoded like their underlying types [ref:newtype_encoding].
+            write_value(
+                buffer,
+                indentation,
+                schemas,
+                namespace,
+                label,
+                suffix,
+                &underlying_type.variant,
+                reader,
+                is_field,
+            )?;
+        }
+        schema::TypeVariant::Unit => {
+            write_line(buffer, indentation, &format!("{label}{suffix}"));
+        }
+        schema::TypeVariant::Bool
+        | schema::TypeVariant::Bytes(_)
+        | schema::TypeVariant::F32
+        | schema::TypeVariant::F64
+        | schema::TypeVariant::S32
+        | schema::TypeVariant::S64
+        | schema::TypeVariant::String
+        | schema::TypeVariant::U32
+        | schema::TypeVariant::U64
+        | schema::TypeVariant::U8 => {
+            let value = read_scalar(&mut reader, type_variant, is_field)?;
+            write_line(buffer, indentation, &format!("{label} = {value}{suffix}"));
+        }
+    }
+
+    Ok(())
+}
+
+// Read a value which can be rendered on a single line.
+fn read_scalar(
+    reader: &mut Reader,
+    type_variant: &schema::TypeVariant,
+    is_field: bool,
+) -> Result<String, Error> {
+    Ok(match type_variant {
+        schema::TypeVariant::Bool => (read_u64(reader, is_field)? != 0_u64).to_string(),
+        schema::TypeVariant::Bytes(length) => {
+            let bytes = reader.take(reader.bytes.len())?.bytes;
+            format!(
+                "<{}>{}",
+                bytes
+                    .iter()
+                    .map(|byte| format!("{byte:02x}"))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                length_note(bytes.len() as u64, *length, "byte"),
+            )
+        }
+        schema::TypeVariant::F32 => {
+            if is_field && reader.is_empty() {
+                format!("{:?}", 0.0_f32)
+            } else {
+                format!("{:?}", f32::from_le_bytes(reader.read_fixed()?))
+            }
+        }
+        schema::TypeVariant::F64 => {
+            if is_field && reader.is_empty() {
+                format!("{:?}", 0.0_f64)
+            } else {
+                format!("{:?}", f64::from_le_bytes(reader.read_fixed()?))
+            }
+        }
+        schema::TypeVariant::S32 => {
+            let value = zigzag_decode(read_u64(reader, is_field)?);
+            with_range_note(value, i32::try_from(value).is_ok())
+        }
+        schema::TypeVariant::S64 => zigzag_decode(read_u64(reader, is_field)?).to_string(),
+        schema::TypeVariant::String => {
+            let bytes = reader.take(reader.bytes.len())?.bytes;
+            match std::str::from_utf8(bytes) {
+                Ok(string) => format!("{string:?}"),
+                Err(_) => format!("{:?} (invalid UTF-8)", String::from_utf8_lossy(bytes)),
+            }
+        }
+        schema::TypeVariant::U32 => {
+            let value = read_u64(reader, is_field)?;
+            with_range_note(value, u32::try_from(value).is_ok())
+        }
+        schema::TypeVariant::U64 => read_u64(reader, is_field)?.to_string(),
+        schema::TypeVariant::U8 => {
+            let value = read_u64(reader, is_field)?;
+            with_range_note(value, u8::try_from(value).is_ok())
+        }
+        schema::TypeVariant::Newtype(_, _, underlying_type) => {
+            read_scalar(reader, &underlying_type.variant, is_field)?
+        }
+        schema::TypeVariant::Array(_, _)
+        | schema::TypeVariant::Custom(_, _, _)
+        | schema::TypeVariant::Map(_, _)
+        | schema::TypeVariant::Unit => panic!("Attempted to read a compound value as a scalar."),
+    })
+}
+
+// Read a `U64`, taking into account the special encoding used for field values.
+fn read_u64(reader: &mut Reader, is_field: bool) -> Result<u64, Error> {
+    if is_field {
+        match reader.bytes.len() {
+            0 => Ok(0),
+            8 => Ok(u64::from_le_bytes(reader.read_fixed()?)),
+            _ => reader.read_varint(),
+        }
+    } else {
+        reader.read_varint()
+    }
+}
+
+// Render an integer which was decoded as a `U64` or `S64` but belongs to a narrower type. Values
+// which don't fit in the narrower type are flagged rather than rejected, like invalid UTF-8.
+fn with_range_note<T: Display>(value: T, in_range: bool) -> String {
+    if in_range {
+        value.to_string()
+    } else {
+        format!("{value} (out of range)")
+    }
+}
+
+// Render a note for a value of a fixed-length type which has the wrong length. Such values are
+// flagged rather than rejected, like integers which are out of range.
+fn length_note(actual: u64, length: Option<usize>, noun: &str) -> String {
+    match length {
+        Some(length) if actual != length as u64 => format!(" (expected {})", count(length, noun)),
+        _ => String::new(),
+    }
+}
+
+// Convert an unsigned ZigZag representation back into a signed integer.
+fn zigzag_decode(value: u64) -> i64 {
+    // The `unwrap` is safe since the shift clears the most significant bit.
+    let magnitude = i64::try_from(value >> 1_u32).unwrap();
+
+    if value & 1 == 0 {
+        magnitude
+    } else {
+        -magnitude - 1
+    }
+}
+
+// Render the kind of a declaration.
+#[must_use]
+pub fn variant_name(variant: &schema::DeclarationVariant) -> &'static str {
+    match variant {
+        schema::DeclarationVariant::Struct => "struct",
+        schema::DeclarationVariant::Choice => "choice",
+        schema::DeclarationVariant::Newtype(_) => "newtype",
+    }
+}
+
+// Append an indented line to the buffer.
+fn write_line(buffer: &mut String, indentation: usize, line: &str) {
+    for _ in 0..indentation {
+        buffer.push_str(INDENTATION);
+    }
+    buffer.push_str(line);
+    buffer.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::{
+            assert_fails, assert_same, decoder::decode, parser::parse, schema::Namespace,
+            tokenizer::tokenize, validator::validate,
+        },
+        std::{collections::BTreeMap, fmt::Write, path::Path},
+    };
+
+    fn decode_with_schema(contents: &str, type_name: &str, bytes: &[u8]) -> String {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+
+        let tokens = tokenize(&path, contents).unwrap();
+        let schema = parse(&path, contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace.clone(), (schema, path, contents.to_owned()));
+        validate(&schemas).unwrap();
+
+        decode(&schemas, &namespace, type_name, bytes).unwrap()
+    }
+
+    #[test]
+    fn decode_struct() {
+        let contents = "
+            struct Foo {
+                x: U64 = 0
+                y: String = 1
+                optional z: S64 = 2
+                w: F64 = 3
+            }
+        ";
+
+        assert_eq!(
+            decode_with_schema(
+                contents,
+                "Foo",
+                &[0x05, 0x0b, 0x0f, 0x05, 0x68, 0x69, 0x15, 0x03, 0x19],
+            ),
+            "\
+struct Foo (9 bytes)
+  x (index 0): U64 = 5
+  y (index 1): String = \"hi\"
+  z (index 2, optional): S64 = -1
+  w (index 3): F64 = 0.0
+",
+        );
+    }
+
+    #[test]
+    fn decode_narrow_numeric_types() {
+        let contents = "
+            struct Foo {
+                a: U8 = 0
+                b: U32 = 1
+                c: S32 = 2
+                d: F32 = 3
+                e: [F32] = 4
+            }
+        ";
+
+        assert_eq!(
+            decode_with_schema(
+                contents,
+                "Foo",
+                &[
+                    0x05, 0xfe, 0x01, 0x0d, 0xf0, 0xef, 0xf7, 0xfb, 0x1d, 0x15, 0xf0, 0xef, 0xf7,
+                    0xfb, 0x1d, 0x1f, 0x09, 0x00, 0x00, 0xc0, 0x3f, 0x23, 0x00, 0x00, 0x00, 0x00,
+                    0x00, 0x00, 0x00, 0xc0,
+                ],
+            ),
+            "\
+struct Foo (30 bytes)
+  a (index 0): U8 = 255
+  b (index 1): U32 = 4294967295
+  c (index 2): S32 = -2147483648
+  d (index 3): F32 = 1.5
+  e (index 4): [F32] = [0.0, -2.0]
+",
+        );
+    }
+
+    #[test]
+    fn decode_narrow_out_of_range() {
+        let contents = "
+            struct Foo {
+                x: U8 = 0
+            }
+        ";
+
+        assert_eq!(
+            decode_with_schema(contents, "Foo", &[0x05, 0x02, 0x02]),
+            "\
+struct Foo (3 bytes)
+  x (index 0): U8 = 256 (out of range)
+",
+        );
+    }
+
+    #[test]
+    fn decode_struct_unrecognized_fields() {
+        let contents = "
+            struct Foo {
+                x: U64 = 0
+
+                deleted 1
+            }
+        ";
+
+        assert_eq!(
+            decode_with_schema(contents, "Foo", &[0x0f, 0x05, 0x68, 0x69, 0x05, 0x0b, 0x39]),
+            "\
+struct Foo (7 bytes)
+  unrecognized field (index 1, deleted, 2 bytes) skipped
+  x (index 0): U64 = 5
+  unrecognized field (index 7, 0 bytes) skipped
+",
+        );
+    }
+
+    #[test]
+    fn decode_struct_missing_required_field() {
+        let contents = "
+            struct Foo {
+                x: U64 = 0
+                asymmetric y: String = 1
+            }
+        ";
+
+        assert_eq!(
+            decode_with_schema(contents, "Foo", &[0x0f, 0x05, 0x68, 0x69]),
+            "\
+struct Foo (4 bytes)
+  y (index 1, asymmetric): String = \"hi\"
+  x (index 0): missing, but the field is required
+",
+        );
+    }
+
+    #[test]
+    fn decode_map() {
+        let contents = "
+            struct Foo {
+                x: {String: U64} = 0
+            }
+        ";
+
+        assert_eq!(
+            decode_with_schema(
+                contents,
+                "Foo",
+                &[0x07, 0x0d, 0x0b, 0x07, 0x03, 0x61, 0x0d, 0x03],
+            ),
+            "\
+struct Foo (8 bytes)
+  x (index 0): {String: U64} (1 entry)
+    [0]
+      key (index 0): String = \"a\"
+      value (index 1): U64 = 1
+",
+        );
+    }
+
+    #[test]
+    fn decode_choice_fallbacks() {
+        let contents = "
+            choice Foo {
+                optional a: String = 0
+                b: U64 = 1
+                c = 2
+            }
+        ";
+
+        assert_eq!(
+            decode_with_schema(contents, "Foo", &[0x07, 0x05, 0x68, 0x69, 0x11, 0x0d, 0x03],),
+            "\
+choice Foo (7 bytes)
+  a (index 0, optional): String = \"hi\" [fallback follows]
+  c (index 2): Unit
+  ignored fallback (2 bytes)
+    b (index 1): U64 = 1
+",
+        );
+    }
+
+    #[test]
+    fn decode_choice_missing_field() {
+        let contents = "
+            choice Foo {
+                optional a: Bool = 0
+                b = 1
+            }
+        ";
+
+        assert_eq!(
+            decode_with_schema(contents, "Foo", &[0x01]),
+            "\
+choice Foo (1 byte)
+  a (index 0, optional): Bool = false [fallback follows]
+  missing a required or asymmetric field to decode the choice
+",
+        );
+    }
+
+    #[test]
+    fn decode_arrays() {
+        let contents = "
+            struct Foo {
+                x: [Bar] = 0
+                y: [U64] = 1
+                z: [Unit] = 2
+            }
+
+            struct Bar {
+                asymmetric a: Bool = 0
+            }
+        ";
+
+        assert_eq!(
+            decode_with_schema(
+                contents,
+                "Foo",
+                &[0x07, 0x09, 0x05, 0x05, 0x03, 0x01, 0x0f, 0x05, 0x03, 0x05, 0x17, 0x03, 0x07],
+            ),
+            "\
+struct Foo (13 bytes)
+  x (index 0): [Bar] (2 elements)
+    [0]
+      a (index 0, asymmetric): Bool = true
+    [1]
+  y (index 1): [U64] = [1, 2]
+  z (index 2): [Unit] (3 elements)
+",
+        );
+    }
+
+    #[test]
+    fn decode_fixed_length() {
+        let contents = "
+            struct Foo {
+                x: [Bar; 2] = 0
+                y: [U64; 3] = 1
+                z: [Unit; 3] = 2
+                w: Bytes(2) = 3
+            }
+
+            struct Bar {
+                asymmetric a: Bool = 0
+            }
+        ";
+
+        assert_eq!(
+            decode_with_schema(
+                contents,
+                "Foo",
+                &[
+                    0x07, 0x09, 0x05, 0x05, 0x03, 0x01, 0x0f, 0x05, 0x03, 0x05, 0x17, 0x03, 0x07,
+                    0x1f, 0x05, 0xbe, 0xef,
+                ],
+            ),
+            "\
+struct Foo (17 bytes)
+  x (index 0): [Bar; 2] (2 elements)
+    [0]
+      a (index 0, asymmetric): Bool = true
+    [1]
+  y (index 1): [U64; 3] = [1, 2] (expected 3 elements)
+  z (index 2): [Unit; 3] (3 elements)
+  w (index 3): Bytes(2) = <be ef>
+",
+        );
+    }
+
+    #[test]
+    fn decode_imported_type() {
+        let foo_namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let foo_path = Path::new("foo.t").to_owned();
+        let foo_contents = "
+            import 'bar.t'
+        "
+        .to_owned();
+        let foo_tokens = tokenize(&foo_path, &foo_contents).unwrap();
+        let mut foo_schema = parse(&foo_path, &foo_contents, &foo_tokens).unwrap();
+        for import in foo_schema.imports.values_mut() {
+            import.namespace = Some(Namespace {
+                components: vec!["bar".into()],
+            });
+        }
+
+        let bar_namespace = Namespace {
+            components: vec!["bar".into()],
+        };
+        let bar_path = Path::new("bar.t").to_owned();
+        let bar_contents = "
+            struct Bar {
+                x: Bytes = 0
+            }
+        "
+        .to_owned();
+        let bar_tokens = tokenize(&bar_path, &bar_contents).unwrap();
+        let bar_schema = parse(&bar_path, &bar_contents, &bar_tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(foo_namespace.clone(), (foo_schema, foo_path, foo_contents));
+        schemas.insert(bar_namespace, (bar_schema, bar_path, bar_contents));
+
+        assert_same!(
+            decode(
+                &schemas,
+                &foo_namespace,
+                "bar.Bar",
+                &[0x07, 0x07, 0x00, 0x2a, 0xff]
+            ),
+            Ok("\
+struct bar.Bar (5 bytes)
+  x (index 0): Bytes = <00 2a ff>
+"
+            .to_owned(),),
+        );
+    }
+
+    #[test]
+    fn decode_truncated() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            struct Foo {
+                x: String = 0
+            }
+        "
+        .to_owned();
+
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace.clone(), (schema, path, contents));
+
+        assert_fails!(
+            decode(&schemas, &namespace, "Foo", &[0x07, 0x05, 0x68]).map_err(|error| vec![error]),
+            "Expected 2 bytes starting at byte `2`, but the input ends at byte `3`.",
+        );
+    }
+
+    #[test]
+    fn decode_newtype() {
+        let contents = "
+            newtype Id = U64
+            newtype Ids = [Id]
+
+            struct Foo {
+                x: Id = 0
+                y: Ids = 1
+            }
+        ";
+
+        assert_eq!(
+            decode_with_schema(contents, "Foo", &[0x05, 0x0b, 0x0f, 0x05, 0x03, 0x05]),
+            "\
+struct Foo (6 bytes)
+  x (index 0): Id = 5
+  y (index 1): Ids = [1, 2]
+",
+        );
+    }
+
+    #[test]
+    fn decode_unknown_type() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = String::new();
+
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace.clone(), (schema, path, contents));
+
+        assert_fails!(
+            decode(&schemas, &namespace, "Foo", &[]).map_err(|error| vec![error]),
+            "There is no type named `Foo` in `foo.t`.",
+        );
+    }
+}
@@ -0,0 +1,1133 @@
+use {
+    crate::{
+        count::count,
+        decoder::{find_declaration, resolve_custom_type, variant_name},
+        error::{listing, throw, Error, SourceRange},
+        format::CodeStr,
+        generate_rust::integer_encoded,
+        identifier::Identifier,
+        json::{self, Member, Value},
+        monomorphizer::monomorphize,
+        schema::{self, encoding_type},
+    },
+    std::{
+        collections::{BTreeMap, HashSet},
+        path::{Path, PathBuf},
+    },
+};
+
+// Keys of this form (e.g., `"#7"`) denote fields which aren't in the schema. Their values are
+// written verbatim, which is useful for testing how readers handle unrecognized fields.
+const UNRECOGNIZED_FIELD_PREFIX: char = '#';
+
+// This is the smallest `U64` field value which is encoded as a fixed-width 8-byte integer.
+const FIXED_WIDTH_THRESHOLD: u64 = 567_382_630_219_904;
+
+// This struct holds everything needed to report errors against the input document.
+struct Context<'a> {
+    schemas: &'a BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>,
+    input_path: &'a Path,
+    input_contents: &'a str,
+    errors: Vec<Error>,
+}
+
+impl Context<'_> {
+    // Record an error pointing to the given range of the input document.
+    fn error(&mut self, message: &str, source_range: SourceRange) {
+        self.errors.push(throw::<Error>(
+            message,
+            Some(self.input_path),
+            Some(&listing(self.input_contents, source_range)),
+            None,
+        ));
+    }
+}
+
+// Encode a message of the given type described by a JSON document. The type is named relative to
+// the root schema, e.g., `Foo` or `bar.Bar` for a type from an imported schema. The result is
+// exactly what the generated code would produce for the same message.
+pub fn encode(
+    schemas: &BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>,
+    root_namespace: &schema::Namespace,
+    type_name: &str,
+    input_path: &Path,
+    input_contents: &str,
+) -> Result<Vec<u8>, Vec<Error>> {
+    // Replace the generic declarations with their instantiations [ref:monomorphization]. The
+    // `unwrap` is safe since the schemas are assumed to be valid.
+    let schemas = &monomorphize(schemas).unwrap();
+
+    // Find the declaration of the requested type.
+    let (namespace, declaration) =
+        find_declaration(schemas, root_namespace, type_name).map_err(|error| vec![error])?;
+
+    // Parse the input document.
+    let value = json::parse(input_path, input_contents).map_err(|error| vec![error])?;
+
+    // Encode the message.
+    let mut context = Context {
+        schemas,
+        input_path,
+        input_contents,
+        errors: vec![],
+    };
+    let mut buffer = vec![];
+    encode_message(&mut context, &mut buffer, &namespace, declaration, &value);
+
+    if context.errors.is_empty() {
+        Ok(buffer)
+    } else {
+        Err(context.errors)
+    }
+}
+
+// Encode a struct or choice.
+#[allow(clippy::too_many_lines)]
+fn encode_message(
+    context: &mut Context,
+    buffer: &mut Vec<u8>,
+    namespace: &schema::Namespace,
+    declaration: &schema::Declaration,
+    value: &Value,
+) {
+    let json::Variant::Object(members) = &value.variant else {
+        context.error(
+            &format!(
+                "Expected an object for {} {}.",
+                variant_name(&declaration.variant),
+                declaration.name.code_str(),
+            ),
+            value.source_range,
+        );
+        return;
+    };
+
+    // Resolve the members to fields of the declaration or unrecognized indices.
+    let mut entries = vec![];
+    let mut seen_indices = HashSet::new();
+    for member in members {
+        if let Some(entry) = resolve_member(context, declaration, member) {
+            if seen_indices.insert(entry.index) {
+                entries.push(entry);
+            } else {
+                context.error(
+                    &format!(
+                        "Field index {} appears more than once.",
+                        entry.index.to_string().code_str(),
+                    ),
+                    member.key_source_range,
+                );
+            }
+        }
+    }
+
+    match declaration.variant {
+        schema::DeclarationVariant::Struct => {
+            // Required fields must be present. Asymmetric fields may be omitted here, which is
+            // useful for testing readers, even though generated code would always include them.
+            for field in &declaration.fields {
+                if matches!(field.rule, schema::Rule::Required)
+                    && !entries
+                        .iter()
+                        .any(|entry| entry.index == field.index as u64)
+                {
+                    context.error(
+                        &format!(
+                            "Missing required field {} of {}.",
+                            field.name.code_str(),
+                            declaration.name.code_str(),
+                        ),
+                        value.source_range,
+                    );
+                }
+            }
+
+            // Known fields are written in the order they're declared, as in the generated code.
+            // Unrecognized fields follow in the order they're given.
+            for field in &declaration.fields {
+                if let Some(entry) = entries
+                    .iter()
+                    .find(|entry| entry.index == field.index as u64)
+                {
+                    encode_entry(context, buffer, namespace, entry);
+                }
+            }
+
+            for entry in &entries {
+                if entry.field.is_none() {
+                    encode_entry(context, buffer, namespace, entry);
+                }
+            }
+        }
+        schema::DeclarationVariant::Choice => {
+            // The fields of a choice are written in the order they're given. Every field but the
+            // last is a fallback for the one before it, and the last must be required.
+            if members.is_empty() {
+                context.error(
+                    &format!(
+                        "Choice {} must have at least one field.",
+                        declaration.name.code_str(),
+                    ),
+                    value.source_range,
+                );
+            }
+
+            for (i, entry) in entries.iter().enumerate() {
+                let is_required = entry
+                    .field
+                    .is_some_and(|field| matches!(field.rule, schema::Rule::Required));
+
+                if i + 1 < entries.len() && is_required {
+                    context.error(
+                        &format!(
+                            "Field {} of {} is required, so it can't be followed by a fallback.",
+                            entry.name.code_str(),
+                            declaration.name.code_str(),
+                        ),
+                        entry.source_range,
+                    );
+                }
+
+                if i + 1 == entries.len() && !is_required {
+                    context.error(
+                        &format!(
+                            "The last field of choice {} must be a required field, since the \
+                                other fields need a fallback.",
+                            declaration.name.code_str(),
+                        ),
+                        entry.source_range,
+                    );
+                }
+
+                encode_entry(context, buffer, namespace, entry);
+            }
+        }
+        schema::DeclarationVariant::Newtype(_) => {
+            // Impossible due to [ref:messages_are_not_newtypes] and the fact that references to
+            // newtypes are resolved by [ref:monomorphization].
+            panic!()
+        }
+    }
+}
+
+// A member of an object which has been resolved against the schema
+struct Entry<'a> {
+    source_range: SourceRange,
+    name: &'a str,
+    index: u64,
+    field: Option<&'a schema::Field>, // `None` for unrecognized fields
+    value: &'a Value,
+}
+
+// Determine which field a member of an object refers to.
+fn resolve_member<'a>(
+    context: &mut Context,
+    declaration: &'a schema::Declaration,
+    member: &'a Member,
+) -> Option<Entry<'a>> {
+    if let Some(index) = member.key.strip_prefix(UNRECOGNIZED_FIELD_PREFIX) {
+        if let Ok(index) = index.parse::<u64>() {
+            return Some(Entry {
+                source_range: member.key_source_range,
+                name: &member.key,
+                index,
+                field: None,
+                value: &member.value,
+            });
+        }
+
+        context.error(
+            &format!(
+                "Invalid field index {}. Keys starting with {} must be followed by a field index.",
+                member.key.code_str(),
+                UNRECOGNIZED_FIELD_PREFIX.to_string().code_str(),
+            ),
+            member.key_source_range,
+        );
+
+        return None;
+    }
+
+    let name: Identifier = member.key.as_str().into();
+
+    if let Some(field) = declaration.fields.iter().find(|field| field.name == name) {
+        Some(Entry {
+            source_range: member.key_source_range,
+            name: &member.key,
+            index: field.index as u64,
+            field: Some(field),
+            value: &member.value,
+        })
+    } else {
+        context.error(
+            &format!(
+                "{} has no field named {}.",
+                declaration.name.code_str(),
+                member.key.code_str(),
+            ),
+            member.key_source_range,
+        );
+
+        None
+    }
+}
+
+// Encode a field header followed by the field value.
+fn encode_entry(
+    context: &mut Context,
+    buffer: &mut Vec<u8>,
+    namespace: &schema::Namespace,
+    entry: &Entry,
+) {
+    let (payload, integer_encoded) = if let Some(field) = entry.field {
+        let mut payload = vec![];
+        encode_value(
+            context,
+            &mut payload,
+            namespace,
+            &field.r#type.variant,
+            entry.value,
+            true,
+        );
+        (payload, integer_encoded(&field.r#type))
+    } else {
+        (decode_hex(context, entry.value).unwrap_or_default(), false)
+    };
+
+    match payload.len() {
+        0 => encode_varint(entry.index << 2_u32, buffer),
+        8 => encode_varint((entry.index << 2_u32) | 0b01, buffer),
+        size => {
+            if integer_encoded {
+                encode_varint((entry.index << 2_u32) | 0b10, buffer);
+            } else {
+                encode_varint((entry.index << 2_u32) | 0b11, buffer);
+                encode_varint(size as u64, buffer);
+            }
+        }
+    }
+
+    buffer.extend_from_slice(&payload);
+}
+
+// Encode a value of the given type.
+#[allow(clippy::too_many_lines)]
+fn encode_value(
+    context: &mut Context,
+    buffer: &mut Vec<u8>,
+    namespace: &schema::Namespace,
+    type_variant: &schema::TypeVariant,
+    value: &Value,
+    is_field: bool,
+) {
+    match (type_variant, &value.variant) {
+        (schema::TypeVariant::Array(inner_type, length), json::Variant::Array(elements)) => {
+            if let Some(length) = length.filter(|length| *length != elements.len()) {
+                context.error(
+                    &format!(
+                        "Expected {}, but found {}.",
+                        count(length, "element"),
+                        elements.len(),
+                    ),
+                    value.source_range,
+                );
+            }
+
+            match encoding_type(&inner_type.variant) {
+                schema::TypeVariant::Array(_, _)
+                | schema::TypeVariant::Bytes(_)
+                | schema::TypeVariant::Custom(_, _, _)
+                | schema::TypeVariant::Map(_, _)
+                | schema::TypeVariant::String => {
+                    // Each element is prefixed by its size.
+                    for element in elements {
+                        let mut element_buffer = vec![];
+                        encode_value(
+                            context,
+                            &mut element_buffer,
+                            namespace,
+                            &inner_type.variant,
+                            element,
+                            false,
+                        );
+                        encode_varint(element_buffer.len() as u64, buffer);
+                        buffer.extend_from_slice(&element_buffer);
+                    }
+                }
+                schema::TypeVariant::Bool
+                | schema::TypeVariant::F32
+                | schema::TypeVariant::F64
+                | schema::TypeVariant::S32
+                | schema::TypeVariant::S64
+                | schema::TypeVariant::U32
+                | schema::TypeVariant::U64
+                | schema::TypeVariant::U8 => {
+                    for element in elements {
+                        encode_value(
+                            context,
+                            buffer,
+                            namespace,
+                            &inner_type.variant,
+                            element,
+                            false,
+                        );
+                    }
+                }
+                schema::TypeVariant::Unit => {
+                    // Only the number of elements is encoded, but check them anyway.
+                    for element in elements {
+                        encode_value(
+                            context,
+                            buffer,
+                            namespace,
+                            &inner_type.variant,
+                            element,
+                            false,
+                        );
+                    }
+                    encode_u64(elements.len() as u64, buffer, is_field);
+                }
+                schema::TypeVariant::Newtype(_, _, _) => {
+                    // Impossible since `encoding_type` strips away newtypes.
+                    panic!()
+                }
+            }
+        }
+        (schema::TypeVariant::Map(key_type, value_type), json::Variant::Array(entries)) => {
+            // Each entry is prefixed by its size and encoded as a struct [ref:map_encoding].
+            let entry_declaration = schema::map_entry(key_type, value_type);
+            for entry in entries {
+                let mut entry_buffer = vec![];
+                encode_message(
+                    context,
+                    &mut entry_buffer,
+                    namespace,
+                    &entry_declaration,
+                    entry,
+                );
+                encode_varint(entry_buffer.len() as u64, buffer);
+                buffer.extend_from_slice(&entry_buffer);
+            }
+        }
+        (schema::TypeVariant::Bool, json::Variant::Bool(payload)) => {
+            encode_u64(u64::from(*payload), buffer, is_field);
+        }
+        (schema::TypeVariant::Bytes(length), json::Variant::String(_)) => {
+            if let Some(payload) = decode_hex(context, value) {
+                if let Some(length) = length.filter(|length| *length != payload.len()) {
+                    context.error(
+                        &format!(
+                            "Expected {}, but found {}.",
+                            count(length, "byte"),
+                            payload.len(),
+                        ),
+                        value.source_range,
+                    );
+                }
+
+                buffer.extend_from_slice(&payload);
+            }
+        }
+        (schema::TypeVariant::Custom(import, name, _), _) => {
+            let (type_namespace, declaration) =
+                resolve_custom_type(context.schemas, namespace, import.as_ref(), name);
+
+            encode_message(context, buffer, &type_namespace, declaration, value);
+        }
+        (schema::TypeVariant::Newtype(_, _, underlying_type), _) => {
+            // Newtypes are encoded like their underlying types [ref:newtype_encoding].
+            encode_value(
+                context,
+                buffer,
+                namespace,
+                &underlying_type.variant,
+                value,
+                is_field,
+            );
+        }
+        (schema::TypeVariant::F32, json::Variant::Number(literal)) => {
+            // The `unwrap` is safe since the JSON parser only accepts valid numbers. A literal which
+            // is too large for the type parses as an infinity, so that case is reported instead.
+            let payload = literal.parse::<f32>().unwrap();
+            if payload.is_finite() {
+                encode_f32(payload, buffer, is_field);
+            } else {
+                context.error(
+                    &format!(
+                        "Expected a finite 32-bit floating-point number for type {}.",
+                        "F32".code_str(),
+                    ),
+                    value.source_range,
+                );
+            }
+        }
+        (schema::TypeVariant::F32, json::Variant::String(string))
+            if matches!(string.as_str(), "NaN" | "Infinity" | "-Infinity") =>
+        {
+            // JSON has no literals for these values, so they're given as strings instead.
+            let payload = match string.as_str() {
+                "NaN" => f32::NAN,
+                "Infinity" => f32::INFINITY,
+                _ => f32::NEG_INFINITY,
+            };
+            encode_f32(payload, buffer, is_field);
+        }
+        (schema::TypeVariant::F64, json::Variant::Number(literal)) => {
+            // The `unwrap` is safe since the JSON parser only accepts valid numbers. A literal which
+            // is too large for the type parses as an infinity, so that case is reported instead.
+            let payload = literal.parse::<f64>().unwrap();
+            if payload.is_finite() {
+                encode_f64(payload, buffer, is_field);
+            } else {
+                context.error(
+                    &format!(
+                        "Expected a finite 64-bit floating-point number for type {}.",
+                        "F64".code_str(),
+                    ),
+                    value.source_range,
+                );
+            }
+        }
+        (schema::TypeVariant::F64, json::Variant::String(string))
+            if matches!(string.as_str(), "NaN" | "Infinity" | "-Infinity") =>
+        {
+            // JSON has no literals for these values, so they're given as strings instead.
+            let payload = match string.as_str() {
+                "NaN" => f64::NAN,
+                "Infinity" => f64::INFINITY,
+                _ => f64::NEG_INFINITY,
+            };
+            encode_f64(payload, buffer, is_field);
+        }
+        (schema::TypeVariant::S32, json::Variant::Number(literal)) => {
+            // `S32` has the same encoding as `S64` [ref:narrow_integer_encoding].
+            if let Ok(payload) = literal.parse::<i32>() {
+                encode_u64(zigzag_encode(i64::from(payload)), buffer, is_field);
+            } else {
+                context.error(
+                    &format!(
+                        "Expected a signed 32-bit integer for type {}.",
+                        "S32".code_str(),
+                    ),
+                    value.source_range,
+                );
+            }
+        }
+        (schema::TypeVariant::S64, json::Variant::Number(literal)) => {
+            if let Ok(payload) = literal.parse::<i64>() {
+                encode_u64(zigzag_encode(payload), buffer, is_field);
+            } else {
+                context.error(
+                    &format!(
+                        "Expected a signed 64-bit integer for type {}.",
+                        "S64".code_str(),
+                    ),
+                    value.source_range,
+                );
+            }
+        }
+        (schema::TypeVariant::String, json::Variant::String(payload)) => {
+            buffer.extend_from_slice(payload.as_bytes());
+        }
+        (schema::TypeVariant::U64, json::Variant::Number(literal)) => {
+            if let Ok(payload) = literal.parse::<u64>() {
+                encode_u64(payload, buffer, is_field);
+            } else {
+                context.error(
+                    &format!(
+                        "Expected an unsigned 64-bit integer for type {}.",
+                        "U64".code_str(),
+                    ),
+                    value.source_range,
+                );
+            }
+        }
+        (schema::TypeVariant::U32, json::Variant::Number(literal)) => {
+            // `U32` has the same encoding as `U64` [ref:narrow_integer_encoding].
+            if let Ok(payload) = literal.parse::<u32>() {
+                encode_u64(u64::from(payload), buffer, is_field);
+            } else {
+                context.error(
+                    &format!(
+                        "Expected an unsigned 32-bit integer for type {}.",
+                        "U32".code_str(),
+                    ),
+                    value.source_range,
+                );
+            }
+        }
+        (schema::TypeVariant::U8, json::Variant::Number(literal)) => {
+            // `U8` has the same encoding as `U64` [ref:narrow_integer_encoding].
+            if let Ok(payload) = literal.parse::<u8>() {
+                encode_u64(u64::from(payload), buffer, is_field);
+            } else {
+                context.error(
+                    &format!(
+                        "Expected an unsigned 8-bit integer for type {}.",
+                        "U8".code_str(),
+                    ),
+                    value.source_range,
+                );
+            }
+        }
+        (schema::TypeVariant::Unit, json::Variant::Null) => {}
+        (_, _) => {
+            context.error(
+                &format!(
+                    "Expected {} for type {}.",
+                    expected_json(type_variant),
+                    schema::Type {
+                        source_range: value.source_range,
+                        variant: type_variant.clone(),
+                    }
+                    .to_string()
+                    .code_str(),
+                ),
+                value.source_range,
+            );
+        }
+    }
+}
+
+// Describe the JSON representation of a type for error messages.
+fn expected_json(type_variant: &schema::TypeVariant) -> &'static str {
+    match type_variant {
+        schema::TypeVariant::Array(_, _) => "an array",
+        schema::TypeVariant::Map(_, _) => "an array of entries",
+        schema::TypeVariant::Bool => "a Boolean",
+        schema::TypeVariant::Bytes(_) => "a string of hexadecimal digits",
+        schema::TypeVariant::Custom(_, _, _) => "an object",
+        schema::TypeVariant::Newtype(_, _, underlying_type) => {
+            expected_json(&underlying_type.variant)
+        }
+        schema::TypeVariant::F32 | schema::TypeVariant::F64 => "a number",
+        schema::TypeVariant::S32
+        | schema::TypeVariant::S64
+        | schema::TypeVariant::U32
+        | schema::TypeVariant::U64
+        | schema::TypeVariant::U8 => "an integer",
+        schema::TypeVariant::String => "a string",
+        schema::TypeVariant::Unit => "null",
+    }
+}
+
+// Decode a string of hexadecimal digits (optionally separated by whitespace) into bytes.
+fn decode_hex(context: &mut Context, value: &Value) -> Option<Vec<u8>> {
+    if let json::Variant::String(string) = &value.variant {
+        let digits = string
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<Vec<_>>();
+
+        if digits.len() % 2 == 0 {
+            if let Some(bytes) = digits
+                .chunks(2)
+                .map(|pair| {
+                    u8::from_str_radix(&pair.iter().collect::<String>(), 16)
+                        .ok()
+                        .filter(|_| pair.iter().all(char::is_ascii_hexdigit))
+                })
+                .collect::<Option<Vec<_>>>()
+            {
+                return Some(bytes);
+            }
+        }
+    }
+
+    context.error(
+        "Expected a string of hexadecimal digits, e.g., \"00 2a ff\".",
+        value.source_range,
+    );
+
+    None
+}
+
+// Encode a `U64`, taking into account the special encoding used for field values.
+fn encode_u64(value: u64, buffer: &mut Vec<u8>, is_field: bool) {
+    if is_field {
+        match value {
+            0 => {}
+            1..FIXED_WIDTH_THRESHOLD => encode_varint(value, buffer),
+            _ => buffer.extend_from_slice(&value.to_le_bytes()),
+        }
+    } else {
+        encode_varint(value, buffer);
+    }
+}
+
+// Encode an `F32`, taking into account the special encoding used for field values.
+fn encode_f32(value: f32, buffer: &mut Vec<u8>, is_field: bool) {
+    if !is_field || value.to_bits() != 0_u32 {
+        buffer.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+// Encode an `F64`, taking into account the special encoding used for field values.
+fn encode_f64(value: f64, buffer: &mut Vec<u8>, is_field: bool) {
+    if !is_field || value.to_bits() != 0_u64 {
+        buffer.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+// Convert a signed integer into its unsigned ZigZag representation.
+fn zigzag_encode(value: i64) -> u64 {
+    ((value << 1_u32) ^ (value >> 63_u32)).cast_unsigned()
+}
+
+// Encode an unsigned variable-width integer.
+#[allow(clippy::cast_possible_truncation)]
+fn encode_varint(mut value: u64, buffer: &mut Vec<u8>) {
+    match value {
+        0_u64..=127_u64 => buffer.push(((value << 1_u32) as u8) | 0b0000_0001),
+        128_u64..=16_511_u64 => {
+            value -= 128_u64;
+            buffer.push(((value << 2_u32) as u8) | 0b0000_0010);
+            buffer.extend_from_slice(&(value >> 6_u32).to_le_bytes()[0..1]);
+        }
+        16_512_u64..=2_113_663_u64 => {
+            value -= 16_512_u64;
+            buffer.push(((value << 3_u32) as u8) | 0b0000_0100);
+            buffer.extend_from_slice(&(value >> 5_u32).to_le_bytes()[0..2]);
+        }
+        2_113_664_u64..=270_549_119_u64 => {
+            value -= 2_113_664_u64;
+            buffer.push(((value << 4_u32) as u8) | 0b0000_1000);
+            buffer.extend_from_slice(&(value >> 4_u32).to_le_bytes()[0..3]);
+        }
+        270_549_120_u64..=34_630_287_487_u64 => {
+            value -= 270_549_120_u64;
+            buffer.push(((value << 5_u32) as u8) | 0b0001_0000);
+            buffer.extend_from_slice(&(value >> 3_u32).to_le_bytes()[0..4]);
+        }
+        34_630_287_488_u64..=4_432_676_798_591_u64 => {
+            value -= 34_630_287_488_u64;
+            buffer.push(((value << 6_u32) as u8) | 0b0010_0000);
+            buffer.extend_from_slice(&(value >> 2_u32).to_le_bytes()[0..5]);
+        }
+        4_432_676_798_592_u64..=567_382_630_219_903_u64 => {
+            value -= 4_432_676_798_592_u64;
+            buffer.push(((value << 7_u32) as u8) | 0b0100_0000);
+            buffer.extend_from_slice(&(value >> 1_u32).to_le_bytes()[0..6]);
+        }
+        567_382_630_219_904_u64..=72_624_976_668_147_839_u64 => {
+            value -= 567_382_630_219_904_u64;
+            buffer.push(0b1000_0000);
+            buffer.extend_from_slice(&value.to_le_bytes()[0..7]);
+        }
+        72_624_976_668_147_840_u64..=18_446_744_073_709_551_615_u64 => {
+            value -= 72_624_976_668_147_840_u64;
+            buffer.push(0b0000_0000);
+            buffer.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::{
+            assert_fails, assert_same, encoder::encode, error::Error, parser::parse,
+            schema::Namespace, tokenizer::tokenize, validator::validate,
+        },
+        std::{collections::BTreeMap, fmt::Write, path::Path},
+    };
+
+    fn encode_with_schema(
+        contents: &str,
+        type_name: &str,
+        input: &str,
+    ) -> Result<Vec<u8>, Vec<Error>> {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+
+        let tokens = tokenize(&path, contents).unwrap();
+        let schema = parse(&path, contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace.clone(), (schema, path, contents.to_owned()));
+        validate(&schemas).unwrap();
+
+        encode(
+            &schemas,
+            &namespace,
+            type_name,
+            Path::new("foo.json"),
+            input,
+        )
+    }
+
+    #[test]
+    fn encode_struct() {
+        let contents = "
+            struct Foo {
+                x: U64 = 0
+                y: String = 1
+                optional z: S64 = 2
+                w: F64 = 3
+            }
+        ";
+
+        assert_same!(
+            encode_with_schema(contents, "Foo", r#"{"w": 0, "z": -1, "y": "hi", "x": 5}"#),
+            Ok(vec![0x05, 0x0b, 0x0f, 0x05, 0x68, 0x69, 0x15, 0x03, 0x19]),
+        );
+    }
+
+    #[test]
+    fn encode_fixed_width_integer() {
+        let contents = "
+            struct Foo {
+                x: U64 = 0
+            }
+        ";
+
+        assert_same!(
+            encode_with_schema(contents, "Foo", r#"{"x": 18446744073709551615}"#),
+            Ok(vec![0x03, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+        );
+    }
+
+    #[test]
+    fn encode_narrow_numeric_types() {
+        let contents = "
+            struct Foo {
+                a: U8 = 0
+                b: U32 = 1
+                c: S32 = 2
+                d: F32 = 3
+                e: [F32] = 4
+            }
+        ";
+
+        assert_same!(
+            encode_with_schema(
+                contents,
+                "Foo",
+                r#"{"a": 255, "b": 4294967295, "c": -2147483648, "d": 1.5, "e": [0, -2]}"#,
+            ),
+            Ok(vec![
+                0x05, 0xfe, 0x01, 0x0d, 0xf0, 0xef, 0xf7, 0xfb, 0x1d, 0x15, 0xf0, 0xef, 0xf7, 0xfb,
+                0x1d, 0x1f, 0x09, 0x00, 0x00, 0xc0, 0x3f, 0x23, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0xc0,
+            ]),
+        );
+    }
+
+    #[test]
+    fn encode_arrays() {
+        let contents = "
+            struct Foo {
+                x: [Bar] = 0
+                y: [U64] = 1
+                z: [Unit] = 2
+            }
+
+            struct Bar {
+                asymmetric a: Bool = 0
+            }
+        ";
+
+        assert_same!(
+            encode_with_schema(
+                contents,
+                "Foo",
+                r#"{"x": [{"a": true}, {}], "y": [1, 2], "z": [null, null, null]}"#,
+            ),
+            Ok(vec![
+                0x07, 0x09, 0x05, 0x05, 0x03, 0x01, 0x0f, 0x05, 0x03, 0x05, 0x17, 0x03, 0x07,
+            ]),
+        );
+    }
+
+    #[test]
+    fn encode_map() {
+        let contents = "
+            struct Foo {
+                x: {String: U64} = 0
+            }
+        ";
+
+        assert_same!(
+            encode_with_schema(contents, "Foo", r#"{"x": [{"key": "a", "value": 1}]}"#),
+            Ok(vec![0x07, 0x0d, 0x0b, 0x07, 0x03, 0x61, 0x0d, 0x03]),
+        );
+    }
+
+    #[test]
+    fn encode_map_same_as_entries() {
+        let map_contents = "
+            struct Foo {
+                x: {String: [Bool]} = 0
+            }
+        ";
+        let entries_contents = "
+            struct Foo {
+                x: [Entry] = 0
+            }
+
+            struct Entry {
+                key: String = 0
+                value: [Bool] = 1
+            }
+        ";
+        let input = r#"{"x": [{"key": "a", "value": [true]}, {"key": "", "value": []}]}"#;
+
+        assert_same!(
+            encode_with_schema(map_contents, "Foo", input),
+            encode_with_schema(entries_contents, "Foo", input),
+        );
+    }
+
+    #[test]
+    fn encode_fixed_length_same_as_variable_length() {
+        let fixed_contents = "
+            struct Foo {
+                x: [[U64; 2]; 3] = 0
+                y: Bytes(2) = 1
+            }
+        ";
+        let variable_contents = "
+            struct Foo {
+                x: [[U64]] = 0
+                y: Bytes = 1
+            }
+        ";
+        let input = r#"{"x": [[1, 2], [3, 4], [5, 6]], "y": "beef"}"#;
+
+        assert_same!(
+            encode_with_schema(fixed_contents, "Foo", input),
+            encode_with_schema(variable_contents, "Foo", input),
+        );
+    }
+
+    #[test]
+    fn encode_wrong_length() {
+        let contents = "
+            struct Foo {
+                x: [U64; 3] = 0
+            }
+        ";
+
+        assert_fails!(
+            encode_with_schema(contents, "Foo", r#"{"x": [1, 2]}"#),
+            "Expected 3 elements, but found 2.",
+        );
+    }
+
+    #[test]
+    fn encode_wrong_length_bytes() {
+        let contents = "
+            struct Foo {
+                x: Bytes(4) = 0
+            }
+        ";
+
+        assert_fails!(
+            encode_with_schema(contents, "Foo", r#"{"x": "beef"}"#),
+            "Expected 4 bytes, but found 2.",
+        );
+    }
+
+    #[test]
+    fn encode_newtype_same_as_underlying_type() {
+        let newtype_contents = "
+            newtype Id = U64
+            newtype Ids = [Id]
+
+            struct Foo {
+                x: Id = 0
+                y: Ids = 1
+            }
+        ";
+        let underlying_contents = "
+            struct Foo {
+                x: U64 = 0
+                y: [U64] = 1
+            }
+        ";
+        let input = r#"{"x": 5, "y": [1, 2]}"#;
+
+        assert_same!(
+            encode_with_schema(newtype_contents, "Foo", input),
+            encode_with_schema(underlying_contents, "Foo", input),
+        );
+    }
+
+    #[test]
+    fn encode_newtype_message() {
+        let contents = "
+            newtype Id = U64
+        ";
+
+        assert_fails!(
+            encode_with_schema(contents, "Id", "5"),
+            "`Id` is a newtype. Only structs and choices can be encoded or decoded.",
+        );
+    }
+
+    #[test]
+    fn encode_unrecognized_fields() {
+        let contents = "
+            struct Foo {
+                x: U64 = 0
+            }
+        ";
+
+        assert_same!(
+            encode_with_schema(contents, "Foo", r##"{"#1": "68 69", "x": 5, "#7": ""}"##),
+            Ok(vec![0x05, 0x0b, 0x0f, 0x05, 0x68, 0x69, 0x39]),
+        );
+    }
+
+    #[test]
+    fn encode_choice_fallbacks() {
+        let contents = "
+            choice Foo {
+                optional a: String = 0
+                b: U64 = 1
+                c = 2
+            }
+        ";
+
+        assert_same!(
+            encode_with_schema(contents, "Foo", r#"{"a": "hi", "c": null}"#),
+            Ok(vec![0x07, 0x05, 0x68, 0x69, 0x11]),
+        );
+    }
+
+    #[test]
+    fn encode_choice_missing_required_field() {
+        let contents = "
+            choice Foo {
+                optional a: String = 0
+                b: U64 = 1
+            }
+        ";
+
+        assert_fails!(
+            encode_with_schema(contents, "Foo", r#"{"a": "hi"}"#),
+            "The last field of choice `Foo` must be a required field",
+        );
+    }
+
+    #[test]
+    fn encode_choice_required_field_with_fallback() {
+        let contents = "
+            choice Foo {
+                a: String = 0
+                b: U64 = 1
+            }
+        ";
+
+        assert_fails!(
+            encode_with_schema(contents, "Foo", r#"{"a": "hi", "b": 1}"#),
+            "Field `a` of `Foo` is required, so it can't be followed by a fallback.",
+        );
+    }
+
+    #[test]
+    fn encode_missing_required_field() {
+        let contents = "
+            struct Foo {
+                x: U64 = 0
+                asymmetric y: U64 = 1
+            }
+        ";
+
+        assert_fails!(
+            encode_with_schema(contents, "Foo", "{}"),
+            "Missing required field `x` of `Foo`.",
+        );
+    }
+
+    #[test]
+    fn encode_unknown_field() {
+        let contents = "
+            struct Foo {
+            }
+        ";
+
+        assert_fails!(
+            encode_with_schema(contents, "Foo", r#"{"x": 1}"#),
+            "`Foo` has no field named `x`.",
+        );
+    }
+
+    #[test]
+    fn encode_type_mismatch() {
+        let contents = "
+            struct Foo {
+                x: [U64] = 0
+            }
+        ";
+
+        assert_fails!(
+            encode_with_schema(contents, "Foo", r#"{"x": [1, "2"]}"#),
+            "Expected an integer for type `U64`.",
+        );
+    }
+
+    #[test]
+    fn encode_out_of_range() {
+        let contents = "
+            struct Foo {
+                x: U64 = 0
+            }
+        ";
+
+        assert_fails!(
+            encode_with_schema(contents, "Foo", r#"{"x": -1}"#),
+            "Expected an unsigned 64-bit integer for type `U64`.",
+        );
+    }
+
+    #[test]
+    fn encode_narrow_out_of_range() {
+        let contents = "
+            struct Foo {
+                x: U8 = 0
+            }
+        ";
+
+        assert_fails!(
+            encode_with_schema(contents, "Foo", r#"{"x": 256}"#),
+            "Expected an unsigned 8-bit integer for type `U8`.",
+        );
+    }
+
+    #[test]
+    fn encode_float_out_of_range() {
+        let contents = "
+            struct Foo {
+                x: F32 = 0
+                y: F64 = 1
+            }
+        ";
+
+        assert_fails!(
+            encode_with_schema(contents, "Foo", r#"{"x": 1e300, "y": 0}"#),
+            "Expected a finite 32-bit floating-point number for type `F32`.",
+        );
+
+        assert_fails!(
+            encode_with_schema(contents, "Foo", r#"{"x": 0, "y": -1e999}"#),
+            "Expected a finite 64-bit floating-point number for type `F64`.",
+        );
+    }
+
+    #[test]
+    fn encode_invalid_hex() {
+        let contents = "
+            struct Foo {
+                x: Bytes = 0
+            }
+        ";
+
+        assert_fails!(
+            encode_with_schema(contents, "Foo", r#"{"x": "abc"}"#),
+            "Expected a string of hexadecimal digits",
+        );
+    }
+}
@@ -1,20 +1,39 @@
 use {
-    crate::format::CodeStr,
-    colored::{control::SHOULD_COLORIZE, Colorize},
+    crate::format::{colors_enabled, paint, CodeStr},
+    colored::Colorize,
     pad::{Alignment, PadStr},
     std::{
         cmp::{max, min},
         error, fmt,
-        path::Path,
-        rc::Rc,
+        path::{Path, PathBuf},
+        sync::Arc,
     },
 };
 
-// This is the primary error type we'll be using everywhere.
+// This is the primary error type we'll be using everywhere. The `message` is the full
+// human-readable report (including the source path and listing, if any), whereas the remaining
+// fields describe the error in a structured way for programmatic consumers.
 #[derive(Clone, Debug)]
 pub struct Error {
     pub message: String,
-    pub reason: Option<Rc<dyn error::Error>>,
+    pub description: String,
+    pub source_path: Option<PathBuf>,
+    pub source_range: Option<SourceRange>,
+    pub reason: Option<Arc<dyn error::Error + Send + Sync>>,
+}
+
+impl Error {
+    // Construct an error which has no structured details beyond its message.
+    #[must_use]
+    pub fn from_message(message: String) -> Self {
+        Self {
+            description: message.clone(),
+            message,
+            source_path: None,
+            source_range: None,
+            reason: None,
+        }
+    }
 }
 
 impl fmt::Display for Error {
@@ -24,7 +43,7 @@ impl fmt::Display for Error {
                 f,
                 "{}\n\n{} {}",
                 self.message,
-                "Reason:".blue().bold(),
+                paint("Reason:".blue().bold()),
                 reason,
             )
         } else {
@@ -34,57 +53,43 @@ impl fmt::Display for Error {
 }
 
 impl error::Error for Error {
-    fn source<'a>(&'a self) -> Option<&(dyn error::Error + 'static)> {
-        self.reason.as_deref()
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.reason
+            .as_deref()
+            .map(|reason| reason as &(dyn error::Error + 'static))
     }
 }
 
 // This function constructs a nicely formatted error.
-pub fn throw<T: error::Error + 'static>(
+pub fn throw<T: error::Error + Send + Sync + 'static>(
     message: &str,
     source_path: Option<&Path>,
-    listing: Option<&str>,
+    listing: Option<&Listing>,
     reason: Option<T>,
 ) -> Error {
+    let prefix = paint("[Error]".red().bold());
+
+    let header = if let Some(path) = source_path {
+        format!(
+            "{} {} {}",
+            prefix,
+            paint(format!("[{}]", path.to_string_lossy().code_str()).magenta()),
+            message,
+        )
+    } else {
+        format!("{prefix} {message}")
+    };
+
     #[allow(clippy::option_map_or_none)]
     Error {
-        message: if let Some(path) = source_path {
-            if let Some(listing) = listing {
-                if listing.is_empty() {
-                    format!(
-                        "{} {} {}",
-                        "[Error]".red().bold(),
-                        format!("[{}]", path.to_string_lossy().code_str()).magenta(),
-                        message,
-                    )
-                } else {
-                    format!(
-                        "{} {} {}\n\n{}",
-                        "[Error]".red().bold(),
-                        format!("[{}]", path.to_string_lossy().code_str()).magenta(),
-                        message,
-                        listing,
-                    )
-                }
-            } else {
-                format!(
-                    "{} {} {}",
-                    "[Error]".red().bold(),
-                    format!("[{}]", path.to_string_lossy().code_str()).magenta(),
-                    message,
-                )
-            }
-        } else if let Some(listing) = listing {
-            if listing.is_empty() {
-                format!("{} {}", "[Error]".red().bold(), message)
-            } else {
-                format!("{} {}\n\n{}", "[Error]".red().bold(), message, listing)
-            }
-        } else {
-            format!("{} {}", "[Error]".red().bold(), message)
+        message: match listing {
+            Some(listing) if !listing.text.is_empty() => format!("{header}\n\n{}", listing.text),
+            _ => header,
         },
-
-        reason: reason.map_or(None, |reason| Some(Rc::new(reason))),
+        description: message.to_owned(),
+        source_path: source_path.map(Path::to_owned),
+        source_range: listing.map(|listing| listing.source_range),
+        reason: reason.map_or(None, |reason| Some(Arc::new(reason))),
     }
 }
 
@@ -96,9 +101,17 @@ pub struct SourceRange {
     pub end: usize,   // Exclusive
 }
 
+// A rendered excerpt of a source file, along with the range it highlights
+#[derive(Clone, Debug)]
+pub struct Listing {
+    pub source_range: SourceRange,
+    pub text: String,
+}
+
 // This function renders the relevant lines of a source file given the source file contents and a
 // range. The range is inclusive on the left and exclusive on the right.
-pub fn listing(source_contents: &str, source_range: SourceRange) -> String {
+#[must_use]
+pub fn listing(source_contents: &str, source_range: SourceRange) -> Listing {
     // Remember the relevant lines and the position of the start of the next line.
     let mut lines = vec![];
     let mut pos = 0_usize;
@@ -155,23 +168,25 @@ pub fn listing(source_contents: &str, source_range: SourceRange) -> String {
     });
 
     // Determine whether the output will be colorized.
-    let colorized = SHOULD_COLORIZE.should_colorize();
+    let colorized = colors_enabled();
 
     // Render the code listing with line numbers.
-    lines
+    let text = lines
         .iter()
         .enumerate()
         .map(|(i, (line_number, line, section_start, section_end))| {
             format!(
                 "{}{}{}{}{}",
-                format!(
-                    "{} \u{2502} ",
-                    line_number.pad(gutter_width, ' ', Alignment::Right, false),
-                )
-                .blue()
-                .bold(),
+                paint(
+                    format!(
+                        "{} \u{2502} ",
+                        line_number.pad(gutter_width, ' ', Alignment::Right, false),
+                    )
+                    .blue()
+                    .bold()
+                ),
                 &line[..*section_start],
-                &line[*section_start..*section_end].red(),
+                paint(line[*section_start..*section_end].red()),
                 &line[*section_end..],
                 if colorized {
                     String::new()
@@ -202,7 +217,9 @@ pub fn listing(source_contents: &str, source_range: SourceRange) -> String {
             )
         })
         .collect::<Vec<_>>()
-        .join("\n")
+        .join("\n");
+
+    Listing { source_range, text }
 }
 
 #[cfg(test)]
@@ -210,20 +227,29 @@ mod tests {
     use {
         crate::{
             assert_same,
-            error::{listing, throw, Error, SourceRange},
+            error::{listing, throw, Error, Listing, SourceRange},
+        },
+        std::{
+            error,
+            path::{Path, PathBuf},
+            sync::Arc,
+            thread,
         },
-        std::{path::Path, rc::Rc},
     };
 
+    fn happened_here() -> Listing {
+        Listing {
+            source_range: SourceRange { start: 3, end: 7 },
+            text: "It happened here.".to_owned(),
+        }
+    }
+
     #[test]
     fn error_no_reason_display() {
         assert_eq!(
             format!(
                 "{}",
-                Error {
-                    message: "Something went wrong.".to_owned(),
-                    reason: None,
-                },
+                Error::from_message("Something went wrong.".to_owned()),
             ),
             "Something went wrong.",
         );
@@ -235,11 +261,10 @@ mod tests {
             format!(
                 "{}",
                 Error {
-                    message: "Something went wrong.".to_owned(),
-                    reason: Some(Rc::new(Error {
-                        message: "Something deeper went wrong.".to_owned(),
-                        reason: None,
-                    })),
+                    reason: Some(Arc::new(Error::from_message(
+                        "Something deeper went wrong.".to_owned(),
+                    ))),
+                    ..Error::from_message("Something went wrong.".to_owned())
                 },
             ),
             "\
@@ -250,12 +275,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn error_crosses_threads() {
+        let error = throw(
+            "Something went wrong.",
+            None,
+            None,
+            Some(Error::from_message(
+                "Something deeper went wrong.".to_owned(),
+            )),
+        );
+
+        let error = thread::spawn(move || error).join().unwrap();
+
+        assert_eq!(
+            error::Error::source(&error).unwrap().to_string(),
+            "Something deeper went wrong.",
+        );
+    }
+
     #[test]
     fn throw_no_source_path_listing_reason() {
         assert_same!(
             throw::<Error>("An error occurred.", None, None, None),
             Error {
                 message: "[Error] An error occurred.".to_owned(),
+                description: "An error occurred.".to_owned(),
+                source_path: None,
+                source_range: None,
                 reason: None,
             },
         );
@@ -267,6 +314,9 @@ mod tests {
             throw::<Error>("An error occurred.", Some(Path::new("foo")), None, None),
             Error {
                 message: "[Error] [`foo`] An error occurred.".to_owned(),
+                description: "An error occurred.".to_owned(),
+                source_path: Some(PathBuf::from("foo")),
+                source_range: None,
                 reason: None,
             },
         );
@@ -275,7 +325,7 @@ mod tests {
     #[test]
     fn throw_with_listing_no_source_path_reason() {
         assert_same!(
-            throw::<Error>("An error occurred.", None, Some("It happened here."), None),
+            throw::<Error>("An error occurred.", None, Some(&happened_here()), None),
             Error {
                 message: "\
                     [Error] An error occurred.\n\
@@ -283,6 +333,9 @@ mod tests {
                     It happened here.\
                 "
                 .to_owned(),
+                description: "An error occurred.".to_owned(),
+                source_path: None,
+                source_range: Some(SourceRange { start: 3, end: 7 }),
                 reason: None,
             },
         );
@@ -296,7 +349,10 @@ mod tests {
             throw::<Error>("An error occurred.", None, None, Some(reason.clone())),
             Error {
                 message: "[Error] An error occurred.".to_owned(),
-                reason: Some(Rc::new(reason)),
+                description: "An error occurred.".to_owned(),
+                source_path: None,
+                source_range: None,
+                reason: Some(Arc::new(reason)),
             },
         );
     }
@@ -307,7 +363,7 @@ mod tests {
             throw::<Error>(
                 "An error occurred.",
                 Some(Path::new("foo")),
-                Some("It happened here."),
+                Some(&happened_here()),
                 None,
             ),
             Error {
@@ -317,6 +373,9 @@ mod tests {
                     It happened here.\
                 "
                 .to_owned(),
+                description: "An error occurred.".to_owned(),
+                source_path: Some(PathBuf::from("foo")),
+                source_range: Some(SourceRange { start: 3, end: 7 }),
                 reason: None,
             },
         );
@@ -330,7 +389,7 @@ mod tests {
             throw::<Error>(
                 "An error occurred.",
                 None,
-                Some("It happened here."),
+                Some(&happened_here()),
                 Some(reason.clone()),
             ),
             Error {
@@ -340,7 +399,10 @@ mod tests {
                     It happened here.\
                 "
                 .to_owned(),
-                reason: Some(Rc::new(reason)),
+                description: "An error occurred.".to_owned(),
+                source_path: None,
+                source_range: Some(SourceRange { start: 3, end: 7 }),
+                reason: Some(Arc::new(reason)),
             },
         );
     }
@@ -358,7 +420,10 @@ mod tests {
             ),
             Error {
                 message: "[Error] [`foo`] An error occurred.".to_owned(),
-                reason: Some(Rc::new(reason)),
+                description: "An error occurred.".to_owned(),
+                source_path: Some(PathBuf::from("foo")),
+                source_range: None,
+                reason: Some(Arc::new(reason)),
             },
         );
     }
@@ -371,7 +436,7 @@ mod tests {
             throw::<Error>(
                 "An error occurred.",
                 Some(Path::new("foo")),
-                Some("It happened here."),
+                Some(&happened_here()),
                 Some(reason.clone()),
             ),
             Error {
@@ -381,20 +446,23 @@ mod tests {
                     It happened here.\
                 "
                 .to_owned(),
-                reason: Some(Rc::new(reason)),
+                description: "An error occurred.".to_owned(),
+                source_path: Some(PathBuf::from("foo")),
+                source_range: Some(SourceRange { start: 3, end: 7 }),
+                reason: Some(Arc::new(reason)),
             },
         );
     }
 
     #[test]
     fn listing_empty() {
-        assert_eq!(listing("", SourceRange { start: 0, end: 0 }), "");
+        assert_eq!(listing("", SourceRange { start: 0, end: 0 }).text, "");
     }
 
     #[test]
     fn listing_single_line_full_range() {
         assert_eq!(
-            listing("foo bar", SourceRange { start: 0, end: 7 }),
+            listing("foo bar", SourceRange { start: 0, end: 7 }).text,
             "1 \u{2502} foo bar\n    \u{203e}\u{203e}\u{203e}\u{203e}\u{203e}\u{203e}\u{203e}",
         );
     }
@@ -402,7 +470,7 @@ mod tests {
     #[test]
     fn listing_single_line_partial_range() {
         assert_eq!(
-            listing("foo bar", SourceRange { start: 1, end: 6 }),
+            listing("foo bar", SourceRange { start: 1, end: 6 }).text,
             "1 \u{2502} foo bar\n     \u{203e}\u{203e}\u{203e}\u{203e}\u{203e}",
         );
     }
@@ -410,7 +478,7 @@ mod tests {
     #[test]
     fn listing_multiple_lines_full_range() {
         assert_eq!(
-            listing("foo\nbar\nbaz\nqux", SourceRange { start: 0, end: 15 }),
+            listing("foo\nbar\nbaz\nqux", SourceRange { start: 0, end: 15 }).text,
             "1 \u{2502} foo\n  \u{250a} \u{203e}\u{203e}\u{203e}\n2 \u{2502} bar\n  \u{250a} \
                 \u{203e}\u{203e}\u{203e}\n3 \u{2502} baz\n  \u{250a} \u{203e}\u{203e}\u{203e}\n4 \
                 \u{2502} qux\n    \u{203e}\u{203e}\u{203e}",
@@ -420,7 +488,7 @@ mod tests {
     #[test]
     fn listing_multiple_lines_partial_range() {
         assert_eq!(
-            listing("foo\nbar\nbaz\nqux", SourceRange { start: 5, end: 9 }),
+            listing("foo\nbar\nbaz\nqux", SourceRange { start: 5, end: 9 }).text,
             "2 \u{2502} bar\n  \u{250a}  \u{203e}\u{203e}\n3 \u{2502} baz\n    \u{203e}",
         );
     }
@@ -431,7 +499,8 @@ mod tests {
             listing(
                 "foo\nbar\nbaz\nqux\nfoo\nbar\nbaz\nqux\nfoo\nbar\nbaz\nqux",
                 SourceRange { start: 33, end: 42 },
-            ),
+            )
+            .text,
             " 9 \u{2502} foo\n   \u{250a}  \u{203e}\u{203e}\n10 \u{2502} bar\n   \u{250a} \
                 \u{203e}\u{203e}\u{203e}\n11 \u{2502} baz\n     \u{203e}\u{203e}",
         );
@@ -1,9 +1,10 @@
 use crate::error::Error;
 
 // Merge a list of errors into a single one.
+#[must_use]
 pub fn merge_errors(errors: &[Error]) -> Error {
-    Error {
-        message: errors
+    Error::from_message(
+        errors
             .iter()
             .fold(String::new(), |acc, error| {
                 format!(
@@ -13,7 +14,7 @@ pub fn merge_errors(errors: &[Error]) -> Error {
                     // already visually look like an empty line. See [ref:overline_u203e].
                     if acc
                         .split('\n')
-                        .last()
+                        .next_back()
                         .unwrap() // Safe since `split` always results in at least one item
                         .chars()
                         .all(|c| c == ' ' || c == '\u{203e}')
@@ -27,8 +28,7 @@ pub fn merge_errors(errors: &[Error]) -> Error {
             })
             .trim()
             .to_owned(),
-        reason: None,
-    }
+    )
 }
 
 #[cfg(test)]
@@ -45,10 +45,7 @@ mod tests {
         assert_eq!(
             format!(
                 "{}",
-                merge_errors(&[Error {
-                    message: "Something went wrong.".to_owned(),
-                    reason: None,
-                }]),
+                merge_errors(&[Error::from_message("Something went wrong.".to_owned())]),
             ),
             "Something went wrong.",
         );
@@ -60,18 +57,9 @@ mod tests {
             format!(
                 "{}",
                 merge_errors(&[
-                    Error {
-                        message: "Something went kinda wrong.".to_owned(),
-                        reason: None,
-                    },
-                    Error {
-                        message: "Something went sorta wrong.".to_owned(),
-                        reason: None,
-                    },
-                    Error {
-                        message: "Something went very wrong.".to_owned(),
-                        reason: None,
-                    },
+                    Error::from_message("Something went kinda wrong.".to_owned()),
+                    Error::from_message("Something went sorta wrong.".to_owned()),
+                    Error::from_message("Something went very wrong.".to_owned()),
                 ]),
             ),
             "\
@@ -90,18 +78,14 @@ Something went very wrong.\
             format!(
                 "{}",
                 merge_errors(&[
-                    Error {
-                        message: "1 \u{2502} foo\n  \u{250a} \u{203e}\u{203e}\u{203e}\n2 \u{2502} \
-                                bar\n  \u{250a} \u{203e}\u{203e}\u{203e}\n3 \u{2502} baz\n  \
-                                \u{250a} \u{203e}\u{203e}\u{203e}\n4 \u{2502} qux\n    \u{203e}\
-                                \u{203e}\u{203e}"
+                    Error::from_message(
+                        "1 \u{2502} foo\n  \u{250a} \u{203e}\u{203e}\u{203e}\n2 \u{2502} \
+                            bar\n  \u{250a} \u{203e}\u{203e}\u{203e}\n3 \u{2502} baz\n  \
+                            \u{250a} \u{203e}\u{203e}\u{203e}\n4 \u{2502} qux\n    \u{203e}\
+                            \u{203e}\u{203e}"
                             .to_owned(),
-                        reason: None,
-                    },
-                    Error {
-                        message: "Something went sorta wrong.".to_owned(),
-                        reason: None,
-                    },
+                    ),
+                    Error::from_message("Something went sorta wrong.".to_owned()),
                 ]),
             ),
             "\
@@ -1,8 +1,38 @@
-use colored::{control::SHOULD_COLORIZE, ColoredString, Colorize};
+use {
+    colored::{control::SHOULD_COLORIZE, ColoredString, Colorize},
+    std::sync::atomic::{AtomicBool, Ordering},
+};
+
+// Colors are a presentation concern of the command-line interface. Library consumers get plain
+// text (e.g., in error messages) unless they opt in by calling `enable_colors`.
+static COLORS_ENABLED: AtomicBool = AtomicBool::new(false);
+
+// Allow colored output, subject to the usual environment checks (e.g., `NO_COLOR`).
+pub fn enable_colors() {
+    COLORS_ENABLED.store(true, Ordering::Relaxed);
+}
+
+// Determine whether text should be rendered with colors.
+#[must_use]
+pub(crate) fn colors_enabled() -> bool {
+    COLORS_ENABLED.load(Ordering::Relaxed) && SHOULD_COLORIZE.should_colorize()
+}
+
+// Strip the styling from a colored string unless colors are enabled.
+#[must_use]
+pub(crate) fn paint(text: ColoredString) -> ColoredString {
+    if colors_enabled() {
+        text
+    } else {
+        text.clear()
+    }
+}
 
 // This trait has a function for formatting "code-like" text, such as a file path. The reason it's
 // implemented as a trait and not just a function is so we can use it with method syntax, as in
-// `x.code_str()`. Rust does not allow us to implement methods on primitive types such as `str`.
+// `x.code_str()`. Rust does not allow us to implement methods on primitive types such as `str`. It's
+// only public for the `typical` binary, so it's hidden from the documented API.
+#[doc(hidden)]
 pub trait CodeStr {
     fn code_str(&self) -> ColoredString;
 }
@@ -11,7 +41,7 @@ impl CodeStr for str {
     fn code_str(&self) -> ColoredString {
         // If colored output is enabled, format the text in magenta. Otherwise, surround it in
         // backticks.
-        if SHOULD_COLORIZE.should_colorize() {
+        if colors_enabled() {
             self.magenta()
         } else {
             ColoredString::from(&format!("`{self}`") as &Self)
@@ -1,10 +1,16 @@
 use {
     crate::{
+        error::{listing, throw, Error, SourceRange},
+        format::CodeStr,
         identifier::Identifier,
-        schema::{self, relativize_namespace},
+        monomorphizer::monomorphize,
+        schema::{
+            self, apply_renames, deprecation, encoding_type, relativize_namespace, rust_derives,
+        },
+        well_known,
     },
     std::{
-        collections::BTreeMap,
+        collections::{BTreeMap, BTreeSet},
         fmt::{self, Write},
         path::PathBuf,
     },
@@ -16,6 +22,102 @@ const INDENTATION: &str = "    ";
 // The generated types will derive these traits.
 const TRAITS_TO_DERIVE: &[&str] = &["Clone", "Debug"];
 
+// A trait which can be requested for the `In` and `Out` types, either for all of them with
+// `Options::derives` or for particular declarations with `@rust.derive` [tag:rust_derives]
+struct DerivableTrait {
+    // The name used to request the trait
+    name: &'static str,
+
+    // The path used in the generated `derive` attribute
+    path: &'static str,
+
+    // The traits which must be derived along with this one, since they're its supertraits
+    supertraits: &'static [&'static str],
+
+    // Whether the trait is implemented for `f32` and `f64`
+    floats: bool,
+
+    // Whether the trait comes from Serde, which only supports arrays of up to 32 elements and
+    // doesn't know about the views used by borrowed types [ref:borrowed_types]
+    serde: bool,
+}
+
+// These are the traits which can be requested for the generated types. `Eq`, `Ord`, and `Hash`
+// aren't implemented for floating-point numbers, since `NaN` isn't equal to itself.
+const DERIVABLE_TRAITS: &[DerivableTrait] = &[
+    DerivableTrait {
+        name: "Clone",
+        path: "Clone",
+        supertraits: &[],
+        floats: true,
+        serde: false,
+    },
+    DerivableTrait {
+        name: "Debug",
+        path: "Debug",
+        supertraits: &[],
+        floats: true,
+        serde: false,
+    },
+    DerivableTrait {
+        name: "PartialEq",
+        path: "PartialEq",
+        supertraits: &[],
+        floats: true,
+        serde: false,
+    },
+    DerivableTrait {
+        name: "Eq",
+        path: "Eq",
+        supertraits: &["PartialEq"],
+        floats: false,
+        serde: false,
+    },
+    DerivableTrait {
+        name: "PartialOrd",
+        path: "PartialOrd",
+        supertraits: &["PartialEq"],
+        floats: true,
+        serde: false,
+    },
+    DerivableTrait {
+        name: "Ord",
+        path: "Ord",
+        supertraits: &["Eq", "PartialOrd"],
+        floats: false,
+        serde: false,
+    },
+    DerivableTrait {
+        name: "Hash",
+        path: "Hash",
+        supertraits: &[],
+        floats: false,
+        serde: false,
+    },
+    DerivableTrait {
+        name: "Serialize",
+        path: "::serde::Serialize",
+        supertraits: &[],
+        floats: true,
+        serde: true,
+    },
+    DerivableTrait {
+        name: "Deserialize",
+        path: "::serde::Deserialize",
+        supertraits: &[],
+        floats: true,
+        serde: true,
+    },
+];
+
+// Serde only implements its traits for arrays of up to this many elements.
+const SERDE_MAX_ARRAY_LENGTH: usize = 32;
+
+// The traits derived for each declaration, along with the `@rust.derive` attributes which requested
+// them [ref:rust_derives]. The traits requested for all the types don't have attributes.
+type Derives =
+    BTreeMap<(schema::Namespace, Identifier), Vec<(&'static DerivableTrait, Option<SourceRange>)>>;
+
 // This list of Rust keywords comes from https://doc.rust-lang.org/reference/keywords.html.
 const RUST_KEYWORDS: &[&str] = &[
     "Self",
@@ -84,10 +186,11 @@ struct Module {
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 enum CaseConvention {
     Pascal,
+    ScreamingSnake,
     Snake,
 }
 
-use CaseConvention::{Pascal, Snake};
+use CaseConvention::{Pascal, ScreamingSnake, Snake};
 
 // This enum is used to distinguish between the ingress and egress versions of a type.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -99,235 +202,1530 @@ enum Direction {
 
 use Direction::{Atlas, In, Out};
 
-// Generate Rust code from a schema and its transitive dependencies.
-#[allow(clippy::too_many_lines)]
-pub fn generate(
-    typical_version: &str,
-    schemas: &BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>,
-) -> String {
-    // Construct a tree of modules and schemas. We start with an empty tree.
-    let mut tree = Module {
-        children: BTreeMap::new(),
-        schema: schema::Schema {
-            comment: vec![],
-            imports: BTreeMap::new(),
-            declarations: vec![],
-        },
-    };
+// Options for customizing the generated code. The flags are independent of each other, so they're
+// kept as separate fields.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct Options {
+    // Generate conversions between the well-known `Uuid` type and `uuid::Uuid`, which requires the
+    // `uuid` crate.
+    pub uuid: bool,
+
+    // Generate `In` types which borrow strings, bytes, and arrays from the serialized message rather
+    // than copying them [ref:borrowed_types].
+    pub borrowed: bool,
+
+    // Generate code which only needs `core` and `alloc` rather than `std` [ref:no_std].
+    pub no_std: bool,
+
+    // Generate asynchronous counterparts of the serialization and deserialization logic, which
+    // require the `tokio` crate [ref:async_io].
+    pub async_io: bool,
+
+    // Generate typed builders for the `Out` types of structs [ref:rust_builders].
+    pub builders: bool,
+
+    // Traits to derive for all the `In` and `Out` types, in addition to the ones requested for
+    // particular declarations with `@rust.derive` [ref:rust_derives]
+    pub derives: Vec<String>,
+}
 
-    // Populate the tree with all the schemas.
-    for (namespace, (schema, _, _)) in schemas {
-        insert_schema(&mut tree, namespace, schema);
-    }
+// The declarations whose `In` types borrow from the serialized message when generating borrowed
+// types [tag:borrowed_types], identified by their namespaces and names. Strings, variable-length
+// `Bytes`, and variable-length arrays are borrowed, so a declaration borrows if it contains any of
+// those, possibly by way of other declarations. The `In` types of such declarations have a lifetime
+// parameter named `'a`.
+type Borrowing = BTreeSet<(schema::Namespace, Identifier)>;
 
-    // Write the code.
-    let mut buffer = String::new();
+// The settings which are threaded through the code generation functions below
+#[derive(Clone, Copy, Debug)]
+struct Context<'a> {
+    // The declarations which borrow from the serialized message, if generating borrowed types
+    borrowing: Option<&'a Borrowing>,
 
-    if !tree.children.is_empty() || !tree.schema.declarations.is_empty() {
-        // The `unwrap` is safe because the `std::fmt::Write` impl for `String` is infallible.
-        // For functions that take abstract parameters which implement `std::io::Read` or
-        // `std::io::Write`, it's idiomatic to consume the reader or writer rather than borrowing
-        // them (https://rust-lang.github.io/api-guidelines/interoperability.html
-        // #generic-readerwriter-functions-take-r-read-and-w-write-by-value-c-rw-value). However,
-        // we borrow them anyway since that allows us to pass the reference (after reborrowing)
-        // to recursive calls, rather than building up and following chains of references.
-        writeln!(
-            &mut buffer,
-            "\
-// This file was automatically generated by Typical {typical_version}.
-// Visit https://github.com/stepchowfun/typical for more information.
+    // Whether the generated code can only use `core` and `alloc` [ref:no_std]
+    no_std: bool,
 
-use std::{{
-    cmp::min,
-    io::{{self, BufRead, Error, ErrorKind, Write}},
-    mem::transmute,
-}};
+    // The traits to derive for all the `In` and `Out` types [ref:rust_derives]
+    derives: &'a [String],
 
-const MISSING_FIELDS_ERROR_MESSAGE: &str = \"Struct missing one or more required field(s).\";
+    // Whether the serialization and deserialization logic being generated is the asynchronous
+    // variant [ref:async_io]
+    asynchronous: bool,
 
-pub trait Serialize {{
-    fn size(&self) -> usize;
+    // The number of modules between the code being generated and the root of the generated code
+    depth: usize,
+}
 
-    fn serialize<T: Write>(&self, writer: T) -> io::Result<()>;
-}}
+impl Context<'_> {
+    // Type names in expressions (e.g., `FooIn::deserialize_from_reader_ref`) don't have lifetimes.
+    fn without_borrowing(self) -> Self {
+        Context {
+            borrowing: None,
+            ..self
+        }
+    }
 
-pub trait Deserialize: Sized {{
-    fn deserialize<T: BufRead>(reader: T) -> io::Result<Self>;
-}}
+    // The path to the module which provides the reader and writer traits and the error type. Without
+    // `std`, that's the `io` module at the root of the generated code [ref:no_std].
+    fn io(self) -> String {
+        if self.no_std {
+            format!("{}io", "super::".repeat(self.depth))
+        } else {
+            "::std::io".to_owned()
+        }
+    }
 
-fn zigzag_encode(value: i64) -> u64 {{
-    unsafe {{ transmute::<i64, u64>(value >> 63_u32) ^ transmute::<i64, u64>(value << 1_u32) }}
-}}
+    // The path to the crate which provides `core` functionality
+    fn core(self) -> &'static str {
+        if self.no_std {
+            "::core"
+        } else {
+            "::std"
+        }
+    }
 
-fn zigzag_decode(value: u64) -> i64 {{
-    unsafe {{ transmute::<u64, i64>(value >> 1_u32) ^ -transmute::<u64, i64>(value & 1) }}
-}}
+    // The path to the crate which provides collections
+    fn alloc(self) -> &'static str {
+        if self.no_std {
+            "::alloc"
+        } else {
+            "::std"
+        }
+    }
 
-fn varint_size_from_value(value: u64) -> usize {{
-    match value {{
-        0_u64..=127_u64 => 1,
-        128_u64..=16_511_u64 => 2,
-        16_512_u64..=2_113_663_u64 => 3,
-        2_113_664_u64..=270_549_119_u64 => 4,
-        270_549_120_u64..=34_630_287_487_u64 => 5,
-        34_630_287_488_u64..=4_432_676_798_591_u64 => 6,
-        4_432_676_798_592_u64..=567_382_630_219_903_u64 => 7,
-        567_382_630_219_904_u64..=72_624_976_668_147_839_u64 => 8,
-        72_624_976_668_147_840_u64..=18_446_744_073_709_551_615_u64 => 9,
-    }}
-}}
+    // The `vec!` macro isn't in the prelude without `std`.
+    fn vec_macro(self) -> &'static str {
+        if self.no_std {
+            "::alloc::vec!"
+        } else {
+            "vec!"
+        }
+    }
 
-fn varint_size_from_first_byte(first_byte: u8) -> u32 {{
-    first_byte.trailing_zeros() + 1
-}}
+    // The serialization and deserialization logic is generated once for each of these contexts:
+    // once synchronously, and once asynchronously if requested [ref:async_io].
+    fn io_variants(self, async_io: bool) -> Vec<Self> {
+        let mut variants = vec![self];
 
-fn serialize_varint<T: Write>(mut value: u64, writer: &mut T) -> io::Result<()> {{
-    match value {{
-        0_u64..=127_u64 => writer.write_all(&[((value << 1_u32) as u8) | 0b0000_0001]),
-        128_u64..=16_511_u64 => {{
-            value -= 128_u64;
-            writer.write_all(&[
-                ((value << 2_u32) as u8) | 0b0000_0010,
-                (value >> 6_u32) as u8,
-            ])
-        }}
-        16_512_u64..=2_113_663_u64 => {{
-            value -= 16_512_u64;
-            writer.write_all(&[
-                ((value << 3_u32) as u8) | 0b0000_0100,
-                (value >> 5_u32) as u8,
-                (value >> 13_u32) as u8,
-            ])
-        }}
-        2_113_664_u64..=270_549_119_u64 => {{
-            value -= 2_113_664_u64;
-            writer.write_all(&[
-                ((value << 4_u32) as u8) | 0b0000_1000,
-                (value >> 4_u32) as u8,
-                (value >> 12_u32) as u8,
-                (value >> 20_u32) as u8,
-            ])
-        }}
-        270_549_120_u64..=34_630_287_487_u64 => {{
-            value -= 270_549_120_u64;
-            writer.write_all(&[
-                ((value << 5_u32) as u8) | 0b0001_0000,
-                (value >> 3_u32) as u8,
-                (value >> 11_u32) as u8,
-                (value >> 19_u32) as u8,
-                (value >> 27_u32) as u8,
-            ])
-        }}
-        34_630_287_488_u64..=4_432_676_798_591_u64 => {{
-            value -= 34_630_287_488_u64;
-            writer.write_all(&[
-                ((value << 6_u32) as u8) | 0b0010_0000,
-                (value >> 2_u32) as u8,
-                (value >> 10_u32) as u8,
-                (value >> 18_u32) as u8,
-                (value >> 26_u32) as u8,
-                (value >> 34_u32) as u8,
-            ])
-        }}
-        4_432_676_798_592_u64..=567_382_630_219_903_u64 => {{
-            value -= 4_432_676_798_592_u64;
-            writer.write_all(&[
-                ((value << 7_u32) as u8) | 0b0100_0000,
-                (value >> 1_u32) as u8,
-                (value >> 9_u32) as u8,
-                (value >> 17_u32) as u8,
-                (value >> 25_u32) as u8,
-                (value >> 33_u32) as u8,
-                (value >> 41_u32) as u8,
-            ])
-        }}
-        567_382_630_219_904_u64..=72_624_976_668_147_839_u64 => {{
-            value -= 567_382_630_219_904_u64;
-            writer.write_all(&[
-                0b1000_0000,
-                value as u8,
-                (value >> 8_u32) as u8,
-                (value >> 16_u32) as u8,
-                (value >> 24_u32) as u8,
-                (value >> 32_u32) as u8,
-                (value >> 40_u32) as u8,
-                (value >> 48_u32) as u8,
-            ])
-        }}
-        72_624_976_668_147_840_u64..=18_446_744_073_709_551_615_u64 => {{
-            value -= 72_624_976_668_147_840_u64;
-            writer.write_all(&[
-                0b0000_0000,
-                value as u8,
-                (value >> 8_u32) as u8,
-                (value >> 16_u32) as u8,
-                (value >> 24_u32) as u8,
-                (value >> 32_u32) as u8,
-                (value >> 40_u32) as u8,
-                (value >> 48_u32) as u8,
-                (value >> 56_u32) as u8,
-            ])
-        }}
-    }}
-}}
+        if async_io {
+            variants.push(Context {
+                asynchronous: true,
+                ..self
+            });
+        }
 
-fn deserialize_varint<T: BufRead>(reader: &mut T) -> io::Result<u64> {{
-    let mut first_byte_buffer = [0; 1];
-    reader.read_exact(&mut first_byte_buffer[..])?;
-    let first_byte = first_byte_buffer[0];
-    let size_minus_one = first_byte.trailing_zeros();
+        variants
+    }
 
-    let mut remaining_bytes_buffer = [0; 8];
-    reader.read_exact(&mut remaining_bytes_buffer[0..size_minus_one as usize])?;
-    let remaining_bytes_value = u64::from_le_bytes(remaining_bytes_buffer);
+    // The asynchronous variants of the functions which do I/O have this suffix [ref:async_io].
+    fn suffix(self) -> &'static str {
+        if self.asynchronous {
+            "_async"
+        } else {
+            ""
+        }
+    }
 
-    match size_minus_one {{
-        0 => Ok(u64::from(first_byte >> 1_u32)),
-        1 => Ok(128_u64 + u64::from(first_byte >> 2_u32) + (remaining_bytes_value << 6_u32)),
-        2 => Ok(16_512_u64 + u64::from(first_byte >> 3_u32) + (remaining_bytes_value << 5_u32)),
-        3 => Ok(2_113_664_u64 + u64::from(first_byte >> 4_u32) + (remaining_bytes_value << 4_u32)),
-        4 => {{
-            Ok(270_549_120_u64 + u64::from(first_byte >> 5_u32) + (remaining_bytes_value << 3_u32))
-        }}
-        5 => Ok(34_630_287_488_u64
-            + u64::from(first_byte >> 6_u32)
-            + (remaining_bytes_value << 2_u32)),
-        6 => Ok(4_432_676_798_592_u64
-            + u64::from(first_byte >> 7_u32)
-            + (remaining_bytes_value << 1_u32)),
-        7 => Ok(567_382_630_219_904_u64 + remaining_bytes_value),
-        _ => Ok(72_624_976_668_147_840_u64.wrapping_add(remaining_bytes_value)),
-    }}
-}}
+    // Functions which do I/O are declared with this prefix.
+    fn async_keyword(self) -> &'static str {
+        if self.asynchronous {
+            "async "
+        } else {
+            ""
+        }
+    }
 
-fn field_header_size(index: u64, payload_size: usize, integer_encoded: bool) -> usize {{
-    match payload_size {{
-        0 => varint_size_from_value((index << 2_u32) | 0b00),
-        8 => varint_size_from_value((index << 2_u32) | 0b01),
-        size => {{
-            if integer_encoded {{
-                varint_size_from_value((index << 2_u32) | 0b10)
-            }} else {{
-                varint_size_from_value((index << 2_u32) | 0b11)
-                    + varint_size_from_value(size as u64)
-            }}
-        }}
-    }}
-}}
+    // The results of functions which do I/O are obtained with this suffix.
+    fn wait(self) -> &'static str {
+        if self.asynchronous {
+            ".await"
+        } else {
+            ""
+        }
+    }
 
-fn serialize_field_header<T: Write>(
-    writer: &mut T,
-    index: u64,
-    payload_size: usize,
-    integer_encoded: bool,
-) -> io::Result<()> {{
-    match payload_size {{
-        0 => serialize_varint((index << 2_u32) | 0b00, writer),
-        8 => serialize_varint((index << 2_u32) | 0b01, writer),
-        size => {{
-            if integer_encoded {{
-                serialize_varint((index << 2_u32) | 0b10, writer)
-            }} else {{
+    // The bound for readers
+    fn reader_bound(self) -> String {
+        if self.asynchronous {
+            "::tokio::io::AsyncBufRead + Unpin".to_owned()
+        } else {
+            format!("{}::BufRead", self.io())
+        }
+    }
+
+    // The bound for writers
+    fn writer_bound(self) -> String {
+        if self.asynchronous {
+            "::tokio::io::AsyncWrite + Unpin".to_owned()
+        } else {
+            format!("{}::Write", self.io())
+        }
+    }
+
+    // The trait which provides the methods for reading
+    fn read(self) -> String {
+        if self.asynchronous {
+            "::tokio::io::AsyncReadExt".to_owned()
+        } else {
+            format!("{}::Read", self.io())
+        }
+    }
+
+    // An expression which writes some bytes to `writer`
+    fn write_all(self, bytes: &str) -> String {
+        if self.asynchronous {
+            format!("::tokio::io::AsyncWriteExt::write_all(writer, {bytes}).await")
+        } else {
+            format!("writer.write_all({bytes})")
+        }
+    }
+}
+
+// Conversions between the well-known time types and their counterparts in the standard library
+// [ref:well_known_schemas]. Times before the epoch have a negative number of seconds, but the
+// number of nanoseconds always counts forward. Conversions which would lose information fail.
+const TIME_CONVERSIONS: &str = "\
+impl TryFrom<::std::time::SystemTime> for TimestampOut {
+    type Error = ::std::io::Error;
+
+    fn try_from(time: ::std::time::SystemTime) -> Result<Self, Self::Error> {
+        let (seconds, nanos) = match time.duration_since(::std::time::UNIX_EPOCH) {
+            Ok(duration) => (i64::try_from(duration.as_secs()).ok(), duration.subsec_nanos()),
+            Err(error) => {
+                let duration = error.duration();
+                let seconds = i64::try_from(duration.as_secs()).ok().map(|seconds| -seconds);
+
+                match duration.subsec_nanos() {
+                    0 => (seconds, 0),
+                    nanos => (
+                        seconds.and_then(|seconds| seconds.checked_sub(1)),
+                        1_000_000_000 - nanos,
+                    ),
+                }
+            }
+        };
+
+        seconds
+            .map(|seconds| TimestampOut { seconds, nanos })
+            .ok_or_else(|| {
+                ::std::io::Error::new(::std::io::ErrorKind::InvalidData, \"Timestamp out of range.\")
+            })
+    }
+}
+
+impl TryFrom<TimestampIn> for ::std::time::SystemTime {
+    type Error = ::std::io::Error;
+
+    fn try_from(timestamp: TimestampIn) -> Result<Self, Self::Error> {
+        let seconds = ::std::time::Duration::from_secs(timestamp.seconds.unsigned_abs());
+        let nanos = ::std::time::Duration::from_nanos(u64::from(timestamp.nanos));
+
+        if timestamp.nanos < 1_000_000_000 {
+            if timestamp.seconds < 0 {
+                ::std::time::UNIX_EPOCH.checked_sub(seconds)
+            } else {
+                ::std::time::UNIX_EPOCH.checked_add(seconds)
+            }
+            .and_then(|time| time.checked_add(nanos))
+        } else {
+            None
+        }
+        .ok_or_else(|| {
+            ::std::io::Error::new(::std::io::ErrorKind::InvalidData, \"Timestamp out of range.\")
+        })
+    }
+}
+
+impl From<::std::time::Duration> for DurationOut {
+    fn from(duration: ::std::time::Duration) -> Self {
+        DurationOut {
+            seconds: duration.as_secs(),
+            nanos: duration.subsec_nanos(),
+        }
+    }
+}
+
+impl TryFrom<DurationIn> for ::std::time::Duration {
+    type Error = ::std::io::Error;
+
+    fn try_from(duration: DurationIn) -> Result<Self, Self::Error> {
+        if duration.nanos < 1_000_000_000 {
+            Ok(::std::time::Duration::new(duration.seconds, duration.nanos))
+        } else {
+            Err(::std::io::Error::new(
+                ::std::io::ErrorKind::InvalidData,
+                \"Duration out of range.\",
+            ))
+        }
+    }
+}
+";
+
+// Conversions between the well-known `Uuid` type and `uuid::Uuid` [ref:well_known_schemas]
+const UUID_CONVERSIONS: &str = "\
+impl From<::uuid::Uuid> for UuidOut {
+    fn from(uuid: ::uuid::Uuid) -> Self {
+        UuidOut(uuid.into_bytes())
+    }
+}
+
+impl From<UuidIn> for ::uuid::Uuid {
+    fn from(uuid: UuidIn) -> Self {
+        ::uuid::Uuid::from_bytes(uuid.0)
+    }
+}
+";
+
+// The trait for deserializing borrowed types [ref:borrowed_types]. It's implemented alongside
+// `Deserialize`, which is still implemented for the types which don't borrow anything.
+const DESERIALIZE_BORROWED_TRAIT: &str = "\
+pub trait DeserializeBorrowed<'a>: Sized {
+    fn deserialize_borrowed(bytes: &'a [u8]) -> io::Result<Self>;
+}";
+
+// Borrowed types split the input into a slice for each field rather than reading it through a
+// `std::io::Take` [ref:borrowed_types]. A truncated input results in a truncated slice, just as
+// reading through a `std::io::Take` would stop early.
+const SPLIT_FUNCTION: &str = "\
+fn split<'a>(reader: &mut &'a [u8], size: usize) -> &'a [u8] {
+    let (payload, rest) = reader.split_at(min(size, reader.len()));
+    *reader = rest;
+    payload
+}";
+
+// Borrowed types represent variable-length arrays as views into the input [ref:borrowed_types].
+// The elements are deserialized once up front to validate them and count them, and then again on
+// demand when the view is iterated over.
+const ARRAY_VIEW: &str = "\
+pub struct ArrayView<'a, T> {
+    bytes: &'a [u8],
+    len: usize,
+    deserialize_element: fn(&mut &'a [u8]) -> io::Result<T>,
+}
+
+impl<'a, T> ArrayView<'a, T> {
+    fn new(
+        bytes: &'a [u8],
+        deserialize_element: fn(&mut &'a [u8]) -> io::Result<T>,
+    ) -> io::Result<Self> {
+        let mut reader = bytes;
+        let mut len = 0;
+
+        loop {
+            match deserialize_element(&mut reader) {
+                Ok(_) => len += 1,
+                Err(err) => {
+                    if let ErrorKind::UnexpectedEof = err.kind() {
+                        break;
+                    }
+
+                    return Err(err);
+                }
+            }
+        }
+
+        Ok(ArrayView {
+            bytes,
+            len,
+            deserialize_element,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn iter(&self) -> ArrayViewIter<'a, T> {
+        ArrayViewIter {
+            bytes: self.bytes,
+            remaining: self.len,
+            deserialize_element: self.deserialize_element,
+        }
+    }
+}
+
+impl<T> Clone for ArrayView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ArrayView<'_, T> {}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for ArrayView<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for ArrayView<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for ArrayView<'_, T> {}
+
+impl<T: PartialOrd> PartialOrd for ArrayView<'_, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for ArrayView<'_, T> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: core::hash::Hash> core::hash::Hash for ArrayView<'_, T> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        self.iter().for_each(|element| element.hash(state));
+    }
+}
+
+impl<'a, T> IntoIterator for ArrayView<'a, T> {
+    type Item = T;
+    type IntoIter = ArrayViewIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &ArrayView<'a, T> {
+    type Item = T;
+    type IntoIter = ArrayViewIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct ArrayViewIter<'a, T> {
+    bytes: &'a [u8],
+    remaining: usize,
+    deserialize_element: fn(&mut &'a [u8]) -> io::Result<T>,
+}
+
+impl<T> Clone for ArrayViewIter<'_, T> {
+    fn clone(&self) -> Self {
+        ArrayViewIter {
+            bytes: self.bytes,
+            remaining: self.remaining,
+            deserialize_element: self.deserialize_element,
+        }
+    }
+}
+
+impl<T> Iterator for ArrayViewIter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        self.remaining -= 1;
+
+        // The elements were already deserialized by `ArrayView::new`, so this can't fail.
+        (self.deserialize_element)(&mut self.bytes).ok()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for ArrayViewIter<'_, T> {}";
+
+// Without `std`, the generated code uses these minimal counterparts of the reader and writer traits
+// and error type from `std::io` [tag:no_std]. They're implemented for byte slices and `Vec<u8>`. If
+// the crate which includes the generated code has a feature named `std`, enabling it replaces those
+// impls with blanket impls for the `std::io` traits, along with conversions between the error
+// types. The crate root must declare `extern crate alloc;` (and `extern crate std;` for that
+// feature).
+const NO_STD_IO: &str = "\
+pub mod io {
+    use {
+        ::alloc::{
+            string::{String, ToString},
+            vec::Vec,
+        },
+        core::{cmp::min, fmt},
+    };
+
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    pub enum ErrorKind {
+        InvalidData,
+        UnexpectedEof,
+        Other,
+    }
+
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct Error {
+        kind: ErrorKind,
+        message: String,
+    }
+
+    impl Error {
+        pub fn new<E: fmt::Display>(kind: ErrorKind, error: E) -> Self {
+            Error {
+                kind,
+                message: error.to_string(),
+            }
+        }
+
+        pub fn kind(&self) -> ErrorKind {
+            self.kind
+        }
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(&self.message)
+        }
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    pub trait Read {
+        fn read(&mut self, buffer: &mut [u8]) -> Result<usize>;
+
+        fn read_exact(&mut self, mut buffer: &mut [u8]) -> Result<()> {
+            while !buffer.is_empty() {
+                match self.read(buffer)? {
+                    0 => {
+                        return Err(Error::new(
+                            ErrorKind::UnexpectedEof,
+                            \"failed to fill whole buffer\",
+                        ));
+                    }
+                    size => buffer = &mut buffer[size..],
+                }
+            }
+
+            Ok(())
+        }
+
+        fn read_to_end(&mut self, buffer: &mut Vec<u8>) -> Result<usize> {
+            let start = buffer.len();
+            let mut chunk = [0; 4096];
+
+            loop {
+                match self.read(&mut chunk)? {
+                    0 => return Ok(buffer.len() - start),
+                    size => buffer.extend_from_slice(&chunk[..size]),
+                }
+            }
+        }
+
+        fn read_to_string(&mut self, buffer: &mut String) -> Result<usize> {
+            let mut bytes = Vec::new();
+            let size = self.read_to_end(&mut bytes)?;
+            let string = String::from_utf8(bytes)
+                .map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+            buffer.push_str(&string);
+            Ok(size)
+        }
+
+        fn take(&mut self, limit: u64) -> Take<'_, Self> {
+            Take {
+                inner: self,
+                limit,
+            }
+        }
+    }
+
+    pub trait BufRead: Read {
+        fn fill_buf(&mut self) -> Result<&[u8]>;
+
+        fn consume(&mut self, amount: usize);
+    }
+
+    pub trait Write {
+        fn write_all(&mut self, buffer: &[u8]) -> Result<()>;
+    }
+
+    pub struct Take<'a, R: ?Sized> {
+        inner: &'a mut R,
+        limit: u64,
+    }
+
+    impl<R: Read + ?Sized> Read for Take<'_, R> {
+        fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
+            let size = min(buffer.len() as u64, self.limit) as usize;
+            let size = self.inner.read(&mut buffer[..size])?;
+            self.limit -= size as u64;
+            Ok(size)
+        }
+    }
+
+    impl<R: BufRead + ?Sized> BufRead for Take<'_, R> {
+        fn fill_buf(&mut self) -> Result<&[u8]> {
+            if self.limit == 0 {
+                return Ok(&[]);
+            }
+
+            let buffer = self.inner.fill_buf()?;
+            let size = min(buffer.len() as u64, self.limit) as usize;
+            Ok(&buffer[..size])
+        }
+
+        fn consume(&mut self, amount: usize) {
+            let amount = min(amount as u64, self.limit) as usize;
+            self.limit -= amount as u64;
+            self.inner.consume(amount);
+        }
+    }
+
+    // Array elements are deserialized from a mutable reference to a `Take`. Without `std`, that's
+    // covered by the impls for mutable references below.
+    #[cfg(feature = \"std\")]
+    impl<R: Read + ?Sized> Read for &mut Take<'_, R> {
+        fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
+            (**self).read(buffer)
+        }
+    }
+
+    #[cfg(feature = \"std\")]
+    impl<R: BufRead + ?Sized> BufRead for &mut Take<'_, R> {
+        fn fill_buf(&mut self) -> Result<&[u8]> {
+            (**self).fill_buf()
+        }
+
+        fn consume(&mut self, amount: usize) {
+            (**self).consume(amount);
+        }
+    }
+
+    #[cfg(not(feature = \"std\"))]
+    impl Read for &[u8] {
+        fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
+            let size = min(buffer.len(), self.len());
+            let (bytes, rest) = self.split_at(size);
+            buffer[..size].copy_from_slice(bytes);
+            *self = rest;
+            Ok(size)
+        }
+    }
+
+    #[cfg(not(feature = \"std\"))]
+    impl BufRead for &[u8] {
+        fn fill_buf(&mut self) -> Result<&[u8]> {
+            Ok(self)
+        }
+
+        fn consume(&mut self, amount: usize) {
+            *self = &self[amount..];
+        }
+    }
+
+    #[cfg(not(feature = \"std\"))]
+    impl<R: Read + ?Sized> Read for &mut R {
+        fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
+            (**self).read(buffer)
+        }
+    }
+
+    #[cfg(not(feature = \"std\"))]
+    impl<R: BufRead + ?Sized> BufRead for &mut R {
+        fn fill_buf(&mut self) -> Result<&[u8]> {
+            (**self).fill_buf()
+        }
+
+        fn consume(&mut self, amount: usize) {
+            (**self).consume(amount);
+        }
+    }
+
+    #[cfg(not(feature = \"std\"))]
+    impl Write for Vec<u8> {
+        fn write_all(&mut self, buffer: &[u8]) -> Result<()> {
+            self.extend_from_slice(buffer);
+            Ok(())
+        }
+    }
+
+    #[cfg(not(feature = \"std\"))]
+    impl<W: Write + ?Sized> Write for &mut W {
+        fn write_all(&mut self, buffer: &[u8]) -> Result<()> {
+            (**self).write_all(buffer)
+        }
+    }
+
+    #[cfg(feature = \"std\")]
+    impl<R: ::std::io::Read + ?Sized> Read for R {
+        fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
+            Ok(::std::io::Read::read(self, buffer)?)
+        }
+
+        fn read_exact(&mut self, buffer: &mut [u8]) -> Result<()> {
+            Ok(::std::io::Read::read_exact(self, buffer)?)
+        }
+
+        fn read_to_end(&mut self, buffer: &mut Vec<u8>) -> Result<usize> {
+            Ok(::std::io::Read::read_to_end(self, buffer)?)
+        }
+
+        fn read_to_string(&mut self, buffer: &mut String) -> Result<usize> {
+            Ok(::std::io::Read::read_to_string(self, buffer)?)
+        }
+    }
+
+    #[cfg(feature = \"std\")]
+    impl<R: ::std::io::BufRead + ?Sized> BufRead for R {
+        fn fill_buf(&mut self) -> Result<&[u8]> {
+            Ok(::std::io::BufRead::fill_buf(self)?)
+        }
+
+        fn consume(&mut self, amount: usize) {
+            ::std::io::BufRead::consume(self, amount);
+        }
+    }
+
+    #[cfg(feature = \"std\")]
+    impl<W: ::std::io::Write + ?Sized> Write for W {
+        fn write_all(&mut self, buffer: &[u8]) -> Result<()> {
+            Ok(::std::io::Write::write_all(self, buffer)?)
+        }
+    }
+
+    #[cfg(feature = \"std\")]
+    impl From<::std::io::Error> for Error {
+        fn from(error: ::std::io::Error) -> Self {
+            let kind = match error.kind() {
+                ::std::io::ErrorKind::InvalidData => ErrorKind::InvalidData,
+                ::std::io::ErrorKind::UnexpectedEof => ErrorKind::UnexpectedEof,
+                _ => ErrorKind::Other,
+            };
+
+            Error::new(kind, error)
+        }
+    }
+
+    #[cfg(feature = \"std\")]
+    impl From<Error> for ::std::io::Error {
+        fn from(error: Error) -> Self {
+            let kind = match error.kind {
+                ErrorKind::InvalidData => ::std::io::ErrorKind::InvalidData,
+                ErrorKind::UnexpectedEof => ::std::io::ErrorKind::UnexpectedEof,
+                ErrorKind::Other => ::std::io::ErrorKind::Other,
+            };
+
+            ::std::io::Error::new(kind, error.message)
+        }
+    }
+
+    #[cfg(feature = \"std\")]
+    impl ::std::error::Error for Error {}
+}";
+
+// The asynchronous counterparts of the `Serialize` and `Deserialize` traits, which are built on
+// Tokio's `AsyncWrite` and `AsyncBufRead` traits [tag:async_io]. The asynchronous logic mirrors the
+// synchronous logic, so the two produce the same bytes and accept the same messages. The futures
+// are `Send` so they can be spawned onto a multithreaded runtime.
+const ASYNC_TRAITS: &str = "\
+pub trait SerializeAsync: Serialize {
+    fn serialize_async<T: ::tokio::io::AsyncWrite + Unpin + Send>(
+        &self,
+        writer: T,
+    ) -> impl ::std::future::Future<Output = io::Result<()>> + Send;
+}
+
+pub trait DeserializeAsync: Sized {
+    fn deserialize_async<T: ::tokio::io::AsyncBufRead + Unpin + Send>(
+        reader: T,
+    ) -> impl ::std::future::Future<Output = io::Result<Self>> + Send;
+}";
+
+// The state of a required or asymmetric field which hasn't been given to a builder yet
+// [tag:rust_builders]. Builders track which of those fields have been given in their type
+// parameters, so forgetting one is a compile-time error. Adding an optional field to a struct
+// doesn't break code which uses its builder.
+const UNSET_TYPE: &str = "\
+#[derive(Clone, Copy, Debug)]
+pub struct Unset;";
+
+// The asynchronous counterparts of the helper functions for varints and field headers
+// [ref:async_io]. Varints are encoded and decoded in small buffers by the synchronous functions, so
+// the encoding logic isn't duplicated.
+const ASYNC_FUNCTIONS: &str = "\
+async fn serialize_varint_async<T: ::tokio::io::AsyncWrite + Unpin>(
+    value: u64,
+    writer: &mut T,
+) -> io::Result<()> {
+    let mut buffer = [0; 9];
+    let mut cursor = &mut buffer[..];
+    serialize_varint(value, &mut cursor)?;
+    let size = 9 - cursor.len();
+    ::tokio::io::AsyncWriteExt::write_all(writer, &buffer[..size]).await
+}
+
+async fn deserialize_varint_async<T: ::tokio::io::AsyncBufRead + Unpin>(
+    reader: &mut T,
+) -> io::Result<u64> {
+    let mut buffer = [0; 9];
+    ::tokio::io::AsyncReadExt::read_exact(&mut *reader, &mut buffer[..1]).await?;
+    let size = varint_size_from_first_byte(buffer[0]) as usize;
+    ::tokio::io::AsyncReadExt::read_exact(&mut *reader, &mut buffer[1..size]).await?;
+    deserialize_varint(&mut &buffer[..size])
+}
+
+async fn serialize_field_header_async<T: ::tokio::io::AsyncWrite + Unpin>(
+    writer: &mut T,
+    index: u64,
+    payload_size: usize,
+    integer_encoded: bool,
+) -> io::Result<()> {
+    let mut buffer = [0; 18];
+    let mut cursor = &mut buffer[..];
+    serialize_field_header(&mut cursor, index, payload_size, integer_encoded)?;
+    let size = 18 - cursor.len();
+    ::tokio::io::AsyncWriteExt::write_all(writer, &buffer[..size]).await
+}
+
+async fn deserialize_field_header_async<T: ::tokio::io::AsyncBufRead + Unpin>(
+    reader: &mut T,
+) -> io::Result<(u64, usize)> {
+    let tag = deserialize_varint_async(&mut *reader).await?;
+
+    let index = tag >> 2_u32;
+
+    let size = match tag & 0b11 {
+        0b00 => 0,
+        0b01 => 8,
+        0b10 => {
+            let buffer = ::tokio::io::AsyncBufReadExt::fill_buf(&mut *reader).await?;
+
+            if buffer.is_empty() {
+                return Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    \"Error decoding field.\",
+                ));
+            }
+
+            varint_size_from_first_byte(buffer[0]) as usize
+        }
+        _ => deserialize_varint_async(&mut *reader).await? as usize,
+    };
+
+    Ok((index, size))
+}
+
+async fn skip_async<T: ::tokio::io::AsyncBufRead + Unpin>(
+    reader: &mut T,
+    mut amount: usize,
+) -> io::Result<()> {
+    while amount > 0 {
+        let buffer = ::tokio::io::AsyncBufReadExt::fill_buf(&mut *reader).await?;
+        let num_bytes_to_consume = min(buffer.len(), amount);
+        ::tokio::io::AsyncBufReadExt::consume(&mut *reader, num_bytes_to_consume);
+        amount -= num_bytes_to_consume;
+    }
+
+    Ok(())
+}
+
+async fn finish_async<T: ::tokio::io::AsyncBufRead + Unpin>(reader: &mut T) -> io::Result<()> {
+    loop {
+        let buffer = ::tokio::io::AsyncBufReadExt::fill_buf(&mut *reader).await?;
+
+        if buffer.is_empty() {
+            return Ok(());
+        }
+
+        let buffer_size = buffer.len();
+        ::tokio::io::AsyncBufReadExt::consume(&mut *reader, buffer_size);
+    }
+}";
+
+// Check that the options are compatible with each other and that the traits requested by
+// `Options::derives` and `@rust.derive` can be derived for the generated types [ref:rust_derives].
+// The schemas are assumed to be valid.
+#[allow(clippy::too_many_lines)]
+pub fn validate(
+    schemas: &BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>,
+    options: &Options,
+) -> Result<(), Vec<Error>> {
+    let mut errors = vec![];
+
+    // The asynchronous logic is built on Tokio, which needs `std`, and borrowed types are
+    // deserialized from slices which are already in memory [ref:async_io].
+    if options.async_io {
+        for (incompatible, name) in [
+            (options.borrowed, "borrowed types"),
+            (options.no_std, "`no_std` code"),
+        ] {
+            if incompatible {
+                errors.push(throw::<Error>(
+                    &format!("Asynchronous code can't be generated for {name}."),
+                    None,
+                    None,
+                    None,
+                ));
+            }
+        }
+    }
+
+    // Check the traits requested for all the types.
+    for name in &options.derives {
+        if let Some(derivable_trait) = derivable_trait(name) {
+            for supertrait in derivable_trait.supertraits {
+                if !options.derives.iter().any(|name| name == supertrait) {
+                    errors.push(throw::<Error>(
+                        &format!(
+                            "Deriving {} for all types requires deriving {} for all types too.",
+                            name.code_str(),
+                            supertrait.code_str(),
+                        ),
+                        None,
+                        None,
+                        None,
+                    ));
+                }
+            }
+        } else {
+            errors.push(throw::<Error>(
+                &unknown_trait_message(name),
+                None,
+                None,
+                None,
+            ));
+        }
+    }
+
+    // Report problems with the global traits once rather than for every declaration.
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    // The traits are derived for the instantiations of the generic declarations
+    // [ref:monomorphization]. The `unwrap` is safe since the schemas are assumed to be valid.
+    let schemas = monomorphize(schemas).unwrap();
+
+    // A builder has a method for each field, as well as one named `build` [ref:rust_builders], so
+    // a field can't have that name.
+    if options.builders {
+        for (schema, source_path, source_contents) in schemas.values() {
+            let mut schema = schema.clone();
+            apply_renames(&mut schema, "rust");
+
+            for declaration in &schema.declarations {
+                if !matches!(declaration.variant, schema::DeclarationVariant::Struct) {
+                    continue;
+                }
+
+                for field in &declaration.fields {
+                    if field.name.snake_case() == "build" {
+                        errors.push(throw::<Error>(
+                            &format!(
+                                "The builder for {} can't have a method for field {}, since \
+                                    that's the name of the method which builds the message.",
+                                declaration.name.code_str(),
+                                field.name.code_str(),
+                            ),
+                            Some(source_path),
+                            Some(&listing(source_contents, field.source_range)),
+                            None,
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    // Determine which types borrow from the serialized message, if borrowed types were requested.
+    let borrowing = if options.borrowed {
+        Some(borrowing_declarations(&schemas))
+    } else {
+        None
+    };
+
+    // Determine which traits are derived for each declaration.
+    let mut derives = Derives::new();
+
+    for (namespace, (schema, source_path, source_contents)) in &schemas {
+        for declaration in &schema.declarations {
+            let mut traits = options
+                .derives
+                .iter()
+                .filter_map(|name| derivable_trait(name))
+                .map(|derivable_trait| (derivable_trait, None))
+                .collect::<Vec<_>>();
+
+            for attribute in &declaration.attributes {
+                if !attribute.is(Some("rust"), "derive") {
+                    continue;
+                }
+
+                for argument in &attribute.arguments {
+                    let schema::AttributeArgument::Identifier(name) = argument else {
+                        continue;
+                    };
+
+                    if let Some(derivable_trait) = derivable_trait(name.original()) {
+                        if !traits
+                            .iter()
+                            .any(|(other_trait, _)| other_trait.name == derivable_trait.name)
+                        {
+                            traits.push((derivable_trait, Some(attribute.source_range)));
+                        }
+                    } else {
+                        errors.push(throw::<Error>(
+                            &unknown_trait_message(name.original()),
+                            Some(source_path),
+                            Some(&listing(source_contents, attribute.source_range)),
+                            None,
+                        ));
+                    }
+                }
+            }
+
+            derives.insert((namespace.clone(), declaration.name.clone()), traits);
+        }
+    }
+
+    // Check that each trait can be derived for the declarations which request it.
+    for (namespace, (schema, source_path, source_contents)) in &schemas {
+        // The `unwrap` is safe due to [ref:namespace_populated].
+        let imports = schema
+            .imports
+            .iter()
+            .map(|(name, import)| (name.clone(), import.namespace.clone().unwrap()))
+            .collect::<BTreeMap<_, _>>();
+
+        for declaration in &schema.declarations {
+            let key = (namespace.clone(), declaration.name.clone());
+
+            for (derivable_trait, source_range) in &derives[&key] {
+                let source_range = source_range.unwrap_or(declaration.source_range);
+
+                // Check that the supertraits are derived too.
+                for supertrait in derivable_trait.supertraits {
+                    if !derives[&key]
+                        .iter()
+                        .any(|(other_trait, _)| other_trait.name == *supertrait)
+                    {
+                        errors.push(throw::<Error>(
+                            &format!(
+                                "{} derives {}, so it must also derive {}.",
+                                declaration.name.code_str(),
+                                derivable_trait.name.code_str(),
+                                supertrait.code_str(),
+                            ),
+                            Some(source_path),
+                            Some(&listing(source_contents, source_range)),
+                            None,
+                        ));
+                    }
+                }
+
+                // Serde doesn't know about the views used by borrowed types.
+                if derivable_trait.serde
+                    && borrowing
+                        .as_ref()
+                        .is_some_and(|borrowing| borrowing.contains(&key))
+                {
+                    errors.push(throw::<Error>(
+                        &format!(
+                            "{} can't derive {}, since its {} type borrows from the serialized \
+                                message.",
+                            declaration.name.code_str(),
+                            derivable_trait.name.code_str(),
+                            "In".code_str(),
+                        ),
+                        Some(source_path),
+                        Some(&listing(source_contents, source_range)),
+                        None,
+                    ));
+                }
+
+                // Check that the trait is implemented for the types of the fields.
+                let types = match &declaration.variant {
+                    schema::DeclarationVariant::Struct | schema::DeclarationVariant::Choice => {
+                        declaration
+                            .fields
+                            .iter()
+                            .map(|field| {
+                                (
+                                    format!("field {}", field.name.code_str()),
+                                    &field.r#type,
+                                    field.source_range,
+                                )
+                            })
+                            .collect::<Vec<_>>()
+                    }
+                    schema::DeclarationVariant::Newtype(underlying_type) => vec![(
+                        "its underlying type".to_owned(),
+                        underlying_type,
+                        source_range,
+                    )],
+                };
+
+                for (subject, r#type, type_source_range) in types {
+                    if let Some(reason) = underivable_reason(
+                        &derives,
+                        &imports,
+                        namespace,
+                        &r#type.variant,
+                        derivable_trait,
+                    ) {
+                        errors.push(throw::<Error>(
+                            &format!(
+                                "{} can't derive {}, since {subject} {reason}.",
+                                declaration.name.code_str(),
+                                derivable_trait.name.code_str(),
+                            ),
+                            Some(source_path),
+                            Some(&listing(source_contents, type_source_range)),
+                            None,
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+// Find a trait which can be requested for the generated types [ref:rust_derives].
+fn derivable_trait(name: &str) -> Option<&'static DerivableTrait> {
+    DERIVABLE_TRAITS
+        .iter()
+        .find(|derivable_trait| derivable_trait.name == name)
+}
+
+// The error message for a trait which can't be requested for the generated types
+fn unknown_trait_message(name: &str) -> String {
+    format!(
+        "Unknown trait {}. The traits which can be derived are {}.",
+        name.code_str(),
+        DERIVABLE_TRAITS
+            .iter()
+            .map(|derivable_trait| derivable_trait.name.code_str().to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    )
+}
+
+// Determine why a trait can't be derived for a type containing the given type, if it can't be
+// [ref:rust_derives]. Custom types and newtypes must derive the trait themselves.
+fn underivable_reason(
+    derives: &Derives,
+    imports: &BTreeMap<Identifier, schema::Namespace>,
+    namespace: &schema::Namespace,
+    type_variant: &schema::TypeVariant,
+    derivable_trait: &DerivableTrait,
+) -> Option<String> {
+    match type_variant {
+        schema::TypeVariant::F32 | schema::TypeVariant::F64 if !derivable_trait.floats => {
+            Some("has a floating-point type".to_owned())
+        }
+        schema::TypeVariant::Array(_, Some(length)) | schema::TypeVariant::Bytes(Some(length))
+            if derivable_trait.serde && *length > SERDE_MAX_ARRAY_LENGTH =>
+        {
+            Some(format!(
+                "has a fixed length greater than {SERDE_MAX_ARRAY_LENGTH}",
+            ))
+        }
+        schema::TypeVariant::Array(inner_type, _) => underivable_reason(
+            derives,
+            imports,
+            namespace,
+            &inner_type.variant,
+            derivable_trait,
+        ),
+        schema::TypeVariant::Map(key_type, value_type) => underivable_reason(
+            derives,
+            imports,
+            namespace,
+            &key_type.variant,
+            derivable_trait,
+        )
+        .or_else(|| {
+            underivable_reason(
+                derives,
+                imports,
+                namespace,
+                &value_type.variant,
+                derivable_trait,
+            )
+        }),
+        schema::TypeVariant::Custom(import, name, _)
+        | schema::TypeVariant::Newtype(import, name, _) => {
+            let key = (
+                type_namespace(imports, namespace, import.as_ref()),
+                name.clone(),
+            );
+
+            if derives.get(&key).is_some_and(|traits| {
+                traits
+                    .iter()
+                    .any(|(other_trait, _)| other_trait.name == derivable_trait.name)
+            }) {
+                None
+            } else {
+                Some(format!(
+                    "refers to {}, which doesn't derive it",
+                    name.code_str()
+                ))
+            }
+        }
+        schema::TypeVariant::Bool
+        | schema::TypeVariant::Bytes(_)
+        | schema::TypeVariant::F32
+        | schema::TypeVariant::F64
+        | schema::TypeVariant::S32
+        | schema::TypeVariant::S64
+        | schema::TypeVariant::String
+        | schema::TypeVariant::U32
+        | schema::TypeVariant::U64
+        | schema::TypeVariant::U8
+        | schema::TypeVariant::Unit => None,
+    }
+}
+
+// Generate Rust code from a schema and its transitive dependencies.
+pub fn generate(
+    typical_version: &str,
+    schemas: &BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>,
+) -> Result<String, Vec<Error>> {
+    generate_with_options(typical_version, schemas, &Options::default())
+}
+
+// Generate Rust code from a schema and its transitive dependencies, customized by the given options.
+// The schemas are assumed to be valid, and the options and requested derives are checked with
+// `validate` first.
+#[allow(clippy::too_many_lines)]
+pub fn generate_with_options(
+    typical_version: &str,
+    schemas: &BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>,
+    options: &Options,
+) -> Result<String, Vec<Error>> {
+    validate(schemas, options)?;
+
+    // Replace the generic declarations with their instantiations [ref:monomorphization]. The
+    // `unwrap` is safe since the schemas are assumed to be valid.
+    let mut schemas = monomorphize(schemas).unwrap();
+
+    // Apply the `@rust.rename` attributes.
+    for (schema, _, _) in schemas.values_mut() {
+        apply_renames(schema, "rust");
+    }
+    let schemas = &schemas;
+
+    // Determine which types borrow from the serialized message, if borrowed types were requested.
+    let borrowing = if options.borrowed {
+        Some(borrowing_declarations(schemas))
+    } else {
+        None
+    };
+
+    // Construct a tree of modules and schemas. We start with an empty tree.
+    let mut tree = Module {
+        children: BTreeMap::new(),
+        schema: schema::Schema {
+            comment: vec![],
+            imports: BTreeMap::new(),
+            constants: vec![],
+            declarations: vec![],
+            services: vec![],
+        },
+    };
+
+    // Populate the tree with all the schemas.
+    for (namespace, (schema, _, _)) in schemas {
+        insert_schema(&mut tree, namespace, schema);
+    }
+
+    // Write the code.
+    let mut buffer = String::new();
+
+    if !tree.children.is_empty()
+        || !tree.schema.constants.is_empty()
+        || !tree.schema.declarations.is_empty()
+        || !tree.schema.services.is_empty()
+    {
+        // The `unwrap` is safe because the `std::fmt::Write` impl for `String` is infallible.
+        writeln!(
+            &mut buffer,
+            "\
+// This file was automatically generated by Typical {typical_version}.
+// Visit https://github.com/stepchowfun/typical for more information.
+",
+        )
+        .unwrap();
+
+        if options.no_std {
+            // The `unwrap` is safe because the `std::fmt::Write` impl for `String` is infallible.
+            writeln!(
+                &mut buffer,
+                "\
+use {{
+    ::alloc::vec::Vec,
+    core::{{cmp::min, mem::transmute}},
+    io::{{BufRead, Error, ErrorKind, Write}},
+}};
+
+{NO_STD_IO}",
+            )
+            .unwrap();
+        } else {
+            // The `unwrap` is safe because the `std::fmt::Write` impl for `String` is infallible.
+            writeln!(
+                &mut buffer,
+                "\
+use std::{{
+    cmp::min,
+    io::{{self, BufRead, Error, ErrorKind, Write}},
+    mem::transmute,
+}};",
+            )
+            .unwrap();
+        }
+
+        // The `unwrap` is safe because the `std::fmt::Write` impl for `String` is infallible.
+        // For functions that take abstract parameters which implement `std::io::Read` or
+        // `std::io::Write`, it's idiomatic to consume the reader or writer rather than borrowing
+        // them (https://rust-lang.github.io/api-guidelines/interoperability.html
+        // #generic-readerwriter-functions-take-r-read-and-w-write-by-value-c-rw-value). However,
+        // we borrow them anyway since that allows us to pass the reference (after reborrowing)
+        // to recursive calls, rather than building up and following chains of references.
+        writeln!(
+            &mut buffer,
+            "
+const MISSING_FIELDS_ERROR_MESSAGE: &str = \"Struct missing one or more required field(s).\";
+
+#[allow(dead_code)]
+const INTEGER_OUT_OF_RANGE_ERROR_MESSAGE: &str = \"Integer out of range.\";
+
+#[allow(dead_code)]
+const WRONG_LENGTH_ERROR_MESSAGE: &str = \"Array or bytes of the wrong length.\";
+
+#[allow(dead_code)]
+const UNKNOWN_METHOD_ERROR_MESSAGE: &str = \"Unknown method.\";
+
+pub trait Serialize {{
+    fn size(&self) -> usize;
+
+    fn serialize<T: Write>(&self, writer: T) -> io::Result<()>;
+}}
+
+pub trait Deserialize: Sized {{
+    fn deserialize<T: BufRead>(reader: T) -> io::Result<Self>;
+}}",
+        )
+        .unwrap();
+
+        if options.borrowed {
+            // The `unwrap` is safe because the `std::fmt::Write` impl for `String` is infallible.
+            writeln!(&mut buffer, "\n{DESERIALIZE_BORROWED_TRAIT}").unwrap();
+        }
+
+        if options.async_io {
+            // The `unwrap` is safe because the `std::fmt::Write` impl for `String` is infallible.
+            writeln!(&mut buffer, "\n{ASYNC_TRAITS}").unwrap();
+        }
+
+        if options.builders {
+            // The `unwrap` is safe because the `std::fmt::Write` impl for `String` is infallible.
+            writeln!(&mut buffer, "\n{UNSET_TYPE}").unwrap();
+        }
+
+        // The `unwrap` is safe because the `std::fmt::Write` impl for `String` is infallible.
+        writeln!(
+            &mut buffer,
+            "
+pub trait Transport {{
+    fn call(&mut self, method: u64, request: Vec<u8>) -> io::Result<Vec<u8>>;
+}}
+
+fn zigzag_encode(value: i64) -> u64 {{
+    unsafe {{ transmute::<i64, u64>(value >> 63_u32) ^ transmute::<i64, u64>(value << 1_u32) }}
+}}
+
+fn zigzag_decode(value: u64) -> i64 {{
+    unsafe {{ transmute::<u64, i64>(value >> 1_u32) ^ -transmute::<u64, i64>(value & 1) }}
+}}
+
+fn varint_size_from_value(value: u64) -> usize {{
+    match value {{
+        0_u64..=127_u64 => 1,
+        128_u64..=16_511_u64 => 2,
+        16_512_u64..=2_113_663_u64 => 3,
+        2_113_664_u64..=270_549_119_u64 => 4,
+        270_549_120_u64..=34_630_287_487_u64 => 5,
+        34_630_287_488_u64..=4_432_676_798_591_u64 => 6,
+        4_432_676_798_592_u64..=567_382_630_219_903_u64 => 7,
+        567_382_630_219_904_u64..=72_624_976_668_147_839_u64 => 8,
+        72_624_976_668_147_840_u64..=18_446_744_073_709_551_615_u64 => 9,
+    }}
+}}
+
+fn varint_size_from_first_byte(first_byte: u8) -> u32 {{
+    first_byte.trailing_zeros() + 1
+}}
+
+fn serialize_varint<T: Write>(mut value: u64, writer: &mut T) -> io::Result<()> {{
+    match value {{
+        0_u64..=127_u64 => writer.write_all(&[((value << 1_u32) as u8) | 0b0000_0001]),
+        128_u64..=16_511_u64 => {{
+            value -= 128_u64;
+            writer.write_all(&[
+                ((value << 2_u32) as u8) | 0b0000_0010,
+                (value >> 6_u32) as u8,
+            ])
+        }}
+        16_512_u64..=2_113_663_u64 => {{
+            value -= 16_512_u64;
+            writer.write_all(&[
+                ((value << 3_u32) as u8) | 0b0000_0100,
+                (value >> 5_u32) as u8,
+                (value >> 13_u32) as u8,
+            ])
+        }}
+        2_113_664_u64..=270_549_119_u64 => {{
+            value -= 2_113_664_u64;
+            writer.write_all(&[
+                ((value << 4_u32) as u8) | 0b0000_1000,
+                (value >> 4_u32) as u8,
+                (value >> 12_u32) as u8,
+                (value >> 20_u32) as u8,
+            ])
+        }}
+        270_549_120_u64..=34_630_287_487_u64 => {{
+            value -= 270_549_120_u64;
+            writer.write_all(&[
+                ((value << 5_u32) as u8) | 0b0001_0000,
+                (value >> 3_u32) as u8,
+                (value >> 11_u32) as u8,
+                (value >> 19_u32) as u8,
+                (value >> 27_u32) as u8,
+            ])
+        }}
+        34_630_287_488_u64..=4_432_676_798_591_u64 => {{
+            value -= 34_630_287_488_u64;
+            writer.write_all(&[
+                ((value << 6_u32) as u8) | 0b0010_0000,
+                (value >> 2_u32) as u8,
+                (value >> 10_u32) as u8,
+                (value >> 18_u32) as u8,
+                (value >> 26_u32) as u8,
+                (value >> 34_u32) as u8,
+            ])
+        }}
+        4_432_676_798_592_u64..=567_382_630_219_903_u64 => {{
+            value -= 4_432_676_798_592_u64;
+            writer.write_all(&[
+                ((value << 7_u32) as u8) | 0b0100_0000,
+                (value >> 1_u32) as u8,
+                (value >> 9_u32) as u8,
+                (value >> 17_u32) as u8,
+                (value >> 25_u32) as u8,
+                (value >> 33_u32) as u8,
+                (value >> 41_u32) as u8,
+            ])
+        }}
+        567_382_630_219_904_u64..=72_624_976_668_147_839_u64 => {{
+            value -= 567_382_630_219_904_u64;
+            writer.write_all(&[
+                0b1000_0000,
+                value as u8,
+                (value >> 8_u32) as u8,
+                (value >> 16_u32) as u8,
+                (value >> 24_u32) as u8,
+                (value >> 32_u32) as u8,
+                (value >> 40_u32) as u8,
+                (value >> 48_u32) as u8,
+            ])
+        }}
+        72_624_976_668_147_840_u64..=18_446_744_073_709_551_615_u64 => {{
+            value -= 72_624_976_668_147_840_u64;
+            writer.write_all(&[
+                0b0000_0000,
+                value as u8,
+                (value >> 8_u32) as u8,
+                (value >> 16_u32) as u8,
+                (value >> 24_u32) as u8,
+                (value >> 32_u32) as u8,
+                (value >> 40_u32) as u8,
+                (value >> 48_u32) as u8,
+                (value >> 56_u32) as u8,
+            ])
+        }}
+    }}
+}}
+
+fn deserialize_varint<T: BufRead>(reader: &mut T) -> io::Result<u64> {{
+    let mut first_byte_buffer = [0; 1];
+    reader.read_exact(&mut first_byte_buffer[..])?;
+    let first_byte = first_byte_buffer[0];
+    let size_minus_one = first_byte.trailing_zeros();
+
+    let mut remaining_bytes_buffer = [0; 8];
+    reader.read_exact(&mut remaining_bytes_buffer[0..size_minus_one as usize])?;
+    let remaining_bytes_value = u64::from_le_bytes(remaining_bytes_buffer);
+
+    match size_minus_one {{
+        0 => Ok(u64::from(first_byte >> 1_u32)),
+        1 => Ok(128_u64 + u64::from(first_byte >> 2_u32) + (remaining_bytes_value << 6_u32)),
+        2 => Ok(16_512_u64 + u64::from(first_byte >> 3_u32) + (remaining_bytes_value << 5_u32)),
+        3 => Ok(2_113_664_u64 + u64::from(first_byte >> 4_u32) + (remaining_bytes_value << 4_u32)),
+        4 => {{
+            Ok(270_549_120_u64 + u64::from(first_byte >> 5_u32) + (remaining_bytes_value << 3_u32))
+        }}
+        5 => Ok(34_630_287_488_u64
+            + u64::from(first_byte >> 6_u32)
+            + (remaining_bytes_value << 2_u32)),
+        6 => Ok(4_432_676_798_592_u64
+            + u64::from(first_byte >> 7_u32)
+            + (remaining_bytes_value << 1_u32)),
+        7 => Ok(567_382_630_219_904_u64 + remaining_bytes_value),
+        _ => Ok(72_624_976_668_147_840_u64.wrapping_add(remaining_bytes_value)),
+    }}
+}}
+
+fn field_header_size(index: u64, payload_size: usize, integer_encoded: bool) -> usize {{
+    match payload_size {{
+        0 => varint_size_from_value((index << 2_u32) | 0b00),
+        8 => varint_size_from_value((index << 2_u32) | 0b01),
+        size => {{
+            if integer_encoded {{
+                varint_size_from_value((index << 2_u32) | 0b10)
+            }} else {{
+                varint_size_from_value((index << 2_u32) | 0b11)
+                    + varint_size_from_value(size as u64)
+            }}
+        }}
+    }}
+}}
+
+fn serialize_field_header<T: Write>(
+    writer: &mut T,
+    index: u64,
+    payload_size: usize,
+    integer_encoded: bool,
+) -> io::Result<()> {{
+    match payload_size {{
+        0 => serialize_varint((index << 2_u32) | 0b00, writer),
+        8 => serialize_varint((index << 2_u32) | 0b01, writer),
+        size => {{
+            if integer_encoded {{
+                serialize_varint((index << 2_u32) | 0b10, writer)
+            }} else {{
                 serialize_varint((index << 2_u32) | 0b11, writer)?;
                 serialize_varint(size as u64, writer)
             }}
@@ -359,8 +1757,19 @@ fn deserialize_field_header<T: BufRead>(reader: &mut T) -> io::Result<(u64, usiz
     }};
 
     Ok((index, size))
-}}
+}}",
+        )
+        .unwrap();
 
+        // The `unwrap`s are safe because the `std::fmt::Write` impl for `String` is infallible.
+        // Borrowed types don't need to skip unrecognized fields, since they split the input into
+        // slices for fields up front [ref:borrowed_types].
+        if options.borrowed {
+            writeln!(&mut buffer, "\n{SPLIT_FUNCTION}").unwrap();
+        } else {
+            writeln!(
+                &mut buffer,
+                "
 fn skip<T: BufRead>(reader: &mut T, mut amount: usize) -> io::Result<()> {{
     while amount > 0 {{
         let buffer = reader.fill_buf()?;
@@ -370,8 +1779,15 @@ fn skip<T: BufRead>(reader: &mut T, mut amount: usize) -> io::Result<()> {{
     }}
 
     Ok(())
-}}
+}}",
+            )
+            .unwrap();
+        }
 
+        // The `unwrap` is safe because the `std::fmt::Write` impl for `String` is infallible.
+        writeln!(
+            &mut buffer,
+            "
 fn finish<T: BufRead>(reader: &mut T) -> io::Result<()> {{
     loop {{
         let buffer = reader.fill_buf()?;
@@ -387,6 +1803,16 @@ fn finish<T: BufRead>(reader: &mut T) -> io::Result<()> {{
         )
         .unwrap();
 
+        if options.async_io {
+            // The `unwrap` is safe because the `std::fmt::Write` impl for `String` is infallible.
+            writeln!(&mut buffer, "\n{ASYNC_FUNCTIONS}").unwrap();
+        }
+
+        if options.borrowed {
+            // The `unwrap` is safe because the `std::fmt::Write` impl for `String` is infallible.
+            writeln!(&mut buffer, "\n{ARRAY_VIEW}").unwrap();
+        }
+
         // The `unwrap` is safe because the `std::fmt::Write` impl for `String` is infallible.
         writeln!(&mut buffer).unwrap();
 
@@ -394,6 +1820,14 @@ fn finish<T: BufRead>(reader: &mut T) -> io::Result<()> {{
         write_module_contents(
             &mut buffer,
             0,
+            options,
+            Context {
+                borrowing: borrowing.as_ref(),
+                no_std: options.no_std,
+                derives: &options.derives,
+                asynchronous: false,
+                depth: 0,
+            },
             &schema::Namespace { components: vec![] },
             &tree.children,
             &tree.schema,
@@ -401,7 +1835,7 @@ fn finish<T: BufRead>(reader: &mut T) -> io::Result<()> {{
         .unwrap();
     }
 
-    buffer
+    Ok(buffer)
 }
 
 // Insert a schema into a module.
@@ -423,7 +1857,9 @@ fn insert_schema(module: &mut Module, namespace: &schema::Namespace, schema: &sc
                 schema: schema::Schema {
                     comment: vec![],
                     imports: BTreeMap::new(),
+                    constants: vec![],
                     declarations: vec![],
+                    services: vec![],
                 },
             };
 
@@ -442,10 +1878,125 @@ fn insert_schema(module: &mut Module, namespace: &schema::Namespace, schema: &sc
     }
 }
 
+// Determine which declarations borrow from the serialized message [ref:borrowed_types].
+fn borrowing_declarations(
+    schemas: &BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>,
+) -> Borrowing {
+    let mut borrowing = Borrowing::new();
+
+    // Declarations can refer to each other, so iterate until nothing changes.
+    loop {
+        let mut changed = false;
+
+        for (namespace, (schema, _, _)) in schemas {
+            // The `unwrap` is safe due to [ref:namespace_populated].
+            let imports = schema
+                .imports
+                .iter()
+                .map(|(name, import)| (name.clone(), import.namespace.clone().unwrap()))
+                .collect::<BTreeMap<_, _>>();
+
+            for declaration in &schema.declarations {
+                let key = (namespace.clone(), declaration.name.clone());
+
+                if borrowing.contains(&key) {
+                    continue;
+                }
+
+                let borrows_type = |r#type: &schema::Type| {
+                    borrows(&borrowing, &imports, namespace, &r#type.variant)
+                };
+
+                if match &declaration.variant {
+                    schema::DeclarationVariant::Struct | schema::DeclarationVariant::Choice => {
+                        declaration
+                            .fields
+                            .iter()
+                            .any(|field| borrows_type(&field.r#type))
+                    }
+                    schema::DeclarationVariant::Newtype(underlying_type) => {
+                        borrows_type(underlying_type)
+                    }
+                } {
+                    borrowing.insert(key);
+                    changed = true;
+                }
+            }
+        }
+
+        if !changed {
+            return borrowing;
+        }
+    }
+}
+
+// Determine whether the `In` version of a type borrows from the serialized message
+// [ref:borrowed_types].
+fn borrows(
+    borrowing: &Borrowing,
+    imports: &BTreeMap<Identifier, schema::Namespace>,
+    namespace: &schema::Namespace,
+    type_variant: &schema::TypeVariant,
+) -> bool {
+    match type_variant {
+        schema::TypeVariant::Array(_, None)
+        | schema::TypeVariant::Bytes(None)
+        | schema::TypeVariant::String => true,
+        schema::TypeVariant::Array(inner_type, Some(_)) => {
+            borrows(borrowing, imports, namespace, &inner_type.variant)
+        }
+        schema::TypeVariant::Custom(import, name, _)
+        | schema::TypeVariant::Newtype(import, name, _) => borrowing.contains(&(
+            type_namespace(imports, namespace, import.as_ref()),
+            name.clone(),
+        )),
+        schema::TypeVariant::Map(key_type, value_type) => {
+            borrows(borrowing, imports, namespace, &key_type.variant)
+                || borrows(borrowing, imports, namespace, &value_type.variant)
+        }
+        schema::TypeVariant::Bool
+        | schema::TypeVariant::Bytes(Some(_))
+        | schema::TypeVariant::F32
+        | schema::TypeVariant::F64
+        | schema::TypeVariant::S32
+        | schema::TypeVariant::S64
+        | schema::TypeVariant::U32
+        | schema::TypeVariant::U64
+        | schema::TypeVariant::U8
+        | schema::TypeVariant::Unit => false,
+    }
+}
+
+// Determine whether the `In` version of a declaration in the given namespace borrows from the
+// serialized message [ref:borrowed_types]. This is always `false` for ordinary `In` types.
+fn declaration_borrows(
+    borrowing: Option<&Borrowing>,
+    namespace: &schema::Namespace,
+    name: &Identifier,
+) -> bool {
+    borrowing.is_some_and(|borrowing| borrowing.contains(&(namespace.clone(), name.clone())))
+}
+
+// Determine the namespace of a user-defined type referenced from the given namespace.
+fn type_namespace(
+    imports: &BTreeMap<Identifier, schema::Namespace>,
+    namespace: &schema::Namespace,
+    import: Option<&Identifier>,
+) -> schema::Namespace {
+    schema::Namespace {
+        components: import.map_or_else(
+            || namespace.components.clone(),
+            |import| imports[import].components.clone(),
+        ),
+    }
+}
+
 // Write a module, including a trailing line break.
 fn write_module<T: Write>(
     buffer: &mut T,
     indentation: usize,
+    options: &Options,
+    context: Context,
     namespace: &schema::Namespace,
     name: &Identifier,
     module: &Module,
@@ -455,12 +2006,29 @@ fn write_module<T: Write>(
     write_identifier(buffer, name, Snake, None)?;
     writeln!(buffer, " {{")?;
 
+    if !module.schema.comment.is_empty() {
+        write_doc_comment(buffer, indentation + 1, "//!", &module.schema.comment)?;
+
+        if !module.children.is_empty()
+            || !module.schema.constants.is_empty()
+            || !module.schema.declarations.is_empty()
+            || !module.schema.services.is_empty()
+        {
+            writeln!(buffer)?;
+        }
+    }
+
     let mut new_namespace = namespace.clone();
     new_namespace.components.push(name.clone());
 
     write_module_contents(
         buffer,
         indentation + 1,
+        options,
+        Context {
+            depth: context.depth + 1,
+            ..context
+        },
         &new_namespace,
         &module.children,
         &module.schema,
@@ -476,21 +2044,32 @@ fn write_module<T: Write>(
 fn write_module_contents<T: Write>(
     buffer: &mut T,
     indentation: usize,
+    options: &Options,
+    context: Context,
     namespace: &schema::Namespace,
     children: &BTreeMap<Identifier, Module>,
     schema: &schema::Schema,
 ) -> Result<(), fmt::Error> {
-    let schema_empty = schema.declarations.is_empty();
+    let schema_empty =
+        schema.constants.is_empty() && schema.declarations.is_empty() && schema.services.is_empty();
 
     for (i, (child_name, child)) in children.iter().enumerate() {
-        write_module(buffer, indentation, namespace, child_name, child)?;
+        write_module(
+            buffer,
+            indentation,
+            options,
+            context,
+            namespace,
+            child_name,
+            child,
+        )?;
 
         if i < children.len() - 1 || !schema_empty {
             writeln!(buffer)?;
         }
     }
 
-    write_schema(buffer, indentation, namespace, schema)?;
+    write_schema(buffer, indentation, options, context, namespace, schema)?;
 
     Ok(())
 }
@@ -500,6 +2079,8 @@ fn write_module_contents<T: Write>(
 fn write_schema<T: Write>(
     buffer: &mut T,
     indentation: usize,
+    options: &Options,
+    context: Context,
     namespace: &schema::Namespace,
     schema: &schema::Schema,
 ) -> Result<(), fmt::Error> {
@@ -510,9 +2091,34 @@ fn write_schema<T: Write>(
         imports.insert(name.clone(), import.namespace.clone().unwrap());
     }
 
+    let io = context.io();
+
+    // Without `std`, the types from `alloc` aren't in the prelude [ref:no_std].
+    if context.no_std && !(schema.declarations.is_empty() && schema.services.is_empty()) {
+        write_indentation(buffer, indentation)?;
+        writeln!(buffer, "#[allow(unused_imports)]")?;
+        write_indentation(buffer, indentation)?;
+        writeln!(
+            buffer,
+            "use ::alloc::{{boxed::Box, string::String, vec::Vec}};"
+        )?;
+        writeln!(buffer)?;
+    }
+
+    // Write the constants.
+    for constant in &schema.constants {
+        write_constant(buffer, indentation, constant)?;
+    }
+
+    if !schema.constants.is_empty() && !schema.declarations.is_empty() {
+        writeln!(buffer)?;
+    }
+
     // Write the declarations.
     let mut iter = schema.declarations.iter().peekable();
     while let Some(declaration) = iter.next() {
+        let borrows = declaration_borrows(context.borrowing, namespace, &declaration.name);
+
         match &declaration.variant {
             schema::DeclarationVariant::Struct => {
                 write_struct(
@@ -520,9 +2126,9 @@ fn write_schema<T: Write>(
                     indentation,
                     &imports,
                     namespace,
-                    &declaration.name,
-                    &declaration.fields,
+                    declaration,
                     Atlas,
+                    context,
                 )?;
 
                 writeln!(buffer)?;
@@ -532,9 +2138,9 @@ fn write_schema<T: Write>(
                     indentation,
                     &imports,
                     namespace,
-                    &declaration.name,
-                    &declaration.fields,
+                    declaration,
                     Out,
+                    context,
                 )?;
 
                 writeln!(buffer)?;
@@ -544,9 +2150,9 @@ fn write_schema<T: Write>(
                     indentation,
                     &imports,
                     namespace,
-                    &declaration.name,
-                    &declaration.fields,
+                    declaration,
                     In,
+                    context,
                 )?;
 
                 writeln!(buffer)?;
@@ -559,77 +2165,78 @@ fn write_schema<T: Write>(
                 writeln!(buffer, " {{")?;
                 write_size_function(buffer, indentation + 1)?;
                 writeln!(buffer)?;
-                write_serialize_function(buffer, indentation + 1)?;
+                write_serialize_function(buffer, indentation + 1, context)?;
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "}}")?;
 
                 writeln!(buffer)?;
 
-                write_indentation(buffer, indentation)?;
-                write!(buffer, "impl ")?;
-                write_supers(buffer, indentation)?;
-                write!(buffer, "Deserialize for ")?;
-                write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
-                writeln!(buffer, " {{")?;
-                write_deserialize_function(buffer, indentation + 1)?;
-                write_indentation(buffer, indentation)?;
-                writeln!(buffer, "}}")?;
+                write_deserialize_impls(buffer, indentation, namespace, declaration, context)?;
 
                 writeln!(buffer)?;
 
-                write_indentation(buffer, indentation)?;
-                write!(buffer, "impl From<")?;
-                write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
-                write!(buffer, "> for ")?;
-                write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
-                writeln!(buffer, " {{")?;
-                write_indentation(buffer, indentation + 1)?;
-                write!(buffer, "fn from(message: ")?;
-                write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
-                writeln!(buffer, ") -> Self {{")?;
-                write_indentation(buffer, indentation + 2)?;
-                write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
-                writeln!(buffer, " {{")?;
-                for field in &declaration.fields {
-                    match field.rule {
-                        schema::Rule::Asymmetric => {
-                            write_indentation(buffer, indentation + 3)?;
-                            write_identifier(buffer, &field.name, Snake, None)?;
-                            write!(buffer, ": Some(message.")?;
-                            write_identifier(buffer, &field.name, Snake, None)?;
-                        }
-                        schema::Rule::Optional => {
-                            write_indentation(buffer, indentation + 3)?;
-                            write_identifier(buffer, &field.name, Snake, None)?;
-                            write!(buffer, ": message.")?;
-                            write_identifier(buffer, &field.name, Snake, None)?;
-                            write!(buffer, ".map(|payload| payload")?;
-                        }
-                        schema::Rule::Required => {
-                            write_indentation(buffer, indentation + 3)?;
-                            write_identifier(buffer, &field.name, Snake, None)?;
-                            write!(buffer, ": message.")?;
-                            write_identifier(buffer, &field.name, Snake, None)?;
-                        }
-                    }
-                    write_into_invocation(buffer, &field.r#type.variant)?;
-                    match field.rule {
-                        schema::Rule::Asymmetric | schema::Rule::Optional => {
-                            writeln!(buffer, "),")?;
+                if options.async_io {
+                    write_async_impls(buffer, indentation, declaration)?;
+
+                    writeln!(buffer)?;
+                }
+
+                // Borrowed types can't be constructed from owned ones [ref:borrowed_types].
+                if !borrows {
+                    write_indentation(buffer, indentation)?;
+                    write!(buffer, "impl From<")?;
+                    write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
+                    write!(buffer, "> for ")?;
+                    write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
+                    writeln!(buffer, " {{")?;
+                    write_indentation(buffer, indentation + 1)?;
+                    write!(buffer, "fn from(message: ")?;
+                    write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
+                    writeln!(buffer, ") -> Self {{")?;
+                    write_indentation(buffer, indentation + 2)?;
+                    write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
+                    writeln!(buffer, " {{")?;
+                    for field in &declaration.fields {
+                        match field.rule {
+                            schema::Rule::Asymmetric => {
+                                write_indentation(buffer, indentation + 3)?;
+                                write_identifier(buffer, &field.name, Snake, None)?;
+                                write!(buffer, ": Some(message.")?;
+                                write_identifier(buffer, &field.name, Snake, None)?;
+                            }
+                            schema::Rule::Optional => {
+                                write_indentation(buffer, indentation + 3)?;
+                                write_identifier(buffer, &field.name, Snake, None)?;
+                                write!(buffer, ": message.")?;
+                                write_identifier(buffer, &field.name, Snake, None)?;
+                                write!(buffer, ".map(|payload| payload")?;
+                            }
+                            schema::Rule::Required => {
+                                write_indentation(buffer, indentation + 3)?;
+                                write_identifier(buffer, &field.name, Snake, None)?;
+                                write!(buffer, ": message.")?;
+                                write_identifier(buffer, &field.name, Snake, None)?;
+                            }
                         }
-                        schema::Rule::Required => {
-                            writeln!(buffer, ",")?;
+                        write_into_invocation(buffer, &field.r#type.variant, context)?;
+                        match field.rule {
+                            schema::Rule::Asymmetric | schema::Rule::Optional => {
+                                writeln!(buffer, "),")?;
+                            }
+                            schema::Rule::Required => {
+                                writeln!(buffer, ",")?;
+                            }
                         }
                     }
-                }
-                write_indentation(buffer, indentation + 2)?;
-                writeln!(buffer, "}}")?;
-                write_indentation(buffer, indentation + 1)?;
-                writeln!(buffer, "}}")?;
-                write_indentation(buffer, indentation)?;
-                writeln!(buffer, "}}")?;
+                    write_indentation(buffer, indentation + 2)?;
+                    writeln!(buffer, "}}")?;
+                    write_indentation(buffer, indentation + 1)?;
+                    writeln!(buffer, "}}")?;
+                    write_indentation(buffer, indentation)?;
+                    writeln!(buffer, "}}")?;
 
-                writeln!(buffer)?;
+                    writeln!(buffer)?;
+                }
 
                 write_indentation(buffer, indentation)?;
                 write!(buffer, "impl ")?;
@@ -734,230 +2341,259 @@ fn write_schema<T: Write>(
                 write_indentation(buffer, indentation + 1)?;
                 writeln!(buffer, "}}")?;
                 writeln!(buffer)?;
-                write_indentation(buffer, indentation + 1)?;
-                writeln!(buffer, "pub fn serialize_with_atlas<T: ::std::io::Write>(")?;
-                write_indentation(buffer, indentation + 2)?;
-                writeln!(buffer, "&self,")?;
-                write_indentation(buffer, indentation + 2)?;
-                writeln!(buffer, "writer: &mut T,")?;
-                write_indentation(buffer, indentation + 2)?;
-                write!(buffer, "atlas: &")?;
-                write_identifier(buffer, &declaration.name, Pascal, Some(Atlas))?;
-                writeln!(buffer, ",")?;
-                write_indentation(buffer, indentation + 1)?;
-                writeln!(buffer, ") -> ::std::io::Result<()> {{")?;
-                for field in &declaration.fields {
-                    match field.rule {
-                        schema::Rule::Asymmetric | schema::Rule::Required => {
-                            write_indentation(buffer, indentation + 2)?;
-                            writeln!(buffer, "{{")?;
-                            write_indentation(buffer, indentation + 3)?;
-                            write!(buffer, "let payload = &self.")?;
-                            write_identifier(buffer, &field.name, Snake, None)?;
-                            writeln!(buffer, ";")?;
-                            write_indentation(buffer, indentation + 3)?;
-                            write!(buffer, "let payload_atlas = &atlas.")?;
-                            write_identifier(buffer, &field.name, Snake, None)?;
-                            writeln!(buffer, ";")?;
+                for context in context.io_variants(options.async_io) {
+                    write_indentation(buffer, indentation + 1)?;
+                    writeln!(
+                        buffer,
+                        "pub {}fn serialize_with_atlas{}<T: {}>(",
+                        context.async_keyword(),
+                        context.suffix(),
+                        context.writer_bound(),
+                    )?;
+                    write_indentation(buffer, indentation + 2)?;
+                    writeln!(buffer, "&self,")?;
+                    write_indentation(buffer, indentation + 2)?;
+                    writeln!(buffer, "writer: &mut T,")?;
+                    write_indentation(buffer, indentation + 2)?;
+                    write!(buffer, "atlas: &")?;
+                    write_identifier(buffer, &declaration.name, Pascal, Some(Atlas))?;
+                    writeln!(buffer, ",")?;
+                    write_indentation(buffer, indentation + 1)?;
+                    writeln!(buffer, ") -> {io}::Result<()> {{")?;
+                    for field in &declaration.fields {
+                        match field.rule {
+                            schema::Rule::Asymmetric | schema::Rule::Required => {
+                                write_indentation(buffer, indentation + 2)?;
+                                writeln!(buffer, "{{")?;
+                                write_indentation(buffer, indentation + 3)?;
+                                write!(buffer, "let payload = &self.")?;
+                                write_identifier(buffer, &field.name, Snake, None)?;
+                                writeln!(buffer, ";")?;
+                                write_indentation(buffer, indentation + 3)?;
+                                write!(buffer, "let payload_atlas = &atlas.")?;
+                                write_identifier(buffer, &field.name, Snake, None)?;
+                                writeln!(buffer, ";")?;
+                            }
+                            schema::Rule::Optional => {
+                                write_indentation(buffer, indentation + 2)?;
+                                write!(
+                                    buffer,
+                                    "if let (Some(payload), Some(payload_atlas)) = (&self.",
+                                )?;
+                                write_identifier(buffer, &field.name, Snake, None)?;
+                                write!(buffer, ", &atlas.")?;
+                                write_identifier(buffer, &field.name, Snake, None)?;
+                                writeln!(buffer, ") {{")?;
+                            }
                         }
-                        schema::Rule::Optional => {
+                        write_indentation(buffer, indentation + 3)?;
+                        write_supers(buffer, indentation)?;
+                        write!(
+                            buffer,
+                            "serialize_field_header{}(writer, {}_u64, ",
+                            context.suffix(),
+                            field.index,
+                        )?;
+                        write_atlas_lookup(buffer, &field.r#type.variant)?;
+                        writeln!(
+                            buffer,
+                            ", {}){}?;",
+                            integer_encoded(&field.r#type),
+                            context.wait(),
+                        )?;
+                        write_serialization_invocation(
+                            buffer,
+                            indentation + 3,
+                            indentation,
+                            &field.r#type.variant,
+                            true,
+                            context,
+                        )?;
+                        write_indentation(buffer, indentation + 2)?;
+                        writeln!(buffer, "}}")?;
+                        writeln!(buffer)?;
+                    }
+                    write_indentation(buffer, indentation + 2)?;
+                    writeln!(buffer, "Ok(())")?;
+                    write_indentation(buffer, indentation + 1)?;
+                    writeln!(buffer, "}}")?;
+                    writeln!(buffer)?;
+                }
+                write_validate_function(buffer, indentation + 1, declaration, context)?;
+                write_indentation(buffer, indentation)?;
+                writeln!(buffer, "}}")?;
+
+                writeln!(buffer)?;
+
+                for context in context.io_variants(options.async_io) {
+                    write_deserialize_from_reader_ref_header(
+                        buffer,
+                        indentation,
+                        declaration,
+                        context,
+                        borrows,
+                    )?;
+                    if !&declaration.fields.is_empty() {
+                        for field in &declaration.fields {
                             write_indentation(buffer, indentation + 2)?;
-                            write!(
+                            write!(buffer, "let mut _")?;
+                            write_identifier(buffer, &field.name, Snake, None)?;
+                            write!(buffer, ": Option<")?;
+                            write_type(
                                 buffer,
-                                "if let (Some(payload), Some(payload_atlas)) = (&self.",
+                                &imports,
+                                namespace,
+                                &field.r#type.variant,
+                                In,
+                                context,
                             )?;
-                            write_identifier(buffer, &field.name, Snake, None)?;
-                            write!(buffer, ", &atlas.")?;
-                            write_identifier(buffer, &field.name, Snake, None)?;
-                            writeln!(buffer, ") {{")?;
+                            writeln!(buffer, "> = None;")?;
                         }
+                        writeln!(buffer)?;
                     }
+                    write_indentation(buffer, indentation + 2)?;
+                    writeln!(buffer, "loop {{")?;
                     write_indentation(buffer, indentation + 3)?;
+                    write!(buffer, "let (index, payload_size) = match ")?;
                     write_supers(buffer, indentation)?;
-                    write!(
+                    writeln!(
                         buffer,
-                        "serialize_field_header(writer, {}_u64, ",
-                        field.index,
+                        "deserialize_field_header{}(&mut *reader){} {{",
+                        context.suffix(),
+                        context.wait(),
                     )?;
-                    write_atlas_lookup(buffer, &field.r#type.variant)?;
-                    writeln!(buffer, ", {})?;", integer_encoded(&field.r#type))?;
-                    write_serialization_invocation(
+                    write_indentation(buffer, indentation + 4)?;
+                    writeln!(buffer, "Ok(header) => header,")?;
+                    write_indentation(buffer, indentation + 4)?;
+                    writeln!(buffer, "Err(err) => {{")?;
+                    write_indentation(buffer, indentation + 5)?;
+                    writeln!(
                         buffer,
-                        indentation + 3,
-                        indentation,
-                        &field.r#type.variant,
-                        true,
+                        "if let {io}::ErrorKind::UnexpectedEof = err.kind() {{",
                     )?;
-                    write_indentation(buffer, indentation + 2)?;
+                    write_indentation(buffer, indentation + 6)?;
+                    writeln!(buffer, "break;")?;
+                    write_indentation(buffer, indentation + 5)?;
                     writeln!(buffer, "}}")?;
                     writeln!(buffer)?;
-                }
-                write_indentation(buffer, indentation + 2)?;
-                writeln!(buffer, "Ok(())")?;
-                write_indentation(buffer, indentation + 1)?;
-                writeln!(buffer, "}}")?;
-                write_indentation(buffer, indentation)?;
-                writeln!(buffer, "}}")?;
-
-                writeln!(buffer)?;
-
-                write_indentation(buffer, indentation)?;
-                write!(buffer, "impl ")?;
-                write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
-                writeln!(buffer, " {{")?;
-                write_indentation(buffer, indentation + 1)?;
-                writeln!(
-                    buffer,
-                    "pub fn deserialize_from_reader_ref<T: ::std::io::BufRead>(",
-                )?;
-                write_indentation(buffer, indentation + 2)?;
-                writeln!(buffer, "reader: &mut T,")?;
-                write_indentation(buffer, indentation + 1)?;
-                writeln!(buffer, ") -> ::std::io::Result<Self> {{")?;
-                if !&declaration.fields.is_empty() {
-                    for field in &declaration.fields {
-                        write_indentation(buffer, indentation + 2)?;
-                        write!(buffer, "let mut _")?;
-                        write_identifier(buffer, &field.name, Snake, None)?;
-                        write!(buffer, ": Option<")?;
-                        write_type(buffer, &imports, namespace, &field.r#type.variant, In)?;
-                        writeln!(buffer, "> = None;")?;
-                    }
-                    writeln!(buffer)?;
-                }
-                write_indentation(buffer, indentation + 2)?;
-                writeln!(buffer, "loop {{")?;
-                write_indentation(buffer, indentation + 3)?;
-                write!(buffer, "let (index, payload_size) = match ")?;
-                write_supers(buffer, indentation)?;
-                writeln!(buffer, "deserialize_field_header(&mut *reader) {{")?;
-                write_indentation(buffer, indentation + 4)?;
-                writeln!(buffer, "Ok(header) => header,")?;
-                write_indentation(buffer, indentation + 4)?;
-                writeln!(buffer, "Err(err) => {{")?;
-                write_indentation(buffer, indentation + 5)?;
-                writeln!(
-                    buffer,
-                    "if let std::io::ErrorKind::UnexpectedEof = err.kind() {{",
-                )?;
-                write_indentation(buffer, indentation + 6)?;
-                writeln!(buffer, "break;")?;
-                write_indentation(buffer, indentation + 5)?;
-                writeln!(buffer, "}}")?;
-                writeln!(buffer)?;
-                write_indentation(buffer, indentation + 5)?;
-                writeln!(buffer, "return Err(err);")?;
-                write_indentation(buffer, indentation + 4)?;
-                writeln!(buffer, "}}")?;
-                write_indentation(buffer, indentation + 3)?;
-                writeln!(buffer, "}};")?;
-                writeln!(buffer)?;
-                write_indentation(buffer, indentation + 3)?;
-                writeln!(
-                    buffer,
-                    "let mut sub_reader = ::std::io::Read::take(&mut *reader, \
-                        payload_size as u64);",
-                )?;
-                writeln!(buffer)?;
-                write_indentation(buffer, indentation + 3)?;
-                writeln!(buffer, "match index {{")?;
-                for field in &declaration.fields {
+                    write_indentation(buffer, indentation + 5)?;
+                    writeln!(buffer, "return Err(err);")?;
                     write_indentation(buffer, indentation + 4)?;
-                    writeln!(buffer, "{} => {{", field.index)?;
-                    write_deserialization_invocation(
+                    writeln!(buffer, "}}")?;
+                    write_indentation(buffer, indentation + 3)?;
+                    writeln!(buffer, "}};")?;
+                    writeln!(buffer)?;
+                    write_sub_reader(
                         buffer,
-                        indentation + 5,
+                        indentation + 3,
                         indentation,
-                        &imports,
-                        namespace,
-                        &field.r#type.variant,
-                        true,
+                        "&mut *reader",
+                        context,
                     )?;
-                    write_indentation(buffer, indentation + 5)?;
-                    write!(buffer, "_")?;
-                    write_identifier(buffer, &field.name, Snake, None)?;
-                    writeln!(buffer, ".get_or_insert(payload);")?;
-                    write_indentation(buffer, indentation + 4)?;
+                    writeln!(buffer)?;
+                    write_indentation(buffer, indentation + 3)?;
+                    writeln!(buffer, "match index {{")?;
+                    for field in &declaration.fields {
+                        write_indentation(buffer, indentation + 4)?;
+                        writeln!(buffer, "{} => {{", field.index)?;
+                        write_deserialization_invocation(
+                            buffer,
+                            indentation + 5,
+                            indentation,
+                            &imports,
+                            namespace,
+                            &field.r#type.variant,
+                            true,
+                            context,
+                        )?;
+                        write_constraint_checks(
+                            buffer,
+                            indentation + 5,
+                            &declaration.name,
+                            field,
+                            false,
+                            context,
+                        )?;
+                        write_indentation(buffer, indentation + 5)?;
+                        write!(buffer, "_")?;
+                        write_identifier(buffer, &field.name, Snake, None)?;
+                        writeln!(buffer, ".get_or_insert(payload);")?;
+                        write_indentation(buffer, indentation + 4)?;
+                        writeln!(buffer, "}}")?;
+                    }
+                    write_skip(buffer, indentation + 4, indentation, context)?;
+                    write_indentation(buffer, indentation + 3)?;
                     writeln!(buffer, "}}")?;
-                }
-                write_indentation(buffer, indentation + 4)?;
-                writeln!(buffer, "_ => {{")?;
-                write_indentation(buffer, indentation + 5)?;
-                write_supers(buffer, indentation)?;
-                writeln!(buffer, "skip(&mut sub_reader, payload_size)?;")?;
-                write_indentation(buffer, indentation + 4)?;
-                writeln!(buffer, "}}")?;
-                write_indentation(buffer, indentation + 3)?;
-                writeln!(buffer, "}}")?;
-                write_indentation(buffer, indentation + 2)?;
-                writeln!(buffer, "}}")?;
-                writeln!(buffer)?;
-                // The logic below ensures that all required fields have been parsed.
-                // [tag:required_fields_present]
-                if declaration.fields.iter().any(|field| match field.rule {
-                    schema::Rule::Asymmetric | schema::Rule::Optional => false,
-                    schema::Rule::Required => true,
-                }) {
                     write_indentation(buffer, indentation + 2)?;
-                    write!(buffer, "if ")?;
-                    first = true;
+                    writeln!(buffer, "}}")?;
+                    writeln!(buffer)?;
+                    // The logic below ensures that all required fields have been parsed.
+                    // [tag:required_fields_present]
+                    if declaration.fields.iter().any(|field| match field.rule {
+                        schema::Rule::Asymmetric | schema::Rule::Optional => false,
+                        schema::Rule::Required => true,
+                    }) {
+                        write_indentation(buffer, indentation + 2)?;
+                        write!(buffer, "if ")?;
+                        first = true;
+                        for field in &declaration.fields {
+                            match field.rule {
+                                schema::Rule::Asymmetric | schema::Rule::Optional => {}
+                                schema::Rule::Required => {
+                                    if first {
+                                        first = false;
+                                    } else {
+                                        writeln!(buffer)?;
+                                        write_indentation(buffer, indentation + 3)?;
+                                        write!(buffer, "|| ")?;
+                                    }
+                                    write!(buffer, "_")?;
+                                    write_identifier(buffer, &field.name, Snake, None)?;
+                                    write!(buffer, ".is_none()")?;
+                                }
+                            }
+                        }
+                        writeln!(buffer, " {{")?;
+                        write_indentation(buffer, indentation + 3)?;
+                        writeln!(buffer, "return Err({io}::Error::new(")?;
+                        write_indentation(buffer, indentation + 4)?;
+                        writeln!(buffer, "{io}::ErrorKind::InvalidData,")?;
+                        write_indentation(buffer, indentation + 4)?;
+                        write_supers(buffer, indentation)?;
+                        writeln!(buffer, "MISSING_FIELDS_ERROR_MESSAGE,")?;
+                        write_indentation(buffer, indentation + 3)?;
+                        writeln!(buffer, "));")?;
+                        write_indentation(buffer, indentation + 2)?;
+                        writeln!(buffer, "}}")?;
+                        writeln!(buffer)?;
+                    }
+                    write_indentation(buffer, indentation + 2)?;
+                    write!(buffer, "Ok(")?;
+                    write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
+                    writeln!(buffer, " {{")?;
                     for field in &declaration.fields {
+                        write_indentation(buffer, indentation + 3)?;
+                        write_identifier(buffer, &field.name, Snake, None)?;
+                        write!(buffer, ": _")?;
+                        write_identifier(buffer, &field.name, Snake, None)?;
                         match field.rule {
                             schema::Rule::Asymmetric | schema::Rule::Optional => {}
                             schema::Rule::Required => {
-                                if first {
-                                    first = false;
-                                } else {
-                                    writeln!(buffer)?;
-                                    write_indentation(buffer, indentation + 3)?;
-                                    write!(buffer, "|| ")?;
-                                }
-                                write!(buffer, "_")?;
-                                write_identifier(buffer, &field.name, Snake, None)?;
-                                write!(buffer, ".is_none()")?;
+                                // This `unwrap` is safe due to [ref:required_fields_present].
+                                write!(buffer, ".unwrap()")?;
                             }
                         }
+                        writeln!(buffer, ",")?;
                     }
-                    writeln!(buffer, " {{")?;
-                    write_indentation(buffer, indentation + 3)?;
-                    writeln!(buffer, "return Err(::std::io::Error::new(")?;
-                    write_indentation(buffer, indentation + 4)?;
-                    writeln!(buffer, "::std::io::ErrorKind::InvalidData,")?;
-                    write_indentation(buffer, indentation + 4)?;
-                    write_supers(buffer, indentation)?;
-                    writeln!(buffer, "MISSING_FIELDS_ERROR_MESSAGE,")?;
-                    write_indentation(buffer, indentation + 3)?;
-                    writeln!(buffer, "));")?;
                     write_indentation(buffer, indentation + 2)?;
+                    writeln!(buffer, "}})")?;
+                    write_indentation(buffer, indentation + 1)?;
+                    writeln!(buffer, "}}")?;
+                    write_indentation(buffer, indentation)?;
                     writeln!(buffer, "}}")?;
+
                     writeln!(buffer)?;
                 }
-                write_indentation(buffer, indentation + 2)?;
-                write!(buffer, "Ok(")?;
-                write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
-                writeln!(buffer, " {{")?;
-                for field in &declaration.fields {
-                    write_indentation(buffer, indentation + 3)?;
-                    write_identifier(buffer, &field.name, Snake, None)?;
-                    write!(buffer, ": _")?;
-                    write_identifier(buffer, &field.name, Snake, None)?;
-                    match field.rule {
-                        schema::Rule::Asymmetric | schema::Rule::Optional => {}
-                        schema::Rule::Required => {
-                            // This `unwrap` is safe due to [ref:required_fields_present].
-                            write!(buffer, ".unwrap()")?;
-                        }
-                    }
-                    writeln!(buffer, ",")?;
-                }
-                write_indentation(buffer, indentation + 2)?;
-                writeln!(buffer, "}})")?;
-                write_indentation(buffer, indentation + 1)?;
-                writeln!(buffer, "}}")?;
-                write_indentation(buffer, indentation)?;
-                writeln!(buffer, "}}")?;
-
-                writeln!(buffer)?;
 
                 write_indentation(buffer, indentation)?;
                 write!(buffer, "impl ")?;
@@ -971,6 +2607,19 @@ fn write_schema<T: Write>(
                 writeln!(buffer, "}}")?;
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "}}")?;
+
+                if options.builders {
+                    writeln!(buffer)?;
+
+                    write_builder(
+                        buffer,
+                        indentation,
+                        &imports,
+                        namespace,
+                        declaration,
+                        context,
+                    )?;
+                }
             }
             schema::DeclarationVariant::Choice => {
                 write_choice(
@@ -978,9 +2627,9 @@ fn write_schema<T: Write>(
                     indentation,
                     &imports,
                     namespace,
-                    &declaration.name,
-                    &declaration.fields,
+                    declaration,
                     Atlas,
+                    context,
                 )?;
 
                 writeln!(buffer)?;
@@ -990,9 +2639,9 @@ fn write_schema<T: Write>(
                     indentation,
                     &imports,
                     namespace,
-                    &declaration.name,
-                    &declaration.fields,
+                    declaration,
                     Out,
+                    context,
                 )?;
 
                 writeln!(buffer)?;
@@ -1002,9 +2651,9 @@ fn write_schema<T: Write>(
                     indentation,
                     &imports,
                     namespace,
-                    &declaration.name,
-                    &declaration.fields,
+                    declaration,
                     In,
+                    context,
                 )?;
 
                 writeln!(buffer)?;
@@ -1017,89 +2666,90 @@ fn write_schema<T: Write>(
                 writeln!(buffer, " {{")?;
                 write_size_function(buffer, indentation + 1)?;
                 writeln!(buffer)?;
-                write_serialize_function(buffer, indentation + 1)?;
+                write_serialize_function(buffer, indentation + 1, context)?;
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "}}")?;
 
                 writeln!(buffer)?;
 
-                write_indentation(buffer, indentation)?;
-                write!(buffer, "impl ")?;
-                write_supers(buffer, indentation)?;
-                write!(buffer, "Deserialize for ")?;
-                write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
-                writeln!(buffer, " {{")?;
-                write_deserialize_function(buffer, indentation + 1)?;
-                write_indentation(buffer, indentation)?;
-                writeln!(buffer, "}}")?;
+                write_deserialize_impls(buffer, indentation, namespace, declaration, context)?;
 
                 writeln!(buffer)?;
 
-                write_indentation(buffer, indentation)?;
-                write!(buffer, "impl From<")?;
-                write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
-                write!(buffer, "> for ")?;
-                write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
-                writeln!(buffer, " {{")?;
-                write_indentation(buffer, indentation + 1)?;
-                write!(buffer, "fn from(message: ")?;
-                write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
-                writeln!(buffer, ") -> Self {{")?;
-                write_indentation(buffer, indentation + 2)?;
-                writeln!(buffer, "match message {{")?;
-                for field in &declaration.fields {
-                    write_indentation(buffer, indentation + 3)?;
+                if options.async_io {
+                    write_async_impls(buffer, indentation, declaration)?;
+
+                    writeln!(buffer)?;
+                }
+
+                // Borrowed types can't be constructed from owned ones [ref:borrowed_types].
+                if !borrows {
+                    write_indentation(buffer, indentation)?;
+                    write!(buffer, "impl From<")?;
                     write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
-                    write!(buffer, "::")?;
-                    write_identifier(buffer, &field.name, Pascal, None)?;
-                    match field.rule {
-                        schema::Rule::Asymmetric | schema::Rule::Optional => {
-                            if matches!(field.r#type.variant, schema::TypeVariant::Unit) {
-                                write!(buffer, "(fallback) => ")?;
-                            } else {
-                                write!(buffer, "(payload, fallback) => ")?;
+                    write!(buffer, "> for ")?;
+                    write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
+                    writeln!(buffer, " {{")?;
+                    write_indentation(buffer, indentation + 1)?;
+                    write!(buffer, "fn from(message: ")?;
+                    write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
+                    writeln!(buffer, ") -> Self {{")?;
+                    write_indentation(buffer, indentation + 2)?;
+                    writeln!(buffer, "match message {{")?;
+                    for field in &declaration.fields {
+                        write_indentation(buffer, indentation + 3)?;
+                        write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
+                        write!(buffer, "::")?;
+                        write_identifier(buffer, &field.name, Pascal, None)?;
+                        match field.rule {
+                            schema::Rule::Asymmetric | schema::Rule::Optional => {
+                                if matches!(field.r#type.variant, schema::TypeVariant::Unit) {
+                                    write!(buffer, "(fallback) => ")?;
+                                } else {
+                                    write!(buffer, "(payload, fallback) => ")?;
+                                }
                             }
-                        }
-                        schema::Rule::Required => {
-                            if matches!(field.r#type.variant, schema::TypeVariant::Unit) {
-                                write!(buffer, " => ")?;
-                            } else {
-                                write!(buffer, "(payload) => ")?;
+                            schema::Rule::Required => {
+                                if matches!(field.r#type.variant, schema::TypeVariant::Unit) {
+                                    write!(buffer, " => ")?;
+                                } else {
+                                    write!(buffer, "(payload) => ")?;
+                                }
                             }
                         }
-                    }
-                    write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
-                    write!(buffer, "::")?;
-                    write_identifier(buffer, &field.name, Pascal, None)?;
-                    match field.rule {
-                        schema::Rule::Asymmetric | schema::Rule::Required => {
-                            if matches!(field.r#type.variant, schema::TypeVariant::Unit) {
-                                writeln!(buffer, ",")?;
-                            } else {
-                                write!(buffer, "(payload")?;
-                                write_into_invocation(buffer, &field.r#type.variant)?;
-                                writeln!(buffer, "),")?;
+                        write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
+                        write!(buffer, "::")?;
+                        write_identifier(buffer, &field.name, Pascal, None)?;
+                        match field.rule {
+                            schema::Rule::Asymmetric | schema::Rule::Required => {
+                                if matches!(field.r#type.variant, schema::TypeVariant::Unit) {
+                                    writeln!(buffer, ",")?;
+                                } else {
+                                    write!(buffer, "(payload")?;
+                                    write_into_invocation(buffer, &field.r#type.variant, context)?;
+                                    writeln!(buffer, "),")?;
+                                }
                             }
-                        }
-                        schema::Rule::Optional => {
-                            if matches!(field.r#type.variant, schema::TypeVariant::Unit) {
-                                writeln!(buffer, "(Box::new((*fallback).into())),")?;
-                            } else {
-                                write!(buffer, "(payload")?;
-                                write_into_invocation(buffer, &field.r#type.variant)?;
-                                writeln!(buffer, ", Box::new((*fallback).into())),")?;
+                            schema::Rule::Optional => {
+                                if matches!(field.r#type.variant, schema::TypeVariant::Unit) {
+                                    writeln!(buffer, "(Box::new((*fallback).into())),")?;
+                                } else {
+                                    write!(buffer, "(payload")?;
+                                    write_into_invocation(buffer, &field.r#type.variant, context)?;
+                                    writeln!(buffer, ", Box::new((*fallback).into())),")?;
+                                }
                             }
                         }
                     }
-                }
-                write_indentation(buffer, indentation + 2)?;
-                writeln!(buffer, "}}")?;
-                write_indentation(buffer, indentation + 1)?;
-                writeln!(buffer, "}}")?;
-                write_indentation(buffer, indentation)?;
-                writeln!(buffer, "}}")?;
+                    write_indentation(buffer, indentation + 2)?;
+                    writeln!(buffer, "}}")?;
+                    write_indentation(buffer, indentation + 1)?;
+                    writeln!(buffer, "}}")?;
+                    write_indentation(buffer, indentation)?;
+                    writeln!(buffer, "}}")?;
 
-                writeln!(buffer)?;
+                    writeln!(buffer)?;
+                }
 
                 write_indentation(buffer, indentation)?;
                 write!(buffer, "impl ")?;
@@ -1192,190 +2842,239 @@ fn write_schema<T: Write>(
                 write_indentation(buffer, indentation + 1)?;
                 writeln!(buffer, "}}")?;
                 writeln!(buffer)?;
-                write_indentation(buffer, indentation + 1)?;
-                writeln!(buffer, "pub fn serialize_with_atlas<T: ::std::io::Write>(")?;
-                write_indentation(buffer, indentation + 2)?;
-                writeln!(buffer, "&self,")?;
-                write_indentation(buffer, indentation + 2)?;
-                writeln!(buffer, "writer: &mut T,")?;
-                write_indentation(buffer, indentation + 2)?;
-                write!(buffer, "atlas: &")?;
-                write_identifier(buffer, &declaration.name, Pascal, Some(Atlas))?;
-                writeln!(buffer, ",")?;
-                write_indentation(buffer, indentation + 1)?;
-                writeln!(buffer, ") -> ::std::io::Result<()> {{")?;
-                write_indentation(buffer, indentation + 2)?;
-                writeln!(buffer, "match (self, atlas) {{")?;
-                for field in &declaration.fields {
-                    write_indentation(buffer, indentation + 3)?;
-                    write!(buffer, "(")?;
-                    write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
-                    write!(buffer, "::")?;
-                    write_identifier(buffer, &field.name, Pascal, None)?;
-                    match field.rule {
-                        schema::Rule::Asymmetric | schema::Rule::Optional => {
-                            if matches!(field.r#type.variant, schema::TypeVariant::Unit) {
-                                write!(buffer, "(fallback), ")?;
-                            } else {
-                                write!(buffer, "(payload, fallback), ")?;
+                for context in context.io_variants(options.async_io) {
+                    write_indentation(buffer, indentation + 1)?;
+                    writeln!(
+                        buffer,
+                        "pub {}fn serialize_with_atlas{}<T: {}>(",
+                        context.async_keyword(),
+                        context.suffix(),
+                        context.writer_bound(),
+                    )?;
+                    write_indentation(buffer, indentation + 2)?;
+                    writeln!(buffer, "&self,")?;
+                    write_indentation(buffer, indentation + 2)?;
+                    writeln!(buffer, "writer: &mut T,")?;
+                    write_indentation(buffer, indentation + 2)?;
+                    write!(buffer, "atlas: &")?;
+                    write_identifier(buffer, &declaration.name, Pascal, Some(Atlas))?;
+                    writeln!(buffer, ",")?;
+                    write_indentation(buffer, indentation + 1)?;
+                    writeln!(buffer, ") -> {io}::Result<()> {{")?;
+                    write_indentation(buffer, indentation + 2)?;
+                    writeln!(buffer, "match (self, atlas) {{")?;
+                    for field in &declaration.fields {
+                        write_indentation(buffer, indentation + 3)?;
+                        write!(buffer, "(")?;
+                        write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
+                        write!(buffer, "::")?;
+                        write_identifier(buffer, &field.name, Pascal, None)?;
+                        match field.rule {
+                            schema::Rule::Asymmetric | schema::Rule::Optional => {
+                                if matches!(field.r#type.variant, schema::TypeVariant::Unit) {
+                                    write!(buffer, "(fallback), ")?;
+                                } else {
+                                    write!(buffer, "(payload, fallback), ")?;
+                                }
                             }
-                        }
-                        schema::Rule::Required => {
-                            if matches!(field.r#type.variant, schema::TypeVariant::Unit) {
-                                write!(buffer, ", ")?;
-                            } else {
-                                write!(buffer, "(payload), ")?;
+                            schema::Rule::Required => {
+                                if matches!(field.r#type.variant, schema::TypeVariant::Unit) {
+                                    write!(buffer, ", ")?;
+                                } else {
+                                    write!(buffer, "(payload), ")?;
+                                }
                             }
                         }
-                    }
-                    write_identifier(buffer, &declaration.name, Pascal, Some(Atlas))?;
-                    write!(buffer, "::")?;
-                    write_identifier(buffer, &field.name, Pascal, None)?;
-                    match field.rule {
-                        schema::Rule::Asymmetric | schema::Rule::Optional => {
-                            writeln!(buffer, "(_, payload_atlas, fallback_atlas)) => {{")?;
-                        }
-                        schema::Rule::Required => {
-                            writeln!(buffer, "(_, payload_atlas)) => {{")?;
-                        }
-                    }
-                    write_indentation(buffer, indentation + 4)?;
-                    write_supers(buffer, indentation)?;
-                    write!(
-                        buffer,
-                        "serialize_field_header(writer, {}_u64, ",
-                        field.index,
-                    )?;
-                    write_atlas_lookup(buffer, &field.r#type.variant)?;
-                    writeln!(buffer, ", {})?;", integer_encoded(&field.r#type))?;
-                    write_serialization_invocation(
-                        buffer,
-                        indentation + 4,
-                        indentation,
-                        &field.r#type.variant,
-                        true,
-                    )?;
-                    match field.rule {
-                        schema::Rule::Asymmetric | schema::Rule::Optional => {
-                            write_indentation(buffer, indentation + 4)?;
-                            writeln!(
-                                buffer,
-                                "fallback.serialize_with_atlas(writer, fallback_atlas)",
-                            )?;
+                        write_identifier(buffer, &declaration.name, Pascal, Some(Atlas))?;
+                        write!(buffer, "::")?;
+                        write_identifier(buffer, &field.name, Pascal, None)?;
+                        match field.rule {
+                            schema::Rule::Asymmetric | schema::Rule::Optional => {
+                                writeln!(buffer, "(_, payload_atlas, fallback_atlas)) => {{")?;
+                            }
+                            schema::Rule::Required => {
+                                writeln!(buffer, "(_, payload_atlas)) => {{")?;
+                            }
                         }
-                        schema::Rule::Required => {
-                            write_indentation(buffer, indentation + 4)?;
-                            writeln!(buffer, "Ok(())")?;
+                        write_indentation(buffer, indentation + 4)?;
+                        write_supers(buffer, indentation)?;
+                        write!(
+                            buffer,
+                            "serialize_field_header{}(writer, {}_u64, ",
+                            context.suffix(),
+                            field.index,
+                        )?;
+                        write_atlas_lookup(buffer, &field.r#type.variant)?;
+                        writeln!(
+                            buffer,
+                            ", {}){}?;",
+                            integer_encoded(&field.r#type),
+                            context.wait(),
+                        )?;
+                        write_serialization_invocation(
+                            buffer,
+                            indentation + 4,
+                            indentation,
+                            &field.r#type.variant,
+                            true,
+                            context,
+                        )?;
+                        match field.rule {
+                            schema::Rule::Asymmetric | schema::Rule::Optional => {
+                                write_indentation(buffer, indentation + 4)?;
+                                // Recursive `async` calls need indirection.
+                                if context.asynchronous {
+                                    writeln!(
+                                        buffer,
+                                        "Box::pin(fallback.serialize_with_atlas_async(writer, \
+                                        fallback_atlas)).await",
+                                    )?;
+                                } else {
+                                    writeln!(
+                                        buffer,
+                                        "fallback.serialize_with_atlas(writer, fallback_atlas)",
+                                    )?;
+                                }
+                            }
+                            schema::Rule::Required => {
+                                write_indentation(buffer, indentation + 4)?;
+                                writeln!(buffer, "Ok(())")?;
+                            }
                         }
+                        write_indentation(buffer, indentation + 3)?;
+                        writeln!(buffer, "}}")?;
                     }
                     write_indentation(buffer, indentation + 3)?;
+                    writeln!(buffer, "(_, _) => panic!(),")?;
+                    write_indentation(buffer, indentation + 2)?;
+                    writeln!(buffer, "}}")?;
+                    write_indentation(buffer, indentation + 1)?;
                     writeln!(buffer, "}}")?;
+                    writeln!(buffer)?;
                 }
-                write_indentation(buffer, indentation + 3)?;
-                writeln!(buffer, "(_, _) => panic!(),")?;
-                write_indentation(buffer, indentation + 2)?;
-                writeln!(buffer, "}}")?;
-                write_indentation(buffer, indentation + 1)?;
-                writeln!(buffer, "}}")?;
+                write_validate_function(buffer, indentation + 1, declaration, context)?;
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "}}")?;
 
                 writeln!(buffer)?;
 
-                write_indentation(buffer, indentation)?;
-                write!(buffer, "impl ")?;
-                write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
-                writeln!(buffer, " {{")?;
-                write_indentation(buffer, indentation + 1)?;
-                writeln!(
-                    buffer,
-                    "pub fn deserialize_from_reader_ref<T: ::std::io::BufRead>(",
-                )?;
-                write_indentation(buffer, indentation + 2)?;
-                writeln!(buffer, "reader: &mut T,")?;
-                write_indentation(buffer, indentation + 1)?;
-                writeln!(buffer, ") -> ::std::io::Result<Self> {{")?;
-                write_indentation(buffer, indentation + 2)?;
-                writeln!(buffer, "loop {{")?;
-                write_indentation(buffer, indentation + 3)?;
-                write!(buffer, "let (index, payload_size) = ")?;
-                write_supers(buffer, indentation)?;
-                writeln!(buffer, "deserialize_field_header(&mut *reader)?;")?;
-                writeln!(buffer)?;
-                write_indentation(buffer, indentation + 3)?;
-                writeln!(
-                    buffer,
-                    "let mut sub_reader = ::std::io::Read::take(&mut *reader, \
-                        payload_size as u64);",
-                )?;
-                writeln!(buffer)?;
-                write_indentation(buffer, indentation + 3)?;
-                writeln!(buffer, "match index {{")?;
-                for field in &declaration.fields {
-                    write_indentation(buffer, indentation + 4)?;
-                    writeln!(buffer, "{} => {{", field.index)?;
-                    write_deserialization_invocation(
+                for context in context.io_variants(options.async_io) {
+                    write_deserialize_from_reader_ref_header(
                         buffer,
-                        indentation + 5,
                         indentation,
-                        &imports,
-                        namespace,
-                        &field.r#type.variant,
-                        true,
+                        declaration,
+                        context,
+                        borrows,
                     )?;
-                    match field.rule {
-                        schema::Rule::Asymmetric | schema::Rule::Required => {
-                            write_indentation(buffer, indentation + 5)?;
-                            write_supers(buffer, indentation)?;
-                            writeln!(buffer, "finish(&mut *reader)?;")?;
-                            write_indentation(buffer, indentation + 5)?;
-                            write!(buffer, "return Ok(")?;
-                            write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
-                            write!(buffer, "::")?;
-                            write_identifier(buffer, &field.name, Pascal, None)?;
-                            if matches!(field.r#type.variant, schema::TypeVariant::Unit) {
-                                writeln!(buffer, ");")?;
-                            } else {
-                                writeln!(buffer, "(payload));")?;
+                    write_indentation(buffer, indentation + 2)?;
+                    writeln!(buffer, "loop {{")?;
+                    write_indentation(buffer, indentation + 3)?;
+                    write!(buffer, "let (index, payload_size) = ")?;
+                    write_supers(buffer, indentation)?;
+                    writeln!(
+                        buffer,
+                        "deserialize_field_header{}(&mut *reader){}?;",
+                        context.suffix(),
+                        context.wait(),
+                    )?;
+                    writeln!(buffer)?;
+                    write_sub_reader(
+                        buffer,
+                        indentation + 3,
+                        indentation,
+                        "&mut *reader",
+                        context,
+                    )?;
+                    writeln!(buffer)?;
+                    write_indentation(buffer, indentation + 3)?;
+                    writeln!(buffer, "match index {{")?;
+                    for field in &declaration.fields {
+                        write_indentation(buffer, indentation + 4)?;
+                        writeln!(buffer, "{} => {{", field.index)?;
+                        write_deserialization_invocation(
+                            buffer,
+                            indentation + 5,
+                            indentation,
+                            &imports,
+                            namespace,
+                            &field.r#type.variant,
+                            true,
+                            context,
+                        )?;
+                        write_constraint_checks(
+                            buffer,
+                            indentation + 5,
+                            &declaration.name,
+                            field,
+                            false,
+                            context,
+                        )?;
+                        match field.rule {
+                            schema::Rule::Asymmetric | schema::Rule::Required => {
+                                write_indentation(buffer, indentation + 5)?;
+                                write_supers(buffer, indentation)?;
+                                writeln!(
+                                    buffer,
+                                    "finish{}(&mut *reader){}?;",
+                                    context.suffix(),
+                                    context.wait(),
+                                )?;
+                                write_indentation(buffer, indentation + 5)?;
+                                write!(buffer, "return Ok(")?;
+                                write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
+                                write!(buffer, "::")?;
+                                write_identifier(buffer, &field.name, Pascal, None)?;
+                                if matches!(field.r#type.variant, schema::TypeVariant::Unit) {
+                                    writeln!(buffer, ");")?;
+                                } else {
+                                    writeln!(buffer, "(payload));")?;
+                                }
                             }
-                        }
-                        schema::Rule::Optional => {
-                            write_indentation(buffer, indentation + 5)?;
-                            write!(buffer, "let fallback = Box::new(")?;
-                            write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
-                            writeln!(buffer, "::deserialize_from_reader_ref(&mut *reader)?);")?;
-                            write_indentation(buffer, indentation + 5)?;
-                            write!(buffer, "return Ok(")?;
-                            write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
-                            write!(buffer, "::")?;
-                            write_identifier(buffer, &field.name, Pascal, None)?;
-                            if matches!(field.r#type.variant, schema::TypeVariant::Unit) {
-                                writeln!(buffer, "(fallback));")?;
-                            } else {
-                                writeln!(buffer, "(payload, fallback));")?;
+                            schema::Rule::Optional => {
+                                write_indentation(buffer, indentation + 5)?;
+                                write!(buffer, "let fallback = Box::new(")?;
+                                // Recursive `async` calls need indirection.
+                                if context.asynchronous {
+                                    write!(buffer, "Box::pin(")?;
+                                    write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
+                                    writeln!(
+                                    buffer,
+                                    "::deserialize_from_reader_ref_async(&mut *reader)).await?);",
+                                )?;
+                                } else {
+                                    write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
+                                    writeln!(
+                                        buffer,
+                                        "::deserialize_from_reader_ref(&mut *reader)?);",
+                                    )?;
+                                }
+                                write_indentation(buffer, indentation + 5)?;
+                                write!(buffer, "return Ok(")?;
+                                write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
+                                write!(buffer, "::")?;
+                                write_identifier(buffer, &field.name, Pascal, None)?;
+                                if matches!(field.r#type.variant, schema::TypeVariant::Unit) {
+                                    writeln!(buffer, "(fallback));")?;
+                                } else {
+                                    writeln!(buffer, "(payload, fallback));")?;
+                                }
                             }
                         }
+                        write_indentation(buffer, indentation + 4)?;
+                        writeln!(buffer, "}}")?;
                     }
-                    write_indentation(buffer, indentation + 4)?;
+                    write_skip(buffer, indentation + 4, indentation, context)?;
+                    write_indentation(buffer, indentation + 3)?;
+                    writeln!(buffer, "}}")?;
+                    write_indentation(buffer, indentation + 2)?;
+                    writeln!(buffer, "}}")?;
+                    write_indentation(buffer, indentation + 1)?;
+                    writeln!(buffer, "}}")?;
+                    write_indentation(buffer, indentation)?;
                     writeln!(buffer, "}}")?;
-                }
-                write_indentation(buffer, indentation + 4)?;
-                writeln!(buffer, "_ => {{")?;
-                write_indentation(buffer, indentation + 5)?;
-                write_supers(buffer, indentation)?;
-                writeln!(buffer, "skip(&mut sub_reader, payload_size)?;")?;
-                write_indentation(buffer, indentation + 4)?;
-                writeln!(buffer, "}}")?;
-                write_indentation(buffer, indentation + 3)?;
-                writeln!(buffer, "}}")?;
-                write_indentation(buffer, indentation + 2)?;
-                writeln!(buffer, "}}")?;
-                write_indentation(buffer, indentation + 1)?;
-                writeln!(buffer, "}}")?;
-                write_indentation(buffer, indentation)?;
-                writeln!(buffer, "}}")?;
 
-                writeln!(buffer)?;
+                    writeln!(buffer)?;
+                }
 
                 write_indentation(buffer, indentation)?;
                 write!(buffer, "impl ")?;
@@ -1406,78 +3105,770 @@ fn write_schema<T: Write>(
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "}}")?;
             }
+            schema::DeclarationVariant::Newtype(underlying_type) => {
+                write_newtype(
+                    buffer,
+                    indentation,
+                    &imports,
+                    namespace,
+                    declaration,
+                    underlying_type,
+                    Out,
+                    context,
+                )?;
+
+                writeln!(buffer)?;
+
+                write_newtype(
+                    buffer,
+                    indentation,
+                    &imports,
+                    namespace,
+                    declaration,
+                    underlying_type,
+                    In,
+                    context,
+                )?;
+
+                // Borrowed types can't be constructed from owned ones [ref:borrowed_types].
+                if !borrows {
+                    writeln!(buffer)?;
+
+                    write_indentation(buffer, indentation)?;
+                    write!(buffer, "impl From<")?;
+                    write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
+                    write!(buffer, "> for ")?;
+                    write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
+                    writeln!(buffer, " {{")?;
+                    write_indentation(buffer, indentation + 1)?;
+                    write!(buffer, "fn from(message: ")?;
+                    write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
+                    writeln!(buffer, ") -> Self {{")?;
+                    write_indentation(buffer, indentation + 2)?;
+                    write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
+                    write!(buffer, "(message.0")?;
+                    write_into_invocation(buffer, &underlying_type.variant, context)?;
+                    writeln!(buffer, ")")?;
+                    write_indentation(buffer, indentation + 1)?;
+                    writeln!(buffer, "}}")?;
+                    write_indentation(buffer, indentation)?;
+                    writeln!(buffer, "}}")?;
+                }
+            }
         }
 
+        if iter.peek().is_some() || !schema.services.is_empty() {
+            writeln!(buffer)?;
+        }
+    }
+
+    // Write the services.
+    let mut iter = schema.services.iter().peekable();
+    while let Some(service) = iter.next() {
+        write_service(buffer, indentation, &imports, namespace, service, context)?;
+
         if iter.peek().is_some() {
             writeln!(buffer)?;
         }
     }
 
+    // Write the conversions to and from native types for the well-known schemas.
+    if well_known::is(namespace, well_known::TIME_PATH) {
+        writeln!(buffer)?;
+        if context.no_std {
+            // The standard library's time types are only available with `std` [ref:no_std].
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "#[cfg(feature = \"std\")]")?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "mod std_conversions {{")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(
+                buffer,
+                "use super::{{DurationIn, DurationOut, TimestampIn, TimestampOut}};",
+            )?;
+            writeln!(buffer)?;
+            write_lines(buffer, indentation + 1, TIME_CONVERSIONS)?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "}}")?;
+        } else {
+            write_lines(buffer, indentation, TIME_CONVERSIONS)?;
+        }
+    }
+    if well_known::is(namespace, well_known::UUID_PATH) && options.uuid {
+        writeln!(buffer)?;
+        write_lines(buffer, indentation, UUID_CONVERSIONS)?;
+    }
+
     Ok(())
 }
 
+// Write a constant, including a trailing line break.
+fn write_constant<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    constant: &schema::Constant,
+) -> Result<(), fmt::Error> {
+    write_doc_comment(buffer, indentation, "///", &constant.comment)?;
+    write_indentation(buffer, indentation)?;
+    write!(buffer, "pub const ")?;
+    write_identifier(buffer, &constant.name, ScreamingSnake, None)?;
+    write!(
+        buffer,
+        ": {} = ",
+        match &constant.r#type.variant {
+            schema::TypeVariant::Bool => "bool".to_owned(),
+            schema::TypeVariant::Bytes(None) => "&[u8]".to_owned(),
+            schema::TypeVariant::Bytes(Some(length)) => format!("&[u8; {length}]"),
+            schema::TypeVariant::F32 => "f32".to_owned(),
+            schema::TypeVariant::F64 => "f64".to_owned(),
+            schema::TypeVariant::S32 => "i32".to_owned(),
+            schema::TypeVariant::S64 => "i64".to_owned(),
+            schema::TypeVariant::String => "&str".to_owned(),
+            schema::TypeVariant::U32 => "u32".to_owned(),
+            schema::TypeVariant::U64 => "u64".to_owned(),
+            schema::TypeVariant::U8 => "u8".to_owned(),
+            schema::TypeVariant::Array(_, _)
+            | schema::TypeVariant::Custom(_, _, _)
+            | schema::TypeVariant::Map(_, _)
+            | schema::TypeVariant::Newtype(_, _, _)
+            | schema::TypeVariant::Unit => {
+                // Impossible since the validator only allows scalar built-in types for constants.
+                panic!()
+            }
+        },
+    )?;
+
+    match &constant.value {
+        schema::Literal::Bool(value) => write!(buffer, "{value}")?,
+        schema::Literal::Bytes(bytes) => write!(
+            buffer,
+            "&[{}]",
+            bytes
+                .iter()
+                .map(|byte| format!("0x{byte:02x}"))
+                .collect::<Vec<_>>()
+                .join(", "),
+        )?,
+        schema::Literal::Float(float) => {
+            if let schema::TypeVariant::F32 = constant.r#type.variant {
+                // The validator ensures the value is within the range of `f32`, so the cast only
+                // rounds the value to the nearest `f32`, as the Rust compiler would anyway.
+                #[allow(clippy::cast_possible_truncation)]
+                let float = *float as f32;
+
+                write!(buffer, "{float:?}")?;
+            } else {
+                write!(buffer, "{float:?}")?;
+            }
+        }
+        schema::Literal::Integer(integer) => write!(buffer, "{}", group_digits(*integer))?,
+        schema::Literal::String(string) => write!(buffer, "{string:?}")?,
+    }
+
+    writeln!(buffer, ";")
+}
+
+// Render an integer with underscores separating groups of three digits, as the rest of the
+// generated code does for large literals.
+fn group_digits(integer: i128) -> String {
+    let digits = integer.unsigned_abs().to_string();
+
+    if digits.len() <= 4 {
+        return integer.to_string();
+    }
+
+    let mut grouped = String::new();
+
+    if integer < 0 {
+        grouped.push('-');
+    }
+
+    for (i, digit) in digits.chars().enumerate() {
+        if i != 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push('_');
+        }
+
+        grouped.push(digit);
+    }
+
+    grouped
+}
+
+// Write a service, including a trailing line break. A service consists of a client which sends
+// requests over a user-provided transport, a trait for handling requests, and a function which
+// routes a request to the appropriate method of a handler.
+#[allow(clippy::too_many_lines)]
+fn write_service<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    imports: &BTreeMap<Identifier, schema::Namespace>,
+    namespace: &schema::Namespace,
+    service: &schema::Service,
+    context: Context,
+) -> Result<(), fmt::Error> {
+    let client_name = service.name.join(&"Client".into());
+    let handler_name = service.name.join(&"Handler".into());
+    let io = context.io();
+
+    // Write the client.
+    write_doc_comment(buffer, indentation, "///", &service.comment)?;
+    write_indentation(buffer, indentation)?;
+    write!(buffer, "pub struct ")?;
+    write_identifier(buffer, &client_name, Pascal, None)?;
+    write!(buffer, "<T: ")?;
+    write_supers(buffer, indentation)?;
+    writeln!(buffer, "Transport> {{")?;
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(buffer, "pub transport: T,")?;
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "}}")?;
+
+    if !service.methods.is_empty() {
+        writeln!(buffer)?;
+        write_indentation(buffer, indentation)?;
+        write!(buffer, "impl<T: ")?;
+        write_supers(buffer, indentation)?;
+        write!(buffer, "Transport> ")?;
+        write_identifier(buffer, &client_name, Pascal, None)?;
+        writeln!(buffer, "<T> {{")?;
+
+        for (i, method) in service.methods.iter().enumerate() {
+            if i != 0 {
+                writeln!(buffer)?;
+            }
+
+            write_doc_comment(buffer, indentation + 1, "///", &method.comment)?;
+            // A borrowed response needs a buffer which outlives the call [ref:borrowed_types].
+            let output_borrows = context.borrowing.is_some_and(|borrowing| {
+                borrows(borrowing, imports, namespace, &method.output_type.variant)
+            });
+            write_indentation(buffer, indentation + 1)?;
+            write!(buffer, "pub fn ")?;
+            write_identifier(buffer, &method.name, Snake, None)?;
+            write_lifetime(buffer, output_borrows)?;
+            write!(buffer, "(&mut self, request: &")?;
+            write_type(
+                buffer,
+                imports,
+                namespace,
+                &method.input_type.variant,
+                Out,
+                context,
+            )?;
+            if output_borrows {
+                write!(buffer, ", response: &'a mut Vec<u8>")?;
+            }
+            write!(buffer, ") -> {io}::Result<")?;
+            write_type(
+                buffer,
+                imports,
+                namespace,
+                &method.output_type.variant,
+                In,
+                context,
+            )?;
+            writeln!(buffer, "> {{")?;
+            write_indentation(buffer, indentation + 2)?;
+            write!(buffer, "let mut payload = Vec::with_capacity(")?;
+            write_supers(buffer, indentation)?;
+            writeln!(buffer, "Serialize::size(request));")?;
+            write_indentation(buffer, indentation + 2)?;
+            write_supers(buffer, indentation)?;
+            writeln!(buffer, "Serialize::serialize(request, &mut payload)?;")?;
+            write_indentation(buffer, indentation + 2)?;
+            if output_borrows {
+                write!(buffer, "*response")?;
+            } else {
+                write!(buffer, "let response")?;
+            }
+            writeln!(
+                buffer,
+                " = self.transport.call({}, payload)?;",
+                method.index,
+            )?;
+            write_indentation(buffer, indentation + 2)?;
+            write_supers(buffer, indentation)?;
+            if context.borrowing.is_some() {
+                writeln!(
+                    buffer,
+                    "DeserializeBorrowed::deserialize_borrowed(response.as_slice())",
+                )?;
+            } else {
+                writeln!(buffer, "Deserialize::deserialize(response.as_slice())")?;
+            }
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "}}")?;
+        }
+
+        write_indentation(buffer, indentation)?;
+        writeln!(buffer, "}}")?;
+    }
+
+    writeln!(buffer)?;
+
+    // Write the handler.
+    write_doc_comment(buffer, indentation, "///", &service.comment)?;
+    write_indentation(buffer, indentation)?;
+    write!(buffer, "pub trait ")?;
+    write_identifier(buffer, &handler_name, Pascal, None)?;
+    writeln!(buffer, " {{")?;
+
+    for (i, method) in service.methods.iter().enumerate() {
+        if i != 0 {
+            writeln!(buffer)?;
+        }
+
+        write_doc_comment(buffer, indentation + 1, "///", &method.comment)?;
+        write_indentation(buffer, indentation + 1)?;
+        write!(buffer, "fn ")?;
+        write_identifier(buffer, &method.name, Snake, None)?;
+        write_lifetime(
+            buffer,
+            context.borrowing.is_some_and(|borrowing| {
+                borrows(borrowing, imports, namespace, &method.input_type.variant)
+            }),
+        )?;
+        write!(buffer, "(&mut self, request: ")?;
+        write_type(
+            buffer,
+            imports,
+            namespace,
+            &method.input_type.variant,
+            In,
+            context,
+        )?;
+        write!(buffer, ") -> ")?;
+        write_type(
+            buffer,
+            imports,
+            namespace,
+            &method.output_type.variant,
+            Out,
+            context,
+        )?;
+        writeln!(buffer, ";")?;
+    }
+
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "}}")?;
+
+    writeln!(buffer)?;
+
+    // Write the dispatch function.
+    let (handler_parameter, method_parameter, request_parameter) = if service.methods.is_empty() {
+        ("_handler", "_method", "_request")
+    } else {
+        ("handler", "method", "request")
+    };
+
+    write_indentation(buffer, indentation)?;
+    write!(buffer, "pub fn ")?;
+    write_identifier(
+        buffer,
+        &Identifier::from("dispatch").join(&service.name),
+        Snake,
+        None,
+    )?;
+    write!(buffer, "<T: ")?;
+    write_identifier(buffer, &handler_name, Pascal, None)?;
+    writeln!(
+        buffer,
+        " + ?Sized>({handler_parameter}: &mut T, {method_parameter}: u64, \
+            {request_parameter}: &[u8]) -> {io}::Result<Vec<u8>> {{",
+    )?;
+
+    if service.methods.is_empty() {
+        write_indentation(buffer, indentation + 1)?;
+        writeln!(buffer, "Err({io}::Error::new(")?;
+        write_indentation(buffer, indentation + 2)?;
+        writeln!(buffer, "{io}::ErrorKind::InvalidData,")?;
+        write_indentation(buffer, indentation + 2)?;
+        write_supers(buffer, indentation)?;
+        writeln!(buffer, "UNKNOWN_METHOD_ERROR_MESSAGE,")?;
+        write_indentation(buffer, indentation + 1)?;
+        writeln!(buffer, "))")?;
+    } else {
+        write_indentation(buffer, indentation + 1)?;
+        writeln!(buffer, "match method {{")?;
+
+        for method in &service.methods {
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "{} => {{", method.index)?;
+            write_indentation(buffer, indentation + 3)?;
+            write!(buffer, "let response = handler.")?;
+            write_identifier(buffer, &method.name, Snake, None)?;
+            write!(buffer, "(")?;
+            write_supers(buffer, indentation)?;
+            if context.borrowing.is_some() {
+                writeln!(
+                    buffer,
+                    "DeserializeBorrowed::deserialize_borrowed(request)?);",
+                )?;
+            } else {
+                writeln!(buffer, "Deserialize::deserialize(request)?);")?;
+            }
+            write_indentation(buffer, indentation + 3)?;
+            write!(buffer, "let mut payload = Vec::with_capacity(")?;
+            write_supers(buffer, indentation)?;
+            writeln!(buffer, "Serialize::size(&response));")?;
+            write_indentation(buffer, indentation + 3)?;
+            write_supers(buffer, indentation)?;
+            writeln!(buffer, "Serialize::serialize(&response, &mut payload)?;")?;
+            write_indentation(buffer, indentation + 3)?;
+            writeln!(buffer, "Ok(payload)")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "}}")?;
+        }
+
+        write_indentation(buffer, indentation + 2)?;
+        writeln!(buffer, "_ => Err({io}::Error::new(")?;
+        write_indentation(buffer, indentation + 3)?;
+        writeln!(buffer, "{io}::ErrorKind::InvalidData,")?;
+        write_indentation(buffer, indentation + 3)?;
+        write_supers(buffer, indentation)?;
+        writeln!(buffer, "UNKNOWN_METHOD_ERROR_MESSAGE,")?;
+        write_indentation(buffer, indentation + 2)?;
+        writeln!(buffer, ")),")?;
+        write_indentation(buffer, indentation + 1)?;
+        writeln!(buffer, "}}")?;
+    }
+
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "}}")
+}
+
 // Write a struct, including a trailing line break.
 fn write_struct<T: Write>(
     buffer: &mut T,
     indentation: usize,
     imports: &BTreeMap<Identifier, schema::Namespace>,
     namespace: &schema::Namespace,
-    name: &Identifier,
-    fields: &[schema::Field],
+    declaration: &schema::Declaration,
     direction: Direction,
+    context: Context,
+) -> Result<(), fmt::Error> {
+    let documented = match direction {
+        Direction::Atlas => false,
+        Direction::In | Direction::Out => true,
+    };
+    let borrows =
+        direction == In && declaration_borrows(context.borrowing, namespace, &declaration.name);
+
+    if documented {
+        write_doc_comment(buffer, indentation, "///", &declaration.comment)?;
+        write_deprecation(buffer, indentation, &declaration.attributes)?;
+    }
+    write_derives(buffer, indentation, declaration, documented, context)?;
+    write_indentation(buffer, indentation)?;
+    write!(buffer, "pub struct ")?;
+    write_identifier(buffer, &declaration.name, Pascal, Some(direction))?;
+    write_lifetime(buffer, borrows)?;
+    writeln!(buffer, " {{")?;
+
+    match direction {
+        Direction::Atlas => {
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "pub _size: usize,")?;
+        }
+        Direction::In | Direction::Out => {}
+    }
+
+    for field in &declaration.fields {
+        if documented {
+            write_doc_comment(buffer, indentation + 1, "///", &field.comment)?;
+            write_deprecation(buffer, indentation + 1, &field.attributes)?;
+        }
+        write_indentation(buffer, indentation + 1)?;
+        write!(buffer, "pub ")?;
+        write_identifier(buffer, &field.name, Snake, None)?;
+        write!(buffer, ": ")?;
+        match field.rule {
+            schema::Rule::Asymmetric => match direction {
+                Direction::Atlas | Direction::Out => {}
+                Direction::In => {
+                    write!(buffer, "Option<")?;
+                }
+            },
+            schema::Rule::Optional => {
+                write!(buffer, "Option<")?;
+            }
+            schema::Rule::Required => {}
+        }
+        write_type(
+            buffer,
+            imports,
+            namespace,
+            &field.r#type.variant,
+            direction,
+            context,
+        )?;
+        match field.rule {
+            schema::Rule::Asymmetric => match direction {
+                Direction::Atlas | Direction::Out => {}
+                Direction::In => {
+                    write!(buffer, ">")?;
+                }
+            },
+            schema::Rule::Optional => {
+                write!(buffer, ">")?;
+            }
+            schema::Rule::Required => {}
+        }
+        writeln!(buffer, ",")?;
+    }
+
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "}}")?;
+
+    Ok(())
+}
+
+// Write a builder for the `Out` type of a struct, including a trailing line break
+// [ref:rust_builders]. Each required or asymmetric field has a type parameter which is `Unset`
+// until the field is given, and the message can only be built once none of them are `Unset`.
+// Optional fields start out as `None`.
+#[allow(clippy::too_many_lines)]
+fn write_builder<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    imports: &BTreeMap<Identifier, schema::Namespace>,
+    namespace: &schema::Namespace,
+    declaration: &schema::Declaration,
+    context: Context,
 ) -> Result<(), fmt::Error> {
+    let builder_name = declaration.name.join(&"Builder".into());
+    let unset = format!("{}Unset", "super::".repeat(indentation));
+
+    // Render the type of each field, and number the type parameters of the required and asymmetric
+    // fields.
+    let mut fields = vec![];
+    let mut parameter_count = 0;
+    for field in &declaration.fields {
+        let mut r#type = String::new();
+        write_type(
+            &mut r#type,
+            imports,
+            namespace,
+            &field.r#type.variant,
+            Out,
+            context,
+        )?;
+
+        let parameter = match field.rule {
+            schema::Rule::Asymmetric | schema::Rule::Required => {
+                parameter_count += 1;
+                Some(parameter_count - 1)
+            }
+            schema::Rule::Optional => None,
+        };
+
+        fields.push((field, r#type, parameter));
+    }
+
+    // Render a list of generic parameters or arguments, given the one for each type parameter (if
+    // any) and the type of the corresponding field.
+    let generics = |argument: &dyn Fn(usize, &str) -> Option<String>| {
+        let arguments = fields
+            .iter()
+            .filter_map(|(_, r#type, parameter)| {
+                parameter.and_then(|parameter| argument(parameter, r#type))
+            })
+            .collect::<Vec<_>>();
+
+        if arguments.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", arguments.join(", "))
+        }
+    };
+    let parameters = generics(&|parameter, _| Some(format!("T{parameter}")));
+
+    // Write the builder itself.
     write_indentation(buffer, indentation)?;
-    writeln!(buffer, "#[derive({})]", TRAITS_TO_DERIVE.join(", "))?;
+    writeln!(buffer, "#[derive(Clone, Debug)]")?;
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "#[must_use]")?;
     write_indentation(buffer, indentation)?;
     write!(buffer, "pub struct ")?;
-    write_identifier(buffer, name, Pascal, Some(direction))?;
+    write_identifier(buffer, &builder_name, Pascal, None)?;
+    writeln!(buffer, "{parameters} {{")?;
+    for (field, r#type, parameter) in &fields {
+        write_indentation(buffer, indentation + 1)?;
+        write_identifier(buffer, &field.name, Snake, None)?;
+        if let Some(parameter) = parameter {
+            writeln!(buffer, ": T{parameter},")?;
+        } else {
+            writeln!(buffer, ": Option<{type}>,")?;
+        }
+    }
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "}}")?;
+
+    writeln!(buffer)?;
+
+    // Write the function which starts building a message.
+    write_indentation(buffer, indentation)?;
+    write!(buffer, "impl ")?;
+    write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
+    writeln!(buffer, " {{")?;
+    write_indentation(buffer, indentation + 1)?;
+    write!(buffer, "pub fn builder() -> ")?;
+    write_identifier(buffer, &builder_name, Pascal, None)?;
+    writeln!(buffer, "{} {{", generics(&|_, _| Some(unset.clone())))?;
+    write_indentation(buffer, indentation + 2)?;
+    write_identifier(buffer, &builder_name, Pascal, None)?;
     writeln!(buffer, " {{")?;
-
-    match direction {
-        Direction::Atlas => {
-            write_indentation(buffer, indentation + 1)?;
-            writeln!(buffer, "pub _size: usize,")?;
+    for (field, _, parameter) in &fields {
+        write_indentation(buffer, indentation + 3)?;
+        write_identifier(buffer, &field.name, Snake, None)?;
+        if parameter.is_some() {
+            writeln!(buffer, ": {unset},")?;
+        } else {
+            writeln!(buffer, ": None,")?;
         }
-        Direction::In | Direction::Out => {}
     }
+    write_indentation(buffer, indentation + 2)?;
+    writeln!(buffer, "}}")?;
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(buffer, "}}")?;
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "}}")?;
+
+    // Write a setter for each required or asymmetric field. It's only available while the field is
+    // unset, and it changes the corresponding type parameter to the type of the field.
+    for (field, r#type, parameter) in &fields {
+        let Some(parameter) = *parameter else {
+            continue;
+        };
 
-    for field in fields {
+        writeln!(buffer)?;
+        write_indentation(buffer, indentation)?;
+        write!(
+            buffer,
+            "impl{} ",
+            generics(&|other, _| (other != parameter).then(|| format!("T{other}"))),
+        )?;
+        write_identifier(buffer, &builder_name, Pascal, None)?;
+        writeln!(
+            buffer,
+            "{} {{",
+            generics(&|other, _| Some(if other == parameter {
+                unset.clone()
+            } else {
+                format!("T{other}")
+            })),
+        )?;
+        write_doc_comment(buffer, indentation + 1, "///", &field.comment)?;
+        write_deprecation(buffer, indentation + 1, &field.attributes)?;
         write_indentation(buffer, indentation + 1)?;
-        write!(buffer, "pub ")?;
+        write!(buffer, "pub fn ")?;
         write_identifier(buffer, &field.name, Snake, None)?;
-        write!(buffer, ": ")?;
-        match field.rule {
-            schema::Rule::Asymmetric => match direction {
-                Direction::Atlas | Direction::Out => {}
-                Direction::In => {
-                    write!(buffer, "Option<")?;
-                }
-            },
-            schema::Rule::Optional => {
-                write!(buffer, "Option<")?;
+        write!(buffer, "(self, ")?;
+        write_identifier(buffer, &field.name, Snake, None)?;
+        write!(buffer, ": {type}) -> ")?;
+        write_identifier(buffer, &builder_name, Pascal, None)?;
+        writeln!(
+            buffer,
+            "{} {{",
+            generics(&|other, other_type| Some(if other == parameter {
+                other_type.to_owned()
+            } else {
+                format!("T{other}")
+            })),
+        )?;
+        write_indentation(buffer, indentation + 2)?;
+        write_identifier(buffer, &builder_name, Pascal, None)?;
+        writeln!(buffer, " {{")?;
+        for (other_field, _, _) in &fields {
+            write_indentation(buffer, indentation + 3)?;
+            if other_field.name != field.name {
+                write_identifier(buffer, &other_field.name, Snake, None)?;
+                write!(buffer, ": self.")?;
             }
-            schema::Rule::Required => {}
+            write_identifier(buffer, &other_field.name, Snake, None)?;
+            writeln!(buffer, ",")?;
         }
-        write_type(buffer, imports, namespace, &field.r#type.variant, direction)?;
-        match field.rule {
-            schema::Rule::Asymmetric => match direction {
-                Direction::Atlas | Direction::Out => {}
-                Direction::In => {
-                    write!(buffer, ">")?;
-                }
-            },
-            schema::Rule::Optional => {
-                write!(buffer, ">")?;
+        write_indentation(buffer, indentation + 2)?;
+        writeln!(buffer, "}}")?;
+        write_indentation(buffer, indentation + 1)?;
+        writeln!(buffer, "}}")?;
+        write_indentation(buffer, indentation)?;
+        writeln!(buffer, "}}")?;
+    }
+
+    // Write the setters for the optional fields, which are available in every state.
+    if fields.iter().any(|(_, _, parameter)| parameter.is_none()) {
+        writeln!(buffer)?;
+        write_indentation(buffer, indentation)?;
+        write!(buffer, "impl{parameters} ")?;
+        write_identifier(buffer, &builder_name, Pascal, None)?;
+        writeln!(buffer, "{parameters} {{")?;
+        let mut first = true;
+        for (field, r#type, parameter) in &fields {
+            if parameter.is_some() {
+                continue;
             }
-            schema::Rule::Required => {}
+
+            if first {
+                first = false;
+            } else {
+                writeln!(buffer)?;
+            }
+
+            write_doc_comment(buffer, indentation + 1, "///", &field.comment)?;
+            write_deprecation(buffer, indentation + 1, &field.attributes)?;
+            write_indentation(buffer, indentation + 1)?;
+            write!(buffer, "pub fn ")?;
+            write_identifier(buffer, &field.name, Snake, None)?;
+            write!(buffer, "(mut self, ")?;
+            write_identifier(buffer, &field.name, Snake, None)?;
+            writeln!(buffer, ": {type}) -> Self {{")?;
+            write_indentation(buffer, indentation + 2)?;
+            write!(buffer, "self.")?;
+            write_identifier(buffer, &field.name, Snake, None)?;
+            write!(buffer, " = Some(")?;
+            write_identifier(buffer, &field.name, Snake, None)?;
+            writeln!(buffer, ");")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "self")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "}}")?;
         }
-        writeln!(buffer, ",")?;
+        write_indentation(buffer, indentation)?;
+        writeln!(buffer, "}}")?;
     }
 
+    // Write the function which builds the message once all the required and asymmetric fields have
+    // been given.
+    writeln!(buffer)?;
     write_indentation(buffer, indentation)?;
+    write!(buffer, "impl ")?;
+    write_identifier(buffer, &builder_name, Pascal, None)?;
+    writeln!(
+        buffer,
+        "{} {{",
+        generics(&|_, r#type| Some(r#type.to_owned())),
+    )?;
+    write_indentation(buffer, indentation + 1)?;
+    write!(buffer, "pub fn build(self) -> ")?;
+    write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
+    writeln!(buffer, " {{")?;
+    write_indentation(buffer, indentation + 2)?;
+    write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
+    writeln!(buffer, " {{")?;
+    for (field, _, _) in &fields {
+        write_indentation(buffer, indentation + 3)?;
+        write_identifier(buffer, &field.name, Snake, None)?;
+        write!(buffer, ": self.")?;
+        write_identifier(buffer, &field.name, Snake, None)?;
+        writeln!(buffer, ",")?;
+    }
+    write_indentation(buffer, indentation + 2)?;
     writeln!(buffer, "}}")?;
-
-    Ok(())
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(buffer, "}}")?;
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "}}")
 }
 
 // Write a choice, including a trailing line break.
@@ -1486,18 +3877,33 @@ fn write_choice<T: Write>(
     indentation: usize,
     imports: &BTreeMap<Identifier, schema::Namespace>,
     namespace: &schema::Namespace,
-    name: &Identifier,
-    fields: &[schema::Field],
+    declaration: &schema::Declaration,
     direction: Direction,
+    context: Context,
 ) -> Result<(), fmt::Error> {
-    write_indentation(buffer, indentation)?;
-    writeln!(buffer, "#[derive({})]", TRAITS_TO_DERIVE.join(", "))?;
+    let documented = match direction {
+        Direction::Atlas => false,
+        Direction::In | Direction::Out => true,
+    };
+    let borrows =
+        direction == In && declaration_borrows(context.borrowing, namespace, &declaration.name);
+
+    if documented {
+        write_doc_comment(buffer, indentation, "///", &declaration.comment)?;
+        write_deprecation(buffer, indentation, &declaration.attributes)?;
+    }
+    write_derives(buffer, indentation, declaration, documented, context)?;
     write_indentation(buffer, indentation)?;
     write!(buffer, "pub enum ")?;
-    write_identifier(buffer, name, Pascal, Some(direction))?;
+    write_identifier(buffer, &declaration.name, Pascal, Some(direction))?;
+    write_lifetime(buffer, borrows)?;
     writeln!(buffer, " {{")?;
 
-    for field in fields {
+    for field in &declaration.fields {
+        if documented {
+            write_doc_comment(buffer, indentation + 1, "///", &field.comment)?;
+            write_deprecation(buffer, indentation + 1, &field.attributes)?;
+        }
         write_indentation(buffer, indentation + 1)?;
         write_identifier(buffer, &field.name, Pascal, None)?;
 
@@ -1535,7 +3941,14 @@ fn write_choice<T: Write>(
         }
 
         if payload {
-            write_type(buffer, imports, namespace, &field.r#type.variant, direction)?;
+            write_type(
+                buffer,
+                imports,
+                namespace,
+                &field.r#type.variant,
+                direction,
+                context,
+            )?;
 
             if fallback {
                 write!(buffer, ", ")?;
@@ -1544,7 +3957,8 @@ fn write_choice<T: Write>(
 
         if fallback {
             write!(buffer, "Box<")?;
-            write_identifier(buffer, name, Pascal, Some(direction))?;
+            write_identifier(buffer, &declaration.name, Pascal, Some(direction))?;
+            write_lifetime(buffer, borrows)?;
             write!(buffer, ">")?;
         }
 
@@ -1561,6 +3975,41 @@ fn write_choice<T: Write>(
     Ok(())
 }
 
+// Write a newtype, including a trailing line break. Newtypes have no atlases, since they share the
+// atlases of their underlying types [ref:newtype_encoding].
+#[allow(clippy::too_many_arguments)]
+fn write_newtype<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    imports: &BTreeMap<Identifier, schema::Namespace>,
+    namespace: &schema::Namespace,
+    declaration: &schema::Declaration,
+    underlying_type: &schema::Type,
+    direction: Direction,
+    context: Context,
+) -> Result<(), fmt::Error> {
+    write_doc_comment(buffer, indentation, "///", &declaration.comment)?;
+    write_deprecation(buffer, indentation, &declaration.attributes)?;
+    write_derives(buffer, indentation, declaration, true, context)?;
+    write_indentation(buffer, indentation)?;
+    write!(buffer, "pub struct ")?;
+    write_identifier(buffer, &declaration.name, Pascal, Some(direction))?;
+    write_lifetime(
+        buffer,
+        direction == In && declaration_borrows(context.borrowing, namespace, &declaration.name),
+    )?;
+    write!(buffer, "(pub ")?;
+    write_type(
+        buffer,
+        imports,
+        namespace,
+        &underlying_type.variant,
+        direction,
+        context,
+    )?;
+    writeln!(buffer, ");")
+}
+
 fn write_size_function<T: Write>(buffer: &mut T, indentation: usize) -> Result<(), fmt::Error> {
     write_indentation(buffer, indentation)?;
     writeln!(buffer, "fn size(&self) -> usize {{")?;
@@ -1574,11 +4023,14 @@ fn write_size_function<T: Write>(buffer: &mut T, indentation: usize) -> Result<(
 fn write_serialize_function<T: Write>(
     buffer: &mut T,
     indentation: usize,
+    context: Context,
 ) -> Result<(), fmt::Error> {
+    let io = context.io();
+
     write_indentation(buffer, indentation)?;
     writeln!(
         buffer,
-        "fn serialize<T: ::std::io::Write>(&self, mut writer: T) -> ::std::io::Result<()> {{",
+        "fn serialize<T: {io}::Write>(&self, mut writer: T) -> {io}::Result<()> {{",
     )?;
     write_indentation(buffer, indentation + 1)?;
     writeln!(buffer, "let atlas = self.atlas();")?;
@@ -1588,52 +4040,376 @@ fn write_serialize_function<T: Write>(
     writeln!(buffer, "}}")
 }
 
-// Write the function to deserialize a message.
-fn write_deserialize_function<T: Write>(
+// Write the implementations of the deserialization traits for the `In` type of a struct or choice,
+// including a trailing line break. Borrowed types only implement `DeserializeBorrowed`, but the
+// types which don't borrow anything implement both traits [ref:borrowed_types].
+fn write_deserialize_impls<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    namespace: &schema::Namespace,
+    declaration: &schema::Declaration,
+    context: Context,
+) -> Result<(), fmt::Error> {
+    let borrows = declaration_borrows(context.borrowing, namespace, &declaration.name);
+
+    if !borrows {
+        write_indentation(buffer, indentation)?;
+        write!(buffer, "impl ")?;
+        write_supers(buffer, indentation)?;
+        write!(buffer, "Deserialize for ")?;
+        write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
+        writeln!(buffer, " {{")?;
+        write_deserialize_function(buffer, indentation + 1, context)?;
+        write_indentation(buffer, indentation)?;
+        writeln!(buffer, "}}")?;
+    }
+
+    if context.borrowing.is_some() {
+        if !borrows {
+            writeln!(buffer)?;
+        }
+
+        write_indentation(buffer, indentation)?;
+        write!(buffer, "impl<'a> ")?;
+        write_supers(buffer, indentation)?;
+        write!(buffer, "DeserializeBorrowed<'a> for ")?;
+        write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
+        write_lifetime(buffer, borrows)?;
+        writeln!(buffer, " {{")?;
+        write_indentation(buffer, indentation + 1)?;
+        writeln!(
+            buffer,
+            "fn deserialize_borrowed(mut bytes: &'a [u8]) -> {}::Result<Self> {{",
+            context.io(),
+        )?;
+        write_indentation(buffer, indentation + 2)?;
+        writeln!(buffer, "Self::deserialize_from_reader_ref(&mut bytes)")?;
+        write_indentation(buffer, indentation + 1)?;
+        writeln!(buffer, "}}")?;
+        write_indentation(buffer, indentation)?;
+        writeln!(buffer, "}}")?;
+    }
+
+    Ok(())
+}
+
+// Write the implementations of the asynchronous serialization and deserialization traits for a
+// struct or choice [ref:async_io]. Asynchronous code is never generated for borrowed types.
+fn write_async_impls<T: Write>(
     buffer: &mut T,
     indentation: usize,
+    declaration: &schema::Declaration,
 ) -> Result<(), fmt::Error> {
     write_indentation(buffer, indentation)?;
+    write!(buffer, "impl ")?;
+    write_supers(buffer, indentation)?;
+    write!(buffer, "SerializeAsync for ")?;
+    write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
+    writeln!(buffer, " {{")?;
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(
+        buffer,
+        "async fn serialize_async<T: ::tokio::io::AsyncWrite + Unpin + Send>(",
+    )?;
+    write_indentation(buffer, indentation + 2)?;
+    writeln!(buffer, "&self,")?;
+    write_indentation(buffer, indentation + 2)?;
+    writeln!(buffer, "mut writer: T,")?;
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(buffer, ") -> ::std::io::Result<()> {{")?;
+    write_indentation(buffer, indentation + 2)?;
+    writeln!(buffer, "let atlas = self.atlas();")?;
+    write_indentation(buffer, indentation + 2)?;
+    writeln!(
+        buffer,
+        "self.serialize_with_atlas_async(&mut writer, &atlas).await",
+    )?;
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(buffer, "}}")?;
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "}}")?;
+
+    writeln!(buffer)?;
+
+    write_indentation(buffer, indentation)?;
+    write!(buffer, "impl ")?;
+    write_supers(buffer, indentation)?;
+    write!(buffer, "DeserializeAsync for ")?;
+    write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
+    writeln!(buffer, " {{")?;
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(
+        buffer,
+        "async fn deserialize_async<T: ::tokio::io::AsyncBufRead + Unpin + Send>(",
+    )?;
+    write_indentation(buffer, indentation + 2)?;
+    writeln!(buffer, "mut reader: T,")?;
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(buffer, ") -> ::std::io::Result<Self> {{")?;
+    write_indentation(buffer, indentation + 2)?;
     writeln!(
         buffer,
-        "fn deserialize<T: ::std::io::BufRead>(mut reader: T) -> ::std::io::Result<Self> {{",
+        "Self::deserialize_from_reader_ref_async(&mut reader).await",
     )?;
     write_indentation(buffer, indentation + 1)?;
-    writeln!(buffer, "Self::deserialize_from_reader_ref(&mut reader)")?;
+    writeln!(buffer, "}}")?;
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "}}")
+}
+
+// Write the function to deserialize a message. When generating borrowed types, the deserialization
+// logic works on slices, so the whole message is read into memory first.
+fn write_deserialize_function<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    context: Context,
+) -> Result<(), fmt::Error> {
+    let io = context.io();
+
+    write_indentation(buffer, indentation)?;
+    writeln!(
+        buffer,
+        "fn deserialize<T: {io}::BufRead>(mut reader: T) -> {io}::Result<Self> {{",
+    )?;
+    if context.borrowing.is_some() {
+        write_indentation(buffer, indentation + 1)?;
+        writeln!(buffer, "let mut bytes = Vec::new();")?;
+        write_indentation(buffer, indentation + 1)?;
+        writeln!(buffer, "{io}::Read::read_to_end(&mut reader, &mut bytes)?;")?;
+        write_indentation(buffer, indentation + 1)?;
+        writeln!(
+            buffer,
+            "Self::deserialize_from_reader_ref(&mut bytes.as_slice())"
+        )?;
+    } else {
+        write_indentation(buffer, indentation + 1)?;
+        writeln!(buffer, "Self::deserialize_from_reader_ref(&mut reader)")?;
+    }
     write_indentation(buffer, indentation)?;
     writeln!(buffer, "}}")
 }
 
+// Write the beginning of the `impl` block for the `In` type of a struct or choice and the signature
+// of its `deserialize_from_reader_ref` function or the asynchronous counterpart [ref:async_io],
+// including a trailing line break. Borrowed types are deserialized from slices rather than
+// arbitrary readers [ref:borrowed_types].
+fn write_deserialize_from_reader_ref_header<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    declaration: &schema::Declaration,
+    context: Context,
+    borrows: bool,
+) -> Result<(), fmt::Error> {
+    let io = context.io();
+
+    write_indentation(buffer, indentation)?;
+    write!(buffer, "impl")?;
+    if borrows {
+        write!(buffer, "<'a>")?;
+    }
+    write!(buffer, " ")?;
+    write_identifier(buffer, &declaration.name, Pascal, Some(In))?;
+    write_lifetime(buffer, borrows)?;
+    writeln!(buffer, " {{")?;
+    write_indentation(buffer, indentation + 1)?;
+    if context.borrowing.is_some() {
+        writeln!(buffer, "pub fn deserialize_from_reader_ref(")?;
+        write_indentation(buffer, indentation + 2)?;
+        if borrows {
+            writeln!(buffer, "reader: &mut &'a [u8],")?;
+        } else {
+            writeln!(buffer, "reader: &mut &[u8],")?;
+        }
+    } else {
+        writeln!(
+            buffer,
+            "pub {}fn deserialize_from_reader_ref{}<T: {}>(",
+            context.async_keyword(),
+            context.suffix(),
+            context.reader_bound(),
+        )?;
+        write_indentation(buffer, indentation + 2)?;
+        writeln!(buffer, "reader: &mut T,")?;
+    }
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(buffer, ") -> {io}::Result<Self> {{")
+}
+
+// Write the logic to limit a reader to the payload of a field, including a trailing line break.
+// Borrowed types split off a slice for the payload instead [ref:borrowed_types].
+//
+// Context variables:
+// - `payload_size` (in)
+// - `sub_reader` (out)
+fn write_sub_reader<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    supers: usize,
+    reader: &str,
+    context: Context,
+) -> Result<(), fmt::Error> {
+    write_indentation(buffer, indentation)?;
+    if context.borrowing.is_some() {
+        write!(buffer, "let mut sub_reader = ")?;
+        write_supers(buffer, supers)?;
+        writeln!(buffer, "split({reader}, payload_size);")
+    } else {
+        writeln!(
+            buffer,
+            "let mut sub_reader = {}::take({reader}, payload_size as u64);",
+            context.read(),
+        )
+    }
+}
+
+// Write the match arm for skipping an unrecognized field, including a trailing line break. The
+// payload of the field has already been split off for borrowed types [ref:borrowed_types], so
+// there's nothing to do in that case.
+//
+// Context variables:
+// - `payload_size` (in)
+// - `sub_reader` (in and out)
+fn write_skip<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    supers: usize,
+    context: Context,
+) -> Result<(), fmt::Error> {
+    write_indentation(buffer, indentation)?;
+    if context.borrowing.is_some() {
+        writeln!(buffer, "_ => {{}}")
+    } else {
+        writeln!(buffer, "_ => {{")?;
+        write_indentation(buffer, indentation + 1)?;
+        write_supers(buffer, supers)?;
+        writeln!(
+            buffer,
+            "skip{}(&mut sub_reader, payload_size){}?;",
+            context.suffix(),
+            context.wait(),
+        )?;
+        write_indentation(buffer, indentation)?;
+        writeln!(buffer, "}}")
+    }
+}
+
 // Write a type.
+#[allow(clippy::too_many_lines)]
 fn write_type<T: Write>(
     buffer: &mut T,
     imports: &BTreeMap<Identifier, schema::Namespace>,
     namespace: &schema::Namespace,
     type_variant: &schema::TypeVariant,
     direction: Direction,
+    context: Context,
 ) -> Result<(), fmt::Error> {
     match type_variant {
-        schema::TypeVariant::Array(inner_type) => match direction {
-            Direction::Atlas => match &inner_type.variant {
-                schema::TypeVariant::Array(_)
-                | schema::TypeVariant::Bytes
-                | schema::TypeVariant::Custom(_, _)
+        schema::TypeVariant::Array(inner_type, length) => match direction {
+            Direction::Atlas => match encoding_type(&inner_type.variant) {
+                schema::TypeVariant::Array(_, _)
+                | schema::TypeVariant::Bytes(_)
+                | schema::TypeVariant::Custom(_, _, _)
+                | schema::TypeVariant::Map(_, _)
                 | schema::TypeVariant::String => {
                     write!(buffer, "(usize, Vec<")?;
-                    write_type(buffer, imports, namespace, &inner_type.variant, direction)?;
+                    write_type(
+                        buffer,
+                        imports,
+                        namespace,
+                        &inner_type.variant,
+                        direction,
+                        context,
+                    )?;
                     write!(buffer, ">)")?;
                 }
+                schema::TypeVariant::Newtype(_, _, _) => {
+                    // Impossible since `encoding_type` strips away newtypes.
+                    panic!()
+                }
                 schema::TypeVariant::Bool
+                | schema::TypeVariant::F32
                 | schema::TypeVariant::F64
+                | schema::TypeVariant::S32
                 | schema::TypeVariant::S64
+                | schema::TypeVariant::U32
                 | schema::TypeVariant::U64
+                | schema::TypeVariant::U8
                 | schema::TypeVariant::Unit => {
                     write!(buffer, "usize")?;
                 }
             },
             Direction::In | Direction::Out => {
-                write!(buffer, "Vec<")?;
-                write_type(buffer, imports, namespace, &inner_type.variant, direction)?;
+                if let Some(length) = length {
+                    write!(buffer, "[")?;
+                    write_type(
+                        buffer,
+                        imports,
+                        namespace,
+                        &inner_type.variant,
+                        direction,
+                        context,
+                    )?;
+                    write!(buffer, "; {length}]")?;
+                } else if direction == In && context.borrowing.is_some() {
+                    write_supers(buffer, namespace.components.len())?;
+                    write!(buffer, "ArrayView<'a, ")?;
+                    write_type(
+                        buffer,
+                        imports,
+                        namespace,
+                        &inner_type.variant,
+                        direction,
+                        context,
+                    )?;
+                    write!(buffer, ">")?;
+                } else {
+                    write!(buffer, "Vec<")?;
+                    write_type(
+                        buffer,
+                        imports,
+                        namespace,
+                        &inner_type.variant,
+                        direction,
+                        context,
+                    )?;
+                    write!(buffer, ">")?;
+                }
+            }
+        },
+        schema::TypeVariant::Map(key_type, value_type) => match direction {
+            Direction::Atlas => {
+                // The atlas holds the total size and, for each entry, the size of the entry along
+                // with the atlases of the key and the value.
+                write!(buffer, "(usize, Vec<(usize, usize, ")?;
+                write_type(
+                    buffer,
+                    imports,
+                    namespace,
+                    &value_type.variant,
+                    direction,
+                    context,
+                )?;
+                write!(buffer, ")>)")?;
+            }
+            Direction::In | Direction::Out => {
+                write!(buffer, "{}::collections::BTreeMap<", context.alloc())?;
+                write_type(
+                    buffer,
+                    imports,
+                    namespace,
+                    &key_type.variant,
+                    direction,
+                    context,
+                )?;
+                write!(buffer, ", ")?;
+                write_type(
+                    buffer,
+                    imports,
+                    namespace,
+                    &value_type.variant,
+                    direction,
+                    context,
+                )?;
                 write!(buffer, ">")?;
             }
         },
@@ -1645,21 +4421,34 @@ fn write_type<T: Write>(
                 write!(buffer, "bool")?;
             }
         },
-        schema::TypeVariant::Bytes => match direction {
+        schema::TypeVariant::Bytes(length) => match direction {
             Direction::Atlas => {
                 write!(buffer, "usize")?;
             }
             Direction::In | Direction::Out => {
-                write!(buffer, "Vec<u8>")?;
+                if let Some(length) = length {
+                    write!(buffer, "[u8; {length}]")?;
+                } else if direction == In && context.borrowing.is_some() {
+                    write!(buffer, "&'a [u8]")?;
+                } else {
+                    write!(buffer, "Vec<u8>")?;
+                }
             }
         },
-        schema::TypeVariant::Custom(import, name) => {
-            let type_namespace = schema::Namespace {
-                components: import.as_ref().map_or_else(
-                    || namespace.components.clone(),
-                    |import| imports[import].components.clone(),
-                ),
-            };
+        schema::TypeVariant::Newtype(_, _, underlying_type) if direction == Atlas => {
+            // Newtypes have the same atlases as their underlying types [ref:newtype_encoding].
+            write_type(
+                buffer,
+                imports,
+                namespace,
+                &underlying_type.variant,
+                direction,
+                context,
+            )?;
+        }
+        schema::TypeVariant::Custom(import, name, _)
+        | schema::TypeVariant::Newtype(import, name, _) => {
+            let type_namespace = type_namespace(imports, namespace, import.as_ref());
 
             let (relative_type_namespace, ancestors) =
                 relativize_namespace(&type_namespace, namespace);
@@ -1672,7 +4461,24 @@ fn write_type<T: Write>(
             }
 
             write_identifier(buffer, name, Pascal, Some(direction))?;
+
+            if direction == In {
+                write_lifetime(
+                    buffer,
+                    context.borrowing.is_some_and(|borrowing| {
+                        borrowing.contains(&(type_namespace, name.clone()))
+                    }),
+                )?;
+            }
         }
+        schema::TypeVariant::F32 => match direction {
+            Direction::Atlas => {
+                write!(buffer, "usize")?;
+            }
+            Direction::In | Direction::Out => {
+                write!(buffer, "f32")?;
+            }
+        },
         schema::TypeVariant::F64 => match direction {
             Direction::Atlas => {
                 write!(buffer, "usize")?;
@@ -1681,6 +4487,14 @@ fn write_type<T: Write>(
                 write!(buffer, "f64")?;
             }
         },
+        schema::TypeVariant::S32 => match direction {
+            Direction::Atlas => {
+                write!(buffer, "usize")?;
+            }
+            Direction::In | Direction::Out => {
+                write!(buffer, "i32")?;
+            }
+        },
         schema::TypeVariant::S64 => match direction {
             Direction::Atlas => {
                 write!(buffer, "usize")?;
@@ -1693,10 +4507,21 @@ fn write_type<T: Write>(
             Direction::Atlas => {
                 write!(buffer, "usize")?;
             }
+            Direction::In if context.borrowing.is_some() => {
+                write!(buffer, "&'a str")?;
+            }
             Direction::In | Direction::Out => {
                 write!(buffer, "String")?;
             }
         },
+        schema::TypeVariant::U32 => match direction {
+            Direction::Atlas => {
+                write!(buffer, "usize")?;
+            }
+            Direction::In | Direction::Out => {
+                write!(buffer, "u32")?;
+            }
+        },
         schema::TypeVariant::U64 => match direction {
             Direction::Atlas => {
                 write!(buffer, "usize")?;
@@ -1705,6 +4530,14 @@ fn write_type<T: Write>(
                 write!(buffer, "u64")?;
             }
         },
+        schema::TypeVariant::U8 => match direction {
+            Direction::Atlas => {
+                write!(buffer, "usize")?;
+            }
+            Direction::In | Direction::Out => {
+                write!(buffer, "u8")?;
+            }
+        },
         schema::TypeVariant::Unit => match direction {
             Direction::Atlas => {
                 write!(buffer, "usize")?;
@@ -1741,6 +4574,7 @@ fn write_identifier<T: Write>(
 
     let converted_identifier = match case {
         CaseConvention::Pascal => identifier_with_suffix.pascal_case(),
+        CaseConvention::ScreamingSnake => identifier_with_suffix.screaming_snake_case(),
         CaseConvention::Snake => identifier_with_suffix.snake_case(),
     };
 
@@ -1756,6 +4590,86 @@ fn write_identifier<T: Write>(
     Ok(())
 }
 
+// Write a comment as documentation using the given marker (e.g., `///` or `//!`). Paragraphs are
+// separated by empty documentation lines.
+fn write_doc_comment<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    marker: &str,
+    paragraphs: &[String],
+) -> Result<(), fmt::Error> {
+    for (i, paragraph) in paragraphs.iter().enumerate() {
+        if i != 0 {
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "{marker}")?;
+        }
+
+        for line in paragraph.lines() {
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "{marker} {line}")?;
+        }
+    }
+
+    Ok(())
+}
+
+// Write the `derive` attribute of a type, including a trailing line break. The traits requested by
+// `Options::derives` and `@rust.derive` are only derived for the `In` and `Out` types, since
+// atlases are internal [ref:rust_derives].
+fn write_derives<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    declaration: &schema::Declaration,
+    include_requested_traits: bool,
+    context: Context,
+) -> Result<(), fmt::Error> {
+    let mut traits = TRAITS_TO_DERIVE.to_vec();
+
+    if include_requested_traits {
+        for name in context
+            .derives
+            .iter()
+            .map(String::as_str)
+            .chain(rust_derives(&declaration.attributes))
+        {
+            if !traits.contains(&name) {
+                traits.push(name);
+            }
+        }
+    }
+
+    write_indentation(buffer, indentation)?;
+    writeln!(
+        buffer,
+        "#[derive({})]",
+        traits
+            .iter()
+            .map(|name| derivable_trait(name).map_or(*name, |derivable_trait| derivable_trait.path))
+            .collect::<Vec<_>>()
+            .join(", "),
+    )
+}
+
+// Write a `deprecated` attribute, including a trailing line break, if the given attributes include
+// `@deprecated`.
+fn write_deprecation<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    attributes: &[schema::Attribute],
+) -> Result<(), fmt::Error> {
+    match deprecation(attributes) {
+        Some("") => {
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "#[deprecated]")
+        }
+        Some(message) => {
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "#[deprecated = {message:?}]")
+        }
+        None => Ok(()),
+    }
+}
+
 // Write the given level of indentation.
 fn write_indentation<T: Write>(buffer: &mut T, indentation: usize) -> Result<(), fmt::Error> {
     for _ in 0..indentation {
@@ -1765,6 +4679,19 @@ fn write_indentation<T: Write>(buffer: &mut T, indentation: usize) -> Result<(),
     Ok(())
 }
 
+// Write some lines of code at the given level of indentation. Empty lines aren't indented.
+fn write_lines<T: Write>(buffer: &mut T, indentation: usize, code: &str) -> Result<(), fmt::Error> {
+    for line in code.lines() {
+        if !line.is_empty() {
+            write_indentation(buffer, indentation)?;
+        }
+
+        writeln!(buffer, "{line}")?;
+    }
+
+    Ok(())
+}
+
 // Write a series of `super::super::...`.
 fn write_supers<T: Write>(buffer: &mut T, count: usize) -> Result<(), fmt::Error> {
     for _ in 0..count {
@@ -1774,6 +4701,15 @@ fn write_supers<T: Write>(buffer: &mut T, count: usize) -> Result<(), fmt::Error
     Ok(())
 }
 
+// Write the lifetime parameter of a borrowed `In` type [ref:borrowed_types] if it has one.
+fn write_lifetime<T: Write>(buffer: &mut T, borrows: bool) -> Result<(), fmt::Error> {
+    if borrows {
+        write!(buffer, "<'a>")?;
+    }
+
+    Ok(())
+}
+
 // Write the logic to convert a message from one type to another.
 //
 // Context variables:
@@ -1781,38 +4717,407 @@ fn write_supers<T: Write>(buffer: &mut T, count: usize) -> Result<(), fmt::Error
 fn write_into_invocation<T: Write>(
     buffer: &mut T,
     type_variant: &schema::TypeVariant,
+    context: Context,
 ) -> Result<(), fmt::Error> {
-    if let schema::TypeVariant::Array(inner_type) = type_variant {
-        let mut layer = inner_type;
-        while let schema::TypeVariant::Array(inner_type) = &layer.variant {
-            layer = inner_type;
-        }
-        if let schema::TypeVariant::Custom(_, _) = &layer.variant {
-            write!(buffer, ".into_iter().map(|x| ")?;
-            layer = inner_type;
-            while let schema::TypeVariant::Array(inner_type) = &layer.variant {
-                layer = inner_type;
-                write!(buffer, "x.into_iter().map(|x| ")?;
-            }
-            write!(buffer, "x.into()")?;
-            layer = inner_type;
-            while let schema::TypeVariant::Array(inner_type) = &layer.variant {
-                layer = inner_type;
-                write!(buffer, ").collect::<Vec<_>>()")?;
-            }
-            write!(buffer, ").collect::<Vec<_>>()")
-        } else {
-            write!(buffer, ".into()")
+    // Collections of user-defined types need to be converted element by element.
+    match type_variant {
+        schema::TypeVariant::Array(inner_type, length)
+            if contains_custom_type(&inner_type.variant) =>
+        {
+            if length.is_some() {
+                write!(buffer, ".map(|x| x")?;
+                write_into_invocation(buffer, &inner_type.variant, context)?;
+                write!(buffer, ")")
+            } else {
+                write!(buffer, ".into_iter().map(|x| x")?;
+                write_into_invocation(buffer, &inner_type.variant, context)?;
+                write!(buffer, ").collect::<Vec<_>>()")
+            }
+        }
+        schema::TypeVariant::Map(_, value_type) if contains_custom_type(&value_type.variant) => {
+            write!(buffer, ".into_iter().map(|(k, x)| (k, x")?;
+            write_into_invocation(buffer, &value_type.variant, context)?;
+            write!(
+                buffer,
+                ")).collect::<{}::collections::BTreeMap<_, _>>()",
+                context.alloc(),
+            )
+        }
+        _ => write!(buffer, ".into()"),
+    }
+}
+
+// Determine whether a type refers to a user-defined type, possibly within a collection.
+fn contains_custom_type(type_variant: &schema::TypeVariant) -> bool {
+    match type_variant {
+        schema::TypeVariant::Array(inner_type, _) => contains_custom_type(&inner_type.variant),
+        schema::TypeVariant::Custom(_, _, _) | schema::TypeVariant::Newtype(_, _, _) => true,
+        schema::TypeVariant::Map(_, value_type) => contains_custom_type(&value_type.variant),
+        schema::TypeVariant::Bool
+        | schema::TypeVariant::Bytes(_)
+        | schema::TypeVariant::F32
+        | schema::TypeVariant::F64
+        | schema::TypeVariant::S32
+        | schema::TypeVariant::S64
+        | schema::TypeVariant::String
+        | schema::TypeVariant::U32
+        | schema::TypeVariant::U64
+        | schema::TypeVariant::U8
+        | schema::TypeVariant::Unit => false,
+    }
+}
+
+// Determine whether a type refers to a struct or choice, possibly within a collection or a
+// newtype. Values of such types are validated by their own `validate` methods.
+fn contains_struct_or_choice(type_variant: &schema::TypeVariant) -> bool {
+    match type_variant {
+        schema::TypeVariant::Array(inner_type, _) => contains_struct_or_choice(&inner_type.variant),
+        schema::TypeVariant::Custom(_, _, _) => true,
+        schema::TypeVariant::Map(_, value_type) => contains_struct_or_choice(&value_type.variant),
+        schema::TypeVariant::Newtype(_, _, underlying_type) => {
+            contains_struct_or_choice(&underlying_type.variant)
+        }
+        schema::TypeVariant::Bool
+        | schema::TypeVariant::Bytes(_)
+        | schema::TypeVariant::F32
+        | schema::TypeVariant::F64
+        | schema::TypeVariant::S32
+        | schema::TypeVariant::S64
+        | schema::TypeVariant::String
+        | schema::TypeVariant::U32
+        | schema::TypeVariant::U64
+        | schema::TypeVariant::U8
+        | schema::TypeVariant::Unit => false,
+    }
+}
+
+// Write a numeric literal of the given type for a bound given by `@min` or `@max`. The literal has
+// a suffix so the generated code doesn't rely on the default numeric type.
+fn write_bound<T: Write>(
+    buffer: &mut T,
+    type_variant: &schema::TypeVariant,
+    bound: &schema::Literal,
+) -> Result<(), fmt::Error> {
+    let suffix = match type_variant {
+        schema::TypeVariant::F32 => "f32",
+        schema::TypeVariant::F64 => "f64",
+        schema::TypeVariant::S32 => "i32",
+        schema::TypeVariant::S64 => "i64",
+        schema::TypeVariant::U32 => "u32",
+        schema::TypeVariant::U64 => "u64",
+        schema::TypeVariant::U8 => "u8",
+        schema::TypeVariant::Array(_, _)
+        | schema::TypeVariant::Bool
+        | schema::TypeVariant::Bytes(_)
+        | schema::TypeVariant::Custom(_, _, _)
+        | schema::TypeVariant::Map(_, _)
+        | schema::TypeVariant::Newtype(_, _, _)
+        | schema::TypeVariant::String
+        | schema::TypeVariant::Unit => {
+            // Impossible since the validator only allows bounds on fields with numeric types.
+            panic!()
+        }
+    };
+
+    match bound {
+        schema::Literal::Float(float) => {
+            if let schema::TypeVariant::F32 = type_variant {
+                // The validator ensures the bound is within the range of `f32`, so the cast only
+                // rounds the bound to the nearest `f32`.
+                #[allow(clippy::cast_possible_truncation)]
+                let float = *float as f32;
+
+                write!(buffer, "{float:?}_{suffix}")
+            } else {
+                write!(buffer, "{float:?}_{suffix}")
+            }
         }
+        schema::Literal::Integer(integer) => write!(buffer, "{}_{suffix}", group_digits(*integer)),
+        schema::Literal::Bool(_) | schema::Literal::Bytes(_) | schema::Literal::String(_) => {
+            // Impossible since the validator only allows numbers as bounds.
+            panic!()
+        }
+    }
+}
+
+// Write the logic to return an error if a value doesn't satisfy the constraints of a field
+// [ref:constraints].
+//
+// Context variables:
+// - `payload` (in), by reference if `by_reference` is `true`
+fn write_constraint_checks<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    declaration_name: &Identifier,
+    field: &schema::Field,
+    by_reference: bool,
+    context: Context,
+) -> Result<(), fmt::Error> {
+    let constraints = schema::constraints(&field.attributes);
+
+    // Newtypes are unwrapped to reach the values of their underlying types.
+    let mut value = "payload".to_owned();
+    let mut type_variant = &field.r#type.variant;
+    while let schema::TypeVariant::Newtype(_, _, underlying_type) = type_variant {
+        value.push_str(".0");
+        type_variant = &underlying_type.variant;
+    }
+    let reference = if by_reference && value == "payload" {
+        value.clone()
+    } else {
+        format!("&{value}")
+    };
+    let length = if let schema::TypeVariant::String = type_variant {
+        format!("{value}.chars().count()")
     } else {
-        write!(buffer, ".into()")
+        format!("{value}.len()")
+    };
+
+    let mut checks = vec![];
+    if let Some(min) = &constraints.min {
+        let mut condition = "!(".to_owned();
+        write_bound(&mut condition, type_variant, min)?;
+        write!(condition, "..).contains({reference})")?;
+        checks.push((condition, format!("be at least {min}")));
+    }
+    if let Some(max) = &constraints.max {
+        let mut condition = "!(..=".to_owned();
+        write_bound(&mut condition, type_variant, max)?;
+        write!(condition, ").contains({reference})")?;
+        checks.push((condition, format!("be at most {max}")));
+    }
+    match constraints.min_length {
+        None | Some(0) => {}
+        Some(1) => {
+            checks.push((
+                format!("{value}.is_empty()"),
+                "have a length of at least 1".to_owned(),
+            ));
+        }
+        Some(min_length) => {
+            checks.push((
+                format!("{length} < {}_usize", group_digits(min_length as i128)),
+                format!("have a length of at least {min_length}"),
+            ));
+        }
+    }
+    if let Some(max_length) = constraints.max_length {
+        checks.push((
+            format!("{length} > {}_usize", group_digits(max_length as i128)),
+            format!("have a length of at most {max_length}"),
+        ));
+    }
+
+    let io = context.io();
+
+    for (condition, requirement) in checks {
+        write_indentation(buffer, indentation)?;
+        writeln!(buffer, "if {condition} {{")?;
+        write_indentation(buffer, indentation + 1)?;
+        writeln!(buffer, "return Err({io}::Error::new(")?;
+        write_indentation(buffer, indentation + 2)?;
+        writeln!(buffer, "{io}::ErrorKind::InvalidData,")?;
+        write_indentation(buffer, indentation + 2)?;
+        writeln!(
+            buffer,
+            "{:?},",
+            schema::constraint_violation(declaration_name, &field.name, &requirement),
+        )?;
+        write_indentation(buffer, indentation + 1)?;
+        writeln!(buffer, "));")?;
+        write_indentation(buffer, indentation)?;
+        writeln!(buffer, "}}")?;
+    }
+
+    Ok(())
+}
+
+// Write the logic to validate the structs and choices within a value.
+//
+// Context variables:
+// - `payload` (in), by reference
+fn write_validation_invocation<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    type_variant: &schema::TypeVariant,
+) -> Result<(), fmt::Error> {
+    if !contains_struct_or_choice(type_variant) {
+        return Ok(());
+    }
+
+    match type_variant {
+        schema::TypeVariant::Array(inner_type, _) => {
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "for payload in payload {{")?;
+            write_validation_invocation(buffer, indentation + 1, &inner_type.variant)?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "}}")?;
+        }
+        schema::TypeVariant::Custom(_, _, _) => {
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "payload.validate()?;")?;
+        }
+        schema::TypeVariant::Map(_, value_type) => {
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "for payload in payload.values() {{")?;
+            write_validation_invocation(buffer, indentation + 1, &value_type.variant)?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "}}")?;
+        }
+        schema::TypeVariant::Newtype(_, _, underlying_type) => {
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "let payload = &payload.0;")?;
+            write_validation_invocation(buffer, indentation, &underlying_type.variant)?;
+        }
+        schema::TypeVariant::Bool
+        | schema::TypeVariant::Bytes(_)
+        | schema::TypeVariant::F32
+        | schema::TypeVariant::F64
+        | schema::TypeVariant::S32
+        | schema::TypeVariant::S64
+        | schema::TypeVariant::String
+        | schema::TypeVariant::U32
+        | schema::TypeVariant::U64
+        | schema::TypeVariant::U8
+        | schema::TypeVariant::Unit => {
+            // Impossible since these types don't contain structs or choices.
+            panic!()
+        }
+    }
+
+    Ok(())
+}
+
+// Write a function which checks that a message satisfies the constraints of its fields and those of
+// any messages within it [ref:constraints]. Messages that are deserialized have already been
+// checked, but messages that are constructed directly haven't.
+#[allow(clippy::too_many_lines)]
+fn write_validate_function<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    declaration: &schema::Declaration,
+    context: Context,
+) -> Result<(), fmt::Error> {
+    let needs_validation = |field: &schema::Field| {
+        !schema::constraints(&field.attributes).is_empty()
+            || contains_struct_or_choice(&field.r#type.variant)
+    };
+
+    write_indentation(buffer, indentation)?;
+    writeln!(
+        buffer,
+        "pub fn validate(&self) -> {}::Result<()> {{",
+        context.io(),
+    )?;
+    match declaration.variant {
+        schema::DeclarationVariant::Struct => {
+            for field in &declaration.fields {
+                if !needs_validation(field) {
+                    continue;
+                }
+
+                match field.rule {
+                    schema::Rule::Asymmetric | schema::Rule::Required => {
+                        write_indentation(buffer, indentation + 1)?;
+                        writeln!(buffer, "{{")?;
+                        write_indentation(buffer, indentation + 2)?;
+                        write!(buffer, "let payload = &self.")?;
+                        write_identifier(buffer, &field.name, Snake, None)?;
+                        writeln!(buffer, ";")?;
+                    }
+                    schema::Rule::Optional => {
+                        write_indentation(buffer, indentation + 1)?;
+                        write!(buffer, "if let Some(payload) = &self.")?;
+                        write_identifier(buffer, &field.name, Snake, None)?;
+                        writeln!(buffer, " {{")?;
+                    }
+                }
+                write_constraint_checks(
+                    buffer,
+                    indentation + 2,
+                    &declaration.name,
+                    field,
+                    true,
+                    context,
+                )?;
+                write_validation_invocation(buffer, indentation + 2, &field.r#type.variant)?;
+                write_indentation(buffer, indentation + 1)?;
+                writeln!(buffer, "}}")?;
+                writeln!(buffer)?;
+            }
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "Ok(())")?;
+        }
+        schema::DeclarationVariant::Choice => {
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "match *self {{")?; // [ref:empty_enum_ref_match]
+            for field in &declaration.fields {
+                let payload = if needs_validation(field) {
+                    "ref payload"
+                } else {
+                    "_"
+                };
+                write_indentation(buffer, indentation + 2)?;
+                write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
+                write!(buffer, "::")?;
+                write_identifier(buffer, &field.name, Pascal, None)?;
+                match (
+                    &field.rule,
+                    matches!(field.r#type.variant, schema::TypeVariant::Unit),
+                ) {
+                    (schema::Rule::Asymmetric | schema::Rule::Optional, true) => {
+                        writeln!(buffer, "(ref fallback) => {{")?;
+                    }
+                    (schema::Rule::Asymmetric | schema::Rule::Optional, false) => {
+                        writeln!(buffer, "({payload}, ref fallback) => {{")?;
+                    }
+                    (schema::Rule::Required, true) => {
+                        writeln!(buffer, " => {{")?;
+                    }
+                    (schema::Rule::Required, false) => {
+                        writeln!(buffer, "({payload}) => {{")?;
+                    }
+                }
+                if needs_validation(field) {
+                    write_constraint_checks(
+                        buffer,
+                        indentation + 3,
+                        &declaration.name,
+                        field,
+                        true,
+                        context,
+                    )?;
+                    write_validation_invocation(buffer, indentation + 3, &field.r#type.variant)?;
+                }
+                write_indentation(buffer, indentation + 3)?;
+                match field.rule {
+                    schema::Rule::Asymmetric | schema::Rule::Optional => {
+                        writeln!(buffer, "fallback.validate()")?;
+                    }
+                    schema::Rule::Required => {
+                        writeln!(buffer, "Ok(())")?;
+                    }
+                }
+                write_indentation(buffer, indentation + 2)?;
+                writeln!(buffer, "}}")?;
+            }
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "}}")?;
+        }
+        schema::DeclarationVariant::Newtype(_) => {
+            // Impossible since only structs and choices have `validate` functions.
+            panic!()
+        }
     }
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "}}")
 }
 
 // Write the logic to compute the encoded size of a value.
 //
 // Context variables:
 // - `*payload` (in)
+#[allow(clippy::too_many_lines)]
 fn write_atlas_calculation<T: Write>(
     buffer: &mut T,
     supers: usize,
@@ -1822,10 +5127,11 @@ fn write_atlas_calculation<T: Write>(
     write!(buffer, "(")?;
 
     match type_variant {
-        schema::TypeVariant::Array(inner_type) => match &inner_type.variant {
-            schema::TypeVariant::Array(_)
-            | schema::TypeVariant::Bytes
-            | schema::TypeVariant::Custom(_, _)
+        schema::TypeVariant::Array(inner_type, _) => match encoding_type(&inner_type.variant) {
+            schema::TypeVariant::Array(_, _)
+            | schema::TypeVariant::Bytes(_)
+            | schema::TypeVariant::Custom(_, _, _)
+            | schema::TypeVariant::Map(_, _)
             | schema::TypeVariant::String => {
                 write!(buffer, "{{ let atlases = payload.iter().map(|payload| ")?;
                 write_atlas_calculation(buffer, supers, &inner_type.variant, false)?;
@@ -1842,11 +5148,19 @@ fn write_atlas_calculation<T: Write>(
                     "varint_size_from_value(atlas_size as u64) + atlas_size }}), atlases) }}",
                 )?;
             }
-            schema::TypeVariant::Bool | schema::TypeVariant::S64 | schema::TypeVariant::U64 => {
+            schema::TypeVariant::Bool
+            | schema::TypeVariant::S32
+            | schema::TypeVariant::S64
+            | schema::TypeVariant::U32
+            | schema::TypeVariant::U64
+            | schema::TypeVariant::U8 => {
                 write!(buffer, "payload.iter().fold(0_usize, |x, payload| x + ")?;
                 write_atlas_calculation(buffer, supers, &inner_type.variant, false)?;
                 write!(buffer, ")")?;
             }
+            schema::TypeVariant::F32 => {
+                write!(buffer, "4_usize * payload.len()")?;
+            }
             schema::TypeVariant::F64 => {
                 write!(buffer, "8_usize * payload.len()")?;
             }
@@ -1855,7 +5169,50 @@ fn write_atlas_calculation<T: Write>(
                 write_atlas_calculation(buffer, supers, &schema::TypeVariant::U64, is_field)?;
                 write!(buffer, " }}")?;
             }
+            schema::TypeVariant::Newtype(_, _, _) => {
+                // Impossible since `encoding_type` strips away newtypes.
+                panic!()
+            }
         },
+        schema::TypeVariant::Map(key_type, value_type) => {
+            // Each entry is encoded as a struct [ref:map_encoding].
+            write!(
+                buffer,
+                "{{ let atlases = payload.iter().map(|(key, value)| {{ let key_atlas = {{ \
+                    let payload = key; ",
+            )?;
+            write_atlas_calculation(buffer, supers, &key_type.variant, true)?;
+            write!(buffer, " }}; let value_atlas = {{ let payload = value; ")?;
+            write_atlas_calculation(buffer, supers, &value_type.variant, true)?;
+            write!(
+                buffer,
+                " }}; let entry_size = {{ let payload_atlas = &key_atlas; let payload_size = ",
+            )?;
+            write_atlas_lookup(buffer, &key_type.variant)?;
+            write!(buffer, "; ")?;
+            write_supers(buffer, supers)?;
+            write!(
+                buffer,
+                "field_header_size(0_u64, payload_size, {}) + payload_size }} + {{ \
+                    let payload_atlas = &value_atlas; let payload_size = ",
+                integer_encoded(key_type),
+            )?;
+            write_atlas_lookup(buffer, &value_type.variant)?;
+            write!(buffer, "; ")?;
+            write_supers(buffer, supers)?;
+            write!(
+                buffer,
+                "field_header_size(1_u64, payload_size, {}) + payload_size }}; \
+                    (entry_size, key_atlas, value_atlas) }}).collect::<Vec<_>>(); \
+                    (atlases.iter().fold(0_usize, |x, (entry_size, _, _)| x + ",
+                integer_encoded(value_type),
+            )?;
+            write_supers(buffer, supers)?;
+            write!(
+                buffer,
+                "varint_size_from_value(*entry_size as u64) + entry_size), atlases) }}",
+            )?;
+        }
         schema::TypeVariant::Bool => {
             if is_field {
                 write!(buffer, "usize::from(*payload)")?;
@@ -1863,12 +5220,28 @@ fn write_atlas_calculation<T: Write>(
                 write!(buffer, "1_usize")?;
             }
         }
-        schema::TypeVariant::Bytes | schema::TypeVariant::String => {
+        schema::TypeVariant::Bytes(_) | schema::TypeVariant::String => {
             write!(buffer, "payload.len()")?;
         }
-        schema::TypeVariant::Custom(_, _) => {
+        schema::TypeVariant::Custom(_, _, _) => {
             write!(buffer, "payload.atlas()")?;
         }
+        schema::TypeVariant::Newtype(_, _, underlying_type) => {
+            // Newtypes are encoded like their underlying types [ref:newtype_encoding].
+            write!(buffer, "{{ let payload = &payload.0; ")?;
+            write_atlas_calculation(buffer, supers, &underlying_type.variant, is_field)?;
+            write!(buffer, " }}")?;
+        }
+        schema::TypeVariant::F32 => {
+            if is_field {
+                write!(
+                    buffer,
+                    "if payload.to_bits() == 0_u32 {{ 0_usize }} else {{ 4_usize }}",
+                )?;
+            } else {
+                write!(buffer, "4_usize")?;
+            }
+        }
         schema::TypeVariant::F64 => {
             if is_field {
                 write!(
@@ -1879,6 +5252,12 @@ fn write_atlas_calculation<T: Write>(
                 write!(buffer, "8_usize")?;
             }
         }
+        schema::TypeVariant::S32 => {
+            // `S32` has the same encoding as `S64` [ref:narrow_integer_encoding].
+            write!(buffer, "{{ let payload = &i64::from(*payload); ")?;
+            write_atlas_calculation(buffer, supers, &schema::TypeVariant::S64, is_field)?;
+            write!(buffer, " }}")?;
+        }
         schema::TypeVariant::S64 => {
             write!(buffer, "{{ let zigzag = ")?;
             write_supers(buffer, supers)?;
@@ -1904,6 +5283,12 @@ fn write_atlas_calculation<T: Write>(
                 write!(buffer, "varint_size_from_value(*payload)")?;
             }
         }
+        schema::TypeVariant::U32 | schema::TypeVariant::U8 => {
+            // `U32` and `U8` have the same encoding as `U64` [ref:narrow_integer_encoding].
+            write!(buffer, "{{ let payload = &u64::from(*payload); ")?;
+            write_atlas_calculation(buffer, supers, &schema::TypeVariant::U64, is_field)?;
+            write!(buffer, " }}")?;
+        }
         schema::TypeVariant::Unit => {
             write!(buffer, "0_usize")?;
         }
@@ -1921,25 +5306,42 @@ fn write_atlas_lookup<T: Write>(
     type_variant: &schema::TypeVariant,
 ) -> Result<(), fmt::Error> {
     match type_variant {
-        schema::TypeVariant::Array(inner_type) => match &inner_type.variant {
-            schema::TypeVariant::Array(_)
-            | schema::TypeVariant::Bytes
-            | schema::TypeVariant::Custom(_, _)
+        schema::TypeVariant::Array(inner_type, _) => match encoding_type(&inner_type.variant) {
+            schema::TypeVariant::Array(_, _)
+            | schema::TypeVariant::Bytes(_)
+            | schema::TypeVariant::Custom(_, _, _)
+            | schema::TypeVariant::Map(_, _)
             | schema::TypeVariant::String => write!(buffer, "payload_atlas.0"),
+            schema::TypeVariant::Newtype(_, _, _) => {
+                // Impossible since `encoding_type` strips away newtypes.
+                panic!()
+            }
             schema::TypeVariant::Bool
+            | schema::TypeVariant::F32
             | schema::TypeVariant::F64
+            | schema::TypeVariant::S32
             | schema::TypeVariant::S64
+            | schema::TypeVariant::U32
             | schema::TypeVariant::U64
+            | schema::TypeVariant::U8
             | schema::TypeVariant::Unit => write!(buffer, "*payload_atlas"),
         },
+        schema::TypeVariant::Map(_, _) => write!(buffer, "payload_atlas.0"),
         schema::TypeVariant::Bool
-        | schema::TypeVariant::Bytes
+        | schema::TypeVariant::Bytes(_)
+        | schema::TypeVariant::F32
         | schema::TypeVariant::F64
+        | schema::TypeVariant::S32
         | schema::TypeVariant::S64
         | schema::TypeVariant::String
+        | schema::TypeVariant::U32
         | schema::TypeVariant::U64
+        | schema::TypeVariant::U8
         | schema::TypeVariant::Unit => write!(buffer, "*payload_atlas"),
-        schema::TypeVariant::Custom(_, _) => write!(buffer, "payload_atlas.size()"),
+        schema::TypeVariant::Custom(_, _, _) => write!(buffer, "payload_atlas.size()"),
+        schema::TypeVariant::Newtype(_, _, underlying_type) => {
+            write_atlas_lookup(buffer, &underlying_type.variant)
+        }
     }
 }
 
@@ -1951,7 +5353,7 @@ fn write_atlas_lookup<T: Write>(
 // - `*writer` (in and out)
 //
 // Additional notes:
-// - If `is_field` is unset and `type_variant` is `Bool`, `S64`, `U64`, or `F64`, then
+// - If `is_field` is unset and `type_variant` is `Bool`, a numeric type, or `F64`, then
 //   `payload_atlas` is never read.
 #[allow(clippy::too_many_lines)]
 fn write_serialization_invocation<T: Write>(
@@ -1960,12 +5362,17 @@ fn write_serialization_invocation<T: Write>(
     supers: usize,
     type_variant: &schema::TypeVariant,
     is_field: bool,
+    context: Context,
 ) -> Result<(), fmt::Error> {
+    let suffix = context.suffix();
+    let wait = context.wait();
+
     match type_variant {
-        schema::TypeVariant::Array(inner_type) => match &inner_type.variant {
-            schema::TypeVariant::Array(_)
-            | schema::TypeVariant::Bytes
-            | schema::TypeVariant::Custom(_, _)
+        schema::TypeVariant::Array(inner_type, _) => match encoding_type(&inner_type.variant) {
+            schema::TypeVariant::Array(_, _)
+            | schema::TypeVariant::Bytes(_)
+            | schema::TypeVariant::Custom(_, _, _)
+            | schema::TypeVariant::Map(_, _)
             | schema::TypeVariant::String => {
                 write_indentation(buffer, indentation)?;
                 writeln!(
@@ -1975,22 +5382,27 @@ fn write_serialization_invocation<T: Write>(
                 )?;
                 write_indentation(buffer, indentation + 1)?;
                 write_supers(buffer, supers)?;
-                write!(buffer, "serialize_varint(")?;
+                write!(buffer, "serialize_varint{suffix}(")?;
                 write_atlas_lookup(buffer, &inner_type.variant)?;
-                writeln!(buffer, " as u64, writer)?;")?;
+                writeln!(buffer, " as u64, writer){wait}?;")?;
                 write_serialization_invocation(
                     buffer,
                     indentation + 1,
                     supers,
                     &inner_type.variant,
                     false,
+                    context,
                 )?;
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "}}")
             }
             schema::TypeVariant::Bool
+            | schema::TypeVariant::S32
             | schema::TypeVariant::S64
+            | schema::TypeVariant::U32
             | schema::TypeVariant::U64
+            | schema::TypeVariant::U8
+            | schema::TypeVariant::F32
             | schema::TypeVariant::F64 => {
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "for payload in payload {{")?;
@@ -2000,6 +5412,7 @@ fn write_serialization_invocation<T: Write>(
                     supers,
                     &inner_type.variant,
                     false,
+                    context,
                 )?;
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "}}")
@@ -2009,41 +5422,123 @@ fn write_serialization_invocation<T: Write>(
                 writeln!(buffer, "{{")?;
                 write_indentation(buffer, indentation + 1)?;
                 writeln!(buffer, "let varint = payload.len() as u64;")?;
-                write_u64_serialization_invocation(buffer, indentation + 1, supers, is_field)?;
+                write_u64_serialization_invocation(
+                    buffer,
+                    indentation + 1,
+                    supers,
+                    is_field,
+                    context,
+                )?;
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "}}")
             }
+            schema::TypeVariant::Newtype(_, _, _) => {
+                // Impossible since `encoding_type` strips away newtypes.
+                panic!()
+            }
         },
+        schema::TypeVariant::Map(key_type, value_type) => {
+            // Each entry is encoded as a struct [ref:map_encoding].
+            write_indentation(buffer, indentation)?;
+            writeln!(
+                buffer,
+                "for ((key, value), (entry_size, key_atlas, value_atlas)) in \
+                    payload.iter().zip(payload_atlas.1.iter()) {{",
+            )?;
+            write_indentation(buffer, indentation + 1)?;
+            write_supers(buffer, supers)?;
+            writeln!(
+                buffer,
+                "serialize_varint{suffix}(*entry_size as u64, writer){wait}?;",
+            )?;
+            for (index, name, r#type) in [(0, "key", key_type), (1, "value", value_type)] {
+                write_indentation(buffer, indentation + 1)?;
+                writeln!(buffer, "{{")?;
+                write_indentation(buffer, indentation + 2)?;
+                writeln!(buffer, "let payload = {name};")?;
+                write_indentation(buffer, indentation + 2)?;
+                writeln!(buffer, "let payload_atlas = {name}_atlas;")?;
+                write_indentation(buffer, indentation + 2)?;
+                write_supers(buffer, supers)?;
+                write!(
+                    buffer,
+                    "serialize_field_header{suffix}(writer, {index}_u64, "
+                )?;
+                write_atlas_lookup(buffer, &r#type.variant)?;
+                writeln!(buffer, ", {}){wait}?;", integer_encoded(r#type))?;
+                write_serialization_invocation(
+                    buffer,
+                    indentation + 2,
+                    supers,
+                    &r#type.variant,
+                    true,
+                    context,
+                )?;
+                write_indentation(buffer, indentation + 1)?;
+                writeln!(buffer, "}}")?;
+            }
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "}}")
+        }
         schema::TypeVariant::Bool => {
             write_indentation(buffer, indentation)?;
             writeln!(buffer, "{{")?;
             write_indentation(buffer, indentation + 1)?;
             writeln!(buffer, "let varint = u64::from(*payload);")?;
-            write_u64_serialization_invocation(buffer, indentation + 1, supers, is_field)?;
+            write_u64_serialization_invocation(buffer, indentation + 1, supers, is_field, context)?;
             write_indentation(buffer, indentation)?;
             writeln!(buffer, "}}")
         }
-        schema::TypeVariant::Bytes => {
+        schema::TypeVariant::Bytes(_) => {
             write_indentation(buffer, indentation)?;
-            writeln!(buffer, "writer.write_all(payload)?;")
+            writeln!(buffer, "{}?;", context.write_all("payload"))
         }
-        schema::TypeVariant::Custom(_, _) => {
+        schema::TypeVariant::Custom(_, _, _) => {
             write_indentation(buffer, indentation)?;
             writeln!(
                 buffer,
-                "payload.serialize_with_atlas(writer, payload_atlas)?;",
+                "payload.serialize_with_atlas{suffix}(writer, payload_atlas){wait}?;",
             )
         }
+        schema::TypeVariant::Newtype(_, _, underlying_type) => {
+            // Newtypes are encoded like their underlying types [ref:newtype_encoding].
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "{{")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "let payload = &payload.0;")?;
+            write_serialization_invocation(
+                buffer,
+                indentation + 1,
+                supers,
+                &underlying_type.variant,
+                is_field,
+                context,
+            )?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "}}")
+        }
+        schema::TypeVariant::F32 => {
+            write_indentation(buffer, indentation)?;
+            if is_field {
+                writeln!(buffer, "if payload.to_bits() != 0_u32 {{")?;
+                write_indentation(buffer, indentation + 1)?;
+                writeln!(buffer, "{}?;", context.write_all("&payload.to_le_bytes()"))?;
+                write_indentation(buffer, indentation)?;
+                writeln!(buffer, "}}")
+            } else {
+                writeln!(buffer, "{}?;", context.write_all("&payload.to_le_bytes()"))
+            }
+        }
         schema::TypeVariant::F64 => {
             write_indentation(buffer, indentation)?;
             if is_field {
                 writeln!(buffer, "if payload.to_bits() != 0_u64 {{")?;
                 write_indentation(buffer, indentation + 1)?;
-                writeln!(buffer, "writer.write_all(&payload.to_le_bytes())?;")?;
+                writeln!(buffer, "{}?;", context.write_all("&payload.to_le_bytes()"))?;
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "}}")
             } else {
-                writeln!(buffer, "writer.write_all(&payload.to_le_bytes())?;")
+                writeln!(buffer, "{}?;", context.write_all("&payload.to_le_bytes()"))
             }
         }
         schema::TypeVariant::S64 => {
@@ -2053,20 +5548,42 @@ fn write_serialization_invocation<T: Write>(
             write!(buffer, "let varint = ")?;
             write_supers(buffer, supers)?;
             writeln!(buffer, "zigzag_encode(*payload);")?;
-            write_u64_serialization_invocation(buffer, indentation + 1, supers, is_field)?;
+            write_u64_serialization_invocation(buffer, indentation + 1, supers, is_field, context)?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "}}")
+        }
+        schema::TypeVariant::S32 => {
+            // `S32` has the same encoding as `S64` [ref:narrow_integer_encoding].
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "{{")?;
+            write_indentation(buffer, indentation + 1)?;
+            write!(buffer, "let varint = ")?;
+            write_supers(buffer, supers)?;
+            writeln!(buffer, "zigzag_encode(i64::from(*payload));")?;
+            write_u64_serialization_invocation(buffer, indentation + 1, supers, is_field, context)?;
             write_indentation(buffer, indentation)?;
             writeln!(buffer, "}}")
         }
         schema::TypeVariant::String => {
             write_indentation(buffer, indentation)?;
-            writeln!(buffer, "writer.write_all(payload.as_bytes())?;")
+            writeln!(buffer, "{}?;", context.write_all("payload.as_bytes()"))
         }
         schema::TypeVariant::U64 => {
             write_indentation(buffer, indentation)?;
             writeln!(buffer, "{{")?;
             write_indentation(buffer, indentation + 1)?;
             writeln!(buffer, "let varint = *payload;")?;
-            write_u64_serialization_invocation(buffer, indentation + 1, supers, is_field)?;
+            write_u64_serialization_invocation(buffer, indentation + 1, supers, is_field, context)?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "}}")
+        }
+        schema::TypeVariant::U32 | schema::TypeVariant::U8 => {
+            // `U32` and `U8` have the same encoding as `U64` [ref:narrow_integer_encoding].
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "{{")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "let varint = u64::from(*payload);")?;
+            write_u64_serialization_invocation(buffer, indentation + 1, supers, is_field, context)?;
             write_indentation(buffer, indentation)?;
             writeln!(buffer, "}}")
         }
@@ -2084,7 +5601,11 @@ fn write_u64_serialization_invocation<T: Write>(
     indentation: usize,
     supers: usize,
     is_field: bool,
+    context: Context,
 ) -> Result<(), fmt::Error> {
+    let suffix = context.suffix();
+    let wait = context.wait();
+
     write_indentation(buffer, indentation)?;
     if is_field {
         writeln!(buffer, "match varint {{")?;
@@ -2093,18 +5614,18 @@ fn write_u64_serialization_invocation<T: Write>(
         write_indentation(buffer, indentation + 1)?;
         write!(buffer, "1_u64..=567_382_630_219_903_u64 => ")?;
         write_supers(buffer, supers)?;
-        writeln!(buffer, "serialize_varint(varint, writer)?,")?;
+        writeln!(buffer, "serialize_varint{suffix}(varint, writer){wait}?,")?;
         write_indentation(buffer, indentation + 1)?;
         writeln!(
             buffer,
-            "567_382_630_219_904_u64..=18_446_744_073_709_551_615_u64 => \
-                writer.write_all(&varint.to_le_bytes())?,",
+            "567_382_630_219_904_u64..=18_446_744_073_709_551_615_u64 => {}?,",
+            context.write_all("&varint.to_le_bytes()"),
         )?;
         write_indentation(buffer, indentation)?;
         writeln!(buffer, "}}")
     } else {
         write_supers(buffer, supers)?;
-        writeln!(buffer, "serialize_varint(varint, writer)?;")
+        writeln!(buffer, "serialize_varint{suffix}(varint, writer){wait}?;")
     }
 }
 
@@ -2118,9 +5639,9 @@ fn write_u64_serialization_invocation<T: Write>(
 // Additional notes:
 // - If `type_variant` is `Array`, `Bytes`, `Custom`, or `String` and the encoded data is well-
 //   formed, then `sub_reader` is consumed to the end.
-// - If `type_variant` is `Array` and the element type is `Bool`, `S64`, or `U64`, then
+// - If `type_variant` is `Array` and the element type is `Bool` or an integer type, then
 //   `payload_size` is never read.
-#[allow(clippy::too_many_lines)]
+#[allow(clippy::too_many_arguments)]
 fn write_deserialization_invocation<T: Write>(
     buffer: &mut T,
     indentation: usize,
@@ -2129,12 +5650,97 @@ fn write_deserialization_invocation<T: Write>(
     namespace: &schema::Namespace,
     type_variant: &schema::TypeVariant,
     is_field: bool,
+    context: Context,
+) -> Result<(), fmt::Error> {
+    // Fixed-length values are decoded like variable-length ones [ref:fixed_length_encoding] and
+    // then converted, which checks their lengths.
+    let (variable_length_variant, length) = match type_variant {
+        schema::TypeVariant::Array(inner_type, Some(length)) => {
+            (schema::TypeVariant::Array(inner_type.clone(), None), length)
+        }
+        schema::TypeVariant::Bytes(Some(length)) => (schema::TypeVariant::Bytes(None), length),
+        _ => {
+            return write_variable_length_deserialization_invocation(
+                buffer,
+                indentation,
+                supers,
+                imports,
+                namespace,
+                type_variant,
+                is_field,
+                context,
+            );
+        }
+    };
+
+    write_variable_length_deserialization_invocation(
+        buffer,
+        indentation,
+        supers,
+        imports,
+        namespace,
+        &variable_length_variant,
+        is_field,
+        context,
+    )?;
+    let io = context.io();
+
+    if context.borrowing.is_some() && matches!(type_variant, schema::TypeVariant::Array(_, _)) {
+        // Fixed-length arrays aren't borrowed, but their elements may be [ref:borrowed_types].
+        write_indentation(buffer, indentation)?;
+        writeln!(buffer, "let payload = payload.iter().collect::<Vec<_>>();")?;
+    }
+    write_indentation(buffer, indentation)?;
+    writeln!(
+        buffer,
+        "let payload = <[_; {length}]>::try_from(payload).map_err(|_| {io}::Error::new(",
+    )?;
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(buffer, "{io}::ErrorKind::InvalidData,")?;
+    write_indentation(buffer, indentation + 1)?;
+    write_supers(buffer, supers)?;
+    writeln!(buffer, "WRONG_LENGTH_ERROR_MESSAGE,")?;
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "))?;")
+}
+
+// Write the logic to invoke the deserialization logic for a value of a type which isn't a fixed-
+// length array or `Bytes`, including a trailing line break. The context variables and notes are the
+// same as for `write_deserialization_invocation`.
+#[allow(clippy::too_many_arguments, clippy::too_many_lines)]
+fn write_variable_length_deserialization_invocation<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    supers: usize,
+    imports: &BTreeMap<Identifier, schema::Namespace>,
+    namespace: &schema::Namespace,
+    type_variant: &schema::TypeVariant,
+    is_field: bool,
+    context: Context,
 ) -> Result<(), fmt::Error> {
+    let io = context.io();
+    let read = context.read();
+    let suffix = context.suffix();
+    let wait = context.wait();
+
     match type_variant {
-        schema::TypeVariant::Array(inner_type) => match &inner_type.variant {
-            schema::TypeVariant::Array(_)
-            | schema::TypeVariant::Bytes
-            | schema::TypeVariant::Custom(_, _)
+        schema::TypeVariant::Array(inner_type, _) if context.borrowing.is_some() => {
+            write_array_view_deserialization_invocation(
+                buffer,
+                indentation,
+                supers,
+                imports,
+                namespace,
+                inner_type,
+                is_field,
+                context,
+            )
+        }
+        schema::TypeVariant::Array(inner_type, _) => match encoding_type(&inner_type.variant) {
+            schema::TypeVariant::Array(_, _)
+            | schema::TypeVariant::Bytes(_)
+            | schema::TypeVariant::Custom(_, _, _)
+            | schema::TypeVariant::Map(_, _)
             | schema::TypeVariant::String => {
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "let mut payload = Vec::new();")?;
@@ -2143,7 +5749,10 @@ fn write_deserialization_invocation<T: Write>(
                 write_indentation(buffer, indentation + 1)?;
                 write!(buffer, "let payload_size = match ")?;
                 write_supers(buffer, supers)?;
-                writeln!(buffer, "deserialize_varint(&mut sub_reader) {{")?;
+                writeln!(
+                    buffer,
+                    "deserialize_varint{suffix}(&mut sub_reader){wait} {{"
+                )?;
                 write_indentation(buffer, indentation + 2)?;
                 writeln!(buffer, "Ok(payload_size) => payload_size as usize,")?;
                 write_indentation(buffer, indentation + 2)?;
@@ -2151,7 +5760,7 @@ fn write_deserialization_invocation<T: Write>(
                 write_indentation(buffer, indentation + 3)?;
                 writeln!(
                     buffer,
-                    "if let std::io::ErrorKind::UnexpectedEof = err.kind() {{",
+                    "if let {io}::ErrorKind::UnexpectedEof = err.kind() {{",
                 )?;
                 write_indentation(buffer, indentation + 4)?;
                 writeln!(buffer, "break;")?;
@@ -2166,8 +5775,7 @@ fn write_deserialization_invocation<T: Write>(
                 write_indentation(buffer, indentation + 1)?;
                 writeln!(
                     buffer,
-                    "let mut sub_reader = ::std::io::Read::take(\
-                            &mut sub_reader, payload_size as u64);",
+                    "let mut sub_reader = {read}::take(&mut sub_reader, payload_size as u64);",
                 )?;
                 write_indentation(buffer, indentation + 1)?;
                 writeln!(buffer, "payload.push({{")?;
@@ -2179,6 +5787,7 @@ fn write_deserialization_invocation<T: Write>(
                     namespace,
                     &inner_type.variant,
                     false,
+                    context,
                 )?;
                 write_indentation(buffer, indentation + 2)?;
                 writeln!(buffer, "payload")?;
@@ -2188,16 +5797,26 @@ fn write_deserialization_invocation<T: Write>(
                 writeln!(buffer, "}}")
             }
             schema::TypeVariant::Bool
+            | schema::TypeVariant::F32
             | schema::TypeVariant::F64
+            | schema::TypeVariant::S32
             | schema::TypeVariant::S64
-            | schema::TypeVariant::U64 => {
+            | schema::TypeVariant::U32
+            | schema::TypeVariant::U64
+            | schema::TypeVariant::U8 => {
+                // The generic `BufRead` bound doesn't extend to mutable references when the traits
+                // are implemented in terms of those from `std`, so the element reader is taken by
+                // value.
+                let element_reader = if context.no_std { "T" } else { "&mut T" };
                 write_indentation(buffer, indentation)?;
                 write!(
                     buffer,
-                    "fn deserialize_element<T: ::std::io::BufRead>(mut sub_reader: &mut T) -> \
-                            ::std::io::Result<",
+                    "{}fn deserialize_element<T: {}>(mut sub_reader: {element_reader}) \
+                            -> {io}::Result<",
+                    context.async_keyword(),
+                    context.reader_bound(),
                 )?;
-                write_type(buffer, imports, namespace, &inner_type.variant, In)?;
+                write_type(buffer, imports, namespace, &inner_type.variant, In, context)?;
                 writeln!(buffer, "> {{")?;
                 write_deserialization_invocation(
                     buffer,
@@ -2207,6 +5826,7 @@ fn write_deserialization_invocation<T: Write>(
                     namespace,
                     &inner_type.variant,
                     false,
+                    context,
                 )?;
                 write_indentation(buffer, indentation + 1)?;
                 writeln!(buffer, "Ok(payload)")?;
@@ -2214,7 +5834,7 @@ fn write_deserialization_invocation<T: Write>(
                 writeln!(buffer, "}}")?;
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "let mut payload = Vec::new();")?;
-                if matches!(inner_type.variant, schema::TypeVariant::F64) {
+                if matches!(encoding_type(&inner_type.variant), schema::TypeVariant::F64) {
                     write_indentation(buffer, indentation)?;
                     writeln!(buffer, "payload.reserve_exact(payload_size);")?;
                 }
@@ -2223,7 +5843,7 @@ fn write_deserialization_invocation<T: Write>(
                 write_indentation(buffer, indentation + 1)?;
                 writeln!(
                     buffer,
-                    "payload.push(match deserialize_element(&mut sub_reader) {{",
+                    "payload.push(match deserialize_element(&mut sub_reader){wait} {{",
                 )?;
                 write_indentation(buffer, indentation + 2)?;
                 writeln!(buffer, "Ok(element) => element,")?;
@@ -2232,7 +5852,7 @@ fn write_deserialization_invocation<T: Write>(
                 write_indentation(buffer, indentation + 3)?;
                 writeln!(
                     buffer,
-                    "if let std::io::ErrorKind::UnexpectedEof = err.kind() {{",
+                    "if let {io}::ErrorKind::UnexpectedEof = err.kind() {{",
                 )?;
                 write_indentation(buffer, indentation + 4)?;
                 writeln!(buffer, "break;")?;
@@ -2256,11 +5876,135 @@ fn write_deserialization_invocation<T: Write>(
                     namespace,
                     &schema::TypeVariant::U64,
                     is_field,
+                    context,
                 )?;
                 write_indentation(buffer, indentation)?;
-                writeln!(buffer, "let payload = vec![(); payload as usize];")
+                writeln!(
+                    buffer,
+                    "let payload = {}[(); payload as usize];",
+                    context.vec_macro(),
+                )
+            }
+            schema::TypeVariant::Newtype(_, _, _) => {
+                // Impossible since `encoding_type` strips away newtypes.
+                panic!()
             }
         },
+        schema::TypeVariant::Map(key_type, value_type) => {
+            // Each entry is encoded as a struct [ref:map_encoding].
+            write_indentation(buffer, indentation)?;
+            writeln!(
+                buffer,
+                "let mut payload = {}::collections::BTreeMap::new();",
+                context.alloc(),
+            )?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "loop {{")?;
+            write_indentation(buffer, indentation + 1)?;
+            write!(buffer, "let payload_size = match ")?;
+            write_supers(buffer, supers)?;
+            writeln!(
+                buffer,
+                "deserialize_varint{suffix}(&mut sub_reader){wait} {{"
+            )?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "Ok(payload_size) => payload_size as usize,")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "Err(err) => {{")?;
+            write_indentation(buffer, indentation + 3)?;
+            writeln!(
+                buffer,
+                "if let {io}::ErrorKind::UnexpectedEof = err.kind() {{",
+            )?;
+            write_indentation(buffer, indentation + 4)?;
+            writeln!(buffer, "break;")?;
+            write_indentation(buffer, indentation + 3)?;
+            writeln!(buffer, "}}")?;
+            write_indentation(buffer, indentation + 3)?;
+            writeln!(buffer, "return Err(err);")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "}}")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "}};")?;
+            write_sub_reader(buffer, indentation + 1, supers, "&mut sub_reader", context)?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "let mut key = None;")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "let mut value = None;")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "loop {{")?;
+            write_indentation(buffer, indentation + 2)?;
+            write!(buffer, "let (index, payload_size) = match ")?;
+            write_supers(buffer, supers)?;
+            writeln!(
+                buffer,
+                "deserialize_field_header{suffix}(&mut sub_reader){wait} {{",
+            )?;
+            write_indentation(buffer, indentation + 3)?;
+            writeln!(buffer, "Ok(header) => header,")?;
+            write_indentation(buffer, indentation + 3)?;
+            writeln!(buffer, "Err(err) => {{")?;
+            write_indentation(buffer, indentation + 4)?;
+            writeln!(
+                buffer,
+                "if let {io}::ErrorKind::UnexpectedEof = err.kind() {{",
+            )?;
+            write_indentation(buffer, indentation + 5)?;
+            writeln!(buffer, "break;")?;
+            write_indentation(buffer, indentation + 4)?;
+            writeln!(buffer, "}}")?;
+            write_indentation(buffer, indentation + 4)?;
+            writeln!(buffer, "return Err(err);")?;
+            write_indentation(buffer, indentation + 3)?;
+            writeln!(buffer, "}}")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "}};")?;
+            write_sub_reader(buffer, indentation + 2, supers, "&mut sub_reader", context)?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "match index {{")?;
+            for (index, name, r#type) in [(0, "key", key_type), (1, "value", value_type)] {
+                write_indentation(buffer, indentation + 3)?;
+                writeln!(buffer, "{index} => {{")?;
+                write_deserialization_invocation(
+                    buffer,
+                    indentation + 4,
+                    supers,
+                    imports,
+                    namespace,
+                    &r#type.variant,
+                    true,
+                    context,
+                )?;
+                write_indentation(buffer, indentation + 4)?;
+                writeln!(buffer, "{name}.get_or_insert(payload);")?;
+                write_indentation(buffer, indentation + 3)?;
+                writeln!(buffer, "}}")?;
+            }
+            write_skip(buffer, indentation + 3, supers, context)?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "}}")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "}}")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "if let (Some(key), Some(value)) = (key, value) {{")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "payload.insert(key, value);")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "}} else {{")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "return Err({io}::Error::new(")?;
+            write_indentation(buffer, indentation + 3)?;
+            writeln!(buffer, "{io}::ErrorKind::InvalidData,")?;
+            write_indentation(buffer, indentation + 3)?;
+            write_supers(buffer, supers)?;
+            writeln!(buffer, "MISSING_FIELDS_ERROR_MESSAGE,")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "));")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "}}")?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "}}")
+        }
         schema::TypeVariant::Bool => {
             write_deserialization_invocation(
                 buffer,
@@ -2270,26 +6014,95 @@ fn write_deserialization_invocation<T: Write>(
                 namespace,
                 &schema::TypeVariant::U64,
                 is_field,
+                context,
             )?;
             write_indentation(buffer, indentation)?;
             writeln!(buffer, "let payload = payload != 0_u64;")
         }
-        schema::TypeVariant::Bytes => {
+        schema::TypeVariant::Bytes(_) if context.borrowing.is_some() => {
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "let payload = sub_reader;")
+        }
+        schema::TypeVariant::Bytes(_) => {
             write_indentation(buffer, indentation)?;
-            writeln!(buffer, "let mut payload = vec![];")?;
+            writeln!(buffer, "let mut payload = {}[];", context.vec_macro())?;
             write_indentation(buffer, indentation)?;
             writeln!(buffer, "payload.reserve_exact(payload_size);")?;
             write_indentation(buffer, indentation)?;
             writeln!(
                 buffer,
-                "::std::io::Read::read_to_end(&mut sub_reader, &mut payload)?;",
+                "{read}::read_to_end(&mut sub_reader, &mut payload){wait}?;",
+            )
+        }
+        schema::TypeVariant::Custom(_, _, _) => {
+            write_indentation(buffer, indentation)?;
+            write!(buffer, "let payload = ")?;
+            write_type(
+                buffer,
+                imports,
+                namespace,
+                type_variant,
+                In,
+                context.without_borrowing(),
+            )?;
+            writeln!(
+                buffer,
+                "::deserialize_from_reader_ref{suffix}(&mut sub_reader){wait}?;",
             )
         }
-        schema::TypeVariant::Custom(_, _) => {
+        schema::TypeVariant::Newtype(_, _, underlying_type) => {
+            // Newtypes are encoded like their underlying types [ref:newtype_encoding].
+            write_deserialization_invocation(
+                buffer,
+                indentation,
+                supers,
+                imports,
+                namespace,
+                &underlying_type.variant,
+                is_field,
+                context,
+            )?;
             write_indentation(buffer, indentation)?;
             write!(buffer, "let payload = ")?;
-            write_type(buffer, imports, namespace, type_variant, In)?;
-            writeln!(buffer, "::deserialize_from_reader_ref(&mut sub_reader)?;")
+            write_type(
+                buffer,
+                imports,
+                namespace,
+                type_variant,
+                In,
+                context.without_borrowing(),
+            )?;
+            writeln!(buffer, "(payload);")
+        }
+        schema::TypeVariant::F32 => {
+            write_indentation(buffer, indentation)?;
+            if is_field {
+                writeln!(buffer, "let payload = if payload_size == 0_usize {{")?;
+                write_indentation(buffer, indentation + 1)?;
+                writeln!(buffer, "0.0_f32")?;
+                write_indentation(buffer, indentation)?;
+                writeln!(buffer, "}} else {{")?;
+                write_indentation(buffer, indentation + 1)?;
+                writeln!(buffer, "let mut buffer = [0; 4];")?;
+                write_indentation(buffer, indentation + 1)?;
+                writeln!(
+                    buffer,
+                    "{read}::read_exact(&mut sub_reader, &mut buffer){wait}?;",
+                )?;
+                write_indentation(buffer, indentation + 1)?;
+                writeln!(buffer, "f32::from_le_bytes(buffer)")?;
+                write_indentation(buffer, indentation)?;
+                writeln!(buffer, "}};")
+            } else {
+                writeln!(buffer, "let mut buffer = [0; 4];")?;
+                write_indentation(buffer, indentation)?;
+                writeln!(
+                    buffer,
+                    "{read}::read_exact(&mut sub_reader, &mut buffer){wait}?;",
+                )?;
+                write_indentation(buffer, indentation)?;
+                writeln!(buffer, "let payload = f32::from_le_bytes(buffer);")
+            }
         }
         schema::TypeVariant::F64 => {
             write_indentation(buffer, indentation)?;
@@ -2304,7 +6117,7 @@ fn write_deserialization_invocation<T: Write>(
                 write_indentation(buffer, indentation + 1)?;
                 writeln!(
                     buffer,
-                    "::std::io::Read::read_exact(&mut sub_reader, &mut buffer)?;",
+                    "{read}::read_exact(&mut sub_reader, &mut buffer){wait}?;",
                 )?;
                 write_indentation(buffer, indentation + 1)?;
                 writeln!(buffer, "f64::from_le_bytes(buffer)")?;
@@ -2315,26 +6128,56 @@ fn write_deserialization_invocation<T: Write>(
                 write_indentation(buffer, indentation)?;
                 writeln!(
                     buffer,
-                    "::std::io::Read::read_exact(&mut sub_reader, &mut buffer)?;",
+                    "{read}::read_exact(&mut sub_reader, &mut buffer){wait}?;",
                 )?;
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "let payload = f64::from_le_bytes(buffer);")
             }
         }
-        schema::TypeVariant::S64 => {
+        schema::TypeVariant::S64 => {
+            write_deserialization_invocation(
+                buffer,
+                indentation,
+                supers,
+                imports,
+                namespace,
+                &schema::TypeVariant::U64,
+                is_field,
+                context,
+            )?;
+            write_indentation(buffer, indentation)?;
+            write!(buffer, "let payload = ")?;
+            write_supers(buffer, supers)?;
+            writeln!(buffer, "zigzag_decode(payload);")
+        }
+        schema::TypeVariant::S32 => {
+            // `S32` has the same encoding as `S64` [ref:narrow_integer_encoding].
             write_deserialization_invocation(
                 buffer,
                 indentation,
                 supers,
                 imports,
                 namespace,
-                &schema::TypeVariant::U64,
+                &schema::TypeVariant::S64,
                 is_field,
+                context,
             )?;
+            write_narrowing_conversion(buffer, indentation, supers, "i32", context)
+        }
+        schema::TypeVariant::String if context.borrowing.is_some() => {
             write_indentation(buffer, indentation)?;
-            write!(buffer, "let payload = ")?;
-            write_supers(buffer, supers)?;
-            writeln!(buffer, "zigzag_decode(payload);")
+            writeln!(
+                buffer,
+                "let payload = {}::str::from_utf8(sub_reader).map_err(|error| {{",
+                context.core(),
+            )?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(
+                buffer,
+                "{io}::Error::new({io}::ErrorKind::InvalidData, error)",
+            )?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "}})?;")
         }
         schema::TypeVariant::String => {
             write_indentation(buffer, indentation)?;
@@ -2344,7 +6187,7 @@ fn write_deserialization_invocation<T: Write>(
             write_indentation(buffer, indentation)?;
             writeln!(
                 buffer,
-                "::std::io::Read::read_to_string(&mut sub_reader, &mut payload)?;",
+                "{read}::read_to_string(&mut sub_reader, &mut payload){wait}?;",
             )
         }
         schema::TypeVariant::U64 => {
@@ -2360,7 +6203,7 @@ fn write_deserialization_invocation<T: Write>(
                 write_indentation(buffer, indentation + 2)?;
                 writeln!(
                     buffer,
-                    "::std::io::Read::read_exact(&mut sub_reader, &mut buffer[..])?;",
+                    "{read}::read_exact(&mut sub_reader, &mut buffer[..]){wait}?;",
                 )?;
                 write_indentation(buffer, indentation + 2)?;
                 writeln!(buffer, "u64::from_le_bytes(buffer)")?;
@@ -2369,15 +6212,45 @@ fn write_deserialization_invocation<T: Write>(
                 write_indentation(buffer, indentation + 1)?;
                 write!(buffer, "_ => ")?;
                 write_supers(buffer, supers)?;
-                writeln!(buffer, "deserialize_varint(&mut sub_reader)?,")?;
+                writeln!(
+                    buffer,
+                    "deserialize_varint{suffix}(&mut sub_reader){wait}?,"
+                )?;
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "}};")
             } else {
                 write!(buffer, "let payload = ")?;
                 write_supers(buffer, supers)?;
-                writeln!(buffer, "deserialize_varint(&mut sub_reader)?;")
+                writeln!(
+                    buffer,
+                    "deserialize_varint{suffix}(&mut sub_reader){wait}?;"
+                )
             }
         }
+        schema::TypeVariant::U32 | schema::TypeVariant::U8 => {
+            // `U32` and `U8` have the same encoding as `U64` [ref:narrow_integer_encoding].
+            write_deserialization_invocation(
+                buffer,
+                indentation,
+                supers,
+                imports,
+                namespace,
+                &schema::TypeVariant::U64,
+                is_field,
+                context,
+            )?;
+            write_narrowing_conversion(
+                buffer,
+                indentation,
+                supers,
+                if matches!(type_variant, schema::TypeVariant::U32) {
+                    "u32"
+                } else {
+                    "u8"
+                },
+                context,
+            )
+        }
         schema::TypeVariant::Unit => {
             write_indentation(buffer, indentation)?;
             writeln!(buffer, "let payload = ();")
@@ -2385,14 +6258,153 @@ fn write_deserialization_invocation<T: Write>(
     }
 }
 
+// Write the logic to deserialize a variable-length array as a view into the input, including a
+// trailing line break [ref:borrowed_types]. The context variables are the same as for
+// `write_deserialization_invocation`.
+#[allow(clippy::too_many_arguments)]
+fn write_array_view_deserialization_invocation<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    supers: usize,
+    imports: &BTreeMap<Identifier, schema::Namespace>,
+    namespace: &schema::Namespace,
+    inner_type: &schema::Type,
+    is_field: bool,
+    context: Context,
+) -> Result<(), fmt::Error> {
+    match encoding_type(&inner_type.variant) {
+        schema::TypeVariant::Array(_, _)
+        | schema::TypeVariant::Bytes(_)
+        | schema::TypeVariant::Custom(_, _, _)
+        | schema::TypeVariant::Map(_, _)
+        | schema::TypeVariant::String => {
+            write_indentation(buffer, indentation)?;
+            write!(buffer, "let payload = ")?;
+            write_supers(buffer, supers)?;
+            writeln!(buffer, "ArrayView::new(sub_reader, |sub_reader| {{")?;
+            write_indentation(buffer, indentation + 1)?;
+            write!(buffer, "let payload_size = ")?;
+            write_supers(buffer, supers)?;
+            writeln!(buffer, "deserialize_varint(&mut *sub_reader)? as usize;")?;
+            write_sub_reader(buffer, indentation + 1, supers, "sub_reader", context)?;
+            write_deserialization_invocation(
+                buffer,
+                indentation + 1,
+                supers,
+                imports,
+                namespace,
+                &inner_type.variant,
+                false,
+                context,
+            )?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "Ok(payload)")?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "}})?;")
+        }
+        schema::TypeVariant::Bool
+        | schema::TypeVariant::F32
+        | schema::TypeVariant::F64
+        | schema::TypeVariant::S32
+        | schema::TypeVariant::S64
+        | schema::TypeVariant::U32
+        | schema::TypeVariant::U64
+        | schema::TypeVariant::U8 => {
+            write_indentation(buffer, indentation)?;
+            write!(buffer, "let payload = ")?;
+            write_supers(buffer, supers)?;
+            writeln!(buffer, "ArrayView::new(sub_reader, |mut sub_reader| {{")?;
+            write_deserialization_invocation(
+                buffer,
+                indentation + 1,
+                supers,
+                imports,
+                namespace,
+                &inner_type.variant,
+                false,
+                context,
+            )?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "Ok(payload)")?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "}})?;")
+        }
+        schema::TypeVariant::Unit => {
+            write_deserialization_invocation(
+                buffer,
+                indentation,
+                supers,
+                imports,
+                namespace,
+                &schema::TypeVariant::U64,
+                is_field,
+                context,
+            )?;
+            write_indentation(buffer, indentation)?;
+            write!(buffer, "let payload = ")?;
+            write_supers(buffer, supers)?;
+            writeln!(buffer, "ArrayView {{")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "bytes: &[],")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "len: payload as usize,")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "deserialize_element: |_| Ok(()),")?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "}};")
+        }
+        schema::TypeVariant::Newtype(_, _, _) => {
+            // Impossible since `encoding_type` strips away newtypes.
+            panic!()
+        }
+    }
+}
+
+// Write the logic to convert a decoded 64-bit integer into a narrower type, including a trailing
+// line break. Values which don't fit are rejected rather than truncated.
+//
+// Context variables:
+// - `payload` (in and out)
+fn write_narrowing_conversion<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    supers: usize,
+    rust_type: &str,
+    context: Context,
+) -> Result<(), fmt::Error> {
+    let io = context.io();
+
+    write_indentation(buffer, indentation)?;
+    writeln!(
+        buffer,
+        "let payload = {rust_type}::try_from(payload).map_err(|_| {io}::Error::new(",
+    )?;
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(buffer, "{io}::ErrorKind::InvalidData,")?;
+    write_indentation(buffer, indentation + 1)?;
+    write_supers(buffer, supers)?;
+    writeln!(buffer, "INTEGER_OUT_OF_RANGE_ERROR_MESSAGE,")?;
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "))?;")
+}
+
 // Determine whether a type is encoded as a varint.
-fn integer_encoded(r#type: &schema::Type) -> bool {
+#[must_use]
+pub fn integer_encoded(r#type: &schema::Type) -> bool {
     match &r#type.variant {
-        schema::TypeVariant::Bool | schema::TypeVariant::S64 | schema::TypeVariant::U64 => true,
-        schema::TypeVariant::Array(_)
-        | schema::TypeVariant::Bytes
-        | schema::TypeVariant::Custom(_, _)
+        schema::TypeVariant::Bool
+        | schema::TypeVariant::S32
+        | schema::TypeVariant::S64
+        | schema::TypeVariant::U32
+        | schema::TypeVariant::U64
+        | schema::TypeVariant::U8 => true,
+        schema::TypeVariant::Newtype(_, _, underlying_type) => integer_encoded(underlying_type),
+        schema::TypeVariant::Array(_, _)
+        | schema::TypeVariant::Bytes(_)
+        | schema::TypeVariant::Custom(_, _, _)
+        | schema::TypeVariant::F32
         | schema::TypeVariant::F64
+        | schema::TypeVariant::Map(_, _)
         | schema::TypeVariant::String
         | schema::TypeVariant::Unit => false,
     }
@@ -2401,7 +6413,11 @@ fn integer_encoded(r#type: &schema::Type) -> bool {
 #[cfg(test)]
 mod tests {
     use {
-        crate::{generate_rust::generate, schema_loader::load_schemas, validator::validate},
+        crate::{
+            generate_rust::{self, generate, generate_with_options, Options},
+            schema_loader::{load_schemas, load_schemas_from, InMemory},
+            validator::validate,
+        },
         std::{fs::read_to_string, path::Path},
     };
 
@@ -2411,8 +6427,424 @@ mod tests {
         validate(&schemas).unwrap();
 
         assert_eq!(
-            generate("0.0.0", &schemas),
+            generate("0.0.0", &schemas).unwrap(),
             read_to_string("test_data/types.rs").unwrap(),
         );
     }
+
+    #[test]
+    fn generate_uuid_conversions() {
+        let mut provider = InMemory::new();
+        provider.insert("main.t", "import 'typical/uuid.t'\n");
+
+        let schemas = load_schemas_from(&provider, Path::new("main.t")).unwrap();
+        validate(&schemas).unwrap();
+
+        assert!(!generate("0.0.0", &schemas)
+            .unwrap()
+            .contains("::uuid::Uuid"));
+        assert!(generate_with_options(
+            "0.0.0",
+            &schemas,
+            &Options {
+                uuid: true,
+                ..Options::default()
+            },
+        )
+        .unwrap()
+        .contains("impl From<UuidIn> for ::uuid::Uuid {"));
+    }
+
+    #[test]
+    fn generate_borrowed_types() {
+        let mut provider = InMemory::new();
+        provider.insert(
+            "main.t",
+            "struct foo {\n  x: String = 0\n  y: U64 = 1\n}\n\nstruct bar {\n  z: U64 = 0\n}\n",
+        );
+
+        let schemas = load_schemas_from(&provider, Path::new("main.t")).unwrap();
+        validate(&schemas).unwrap();
+
+        let owned = generate("0.0.0", &schemas).unwrap();
+        assert!(owned.contains("pub struct FooIn {"));
+        assert!(!owned.contains("DeserializeBorrowed"));
+
+        let borrowed = generate_with_options(
+            "0.0.0",
+            &schemas,
+            &Options {
+                borrowed: true,
+                ..Options::default()
+            },
+        )
+        .unwrap();
+        assert!(borrowed.contains("pub struct FooIn<'a> {"));
+        assert!(borrowed.contains("pub x: &'a str,"));
+        assert!(borrowed.contains("pub struct BarIn {"));
+        assert!(borrowed.contains("impl<'a> super::DeserializeBorrowed<'a> for FooIn<'a> {"));
+        assert!(!borrowed.contains("impl super::Deserialize for FooIn"));
+        assert!(borrowed.contains("impl super::Deserialize for BarIn {"));
+    }
+
+    #[test]
+    fn generate_no_std() {
+        let mut provider = InMemory::new();
+        provider.insert(
+            "main.t",
+            "struct foo {\n  x: String = 0\n  y: {String: U64} = 1\n}\n",
+        );
+
+        let schemas = load_schemas_from(&provider, Path::new("main.t")).unwrap();
+        validate(&schemas).unwrap();
+
+        let std = generate("0.0.0", &schemas).unwrap();
+        assert!(!std.contains("pub mod io {"));
+        assert!(std.contains("::std::io::Result<Self>"));
+
+        let no_std = generate_with_options(
+            "0.0.0",
+            &schemas,
+            &Options {
+                no_std: true,
+                ..Options::default()
+            },
+        )
+        .unwrap();
+        assert!(no_std.contains("pub mod io {"));
+        assert!(no_std.contains("use ::alloc::{boxed::Box, string::String, vec::Vec};"));
+        assert!(no_std.contains("super::io::Result<Self>"));
+        assert!(no_std.contains("::alloc::collections::BTreeMap<String, u64>"));
+        assert!(!no_std.contains("std::io::BufRead>"));
+        assert!(!no_std.contains("::std::collections"));
+    }
+
+    #[test]
+    fn generate_async_io() {
+        let mut provider = InMemory::new();
+        provider.insert(
+            "main.t",
+            "struct foo {\n  x: String = 0\n}\n\nchoice bar {\n  y: foo = 0\n  \
+                optional z: U64 = 1\n}\n",
+        );
+
+        let schemas = load_schemas_from(&provider, Path::new("main.t")).unwrap();
+        validate(&schemas).unwrap();
+
+        let sync = generate("0.0.0", &schemas).unwrap();
+        assert!(!sync.contains("tokio"));
+
+        let code = generate_with_options(
+            "0.0.0",
+            &schemas,
+            &Options {
+                async_io: true,
+                ..Options::default()
+            },
+        )
+        .unwrap();
+        assert!(code.starts_with(&sync[..sync.find("pub trait Serialize").unwrap()]));
+        assert!(code.contains("pub trait SerializeAsync: Serialize {"));
+        assert!(code.contains("async fn deserialize_field_header_async<"));
+        assert!(code.contains("impl super::SerializeAsync for FooOut {"));
+        assert!(code.contains("impl super::DeserializeAsync for BarIn {"));
+        assert!(code.contains(
+            "pub async fn serialize_with_atlas_async<T: ::tokio::io::AsyncWrite + Unpin>(",
+        ));
+        assert!(code.contains(
+            "pub async fn deserialize_from_reader_ref_async<T: ::tokio::io::AsyncBufRead + Unpin>(",
+        ));
+        assert!(code.contains("FooIn::deserialize_from_reader_ref_async(&mut sub_reader).await?;"));
+        assert!(code
+            .contains("Box::pin(BarIn::deserialize_from_reader_ref_async(&mut *reader)).await?",));
+    }
+
+    #[test]
+    fn validate_async_io() {
+        let mut provider = InMemory::new();
+        provider.insert("main.t", "struct foo {\n  x: String = 0\n}\n");
+
+        let schemas = load_schemas_from(&provider, Path::new("main.t")).unwrap();
+        validate(&schemas).unwrap();
+
+        let errors = generate_rust::validate(
+            &schemas,
+            &Options {
+                async_io: true,
+                borrowed: true,
+                no_std: true,
+                ..Options::default()
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            errors
+                .into_iter()
+                .map(|error| error.description)
+                .collect::<Vec<_>>(),
+            vec![
+                "Asynchronous code can't be generated for borrowed types.",
+                "Asynchronous code can't be generated for `no_std` code.",
+            ],
+        );
+    }
+
+    #[test]
+    fn generate_builders() {
+        let mut provider = InMemory::new();
+        provider.insert(
+            "main.t",
+            "struct foo {\n  x: String = 0\n  asymmetric y: U64 = 1\n  optional z: Bool = 2\n}\n",
+        );
+
+        let schemas = load_schemas_from(&provider, Path::new("main.t")).unwrap();
+        validate(&schemas).unwrap();
+
+        let plain = generate("0.0.0", &schemas).unwrap();
+        assert!(!plain.contains("Unset"));
+
+        let code = generate_with_options(
+            "0.0.0",
+            &schemas,
+            &Options {
+                builders: true,
+                ..Options::default()
+            },
+        )
+        .unwrap();
+        assert!(code.contains("pub struct Unset;"));
+        assert!(code.contains(
+            "pub struct FooBuilder<T0, T1> {\n        x: T0,\n        y: T1,\n        \
+                z: Option<bool>,\n    }",
+        ));
+        assert!(code.contains("pub fn builder() -> FooBuilder<super::Unset, super::Unset> {"));
+        assert!(code.contains("impl<T1> FooBuilder<super::Unset, T1> {"));
+        assert!(code.contains("pub fn x(self, x: String) -> FooBuilder<String, T1> {"));
+        assert!(code.contains("impl<T0> FooBuilder<T0, super::Unset> {"));
+        assert!(code.contains("pub fn y(self, y: u64) -> FooBuilder<T0, u64> {"));
+        assert!(code.contains("impl<T0, T1> FooBuilder<T0, T1> {"));
+        assert!(code.contains("pub fn z(mut self, z: bool) -> Self {"));
+        assert!(
+            code.contains("impl FooBuilder<String, u64> {\n        pub fn build(self) -> FooOut {")
+        );
+    }
+
+    #[test]
+    fn validate_builders() {
+        let mut provider = InMemory::new();
+        provider.insert(
+            "main.t",
+            "struct foo {\n  build: String = 0\n}\n\nstruct bar {\n  @rust.rename(build)\n  \
+                x: String = 0\n}\n\nchoice baz {\n  build: String = 0\n}\n",
+        );
+
+        let schemas = load_schemas_from(&provider, Path::new("main.t")).unwrap();
+        validate(&schemas).unwrap();
+
+        generate_rust::validate(&schemas, &Options::default()).unwrap();
+
+        let errors = generate_rust::validate(
+            &schemas,
+            &Options {
+                builders: true,
+                ..Options::default()
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            errors
+                .into_iter()
+                .map(|error| error.description)
+                .collect::<Vec<_>>(),
+            vec![
+                "The builder for `foo` can't have a method for field `build`, since that's the \
+                    name of the method which builds the message.",
+                "The builder for `bar` can't have a method for field `build`, since that's the \
+                    name of the method which builds the message.",
+            ],
+        );
+    }
+
+    // Check the requested derives for a schema and return the descriptions of the errors.
+    fn derive_errors(contents: &str, options: &Options) -> Vec<String> {
+        let mut provider = InMemory::new();
+        provider.insert("main.t", contents);
+
+        let schemas = load_schemas_from(&provider, Path::new("main.t")).unwrap();
+        validate(&schemas).unwrap();
+
+        generate_rust::validate(&schemas, options).map_or_else(
+            |errors| errors.into_iter().map(|error| error.description).collect(),
+            |()| vec![],
+        )
+    }
+
+    #[test]
+    fn generate_derives() {
+        let mut provider = InMemory::new();
+        provider.insert(
+            "main.t",
+            "@rust.derive(Hash, Eq)\nstruct foo {\n  x: [U64] = 0\n}\n\n\
+                @rust.derive(PartialOrd)\nchoice bar {\n  y: F64 = 0\n}\n",
+        );
+
+        let schemas = load_schemas_from(&provider, Path::new("main.t")).unwrap();
+        validate(&schemas).unwrap();
+
+        let options = Options {
+            derives: vec!["PartialEq".to_owned(), "Serialize".to_owned()],
+            ..Options::default()
+        };
+        generate_rust::validate(&schemas, &options).unwrap();
+
+        let code = generate_with_options("0.0.0", &schemas, &options).unwrap();
+        assert!(code.contains(
+            "#[derive(Clone, Debug, PartialEq, ::serde::Serialize, Hash, Eq)]\n    pub struct FooOut",
+        ));
+        assert!(code.contains(
+            "#[derive(Clone, Debug, PartialEq, ::serde::Serialize, PartialOrd)]\n    pub enum BarIn",
+        ));
+        assert!(code.contains("#[derive(Clone, Debug)]\n    pub struct FooAtlas"));
+    }
+
+    #[test]
+    fn generate_underivable() {
+        let mut provider = InMemory::new();
+        provider.insert(
+            "main.t",
+            "@rust.derive(PartialEq, Eq)\nstruct foo {\n  x: F64 = 0\n}\n",
+        );
+
+        let schemas = load_schemas_from(&provider, Path::new("main.t")).unwrap();
+        validate(&schemas).unwrap();
+
+        assert_eq!(
+            generate("0.0.0", &schemas)
+                .unwrap_err()
+                .into_iter()
+                .map(|error| error.description)
+                .collect::<Vec<_>>(),
+            vec!["`foo` can't derive `Eq`, since field `x` has a floating-point type."],
+        );
+    }
+
+    #[test]
+    fn validate_derives_floats() {
+        assert_eq!(
+            derive_errors(
+                "@rust.derive(PartialEq, Eq)\nstruct foo {\n  x: {String: [F32]} = 0\n}\n",
+                &Options::default(),
+            ),
+            vec!["`foo` can't derive `Eq`, since field `x` has a floating-point type."],
+        );
+    }
+
+    #[test]
+    fn validate_derives_floats_global() {
+        assert_eq!(
+            derive_errors(
+                "struct foo {\n  x: F64 = 0\n}\n",
+                &Options {
+                    derives: vec!["Hash".to_owned()],
+                    ..Options::default()
+                },
+            ),
+            vec!["`foo` can't derive `Hash`, since field `x` has a floating-point type."],
+        );
+    }
+
+    #[test]
+    fn validate_derives_unknown() {
+        let expected = "Unknown trait `Default`. The traits which can be derived are `Clone`, \
+            `Debug`, `PartialEq`, `Eq`, `PartialOrd`, `Ord`, `Hash`, `Serialize`, `Deserialize`.";
+
+        assert_eq!(
+            derive_errors(
+                "@rust.derive(Default)\nstruct foo {}\n",
+                &Options::default()
+            ),
+            vec![expected],
+        );
+        assert_eq!(
+            derive_errors(
+                "struct foo {}\n",
+                &Options {
+                    derives: vec!["Default".to_owned()],
+                    ..Options::default()
+                },
+            ),
+            vec![expected],
+        );
+    }
+
+    #[test]
+    fn validate_derives_supertraits() {
+        assert_eq!(
+            derive_errors("@rust.derive(Ord)\nstruct foo {}\n", &Options::default()),
+            vec![
+                "`foo` derives `Ord`, so it must also derive `Eq`.",
+                "`foo` derives `Ord`, so it must also derive `PartialOrd`.",
+            ],
+        );
+        assert_eq!(
+            derive_errors(
+                "struct foo {}\n",
+                &Options {
+                    derives: vec!["Eq".to_owned()],
+                    ..Options::default()
+                },
+            ),
+            vec!["Deriving `Eq` for all types requires deriving `PartialEq` for all types too."],
+        );
+        assert!(derive_errors(
+            "@rust.derive(Eq)\nstruct foo {}\n",
+            &Options {
+                derives: vec!["PartialEq".to_owned()],
+                ..Options::default()
+            },
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn validate_derives_references() {
+        assert_eq!(
+            derive_errors(
+                "@rust.derive(Hash)\nstruct foo {\n  x: bar = 0\n}\n\n\
+                    newtype bar = U64\n\n\
+                    @rust.derive(Hash)\nnewtype baz = [foo]\n",
+                &Options::default(),
+            ),
+            vec![
+                "`foo` can't derive `Hash`, since field `x` refers to `bar`, which doesn't derive \
+                it."
+            ],
+        );
+    }
+
+    #[test]
+    fn validate_derives_serde() {
+        assert_eq!(
+            derive_errors(
+                "@rust.derive(Serialize)\nstruct foo {\n  x: Bytes(32) = 0\n  y: [[U8; 33]] = 1\n}\n",
+                &Options::default(),
+            ),
+            vec!["`foo` can't derive `Serialize`, since field `y` has a fixed length greater than \
+                32."],
+        );
+        assert_eq!(
+            derive_errors(
+                "@rust.derive(Deserialize)\nstruct foo {\n  x: String = 0\n}\n\n\
+                    @rust.derive(Deserialize)\nstruct bar {\n  y: U64 = 0\n}\n",
+                &Options {
+                    borrowed: true,
+                    ..Options::default()
+                },
+            ),
+            vec![
+                "`foo` can't derive `Deserialize`, since its `In` type borrows from the \
+                serialized message."
+            ],
+        );
+    }
 }
@@ -1,5 +1,12 @@
 use {
-    crate::{identifier::Identifier, schema},
+    crate::{
+        error::{listing, throw, Error},
+        format::CodeStr,
+        identifier::Identifier,
+        monomorphizer::monomorphize,
+        schema::{self, apply_renames, deprecation, encoding_type},
+        well_known,
+    },
     std::{
         collections::BTreeMap,
         fmt::{self, Write},
@@ -10,6 +17,47 @@ use {
 // The string to be used for each indentation level.
 const INDENTATION: &str = "  ";
 
+// Conversions between the well-known `Timestamp` type and `Date` [ref:well_known_schemas]. Times
+// before the epoch have a negative number of seconds, but the number of nanoseconds always counts
+// forward. `Date` has millisecond precision, so conversions which would lose information fail.
+const TIMESTAMP_CONVERSIONS: &str = "\
+export function fromDate(date: Date): TimestampOut | Error {
+  const milliseconds = date.getTime();
+
+  if (Number.isNaN(milliseconds)) {
+    return new Error('Invalid date.');
+  }
+
+  const seconds = Math.floor(milliseconds / 1000);
+
+  return {
+    seconds: BigInt(seconds),
+    nanos: (milliseconds - seconds * 1000) * 1_000_000,
+  };
+}
+
+export function toDate(timestamp: TimestampIn): Date | Error {
+  if (
+    !(timestamp.nanos >= 0 && timestamp.nanos < 1_000_000_000) ||
+    timestamp.nanos % 1_000_000 !== 0
+  ) {
+    return new Error('Timestamp not representable as a date.');
+  }
+
+  const milliseconds =
+    timestamp.seconds * 1000n + BigInt(timestamp.nanos / 1_000_000);
+
+  if (
+    milliseconds < -8_640_000_000_000_000n ||
+    milliseconds > 8_640_000_000_000_000n
+  ) {
+    return new Error('Timestamp not representable as a date.');
+  }
+
+  return new Date(Number(milliseconds));
+}
+";
+
 // This is the full list of TypeScript keywords, derived from:
 //   https://github.com/microsoft/TypeScript/blob/2161e1852f4f627bbc7571c6b7284f419ec524c9
 //   /src/compiler/types.ts#L113-L194
@@ -107,9 +155,10 @@ struct Module {
 enum CaseConvention {
     Camel,
     Pascal,
+    ScreamingSnake,
 }
 
-use CaseConvention::{Camel, Pascal};
+use CaseConvention::{Camel, Pascal, ScreamingSnake};
 
 // This enum is used to distinguish between the ingress and egress versions of a type.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -121,19 +170,107 @@ enum Direction {
 
 use Direction::{Atlas, In, Out};
 
+// Options for customizing the generated code
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct Options {
+    // Generate typed builders for the `Out` types of structs [ref:typescript_builders].
+    pub builders: bool,
+}
+
+// Check that code can be generated for the schemas with the given options. The schemas are assumed
+// to be valid.
+pub fn validate(
+    schemas: &BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>,
+    options: &Options,
+) -> Result<(), Vec<Error>> {
+    let mut errors = vec![];
+
+    // A builder has a method for each field, as well as one named `build`
+    // [ref:typescript_builders], so a field can't have that name. The `unwrap` is safe since the
+    // schemas are assumed to be valid.
+    if options.builders {
+        for (schema, source_path, source_contents) in monomorphize(schemas).unwrap().values() {
+            let mut schema = schema.clone();
+            apply_renames(&mut schema, "typescript");
+
+            for declaration in &schema.declarations {
+                if !matches!(declaration.variant, schema::DeclarationVariant::Struct) {
+                    continue;
+                }
+
+                for field in &declaration.fields {
+                    if field.name.camel_case() == "build" {
+                        errors.push(throw::<Error>(
+                            &format!(
+                                "The builder for {} can't have a method for field {}, since \
+                                    that's the name of the method which builds the message.",
+                                declaration.name.code_str(),
+                                field.name.code_str(),
+                            ),
+                            Some(source_path),
+                            Some(&listing(source_contents, field.source_range)),
+                            None,
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
 // Generate TypeScript code from a schema and its transitive dependencies.
-#[allow(clippy::too_many_lines)]
 pub fn generate(
     typical_version: &str,
     schemas: &BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>,
-) -> String {
+) -> Result<String, Vec<Error>> {
+    generate_with_options(typical_version, schemas, &Options::default())
+}
+
+// Generate TypeScript code from a schema and its transitive dependencies, customized by the given
+// options. The schemas are assumed to be valid, and the options are checked with `validate` first.
+#[allow(clippy::too_many_lines)]
+pub fn generate_with_options(
+    typical_version: &str,
+    schemas: &BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>,
+    options: &Options,
+) -> Result<String, Vec<Error>> {
+    validate(schemas, options)?;
+
+    // Replace the generic declarations with their instantiations [ref:monomorphization]. The
+    // `unwrap` is safe since the schemas are assumed to be valid.
+    let mut schemas = monomorphize(schemas).unwrap();
+
+    // Apply the `@typescript.rename` attributes, and document deprecations with the `@deprecated`
+    // JSDoc tag.
+    for (schema, _, _) in schemas.values_mut() {
+        apply_renames(schema, "typescript");
+
+        for declaration in &mut schema.declarations {
+            document_deprecation(&mut declaration.comment, &declaration.attributes);
+
+            for field in &mut declaration.fields {
+                document_deprecation(&mut field.comment, &field.attributes);
+            }
+        }
+    }
+    let schemas = &schemas;
+
     // Construct a tree of modules and schemas. We start with an empty tree.
     let mut tree = Module {
         children: BTreeMap::new(),
         schema: schema::Schema {
             comment: vec![],
             imports: BTreeMap::new(),
+            constants: vec![],
             declarations: vec![],
+            services: vec![],
         },
     };
 
@@ -145,7 +282,11 @@ pub fn generate(
     // Write the code.
     let mut buffer = String::new();
 
-    if !tree.children.is_empty() || !tree.schema.declarations.is_empty() {
+    if !tree.children.is_empty()
+        || !tree.schema.constants.is_empty()
+        || !tree.schema.declarations.is_empty()
+        || !tree.schema.services.is_empty()
+    {
         // The `unwrap` is safe because the `std::fmt::Write` impl for `String` is infallible.
         writeln!(
             &mut buffer,
@@ -445,6 +586,8 @@ function deserializeFieldHeader(
 }}
 
 const missingFieldsErrorMessage = 'Struct missing one or more required field(s).';
+const integerOutOfRangeErrorMessage = 'Integer out of range.';
+const wrongLengthErrorMessage = 'Array or bytes of the wrong length.';
 const dataView64 = new DataView(new ArrayBuffer(8));
 const textEncoder = new TextEncoder();
 const textDecoder = new TextDecoder();",
@@ -458,6 +601,7 @@ const textDecoder = new TextDecoder();",
         write_module_contents(
             &mut buffer,
             0,
+            options,
             &schema::Namespace { components: vec![] },
             &tree.children,
             &tree.schema,
@@ -465,7 +609,7 @@ const textDecoder = new TextDecoder();",
         .unwrap();
     }
 
-    buffer
+    Ok(buffer)
 }
 
 // Insert a schema into a module.
@@ -487,7 +631,9 @@ fn insert_schema(module: &mut Module, namespace: &schema::Namespace, schema: &sc
                 schema: schema::Schema {
                     comment: vec![],
                     imports: BTreeMap::new(),
+                    constants: vec![],
                     declarations: vec![],
+                    services: vec![],
                 },
             };
 
@@ -510,10 +656,12 @@ fn insert_schema(module: &mut Module, namespace: &schema::Namespace, schema: &sc
 fn write_module<T: Write>(
     buffer: &mut T,
     indentation: usize,
+    options: &Options,
     namespace: &schema::Namespace,
     name: &Identifier,
     module: &Module,
 ) -> Result<(), fmt::Error> {
+    write_doc_comment(buffer, indentation, &module.schema.comment)?;
     write_indentation(buffer, indentation)?;
     write!(buffer, "export namespace ")?;
     write_identifier(buffer, name, Pascal, None)?;
@@ -525,6 +673,7 @@ fn write_module<T: Write>(
     write_module_contents(
         buffer,
         indentation + 1,
+        options,
         &new_namespace,
         &module.children,
         &module.schema,
@@ -540,21 +689,23 @@ fn write_module<T: Write>(
 fn write_module_contents<T: Write>(
     buffer: &mut T,
     indentation: usize,
+    options: &Options,
     namespace: &schema::Namespace,
     children: &BTreeMap<Identifier, Module>,
     schema: &schema::Schema,
 ) -> Result<(), fmt::Error> {
-    let schema_empty = schema.declarations.is_empty();
+    let schema_empty =
+        schema.constants.is_empty() && schema.declarations.is_empty() && schema.services.is_empty();
 
     for (i, (child_name, child)) in children.iter().enumerate() {
-        write_module(buffer, indentation, namespace, child_name, child)?;
+        write_module(buffer, indentation, options, namespace, child_name, child)?;
 
         if i < children.len() - 1 || !schema_empty {
             writeln!(buffer)?;
         }
     }
 
-    write_schema(buffer, indentation, namespace, schema)?;
+    write_schema(buffer, indentation, options, namespace, schema)?;
 
     Ok(())
 }
@@ -564,6 +715,7 @@ fn write_module_contents<T: Write>(
 fn write_schema<T: Write>(
     buffer: &mut T,
     indentation: usize,
+    options: &Options,
     namespace: &schema::Namespace,
     schema: &schema::Schema,
 ) -> Result<(), fmt::Error> {
@@ -574,44 +726,29 @@ fn write_schema<T: Write>(
         imports.insert(name.clone(), import.namespace.clone().unwrap());
     }
 
+    // Write the constants.
+    for constant in &schema.constants {
+        write_constant(buffer, indentation, constant)?;
+    }
+
+    if !schema.constants.is_empty() && !schema.declarations.is_empty() {
+        writeln!(buffer)?;
+    }
+
     // Write the declarations.
     let mut iter = schema.declarations.iter().peekable();
     while let Some(declaration) = iter.next() {
         match &declaration.variant {
             schema::DeclarationVariant::Struct => {
-                write_struct(
-                    buffer,
-                    indentation,
-                    &imports,
-                    namespace,
-                    &declaration.name,
-                    &declaration.fields,
-                    Atlas,
-                )?;
+                write_struct(buffer, indentation, &imports, namespace, declaration, Atlas)?;
 
                 writeln!(buffer)?;
 
-                write_struct(
-                    buffer,
-                    indentation,
-                    &imports,
-                    namespace,
-                    &declaration.name,
-                    &declaration.fields,
-                    Out,
-                )?;
+                write_struct(buffer, indentation, &imports, namespace, declaration, Out)?;
 
                 writeln!(buffer)?;
 
-                write_struct(
-                    buffer,
-                    indentation,
-                    &imports,
-                    namespace,
-                    &declaration.name,
-                    &declaration.fields,
-                    In,
-                )?;
+                write_struct(buffer, indentation, &imports, namespace, declaration, In)?;
 
                 writeln!(buffer)?;
 
@@ -901,6 +1038,7 @@ fn write_schema<T: Write>(
                         &field.r#type.variant,
                         true,
                     )?;
+                    write_constraint_checks(buffer, indentation + 5, &declaration.name, field)?;
                     write_indentation(buffer, indentation + 5)?;
                     writeln!(buffer, "offset += oldOffset;")?;
                     write_indentation(buffer, indentation + 5)?;
@@ -968,43 +1106,43 @@ fn write_schema<T: Write>(
                 write_indentation(buffer, indentation + 1)?;
                 writeln!(buffer, "}}")?;
 
-                write_indentation(buffer, indentation)?;
-                writeln!(buffer, "}}")?;
-            }
-            schema::DeclarationVariant::Choice => {
-                write_choice(
+                writeln!(buffer)?;
+
+                write_validate_functions(
                     buffer,
-                    indentation,
+                    indentation + 1,
                     &imports,
                     namespace,
-                    &declaration.name,
-                    &declaration.fields,
-                    Atlas,
+                    declaration,
                 )?;
 
+                if options.builders {
+                    writeln!(buffer)?;
+
+                    write_builder(buffer, indentation + 1, &imports, namespace, declaration)?;
+                }
+
+                // Write the conversions to and from native types for the well-known schemas.
+                if well_known::is(namespace, well_known::TIME_PATH)
+                    && declaration.name.original() == "Timestamp"
+                {
+                    writeln!(buffer)?;
+                    write_lines(buffer, indentation + 1, TIMESTAMP_CONVERSIONS)?;
+                }
+
+                write_indentation(buffer, indentation)?;
+                writeln!(buffer, "}}")?;
+            }
+            schema::DeclarationVariant::Choice => {
+                write_choice(buffer, indentation, &imports, namespace, declaration, Atlas)?;
+
                 writeln!(buffer)?;
 
-                write_choice(
-                    buffer,
-                    indentation,
-                    &imports,
-                    namespace,
-                    &declaration.name,
-                    &declaration.fields,
-                    Out,
-                )?;
+                write_choice(buffer, indentation, &imports, namespace, declaration, Out)?;
 
                 writeln!(buffer)?;
 
-                write_choice(
-                    buffer,
-                    indentation,
-                    &imports,
-                    namespace,
-                    &declaration.name,
-                    &declaration.fields,
-                    In,
-                )?;
+                write_choice(buffer, indentation, &imports, namespace, declaration, In)?;
 
                 writeln!(buffer)?;
 
@@ -1258,6 +1396,7 @@ fn write_schema<T: Write>(
                         &field.r#type.variant,
                         true,
                     )?;
+                    write_constraint_checks(buffer, indentation + 5, &declaration.name, field)?;
                     match field.rule {
                         schema::Rule::Asymmetric | schema::Rule::Required => {}
                         schema::Rule::Optional => {
@@ -1313,10 +1452,53 @@ fn write_schema<T: Write>(
                 write_indentation(buffer, indentation + 1)?;
                 writeln!(buffer, "}}")?;
 
+                writeln!(buffer)?;
+
+                write_validate_functions(
+                    buffer,
+                    indentation + 1,
+                    &imports,
+                    namespace,
+                    declaration,
+                )?;
+
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "}}")?;
             }
+            schema::DeclarationVariant::Newtype(underlying_type) => {
+                write_newtype(
+                    buffer,
+                    indentation,
+                    &imports,
+                    namespace,
+                    declaration,
+                    underlying_type,
+                    Out,
+                )?;
+
+                writeln!(buffer)?;
+
+                write_newtype(
+                    buffer,
+                    indentation,
+                    &imports,
+                    namespace,
+                    declaration,
+                    underlying_type,
+                    In,
+                )?;
+            }
+        }
+
+        if iter.peek().is_some() || !schema.services.is_empty() {
+            writeln!(buffer)?;
         }
+    }
+
+    // Write the services.
+    let mut iter = schema.services.iter().peekable();
+    while let Some(service) = iter.next() {
+        write_service(buffer, indentation, &imports, namespace, service)?;
 
         if iter.peek().is_some() {
             writeln!(buffer)?;
@@ -1326,19 +1508,256 @@ fn write_schema<T: Write>(
     Ok(())
 }
 
+// Write a constant, including a trailing line break.
+fn write_constant<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    constant: &schema::Constant,
+) -> Result<(), fmt::Error> {
+    write_doc_comment(buffer, indentation, &constant.comment)?;
+    write_indentation(buffer, indentation)?;
+    write!(buffer, "export const ")?;
+    write_identifier(buffer, &constant.name, ScreamingSnake, None)?;
+    write!(buffer, " = ")?;
+
+    match &constant.value {
+        schema::Literal::Bool(value) => write!(buffer, "{value}")?,
+        schema::Literal::Bytes(bytes) => write!(
+            buffer,
+            "new Uint8Array([{}]).buffer",
+            bytes
+                .iter()
+                .map(|byte| format!("0x{byte:02x}"))
+                .collect::<Vec<_>>()
+                .join(", "),
+        )?,
+        schema::Literal::Float(float) => write!(buffer, "{float:?}")?,
+        schema::Literal::Integer(integer) => {
+            // 64-bit integers are represented as `bigint`s.
+            match constant.r#type.variant {
+                schema::TypeVariant::S64 | schema::TypeVariant::U64 => {
+                    write!(buffer, "{integer}n")?;
+                }
+                _ => write!(buffer, "{integer}")?,
+            }
+        }
+        schema::Literal::String(string) => {
+            write!(buffer, "'")?;
+
+            for c in string.chars() {
+                match c {
+                    '\'' => write!(buffer, "\\'")?,
+                    '\\' => write!(buffer, "\\\\")?,
+                    '\n' => write!(buffer, "\\n")?,
+                    '\r' => write!(buffer, "\\r")?,
+                    '\t' => write!(buffer, "\\t")?,
+                    _ if c.is_control() || c == '\u{2028}' || c == '\u{2029}' => {
+                        write!(buffer, "\\u{{{:x}}}", u32::from(c))?;
+                    }
+                    _ => write!(buffer, "{c}")?,
+                }
+            }
+
+            write!(buffer, "'")?;
+        }
+    }
+
+    writeln!(buffer, ";")
+}
+
+// Write a service, including a trailing line break. A service consists of an interface for
+// clients, an interface for handlers, and a namespace with a function which constructs a client
+// from a user-provided transport and a function which routes a request to the appropriate method of
+// a handler.
+#[allow(clippy::too_many_lines)]
+fn write_service<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    imports: &BTreeMap<Identifier, schema::Namespace>,
+    namespace: &schema::Namespace,
+    service: &schema::Service,
+) -> Result<(), fmt::Error> {
+    let client_name = service.name.join(&"Client".into());
+    let handler_name = service.name.join(&"Handler".into());
+
+    // Write the client interface.
+    write_doc_comment(buffer, indentation, &service.comment)?;
+    write_indentation(buffer, indentation)?;
+    write!(buffer, "export interface ")?;
+    write_identifier(buffer, &client_name, Pascal, None)?;
+    writeln!(buffer, " {{")?;
+    for method in &service.methods {
+        write_doc_comment(buffer, indentation + 1, &method.comment)?;
+        write_indentation(buffer, indentation + 1)?;
+        write_identifier(buffer, &method.name, Camel, None)?;
+        write!(buffer, "(request: ")?;
+        write_type(buffer, imports, namespace, &method.input_type.variant, Out)?;
+        write!(buffer, "): Promise<")?;
+        write_type(buffer, imports, namespace, &method.output_type.variant, In)?;
+        writeln!(buffer, " | Error>;")?;
+    }
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "}}")?;
+
+    writeln!(buffer)?;
+
+    // Write the handler interface.
+    write_doc_comment(buffer, indentation, &service.comment)?;
+    write_indentation(buffer, indentation)?;
+    write!(buffer, "export interface ")?;
+    write_identifier(buffer, &handler_name, Pascal, None)?;
+    writeln!(buffer, " {{")?;
+    for method in &service.methods {
+        write_doc_comment(buffer, indentation + 1, &method.comment)?;
+        write_indentation(buffer, indentation + 1)?;
+        write_identifier(buffer, &method.name, Camel, None)?;
+        write!(buffer, "(request: ")?;
+        write_type(buffer, imports, namespace, &method.input_type.variant, In)?;
+        write!(buffer, "): ")?;
+        write_type(buffer, imports, namespace, &method.output_type.variant, Out)?;
+        write!(buffer, " | Promise<")?;
+        write_type(buffer, imports, namespace, &method.output_type.variant, Out)?;
+        writeln!(buffer, ">;")?;
+    }
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "}}")?;
+
+    writeln!(buffer)?;
+
+    // Write the namespace containing the client constructor and the dispatch function.
+    write_indentation(buffer, indentation)?;
+    write!(buffer, "export namespace ")?;
+    write_identifier(buffer, &service.name, Pascal, None)?;
+    writeln!(buffer, " {{")?;
+
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(buffer, "export function client(")?;
+    write_indentation(buffer, indentation + 2)?;
+    writeln!(
+        buffer,
+        "transport: (method: number, request: ArrayBuffer) => Promise<ArrayBuffer>,",
+    )?;
+    write_indentation(buffer, indentation + 1)?;
+    write!(buffer, "): ")?;
+    write_custom_type(buffer, imports, namespace, None, &client_name, None)?;
+    writeln!(buffer, " {{")?;
+    write_indentation(buffer, indentation + 2)?;
+    writeln!(buffer, "return {{")?;
+    for method in &service.methods {
+        write_indentation(buffer, indentation + 3)?;
+        write!(buffer, "async ")?;
+        write_identifier(buffer, &method.name, Camel, None)?;
+        write!(buffer, "(request: ")?;
+        write_type(buffer, imports, namespace, &method.input_type.variant, Out)?;
+        write!(buffer, "): Promise<")?;
+        write_type(buffer, imports, namespace, &method.output_type.variant, In)?;
+        writeln!(buffer, " | Error> {{")?;
+        write_indentation(buffer, indentation + 4)?;
+        writeln!(buffer, "const response = await transport(")?;
+        write_indentation(buffer, indentation + 5)?;
+        writeln!(buffer, "{},", method.index)?;
+        write_indentation(buffer, indentation + 5)?;
+        write_message_namespace(buffer, imports, namespace, &method.input_type.variant)?;
+        writeln!(buffer, ".serialize(request),")?;
+        write_indentation(buffer, indentation + 4)?;
+        writeln!(buffer, ");")?;
+        write_indentation(buffer, indentation + 4)?;
+        write!(buffer, "return ")?;
+        write_message_namespace(buffer, imports, namespace, &method.output_type.variant)?;
+        writeln!(buffer, ".deserialize(response);")?;
+        write_indentation(buffer, indentation + 3)?;
+        writeln!(buffer, "}},")?;
+    }
+    write_indentation(buffer, indentation + 2)?;
+    writeln!(buffer, "}};")?;
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(buffer, "}}")?;
+
+    writeln!(buffer)?;
+
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(buffer, "export async function dispatch(")?;
+    write_indentation(buffer, indentation + 2)?;
+    write!(buffer, "handler: ")?;
+    write_custom_type(buffer, imports, namespace, None, &handler_name, None)?;
+    writeln!(buffer, ",")?;
+    write_indentation(buffer, indentation + 2)?;
+    writeln!(buffer, "method: number,")?;
+    write_indentation(buffer, indentation + 2)?;
+    writeln!(buffer, "request: Deserializable,")?;
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(buffer, "): Promise<ArrayBuffer | Error> {{")?;
+    write_indentation(buffer, indentation + 2)?;
+    writeln!(buffer, "switch (method) {{")?;
+    for method in &service.methods {
+        write_indentation(buffer, indentation + 3)?;
+        writeln!(buffer, "case {}: {{", method.index)?;
+        write_indentation(buffer, indentation + 4)?;
+        write!(buffer, "const message = ")?;
+        write_message_namespace(buffer, imports, namespace, &method.input_type.variant)?;
+        writeln!(buffer, ".deserialize(request);")?;
+        write_indentation(buffer, indentation + 4)?;
+        writeln!(buffer, "if (message instanceof Error) {{")?;
+        write_indentation(buffer, indentation + 5)?;
+        writeln!(buffer, "return message;")?;
+        write_indentation(buffer, indentation + 4)?;
+        writeln!(buffer, "}}")?;
+        write_indentation(buffer, indentation + 4)?;
+        write!(buffer, "return ")?;
+        write_message_namespace(buffer, imports, namespace, &method.output_type.variant)?;
+        write!(buffer, ".serialize(await handler.")?;
+        write_identifier(buffer, &method.name, Camel, None)?;
+        writeln!(buffer, "(message));")?;
+        write_indentation(buffer, indentation + 3)?;
+        writeln!(buffer, "}}")?;
+    }
+    write_indentation(buffer, indentation + 3)?;
+    writeln!(buffer, "default:")?;
+    write_indentation(buffer, indentation + 4)?;
+    writeln!(buffer, "return new Error('Unknown method.');")?;
+    write_indentation(buffer, indentation + 2)?;
+    writeln!(buffer, "}}")?;
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(buffer, "}}")?;
+
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "}}")
+}
+
+// Write the namespace containing the functions for a struct or choice.
+fn write_message_namespace<T: Write>(
+    buffer: &mut T,
+    imports: &BTreeMap<Identifier, schema::Namespace>,
+    namespace: &schema::Namespace,
+    type_variant: &schema::TypeVariant,
+) -> Result<(), fmt::Error> {
+    if let schema::TypeVariant::Custom(import, name, _) = type_variant {
+        write_custom_type(buffer, imports, namespace, import.as_ref(), name, None)
+    } else {
+        // Impossible since the validator only allows structs and choices as the input and output
+        // types of methods.
+        panic!()
+    }
+}
+
 // Write a struct, including a trailing line break.
 fn write_struct<T: Write>(
     buffer: &mut T,
     indentation: usize,
     imports: &BTreeMap<Identifier, schema::Namespace>,
     namespace: &schema::Namespace,
-    name: &Identifier,
-    fields: &[schema::Field],
+    declaration: &schema::Declaration,
     direction: Direction,
 ) -> Result<(), fmt::Error> {
+    match direction {
+        Direction::Atlas => {}
+        Direction::In | Direction::Out => {
+            write_doc_comment(buffer, indentation, &declaration.comment)?;
+        }
+    }
     write_indentation(buffer, indentation)?;
     write!(buffer, "export type ")?;
-    write_identifier(buffer, name, Pascal, Some(direction))?;
+    write_identifier(buffer, &declaration.name, Pascal, Some(direction))?;
     writeln!(buffer, " = {{")?;
 
     match direction {
@@ -1349,7 +1768,13 @@ fn write_struct<T: Write>(
         Direction::In | Direction::Out => {}
     }
 
-    for field in fields {
+    for field in &declaration.fields {
+        match direction {
+            Direction::Atlas => {}
+            Direction::In | Direction::Out => {
+                write_doc_comment(buffer, indentation + 1, &field.comment)?;
+            }
+        }
         write_indentation(buffer, indentation + 1)?;
         write_identifier(buffer, &field.name, Camel, None)?;
         write!(buffer, ": ")?;
@@ -1375,47 +1800,208 @@ fn write_struct<T: Write>(
     Ok(())
 }
 
-// Write a choice, including a trailing line break.
-fn write_choice<T: Write>(
+// Write a builder for the `Out` type of a struct, including a trailing line break
+// [tag:typescript_builders]. The type parameter of the builder is the union of the names of the
+// required and asymmetric fields which haven't been given yet, and the message can only be built
+// once it's `never`. Optional fields start out as `undefined`.
+fn write_builder<T: Write>(
     buffer: &mut T,
     indentation: usize,
     imports: &BTreeMap<Identifier, schema::Namespace>,
     namespace: &schema::Namespace,
-    name: &Identifier,
-    fields: &[schema::Field],
-    direction: Direction,
+    declaration: &schema::Declaration,
 ) -> Result<(), fmt::Error> {
+    // Render the union of the names of the required and asymmetric fields.
+    let mut unset = vec![];
+    for field in &declaration.fields {
+        match field.rule {
+            schema::Rule::Asymmetric | schema::Rule::Required => {
+                let mut name = String::new();
+                write_identifier(&mut name, &field.name, Camel, None)?;
+                unset.push(format!("'{name}'"));
+            }
+            schema::Rule::Optional => {}
+        }
+    }
+    let unset = if unset.is_empty() {
+        "never".to_owned()
+    } else {
+        unset.join(" | ")
+    };
+
+    // Write the function which starts building a message.
     write_indentation(buffer, indentation)?;
-    write!(buffer, "export type ")?;
-    write_identifier(buffer, name, Pascal, Some(direction))?;
-    write!(buffer, " =")?;
+    writeln!(buffer, "export function builder(): Builder<{unset}> {{")?;
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(buffer, "return new Builder<{unset}>({{}});")?;
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "}}")?;
 
-    for field in fields {
-        writeln!(buffer)?;
-        write_indentation(buffer, indentation + 1)?;
-        write!(buffer, "| {{ ")?;
+    writeln!(buffer)?;
 
-        match direction {
-            Direction::Atlas => {
-                write!(buffer, "$size: number; ")?;
-                write_identifier(buffer, &field.name, Camel, None)?;
-                write!(buffer, ": ")?;
-                write_type(buffer, imports, namespace, &field.r#type.variant, direction)?;
-            }
-            Direction::In => {
-                write!(buffer, "$field: '")?;
-                write_identifier(buffer, &field.name, Camel, None)?;
-                write!(buffer, "'; ")?;
+    // Write the builder itself. The type parameter is only used by the phantom `$unset` property,
+    // which makes builders in different states incompatible with each other.
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "export class Builder<Unset extends string> {{")?;
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(buffer, "protected readonly $unset?: Unset;")?;
+    writeln!(buffer)?;
+    write_indentation(buffer, indentation + 1)?;
+    write!(buffer, "private readonly $fields: Partial<")?;
+    write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
+    writeln!(buffer, ">;")?;
+    writeln!(buffer)?;
+    write_indentation(buffer, indentation + 1)?;
+    write!(buffer, "constructor(fields: Partial<")?;
+    write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
+    writeln!(buffer, ">) {{")?;
+    write_indentation(buffer, indentation + 2)?;
+    writeln!(buffer, "this.$fields = fields;")?;
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(buffer, "}}")?;
+
+    // Write a setter for each field. Giving a required or asymmetric field removes it from the
+    // type parameter.
+    for field in &declaration.fields {
+        let mut name = String::new();
+        write_identifier(&mut name, &field.name, Camel, None)?;
+        let state = match field.rule {
+            schema::Rule::Asymmetric | schema::Rule::Required => {
+                format!("Exclude<Unset, '{name}'>")
+            }
+            schema::Rule::Optional => "Unset".to_owned(),
+        };
+
+        writeln!(buffer)?;
+        write_doc_comment(buffer, indentation + 1, &field.comment)?;
+        write_indentation(buffer, indentation + 1)?;
+        write!(buffer, "{name}(value: ")?;
+        write_type(buffer, imports, namespace, &field.r#type.variant, Out)?;
+        writeln!(buffer, "): Builder<{state}> {{")?;
+        write_indentation(buffer, indentation + 2)?;
+        writeln!(
+            buffer,
+            "return new Builder<{state}>({{ ...this.$fields, {name}: value }});",
+        )?;
+        write_indentation(buffer, indentation + 1)?;
+        writeln!(buffer, "}}")?;
+    }
+
+    // Write the function which builds the message once all the required and asymmetric fields have
+    // been given.
+    writeln!(buffer)?;
+    write_indentation(buffer, indentation + 1)?;
+    write!(buffer, "build(this: Builder<never>): ")?;
+    write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
+    writeln!(buffer, " {{")?;
+    if declaration.fields.is_empty() {
+        write_indentation(buffer, indentation + 2)?;
+        writeln!(buffer, "return {{}};")?;
+    } else {
+        write_indentation(buffer, indentation + 2)?;
+        write!(buffer, "const fields = this.$fields as ")?;
+        write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
+        writeln!(buffer, ";")?;
+        write_indentation(buffer, indentation + 2)?;
+        writeln!(buffer, "return {{")?;
+        for field in &declaration.fields {
+            write_indentation(buffer, indentation + 3)?;
+            write_identifier(buffer, &field.name, Camel, None)?;
+            write!(buffer, ": fields.")?;
+            write_identifier(buffer, &field.name, Camel, None)?;
+            writeln!(buffer, ",")?;
+        }
+        write_indentation(buffer, indentation + 2)?;
+        writeln!(buffer, "}};")?;
+    }
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(buffer, "}}")?;
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "}}")
+}
+
+// Write a newtype, including a trailing line break. Newtypes have no atlases or namespaces, since
+// they are encoded like their underlying types [ref:newtype_encoding]. The brand prevents values of
+// the underlying type from being used where the newtype is expected. Each newtype gets its own key
+// within the brand so that a newtype of a newtype carries both brands rather than conflicting ones.
+fn write_newtype<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    imports: &BTreeMap<Identifier, schema::Namespace>,
+    namespace: &schema::Namespace,
+    declaration: &schema::Declaration,
+    underlying_type: &schema::Type,
+    direction: Direction,
+) -> Result<(), fmt::Error> {
+    write_doc_comment(buffer, indentation, &declaration.comment)?;
+    write_indentation(buffer, indentation)?;
+    write!(buffer, "export type ")?;
+    write_identifier(buffer, &declaration.name, Pascal, Some(direction))?;
+    write!(buffer, " = ")?;
+    write_type(
+        buffer,
+        imports,
+        namespace,
+        &underlying_type.variant,
+        direction,
+    )?;
+    write!(buffer, " & {{ readonly $brand: {{ readonly '")?;
+    for component in &namespace.components {
+        write_identifier(buffer, component, Pascal, None)?;
+        write!(buffer, ".")?;
+    }
+    write_identifier(buffer, &declaration.name, Pascal, None)?;
+    writeln!(buffer, "': true }} }};")
+}
+
+// Write a choice, including a trailing line break.
+fn write_choice<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    imports: &BTreeMap<Identifier, schema::Namespace>,
+    namespace: &schema::Namespace,
+    declaration: &schema::Declaration,
+    direction: Direction,
+) -> Result<(), fmt::Error> {
+    match direction {
+        Direction::Atlas => {}
+        Direction::In | Direction::Out => {
+            write_doc_comment(buffer, indentation, &declaration.comment)?;
+        }
+    }
+    write_indentation(buffer, indentation)?;
+    write!(buffer, "export type ")?;
+    write_identifier(buffer, &declaration.name, Pascal, Some(direction))?;
+    write!(buffer, " =")?;
+
+    for field in &declaration.fields {
+        writeln!(buffer)?;
+        write_indentation(buffer, indentation + 1)?;
+        write!(buffer, "| {{")?;
+
+        match direction {
+            Direction::Atlas => {
+                write!(buffer, " $size: number; ")?;
+                write_identifier(buffer, &field.name, Camel, None)?;
+                write!(buffer, ": ")?;
+                write_type(buffer, imports, namespace, &field.r#type.variant, direction)?;
+            }
+            Direction::In => {
+                write!(buffer, " $field: '")?;
+                write_identifier(buffer, &field.name, Camel, None)?;
+                write!(buffer, "';")?;
+                write_member_doc_comment(buffer, indentation + 2, &field.comment)?;
                 write_identifier(buffer, &field.name, Camel, None)?;
                 write!(buffer, ": ")?;
                 write_type(buffer, imports, namespace, &field.r#type.variant, direction)?;
             }
             Direction::Out => {
+                write_member_doc_comment(buffer, indentation + 2, &field.comment)?;
                 write_identifier(buffer, &field.name, Camel, None)?;
                 write!(buffer, ": ")?;
                 write_type(buffer, imports, namespace, &field.r#type.variant, direction)?;
             }
-        };
+        }
 
         if match field.rule {
             schema::Rule::Asymmetric => match direction {
@@ -1426,7 +2012,7 @@ fn write_choice<T: Write>(
             schema::Rule::Required => false,
         } {
             write!(buffer, "; $fallback: ")?;
-            write_identifier(buffer, name, Pascal, Some(direction))?;
+            write_identifier(buffer, &declaration.name, Pascal, Some(direction))?;
         }
 
         write!(buffer, " }}")?;
@@ -1437,7 +2023,7 @@ fn write_choice<T: Write>(
         Direction::In => {
             // See https://github.com/microsoft/TypeScript/issues/46978#issuecomment-984093435 for
             // an explanation of this extra case.
-            if fields.len() == 1 {
+            if declaration.fields.len() == 1 {
                 writeln!(buffer)?;
                 write_indentation(buffer, indentation + 1)?;
                 write!(buffer, "| {{ $field: never }}")?;
@@ -1445,7 +2031,7 @@ fn write_choice<T: Write>(
         }
     }
 
-    if fields.is_empty() {
+    if declaration.fields.is_empty() {
         write!(buffer, " never")?;
     }
 
@@ -1555,6 +2141,7 @@ fn write_deserialize_function<T: Write>(
 }
 
 // Write a type.
+#[allow(clippy::too_many_lines)]
 fn write_type<T: Write>(
     buffer: &mut T,
     imports: &BTreeMap<Identifier, schema::Namespace>,
@@ -1563,20 +2150,29 @@ fn write_type<T: Write>(
     direction: Direction,
 ) -> Result<(), fmt::Error> {
     match type_variant {
-        schema::TypeVariant::Array(inner_type) => match direction {
-            Direction::Atlas => match &inner_type.variant {
-                schema::TypeVariant::Array(_)
-                | schema::TypeVariant::Bytes
-                | schema::TypeVariant::Custom(_, _)
+        schema::TypeVariant::Array(inner_type, _) => match direction {
+            Direction::Atlas => match encoding_type(&inner_type.variant) {
+                schema::TypeVariant::Array(_, _)
+                | schema::TypeVariant::Bytes(_)
+                | schema::TypeVariant::Custom(_, _, _)
+                | schema::TypeVariant::Map(_, _)
                 | schema::TypeVariant::String => {
                     write!(buffer, "{{ $size: number; $elements: ")?;
                     write_type(buffer, imports, namespace, &inner_type.variant, direction)?;
                     write!(buffer, "[] }}")?;
                 }
+                schema::TypeVariant::Newtype(_, _, _) => {
+                    // Impossible since `encoding_type` strips away newtypes.
+                    panic!()
+                }
                 schema::TypeVariant::Bool
+                | schema::TypeVariant::F32
                 | schema::TypeVariant::F64
+                | schema::TypeVariant::S32
                 | schema::TypeVariant::S64
+                | schema::TypeVariant::U32
                 | schema::TypeVariant::U64
+                | schema::TypeVariant::U8
                 | schema::TypeVariant::Unit => {
                     write!(buffer, "number")?;
                 }
@@ -1594,7 +2190,7 @@ fn write_type<T: Write>(
                 write!(buffer, "boolean")?;
             }
         },
-        schema::TypeVariant::Bytes => match direction {
+        schema::TypeVariant::Bytes(_) => match direction {
             Direction::Atlas => {
                 write!(buffer, "number")?;
             }
@@ -1602,12 +2198,53 @@ fn write_type<T: Write>(
                 write!(buffer, "ArrayBuffer")?;
             }
         },
-        schema::TypeVariant::Custom(import, name) => {
-            write_custom_type(buffer, imports, namespace, import, name, Some(direction))?;
+        schema::TypeVariant::Newtype(_, _, underlying_type) if direction == Atlas => {
+            // Newtypes have the same atlases as their underlying types [ref:newtype_encoding].
+            write_type(
+                buffer,
+                imports,
+                namespace,
+                &underlying_type.variant,
+                direction,
+            )?;
         }
-        schema::TypeVariant::F64 => {
+        schema::TypeVariant::Custom(import, name, _)
+        | schema::TypeVariant::Newtype(import, name, _) => {
+            write_custom_type(
+                buffer,
+                imports,
+                namespace,
+                import.as_ref(),
+                name,
+                Some(direction),
+            )?;
+        }
+        schema::TypeVariant::F32
+        | schema::TypeVariant::F64
+        | schema::TypeVariant::S32
+        | schema::TypeVariant::U32
+        | schema::TypeVariant::U8 => {
             write!(buffer, "number")?;
         }
+        schema::TypeVariant::Map(key_type, value_type) => match direction {
+            Direction::Atlas => {
+                write!(
+                    buffer,
+                    "{{ $size: number; $entries: {{ $size: number; key: "
+                )?;
+                write_type(buffer, imports, namespace, &key_type.variant, direction)?;
+                write!(buffer, "; value: ")?;
+                write_type(buffer, imports, namespace, &value_type.variant, direction)?;
+                write!(buffer, " }}[] }}")?;
+            }
+            Direction::In | Direction::Out => {
+                write!(buffer, "Map<")?;
+                write_type(buffer, imports, namespace, &key_type.variant, direction)?;
+                write!(buffer, ", ")?;
+                write_type(buffer, imports, namespace, &value_type.variant, direction)?;
+                write!(buffer, ">")?;
+            }
+        },
         schema::TypeVariant::S64 | schema::TypeVariant::U64 => match direction {
             Direction::Atlas => {
                 write!(buffer, "number")?;
@@ -1642,12 +2279,12 @@ fn write_custom_type<T: Write>(
     buffer: &mut T,
     imports: &BTreeMap<Identifier, schema::Namespace>,
     namespace: &schema::Namespace,
-    import: &Option<Identifier>,
+    import: Option<&Identifier>,
     name: &Identifier,
     direction: Option<Direction>,
 ) -> Result<(), fmt::Error> {
     let type_namespace = schema::Namespace {
-        components: import.as_ref().map_or_else(
+        components: import.map_or_else(
             || namespace.components.clone(),
             |import| imports[import].components.clone(),
         ),
@@ -1685,6 +2322,7 @@ fn write_identifier<T: Write>(
     let converted_identifier = match case {
         CaseConvention::Camel => identifier_with_suffix.camel_case(),
         CaseConvention::Pascal => identifier_with_suffix.pascal_case(),
+        CaseConvention::ScreamingSnake => identifier_with_suffix.screaming_snake_case(),
     };
 
     if TYPESCRIPT_KEYWORDS
@@ -1699,6 +2337,66 @@ fn write_identifier<T: Write>(
     Ok(())
 }
 
+// Write a comment as a JSDoc block, with paragraphs separated by empty lines.
+fn write_doc_comment<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    paragraphs: &[String],
+) -> Result<(), fmt::Error> {
+    if paragraphs.is_empty() {
+        return Ok(());
+    }
+
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "/**")?;
+
+    for (i, paragraph) in paragraphs.iter().enumerate() {
+        if i != 0 {
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, " *")?;
+        }
+
+        for line in paragraph.lines() {
+            write_indentation(buffer, indentation)?;
+            // Make sure the comment can't terminate the block early.
+            writeln!(buffer, " * {}", line.replace("*/", "*\\/"))?;
+        }
+    }
+
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, " */")?;
+
+    Ok(())
+}
+
+// Add a paragraph with the `@deprecated` JSDoc tag to a comment if the given attributes include
+// `@deprecated`.
+fn document_deprecation(comment: &mut Vec<String>, attributes: &[schema::Attribute]) {
+    match deprecation(attributes) {
+        Some("") => comment.push("@deprecated".to_owned()),
+        Some(message) => comment.push(format!("@deprecated {message}")),
+        None => {}
+    }
+}
+
+// Write the separator before a member of an object type, which is a JSDoc block on its own lines
+// if the member has a comment and a space otherwise.
+fn write_member_doc_comment<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    paragraphs: &[String],
+) -> Result<(), fmt::Error> {
+    if paragraphs.is_empty() {
+        write!(buffer, " ")?;
+    } else {
+        writeln!(buffer)?;
+        write_doc_comment(buffer, indentation, paragraphs)?;
+        write_indentation(buffer, indentation)?;
+    }
+
+    Ok(())
+}
+
 // Write the given level of indentation.
 fn write_indentation<T: Write>(buffer: &mut T, indentation: usize) -> Result<(), fmt::Error> {
     for _ in 0..indentation {
@@ -1708,6 +2406,19 @@ fn write_indentation<T: Write>(buffer: &mut T, indentation: usize) -> Result<(),
     Ok(())
 }
 
+// Write some lines of code at the given level of indentation. Empty lines aren't indented.
+fn write_lines<T: Write>(buffer: &mut T, indentation: usize, code: &str) -> Result<(), fmt::Error> {
+    for line in code.lines() {
+        if !line.is_empty() {
+            write_indentation(buffer, indentation)?;
+        }
+
+        writeln!(buffer, "{line}")?;
+    }
+
+    Ok(())
+}
+
 // Write the logic to compute the encoded size of a value.
 //
 // Context variables:
@@ -1723,10 +2434,11 @@ fn write_atlas_calculation<T: Write>(
     is_field: bool,
 ) -> Result<(), fmt::Error> {
     match type_variant {
-        schema::TypeVariant::Array(inner_type) => match &inner_type.variant {
-            schema::TypeVariant::Array(_)
-            | schema::TypeVariant::Bytes
-            | schema::TypeVariant::Custom(_, _)
+        schema::TypeVariant::Array(inner_type, _) => match encoding_type(&inner_type.variant) {
+            schema::TypeVariant::Array(_, _)
+            | schema::TypeVariant::Bytes(_)
+            | schema::TypeVariant::Custom(_, _, _)
+            | schema::TypeVariant::Map(_, _)
             | schema::TypeVariant::String => {
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "{{")?;
@@ -1771,7 +2483,12 @@ fn write_atlas_calculation<T: Write>(
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "}}")
             }
-            schema::TypeVariant::Bool | schema::TypeVariant::S64 | schema::TypeVariant::U64 => {
+            schema::TypeVariant::Bool
+            | schema::TypeVariant::S32
+            | schema::TypeVariant::S64
+            | schema::TypeVariant::U32
+            | schema::TypeVariant::U64
+            | schema::TypeVariant::U8 => {
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "{{")?;
                 write_indentation(buffer, indentation + 1)?;
@@ -1801,6 +2518,10 @@ fn write_atlas_calculation<T: Write>(
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "}}")
             }
+            schema::TypeVariant::F32 => {
+                write_indentation(buffer, indentation)?;
+                writeln!(buffer, "payloadAtlas = 4 * payload.length;")
+            }
             schema::TypeVariant::F64 => {
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "payloadAtlas = 8 * payload.length;")
@@ -1827,6 +2548,10 @@ fn write_atlas_calculation<T: Write>(
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "}}")
             }
+            schema::TypeVariant::Newtype(_, _, _) => {
+                // Impossible since `encoding_type` strips away newtypes.
+                panic!()
+            }
         },
         schema::TypeVariant::Bool => {
             write_indentation(buffer, indentation)?;
@@ -1844,7 +2569,7 @@ fn write_atlas_calculation<T: Write>(
                 writeln!(buffer, "payloadAtlas = 1;")
             }
         }
-        schema::TypeVariant::Bytes => {
+        schema::TypeVariant::Bytes(_) => {
             write_indentation(buffer, indentation)?;
             writeln!(buffer, "payloadAtlas = payload.byteLength;")
         }
@@ -1852,12 +2577,39 @@ fn write_atlas_calculation<T: Write>(
             write_indentation(buffer, indentation)?;
             writeln!(buffer, "payloadAtlas = textEncoder.encode(payload);")
         }
-        schema::TypeVariant::Custom(import, name) => {
+        schema::TypeVariant::Custom(import, name, _) => {
             write_indentation(buffer, indentation)?;
             write!(buffer, "payloadAtlas = ")?;
-            write_custom_type(buffer, imports, namespace, import, name, None)?;
+            write_custom_type(buffer, imports, namespace, import.as_ref(), name, None)?;
             writeln!(buffer, ".atlas(payload);")
         }
+        schema::TypeVariant::Newtype(_, _, underlying_type) => {
+            // Newtypes are encoded like their underlying types [ref:newtype_encoding].
+            write_atlas_calculation(
+                buffer,
+                indentation,
+                imports,
+                namespace,
+                &underlying_type.variant,
+                is_field,
+            )
+        }
+        schema::TypeVariant::F32 => {
+            write_indentation(buffer, indentation)?;
+            if is_field {
+                writeln!(buffer, "if (Object.is(payload, 0)) {{")?;
+                write_indentation(buffer, indentation + 1)?;
+                writeln!(buffer, "payloadAtlas = 0;")?;
+                write_indentation(buffer, indentation)?;
+                writeln!(buffer, "}} else {{")?;
+                write_indentation(buffer, indentation + 1)?;
+                writeln!(buffer, "payloadAtlas = 4;")?;
+                write_indentation(buffer, indentation)?;
+                writeln!(buffer, "}}")
+            } else {
+                writeln!(buffer, "payloadAtlas = 4;")
+            }
+        }
         schema::TypeVariant::F64 => {
             write_indentation(buffer, indentation)?;
             if is_field {
@@ -1874,6 +2626,72 @@ fn write_atlas_calculation<T: Write>(
                 writeln!(buffer, "payloadAtlas = 8;")
             }
         }
+        schema::TypeVariant::Map(key_type, value_type) => {
+            // Each entry is encoded like a struct with the key and value as fields 0 and 1
+            // [ref:map_encoding].
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "{{")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "let size = 0;")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "let entries = [];")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "for (const [key, value] of payload) {{")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "let entrySize = 0;")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "let keyAtlas, valueAtlas;")?;
+            for (index, name, r#type) in [(0_usize, "key", key_type), (1, "value", value_type)] {
+                write_indentation(buffer, indentation + 2)?;
+                writeln!(buffer, "{{")?;
+                write_indentation(buffer, indentation + 3)?;
+                writeln!(buffer, "const payload = {name};")?;
+                write_indentation(buffer, indentation + 3)?;
+                writeln!(buffer, "let payloadAtlas;")?;
+                write_atlas_calculation(
+                    buffer,
+                    indentation + 3,
+                    imports,
+                    namespace,
+                    &r#type.variant,
+                    true,
+                )?;
+                write_indentation(buffer, indentation + 3)?;
+                writeln!(buffer, "{name}Atlas = payloadAtlas;")?;
+                write_indentation(buffer, indentation + 3)?;
+                write!(buffer, "const payloadSize = ")?;
+                write_atlas_lookup(buffer, &r#type.variant)?;
+                writeln!(buffer, ";")?;
+                write_indentation(buffer, indentation + 3)?;
+                writeln!(
+                    buffer,
+                    "entrySize += fieldHeaderSize({}n, payloadSize, {}) + payloadSize;",
+                    index,
+                    integer_encoded(r#type),
+                )?;
+                write_indentation(buffer, indentation + 2)?;
+                writeln!(buffer, "}}")?;
+            }
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(
+                buffer,
+                "entries.push({{ $size: entrySize, key: keyAtlas, value: valueAtlas }});",
+            )?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(
+                buffer,
+                "size += varintSizeFromValue(BigInt(entrySize)) + entrySize;",
+            )?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "}}")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(
+                buffer,
+                "payloadAtlas = {{ $size: size, $entries: entries }};"
+            )?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "}}")
+        }
         schema::TypeVariant::S64 => {
             write_indentation(buffer, indentation)?;
             if is_field {
@@ -1923,6 +2741,29 @@ fn write_atlas_calculation<T: Write>(
                 writeln!(buffer, "payloadAtlas = varintSizeFromValue(payload);")
             }
         }
+        schema::TypeVariant::S32 | schema::TypeVariant::U32 | schema::TypeVariant::U8 => {
+            // These have the same encodings as `S64` and `U64` [ref:narrow_integer_encoding].
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "{{")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "const oldPayload = payload;")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "{{")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "const payload = BigInt(oldPayload);")?;
+            write_atlas_calculation(
+                buffer,
+                indentation + 2,
+                imports,
+                namespace,
+                wide_integer_type(type_variant),
+                is_field,
+            )?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "}}")?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "}}")
+        }
         schema::TypeVariant::Unit => {
             write_indentation(buffer, indentation)?;
             writeln!(buffer, "payloadAtlas = 0;")
@@ -1939,28 +2780,45 @@ fn write_atlas_lookup<T: Write>(
     type_variant: &schema::TypeVariant,
 ) -> Result<(), fmt::Error> {
     match type_variant {
-        schema::TypeVariant::Array(inner_type) => match &inner_type.variant {
-            schema::TypeVariant::Array(_)
-            | schema::TypeVariant::Bytes
-            | schema::TypeVariant::Custom(_, _)
+        schema::TypeVariant::Array(inner_type, _) => match encoding_type(&inner_type.variant) {
+            schema::TypeVariant::Array(_, _)
+            | schema::TypeVariant::Bytes(_)
+            | schema::TypeVariant::Custom(_, _, _)
+            | schema::TypeVariant::Map(_, _)
             | schema::TypeVariant::String => write!(buffer, "payloadAtlas.$size"),
             schema::TypeVariant::Bool
+            | schema::TypeVariant::F32
             | schema::TypeVariant::F64
+            | schema::TypeVariant::S32
             | schema::TypeVariant::S64
+            | schema::TypeVariant::U32
             | schema::TypeVariant::U64
+            | schema::TypeVariant::U8
             | schema::TypeVariant::Unit => write!(buffer, "payloadAtlas"),
+            schema::TypeVariant::Newtype(_, _, _) => {
+                // Impossible since `encoding_type` strips away newtypes.
+                panic!()
+            }
         },
         schema::TypeVariant::Bool
-        | schema::TypeVariant::Bytes
+        | schema::TypeVariant::Bytes(_)
+        | schema::TypeVariant::F32
         | schema::TypeVariant::F64
+        | schema::TypeVariant::S32
         | schema::TypeVariant::S64
+        | schema::TypeVariant::U32
         | schema::TypeVariant::U64
+        | schema::TypeVariant::U8
         | schema::TypeVariant::Unit => write!(buffer, "payloadAtlas"),
-        schema::TypeVariant::Custom(_, _) => {
+        schema::TypeVariant::Map(_, _) => write!(buffer, "payloadAtlas.$size"),
+        schema::TypeVariant::Custom(_, _, _) => {
             // The type assertion is needed for singleton choices and empty choices, which are
             // special cases due to the nature of TypeScript's type system.
             write!(buffer, "(payloadAtlas as {{ $size: number }}).$size")
         }
+        schema::TypeVariant::Newtype(_, _, underlying_type) => {
+            write_atlas_lookup(buffer, &underlying_type.variant)
+        }
         schema::TypeVariant::String => write!(buffer, "payloadAtlas.byteLength"),
     }
 }
@@ -1974,7 +2832,7 @@ fn write_atlas_lookup<T: Write>(
 // - `payload` (in)
 //
 // Additional notes:
-// - If `is_field` is unset and `type_variant` is `Bool`, `S64`, `U64`, or `F64`, then
+// - If `is_field` is unset and `type_variant` is `Bool`, a numeric type, or `F64`, then
 //   `payloadAtlas` is never read.
 #[allow(clippy::too_many_lines)]
 fn write_serialization_invocation<T: Write>(
@@ -1986,10 +2844,11 @@ fn write_serialization_invocation<T: Write>(
     is_field: bool,
 ) -> Result<(), fmt::Error> {
     match type_variant {
-        schema::TypeVariant::Array(inner_type) => match &inner_type.variant {
-            schema::TypeVariant::Array(_)
-            | schema::TypeVariant::Bytes
-            | schema::TypeVariant::Custom(_, _)
+        schema::TypeVariant::Array(inner_type, _) => match encoding_type(&inner_type.variant) {
+            schema::TypeVariant::Array(_, _)
+            | schema::TypeVariant::Bytes(_)
+            | schema::TypeVariant::Custom(_, _, _)
+            | schema::TypeVariant::Map(_, _)
             | schema::TypeVariant::String => {
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "{{")?;
@@ -2021,8 +2880,12 @@ fn write_serialization_invocation<T: Write>(
                 writeln!(buffer, "}}")
             }
             schema::TypeVariant::Bool
+            | schema::TypeVariant::S32
             | schema::TypeVariant::S64
+            | schema::TypeVariant::U32
             | schema::TypeVariant::U64
+            | schema::TypeVariant::U8
+            | schema::TypeVariant::F32
             | schema::TypeVariant::F64 => {
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "{{")?;
@@ -2054,6 +2917,10 @@ fn write_serialization_invocation<T: Write>(
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "}}")
             }
+            schema::TypeVariant::Newtype(_, _, _) => {
+                // Impossible since `encoding_type` strips away newtypes.
+                panic!()
+            }
         },
         schema::TypeVariant::Bool => {
             write_indentation(buffer, indentation)?;
@@ -2064,7 +2931,7 @@ fn write_serialization_invocation<T: Write>(
             write_indentation(buffer, indentation)?;
             writeln!(buffer, "}}")
         }
-        schema::TypeVariant::Bytes => {
+        schema::TypeVariant::Bytes(_) => {
             write_indentation(buffer, indentation)?;
             writeln!(buffer, "{{")?;
             write_indentation(buffer, indentation + 1)?;
@@ -2086,15 +2953,44 @@ fn write_serialization_invocation<T: Write>(
             write_indentation(buffer, indentation)?;
             writeln!(buffer, "}}")
         }
-        schema::TypeVariant::Custom(import, name) => {
+        schema::TypeVariant::Custom(import, name, _) => {
             write_indentation(buffer, indentation)?;
             write!(buffer, "offset = ")?;
-            write_custom_type(buffer, imports, namespace, import, name, None)?;
+            write_custom_type(buffer, imports, namespace, import.as_ref(), name, None)?;
             writeln!(
                 buffer,
                 ".serializeWithAtlasUnsafe(dataView, offset, payload, payloadAtlas);",
             )
         }
+        schema::TypeVariant::Newtype(_, _, underlying_type) => {
+            // Newtypes are encoded like their underlying types [ref:newtype_encoding].
+            write_serialization_invocation(
+                buffer,
+                indentation,
+                imports,
+                namespace,
+                &underlying_type.variant,
+                is_field,
+            )
+        }
+        schema::TypeVariant::F32 => {
+            write_indentation(buffer, indentation)?;
+            if is_field {
+                write!(buffer, "if (")?;
+                write_atlas_lookup(buffer, type_variant)?;
+                writeln!(buffer, " !== 0) {{")?;
+                write_indentation(buffer, indentation + 1)?;
+                writeln!(buffer, "dataView.setFloat32(offset, payload, true);")?;
+                write_indentation(buffer, indentation + 1)?;
+                writeln!(buffer, "offset += 4;")?;
+                write_indentation(buffer, indentation)?;
+                writeln!(buffer, "}}")
+            } else {
+                writeln!(buffer, "dataView.setFloat32(offset, payload, true);")?;
+                write_indentation(buffer, indentation)?;
+                writeln!(buffer, "offset += 4;")
+            }
+        }
         schema::TypeVariant::F64 => {
             write_indentation(buffer, indentation)?;
             if is_field {
@@ -2113,17 +3009,81 @@ fn write_serialization_invocation<T: Write>(
                 writeln!(buffer, "offset += 8;")
             }
         }
-        schema::TypeVariant::S64 => {
+        schema::TypeVariant::Map(key_type, value_type) => {
             write_indentation(buffer, indentation)?;
             writeln!(buffer, "{{")?;
             write_indentation(buffer, indentation + 1)?;
-            writeln!(buffer, "const varint = zigzagEncode(payload);")?;
-            write_u64_serialization_invocation(buffer, indentation + 1, is_field)?;
-            write_indentation(buffer, indentation)?;
-            writeln!(buffer, "}}")
-        }
-        schema::TypeVariant::String => {
-            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "const oldPayload = payload;")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "const oldPayloadAtlas = payloadAtlas;")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "let i = 0;")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "for (const [key, value] of oldPayload) {{")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "const entryAtlas = oldPayloadAtlas.$entries[i];")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "i += 1;")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(
+                buffer,
+                "offset = serializeVarint(dataView, offset, BigInt(entryAtlas.$size));",
+            )?;
+            for (index, name, r#type) in [(0_usize, "key", key_type), (1, "value", value_type)] {
+                write_indentation(buffer, indentation + 2)?;
+                writeln!(buffer, "{{")?;
+                write_indentation(buffer, indentation + 3)?;
+                writeln!(buffer, "const payload = {name};")?;
+                write_indentation(buffer, indentation + 3)?;
+                writeln!(buffer, "const payloadAtlas = entryAtlas.{name};")?;
+                write_indentation(buffer, indentation + 3)?;
+                write!(buffer, "const payloadSize = ")?;
+                write_atlas_lookup(buffer, &r#type.variant)?;
+                writeln!(buffer, ";")?;
+                write_indentation(buffer, indentation + 3)?;
+                writeln!(
+                    buffer,
+                    "offset = serializeFieldHeader(dataView, offset, {}n, payloadSize, {});",
+                    index,
+                    integer_encoded(r#type),
+                )?;
+                write_serialization_invocation(
+                    buffer,
+                    indentation + 3,
+                    imports,
+                    namespace,
+                    &r#type.variant,
+                    true,
+                )?;
+                write_indentation(buffer, indentation + 2)?;
+                writeln!(buffer, "}}")?;
+            }
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "}}")?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "}}")
+        }
+        schema::TypeVariant::S64 => {
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "{{")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "const varint = zigzagEncode(payload);")?;
+            write_u64_serialization_invocation(buffer, indentation + 1, is_field)?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "}}")
+        }
+        schema::TypeVariant::S32 => {
+            // `S32` has the same encoding as `S64` [ref:narrow_integer_encoding].
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "{{")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "const varint = zigzagEncode(BigInt(payload));")?;
+            write_u64_serialization_invocation(buffer, indentation + 1, is_field)?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "}}")
+        }
+        schema::TypeVariant::String => {
+            write_indentation(buffer, indentation)?;
             writeln!(buffer, "{{")?;
             write_indentation(buffer, indentation + 1)?;
             writeln!(buffer, "const targetBuffer = new Uint8Array(")?;
@@ -2151,6 +3111,16 @@ fn write_serialization_invocation<T: Write>(
             write_indentation(buffer, indentation)?;
             writeln!(buffer, "}}")
         }
+        schema::TypeVariant::U32 | schema::TypeVariant::U8 => {
+            // `U32` and `U8` have the same encoding as `U64` [ref:narrow_integer_encoding].
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "{{")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "const varint = BigInt(payload);")?;
+            write_u64_serialization_invocation(buffer, indentation + 1, is_field)?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "}}")
+        }
         schema::TypeVariant::Unit => Ok(()),
     }
 }
@@ -2204,7 +3174,6 @@ fn write_u64_serialization_invocation<T: Write>(
 // - If `type_variant` is `Array`, `Bytes`, `Custom`, or `String` and the encoded data is well-
 //   formed, then `dataView` is consumed to the end.
 // - If `type_variant` is `Custom`, then `offset` must be 0.
-#[allow(clippy::too_many_lines)]
 fn write_deserialization_invocation<T: Write>(
     buffer: &mut T,
     indentation: usize,
@@ -2212,12 +3181,64 @@ fn write_deserialization_invocation<T: Write>(
     namespace: &schema::Namespace,
     type_variant: &schema::TypeVariant,
     is_field: bool,
+) -> Result<(), fmt::Error> {
+    // Fixed-length values are decoded like variable-length ones [ref:fixed_length_encoding] and
+    // then have their lengths checked.
+    let (variable_length_variant, length, length_property) = match type_variant {
+        schema::TypeVariant::Array(inner_type, Some(length)) => (
+            schema::TypeVariant::Array(inner_type.clone(), None),
+            length,
+            "length",
+        ),
+        schema::TypeVariant::Bytes(Some(length)) => {
+            (schema::TypeVariant::Bytes(None), length, "byteLength")
+        }
+        _ => {
+            return write_variable_length_deserialization_invocation(
+                buffer,
+                indentation,
+                imports,
+                namespace,
+                type_variant,
+                is_field,
+            );
+        }
+    };
+
+    write_variable_length_deserialization_invocation(
+        buffer,
+        indentation,
+        imports,
+        namespace,
+        &variable_length_variant,
+        is_field,
+    )?;
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "if (payload.{length_property} !== {length}) {{")?;
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(buffer, "throw new Error(wrongLengthErrorMessage);")?;
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "}}")
+}
+
+// Write the logic to invoke the deserialization logic for a value of a type which isn't a fixed-
+// length array or `Bytes`, including a trailing line break. The context variables and notes are the
+// same as for `write_deserialization_invocation`.
+#[allow(clippy::too_many_lines)]
+fn write_variable_length_deserialization_invocation<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    imports: &BTreeMap<Identifier, schema::Namespace>,
+    namespace: &schema::Namespace,
+    type_variant: &schema::TypeVariant,
+    is_field: bool,
 ) -> Result<(), fmt::Error> {
     match type_variant {
-        schema::TypeVariant::Array(inner_type) => match &inner_type.variant {
-            schema::TypeVariant::Array(_)
-            | schema::TypeVariant::Bytes
-            | schema::TypeVariant::Custom(_, _)
+        schema::TypeVariant::Array(inner_type, _) => match encoding_type(&inner_type.variant) {
+            schema::TypeVariant::Array(_, _)
+            | schema::TypeVariant::Bytes(_)
+            | schema::TypeVariant::Custom(_, _, _)
+            | schema::TypeVariant::Map(_, _)
             | schema::TypeVariant::String => {
                 write_indentation(buffer, indentation)?;
                 write!(buffer, "let payload: ")?;
@@ -2290,8 +3311,12 @@ fn write_deserialization_invocation<T: Write>(
                 writeln!(buffer, "}}")
             }
             schema::TypeVariant::Bool
+            | schema::TypeVariant::S32
             | schema::TypeVariant::S64
+            | schema::TypeVariant::U32
             | schema::TypeVariant::U64
+            | schema::TypeVariant::U8
+            | schema::TypeVariant::F32
             | schema::TypeVariant::F64 => {
                 write_indentation(buffer, indentation)?;
                 write!(buffer, "let payload: ")?;
@@ -2367,6 +3392,10 @@ fn write_deserialization_invocation<T: Write>(
                 write_indentation(buffer, indentation)?;
                 writeln!(buffer, "}}")
             }
+            schema::TypeVariant::Newtype(_, _, _) => {
+                // Impossible since `encoding_type` strips away newtypes.
+                panic!()
+            }
         },
         schema::TypeVariant::Bool => {
             write_indentation(buffer, indentation)?;
@@ -2394,7 +3423,7 @@ fn write_deserialization_invocation<T: Write>(
             write_indentation(buffer, indentation)?;
             writeln!(buffer, "}}")
         }
-        schema::TypeVariant::Bytes => {
+        schema::TypeVariant::Bytes(_) => {
             write_indentation(buffer, indentation)?;
             writeln!(buffer, "let payload = dataView.buffer.slice(")?;
             write_indentation(buffer, indentation + 1)?;
@@ -2406,14 +3435,75 @@ fn write_deserialization_invocation<T: Write>(
             write_indentation(buffer, indentation)?;
             writeln!(buffer, "offset = dataView.byteLength;")
         }
-        schema::TypeVariant::Custom(import, name) => {
+        schema::TypeVariant::Custom(import, name, _) => {
             write_indentation(buffer, indentation)?;
             write!(buffer, "let payload = ")?;
-            write_custom_type(buffer, imports, namespace, import, name, None)?;
+            write_custom_type(buffer, imports, namespace, import.as_ref(), name, None)?;
             writeln!(buffer, ".deserializeUnsafe(dataView);")?;
             write_indentation(buffer, indentation)?;
             writeln!(buffer, "offset = dataView.byteLength;")
         }
+        schema::TypeVariant::Newtype(import, name, underlying_type) => {
+            // Newtypes are encoded like their underlying types [ref:newtype_encoding].
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "let payload;")?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "{{")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "let newPayload;")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "{{")?;
+            write_deserialization_invocation(
+                buffer,
+                indentation + 2,
+                imports,
+                namespace,
+                &underlying_type.variant,
+                is_field,
+            )?;
+            write_indentation(buffer, indentation + 2)?;
+            write!(buffer, "newPayload = payload as ")?;
+            write_custom_type(buffer, imports, namespace, import.as_ref(), name, Some(In))?;
+            writeln!(buffer, ";")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "}}")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "payload = newPayload;")?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "}}")
+        }
+        schema::TypeVariant::F32 => {
+            write_indentation(buffer, indentation)?;
+            if is_field {
+                writeln!(buffer, "let payload;")?;
+                write_indentation(buffer, indentation)?;
+                writeln!(buffer, "{{")?;
+                write_indentation(buffer, indentation + 1)?;
+                writeln!(buffer, "switch (payloadSize) {{")?;
+                write_indentation(buffer, indentation + 2)?;
+                writeln!(buffer, "case 0:")?;
+                write_indentation(buffer, indentation + 3)?;
+                writeln!(buffer, "payload = 0;")?;
+                write_indentation(buffer, indentation + 3)?;
+                writeln!(buffer, "break;")?;
+                write_indentation(buffer, indentation + 2)?;
+                writeln!(buffer, "default:")?;
+                write_indentation(buffer, indentation + 3)?;
+                writeln!(buffer, "payload = dataView.getFloat32(offset, true);")?;
+                write_indentation(buffer, indentation + 3)?;
+                writeln!(buffer, "offset += 4;")?;
+                write_indentation(buffer, indentation + 3)?;
+                writeln!(buffer, "break;")?;
+                write_indentation(buffer, indentation + 1)?;
+                writeln!(buffer, "}}")?;
+                write_indentation(buffer, indentation)?;
+                writeln!(buffer, "}}")
+            } else {
+                writeln!(buffer, "let payload = dataView.getFloat32(offset, true);")?;
+                write_indentation(buffer, indentation)?;
+                writeln!(buffer, "offset += 4;")
+            }
+        }
         schema::TypeVariant::F64 => {
             write_indentation(buffer, indentation)?;
             if is_field {
@@ -2446,6 +3536,141 @@ fn write_deserialization_invocation<T: Write>(
                 writeln!(buffer, "offset += 8;")
             }
         }
+        schema::TypeVariant::Map(key_type, value_type) => {
+            write_indentation(buffer, indentation)?;
+            write!(buffer, "let payload: ")?;
+            write_type(buffer, imports, namespace, type_variant, In)?;
+            writeln!(buffer, " = new Map();")?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "{{")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "const dataViewAlias = dataView;")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "const payloadAlias = payload;")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "while (true) {{")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "let entrySizeBig;")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "try {{")?;
+            write_indentation(buffer, indentation + 3)?;
+            writeln!(
+                buffer,
+                "[offset, entrySizeBig] = deserializeVarint(dataViewAlias, offset);",
+            )?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "}} catch (e) {{")?;
+            write_indentation(buffer, indentation + 3)?;
+            writeln!(buffer, "if (e instanceof RangeError) {{")?;
+            write_indentation(buffer, indentation + 4)?;
+            writeln!(buffer, "break;")?;
+            write_indentation(buffer, indentation + 3)?;
+            writeln!(buffer, "}} else {{")?;
+            write_indentation(buffer, indentation + 4)?;
+            writeln!(buffer, "throw e;")?;
+            write_indentation(buffer, indentation + 3)?;
+            writeln!(buffer, "}}")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "}}")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "const entryDataView = new DataView(")?;
+            write_indentation(buffer, indentation + 3)?;
+            writeln!(buffer, "dataViewAlias.buffer,")?;
+            write_indentation(buffer, indentation + 3)?;
+            writeln!(buffer, "dataViewAlias.byteOffset + offset,")?;
+            write_indentation(buffer, indentation + 3)?;
+            writeln!(buffer, "Number(entrySizeBig),")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, ");")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "offset += Number(entrySizeBig);")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "let entryOffset = 0;")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "let key, value;")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "while (true) {{")?;
+            write_indentation(buffer, indentation + 3)?;
+            writeln!(buffer, "let index, payloadSize;")?;
+            write_indentation(buffer, indentation + 3)?;
+            writeln!(buffer, "try {{")?;
+            write_indentation(buffer, indentation + 4)?;
+            writeln!(
+                buffer,
+                "[entryOffset, index, payloadSize] = \
+                    deserializeFieldHeader(entryDataView, entryOffset);",
+            )?;
+            write_indentation(buffer, indentation + 3)?;
+            writeln!(buffer, "}} catch (e) {{")?;
+            write_indentation(buffer, indentation + 4)?;
+            writeln!(buffer, "if (e instanceof RangeError) {{")?;
+            write_indentation(buffer, indentation + 5)?;
+            writeln!(buffer, "break;")?;
+            write_indentation(buffer, indentation + 4)?;
+            writeln!(buffer, "}} else {{")?;
+            write_indentation(buffer, indentation + 5)?;
+            writeln!(buffer, "throw e;")?;
+            write_indentation(buffer, indentation + 4)?;
+            writeln!(buffer, "}}")?;
+            write_indentation(buffer, indentation + 3)?;
+            writeln!(buffer, "}}")?;
+            write_indentation(buffer, indentation + 3)?;
+            writeln!(buffer, "switch (index) {{")?;
+            for (index, name, r#type) in [(0_usize, "key", key_type), (1, "value", value_type)] {
+                write_indentation(buffer, indentation + 4)?;
+                writeln!(buffer, "case {index}n: {{")?;
+                write_indentation(buffer, indentation + 5)?;
+                writeln!(buffer, "const dataView = new DataView(")?;
+                write_indentation(buffer, indentation + 6)?;
+                writeln!(buffer, "entryDataView.buffer,")?;
+                write_indentation(buffer, indentation + 6)?;
+                writeln!(buffer, "entryDataView.byteOffset + entryOffset,")?;
+                write_indentation(buffer, indentation + 6)?;
+                writeln!(buffer, "payloadSize,")?;
+                write_indentation(buffer, indentation + 5)?;
+                writeln!(buffer, ");")?;
+                write_indentation(buffer, indentation + 5)?;
+                writeln!(buffer, "let offset = 0;")?;
+                write_deserialization_invocation(
+                    buffer,
+                    indentation + 5,
+                    imports,
+                    namespace,
+                    &r#type.variant,
+                    true,
+                )?;
+                write_indentation(buffer, indentation + 5)?;
+                writeln!(buffer, "entryOffset += offset;")?;
+                write_indentation(buffer, indentation + 5)?;
+                writeln!(buffer, "{name} = payload;")?;
+                write_indentation(buffer, indentation + 5)?;
+                writeln!(buffer, "break;")?;
+                write_indentation(buffer, indentation + 4)?;
+                writeln!(buffer, "}}")?;
+            }
+            write_indentation(buffer, indentation + 4)?;
+            writeln!(buffer, "default:")?;
+            write_indentation(buffer, indentation + 5)?;
+            writeln!(buffer, "entryOffset += payloadSize;")?;
+            write_indentation(buffer, indentation + 5)?;
+            writeln!(buffer, "break;")?;
+            write_indentation(buffer, indentation + 3)?;
+            writeln!(buffer, "}}")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "}}")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "if (key === undefined || value === undefined) {{")?;
+            write_indentation(buffer, indentation + 3)?;
+            writeln!(buffer, "throw new Error(missingFieldsErrorMessage);")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "}}")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "payloadAlias.set(key, value);")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "}}")?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "}}")
+        }
         schema::TypeVariant::S64 => {
             write_deserialization_invocation(
                 buffer,
@@ -2458,6 +3683,44 @@ fn write_deserialization_invocation<T: Write>(
             write_indentation(buffer, indentation)?;
             writeln!(buffer, "payload = zigzagDecode(payload);")
         }
+        schema::TypeVariant::S32 | schema::TypeVariant::U32 | schema::TypeVariant::U8 => {
+            // These have the same encodings as `S64` and `U64` [ref:narrow_integer_encoding].
+            let condition = match type_variant {
+                schema::TypeVariant::S32 => "payload < -2_147_483_648n || payload > 2_147_483_647n",
+                schema::TypeVariant::U32 => "payload > 4_294_967_295n",
+                _ => "payload > 255n",
+            };
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "let payload;")?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "{{")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "let newPayload;")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "{{")?;
+            write_deserialization_invocation(
+                buffer,
+                indentation + 2,
+                imports,
+                namespace,
+                wide_integer_type(type_variant),
+                is_field,
+            )?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "if ({condition}) {{")?;
+            write_indentation(buffer, indentation + 3)?;
+            writeln!(buffer, "throw new Error(integerOutOfRangeErrorMessage);")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "}}")?;
+            write_indentation(buffer, indentation + 2)?;
+            writeln!(buffer, "newPayload = Number(payload);")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "}}")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "payload = newPayload;")?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "}}")
+        }
         schema::TypeVariant::String => {
             write_indentation(buffer, indentation)?;
             writeln!(buffer, "let payload = textDecoder.decode(")?;
@@ -2525,14 +3788,362 @@ fn write_deserialization_invocation<T: Write>(
     }
 }
 
+// Determine whether a type refers to a struct or choice, possibly within a collection or a
+// newtype. Values of such types are validated by the `validateUnsafe` functions of their
+// namespaces.
+fn contains_struct_or_choice(type_variant: &schema::TypeVariant) -> bool {
+    match type_variant {
+        schema::TypeVariant::Array(inner_type, _) => contains_struct_or_choice(&inner_type.variant),
+        schema::TypeVariant::Custom(_, _, _) => true,
+        schema::TypeVariant::Map(_, value_type) => contains_struct_or_choice(&value_type.variant),
+        schema::TypeVariant::Newtype(_, _, underlying_type) => {
+            contains_struct_or_choice(&underlying_type.variant)
+        }
+        schema::TypeVariant::Bool
+        | schema::TypeVariant::Bytes(_)
+        | schema::TypeVariant::F32
+        | schema::TypeVariant::F64
+        | schema::TypeVariant::S32
+        | schema::TypeVariant::S64
+        | schema::TypeVariant::String
+        | schema::TypeVariant::U32
+        | schema::TypeVariant::U64
+        | schema::TypeVariant::U8
+        | schema::TypeVariant::Unit => false,
+    }
+}
+
+// Determine the length of a fixed-length array or `Bytes` type.
+fn fixed_length(type_variant: &schema::TypeVariant) -> Option<usize> {
+    match type_variant {
+        schema::TypeVariant::Array(_, length) | schema::TypeVariant::Bytes(length) => *length,
+        _ => None,
+    }
+}
+
+// Write the logic to throw an error if a value doesn't satisfy the constraints of a field
+// [ref:constraints]. Newtypes need no unwrapping, since they're represented by their underlying
+// types at runtime.
+//
+// Context variables:
+// - `payload` (in)
+fn write_constraint_checks<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    declaration_name: &Identifier,
+    field: &schema::Field,
+) -> Result<(), fmt::Error> {
+    let constraints = schema::constraints(&field.attributes);
+    let type_variant = encoding_type(&field.r#type.variant);
+
+    // 64-bit integers are represented as `bigint`s. An `F32` is encoded as the nearest 32-bit float,
+    // so both sides of the comparison are rounded to match the checks in the generated Rust code,
+    // which compare 32-bit floats with bounds which are rounded to 32 bits as well.
+    let bound = |bound: &schema::Literal| match (type_variant, bound) {
+        (
+            schema::TypeVariant::S64 | schema::TypeVariant::U64,
+            schema::Literal::Integer(integer),
+        ) => {
+            format!("{integer}n")
+        }
+        (schema::TypeVariant::F32, schema::Literal::Float(float)) => {
+            format!("Math.fround({float:?})")
+        }
+        (schema::TypeVariant::F32, _) => format!("Math.fround({bound})"),
+        (_, schema::Literal::Float(float)) => format!("{float:?}"),
+        (_, _) => bound.to_string(),
+    };
+    let value = if let schema::TypeVariant::F32 = type_variant {
+        "Math.fround(payload)"
+    } else {
+        "payload"
+    };
+    let length = match type_variant {
+        schema::TypeVariant::Bytes(_) => "payload.byteLength",
+        schema::TypeVariant::Map(_, _) => "payload.size",
+        schema::TypeVariant::String => "Array.from(payload).length", // Count code points
+        _ => "payload.length",
+    };
+
+    // The negated comparisons ensure that NaN doesn't satisfy any bounds.
+    let mut checks = vec![];
+    if let Some(min) = &constraints.min {
+        checks.push((
+            format!("!({value} >= {})", bound(min)),
+            format!("be at least {min}"),
+        ));
+    }
+    if let Some(max) = &constraints.max {
+        checks.push((
+            format!("!({value} <= {})", bound(max)),
+            format!("be at most {max}"),
+        ));
+    }
+    // Fixed lengths are deliberately left out of the generated types, so they're checked here.
+    if let Some(fixed_length) = fixed_length(type_variant) {
+        checks.push((
+            format!("{length} !== {fixed_length}"),
+            format!("have a length of exactly {fixed_length}"),
+        ));
+    }
+    match constraints.min_length {
+        None | Some(0) => {}
+        Some(min_length) => {
+            checks.push((
+                format!("{length} < {min_length}"),
+                format!("have a length of at least {min_length}"),
+            ));
+        }
+    }
+    if let Some(max_length) = constraints.max_length {
+        checks.push((
+            format!("{length} > {max_length}"),
+            format!("have a length of at most {max_length}"),
+        ));
+    }
+
+    for (condition, requirement) in checks {
+        write_indentation(buffer, indentation)?;
+        writeln!(buffer, "if ({condition}) {{")?;
+        write_indentation(buffer, indentation + 1)?;
+        writeln!(
+            buffer,
+            "throw new Error('{}');",
+            schema::constraint_violation(declaration_name, &field.name, &requirement),
+        )?;
+        write_indentation(buffer, indentation)?;
+        writeln!(buffer, "}}")?;
+    }
+
+    Ok(())
+}
+
+// Write the logic to validate the structs and choices within a value.
+//
+// Context variables:
+// - `payload` (in)
+fn write_validation_invocation<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    imports: &BTreeMap<Identifier, schema::Namespace>,
+    namespace: &schema::Namespace,
+    type_variant: &schema::TypeVariant,
+) -> Result<(), fmt::Error> {
+    if !contains_struct_or_choice(type_variant) {
+        return Ok(());
+    }
+
+    match type_variant {
+        schema::TypeVariant::Array(inner_type, _) | schema::TypeVariant::Map(_, inner_type) => {
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "{{")?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "const oldPayload = payload;")?;
+            write_indentation(buffer, indentation + 1)?;
+            if let schema::TypeVariant::Array(_, _) = type_variant {
+                writeln!(buffer, "for (const payload of oldPayload) {{")?;
+            } else {
+                writeln!(buffer, "for (const payload of oldPayload.values()) {{")?;
+            }
+            write_validation_invocation(
+                buffer,
+                indentation + 2,
+                imports,
+                namespace,
+                &inner_type.variant,
+            )?;
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "}}")?;
+            write_indentation(buffer, indentation)?;
+            writeln!(buffer, "}}")?;
+        }
+        schema::TypeVariant::Custom(import, name, _) => {
+            write_indentation(buffer, indentation)?;
+            write_custom_type(buffer, imports, namespace, import.as_ref(), name, None)?;
+            writeln!(buffer, ".validateUnsafe(payload);")?;
+        }
+        schema::TypeVariant::Newtype(_, _, underlying_type) => {
+            write_validation_invocation(
+                buffer,
+                indentation,
+                imports,
+                namespace,
+                &underlying_type.variant,
+            )?;
+        }
+        schema::TypeVariant::Bool
+        | schema::TypeVariant::Bytes(_)
+        | schema::TypeVariant::F32
+        | schema::TypeVariant::F64
+        | schema::TypeVariant::S32
+        | schema::TypeVariant::S64
+        | schema::TypeVariant::String
+        | schema::TypeVariant::U32
+        | schema::TypeVariant::U64
+        | schema::TypeVariant::U8
+        | schema::TypeVariant::Unit => {
+            // Impossible since these types don't contain structs or choices.
+            panic!()
+        }
+    }
+
+    Ok(())
+}
+
+// Write the functions which check that a message satisfies the constraints of its fields and those
+// of any messages within it [ref:constraints]. Messages that are deserialized have already been
+// checked, but messages that are constructed directly haven't.
+#[allow(clippy::too_many_lines)]
+fn write_validate_functions<T: Write>(
+    buffer: &mut T,
+    indentation: usize,
+    imports: &BTreeMap<Identifier, schema::Namespace>,
+    namespace: &schema::Namespace,
+    declaration: &schema::Declaration,
+) -> Result<(), fmt::Error> {
+    let needs_validation = |field: &schema::Field| {
+        !schema::constraints(&field.attributes).is_empty()
+            || fixed_length(encoding_type(&field.r#type.variant)).is_some()
+            || contains_struct_or_choice(&field.r#type.variant)
+    };
+
+    write_indentation(buffer, indentation)?;
+    write!(buffer, "export function validate(message: ")?;
+    write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
+    writeln!(buffer, "): Error | undefined {{")?;
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(buffer, "try {{")?;
+    write_indentation(buffer, indentation + 2)?;
+    writeln!(buffer, "validateUnsafe(message);")?;
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(buffer, "}} catch (e) {{")?;
+    write_indentation(buffer, indentation + 2)?;
+    writeln!(buffer, "return e as Error;")?;
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(buffer, "}}")?;
+    writeln!(buffer)?;
+    write_indentation(buffer, indentation + 1)?;
+    writeln!(buffer, "return undefined;")?;
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "}}")?;
+
+    writeln!(buffer)?;
+
+    // This function is "unsafe" in the sense that it will throw an error to signify a violation.
+    write_indentation(buffer, indentation)?;
+    write!(buffer, "export function validateUnsafe(message: ")?;
+    write_identifier(buffer, &declaration.name, Pascal, Some(Out))?;
+    writeln!(buffer, "): void {{")?;
+    match declaration.variant {
+        schema::DeclarationVariant::Struct => {
+            for field in &declaration.fields {
+                if !needs_validation(field) {
+                    continue;
+                }
+
+                write_indentation(buffer, indentation + 1)?;
+                writeln!(buffer, "{{")?;
+                write_indentation(buffer, indentation + 2)?;
+                write!(buffer, "const payload = message.")?;
+                write_identifier(buffer, &field.name, Camel, None)?;
+                writeln!(buffer, ";")?;
+                let conditional_indentation = match field.rule {
+                    schema::Rule::Asymmetric | schema::Rule::Required => indentation + 2,
+                    schema::Rule::Optional => {
+                        write_indentation(buffer, indentation + 2)?;
+                        writeln!(buffer, "if (payload !== undefined) {{")?;
+                        indentation + 3
+                    }
+                };
+                write_constraint_checks(buffer, conditional_indentation, &declaration.name, field)?;
+                write_validation_invocation(
+                    buffer,
+                    conditional_indentation,
+                    imports,
+                    namespace,
+                    &field.r#type.variant,
+                )?;
+                match field.rule {
+                    schema::Rule::Asymmetric | schema::Rule::Required => {}
+                    schema::Rule::Optional => {
+                        write_indentation(buffer, indentation + 2)?;
+                        writeln!(buffer, "}}")?;
+                    }
+                }
+                write_indentation(buffer, indentation + 1)?;
+                writeln!(buffer, "}}")?;
+            }
+        }
+        schema::DeclarationVariant::Choice => {
+            for field in &declaration.fields {
+                write_indentation(buffer, indentation + 1)?;
+                write!(buffer, "if ('")?;
+                write_identifier(buffer, &field.name, Camel, None)?;
+                writeln!(buffer, "' in message) {{")?;
+                if needs_validation(field) {
+                    write_indentation(buffer, indentation + 2)?;
+                    write!(buffer, "const payload = message.")?;
+                    write_identifier(buffer, &field.name, Camel, None)?;
+                    writeln!(buffer, ";")?;
+                    write_constraint_checks(buffer, indentation + 2, &declaration.name, field)?;
+                    write_validation_invocation(
+                        buffer,
+                        indentation + 2,
+                        imports,
+                        namespace,
+                        &field.r#type.variant,
+                    )?;
+                }
+                match field.rule {
+                    schema::Rule::Asymmetric | schema::Rule::Optional => {
+                        write_indentation(buffer, indentation + 2)?;
+                        writeln!(buffer, "validateUnsafe(message.$fallback);")?;
+                    }
+                    schema::Rule::Required => {}
+                }
+                write_indentation(buffer, indentation + 2)?;
+                writeln!(buffer, "return;")?;
+                write_indentation(buffer, indentation + 1)?;
+                writeln!(buffer, "}}")?;
+            }
+            write_indentation(buffer, indentation + 1)?;
+            writeln!(buffer, "unreachable(message);")?;
+        }
+        schema::DeclarationVariant::Newtype(_) => {
+            // Impossible since only structs and choices have `validate` functions.
+            panic!()
+        }
+    }
+    write_indentation(buffer, indentation)?;
+    writeln!(buffer, "}}")
+}
+
+// Determine the 64-bit integer type which has the same encoding as a narrower integer type
+// [ref:narrow_integer_encoding].
+fn wide_integer_type(type_variant: &schema::TypeVariant) -> &'static schema::TypeVariant {
+    if matches!(type_variant, schema::TypeVariant::S32) {
+        &schema::TypeVariant::S64
+    } else {
+        &schema::TypeVariant::U64
+    }
+}
+
 // Determine whether a type is encoded as a varint.
 fn integer_encoded(r#type: &schema::Type) -> bool {
     match &r#type.variant {
-        schema::TypeVariant::Bool | schema::TypeVariant::S64 | schema::TypeVariant::U64 => true,
-        schema::TypeVariant::Array(_)
-        | schema::TypeVariant::Bytes
-        | schema::TypeVariant::Custom(_, _)
+        schema::TypeVariant::Bool
+        | schema::TypeVariant::S32
+        | schema::TypeVariant::S64
+        | schema::TypeVariant::U32
+        | schema::TypeVariant::U64
+        | schema::TypeVariant::U8 => true,
+        schema::TypeVariant::Newtype(_, _, underlying_type) => integer_encoded(underlying_type),
+        schema::TypeVariant::Array(_, _)
+        | schema::TypeVariant::Bytes(_)
+        | schema::TypeVariant::Custom(_, _, _)
+        | schema::TypeVariant::F32
         | schema::TypeVariant::F64
+        | schema::TypeVariant::Map(_, _)
         | schema::TypeVariant::String
         | schema::TypeVariant::Unit => false,
     }
@@ -2541,7 +4152,11 @@ fn integer_encoded(r#type: &schema::Type) -> bool {
 #[cfg(test)]
 mod tests {
     use {
-        crate::{generate_typescript::generate, schema_loader::load_schemas, validator::validate},
+        crate::{
+            generate_typescript::{self, generate, generate_with_options, Options},
+            schema_loader::{load_schemas, load_schemas_from, InMemory},
+            validator::validate,
+        },
         std::{fs::read_to_string, path::Path},
     };
 
@@ -2551,8 +4166,68 @@ mod tests {
         validate(&schemas).unwrap();
 
         assert_eq!(
-            generate("0.0.0", &schemas),
+            generate("0.0.0", &schemas).unwrap(),
             read_to_string("test_data/types.ts").unwrap(),
         );
     }
+    #[test]
+    fn generate_builders() {
+        let mut provider = InMemory::new();
+        provider.insert(
+            "main.t",
+            "struct foo {\n  x: String = 0\n  asymmetric y: U64 = 1\n  optional z: Bool = 2\n}\n",
+        );
+
+        let schemas = load_schemas_from(&provider, Path::new("main.t")).unwrap();
+        validate(&schemas).unwrap();
+
+        assert!(!generate("0.0.0", &schemas).unwrap().contains("Builder"));
+
+        let code = generate_with_options("0.0.0", &schemas, &Options { builders: true }).unwrap();
+        assert!(code.contains(
+            "    export function builder(): Builder<'x' | 'y'> {\n      \
+                return new Builder<'x' | 'y'>({});\n    }",
+        ));
+        assert!(code.contains("    export class Builder<Unset extends string> {"));
+        assert!(code.contains(
+            "      x(value: string): Builder<Exclude<Unset, 'x'>> {\n        \
+                return new Builder<Exclude<Unset, 'x'>>({ ...this.$fields, x: value });",
+        ));
+        assert!(code.contains("      y(value: bigint): Builder<Exclude<Unset, 'y'>> {"));
+        assert!(code.contains("      z(value: boolean): Builder<Unset> {"));
+        assert!(code.contains(
+            "      build(this: Builder<never>): FooOut {\n        \
+                const fields = this.$fields as FooOut;\n        \
+                return {\n          x: fields.x,\n          y: fields.y,\n          \
+                z: fields.z,\n        };",
+        ));
+    }
+
+    #[test]
+    fn validate_builders() {
+        let mut provider = InMemory::new();
+        provider.insert(
+            "main.t",
+            "struct foo {\n  @typescript.rename(build)\n  x: String = 0\n}\n\nstruct bar {\n  \
+                @typescript.rename(y)\n  build: String = 0\n}\n",
+        );
+
+        let schemas = load_schemas_from(&provider, Path::new("main.t")).unwrap();
+        validate(&schemas).unwrap();
+
+        generate_typescript::validate(&schemas, &Options::default()).unwrap();
+
+        let errors =
+            generate_typescript::validate(&schemas, &Options { builders: true }).unwrap_err();
+        assert_eq!(
+            errors
+                .into_iter()
+                .map(|error| error.description)
+                .collect::<Vec<_>>(),
+            vec![
+                "The builder for `foo` can't have a method for field `build`, since that's the \
+                    name of the method which builds the message.",
+            ],
+        );
+    }
 }
@@ -69,16 +69,19 @@ impl CodeStr for Identifier {
 
 impl Identifier {
     // This function returns the original identifier as provided by the user.
+    #[must_use]
     pub fn original(&self) -> &str {
         &self.original
     }
 
     // This function returns a `snake_case` version of an identifier.
+    #[must_use]
     pub fn snake_case(&self) -> String {
         self.snake_case.clone()
     }
 
     // This function returns a `camelCase` version of an identifier.
+    #[must_use]
     pub fn camel_case(&self) -> String {
         let words = split_words(&self.original);
         let mut words_iter = words.iter();
@@ -105,6 +108,7 @@ impl Identifier {
     }
 
     // This function returns a `PascalCase` version of an identifier.
+    #[must_use]
     pub fn pascal_case(&self) -> String {
         split_words(&self.original)
             .iter()
@@ -122,18 +126,46 @@ impl Identifier {
             .collect::<String>()
     }
 
+    // This function returns a `SCREAMING_SNAKE_CASE` version of an identifier.
+    #[must_use]
+    pub fn screaming_snake_case(&self) -> String {
+        self.snake_case.to_uppercase()
+    }
+
     // This function concatenates two identifiers.
+    #[must_use]
     pub fn join(&self, other: &Identifier) -> Identifier {
         AsRef::<str>::as_ref(&format!("{}_{}", self.original, other.original)).into()
     }
+
+    // This function forms the qualified name of a declaration nested within another (e.g.,
+    // `Device.IpAddress`). Dots are word delimiters, so the result is equal to the concatenation of
+    // the two identifiers, and that's how the code generators render it (e.g., `DeviceIpAddress`).
+    #[must_use]
+    pub fn qualify(&self, other: &Identifier) -> Identifier {
+        AsRef::<str>::as_ref(&format!("{}.{}", self.original, other.original)).into()
+    }
+
+    // This function returns a `PascalCase` version of an identifier in which the components of a
+    // qualified name remain separated by dots (e.g., `Device.IpAddress`).
+    #[must_use]
+    pub fn qualified_pascal_case(&self) -> String {
+        self.original
+            .split('.')
+            .map(|component| Identifier::from(component).pascal_case())
+            .collect::<Vec<_>>()
+            .join(".")
+    }
 }
 
-// This function splits a name into words using underscore delimiters and word case.
+// This function splits a name into words using underscore, hyphen, and dot delimiters and word
+// case. Names without any lowercase letters (e.g., `MAX_SIZE`) are only split at the delimiters.
 fn split_words(name: &str) -> Vec<String> {
     let mut snaked = String::new();
+    let has_lowercase = name.chars().any(char::is_lowercase);
 
     for c in name.chars() {
-        if c.is_uppercase() {
+        if has_lowercase && c.is_uppercase() {
             snaked.push('_');
         }
         snaked.push(c);
@@ -141,7 +173,7 @@ fn split_words(name: &str) -> Vec<String> {
 
     snaked
         .split('_')
-        .flat_map(|word| word.split('-'))
+        .flat_map(|word| word.split(['-', '.']))
         .filter_map(|word| {
             if word.is_empty() {
                 None
@@ -434,6 +466,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn from_screaming_snake_case() {
+        assert_eq!(
+            Identifier::from("HELLO_WORLD"),
+            Identifier {
+                original: "HELLO_WORLD".to_owned(),
+                snake_case: "hello_world".to_owned(),
+            },
+        );
+    }
+
     #[test]
     fn code_str_empty() {
         assert_eq!(
@@ -701,4 +744,54 @@ mod tests {
             "HelloWorld".to_owned(),
         );
     }
+
+    #[test]
+    fn screaming_snake_case_empty() {
+        assert_eq!(Identifier::from("").screaming_snake_case(), String::new());
+    }
+
+    #[test]
+    fn screaming_snake_case_snake_case() {
+        assert_eq!(
+            Identifier::from("hello_world").screaming_snake_case(),
+            "HELLO_WORLD".to_owned(),
+        );
+    }
+
+    #[test]
+    fn screaming_snake_case_camel_case() {
+        assert_eq!(
+            Identifier::from("helloWorld").screaming_snake_case(),
+            "HELLO_WORLD".to_owned(),
+        );
+    }
+
+    #[test]
+    fn screaming_snake_case_pascal_case() {
+        assert_eq!(
+            Identifier::from("HelloWorld").screaming_snake_case(),
+            "HELLO_WORLD".to_owned(),
+        );
+    }
+
+    #[test]
+    fn screaming_snake_case_screaming_snake_case() {
+        assert_eq!(
+            Identifier::from("HELLO_WORLD").screaming_snake_case(),
+            "HELLO_WORLD".to_owned(),
+        );
+    }
+
+    #[test]
+    fn qualify() {
+        let qualified_name = Identifier::from("Device").qualify(&"ipAddress".into());
+
+        assert_eq!(qualified_name.original(), "Device.ipAddress");
+        assert_eq!(qualified_name, Identifier::from("DeviceIpAddress"));
+        assert_eq!(qualified_name.pascal_case(), "DeviceIpAddress".to_owned());
+        assert_eq!(
+            qualified_name.qualified_pascal_case(),
+            "Device.IpAddress".to_owned(),
+        );
+    }
 }
@@ -0,0 +1,692 @@
+use {
+    crate::{
+        error::{listing, throw, Error, SourceRange},
+        format::CodeStr,
+    },
+    std::{
+        fmt::{self, Display, Formatter, Write},
+        path::Path,
+    },
+};
+
+// A JSON value along with its location in the source document
+#[derive(Clone, Debug)]
+pub struct Value {
+    pub source_range: SourceRange,
+    pub variant: Variant,
+}
+
+#[derive(Clone, Debug)]
+pub enum Variant {
+    Null,
+    Bool(bool),
+    Number(String), // The literal as it appears in the source document
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<Member>),
+}
+
+// A key-value pair of an object. The members of an object are kept in the order they appear in the
+// source document.
+#[derive(Clone, Debug)]
+pub struct Member {
+    pub key_source_range: SourceRange,
+    pub key: String,
+    pub value: Value,
+}
+
+impl Value {
+    // Look up a member of an object by key.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        if let Variant::Object(members) = &self.variant {
+            members
+                .iter()
+                .find(|member| member.key == key)
+                .map(|member| &member.value)
+        } else {
+            None
+        }
+    }
+
+    // Return the contents of a string.
+    #[must_use]
+    pub fn as_str(&self) -> Option<&str> {
+        if let Variant::String(string) = &self.variant {
+            Some(string)
+        } else {
+            None
+        }
+    }
+
+    // Return the value of a number which is a non-negative integer that fits in a `usize`.
+    #[must_use]
+    pub fn as_usize(&self) -> Option<usize> {
+        if let Variant::Number(literal) = &self.variant {
+            literal.parse().ok()
+        } else {
+            None
+        }
+    }
+
+    // Return the elements of an array.
+    #[must_use]
+    pub fn as_array(&self) -> Option<&[Value]> {
+        if let Variant::Array(elements) = &self.variant {
+            Some(elements)
+        } else {
+            None
+        }
+    }
+}
+
+// Values are rendered as compact JSON.
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match &self.variant {
+            Variant::Null => write!(f, "null"),
+            Variant::Bool(value) => write!(f, "{value}"),
+            Variant::Number(literal) => write!(f, "{literal}"),
+            Variant::String(string) => write!(f, "{}", quote(string)),
+            Variant::Array(elements) => {
+                write!(f, "[")?;
+                for (i, element) in elements.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{element}")?;
+                }
+                write!(f, "]")
+            }
+            Variant::Object(members) => {
+                write!(f, "{{")?;
+                for (i, member) in members.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}:{}", quote(&member.key), member.value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+// Render a string as a JSON string literal.
+#[must_use]
+pub fn quote(string: &str) -> String {
+    let mut result = String::with_capacity(string.len() + 2);
+    result.push('"');
+
+    for c in string.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            '\u{0}'..='\u{1f}' => {
+                // The `unwrap` is safe because the `std::fmt::Write` impl for `String` is
+                // infallible.
+                write!(result, "\\u{:04x}", u32::from(c)).unwrap();
+            }
+            _ => result.push(c),
+        }
+    }
+
+    result.push('"');
+    result
+}
+
+// This struct tracks the position of the parser within the document.
+struct Parser<'a> {
+    path: &'a Path,
+    contents: &'a str,
+    position: usize,
+}
+
+// Parse a JSON document.
+pub fn parse(path: &Path, contents: &str) -> Result<Value, Error> {
+    let mut parser = Parser {
+        path,
+        contents,
+        position: 0,
+    };
+
+    let value = parser.parse_value()?;
+
+    parser.skip_whitespace();
+    if parser.position < contents.len() {
+        return Err(parser.error(
+            "Unexpected text after the end of the document.",
+            SourceRange {
+                start: parser.position,
+                end: contents.len(),
+            },
+        ));
+    }
+
+    Ok(value)
+}
+
+impl Parser<'_> {
+    // Construct an error pointing to the given range of the document.
+    fn error(&self, message: &str, source_range: SourceRange) -> Error {
+        throw::<Error>(
+            message,
+            Some(self.path),
+            Some(&listing(self.contents, source_range)),
+            None,
+        )
+    }
+
+    // Return the next code point without consuming it.
+    fn peek(&self) -> Option<char> {
+        self.contents[self.position..].chars().next()
+    }
+
+    // Consume the next code point.
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    // Consume the given code point, or fail if the next code point is different.
+    fn expect(&mut self, expected: char) -> Result<(), Error> {
+        self.skip_whitespace();
+
+        if self.peek() == Some(expected) {
+            self.next();
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("{}", expected.to_string().code_str())))
+        }
+    }
+
+    // Construct an error for an unexpected code point (or the end of the document).
+    fn unexpected(&self, expectation: &str) -> Error {
+        let (description, end) = self.peek().map_or_else(
+            || ("the end of the document".to_owned(), self.position),
+            |c| {
+                (
+                    c.to_string().code_str().to_string(),
+                    self.position + c.len_utf8(),
+                )
+            },
+        );
+
+        self.error(
+            &format!("Expected {expectation}, but found {description}."),
+            SourceRange {
+                start: self.position,
+                end,
+            },
+        )
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(' ' | '\t' | '\n' | '\r') = self.peek() {
+            self.next();
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Value, Error> {
+        self.skip_whitespace();
+
+        let start = self.position;
+
+        let variant = match self.peek() {
+            Some('{') => self.parse_object()?,
+            Some('[') => self.parse_array()?,
+            Some('"') => Variant::String(self.parse_string()?),
+            Some('-' | '0'..='9') => self.parse_number()?,
+            Some('a'..='z') => self.parse_literal()?,
+            _ => return Err(self.unexpected("a value")),
+        };
+
+        Ok(Value {
+            source_range: SourceRange {
+                start,
+                end: self.position,
+            },
+            variant,
+        })
+    }
+
+    fn parse_object(&mut self) -> Result<Variant, Error> {
+        self.expect('{')?;
+
+        let mut members = vec![];
+
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.next();
+            return Ok(Variant::Object(members));
+        }
+
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('"') {
+                return Err(self.unexpected("a key"));
+            }
+
+            let key_start = self.position;
+            let key = self.parse_string()?;
+            let key_source_range = SourceRange {
+                start: key_start,
+                end: self.position,
+            };
+
+            self.expect(':')?;
+
+            let value = self.parse_value()?;
+
+            members.push(Member {
+                key_source_range,
+                key,
+                value,
+            });
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => {
+                    self.next();
+                }
+                Some('}') => {
+                    self.next();
+                    return Ok(Variant::Object(members));
+                }
+                _ => {
+                    return Err(self.unexpected(&format!(
+                        "{} or {}",
+                        ",".code_str(),
+                        "}".code_str()
+                    )))
+                }
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Variant, Error> {
+        self.expect('[')?;
+
+        let mut elements = vec![];
+
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.next();
+            return Ok(Variant::Array(elements));
+        }
+
+        loop {
+            elements.push(self.parse_value()?);
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => {
+                    self.next();
+                }
+                Some(']') => {
+                    self.next();
+                    return Ok(Variant::Array(elements));
+                }
+                _ => {
+                    return Err(self.unexpected(&format!(
+                        "{} or {}",
+                        ",".code_str(),
+                        "]".code_str()
+                    )))
+                }
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, Error> {
+        let start = self.position;
+        self.expect('"')?;
+
+        let mut string = String::new();
+
+        loop {
+            match self.next() {
+                None => {
+                    return Err(self.error(
+                        "This string is missing a closing quotation mark.",
+                        SourceRange {
+                            start,
+                            end: self.position,
+                        },
+                    ));
+                }
+                Some('"') => return Ok(string),
+                Some('\\') => {
+                    let escape_start = self.position - 1;
+
+                    match self.next() {
+                        Some('"') => string.push('"'),
+                        Some('\\') => string.push('\\'),
+                        Some('/') => string.push('/'),
+                        Some('b') => string.push('\u{8}'),
+                        Some('f') => string.push('\u{c}'),
+                        Some('n') => string.push('\n'),
+                        Some('r') => string.push('\r'),
+                        Some('t') => string.push('\t'),
+                        Some('u') => {
+                            let code_unit = self.parse_code_unit(escape_start)?;
+
+                            // Code units outside the surrogate range are code points on their own.
+                            // Otherwise, a high surrogate must be followed by a low surrogate.
+                            let code_point = if (0xd800..0xdc00).contains(&code_unit)
+                                && self.contents[self.position..].starts_with("\\u")
+                            {
+                                let low_start = self.position;
+                                self.position += 2;
+                                let low = self.parse_code_unit(low_start)?;
+
+                                if (0xdc00..0xe000).contains(&low) {
+                                    0x1_0000 + ((code_unit - 0xd800) << 10_u32) + (low - 0xdc00)
+                                } else {
+                                    code_unit
+                                }
+                            } else {
+                                code_unit
+                            };
+
+                            string.push(char::from_u32(code_point).ok_or_else(|| {
+                                self.error(
+                                    "This escape sequence isn't a valid Unicode scalar value.",
+                                    SourceRange {
+                                        start: escape_start,
+                                        end: self.position,
+                                    },
+                                )
+                            })?);
+                        }
+                        _ => {
+                            return Err(self.error(
+                                "Invalid escape sequence.",
+                                SourceRange {
+                                    start: escape_start,
+                                    end: self.position,
+                                },
+                            ));
+                        }
+                    }
+                }
+                Some(c) if c < ' ' => {
+                    return Err(self.error(
+                        "Control characters must be escaped in strings.",
+                        SourceRange {
+                            start: self.position - 1,
+                            end: self.position,
+                        },
+                    ));
+                }
+                Some(c) => string.push(c),
+            }
+        }
+    }
+
+    // Parse the four hexadecimal digits of a `\u` escape sequence.
+    fn parse_code_unit(&mut self, escape_start: usize) -> Result<u32, Error> {
+        let digits = self.contents[self.position..].get(0..4).and_then(|digits| {
+            u32::from_str_radix(digits, 16)
+                .ok()
+                .map(|value| (digits, value))
+        });
+
+        if let Some((digits, value)) = digits {
+            if digits.chars().all(|c| c.is_ascii_hexdigit()) {
+                self.position += 4;
+                return Ok(value);
+            }
+        }
+
+        Err(self.error(
+            "A Unicode escape sequence must have exactly four hexadecimal digits.",
+            SourceRange {
+                start: escape_start,
+                end: self.position,
+            },
+        ))
+    }
+
+    fn parse_number(&mut self) -> Result<Variant, Error> {
+        let start = self.position;
+
+        // Consume everything that could plausibly be part of the number, then check its syntax.
+        while let Some('-' | '+' | '.' | '0'..='9' | 'e' | 'E') = self.peek() {
+            self.next();
+        }
+
+        let literal = &self.contents[start..self.position];
+
+        if is_valid_number(literal) {
+            Ok(Variant::Number(literal.to_owned()))
+        } else {
+            Err(self.error(
+                &format!("{} is not a valid number.", literal.code_str()),
+                SourceRange {
+                    start,
+                    end: self.position,
+                },
+            ))
+        }
+    }
+
+    fn parse_literal(&mut self) -> Result<Variant, Error> {
+        let start = self.position;
+
+        while let Some('a'..='z') = self.peek() {
+            self.next();
+        }
+
+        match &self.contents[start..self.position] {
+            "null" => Ok(Variant::Null),
+            "true" => Ok(Variant::Bool(true)),
+            "false" => Ok(Variant::Bool(false)),
+            literal => Err(self.error(
+                &format!("Unknown literal {}.", literal.code_str()),
+                SourceRange {
+                    start,
+                    end: self.position,
+                },
+            )),
+        }
+    }
+}
+
+// Check a number literal against the JSON grammar:
+// `-?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][+-]?[0-9]+)?`
+fn is_valid_number(literal: &str) -> bool {
+    // Consume a run of digits and return the number of digits consumed.
+    fn digits(chars: &mut std::iter::Peekable<std::str::Chars>) -> usize {
+        let mut count = 0_usize;
+
+        while chars.peek().is_some_and(char::is_ascii_digit) {
+            chars.next();
+            count += 1;
+        }
+
+        count
+    }
+
+    let mut chars = literal.chars().peekable();
+
+    if chars.peek() == Some(&'-') {
+        chars.next();
+    }
+
+    if chars.peek() == Some(&'0') {
+        chars.next();
+    } else if digits(&mut chars) == 0 {
+        return false;
+    }
+
+    if chars.peek() == Some(&'.') {
+        chars.next();
+
+        if digits(&mut chars) == 0 {
+            return false;
+        }
+    }
+
+    if let Some('e' | 'E') = chars.peek() {
+        chars.next();
+
+        if let Some('+' | '-') = chars.peek() {
+            chars.next();
+        }
+
+        if digits(&mut chars) == 0 {
+            return false;
+        }
+    }
+
+    chars.next().is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::{
+            assert_fails, assert_same,
+            error::SourceRange,
+            json::{parse, quote, Member, Value, Variant},
+        },
+        std::{fmt::Write, path::Path},
+    };
+
+    #[test]
+    fn parse_literals() {
+        assert_same!(
+            parse(Path::new("foo.json"), " null ").unwrap().variant,
+            Variant::Null,
+        );
+        assert_same!(
+            parse(Path::new("foo.json"), "true").unwrap().variant,
+            Variant::Bool(true),
+        );
+        assert_same!(
+            parse(Path::new("foo.json"), "false").unwrap().variant,
+            Variant::Bool(false),
+        );
+    }
+
+    #[test]
+    fn parse_numbers() {
+        for literal in ["0", "-0", "42", "-3.25", "1e10", "6.02E+23", "1.5e-7"] {
+            assert_same!(
+                parse(Path::new("foo.json"), literal).unwrap().variant,
+                Variant::Number(literal.to_owned()),
+            );
+        }
+    }
+
+    #[test]
+    fn parse_invalid_numbers() {
+        for literal in ["01", "-", "1.", ".5", "1e", "1-2", "+1"] {
+            assert!(parse(Path::new("foo.json"), literal).is_err());
+        }
+    }
+
+    #[test]
+    fn parse_string_escapes() {
+        assert_same!(
+            parse(Path::new("foo.json"), r#""a\"b\\c\/d\n\té😀""#,)
+                .unwrap()
+                .variant,
+            Variant::String("a\"b\\c/d\n\t\u{e9}\u{1f600}".to_owned()),
+        );
+    }
+
+    #[test]
+    fn parse_nested() {
+        assert_same!(
+            parse(Path::new("foo.json"), r#"{"x": [1, {}], "y": "z"}"#).unwrap(),
+            Value {
+                source_range: SourceRange { start: 0, end: 24 },
+                variant: Variant::Object(vec![
+                    Member {
+                        key_source_range: SourceRange { start: 1, end: 4 },
+                        key: "x".to_owned(),
+                        value: Value {
+                            source_range: SourceRange { start: 6, end: 13 },
+                            variant: Variant::Array(vec![
+                                Value {
+                                    source_range: SourceRange { start: 7, end: 8 },
+                                    variant: Variant::Number("1".to_owned()),
+                                },
+                                Value {
+                                    source_range: SourceRange { start: 10, end: 12 },
+                                    variant: Variant::Object(vec![]),
+                                },
+                            ]),
+                        },
+                    },
+                    Member {
+                        key_source_range: SourceRange { start: 15, end: 18 },
+                        key: "y".to_owned(),
+                        value: Value {
+                            source_range: SourceRange { start: 20, end: 23 },
+                            variant: Variant::String("z".to_owned()),
+                        },
+                    },
+                ]),
+            },
+        );
+    }
+
+    #[test]
+    fn parse_unterminated_string() {
+        assert_fails!(
+            parse(Path::new("foo.json"), "\"abc").map_err(|error| vec![error]),
+            "This string is missing a closing quotation mark.",
+        );
+    }
+
+    #[test]
+    fn parse_missing_comma() {
+        assert_fails!(
+            parse(Path::new("foo.json"), "[1 2]").map_err(|error| vec![error]),
+            "Expected `,` or `]`, but found `2`.",
+        );
+    }
+
+    #[test]
+    fn parse_trailing_text() {
+        assert_fails!(
+            parse(Path::new("foo.json"), "{} {}").map_err(|error| vec![error]),
+            "Unexpected text after the end of the document.",
+        );
+    }
+
+    #[test]
+    fn quote_escapes() {
+        assert_eq!(quote("a\"b\\c\nd\u{1}é"), "\"a\\\"b\\\\c\\nd\\u0001é\"");
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let contents = r#"{"a":[1,-2.5e3,true,null],"b":"x\"y","c":{}}"#;
+
+        assert_eq!(
+            parse(Path::new("foo.json"), contents).unwrap().to_string(),
+            contents,
+        );
+    }
+
+    #[test]
+    fn accessors() {
+        let value = parse(Path::new("foo.json"), r#"{"a": 3, "b": "x", "c": [null]}"#).unwrap();
+
+        assert_eq!(value.get("a").and_then(Value::as_usize), Some(3));
+        assert_eq!(value.get("b").and_then(Value::as_str), Some("x"));
+        assert_eq!(
+            value.get("c").and_then(Value::as_array).map(<[_]>::len),
+            Some(1)
+        );
+        assert!(value.get("d").is_none());
+    }
+}
@@ -0,0 +1,53 @@
+// This crate contains the Typical compiler. The `typical` binary is a thin command-line interface
+// on top of it, but the same functionality is available to build scripts and other tools:
+//
+//   1. Load a schema and its transitive dependencies with `schema_loader::load_schemas`.
+//   2. Check them with `validator::validate`.
+//   3. Generate code with `generate_rust::generate` or `generate_typescript::generate`. To
+//      customize the generated code, use `generate_rust::generate_with_options` or
+//      `generate_typescript::generate_with_options` instead. These fail if the schemas can't be
+//      generated for the target language (e.g., a `@rust.derive` attribute requests a trait
+//      which can't be derived) or the options conflict. The same checks are available without
+//      generating code as `generate_rust::validate` and `generate_typescript::validate`.
+//
+// The loaded schemas are exposed as a syntax tree (see the `schema` module). Errors are returned as
+// `error::Error` values, which carry the source path and range in addition to a rendered message.
+// Messages are free of color codes unless `format::enable_colors` is called.
+//
+// The syntax tree types are non-exhaustive, since new language features add variants and fields to
+// them. The modules which are hidden from the documentation below only exist to serve the `typical`
+// binary, and they aren't part of the stable API.
+
+// This codebase documents its functions with ordinary comments rather than rustdoc sections.
+#![allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+
+#[cfg(test)]
+mod assertions;
+pub mod compatibility_checker;
+#[doc(hidden)]
+pub mod count;
+#[doc(hidden)]
+pub mod decoder;
+#[doc(hidden)]
+pub mod encoder;
+pub mod error;
+#[doc(hidden)]
+pub mod error_merger;
+pub mod format;
+pub mod generate_rust;
+pub mod generate_typescript;
+pub mod identifier;
+mod json;
+#[doc(hidden)]
+pub mod lsp;
+mod monomorphizer;
+mod parser;
+pub mod schema;
+pub mod schema_loader;
+mod token;
+mod tokenizer;
+pub mod validator;
+pub mod well_known;
+
+// The version of the compiler, which is embedded in the generated code
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
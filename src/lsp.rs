@@ -0,0 +1,1419 @@
+use {
+    crate::{
+        count::count,
+        error::{throw, Error, SourceRange},
+        identifier::Identifier,
+        json::{self, quote, Value},
+        parser::parse,
+        schema,
+        schema_loader::{load_schemas_from, normalize, SourceProvider},
+        token::{
+            self, BOOL_KEYWORD, BYTES_KEYWORD, F32_KEYWORD, F64_KEYWORD, S32_KEYWORD, S64_KEYWORD,
+            STRING_KEYWORD, U32_KEYWORD, U64_KEYWORD, U8_KEYWORD, UNIT_KEYWORD,
+        },
+        tokenizer::tokenize,
+        validator::validate,
+        VERSION,
+    },
+    std::{
+        cmp::min,
+        collections::{BTreeMap, HashMap, HashSet},
+        fmt::Write as _,
+        fs::read_to_string,
+        io::{self, BufRead, Write},
+        path::{Path, PathBuf},
+    },
+};
+
+// The JSON-RPC error code for requests with an unknown method
+const METHOD_NOT_FOUND: i64 = -32601;
+
+// LSP completion item kinds
+const COMPLETION_KIND_MODULE: usize = 9;
+const COMPLETION_KIND_ENUM: usize = 13;
+const COMPLETION_KIND_KEYWORD: usize = 14;
+const COMPLETION_KIND_STRUCT: usize = 22;
+
+// The built-in types, which are offered as completions
+const BUILT_IN_TYPES: &[&str] = &[
+    BOOL_KEYWORD,
+    BYTES_KEYWORD,
+    F32_KEYWORD,
+    F64_KEYWORD,
+    S32_KEYWORD,
+    S64_KEYWORD,
+    STRING_KEYWORD,
+    U32_KEYWORD,
+    U64_KEYWORD,
+    U8_KEYWORD,
+    UNIT_KEYWORD,
+];
+
+// This struct holds the state of the language server.
+struct Server<W: Write> {
+    writer: W,
+
+    // The contents of the open documents, which take precedence over the file system
+    documents: HashMap<PathBuf, String>,
+
+    // For each document we've diagnosed, the files we published diagnostics for
+    diagnosed: HashMap<PathBuf, HashSet<PathBuf>>,
+}
+
+// This source provider serves the open documents from memory and everything else from the file
+// system, so diagnostics reflect unsaved changes.
+struct Overlay<'a> {
+    documents: &'a HashMap<PathBuf, String>,
+}
+
+impl SourceProvider for Overlay<'_> {
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        path.canonicalize().or_else(|error| {
+            normalize(path)
+                .filter(|path| self.documents.contains_key(path))
+                .ok_or(error)
+        })
+    }
+
+    fn read(&self, path: &Path) -> io::Result<String> {
+        if let Some(contents) = normalize(path).and_then(|path| self.documents.get(&path)) {
+            return Ok(contents.clone());
+        }
+
+        if let Some(contents) = path
+            .canonicalize()
+            .ok()
+            .and_then(|path| self.documents.get(&path))
+        {
+            return Ok(contents.clone());
+        }
+
+        read_to_string(path)
+    }
+}
+
+// Run a language server which communicates with the client over the given streams until the
+// client asks it to exit or closes the input stream.
+pub fn serve<R: BufRead, W: Write>(mut reader: R, writer: W) -> Result<(), Error> {
+    let mut server = Server {
+        writer,
+        documents: HashMap::new(),
+        diagnosed: HashMap::new(),
+    };
+
+    while let Some(message) = read_message(&mut reader)? {
+        // There's no way to respond to a message we can't parse, so we skip it.
+        let Ok(message) = json::parse(Path::new("message"), &message) else {
+            continue;
+        };
+
+        if !server.handle(&message)? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+// Read a message from the client. Returns `None` at the end of the input.
+fn read_message<R: BufRead>(reader: &mut R) -> Result<Option<String>, Error> {
+    let mut content_length = None;
+
+    // Read the headers, which are terminated by an empty line.
+    loop {
+        let mut line = String::new();
+        if reader
+            .read_line(&mut line)
+            .map_err(|error| throw("Unable to read a message.", None, None, Some(error)))?
+            == 0
+        {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                content_length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let Some(content_length) = content_length else {
+        return Err(throw::<Error>(
+            "A message is missing the `Content-Length` header.",
+            None,
+            None,
+            None,
+        ));
+    };
+
+    // Read the content.
+    let mut content = vec![0; content_length];
+    reader
+        .read_exact(&mut content)
+        .map_err(|error| throw("Unable to read a message.", None, None, Some(error)))?;
+
+    String::from_utf8(content)
+        .map(Some)
+        .map_err(|error| throw("A message is not valid UTF-8.", None, None, Some(error)))
+}
+
+impl<W: Write> Server<W> {
+    // Handle a message from the client. Returns `false` if the server should exit.
+    fn handle(&mut self, message: &Value) -> Result<bool, Error> {
+        let method = message.get("method").and_then(Value::as_str);
+        let params = message.get("params");
+
+        match (method, message.get("id")) {
+            (Some("initialize"), Some(id)) => {
+                self.respond(
+                    id,
+                    &format!(
+                        "{{\"capabilities\":{{\"textDocumentSync\":1,\"hoverProvider\":true,\
+                            \"definitionProvider\":true,\"completionProvider\":\
+                            {{\"triggerCharacters\":[\".\"]}},\"documentFormattingProvider\":\
+                            true}},\"serverInfo\":{{\"name\":\"typical\",\"version\":{}}}}}",
+                        quote(VERSION),
+                    ),
+                )?;
+            }
+            (Some("shutdown"), Some(id)) => {
+                self.respond(id, "null")?;
+            }
+            (Some("exit"), _) => {
+                return Ok(false);
+            }
+            (Some("textDocument/didOpen"), None) => {
+                let document = params.and_then(|params| params.get("textDocument"));
+                if let (Some(path), Some(text)) = (
+                    document.and_then(document_path),
+                    document
+                        .and_then(|document| document.get("text"))
+                        .and_then(Value::as_str),
+                ) {
+                    self.documents.insert(path.clone(), text.to_owned());
+                    self.diagnose(&path)?;
+                }
+            }
+            (Some("textDocument/didChange"), None) => {
+                // We only support full synchronization, so the last change has the whole text.
+                if let (Some(path), Some(text)) = (
+                    params
+                        .and_then(|params| params.get("textDocument"))
+                        .and_then(document_path),
+                    params
+                        .and_then(|params| params.get("contentChanges"))
+                        .and_then(Value::as_array)
+                        .and_then(<[Value]>::last)
+                        .and_then(|change| change.get("text"))
+                        .and_then(Value::as_str),
+                ) {
+                    self.documents.insert(path.clone(), text.to_owned());
+                    self.diagnose(&path)?;
+                }
+            }
+            (Some("textDocument/didClose"), None) => {
+                if let Some(path) = params
+                    .and_then(|params| params.get("textDocument"))
+                    .and_then(document_path)
+                {
+                    self.documents.remove(&path);
+                    for file in self.diagnosed.remove(&path).unwrap_or_default() {
+                        self.publish_diagnostics(&file, &[])?;
+                    }
+                }
+            }
+            (Some("textDocument/hover"), Some(id)) => {
+                let result = params.and_then(|params| self.hover(params));
+                self.respond(id, result.as_deref().unwrap_or("null"))?;
+            }
+            (Some("textDocument/definition"), Some(id)) => {
+                let result = params.and_then(|params| self.definition(params));
+                self.respond(id, result.as_deref().unwrap_or("null"))?;
+            }
+            (Some("textDocument/completion"), Some(id)) => {
+                let result = params.and_then(|params| self.completion(params));
+                self.respond(id, result.as_deref().unwrap_or("null"))?;
+            }
+            (Some("textDocument/formatting"), Some(id)) => {
+                let result = params.and_then(|params| self.formatting(params));
+                self.respond(id, result.as_deref().unwrap_or("null"))?;
+            }
+            (Some(method), Some(id)) => {
+                self.send(&format!(
+                    "{{\"jsonrpc\":\"2.0\",\"id\":{},\"error\":{{\"code\":{},\"message\":{}}}}}",
+                    id,
+                    METHOD_NOT_FOUND,
+                    quote(&format!("Unknown method {method}.")),
+                ))?;
+            }
+            // Other notifications and responses to our own messages (we don't send any requests)
+            // are ignored.
+            _ => {}
+        }
+
+        Ok(true)
+    }
+
+    // Send a message to the client.
+    fn send(&mut self, content: &str) -> Result<(), Error> {
+        write!(
+            self.writer,
+            "Content-Length: {}\r\n\r\n{}",
+            content.len(),
+            content,
+        )
+        .and_then(|()| self.writer.flush())
+        .map_err(|error| throw("Unable to send a message.", None, None, Some(error)))
+    }
+
+    // Send the result of a request to the client.
+    fn respond(&mut self, id: &Value, result: &str) -> Result<(), Error> {
+        self.send(&format!(
+            "{{\"jsonrpc\":\"2.0\",\"id\":{id},\"result\":{result}}}",
+        ))
+    }
+
+    // Send the diagnostics for a file to the client.
+    fn publish_diagnostics(&mut self, path: &Path, diagnostics: &[String]) -> Result<(), Error> {
+        self.send(&format!(
+            "{{\"jsonrpc\":\"2.0\",\"method\":\"textDocument/publishDiagnostics\",\"params\":\
+                {{\"uri\":{},\"diagnostics\":[{}]}}}}",
+            quote(&path_to_uri(path)),
+            diagnostics.join(","),
+        ))
+    }
+
+    // Load and validate a document along with its transitive dependencies, and publish any errors
+    // as diagnostics for the files they occur in.
+    fn diagnose(&mut self, path: &Path) -> Result<(), Error> {
+        let provider = Overlay {
+            documents: &self.documents,
+        };
+
+        let errors = match load_schemas_from(&provider, path) {
+            Ok(schemas) => validate(&schemas).err().unwrap_or_default(),
+            Err(errors) => errors,
+        };
+
+        // Group the diagnostics by file. Paths in errors are relative to the directory containing
+        // the document. We always publish for the document itself to clear any old diagnostics.
+        let base_path = path.parent().unwrap_or_else(|| Path::new(""));
+        let mut diagnostics = BTreeMap::<PathBuf, Vec<String>>::new();
+        diagnostics.insert(path.to_owned(), vec![]);
+
+        for error in errors {
+            let file = error
+                .source_path
+                .as_ref()
+                .and_then(|source_path| normalize(&base_path.join(source_path)))
+                .unwrap_or_else(|| path.to_owned());
+            let contents = provider.read(&file).unwrap_or_default();
+            let source_range = error
+                .source_range
+                .unwrap_or(SourceRange { start: 0, end: 0 });
+
+            diagnostics.entry(file).or_default().push(format!(
+                "{{\"range\":{},\"severity\":1,\"source\":\"typical\",\"message\":{}}}",
+                range_json(&contents, source_range),
+                quote(&error.description),
+            ));
+        }
+
+        // Clear the diagnostics of any files which no longer have errors.
+        let files = diagnostics.keys().cloned().collect::<HashSet<_>>();
+        for file in self
+            .diagnosed
+            .insert(path.to_owned(), files)
+            .unwrap_or_default()
+        {
+            diagnostics.entry(file).or_default();
+        }
+
+        for (file, file_diagnostics) in diagnostics {
+            self.publish_diagnostics(&file, &file_diagnostics)?;
+        }
+
+        Ok(())
+    }
+
+    // Determine the document, its contents, and the offset referred to by the parameters of a
+    // request about a position in a document.
+    fn document_position(&self, params: &Value) -> Option<(PathBuf, &str, usize)> {
+        let path = params.get("textDocument").and_then(document_path)?;
+        let contents = self.documents.get(&path)?;
+        let position = params.get("position")?;
+        let offset = position_to_offset(
+            contents,
+            position.get("line").and_then(Value::as_usize)?,
+            position.get("character").and_then(Value::as_usize)?,
+        );
+
+        Some((path, contents, offset))
+    }
+
+    // Read a file, preferring the contents of the open documents.
+    fn read(&self, path: &Path) -> Option<String> {
+        Overlay {
+            documents: &self.documents,
+        }
+        .read(path)
+        .ok()
+    }
+
+    // Find the declaration a custom type refers to. Returns the path and contents of the file
+    // containing it along with the declaration itself.
+    fn resolve_declaration(
+        &self,
+        path: &Path,
+        contents: &str,
+        schema: &schema::Schema,
+        import: Option<&Identifier>,
+        name: &Identifier,
+    ) -> Option<(PathBuf, String, schema::Declaration)> {
+        let (target_path, target_contents, target_schema) = if let Some(import) = import {
+            let target_path = normalize(&path.parent()?.join(&schema.imports.get(import)?.path))?;
+            let target_contents = self.read(&target_path)?;
+            let target_schema = schema::flatten(&parse_document(&target_path, &target_contents)?);
+            (target_path, target_contents, target_schema)
+        } else {
+            (path.to_owned(), contents.to_owned(), schema.clone())
+        };
+
+        let declaration = target_schema
+            .declarations
+            .into_iter()
+            .find(|declaration| declaration.name == *name)?;
+
+        Some((target_path, target_contents, declaration))
+    }
+
+    // Describe the item under the cursor.
+    fn hover(&self, params: &Value) -> Option<String> {
+        let (path, contents, offset) = self.document_position(params)?;
+        let schema = schema::flatten(&parse_document(&path, contents)?);
+
+        let (source_range, description) = if let Some((name, import)) = schema
+            .imports
+            .iter()
+            .find(|(_, import)| contains(import.source_range, offset))
+        {
+            (
+                import.source_range,
+                format!(
+                    "```\nimport '{}' as {}\n```",
+                    import.path.to_string_lossy(),
+                    name.snake_case(),
+                ),
+            )
+        } else if let Some(constant) = schema
+            .constants
+            .iter()
+            .find(|constant| contains(constant.source_range, offset))
+        {
+            (constant.source_range, describe_constant(constant))
+        } else if let Some(service) = schema
+            .services
+            .iter()
+            .find(|service| contains(service.source_range, offset))
+        {
+            if let Some(method) = service
+                .methods
+                .iter()
+                .find(|method| contains(method.source_range, offset))
+            {
+                if let Some(r#type) =
+                    method_types(method).find_map(|r#type| find_custom_type(r#type, offset))
+                {
+                    let schema::TypeVariant::Custom(import, name, _) = &r#type.variant else {
+                        return None;
+                    };
+                    let (_, _, target) =
+                        self.resolve_declaration(&path, contents, &schema, import.as_ref(), name)?;
+
+                    (r#type.source_range, describe_declaration(&target))
+                } else {
+                    (method.source_range, describe_method(method))
+                }
+            } else {
+                (service.source_range, describe_service(service))
+            }
+        } else {
+            // Nested declarations follow their parents, so we search backward to find the
+            // innermost declaration containing the cursor [ref:nested_declarations].
+            let declaration = schema
+                .declarations
+                .iter()
+                .rev()
+                .find(|declaration| contains(declaration.source_range, offset))?;
+
+            if let Some(field) = declaration
+                .fields
+                .iter()
+                .find(|field| contains(field.source_range, offset))
+            {
+                if let Some(r#type) = find_custom_type(&field.r#type, offset)
+                    .filter(|r#type| !is_type_parameter(declaration, r#type))
+                {
+                    let schema::TypeVariant::Custom(import, name, _) = &r#type.variant else {
+                        return None;
+                    };
+                    let (_, _, target) =
+                        self.resolve_declaration(&path, contents, &schema, import.as_ref(), name)?;
+
+                    (r#type.source_range, describe_declaration(&target))
+                } else {
+                    (field.source_range, describe_field(field))
+                }
+            } else if let Some(r#type) = declaration_types(declaration)
+                .find_map(|r#type| find_custom_type(r#type, offset))
+                .filter(|r#type| !is_type_parameter(declaration, r#type))
+            {
+                let schema::TypeVariant::Custom(import, name, _) = &r#type.variant else {
+                    return None;
+                };
+                let (_, _, target) =
+                    self.resolve_declaration(&path, contents, &schema, import.as_ref(), name)?;
+
+                (r#type.source_range, describe_declaration(&target))
+            } else {
+                (declaration.source_range, describe_declaration(declaration))
+            }
+        };
+
+        Some(format!(
+            "{{\"contents\":{{\"kind\":\"markdown\",\"value\":{}}},\"range\":{}}}",
+            quote(&description),
+            range_json(contents, source_range),
+        ))
+    }
+
+    // Find the declaration of the type under the cursor, or the file of the import under the
+    // cursor.
+    fn definition(&self, params: &Value) -> Option<String> {
+        let (path, contents, offset) = self.document_position(params)?;
+        let schema = schema::flatten(&parse_document(&path, contents)?);
+
+        let (target_path, target_contents, source_range) = if let Some(import) = schema
+            .imports
+            .values()
+            .find(|import| contains(import.source_range, offset))
+        {
+            let target_path = normalize(&path.parent()?.join(&import.path))?;
+
+            (target_path, String::new(), SourceRange { start: 0, end: 0 })
+        } else {
+            let r#type = schema
+                .declarations
+                .iter()
+                .flat_map(|declaration| {
+                    declaration_types(declaration).map(move |r#type| (declaration, r#type))
+                })
+                .find_map(|(declaration, r#type)| {
+                    find_custom_type(r#type, offset)
+                        .filter(|r#type| !is_type_parameter(declaration, r#type))
+                })
+                .or_else(|| {
+                    schema
+                        .services
+                        .iter()
+                        .flat_map(|service| service.methods.iter().flat_map(method_types))
+                        .find_map(|r#type| find_custom_type(r#type, offset))
+                })?;
+            let schema::TypeVariant::Custom(import, name, _) = &r#type.variant else {
+                return None;
+            };
+            let (target_path, target_contents, declaration) =
+                self.resolve_declaration(&path, contents, &schema, import.as_ref(), name)?;
+
+            (target_path, target_contents, declaration.source_range)
+        };
+
+        Some(format!(
+            "{{\"uri\":{},\"range\":{}}}",
+            quote(&path_to_uri(&target_path)),
+            range_json(&target_contents, source_range),
+        ))
+    }
+
+    // Suggest type names and import names. After an import name and a dot, suggest the types
+    // declared in the imported file. After a type name and a dot, suggest its nested types.
+    fn completion(&self, params: &Value) -> Option<String> {
+        let (path, contents, offset) = self.document_position(params)?;
+        let (imports, declarations) = outline(&path, contents);
+
+        // Determine whether the cursor is on a qualified name like `foo.Bar`.
+        let before = &contents[..offset];
+        let before = &before[..identifier_start(before)];
+        let qualifier = before
+            .strip_suffix('.')
+            .map(|before| &before[identifier_start(before)..])
+            .filter(|qualifier| !qualifier.is_empty());
+
+        let mut items = vec![];
+
+        if let Some(import_path) = qualifier.and_then(|qualifier| imports.get(&qualifier.into())) {
+            let target_path = normalize(&path.parent()?.join(import_path))?;
+            let target_contents = self.read(&target_path)?;
+
+            for (name, kind) in outline(&target_path, &target_contents).1 {
+                items.push(completion_item(name.original(), kind));
+            }
+        } else if let Some(qualifier) = qualifier {
+            for (name, kind) in &declarations {
+                if let Some(nested_name) = name
+                    .original()
+                    .strip_prefix(qualifier)
+                    .and_then(|name| name.strip_prefix('.'))
+                {
+                    items.push(completion_item(nested_name, *kind));
+                }
+            }
+        } else {
+            for built_in_type in BUILT_IN_TYPES {
+                items.push(completion_item(built_in_type, COMPLETION_KIND_KEYWORD));
+            }
+
+            for (name, kind) in &declarations {
+                items.push(completion_item(name.original(), *kind));
+            }
+
+            for name in imports.keys() {
+                items.push(completion_item(name.original(), COMPLETION_KIND_MODULE));
+            }
+        }
+
+        Some(format!("[{}]", items.join(",")))
+    }
+
+    // Format a document in the same way as the `format` subcommand.
+    fn formatting(&self, params: &Value) -> Option<String> {
+        let path = params.get("textDocument").and_then(document_path)?;
+        let contents = self.documents.get(&path)?;
+        let formatted = parse_document(&path, contents)?.to_string();
+
+        Some(if formatted == *contents {
+            "[]".to_owned()
+        } else {
+            format!(
+                "[{{\"range\":{},\"newText\":{}}}]",
+                range_json(
+                    contents,
+                    SourceRange {
+                        start: 0,
+                        end: contents.len(),
+                    },
+                ),
+                quote(&formatted),
+            )
+        })
+    }
+}
+
+// Tokenize and parse a document, if it's syntactically valid.
+fn parse_document(path: &Path, contents: &str) -> Option<schema::Schema> {
+    let tokens = tokenize(path, contents).ok()?;
+    parse(path, contents, &tokens).ok()
+}
+
+// Extract the imports and declared types of a document. This only requires the document to be
+// tokenizable, so it works while the user is in the middle of typing. Nested types are given
+// qualified names like `Device.IpAddress` [ref:nested_declarations].
+#[allow(clippy::type_complexity)]
+fn outline(
+    path: &Path,
+    contents: &str,
+) -> (BTreeMap<Identifier, PathBuf>, Vec<(Identifier, usize)>) {
+    let mut imports = BTreeMap::new();
+    let mut declarations = vec![];
+
+    let Ok(tokens) = tokenize(path, contents) else {
+        return (imports, declarations);
+    };
+
+    let variant = |i: usize| tokens.get(i).map(|token| &token.variant);
+
+    // The names of the declarations whose bodies enclose the current token, if any. Braces which
+    // don't open a declaration body (e.g., those of a service) are tracked with `None`.
+    let mut scopes: Vec<Option<Identifier>> = vec![];
+    let mut pending = None;
+    let qualify = |scopes: &[Option<Identifier>], name: &Identifier| {
+        scopes
+            .iter()
+            .rev()
+            .find_map(Option::as_ref)
+            .map_or_else(|| name.clone(), |parent| parent.qualify(name))
+    };
+
+    for i in 0..tokens.len() {
+        match (variant(i), variant(i + 1)) {
+            (Some(token::Variant::Import), Some(token::Variant::Path(import_path))) => {
+                let name =
+                    if let (Some(token::Variant::As), Some(token::Variant::Identifier(name))) =
+                        (variant(i + 2), variant(i + 3))
+                    {
+                        Some(name.clone())
+                    } else {
+                        import_path
+                            .file_stem()
+                            .map(|file_stem| file_stem.to_string_lossy().as_ref().into())
+                    };
+
+                if let Some(name) = name {
+                    imports.insert(name, import_path.clone());
+                }
+            }
+            (Some(token::Variant::Struct), Some(token::Variant::Identifier(name))) => {
+                let name = qualify(&scopes, name);
+                declarations.push((name.clone(), COMPLETION_KIND_STRUCT));
+                pending = Some(name);
+            }
+            (Some(token::Variant::Newtype), Some(token::Variant::Identifier(name))) => {
+                declarations.push((qualify(&scopes, name), COMPLETION_KIND_STRUCT));
+            }
+            (Some(token::Variant::Choice), Some(token::Variant::Identifier(name))) => {
+                let name = qualify(&scopes, name);
+                declarations.push((name.clone(), COMPLETION_KIND_ENUM));
+                pending = Some(name);
+            }
+            (Some(token::Variant::LeftCurly), _) => {
+                scopes.push(pending.take());
+            }
+            (Some(token::Variant::RightCurly), _) => {
+                scopes.pop();
+            }
+            _ => {}
+        }
+    }
+
+    (imports, declarations)
+}
+
+// Render a completion item.
+fn completion_item(label: &str, kind: usize) -> String {
+    format!("{{\"label\":{},\"kind\":{}}}", quote(label), kind)
+}
+
+// Determine whether a custom type refers to a type parameter of the given declaration.
+fn is_type_parameter(declaration: &schema::Declaration, r#type: &schema::Type) -> bool {
+    matches!(
+        &r#type.variant,
+        schema::TypeVariant::Custom(None, name, _) if declaration.parameters.contains(name),
+    )
+}
+
+// Iterate over the types which appear in a declaration, namely the types of its fields and the
+// underlying type of a newtype.
+fn declaration_types(declaration: &schema::Declaration) -> impl Iterator<Item = &schema::Type> {
+    declaration
+        .fields
+        .iter()
+        .map(|field| &field.r#type)
+        .chain(match &declaration.variant {
+            schema::DeclarationVariant::Newtype(underlying_type) => Some(underlying_type),
+            schema::DeclarationVariant::Struct | schema::DeclarationVariant::Choice => None,
+        })
+}
+
+// Iterate over the types which appear in a method, namely its input and output types.
+fn method_types(method: &schema::Method) -> impl Iterator<Item = &schema::Type> {
+    [&method.input_type, &method.output_type].into_iter()
+}
+
+// Find the innermost custom type at the given offset.
+fn find_custom_type(r#type: &schema::Type, offset: usize) -> Option<&schema::Type> {
+    if !contains(r#type.source_range, offset) {
+        return None;
+    }
+
+    match &r#type.variant {
+        schema::TypeVariant::Array(inner_type, _) => find_custom_type(inner_type, offset),
+        schema::TypeVariant::Custom(_, _, arguments) => Some(
+            arguments
+                .iter()
+                .find_map(|argument| find_custom_type(argument, offset))
+                .unwrap_or(r#type),
+        ),
+        schema::TypeVariant::Map(key_type, value_type) => {
+            find_custom_type(key_type, offset).or_else(|| find_custom_type(value_type, offset))
+        }
+        schema::TypeVariant::Newtype(_, _, _) => {
+            // Impossible since references to newtypes are only resolved by [ref:monomorphization].
+            panic!()
+        }
+        schema::TypeVariant::Bool
+        | schema::TypeVariant::Bytes(_)
+        | schema::TypeVariant::F32
+        | schema::TypeVariant::F64
+        | schema::TypeVariant::S32
+        | schema::TypeVariant::S64
+        | schema::TypeVariant::String
+        | schema::TypeVariant::U32
+        | schema::TypeVariant::U64
+        | schema::TypeVariant::U8
+        | schema::TypeVariant::Unit => None,
+    }
+}
+
+// Describe a declaration in Markdown.
+fn describe_declaration(declaration: &schema::Declaration) -> String {
+    let mut description = format!(
+        "```\n{} {}{}{}\n```",
+        match declaration.variant {
+            schema::DeclarationVariant::Struct => token::STRUCT_KEYWORD,
+            schema::DeclarationVariant::Choice => token::CHOICE_KEYWORD,
+            schema::DeclarationVariant::Newtype(_) => token::NEWTYPE_KEYWORD,
+        },
+        declaration.name.original(),
+        if declaration.parameters.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "<{}>",
+                declaration
+                    .parameters
+                    .iter()
+                    .map(Identifier::original)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )
+        },
+        match &declaration.variant {
+            schema::DeclarationVariant::Struct | schema::DeclarationVariant::Choice => {
+                String::new()
+            }
+            schema::DeclarationVariant::Newtype(underlying_type) => {
+                format!(" = {underlying_type}")
+            }
+        },
+    );
+
+    for paragraph in &declaration.comment {
+        let _ = write!(description, "\n\n{paragraph}");
+    }
+
+    match declaration.variant {
+        schema::DeclarationVariant::Struct | schema::DeclarationVariant::Choice => {
+            let _ = write!(
+                description,
+                "\n\n{}",
+                count(declaration.fields.len(), "field"),
+            );
+        }
+        schema::DeclarationVariant::Newtype(_) => {}
+    }
+
+    description
+}
+
+// Describe a constant in Markdown.
+fn describe_constant(constant: &schema::Constant) -> String {
+    let mut description = format!(
+        "```\n{} {}: {} = {}\n```",
+        token::CONST_KEYWORD,
+        constant.name.original(),
+        constant.r#type,
+        constant.value,
+    );
+
+    for paragraph in &constant.comment {
+        let _ = write!(description, "\n\n{paragraph}");
+    }
+
+    description
+}
+
+// Describe a service in Markdown.
+fn describe_service(service: &schema::Service) -> String {
+    let mut description = format!(
+        "```\n{} {}\n```",
+        token::SERVICE_KEYWORD,
+        service.name.original(),
+    );
+
+    for paragraph in &service.comment {
+        let _ = write!(description, "\n\n{paragraph}");
+    }
+
+    let _ = write!(
+        description,
+        "\n\n{}",
+        count(service.methods.len(), "method"),
+    );
+
+    description
+}
+
+// Describe a method in Markdown.
+fn describe_method(method: &schema::Method) -> String {
+    let mut description = format!(
+        "```\n{}: {} -> {} = {}\n```\n\nThis method has index {}.",
+        method.name.snake_case(),
+        method.input_type,
+        method.output_type,
+        method.index,
+        method.index,
+    );
+
+    for paragraph in &method.comment {
+        let _ = write!(description, "\n\n{paragraph}");
+    }
+
+    description
+}
+
+// Describe a field in Markdown.
+fn describe_field(field: &schema::Field) -> String {
+    let (rule_keyword, rule) = match field.rule {
+        schema::Rule::Asymmetric => ("asymmetric ", "asymmetric"),
+        schema::Rule::Optional => ("optional ", "optional"),
+        schema::Rule::Required => ("", "required"),
+    };
+
+    let mut description = format!("```\n{rule_keyword}{}", field.name.snake_case());
+    if !matches!(field.r#type.variant, schema::TypeVariant::Unit) {
+        let _ = write!(description, ": {}", field.r#type);
+    }
+    let _ = write!(
+        description,
+        " = {}\n```\n\nThis field is {rule} and has index {}.",
+        field.index, field.index,
+    );
+
+    for paragraph in &field.comment {
+        let _ = write!(description, "\n\n{paragraph}");
+    }
+
+    description
+}
+
+// Determine whether an offset is within a source range. The end is included so that a cursor right
+// after an item still refers to it.
+fn contains(source_range: SourceRange, offset: usize) -> bool {
+    source_range.start <= offset && offset <= source_range.end
+}
+
+// Find the start of the identifier (if any) at the end of the given text.
+fn identifier_start(text: &str) -> usize {
+    text.char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_alphanumeric() || *c == '_')
+        .last()
+        .map_or(text.len(), |(i, _)| i)
+}
+
+// Determine the path of a document from a `TextDocumentIdentifier`.
+fn document_path(document: &Value) -> Option<PathBuf> {
+    uri_to_path(document.get("uri")?.as_str()?)
+}
+
+// Convert a `file` URI to a path.
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?.as_bytes();
+
+    // Decode any percent-encoded bytes.
+    let mut decoded = vec![];
+    let mut i = 0;
+    while i < encoded.len() {
+        if encoded[i] == b'%' && i + 2 < encoded.len() + 1 {
+            let byte = std::str::from_utf8(encoded.get(i + 1..i + 3)?)
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())?;
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(encoded[i]);
+            i += 1;
+        }
+    }
+    let decoded = String::from_utf8(decoded).ok()?;
+
+    // On Windows, the path of a URI like `file:///C:/foo` starts with a drive letter.
+    let path = if cfg!(windows) {
+        decoded.strip_prefix('/').unwrap_or(&decoded)
+    } else {
+        &decoded
+    };
+
+    normalize(Path::new(path))
+}
+
+// Convert a path to a `file` URI.
+fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = if path.starts_with('/') {
+        "file://".to_owned()
+    } else {
+        "file:///".to_owned()
+    };
+
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-._~:".contains(&byte) {
+            uri.push(char::from(byte));
+        } else {
+            let _ = write!(uri, "%{byte:02X}");
+        }
+    }
+
+    uri
+}
+
+// Convert a byte offset into an LSP position, i.e., a line and a column in UTF-16 code units.
+fn offset_to_position(text: &str, offset: usize) -> (usize, usize) {
+    let mut offset = min(offset, text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+    (
+        before.matches('\n').count(),
+        before[line_start..].encode_utf16().count(),
+    )
+}
+
+// Convert an LSP position into a byte offset.
+fn position_to_offset(text: &str, line: usize, character: usize) -> usize {
+    let mut line_start = 0;
+    for _ in 0..line {
+        match text[line_start..].find('\n') {
+            Some(i) => line_start += i + 1,
+            None => return text.len(),
+        }
+    }
+
+    let mut code_units = 0;
+    for (i, c) in text[line_start..].char_indices() {
+        if code_units >= character || c == '\n' {
+            return line_start + i;
+        }
+
+        code_units += c.len_utf16();
+    }
+
+    text.len()
+}
+
+// Render a source range as an LSP range.
+fn range_json(text: &str, source_range: SourceRange) -> String {
+    let (start_line, start_character) = offset_to_position(text, source_range.start);
+    let (end_line, end_character) = offset_to_position(text, source_range.end);
+
+    format!(
+        "{{\"start\":{{\"line\":{start_line},\"character\":{start_character}}},\
+            \"end\":{{\"line\":{end_line},\"character\":{end_character}}}}}",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::{
+            json::{parse, quote, Value},
+            lsp::{offset_to_position, path_to_uri, position_to_offset, serve, uri_to_path},
+        },
+        std::{fmt::Write, path::Path},
+    };
+
+    // Run a session with the given messages and return the responses and notifications sent by
+    // the server.
+    fn session(messages: &[String]) -> Vec<Value> {
+        let mut input = String::new();
+        for message in messages {
+            let _ = write!(
+                input,
+                "Content-Length: {}\r\n\r\n{}",
+                message.len(),
+                message
+            );
+        }
+
+        let mut output = vec![];
+        serve(input.as_bytes(), &mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        output
+            .split("Content-Length: ")
+            .skip(1)
+            .map(|message| {
+                let (_, content) = message.split_once("\r\n\r\n").unwrap();
+                parse(Path::new("message"), content).unwrap()
+            })
+            .collect()
+    }
+
+    fn open(uri: &str, text: &str) -> String {
+        format!(
+            "{{\"jsonrpc\":\"2.0\",\"method\":\"textDocument/didOpen\",\"params\":\
+                {{\"textDocument\":{{\"uri\":{},\"languageId\":\"typical\",\"version\":1,\
+                \"text\":{}}}}}}}",
+            quote(uri),
+            quote(text),
+        )
+    }
+
+    fn request(id: usize, method: &str, uri: &str, line: usize, character: usize) -> String {
+        format!(
+            "{{\"jsonrpc\":\"2.0\",\"id\":{id},\"method\":{},\"params\":\
+                {{\"textDocument\":{{\"uri\":{}}},\"position\":\
+                {{\"line\":{line},\"character\":{character}}}}}}}",
+            quote(method),
+            quote(uri),
+        )
+    }
+
+    #[test]
+    fn uri_round_trip() {
+        let path = Path::new("/nonexistent/foo bar/types.t");
+        let uri = path_to_uri(path);
+
+        assert_eq!(uri, "file:///nonexistent/foo%20bar/types.t");
+        assert_eq!(uri_to_path(&uri).unwrap(), path);
+    }
+
+    #[test]
+    fn position_conversions() {
+        let text = "ab\n\u{e9}\u{1f600}x\n";
+
+        assert_eq!(offset_to_position(text, 1), (0, 1));
+        assert_eq!(offset_to_position(text, 3), (1, 0));
+        assert_eq!(offset_to_position(text, 9), (1, 3));
+        assert_eq!(position_to_offset(text, 1, 3), 9);
+        assert_eq!(position_to_offset(text, 0, 10), 2);
+        assert_eq!(position_to_offset(text, 5, 0), text.len());
+    }
+
+    #[test]
+    fn diagnostics() {
+        let uri = "file:///nonexistent/types.t";
+        let responses = session(&[open(uri, "struct Foo {\n    x: Bar = 0\n}\n")]);
+
+        assert_eq!(
+            responses[0].to_string(),
+            format!(
+                "{{\"jsonrpc\":\"2.0\",\"method\":\"textDocument/publishDiagnostics\",\"params\":\
+                    {{\"uri\":{},\"diagnostics\":[{{\"range\":{{\"start\":{{\"line\":1,\
+                    \"character\":7}},\"end\":{{\"line\":1,\"character\":10}}}},\"severity\":1,\
+                    \"source\":\"typical\",\"message\":\
+                    \"There is no type named `Bar` in this file.\"}}]}}}}",
+                quote(uri),
+            ),
+        );
+    }
+
+    #[test]
+    fn hover_and_definition() {
+        let uri = "file:///nonexistent/types.t";
+        let responses = session(&[
+            open(
+                uri,
+                "struct Foo {\n    optional x: [Bar] = 3\n}\n\n# A bar\nchoice Bar {\n}\n",
+            ),
+            request(1, "textDocument/hover", uri, 1, 14),
+            request(2, "textDocument/hover", uri, 1, 19),
+            request(3, "textDocument/definition", uri, 1, 19),
+        ]);
+
+        assert_eq!(
+            responses[1]
+                .get("result")
+                .and_then(|result| result.get("contents"))
+                .and_then(|contents| contents.get("value"))
+                .and_then(Value::as_str),
+            Some("```\noptional x: [Bar] = 3\n```\n\nThis field is optional and has index 3.",),
+        );
+        assert_eq!(
+            responses[2]
+                .get("result")
+                .and_then(|result| result.get("contents"))
+                .and_then(|contents| contents.get("value"))
+                .and_then(Value::as_str),
+            Some("```\nchoice Bar\n```\n\nA bar\n\n0 fields"),
+        );
+        assert_eq!(
+            responses[3]
+                .get("result")
+                .and_then(|result| result.get("range"))
+                .map(ToString::to_string),
+            Some(
+                "{\"start\":{\"line\":4,\"character\":0},\"end\":{\"line\":6,\"character\":1}}"
+                    .to_owned(),
+            ),
+        );
+    }
+
+    #[test]
+    fn hover_generic() {
+        let uri = "file:///nonexistent/types.t";
+        let responses = session(&[
+            open(
+                uri,
+                "struct Page<T> {\n    x: [T] = 0\n}\n\nstruct Bar {\n    y: Page<Bar> = 0\n}\n",
+            ),
+            request(1, "textDocument/hover", uri, 1, 8),
+            request(2, "textDocument/hover", uri, 5, 8),
+            request(3, "textDocument/hover", uri, 5, 13),
+        ]);
+
+        let values = responses
+            .iter()
+            .skip(1)
+            .map(|response| {
+                response
+                    .get("result")
+                    .and_then(|result| result.get("contents"))
+                    .and_then(|contents| contents.get("value"))
+                    .and_then(Value::as_str)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            values,
+            vec![
+                Some("```\nx: [T] = 0\n```\n\nThis field is required and has index 0."),
+                Some("```\nstruct Page<T>\n```\n\n1 field"),
+                Some("```\nstruct Bar\n```\n\n1 field"),
+            ],
+        );
+    }
+
+    #[test]
+    fn hover_newtype() {
+        let uri = "file:///nonexistent/types.t";
+        let responses = session(&[
+            open(
+                uri,
+                "# An identifier\nnewtype Id = U64\n\nnewtype Ids = [Id]\n",
+            ),
+            request(1, "textDocument/hover", uri, 1, 9),
+            request(2, "textDocument/hover", uri, 3, 16),
+            request(3, "textDocument/definition", uri, 3, 16),
+        ]);
+
+        assert_eq!(
+            responses[1]
+                .get("result")
+                .and_then(|result| result.get("contents"))
+                .and_then(|contents| contents.get("value"))
+                .and_then(Value::as_str),
+            Some("```\nnewtype Id = U64\n```\n\nAn identifier"),
+        );
+        assert_eq!(
+            responses[2]
+                .get("result")
+                .and_then(|result| result.get("contents"))
+                .and_then(|contents| contents.get("value"))
+                .and_then(Value::as_str),
+            Some("```\nnewtype Id = U64\n```\n\nAn identifier"),
+        );
+        assert_eq!(
+            responses[3]
+                .get("result")
+                .and_then(|result| result.get("range"))
+                .map(ToString::to_string),
+            Some(
+                "{\"start\":{\"line\":0,\"character\":0},\"end\":{\"line\":1,\"character\":16}}"
+                    .to_owned(),
+            ),
+        );
+    }
+
+    #[test]
+    fn hover_constant() {
+        let uri = "file:///nonexistent/types.t";
+        let responses = session(&[
+            open(uri, "# The version\nconst VERSION: U64 = 3\n"),
+            request(1, "textDocument/hover", uri, 1, 8),
+        ]);
+
+        assert_eq!(
+            responses[1]
+                .get("result")
+                .and_then(|result| result.get("contents"))
+                .and_then(|contents| contents.get("value"))
+                .and_then(Value::as_str),
+            Some("```\nconst VERSION: U64 = 3\n```\n\nThe version"),
+        );
+    }
+
+    #[test]
+    fn hover_and_definition_service() {
+        let uri = "file:///nonexistent/types.t";
+        let responses = session(&[
+            open(
+                uri,
+                "struct Ping {\n}\n\n# An echo server\nservice Echo {\n    \
+                    ping: Ping -> Ping = 0\n}\n",
+            ),
+            request(1, "textDocument/hover", uri, 4, 9),
+            request(2, "textDocument/hover", uri, 5, 5),
+            request(3, "textDocument/hover", uri, 5, 11),
+            request(4, "textDocument/definition", uri, 5, 20),
+        ]);
+
+        let values = responses
+            .iter()
+            .skip(1)
+            .take(3)
+            .map(|response| {
+                response
+                    .get("result")
+                    .and_then(|result| result.get("contents"))
+                    .and_then(|contents| contents.get("value"))
+                    .and_then(Value::as_str)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            values,
+            vec![
+                Some("```\nservice Echo\n```\n\nAn echo server\n\n1 method"),
+                Some("```\nping: Ping -> Ping = 0\n```\n\nThis method has index 0."),
+                Some("```\nstruct Ping\n```\n\n0 fields"),
+            ],
+        );
+        assert_eq!(
+            responses[4]
+                .get("result")
+                .and_then(|result| result.get("range"))
+                .map(ToString::to_string),
+            Some(
+                "{\"start\":{\"line\":0,\"character\":0},\"end\":{\"line\":1,\"character\":1}}"
+                    .to_owned(),
+            ),
+        );
+    }
+
+    #[test]
+    fn completion() {
+        let uri = "file:///nonexistent/types.t";
+        let responses = session(&[
+            open(uri, "import 'bar.t'\n\nstruct Foo {\n    x: \n}\n"),
+            request(1, "textDocument/completion", uri, 3, 7),
+        ]);
+
+        let labels = responses
+            .last()
+            .and_then(|response| response.get("result"))
+            .and_then(Value::as_array)
+            .unwrap()
+            .iter()
+            .filter_map(|item| item.get("label").and_then(Value::as_str))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            labels,
+            vec![
+                "Bool", "Bytes", "F32", "F64", "S32", "S64", "String", "U32", "U64", "U8", "Unit",
+                "Foo", "bar",
+            ],
+        );
+    }
+
+    #[test]
+    fn hover_and_definition_nested() {
+        let uri = "file:///nonexistent/types.t";
+        let responses = session(&[
+            open(
+                uri,
+                "struct Device {\n    address: Device.IpAddress = 0\n\n    \
+                    # An IP address\n    choice IpAddress {\n        v4: U32 = 0\n    }\n}\n",
+            ),
+            request(1, "textDocument/hover", uri, 1, 22),
+            request(2, "textDocument/hover", uri, 5, 9),
+            request(3, "textDocument/definition", uri, 1, 22),
+        ]);
+
+        assert_eq!(
+            responses[1]
+                .get("result")
+                .and_then(|result| result.get("contents"))
+                .and_then(|contents| contents.get("value"))
+                .and_then(Value::as_str),
+            Some("```\nchoice Device.IpAddress\n```\n\nAn IP address\n\n1 field"),
+        );
+        assert_eq!(
+            responses[2]
+                .get("result")
+                .and_then(|result| result.get("contents"))
+                .and_then(|contents| contents.get("value"))
+                .and_then(Value::as_str),
+            Some("```\nv4: U32 = 0\n```\n\nThis field is required and has index 0."),
+        );
+        assert_eq!(
+            responses[3]
+                .get("result")
+                .and_then(|result| result.get("range"))
+                .map(ToString::to_string),
+            Some(
+                "{\"start\":{\"line\":3,\"character\":4},\"end\":{\"line\":6,\"character\":5}}"
+                    .to_owned(),
+            ),
+        );
+    }
+
+    #[test]
+    fn completion_nested() {
+        let uri = "file:///nonexistent/types.t";
+        let responses = session(&[
+            open(
+                uri,
+                "struct Device {\n    x: Device.\n\n    choice IpAddress {\n    }\n\n    \
+                    newtype Id = U64\n}\n",
+            ),
+            request(1, "textDocument/completion", uri, 1, 14),
+        ]);
+
+        let labels = responses
+            .last()
+            .and_then(|response| response.get("result"))
+            .and_then(Value::as_array)
+            .unwrap()
+            .iter()
+            .filter_map(|item| item.get("label").and_then(Value::as_str))
+            .collect::<Vec<_>>();
+
+        assert_eq!(labels, vec!["IpAddress", "Id"]);
+    }
+
+    #[test]
+    fn formatting() {
+        let uri = "file:///nonexistent/types.t";
+        let responses = session(&[
+            open(uri, "struct Foo{x:U64=0}"),
+            format!(
+                "{{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"textDocument/formatting\",\
+                    \"params\":{{\"textDocument\":{{\"uri\":{}}},\"options\":{{}}}}}}",
+                quote(uri),
+            ),
+        ]);
+
+        assert_eq!(
+            responses
+                .last()
+                .and_then(|response| response.get("result"))
+                .and_then(Value::as_array)
+                .and_then(|edits| edits[0].get("newText"))
+                .and_then(Value::as_str),
+            Some("struct Foo {\n    x: U64 = 0\n}\n"),
+        );
+    }
+
+    #[test]
+    fn unknown_method() {
+        let responses =
+            session(&["{\"jsonrpc\":\"2.0\",\"id\":7,\"method\":\"foo/bar\"}".to_owned()]);
+
+        assert_eq!(
+            responses[0].to_string(),
+            "{\"jsonrpc\":\"2.0\",\"id\":7,\"error\":{\"code\":-32601,\
+                \"message\":\"Unknown method foo/bar.\"}}",
+        );
+    }
+}
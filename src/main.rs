@@ -1,55 +1,79 @@
-mod assertions;
-mod count;
-mod error;
-mod error_merger;
-mod format;
-mod generate_rust;
-mod generate_typescript;
-mod identifier;
-mod parser;
-mod schema;
-mod schema_loader;
-mod token;
-mod tokenizer;
-mod validator;
-
 use {
-    crate::{
+    clap::{App, AppSettings, Arg, ArgMatches, Shell, SubCommand},
+    std::{
+        env::current_dir,
+        fs::{create_dir_all, read, read_to_string, write},
+        io::{stdin, stdout, Read, Write},
+        path::{Path, PathBuf},
+        process::exit,
+    },
+    typical::{
+        compatibility_checker::check_compatibility,
         count::count,
+        decoder::decode,
+        encoder::encode,
         error::{throw, Error},
         error_merger::merge_errors,
-        format::CodeStr,
-        schema_loader::load_schemas,
+        format::{enable_colors, CodeStr},
+        generate_rust, generate_typescript,
+        lsp::serve,
+        schema_loader::{load_schemas_with_includes, path_to_namespace},
         validator::validate,
-    },
-    clap::{App, AppSettings, Arg, Shell, SubCommand},
-    std::{
-        fs::{create_dir_all, write},
-        io::stdout,
-        path::Path,
-        process::exit,
+        well_known, VERSION,
     },
 };
 
-// The program version
-const VERSION: &str = env!("CARGO_PKG_VERSION");
-
 // The name of the program binary
 const BIN_NAME: &str = "typical";
 
 // Command-line option and subcommand names
+const CHECK_COMPAT_SUBCOMMAND: &str = "check-compat";
+const CHECK_COMPAT_SUBCOMMAND_OLD_PATH_OPTION: &str = "check-compat-old-path";
+const CHECK_COMPAT_SUBCOMMAND_NEW_PATH_OPTION: &str = "check-compat-new-path";
+const DECODE_SUBCOMMAND: &str = "decode";
+const DECODE_SUBCOMMAND_PATH_OPTION: &str = "decode-path";
+const DECODE_SUBCOMMAND_TYPE_OPTION: &str = "decode-type";
+const DECODE_SUBCOMMAND_MESSAGE_PATH_OPTION: &str = "decode-message-path";
+const ENCODE_SUBCOMMAND: &str = "encode";
+const ENCODE_SUBCOMMAND_PATH_OPTION: &str = "encode-path";
+const ENCODE_SUBCOMMAND_TYPE_OPTION: &str = "encode-type";
+const ENCODE_SUBCOMMAND_INPUT_PATH_OPTION: &str = "encode-input-path";
+const ENCODE_SUBCOMMAND_OUTPUT_OPTION: &str = "output";
 const GENERATE_SUBCOMMAND: &str = "generate";
 const GENERATE_SUBCOMMAND_PATH_OPTION: &str = "generate-path";
 const GENERATE_SUBCOMMAND_RUST_OPTION: &str = "rust";
 const GENERATE_SUBCOMMAND_TYPESCRIPT_OPTION: &str = "typescript";
+const GENERATE_SUBCOMMAND_RUST_UUID_OPTION: &str = "rust-uuid";
+const GENERATE_SUBCOMMAND_RUST_BORROWED_OPTION: &str = "rust-borrowed";
+const GENERATE_SUBCOMMAND_RUST_NO_STD_OPTION: &str = "rust-no-std";
+const GENERATE_SUBCOMMAND_RUST_ASYNC_OPTION: &str = "rust-async";
+const GENERATE_SUBCOMMAND_RUST_BUILDERS_OPTION: &str = "rust-builders";
+const GENERATE_SUBCOMMAND_RUST_DERIVE_OPTION: &str = "rust-derive";
+const GENERATE_SUBCOMMAND_TYPESCRIPT_BUILDERS_OPTION: &str = "typescript-builders";
 const GENERATE_SUBCOMMAND_LIST_SCHEMAS_OPTION: &str = "list-schemas";
+const GENERATE_SUBCOMMAND_CHECK_OPTION: &str = "check";
 const FORMAT_SUBCOMMAND: &str = "format";
 const FORMAT_SUBCOMMAND_PATH_OPTION: &str = "format-path";
 const FORMAT_SUBCOMMAND_CHECK_OPTION: &str = "check";
+const LSP_SUBCOMMAND: &str = "lsp";
 const SHELL_COMPLETION_SUBCOMMAND: &str = "shell-completion";
 const SHELL_COMPLETION_SUBCOMMAND_SHELL_OPTION: &str = "shell-completion-shell";
+const INCLUDE_OPTION: &str = "include";
+
+// Set up the option for adding include directories, which is shared by the subcommands that load
+// schemas.
+fn include_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name(INCLUDE_OPTION)
+        .value_name("DIRECTORY")
+        .short("I")
+        .long(INCLUDE_OPTION)
+        .multiple(true)
+        .number_of_values(1)
+        .help("Adds a directory to search for imports (can be used multiple times)")
+}
 
 // Set up the command-line interface.
+#[allow(clippy::too_many_lines)]
 fn cli<'a, 'b>() -> App<'a, 'b> {
     App::new("Typical")
         .version(VERSION)
@@ -61,6 +85,83 @@ fn cli<'a, 'b>() -> App<'a, 'b> {
         .setting(AppSettings::SubcommandRequiredElseHelp) // [tag:subcommand_required_else_help]
         .setting(AppSettings::UnifiedHelpMessage)
         .setting(AppSettings::VersionlessSubcommands)
+        .subcommand(
+            SubCommand::with_name(CHECK_COMPAT_SUBCOMMAND)
+                .about(
+                    "Checks that a new version of a schema and its transitive dependencies can \
+                    safely replace an old version",
+                )
+                .arg(
+                    Arg::with_name(CHECK_COMPAT_SUBCOMMAND_OLD_PATH_OPTION)
+                        .value_name("OLD_SCHEMA_PATH")
+                        .help("Sets the path of the old version of the schema")
+                        .required(true), // [tag:check_compat_subcommand_old_path_required]
+                )
+                .arg(
+                    Arg::with_name(CHECK_COMPAT_SUBCOMMAND_NEW_PATH_OPTION)
+                        .value_name("NEW_SCHEMA_PATH")
+                        .help("Sets the path of the new version of the schema")
+                        .required(true), // [tag:check_compat_subcommand_new_path_required]
+                )
+                .arg(include_arg()),
+        )
+        .subcommand(
+            SubCommand::with_name(DECODE_SUBCOMMAND)
+                .about("Decodes a binary message and prints its contents according to a schema")
+                .arg(
+                    Arg::with_name(DECODE_SUBCOMMAND_PATH_OPTION)
+                        .value_name("SCHEMA_PATH")
+                        .help("Sets the path of the schema")
+                        .required(true), // [tag:decode_subcommand_path_required]
+                )
+                .arg(include_arg())
+                .arg(
+                    Arg::with_name(DECODE_SUBCOMMAND_TYPE_OPTION)
+                        .value_name("TYPE")
+                        .help(
+                            "Sets the type of the message, e.g., `Foo` or `bar.Bar` for a type \
+                            from an imported schema",
+                        )
+                        .required(true), // [tag:decode_subcommand_type_required]
+                )
+                .arg(
+                    Arg::with_name(DECODE_SUBCOMMAND_MESSAGE_PATH_OPTION)
+                        .value_name("MESSAGE_PATH")
+                        .help("Sets the path of the message to decode (defaults to STDIN)"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name(ENCODE_SUBCOMMAND)
+                .about("Encodes a message described by a JSON document according to a schema")
+                .arg(
+                    Arg::with_name(ENCODE_SUBCOMMAND_PATH_OPTION)
+                        .value_name("SCHEMA_PATH")
+                        .help("Sets the path of the schema")
+                        .required(true), // [tag:encode_subcommand_path_required]
+                )
+                .arg(include_arg())
+                .arg(
+                    Arg::with_name(ENCODE_SUBCOMMAND_TYPE_OPTION)
+                        .value_name("TYPE")
+                        .help(
+                            "Sets the type of the message, e.g., `Foo` or `bar.Bar` for a type \
+                            from an imported schema",
+                        )
+                        .required(true), // [tag:encode_subcommand_type_required]
+                )
+                .arg(
+                    Arg::with_name(ENCODE_SUBCOMMAND_INPUT_PATH_OPTION)
+                        .value_name("INPUT_PATH")
+                        .help("Sets the path of the JSON document describing the message")
+                        .required(true), // [tag:encode_subcommand_input_path_required]
+                )
+                .arg(
+                    Arg::with_name(ENCODE_SUBCOMMAND_OUTPUT_OPTION)
+                        .value_name("PATH")
+                        .long(ENCODE_SUBCOMMAND_OUTPUT_OPTION)
+                        .help("Sets the path of the binary file to emit (defaults to STDOUT)"),
+                ),
+        )
         .subcommand(
             SubCommand::with_name(GENERATE_SUBCOMMAND)
                 .about("Generates code for a schema and its transitive dependencies")
@@ -70,6 +171,7 @@ fn cli<'a, 'b>() -> App<'a, 'b> {
                         .help("Sets the path of the schema")
                         .required(true), // [tag:generate_subcommand_path_required]
                 )
+                .arg(include_arg())
                 .arg(
                     Arg::with_name(GENERATE_SUBCOMMAND_LIST_SCHEMAS_OPTION)
                         .long(GENERATE_SUBCOMMAND_LIST_SCHEMAS_OPTION)
@@ -89,6 +191,73 @@ fn cli<'a, 'b>() -> App<'a, 'b> {
                         .value_name("PATH")
                         .long(GENERATE_SUBCOMMAND_TYPESCRIPT_OPTION)
                         .help("Sets the path of the TypeScript file to emit"),
+                )
+                .arg(
+                    Arg::with_name(GENERATE_SUBCOMMAND_RUST_UUID_OPTION)
+                        .long(GENERATE_SUBCOMMAND_RUST_UUID_OPTION)
+                        .help(
+                            "Generates Rust conversions between the well-known `Uuid` type and \
+                            `uuid::Uuid`, which requires the `uuid` crate",
+                        ),
+                )
+                .arg(
+                    Arg::with_name(GENERATE_SUBCOMMAND_RUST_BORROWED_OPTION)
+                        .long(GENERATE_SUBCOMMAND_RUST_BORROWED_OPTION)
+                        .help(
+                            "Generates Rust `In` types which borrow strings, bytes, and arrays \
+                            from the serialized message rather than copying them",
+                        ),
+                )
+                .arg(
+                    Arg::with_name(GENERATE_SUBCOMMAND_RUST_NO_STD_OPTION)
+                        .long(GENERATE_SUBCOMMAND_RUST_NO_STD_OPTION)
+                        .help(
+                            "Generates Rust code which only requires `core` and `alloc` rather \
+                            than `std`",
+                        ),
+                )
+                .arg(
+                    Arg::with_name(GENERATE_SUBCOMMAND_RUST_ASYNC_OPTION)
+                        .long(GENERATE_SUBCOMMAND_RUST_ASYNC_OPTION)
+                        .help(
+                            "Generates asynchronous Rust serialization and deserialization \
+                            functions based on Tokio, which requires the `tokio` crate",
+                        ),
+                )
+                .arg(
+                    Arg::with_name(GENERATE_SUBCOMMAND_RUST_BUILDERS_OPTION)
+                        .long(GENERATE_SUBCOMMAND_RUST_BUILDERS_OPTION)
+                        .help(
+                            "Generates typed builders for the Rust `Out` types of structs, which \
+                            check at compile time that all the required fields are given",
+                        ),
+                )
+                .arg(
+                    Arg::with_name(GENERATE_SUBCOMMAND_RUST_DERIVE_OPTION)
+                        .value_name("TRAIT")
+                        .long(GENERATE_SUBCOMMAND_RUST_DERIVE_OPTION)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help(
+                            "Derives an additional trait (e.g., `PartialEq` or `Serialize`) for \
+                            all the generated Rust types (can be used multiple times)",
+                        ),
+                )
+                .arg(
+                    Arg::with_name(GENERATE_SUBCOMMAND_TYPESCRIPT_BUILDERS_OPTION)
+                        .long(GENERATE_SUBCOMMAND_TYPESCRIPT_BUILDERS_OPTION)
+                        .help(
+                            "Generates typed builders for the TypeScript `Out` types of structs, \
+                            which check at compile time that all the required fields are given",
+                        ),
+                )
+                .arg(
+                    Arg::with_name(GENERATE_SUBCOMMAND_CHECK_OPTION)
+                        .long(GENERATE_SUBCOMMAND_CHECK_OPTION)
+                        .help(
+                            "Check that the files are up to date rather than actually writing \
+                            them",
+                        ),
                 ),
         )
         .subcommand(
@@ -100,12 +269,17 @@ fn cli<'a, 'b>() -> App<'a, 'b> {
                         .help("Sets the path of the schema")
                         .required(true), // [tag:format_subcommand_path_required]
                 )
+                .arg(include_arg())
                 .arg(
                     Arg::with_name(FORMAT_SUBCOMMAND_CHECK_OPTION)
                         .long(FORMAT_SUBCOMMAND_CHECK_OPTION)
                         .help("Check the formatting rather than actually doing it"),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name(LSP_SUBCOMMAND)
+                .about("Runs a language server for schemas, communicating over STDIN and STDOUT"),
+        )
         .subcommand(
             SubCommand::with_name(SHELL_COMPLETION_SUBCOMMAND)
                 .about(
@@ -124,16 +298,197 @@ fn cli<'a, 'b>() -> App<'a, 'b> {
         )
 }
 
-// Generate code for a schema and its transitive dependencies.
+// Determine the include directories given on the command line. Relative paths are relative to the
+// working directory, so they're made absolute here rather than being interpreted relative to the
+// directory containing the schema.
+fn include_paths(matches: &ArgMatches) -> Result<Vec<PathBuf>, Error> {
+    let Some(values) = matches.values_of(INCLUDE_OPTION) else {
+        return Ok(vec![]);
+    };
+
+    let working_directory = current_dir().map_err(|error| {
+        throw(
+            "Unable to determine the working directory.",
+            None,
+            None,
+            Some(error),
+        )
+    })?;
+
+    Ok(values.map(|value| working_directory.join(value)).collect())
+}
+
+// Check that a new version of a schema and its transitive dependencies can safely replace an old
+// version.
+fn check_compat(old_path: &Path, new_path: &Path, include_paths: &[PathBuf]) -> Result<(), Error> {
+    // Load and validate both versions of the schema and their transitive dependencies.
+    eprintln!("Loading schemas\u{2026}");
+    let old_schemas = load_schemas_with_includes(old_path, include_paths)
+        .map_err(|errors| merge_errors(&errors))?;
+    let new_schemas = load_schemas_with_includes(new_path, include_paths)
+        .map_err(|errors| merge_errors(&errors))?;
+    eprintln!(
+        "{} loaded.",
+        count(old_schemas.len() + new_schemas.len(), "schema"),
+    );
+
+    eprintln!("Validating schemas\u{2026}");
+    validate(&old_schemas).map_err(|errors| merge_errors(&errors))?;
+    validate(&new_schemas).map_err(|errors| merge_errors(&errors))?;
+
+    // Compute the namespaces of the root schemas. The `unwrap`s are safe since otherwise the
+    // schemas would've failed to load above.
+    let old_root_namespace = path_to_namespace(Path::new(old_path.file_name().unwrap()));
+    let new_root_namespace = path_to_namespace(Path::new(new_path.file_name().unwrap()));
+
+    // Compare the two versions.
+    eprintln!("Checking compatibility\u{2026}");
+    check_compatibility(
+        &old_root_namespace,
+        &old_schemas,
+        &new_root_namespace,
+        &new_schemas,
+    )
+    .map_err(|errors| merge_errors(&errors))?;
+
+    eprintln!("Done.");
+    Ok(())
+}
+
+// Decode a binary message according to a schema and print its contents.
+fn decode_message(
+    path: &Path,
+    include_paths: &[PathBuf],
+    type_name: &str,
+    message_path: Option<&Path>,
+) -> Result<(), Error> {
+    // Load the schema and its transitive dependencies.
+    eprintln!("Loading schemas\u{2026}");
+    let schemas =
+        load_schemas_with_includes(path, include_paths).map_err(|errors| merge_errors(&errors))?;
+    eprintln!("{} loaded.", count(schemas.len(), "schema"));
+
+    // Validate the schemas.
+    eprintln!("Validating schemas\u{2026}");
+    validate(&schemas).map_err(|errors| merge_errors(&errors))?;
+
+    // Read the message from the file, if provided, or from STDIN otherwise.
+    let bytes = if let Some(message_path) = message_path {
+        read(message_path).map_err(|error| {
+            throw(
+                &format!(
+                    "Unable to read {}.",
+                    message_path.to_string_lossy().code_str()
+                ),
+                None,
+                None,
+                Some(error),
+            )
+        })?
+    } else {
+        let mut bytes = vec![];
+        stdin()
+            .read_to_end(&mut bytes)
+            .map_err(|error| throw("Unable to read STDIN.", None, None, Some(error)))?;
+        bytes
+    };
+
+    // Compute the namespace of the root schema. The `unwrap` is safe since otherwise the schema
+    // would've failed to load above.
+    let root_namespace = path_to_namespace(Path::new(path.file_name().unwrap()));
+
+    // Decode the message and print the result.
+    eprintln!("Decoding message\u{2026}");
+    print!("{}", decode(&schemas, &root_namespace, type_name, &bytes)?);
+
+    eprintln!("Done.");
+    Ok(())
+}
+
+// Encode a message described by a JSON document according to a schema.
+fn encode_message(
+    path: &Path,
+    include_paths: &[PathBuf],
+    type_name: &str,
+    input_path: &Path,
+    output: Option<&Path>,
+) -> Result<(), Error> {
+    // Load the schema and its transitive dependencies.
+    eprintln!("Loading schemas\u{2026}");
+    let schemas =
+        load_schemas_with_includes(path, include_paths).map_err(|errors| merge_errors(&errors))?;
+    eprintln!("{} loaded.", count(schemas.len(), "schema"));
+
+    // Validate the schemas.
+    eprintln!("Validating schemas\u{2026}");
+    validate(&schemas).map_err(|errors| merge_errors(&errors))?;
+
+    // Read the JSON document.
+    let input_contents = read_to_string(input_path).map_err(|error| {
+        throw(
+            &format!(
+                "Unable to read {}.",
+                input_path.to_string_lossy().code_str()
+            ),
+            None,
+            None,
+            Some(error),
+        )
+    })?;
+
+    // Compute the namespace of the root schema. The `unwrap` is safe since otherwise the schema
+    // would've failed to load above.
+    let root_namespace = path_to_namespace(Path::new(path.file_name().unwrap()));
+
+    // Encode the message.
+    eprintln!("Encoding message\u{2026}");
+    let bytes = encode(
+        &schemas,
+        &root_namespace,
+        type_name,
+        input_path,
+        &input_contents,
+    )
+    .map_err(|errors| merge_errors(&errors))?;
+
+    // Write the message to the file, if provided, or to STDOUT otherwise.
+    if let Some(output) = output {
+        eprintln!("Writing {}\u{2026}", output.to_string_lossy().code_str());
+        write(output, bytes).map_err(|error| {
+            throw(
+                &format!("Unable to write {}.", output.to_string_lossy().code_str()),
+                None,
+                None,
+                Some(error),
+            )
+        })?;
+    } else {
+        stdout()
+            .write_all(&bytes)
+            .map_err(|error| throw("Unable to write to STDOUT.", None, None, Some(error)))?;
+    }
+
+    eprintln!("Done.");
+    Ok(())
+}
+
+// Generate code for a schema and its transitive dependencies. In check mode, the generated code is
+// compared against the existing files instead of being written.
+#[allow(clippy::too_many_arguments)]
 fn generate_code(
     path: &Path,
+    include_paths: &[PathBuf],
     list_schemas: bool,
     rust: Option<&Path>,
+    rust_options: &generate_rust::Options,
     typescript: Option<&Path>,
+    typescript_options: &generate_typescript::Options,
+    check: bool,
 ) -> Result<(), Error> {
     // Load the schema and its transitive dependencies.
     eprintln!("Loading schemas\u{2026}");
-    let schemas = load_schemas(path).map_err(|errors| merge_errors(&errors))?;
+    let schemas =
+        load_schemas_with_includes(path, include_paths).map_err(|errors| merge_errors(&errors))?;
     eprintln!("{} loaded.", count(schemas.len(), "schema"));
 
     // Validate the schemas.
@@ -147,66 +502,169 @@ fn generate_code(
         // The `unwrap` is safe since otherwise the schema would've failed to load above.
         let directory = path.parent().unwrap();
 
+        // Well-known schemas are skipped, since they're embedded rather than read from files
+        // [ref:well_known_schemas].
         for (_, source_path, _) in schemas.values() {
-            println!("{}", directory.join(source_path).to_string_lossy());
+            if well_known::contents(source_path).is_none() {
+                println!("{}", directory.join(source_path).to_string_lossy());
+            }
         }
     }
 
+    // Any outdated files found in check mode will be described here.
+    let mut mismatches = vec![];
+
     // Generate Rust code, if applicable.
     if let Some(rust) = rust {
         eprintln!("Generating Rust\u{2026}");
-
-        // Create any missing intermediate directories as needed.
-        if let Some(parent) = rust.parent() {
-            create_dir_all(parent).map_err(|error| {
-                throw(
-                    &format!("Unable to create {}.", parent.to_string_lossy().code_str()),
-                    None,
-                    None,
-                    Some(error),
-                )
-            })?;
-        }
-
-        // Generate the code and write it to the file.
-        eprintln!("Writing {}\u{2026}", rust.to_string_lossy().code_str());
-        write(rust, generate_rust::generate(VERSION, &schemas)).map_err(|error| {
-            throw(
-                &format!("Unable to write {}.", rust.to_string_lossy().code_str()),
-                None,
-                None,
-                Some(error),
-            )
-        })?;
+        let code = generate_rust::generate_with_options(VERSION, &schemas, rust_options)
+            .map_err(|errors| merge_errors(&errors))?;
+        mismatches.extend(emit_code(rust, &code, check)?);
     }
 
     // Generate TypeScript code, if applicable.
     if let Some(typescript) = typescript {
         eprintln!("Generating TypeScript\u{2026}");
+        let code =
+            generate_typescript::generate_with_options(VERSION, &schemas, typescript_options)
+                .map_err(|errors| merge_errors(&errors))?;
+        mismatches.extend(emit_code(typescript, &code, check)?);
+    }
 
-        // Create any missing intermediate directories as needed.
-        if let Some(parent) = typescript.parent() {
-            create_dir_all(parent).map_err(|error| {
-                throw(
-                    &format!("Unable to create {}.", parent.to_string_lossy().code_str()),
-                    None,
-                    None,
-                    Some(error),
-                )
-            })?;
-        }
-
-        // Generate the code and write it to the file.
-        eprintln!(
-            "Writing {}\u{2026}",
-            typescript.to_string_lossy().code_str(),
+    // In check mode, fail if any of the files are out of date.
+    if !mismatches.is_empty() {
+        let command = generate_command(
+            path,
+            include_paths,
+            rust,
+            rust_options,
+            typescript,
+            typescript_options,
         );
-        write(typescript, generate_typescript::generate(VERSION, &schemas)).map_err(|error| {
+
+        return Err(throw::<Error>(
+            &format!(
+                "The generated code is out of date:\n\n{}\n\nPlease run {}.",
+                mismatches.join("\n"),
+                command.code_str(),
+            ),
+            None,
+            None,
+            None,
+        ));
+    }
+
+    eprintln!("Done.");
+    Ok(())
+}
+
+// Construct the command which regenerates the code, for check mode to suggest. The arguments are
+// quoted as needed so the command can be pasted into a shell.
+fn generate_command(
+    path: &Path,
+    include_paths: &[PathBuf],
+    rust: Option<&Path>,
+    rust_options: &generate_rust::Options,
+    typescript: Option<&Path>,
+    typescript_options: &generate_typescript::Options,
+) -> String {
+    format!(
+        "typical generate {}{}{}{}{}{}{}{}{}{}{}",
+        shell_quote(&path.to_string_lossy()),
+        include_paths
+            .iter()
+            .fold(String::new(), |acc, include_path| format!(
+                "{acc} --include {}",
+                shell_quote(&include_path.to_string_lossy()),
+            )),
+        rust.map_or_else(String::new, |rust| format!(
+            " --rust {}",
+            shell_quote(&rust.to_string_lossy()),
+        )),
+        if rust.is_some() && rust_options.uuid {
+            " --rust-uuid"
+        } else {
+            ""
+        },
+        if rust.is_some() && rust_options.borrowed {
+            " --rust-borrowed"
+        } else {
+            ""
+        },
+        if rust.is_some() && rust_options.no_std {
+            " --rust-no-std"
+        } else {
+            ""
+        },
+        if rust.is_some() && rust_options.async_io {
+            " --rust-async"
+        } else {
+            ""
+        },
+        if rust.is_some() && rust_options.builders {
+            " --rust-builders"
+        } else {
+            ""
+        },
+        if rust.is_some() {
+            rust_options
+                .derives
+                .iter()
+                .fold(String::new(), |acc, name| {
+                    format!("{acc} --rust-derive {}", shell_quote(name))
+                })
+        } else {
+            String::new()
+        },
+        typescript.map_or_else(String::new, |typescript| format!(
+            " --typescript {}",
+            shell_quote(&typescript.to_string_lossy()),
+        )),
+        if typescript.is_some() && typescript_options.builders {
+            " --typescript-builders"
+        } else {
+            ""
+        },
+    )
+}
+
+// Quote an argument for a POSIX shell, unless it only contains characters which don't need quoting.
+// Single quotes within the argument are written as `'\''`, which ends the quoted string, adds an
+// escaped quote, and starts a new quoted string.
+fn shell_quote(argument: &str) -> String {
+    if !argument.is_empty()
+        && argument.chars().all(|c| {
+            c.is_ascii_alphanumeric()
+                || matches!(c, '_' | '-' | '.' | '/' | ':' | '=' | '@' | '+' | ',')
+        })
+    {
+        argument.to_owned()
+    } else {
+        format!("'{}'", argument.replace('\'', "'\\''"))
+    }
+}
+
+// Write generated code to a file or, in check mode, compare it against the contents of the file.
+// Returns a description of the difference if the check fails.
+fn emit_code(path: &Path, code: &str, check: bool) -> Result<Option<String>, Error> {
+    if check {
+        eprintln!("Checking {}\u{2026}", path.to_string_lossy().code_str());
+
+        return Ok(match read_to_string(path) {
+            Ok(contents) => diff_summary(&contents, code)
+                .map(|summary| format!("  {}: {}", path.to_string_lossy().code_str(), summary)),
+            Err(_) => Some(format!(
+                "  {}: the file is missing or unreadable.",
+                path.to_string_lossy().code_str(),
+            )),
+        });
+    }
+
+    // Create any missing intermediate directories as needed.
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent).map_err(|error| {
             throw(
-                &format!(
-                    "Unable to write {}.",
-                    typescript.to_string_lossy().code_str(),
-                ),
+                &format!("Unable to create {}.", parent.to_string_lossy().code_str()),
                 None,
                 None,
                 Some(error),
@@ -214,15 +672,69 @@ fn generate_code(
         })?;
     }
 
-    eprintln!("Done.");
-    Ok(())
+    // Write the code to the file.
+    eprintln!("Writing {}\u{2026}", path.to_string_lossy().code_str());
+    write(path, code).map_err(|error| {
+        throw(
+            &format!("Unable to write {}.", path.to_string_lossy().code_str()),
+            None,
+            None,
+            Some(error),
+        )
+    })?;
+
+    Ok(None)
+}
+
+// Summarize how the contents of a file differ from the expected contents, if at all. The summary
+// reports the range of lines between the common prefix and the common suffix of the two.
+fn diff_summary(actual: &str, expected: &str) -> Option<String> {
+    if actual == expected {
+        return None;
+    }
+
+    let actual_lines = actual.lines().collect::<Vec<_>>();
+    let expected_lines = expected.lines().collect::<Vec<_>>();
+
+    let prefix = actual_lines
+        .iter()
+        .zip(&expected_lines)
+        .take_while(|(actual_line, expected_line)| actual_line == expected_line)
+        .count();
+    let suffix = actual_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(expected_lines[prefix..].iter().rev())
+        .take_while(|(actual_line, expected_line)| actual_line == expected_line)
+        .count();
+
+    let removed = actual_lines.len() - prefix - suffix;
+    let added = expected_lines.len() - prefix - suffix;
+
+    Some(match (removed, added) {
+        // The lines are the same, so the difference is in the line endings.
+        (0, 0) => "the line endings differ.".to_owned(),
+        (0, _) => format!("missing {} at line {}.", count(added, "line"), prefix + 1),
+        (_, 0) => format!(
+            "{} too many at line {}.",
+            count(removed, "line"),
+            prefix + 1
+        ),
+        (_, _) => format!(
+            "{} at line {} should be replaced with {}.",
+            count(removed, "line"),
+            prefix + 1,
+            count(added, "line"),
+        ),
+    })
 }
 
 // Format a schema and its transitive dependencies.
-fn format_schema(path: &Path, check: bool) -> Result<(), Error> {
+fn format_schema(path: &Path, include_paths: &[PathBuf], check: bool) -> Result<(), Error> {
     // Load the schema and its transitive dependencies.
     eprintln!("Loading schemas\u{2026}");
-    let schemas = load_schemas(path).map_err(|errors| merge_errors(&errors))?;
+    let schemas =
+        load_schemas_with_includes(path, include_paths).map_err(|errors| merge_errors(&errors))?;
     eprintln!("{} loaded.", count(schemas.len(), "schema"));
 
     // This flag will be set if any changes were made to any of the schemas.
@@ -238,6 +750,11 @@ fn format_schema(path: &Path, check: bool) -> Result<(), Error> {
         if check { "Checking" } else { "Formatting" },
     );
     for (schema, source_path, source_contents) in schemas.values() {
+        // Skip the well-known schemas, which aren't files [ref:well_known_schemas].
+        if well_known::contents(source_path).is_some() {
+            continue;
+        }
+
         // Compute the full path and new contents of the schema.
         let full_source_path = directory.join(source_path);
         let new_source_contents = schema.to_string();
@@ -290,13 +807,10 @@ fn shell_completion(shell: &str) -> Result<(), Error> {
         "powershell" => Shell::PowerShell,
         "elvish" => Shell::Elvish,
         _ => {
-            return Err(Error {
-                message: format!(
-                    "Unknown shell {}. Must be one of Bash, Fish, Zsh, PowerShell, or Elvish.",
-                    shell.code_str(),
-                ),
-                reason: None,
-            });
+            return Err(Error::from_message(format!(
+                "Unknown shell {}. Must be one of Bash, Fish, Zsh, PowerShell, or Elvish.",
+                shell.code_str(),
+            )));
         }
     };
 
@@ -308,12 +822,116 @@ fn shell_completion(shell: &str) -> Result<(), Error> {
 }
 
 // Program entrypoint
+#[allow(clippy::too_many_lines)]
 fn entry() -> Result<(), Error> {
     // Parse command-line arguments.
     let matches = cli().get_matches();
 
+    // Colors are opt-in for library consumers, but the command-line interface wants them. The
+    // language server is the exception, since its output is consumed by an editor.
+    if matches.subcommand_name() != Some(LSP_SUBCOMMAND) {
+        enable_colors();
+    }
+
     // Decide what to do based on the subcommand.
     match matches.subcommand_name() {
+        // [tag:check_compat_subcommand]
+        Some(subcommand) if subcommand == CHECK_COMPAT_SUBCOMMAND => {
+            // Get the subcommand matches. The `unwrap` is safe due to
+            // [ref:check_compat_subcommand].
+            let subcommand_matches = matches.subcommand_matches(CHECK_COMPAT_SUBCOMMAND).unwrap();
+
+            // Determine the paths to the two versions of the schema.
+            let old_path = Path::new(
+                subcommand_matches
+                    .value_of(CHECK_COMPAT_SUBCOMMAND_OLD_PATH_OPTION)
+                    // [ref:check_compat_subcommand_old_path_required]
+                    .unwrap(),
+            );
+            let new_path = Path::new(
+                subcommand_matches
+                    .value_of(CHECK_COMPAT_SUBCOMMAND_NEW_PATH_OPTION)
+                    // [ref:check_compat_subcommand_new_path_required]
+                    .unwrap(),
+            );
+
+            // Determine the include directories.
+            let include_paths = include_paths(subcommand_matches)?;
+
+            // Compare the two versions of the schema.
+            check_compat(old_path, new_path, &include_paths)?;
+        }
+
+        // [tag:decode_subcommand]
+        Some(subcommand) if subcommand == DECODE_SUBCOMMAND => {
+            // Get the subcommand matches. The `unwrap` is safe due to [ref:decode_subcommand].
+            let subcommand_matches = matches.subcommand_matches(DECODE_SUBCOMMAND).unwrap();
+
+            // Determine the path to the schema file.
+            let path = Path::new(
+                subcommand_matches
+                    .value_of(DECODE_SUBCOMMAND_PATH_OPTION)
+                    // [ref:decode_subcommand_path_required]
+                    .unwrap(),
+            );
+
+            // Determine the include directories.
+            let include_paths = include_paths(subcommand_matches)?;
+
+            // Determine the type of the message.
+            let type_name = subcommand_matches
+                .value_of(DECODE_SUBCOMMAND_TYPE_OPTION)
+                // [ref:decode_subcommand_type_required]
+                .unwrap();
+
+            // Determine the path to the message, if provided.
+            let message_path = subcommand_matches
+                .value_of(DECODE_SUBCOMMAND_MESSAGE_PATH_OPTION)
+                .map(Path::new);
+
+            // Decode the message.
+            decode_message(path, &include_paths, type_name, message_path)?;
+        }
+
+        // [tag:encode_subcommand]
+        Some(subcommand) if subcommand == ENCODE_SUBCOMMAND => {
+            // Get the subcommand matches. The `unwrap` is safe due to [ref:encode_subcommand].
+            let subcommand_matches = matches.subcommand_matches(ENCODE_SUBCOMMAND).unwrap();
+
+            // Determine the path to the schema file.
+            let path = Path::new(
+                subcommand_matches
+                    .value_of(ENCODE_SUBCOMMAND_PATH_OPTION)
+                    // [ref:encode_subcommand_path_required]
+                    .unwrap(),
+            );
+
+            // Determine the include directories.
+            let include_paths = include_paths(subcommand_matches)?;
+
+            // Determine the type of the message.
+            let type_name = subcommand_matches
+                .value_of(ENCODE_SUBCOMMAND_TYPE_OPTION)
+                // [ref:encode_subcommand_type_required]
+                .unwrap();
+
+            // Determine the path to the JSON document.
+            let input_path = Path::new(
+                subcommand_matches
+                    .value_of(ENCODE_SUBCOMMAND_INPUT_PATH_OPTION)
+                    // [ref:encode_subcommand_input_path_required]
+                    .unwrap(),
+            );
+
+            // Determine the path to the output file, if provided.
+            let output = subcommand_matches
+                .value_of(ENCODE_SUBCOMMAND_OUTPUT_OPTION)
+                .map(Path::new);
+
+            // Encode the message.
+            encode_message(path, &include_paths, type_name, input_path, output)?;
+        }
+
         // [tag:generate_subcommand]
         Some(subcommand) if subcommand == GENERATE_SUBCOMMAND => {
             // Get the subcommand matches. The `unwrap` is safe due to [ref:generate_subcommand].
@@ -327,6 +945,9 @@ fn entry() -> Result<(), Error> {
                     .unwrap(),
             );
 
+            // Determine the include directories.
+            let include_paths = include_paths(subcommand_matches)?;
+
             // Determine if the user wants to list the schemas.
             let list_schemas =
                 subcommand_matches.is_present(GENERATE_SUBCOMMAND_LIST_SCHEMAS_OPTION);
@@ -336,13 +957,46 @@ fn entry() -> Result<(), Error> {
                 .value_of(GENERATE_SUBCOMMAND_RUST_OPTION)
                 .map(Path::new);
 
+            // Determine the options for the Rust code. They're non-exhaustive, so they have to be
+            // set one by one rather than with a struct expression.
+            let mut rust_options = generate_rust::Options::default();
+            rust_options.uuid = subcommand_matches.is_present(GENERATE_SUBCOMMAND_RUST_UUID_OPTION);
+            rust_options.borrowed =
+                subcommand_matches.is_present(GENERATE_SUBCOMMAND_RUST_BORROWED_OPTION);
+            rust_options.no_std =
+                subcommand_matches.is_present(GENERATE_SUBCOMMAND_RUST_NO_STD_OPTION);
+            rust_options.async_io =
+                subcommand_matches.is_present(GENERATE_SUBCOMMAND_RUST_ASYNC_OPTION);
+            rust_options.builders =
+                subcommand_matches.is_present(GENERATE_SUBCOMMAND_RUST_BUILDERS_OPTION);
+            rust_options.derives = subcommand_matches
+                .values_of(GENERATE_SUBCOMMAND_RUST_DERIVE_OPTION)
+                .map_or_else(Vec::new, |values| values.map(ToOwned::to_owned).collect());
+
             // Determine the path to the TypeScript output file, if provided.
             let typescript = subcommand_matches
                 .value_of(GENERATE_SUBCOMMAND_TYPESCRIPT_OPTION)
                 .map(Path::new);
 
+            // Determine the options for the TypeScript code.
+            let mut typescript_options = generate_typescript::Options::default();
+            typescript_options.builders =
+                subcommand_matches.is_present(GENERATE_SUBCOMMAND_TYPESCRIPT_BUILDERS_OPTION);
+
+            // Determine if the user only wants to check the generated code.
+            let check = subcommand_matches.is_present(GENERATE_SUBCOMMAND_CHECK_OPTION);
+
             // Generate code for the schema and its transitive dependencies.
-            generate_code(path, list_schemas, rust, typescript)?;
+            generate_code(
+                path,
+                &include_paths,
+                list_schemas,
+                rust,
+                &rust_options,
+                typescript,
+                &typescript_options,
+                check,
+            )?;
         }
 
         // [tag:format_subcommand]
@@ -358,11 +1012,19 @@ fn entry() -> Result<(), Error> {
                     .unwrap(),
             );
 
+            // Determine the include directories.
+            let include_paths = include_paths(subcommand_matches)?;
+
             // Determine if the user wants to check the formatting.
             let check = subcommand_matches.is_present(FORMAT_SUBCOMMAND_CHECK_OPTION);
 
             // Format the schema and its transitive dependencies.
-            format_schema(path, check)?;
+            format_schema(path, &include_paths, check)?;
+        }
+
+        // [tag:lsp_subcommand]
+        Some(subcommand) if subcommand == LSP_SUBCOMMAND => {
+            serve(stdin().lock(), stdout().lock())?;
         }
 
         // [tag:shell_completion_subcommand]
@@ -398,3 +1060,143 @@ fn main() {
         exit(1);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::{diff_summary, emit_code, generate_command, shell_quote},
+        std::{
+            env,
+            fs::{create_dir_all, read_to_string, remove_dir_all, write},
+            path::{Path, PathBuf},
+            process,
+        },
+        typical::{generate_rust, generate_typescript},
+    };
+
+    // Create an empty directory for a test to write files in.
+    fn scratch_directory(name: &str) -> PathBuf {
+        let directory = env::temp_dir().join(format!("typical-{}-{name}", process::id()));
+        let _ = remove_dir_all(&directory);
+        create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    #[test]
+    fn diff_summary_up_to_date() {
+        assert_eq!(diff_summary("a\nb\nc\n", "a\nb\nc\n"), None);
+    }
+
+    #[test]
+    fn diff_summary_replaced() {
+        assert_eq!(
+            diff_summary("a\nb\nc\n", "a\nx\ny\nc\n").as_deref(),
+            Some("1 line at line 2 should be replaced with 2 lines."),
+        );
+    }
+
+    #[test]
+    fn diff_summary_missing_lines() {
+        assert_eq!(
+            diff_summary("a\nc\n", "a\nb\nc\n").as_deref(),
+            Some("missing 1 line at line 2."),
+        );
+    }
+
+    #[test]
+    fn diff_summary_extra_lines() {
+        assert_eq!(
+            diff_summary("a\nb\nc\n", "a\n").as_deref(),
+            Some("2 lines too many at line 2."),
+        );
+    }
+
+    #[test]
+    fn diff_summary_line_endings() {
+        assert_eq!(
+            diff_summary("a\r\nb\r\n", "a\nb\n").as_deref(),
+            Some("the line endings differ."),
+        );
+    }
+
+    #[test]
+    fn emit_code_up_to_date() {
+        let path = scratch_directory("emit_code_up_to_date").join("types.rs");
+        write(&path, "a\nb\n").unwrap();
+
+        assert_eq!(emit_code(&path, "a\nb\n", true).unwrap(), None);
+    }
+
+    #[test]
+    fn emit_code_stale() {
+        let path = scratch_directory("emit_code_stale").join("types.rs");
+        write(&path, "a\nb\n").unwrap();
+
+        assert_eq!(
+            emit_code(&path, "a\nc\n", true).unwrap(),
+            Some(format!(
+                "  `{}`: 1 line at line 2 should be replaced with 1 line.",
+                path.to_string_lossy(),
+            )),
+        );
+
+        // The file isn't changed in check mode.
+        assert_eq!(read_to_string(&path).unwrap(), "a\nb\n");
+    }
+
+    #[test]
+    fn emit_code_missing() {
+        let path = scratch_directory("emit_code_missing").join("types.rs");
+
+        assert_eq!(
+            emit_code(&path, "a\n", true).unwrap(),
+            Some(format!(
+                "  `{}`: the file is missing or unreadable.",
+                path.to_string_lossy(),
+            )),
+        );
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn emit_code_write() {
+        let path = scratch_directory("emit_code_write")
+            .join("generated")
+            .join("types.rs");
+
+        assert_eq!(emit_code(&path, "a\n", false).unwrap(), None);
+        assert_eq!(read_to_string(&path).unwrap(), "a\n");
+    }
+
+    #[test]
+    fn shell_quote_plain() {
+        assert_eq!(shell_quote("src/types-v2.rs"), "src/types-v2.rs");
+    }
+
+    #[test]
+    fn shell_quote_special_characters() {
+        assert_eq!(shell_quote(""), "''");
+        assert_eq!(shell_quote("my types.t"), "'my types.t'");
+        assert_eq!(shell_quote("$(rm -rf ~)"), "'$(rm -rf ~)'");
+        assert_eq!(shell_quote("it's.t"), "'it'\\''s.t'");
+    }
+
+    #[test]
+    fn generate_command_quotes_paths() {
+        let mut rust_options = generate_rust::Options::default();
+        rust_options.borrowed = true;
+
+        assert_eq!(
+            generate_command(
+                Path::new("my schemas/types.t"),
+                &[PathBuf::from("common")],
+                Some(Path::new("out dir/types.rs")),
+                &rust_options,
+                Some(Path::new("it's/types.ts")),
+                &generate_typescript::Options::default(),
+            ),
+            "typical generate 'my schemas/types.t' --include common --rust 'out dir/types.rs' \
+                --rust-borrowed --typescript 'it'\\''s/types.ts'",
+        );
+    }
+}
@@ -0,0 +1,954 @@
+use {
+    crate::{
+        error::{listing, throw, Error, SourceRange},
+        format::CodeStr,
+        identifier::Identifier,
+        schema,
+    },
+    std::{
+        collections::{BTreeMap, HashMap, VecDeque},
+        path::{Path, PathBuf},
+    },
+};
+
+// Generic declarations are compiled by monomorphization: every distinct instantiation of a generic
+// declaration (e.g., `Page<User>`) is replaced by an ordinary declaration (e.g., `PageUser`) in the
+// same file as the generic declaration, and the generic declarations themselves are dropped. The
+// rest of the pipeline (the code generators, the encoder, the decoder, and the compatibility
+// checker) thus never has to deal with type parameters [tag:monomorphization].
+//
+// This pass also hoists nested declarations to the top level [ref:nested_declarations] and
+// resolves references to newtypes. Each reference to a newtype is replaced by a `Newtype` type
+// which carries the underlying type, expressed in terms of the schema containing the reference.
+// This spares the rest of the pipeline from having to look up the underlying type to determine how
+// to encode the value [ref:newtype_encoding].
+//
+// The name of an instantiation is formed by joining the name of the generic declaration with the
+// names of the type arguments. For example, `Page<[{String: User}]>` becomes
+// `PageArrayMapStringUser`. Distinct instantiations which would receive the same name are reported
+// as errors, as are instantiations which would shadow an existing declaration.
+
+// A type in which every custom type has been resolved to the namespace of its declaration, every
+// instantiation of a generic declaration has been replaced by the corresponding monomorphic
+// declaration, and every newtype has been paired with its underlying type
+#[derive(Clone)]
+enum ResolvedType {
+    Array(Box<ResolvedType>, Option<usize>), // (element type, fixed length)
+    Custom(schema::Namespace, Identifier),
+    Map(Box<ResolvedType>, Box<ResolvedType>), // (key, value)
+    Newtype(schema::Namespace, Identifier, Box<ResolvedType>), // (.., .., underlying type)
+    Primitive(schema::TypeVariant),            // Any variant other than `Array`, `Custom`, or `Map`
+}
+
+impl ResolvedType {
+    // This function returns the fragment of an instantiation name that corresponds to this type.
+    fn mangle(&self) -> String {
+        match self {
+            Self::Array(inner_type, None) => format!("Array_{}", inner_type.mangle()),
+            Self::Array(inner_type, Some(length)) => {
+                format!("Array_{length}_{}", inner_type.mangle())
+            }
+            Self::Custom(_, name) | Self::Newtype(_, name, _) => name.original().to_owned(),
+            Self::Map(key_type, value_type) => {
+                format!("Map_{}_{}", key_type.mangle(), value_type.mangle())
+            }
+            Self::Primitive(schema::TypeVariant::Bytes(Some(length))) => format!("Bytes_{length}"),
+            Self::Primitive(variant) => variant.to_string(),
+        }
+    }
+
+    // This function returns an unambiguous description of this type, for use in error messages.
+    fn describe(&self) -> String {
+        match self {
+            Self::Array(inner_type, None) => format!("[{}]", inner_type.describe()),
+            Self::Array(inner_type, Some(length)) => {
+                format!("[{}; {length}]", inner_type.describe())
+            }
+            Self::Custom(namespace, name) | Self::Newtype(namespace, name, _) => {
+                format!("{}.{}", namespace, name.pascal_case())
+            }
+            Self::Map(key_type, value_type) => {
+                format!("{{{}: {}}}", key_type.describe(), value_type.describe())
+            }
+            Self::Primitive(variant) => variant.to_string(),
+        }
+    }
+}
+
+// An instantiation of a generic declaration which has yet to be generated
+struct Instantiation<'a> {
+    namespace: schema::Namespace,
+    name: Identifier,
+    declaration: &'a schema::Declaration,
+    substitution: HashMap<Identifier, ResolvedType>,
+}
+
+// The state threaded through the monomorphization of a set of schemas
+struct Context<'a> {
+    schemas: &'a BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>,
+    declarations: HashMap<(schema::Namespace, Identifier), &'a schema::Declaration>,
+    instantiations: HashMap<(schema::Namespace, Identifier), String>, // Values are descriptions.
+    pending: VecDeque<Instantiation<'a>>,
+    errors: Vec<Error>,
+}
+
+// This function replaces the generic declarations in a set of schemas with their instantiations.
+// The schemas are expected to be valid, except that this function reports the naming conflicts
+// described above.
+#[allow(clippy::too_many_lines, clippy::type_complexity)]
+pub fn monomorphize(
+    schemas: &BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>,
+) -> Result<BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>, Vec<Error>> {
+    // Hoist the nested declarations to the top level [ref:nested_declarations].
+    let schemas = &schemas
+        .iter()
+        .map(|(namespace, (schema, source_path, source_contents))| {
+            (
+                namespace.clone(),
+                (
+                    schema::flatten(schema),
+                    source_path.clone(),
+                    source_contents.clone(),
+                ),
+            )
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    // Construct a map from (namespace, name) to declaration.
+    let mut context = Context {
+        schemas,
+        declarations: HashMap::new(),
+        instantiations: HashMap::new(),
+        pending: VecDeque::new(),
+        errors: vec![],
+    };
+    for (namespace, (schema, _, _)) in schemas {
+        for declaration in &schema.declarations {
+            context
+                .declarations
+                .insert((namespace.clone(), declaration.name.clone()), declaration);
+        }
+    }
+
+    // Copy the non-generic declarations and the services, instantiating any generic declarations
+    // they refer to.
+    let mut monomorphized_schemas = BTreeMap::new();
+    for (namespace, (schema, source_path, source_contents)) in schemas {
+        let mut monomorphized_schema = schema::Schema {
+            comment: schema.comment.clone(),
+            imports: schema.imports.clone(),
+            constants: schema.constants.clone(),
+            declarations: vec![],
+            services: vec![],
+        };
+
+        for declaration in &schema.declarations {
+            if declaration.parameters.is_empty() {
+                let monomorphized_declaration = monomorphize_declaration(
+                    &mut context,
+                    namespace,
+                    schema,
+                    &mut monomorphized_schema.imports,
+                    source_path,
+                    source_contents,
+                    declaration,
+                    &declaration.name,
+                    &HashMap::new(),
+                );
+
+                monomorphized_schema
+                    .declarations
+                    .push(monomorphized_declaration);
+            }
+        }
+
+        for service in &schema.services {
+            let methods = service
+                .methods
+                .iter()
+                .map(|method| schema::Method {
+                    input_type: monomorphize_type(
+                        &mut context,
+                        namespace,
+                        schema,
+                        &mut monomorphized_schema.imports,
+                        source_path,
+                        source_contents,
+                        &HashMap::new(),
+                        &method.input_type,
+                    ),
+                    output_type: monomorphize_type(
+                        &mut context,
+                        namespace,
+                        schema,
+                        &mut monomorphized_schema.imports,
+                        source_path,
+                        source_contents,
+                        &HashMap::new(),
+                        &method.output_type,
+                    ),
+                    ..method.clone()
+                })
+                .collect();
+
+            monomorphized_schema.services.push(schema::Service {
+                methods,
+                ..service.clone()
+            });
+        }
+
+        monomorphized_schemas.insert(
+            namespace.clone(),
+            (
+                monomorphized_schema,
+                source_path.clone(),
+                source_contents.clone(),
+            ),
+        );
+    }
+
+    // Generate the instantiations. Doing so may discover more instantiations, but the process
+    // terminates since the validator rejects cycles, including those through type arguments.
+    while let Some(instantiation) = context.pending.pop_front() {
+        // The `unwrap`s are safe since instantiations are only created for declarations in the
+        // given schemas.
+        let (schema, source_path, source_contents) = schemas.get(&instantiation.namespace).unwrap();
+        let (monomorphized_schema, _, _) = monomorphized_schemas
+            .get_mut(&instantiation.namespace)
+            .unwrap();
+
+        let monomorphized_declaration = monomorphize_declaration(
+            &mut context,
+            &instantiation.namespace,
+            schema,
+            &mut monomorphized_schema.imports,
+            source_path,
+            source_contents,
+            instantiation.declaration,
+            &instantiation.name,
+            &instantiation.substitution,
+        );
+
+        monomorphized_schema
+            .declarations
+            .push(monomorphized_declaration);
+    }
+
+    // Return the monomorphized schemas or report any errors.
+    if context.errors.is_empty() {
+        Ok(monomorphized_schemas)
+    } else {
+        Err(context.errors)
+    }
+}
+
+// This function constructs a declaration with the given name from a (possibly generic) declaration
+// such that it no longer refers to type parameters, generic declarations, or newtypes.
+#[allow(clippy::too_many_arguments)]
+fn monomorphize_declaration(
+    context: &mut Context,
+    namespace: &schema::Namespace,
+    schema: &schema::Schema,
+    imports: &mut BTreeMap<Identifier, schema::Import>,
+    source_path: &Path,
+    source_contents: &str,
+    declaration: &schema::Declaration,
+    name: &Identifier,
+    substitution: &HashMap<Identifier, ResolvedType>,
+) -> schema::Declaration {
+    let variant = match &declaration.variant {
+        schema::DeclarationVariant::Struct => schema::DeclarationVariant::Struct,
+        schema::DeclarationVariant::Choice => schema::DeclarationVariant::Choice,
+        schema::DeclarationVariant::Newtype(underlying_type) => {
+            schema::DeclarationVariant::Newtype(monomorphize_type(
+                context,
+                namespace,
+                schema,
+                imports,
+                source_path,
+                source_contents,
+                substitution,
+                underlying_type,
+            ))
+        }
+    };
+
+    let fields = declaration
+        .fields
+        .iter()
+        .map(|field| schema::Field {
+            r#type: monomorphize_type(
+                context,
+                namespace,
+                schema,
+                imports,
+                source_path,
+                source_contents,
+                substitution,
+                &field.r#type,
+            ),
+            ..field.clone()
+        })
+        .collect();
+
+    schema::Declaration {
+        source_range: declaration.source_range,
+        comment: declaration.comment.clone(),
+        attributes: declaration.attributes.clone(),
+        variant,
+        name: name.clone(),
+        parameters: vec![],
+        fields,
+        declarations: vec![],
+        deleted: declaration.deleted.clone(),
+    }
+}
+
+// This function rewrites a type such that it no longer refers to type parameters, generic
+// declarations, or newtypes.
+#[allow(clippy::too_many_arguments)]
+fn monomorphize_type(
+    context: &mut Context,
+    namespace: &schema::Namespace,
+    schema: &schema::Schema,
+    imports: &mut BTreeMap<Identifier, schema::Import>,
+    source_path: &Path,
+    source_contents: &str,
+    substitution: &HashMap<Identifier, ResolvedType>,
+    r#type: &schema::Type,
+) -> schema::Type {
+    // Types which don't need to be resolved are left alone to preserve their source ranges.
+    if needs_resolution(context, namespace, schema, substitution, r#type) {
+        let resolved_type = resolve_type(
+            context,
+            namespace,
+            schema,
+            source_path,
+            source_contents,
+            substitution,
+            r#type,
+        );
+
+        localize_type(namespace, imports, r#type.source_range, &resolved_type)
+    } else {
+        r#type.clone()
+    }
+}
+
+// This function determines whether a type refers to a type parameter, a generic declaration, or a
+// newtype.
+fn needs_resolution(
+    context: &Context,
+    namespace: &schema::Namespace,
+    schema: &schema::Schema,
+    substitution: &HashMap<Identifier, ResolvedType>,
+    r#type: &schema::Type,
+) -> bool {
+    match &r#type.variant {
+        schema::TypeVariant::Array(inner_type, _) => {
+            needs_resolution(context, namespace, schema, substitution, inner_type)
+        }
+        schema::TypeVariant::Custom(import, name, arguments) => {
+            if !arguments.is_empty() || (import.is_none() && substitution.contains_key(name)) {
+                return true;
+            }
+
+            // The `unwrap` is safe due to the validity of the schemas.
+            let declaration = context
+                .declarations
+                .get(&(
+                    declaration_namespace(namespace, schema, import.as_ref()),
+                    name.clone(),
+                ))
+                .unwrap();
+
+            matches!(declaration.variant, schema::DeclarationVariant::Newtype(_))
+        }
+        schema::TypeVariant::Map(key_type, value_type) => {
+            needs_resolution(context, namespace, schema, substitution, key_type)
+                || needs_resolution(context, namespace, schema, substitution, value_type)
+        }
+        schema::TypeVariant::Bool
+        | schema::TypeVariant::Bytes(_)
+        | schema::TypeVariant::F32
+        | schema::TypeVariant::F64
+        | schema::TypeVariant::Newtype(_, _, _)
+        | schema::TypeVariant::S32
+        | schema::TypeVariant::S64
+        | schema::TypeVariant::String
+        | schema::TypeVariant::U32
+        | schema::TypeVariant::U64
+        | schema::TypeVariant::U8
+        | schema::TypeVariant::Unit => false,
+    }
+}
+
+// This function determines which file a custom type which appears in the given schema is from.
+fn declaration_namespace(
+    namespace: &schema::Namespace,
+    schema: &schema::Schema,
+    import: Option<&Identifier>,
+) -> schema::Namespace {
+    // The first `unwrap` is safe due to the validity of the schemas, and the second is safe due to
+    // [ref:namespace_populated].
+    import.map_or_else(
+        || namespace.clone(),
+        |import| {
+            schema
+                .imports
+                .get(import)
+                .unwrap()
+                .namespace
+                .clone()
+                .unwrap()
+        },
+    )
+}
+
+// This function resolves a type which appears in the given schema, instantiating any generic
+// declarations it refers to.
+#[allow(clippy::too_many_lines)]
+fn resolve_type(
+    context: &mut Context,
+    namespace: &schema::Namespace,
+    schema: &schema::Schema,
+    source_path: &Path,
+    source_contents: &str,
+    substitution: &HashMap<Identifier, ResolvedType>,
+    r#type: &schema::Type,
+) -> ResolvedType {
+    match &r#type.variant {
+        schema::TypeVariant::Array(inner_type, length) => ResolvedType::Array(
+            Box::new(resolve_type(
+                context,
+                namespace,
+                schema,
+                source_path,
+                source_contents,
+                substitution,
+                inner_type,
+            )),
+            *length,
+        ),
+        schema::TypeVariant::Custom(import, name, arguments) => {
+            // Type parameters are replaced by their arguments.
+            if import.is_none() {
+                if let Some(resolved_type) = substitution.get(name) {
+                    return resolved_type.clone();
+                }
+            }
+
+            // Determine which file the type is from.
+            let type_namespace = declaration_namespace(namespace, schema, import.as_ref());
+
+            // Resolve the type arguments, if there are any.
+            let resolved_arguments = arguments
+                .iter()
+                .map(|argument| {
+                    resolve_type(
+                        context,
+                        namespace,
+                        schema,
+                        source_path,
+                        source_contents,
+                        substitution,
+                        argument,
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            // The `unwrap` is safe due to the validity of the schemas.
+            let declaration = *context
+                .declarations
+                .get(&(type_namespace.clone(), name.clone()))
+                .unwrap();
+
+            // Determine the name of the monomorphic declaration.
+            let monomorphic_name = if resolved_arguments.is_empty() {
+                name.clone()
+            } else {
+                instantiate(
+                    context,
+                    &type_namespace,
+                    name,
+                    resolved_arguments.clone(),
+                    source_path,
+                    source_contents,
+                    r#type.source_range,
+                )
+            };
+
+            // Newtypes are paired with their underlying types, which are resolved in the context
+            // of the newtype declarations.
+            if let schema::DeclarationVariant::Newtype(underlying_type) = &declaration.variant {
+                // The `unwrap` is safe since the declaration is from one of the given schemas.
+                let (type_schema, type_source_path, type_source_contents) =
+                    context.schemas.get(&type_namespace).unwrap();
+
+                let resolved_underlying_type = resolve_type(
+                    context,
+                    &type_namespace,
+                    type_schema,
+                    type_source_path,
+                    type_source_contents,
+                    &declaration
+                        .parameters
+                        .iter()
+                        .cloned()
+                        .zip(resolved_arguments)
+                        .collect(),
+                    underlying_type,
+                );
+
+                ResolvedType::Newtype(
+                    type_namespace,
+                    monomorphic_name,
+                    Box::new(resolved_underlying_type),
+                )
+            } else {
+                ResolvedType::Custom(type_namespace, monomorphic_name)
+            }
+        }
+        schema::TypeVariant::Map(key_type, value_type) => ResolvedType::Map(
+            Box::new(resolve_type(
+                context,
+                namespace,
+                schema,
+                source_path,
+                source_contents,
+                substitution,
+                key_type,
+            )),
+            Box::new(resolve_type(
+                context,
+                namespace,
+                schema,
+                source_path,
+                source_contents,
+                substitution,
+                value_type,
+            )),
+        ),
+        schema::TypeVariant::Newtype(_, _, _) => {
+            // Impossible since references to newtypes are only resolved by this module.
+            panic!()
+        }
+        variant @ (schema::TypeVariant::Bool
+        | schema::TypeVariant::Bytes(_)
+        | schema::TypeVariant::F32
+        | schema::TypeVariant::F64
+        | schema::TypeVariant::S32
+        | schema::TypeVariant::S64
+        | schema::TypeVariant::String
+        | schema::TypeVariant::U32
+        | schema::TypeVariant::U64
+        | schema::TypeVariant::U8
+        | schema::TypeVariant::Unit) => ResolvedType::Primitive(variant.clone()),
+    }
+}
+
+// This function returns the name of the declaration for an instantiation of a generic declaration,
+// scheduling the declaration to be generated if this is the first time it's been encountered.
+fn instantiate(
+    context: &mut Context,
+    namespace: &schema::Namespace,
+    name: &Identifier,
+    arguments: Vec<ResolvedType>,
+    source_path: &Path,
+    source_contents: &str,
+    source_range: SourceRange,
+) -> Identifier {
+    // The `unwrap` is safe due to the validity of the schemas.
+    let declaration = *context
+        .declarations
+        .get(&(namespace.clone(), name.clone()))
+        .unwrap();
+
+    // Compute the name and description of the instantiation.
+    let instantiation_name: Identifier = format!(
+        "{}_{}",
+        declaration.name.original(),
+        arguments
+            .iter()
+            .map(ResolvedType::mangle)
+            .collect::<Vec<_>>()
+            .join("_"),
+    )
+    .as_str()
+    .into();
+    let description = format!(
+        "{}.{}<{}>",
+        namespace,
+        declaration.name.pascal_case(),
+        arguments
+            .iter()
+            .map(ResolvedType::describe)
+            .collect::<Vec<_>>()
+            .join(", "),
+    );
+
+    // Check whether we've seen this instantiation or another one with the same name.
+    let key = (namespace.clone(), instantiation_name.clone());
+    if let Some(existing_description) = context.instantiations.get(&key) {
+        if *existing_description != description {
+            context.errors.push(throw::<Error>(
+                &format!(
+                    "{} and {} would both be named {}.",
+                    description.code_str(),
+                    existing_description.code_str(),
+                    instantiation_name.pascal_case().code_str(),
+                ),
+                Some(source_path),
+                Some(&listing(source_contents, source_range)),
+                None,
+            ));
+        }
+
+        return instantiation_name;
+    }
+
+    context
+        .instantiations
+        .insert(key.clone(), description.clone());
+
+    // Make sure the instantiation doesn't shadow an existing declaration.
+    if context.declarations.contains_key(&key) {
+        context.errors.push(throw::<Error>(
+            &format!(
+                "{} would be named {}, but a declaration with that name already exists in {}.",
+                description.code_str(),
+                instantiation_name.pascal_case().code_str(),
+                namespace.to_string().code_str(),
+            ),
+            Some(source_path),
+            Some(&listing(source_contents, source_range)),
+            None,
+        ));
+
+        return instantiation_name;
+    }
+
+    // Schedule the instantiation to be generated.
+    context.pending.push_back(Instantiation {
+        namespace: namespace.clone(),
+        name: instantiation_name.clone(),
+        declaration,
+        substitution: declaration
+            .parameters
+            .iter()
+            .cloned()
+            .zip(arguments)
+            .collect(),
+    });
+
+    instantiation_name
+}
+
+// This function converts a resolved type into a type which can appear in the schema for the given
+// namespace, adding imports to the schema as needed.
+fn localize_type(
+    namespace: &schema::Namespace,
+    imports: &mut BTreeMap<Identifier, schema::Import>,
+    source_range: SourceRange,
+    resolved_type: &ResolvedType,
+) -> schema::Type {
+    let variant = match resolved_type {
+        ResolvedType::Array(inner_type, length) => schema::TypeVariant::Array(
+            Box::new(localize_type(namespace, imports, source_range, inner_type)),
+            *length,
+        ),
+        ResolvedType::Custom(type_namespace, name) => schema::TypeVariant::Custom(
+            localize_namespace(namespace, imports, source_range, type_namespace),
+            name.clone(),
+            vec![],
+        ),
+        ResolvedType::Map(key_type, value_type) => schema::TypeVariant::Map(
+            Box::new(localize_type(namespace, imports, source_range, key_type)),
+            Box::new(localize_type(namespace, imports, source_range, value_type)),
+        ),
+        ResolvedType::Newtype(type_namespace, name, underlying_type) => {
+            schema::TypeVariant::Newtype(
+                localize_namespace(namespace, imports, source_range, type_namespace),
+                name.clone(),
+                Box::new(localize_type(
+                    namespace,
+                    imports,
+                    source_range,
+                    underlying_type,
+                )),
+            )
+        }
+        ResolvedType::Primitive(variant) => variant.clone(),
+    };
+
+    schema::Type {
+        source_range,
+        variant,
+    }
+}
+
+// This function returns the import which refers to a namespace from the schema for the given
+// namespace, adding an import to the schema if needed. No import is needed to refer to the schema
+// itself.
+fn localize_namespace(
+    namespace: &schema::Namespace,
+    imports: &mut BTreeMap<Identifier, schema::Import>,
+    source_range: SourceRange,
+    type_namespace: &schema::Namespace,
+) -> Option<Identifier> {
+    if type_namespace == namespace {
+        None
+    } else if let Some((import, _)) = imports
+        .iter()
+        .find(|(_, import)| import.namespace.as_ref() == Some(type_namespace))
+    {
+        Some(import.clone())
+    } else {
+        // Identifiers in schemas can't contain `$`, so this import can't conflict with the imports
+        // written by the user.
+        let import: Identifier = format!("${type_namespace}").as_str().into();
+
+        imports.insert(
+            import.clone(),
+            schema::Import {
+                source_range,
+                path: PathBuf::new(),
+                namespace: Some(type_namespace.clone()),
+            },
+        );
+
+        Some(import)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::{
+            assert_fails,
+            monomorphizer::monomorphize,
+            parser::parse,
+            schema::{Namespace, TypeVariant},
+            tokenizer::tokenize,
+        },
+        std::{collections::BTreeMap, fmt::Write, path::Path},
+    };
+
+    #[test]
+    fn monomorphize_example() {
+        let foo_namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let foo_path = Path::new("foo.t").to_owned();
+        let foo_contents = "
+            import 'bar.t'
+
+            struct Foo {
+                x: bar.Page<User> = 0
+                y: bar.Page<bar.Page<[U64]>> = 1
+                z: Maybe<bar.Page<User>> = 2
+            }
+
+            choice Maybe<T> {
+                some: T = 0
+                none = 1
+            }
+
+            struct User {
+            }
+        "
+        .to_owned();
+
+        let bar_namespace = Namespace {
+            components: vec!["bar".into()],
+        };
+        let bar_path = Path::new("bar.t").to_owned();
+        let bar_contents = "
+            struct Page<T> {
+                items: [T] = 0
+                next: String = 1
+            }
+        "
+        .to_owned();
+
+        let foo_tokens = tokenize(&foo_path, &foo_contents).unwrap();
+        let mut foo_schema = parse(&foo_path, &foo_contents, &foo_tokens).unwrap();
+        foo_schema.imports.get_mut(&"bar".into()).unwrap().namespace = Some(bar_namespace.clone());
+
+        let bar_tokens = tokenize(&bar_path, &bar_contents).unwrap();
+        let bar_schema = parse(&bar_path, &bar_contents, &bar_tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(foo_namespace.clone(), (foo_schema, foo_path, foo_contents));
+        schemas.insert(bar_namespace.clone(), (bar_schema, bar_path, bar_contents));
+
+        let monomorphized_schemas = monomorphize(&schemas).unwrap();
+
+        let expected_foo = "\
+            import 'bar.t'\n\
+            \n\
+            struct Foo {\n\
+            \x20   x: bar.PageUser = 0\n\
+            \x20   y: bar.PagePageArrayU64 = 1\n\
+            \x20   z: MaybePageUser = 2\n\
+            }\n\
+            \n\
+            struct User {\n\
+            }\n\
+            \n\
+            choice MaybePageUser {\n\
+            \x20   some: bar.PageUser = 0\n\
+            \x20   none = 1\n\
+            }\n\
+        ";
+
+        let expected_bar = "\
+            import '' as $foo\n\
+            \n\
+            struct PageUser {\n\
+            \x20   items: [$foo.User] = 0\n\
+            \x20   next: String = 1\n\
+            }\n\
+            \n\
+            struct PageArrayU64 {\n\
+            \x20   items: [[U64]] = 0\n\
+            \x20   next: String = 1\n\
+            }\n\
+            \n\
+            struct PagePageArrayU64 {\n\
+            \x20   items: [PageArrayU64] = 0\n\
+            \x20   next: String = 1\n\
+            }\n\
+        ";
+
+        assert_eq!(
+            monomorphized_schemas[&foo_namespace].0.to_string(),
+            expected_foo,
+        );
+        assert_eq!(
+            monomorphized_schemas[&bar_namespace].0.to_string(),
+            expected_bar,
+        );
+    }
+
+    #[test]
+    fn monomorphize_newtype() {
+        let foo_namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let foo_path = Path::new("foo.t").to_owned();
+        let foo_contents = "
+            import 'bar.t'
+
+            struct Foo {
+                x: bar.Ids<User> = 0
+            }
+
+            struct User {
+            }
+        "
+        .to_owned();
+
+        let bar_namespace = Namespace {
+            components: vec!["bar".into()],
+        };
+        let bar_path = Path::new("bar.t").to_owned();
+        let bar_contents = "
+            newtype Ids<T> = [T]
+        "
+        .to_owned();
+
+        let foo_tokens = tokenize(&foo_path, &foo_contents).unwrap();
+        let mut foo_schema = parse(&foo_path, &foo_contents, &foo_tokens).unwrap();
+        foo_schema.imports.get_mut(&"bar".into()).unwrap().namespace = Some(bar_namespace.clone());
+
+        let bar_tokens = tokenize(&bar_path, &bar_contents).unwrap();
+        let bar_schema = parse(&bar_path, &bar_contents, &bar_tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(foo_namespace.clone(), (foo_schema, foo_path, foo_contents));
+        schemas.insert(bar_namespace.clone(), (bar_schema, bar_path, bar_contents));
+
+        let monomorphized_schemas = monomorphize(&schemas).unwrap();
+
+        let expected_foo = "\
+            import 'bar.t'\n\
+            \n\
+            struct Foo {\n\
+            \x20   x: bar.IdsUser = 0\n\
+            }\n\
+            \n\
+            struct User {\n\
+            }\n\
+        ";
+
+        let expected_bar = "\
+            import '' as $foo\n\
+            \n\
+            newtype IdsUser = [$foo.User]\n\
+        ";
+
+        assert_eq!(
+            monomorphized_schemas[&foo_namespace].0.to_string(),
+            expected_foo,
+        );
+        assert_eq!(
+            monomorphized_schemas[&bar_namespace].0.to_string(),
+            expected_bar,
+        );
+
+        // The reference to the newtype carries its underlying type, relative to the referring
+        // schema.
+        let TypeVariant::Newtype(_, _, underlying_type) =
+            &monomorphized_schemas[&foo_namespace].0.declarations[0].fields[0]
+                .r#type
+                .variant
+        else {
+            panic!("Expected a newtype.");
+        };
+        assert_eq!(underlying_type.to_string(), "[User]");
+    }
+
+    #[test]
+    fn monomorphize_ambiguous_names() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            struct Pair<A, B> {
+                x: A = 0
+                y: B = 1
+            }
+
+            struct Foo {
+                x: Pair<Bar, BazQux> = 0
+                y: Pair<BarBaz, Qux> = 1
+            }
+
+            struct Bar {
+            }
+
+            struct BarBaz {
+            }
+
+            struct BazQux {
+            }
+
+            struct Qux {
+            }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            monomorphize(&schemas),
+            "`foo.Pair<foo.BarBaz, foo.Qux>` and `foo.Pair<foo.Bar, foo.BazQux>` would both be \
+                named `PairBarBazQux`.",
+        );
+    }
+}
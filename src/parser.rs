@@ -200,7 +200,7 @@ pub fn parse(
     // Try to parse the tokens into a schema.
     let mut position = 0;
     let mut errors = vec![];
-    let schema = parse_schema(
+    let mut schema = parse_schema(
         source_path,
         source_contents,
         tokens,
@@ -224,12 +224,93 @@ pub fn parse(
 
     // If there are no errors, return the schema. Otherwise, report the errors.
     if errors.is_empty() {
+        resolve_qualifiers(&mut schema);
+
         Ok(schema)
     } else {
         Err(errors)
     }
 }
 
+// The parser treats the first component of a qualified type name (e.g., `foo` in `foo.Bar`) as the
+// name of an import [ref:parse_type_qualifier], since the imports aren't known until the whole file
+// has been parsed. This function finds the qualifiers which aren't the names of imports and merges
+// them into the type names, since such types refer to nested declarations in the same file (e.g.,
+// `Device.IpAddress`) [ref:nested_declarations].
+fn resolve_qualifiers(schema: &mut schema::Schema) {
+    let imports = &schema.imports;
+
+    for constant in &mut schema.constants {
+        resolve_type_qualifiers(imports, &mut constant.r#type);
+    }
+
+    for declaration in &mut schema.declarations {
+        resolve_declaration_qualifiers(imports, declaration);
+    }
+
+    for service in &mut schema.services {
+        for method in &mut service.methods {
+            resolve_type_qualifiers(imports, &mut method.input_type);
+            resolve_type_qualifiers(imports, &mut method.output_type);
+        }
+    }
+}
+
+// Apply `resolve_qualifiers` to a declaration and its nested declarations.
+fn resolve_declaration_qualifiers(
+    imports: &BTreeMap<Identifier, schema::Import>,
+    declaration: &mut schema::Declaration,
+) {
+    if let schema::DeclarationVariant::Newtype(underlying_type) = &mut declaration.variant {
+        resolve_type_qualifiers(imports, underlying_type);
+    }
+
+    for field in &mut declaration.fields {
+        resolve_type_qualifiers(imports, &mut field.r#type);
+    }
+
+    for nested_declaration in &mut declaration.declarations {
+        resolve_declaration_qualifiers(imports, nested_declaration);
+    }
+}
+
+// Apply `resolve_qualifiers` to a type.
+fn resolve_type_qualifiers(
+    imports: &BTreeMap<Identifier, schema::Import>,
+    r#type: &mut schema::Type,
+) {
+    match &mut r#type.variant {
+        schema::TypeVariant::Array(inner_type, _) => {
+            resolve_type_qualifiers(imports, inner_type);
+        }
+        schema::TypeVariant::Custom(import, name, arguments) => {
+            for argument in arguments {
+                resolve_type_qualifiers(imports, argument);
+            }
+
+            if let Some(qualifier) = import.take_if(|import| !imports.contains_key(import)) {
+                *name = qualifier.qualify(name);
+            }
+        }
+        schema::TypeVariant::Map(key_type, value_type) => {
+            resolve_type_qualifiers(imports, key_type);
+            resolve_type_qualifiers(imports, value_type);
+        }
+        schema::TypeVariant::Bool
+        | schema::TypeVariant::Bytes(_)
+        | schema::TypeVariant::F32
+        | schema::TypeVariant::F64
+        | schema::TypeVariant::Newtype(_, _, _)
+        | schema::TypeVariant::S32
+        | schema::TypeVariant::S64
+        | schema::TypeVariant::String
+        | schema::TypeVariant::U32
+        | schema::TypeVariant::U64
+        | schema::TypeVariant::U8
+        | schema::TypeVariant::Unit => {}
+    }
+}
+
 // Parse a schema.
 #[allow(clippy::too_many_lines)]
 fn parse_schema(
@@ -241,14 +322,21 @@ fn parse_schema(
 ) -> schema::Schema {
     let mut comment = vec![];
     let mut imports = BTreeMap::new();
+    let mut constants = vec![];
     let mut declarations = vec![];
+    let mut services = vec![];
 
     // Parse the comment, if it exists.
     if *position < tokens.len() {
         if let token::Variant::Comment(paragraphs) = &tokens[*position].variant {
             if *position + 1 < tokens.len() {
                 match tokens[*position + 1].variant {
-                    token::Variant::Struct | token::Variant::Choice => {
+                    token::Variant::At
+                    | token::Variant::Struct
+                    | token::Variant::Choice
+                    | token::Variant::Newtype
+                    | token::Variant::Const
+                    | token::Variant::Service => {
                         if tokens[*position].source_range.end + 1
                             < tokens[*position + 1].source_range.start
                         {
@@ -296,7 +384,7 @@ fn parse_schema(
         }
     }
 
-    // Parse the declarations.
+    // Parse the constants, declarations, and services.
     while *position < tokens.len() {
         let start = *position;
 
@@ -311,165 +399,121 @@ fn parse_schema(
             vec![]
         };
 
-        // Parse the keyword [tag:keyword_is_struct_or_choice].
-        let keyword =
-            if let token::Variant::Struct | token::Variant::Choice = tokens[*position].variant {
-                tokens[*position].variant.clone()
-            } else {
-                errors.push(unexpected_token(
-                    source_path,
-                    source_contents,
-                    tokens,
-                    *position,
-                    "a declaration",
-                ));
-
-                return schema::Schema {
-                    comment,
-                    imports,
-                    declarations,
-                };
-            };
-        *position += 1;
-
-        // Parse the name.
-        let name = consume_token_1!(
-            source_path,
-            source_contents,
-            tokens,
-            &mut *position,
-            errors,
-            Identifier,
-            &format!(
-                "a name for the {}",
-                match keyword {
-                    token::Variant::Struct => "struct",
-                    token::Variant::Choice => "choice",
-                    _ => {
-                        // Impossible due to [ref:keyword_is_struct_or_choice].
-                        panic!()
-                    }
-                },
-            ),
-            schema::Schema {
-                comment,
-                imports,
-                declarations,
-            },
-        );
-
-        // Consume the `{`.
-        consume_token_0!(
-            source_path,
-            source_contents,
-            tokens,
-            &mut *position,
-            errors,
-            LeftCurly,
-            schema::Schema {
+        // Parse the attributes, if there are any.
+        let Some(attributes) =
+            parse_attributes(source_path, source_contents, tokens, position, errors)
+        else {
+            return schema::Schema {
                 comment,
                 imports,
+                constants,
                 declarations,
-            },
-        );
-
-        // Parse the fields.
-        let mut fields = vec![];
-        while *position < tokens.len() {
-            match tokens[*position].variant {
-                token::Variant::Deleted | token::Variant::RightCurly => break,
-                _ => {}
-            }
+                services,
+            };
+        };
 
-            if let Some(field) = parse_field(source_path, source_contents, tokens, position, errors)
-            {
-                // In this case, [ref:parse_field_some_advance] guarantees that we will not
-                // loop forever.
-                fields.push(field.clone());
-            } else {
-                // Jump to the closing curly brace, if it exists. Otherwise, jump to the
-                // end of the source.
-                while *position < tokens.len() {
-                    if let token::Variant::RightCurly = tokens[*position].variant {
-                        break;
-                    }
+        // Only declarations can have attributes.
+        if !attributes.is_empty()
+            && *position < tokens.len()
+            && matches!(
+                tokens[*position].variant,
+                token::Variant::Const | token::Variant::Service,
+            )
+        {
+            errors.push(throw::<Error>(
+                "Attributes can only be applied to declarations and fields.",
+                Some(source_path),
+                Some(&listing(
+                    source_contents,
+                    span_tokens(tokens, start, *position),
+                )),
+                None,
+            ));
+        }
 
-                    *position += 1;
-                }
+        // Parse a constant, if that's what comes next.
+        if *position < tokens.len() && matches!(tokens[*position].variant, token::Variant::Const) {
+            if let Some(constant) = parse_constant(
+                source_path,
+                source_contents,
+                tokens,
+                position,
+                errors,
+                start,
+                declaration_comment,
+            ) {
+                constants.push(constant);
 
-                break;
+                continue;
             }
+
+            return schema::Schema {
+                comment,
+                imports,
+                constants,
+                declarations,
+                services,
+            };
         }
 
-        // Parse the set of deleted fields.
-        let mut deleted = BTreeSet::new();
-        if *position != tokens.len() && matches!(tokens[*position].variant, token::Variant::Deleted)
+        // Parse a service, if that's what comes next.
+        if *position < tokens.len() && matches!(tokens[*position].variant, token::Variant::Service)
         {
-            *position += 1;
-
-            while *position != tokens.len() {
-                if let index_token @ token::Variant::Integer(index) = &tokens[*position].variant {
-                    if !deleted.insert(*index) {
-                        errors.push(throw::<Error>(
-                            &format!(
-                                "Index {} is already marked as deleted.",
-                                index_token.to_string().code_str(),
-                            ),
-                            Some(source_path),
-                            Some(&listing(
-                                source_contents,
-                                token_source_range(tokens, *position),
-                            )),
-                            None,
-                        ));
-                    }
+            if let Some(service) = parse_service(
+                source_path,
+                source_contents,
+                tokens,
+                position,
+                errors,
+                start,
+                declaration_comment,
+            ) {
+                services.push(service);
 
-                    *position += 1;
-                } else {
-                    break;
-                }
+                continue;
             }
-        };
 
-        // Consume the `}`.
-        consume_token_0!(
+            return schema::Schema {
+                comment,
+                imports,
+                constants,
+                declarations,
+                services,
+            };
+        }
+
+        // Otherwise, parse a declaration.
+        if let Some(declaration) = parse_declaration(
             source_path,
             source_contents,
             tokens,
-            &mut *position,
+            position,
             errors,
-            RightCurly,
-            schema::Schema {
-                comment,
-                imports,
-                declarations,
-            },
-        );
+            start,
+            declaration_comment,
+            attributes,
+        ) {
+            declarations.push(declaration);
 
-        // Construct the declaration.
-        let source_range = span_tokens(tokens, start, *position);
-        declarations.push(schema::Declaration {
-            source_range,
-            comment: declaration_comment,
-            variant: match keyword {
-                token::Variant::Struct => schema::DeclarationVariant::Struct,
-                token::Variant::Choice => schema::DeclarationVariant::Choice,
-                _ => {
-                    // Impossible due to [ref:keyword_is_struct_or_choice].
-                    panic!()
-                }
-            },
-            name,
-            fields,
-            deleted,
-        });
+            continue;
+        }
+
+        return schema::Schema {
+            comment,
+            imports,
+            constants,
+            declarations,
+            services,
+        };
     }
 
     // Construct and return the schema.
     schema::Schema {
         comment,
         imports,
+        constants,
         declarations,
+        services,
     }
 }
 
@@ -568,47 +612,52 @@ fn parse_import(
     ))
 }
 
-// Parse a field. If this function returns `None`, then at least one error was added to `errors`.
-// Otherwise, the `position` is guaranteed to have advanced [tag:parse_field_some_advance].
-#[allow(clippy::too_many_lines)]
-fn parse_field(
+// Parse a declaration, starting at the `struct`, `choice`, or `newtype` keyword. The `start`
+// position, `comment`, and `attributes` account for the comment and attributes preceding the
+// keyword, if there are any. If this function returns `None`, then at least one error was added to
+// `errors`. Otherwise, the `position` is guaranteed to have advanced
+// [tag:parse_declaration_some_advance].
+#[allow(clippy::too_many_arguments, clippy::too_many_lines)]
+fn parse_declaration(
     source_path: &Path,
     source_contents: &str,
     tokens: &[token::Token],
     position: &mut usize,
     errors: &mut Vec<Error>,
-) -> Option<schema::Field> {
-    let start = *position;
-
-    // Parse the comment, if it exists.
-    let comment = if *position == tokens.len() {
-        vec![]
-    } else if let token::Variant::Comment(paragraphs) = &tokens[*position].variant {
-        *position += 1;
+    start: usize,
+    comment: Vec<String>,
+    attributes: Vec<schema::Attribute>,
+) -> Option<schema::Declaration> {
+    // Make sure we have a token to parse.
+    if *position == tokens.len() {
+        errors.push(unexpected_token(
+            source_path,
+            source_contents,
+            tokens,
+            *position,
+            "a declaration",
+        ));
 
-        paragraphs.clone()
-    } else {
-        vec![]
-    };
+        return None;
+    }
 
-    // Parse the rule, if it exists.
-    let rule = if *position == tokens.len() {
-        schema::Rule::Required
+    // Parse the keyword [tag:keyword_is_declaration_keyword].
+    let keyword = if let token::Variant::Struct | token::Variant::Choice | token::Variant::Newtype =
+        tokens[*position].variant
+    {
+        tokens[*position].variant.clone()
     } else {
-        match tokens[*position].variant {
-            token::Variant::Asymmetric => {
-                *position += 1;
-
-                schema::Rule::Asymmetric
-            }
-            token::Variant::Optional => {
-                *position += 1;
+        errors.push(unexpected_token(
+            source_path,
+            source_contents,
+            tokens,
+            *position,
+            "a declaration",
+        ));
 
-                schema::Rule::Optional
-            }
-            _ => schema::Rule::Required,
-        }
+        return None;
     };
+    *position += 1;
 
     // Parse the name.
     let name = consume_token_1!(
@@ -618,27 +667,69 @@ fn parse_field(
         &mut *position,
         errors,
         Identifier,
-        "a field",
+        &format!(
+            "a name for the {}",
+            match keyword {
+                token::Variant::Struct => "struct",
+                token::Variant::Choice => "choice",
+                token::Variant::Newtype => "newtype",
+                _ => {
+                    // Impossible due to [ref:keyword_is_declaration_keyword].
+                    panic!()
+                }
+            },
+        ),
         None,
     );
 
-    // Determine if the type was given explicitly.
-    let explicit_type = if *position == tokens.len() {
-        false
-    } else {
-        matches!(tokens[*position].variant, token::Variant::Colon)
-    };
+    // Parse the type parameters, if they exist.
+    let mut parameters = vec![];
+    if *position != tokens.len() && matches!(tokens[*position].variant, token::Variant::LeftAngle) {
+        *position += 1;
 
-    // Parse the type, if applicable.
-    let r#type = if explicit_type {
-        // Consume the colon.
+        loop {
+            parameters.push(consume_token_1!(
+                source_path,
+                source_contents,
+                tokens,
+                &mut *position,
+                errors,
+                Identifier,
+                "a type parameter",
+                None,
+            ));
+
+            if *position != tokens.len()
+                && matches!(tokens[*position].variant, token::Variant::Comma)
+            {
+                *position += 1;
+            } else {
+                break;
+            }
+        }
+
+        // Consume the `>`.
         consume_token_0!(
             source_path,
             source_contents,
             tokens,
             &mut *position,
             errors,
-            Colon,
+            RightAngle,
+            None,
+        );
+    }
+
+    // A newtype has an underlying type rather than fields.
+    if let token::Variant::Newtype = keyword {
+        // Consume the `=`.
+        consume_token_0!(
+            source_path,
+            source_contents,
+            tokens,
+            &mut *position,
+            errors,
+            Equals,
             None,
         );
 
@@ -655,60 +746,190 @@ fn parse_field(
             return None;
         }
 
-        // Parse the type [ref:parse_type_some_advance].
-        parse_type(source_path, source_contents, tokens, position, errors)?
-    } else {
-        schema::Type {
-            source_range: span_tokens(tokens, *position, *position),
-            variant: schema::TypeVariant::Unit,
-        }
-    };
+        // Parse the underlying type [ref:parse_type_some_advance].
+        let underlying_type = parse_type(source_path, source_contents, tokens, position, errors)?;
 
-    // Consume the equals sign.
+        // Construct the declaration.
+        return Some(schema::Declaration {
+            source_range: span_tokens(tokens, start, *position),
+            comment,
+            attributes,
+            variant: schema::DeclarationVariant::Newtype(underlying_type),
+            name,
+            parameters,
+            fields: vec![],
+            declarations: vec![],
+            deleted: BTreeSet::new(),
+        });
+    }
+
+    // Consume the `{`.
     consume_token_0!(
         source_path,
         source_contents,
         tokens,
         &mut *position,
         errors,
-        Equals,
+        LeftCurly,
         None,
     );
 
-    // Parse the index.
-    let index = consume_token_1!(
-        source_path,
-        source_contents,
-        tokens,
-        &mut *position,
-        errors,
-        Integer,
-        "an index for the field",
-        None,
-    );
+    // Parse the fields and the nested declarations.
+    let mut fields = vec![];
+    let mut declarations = vec![];
+    while *position < tokens.len() {
+        match tokens[*position].variant {
+            token::Variant::Deleted | token::Variant::RightCurly => break,
+            _ => {}
+        }
 
-    // Return the field.
-    Some(schema::Field {
-        source_range: span_tokens(tokens, start, *position),
-        comment,
-        rule,
-        name,
-        r#type,
-        index,
-    })
-}
+        let start = *position;
 
-// Parse a type. If this function returns `None`, then at least one error was added to `errors`.
-// Otherwise, the `position` is guaranteed to have advanced [tag:parse_type_some_advance].
-#[allow(clippy::too_many_lines)]
-fn parse_type(
+        // Parse the comment, if it exists.
+        let comment = if let token::Variant::Comment(paragraphs) = &tokens[*position].variant {
+            *position += 1;
+
+            paragraphs.clone()
+        } else {
+            vec![]
+        };
+
+        // Parse the attributes, if there are any. Then parse a nested declaration if one of the
+        // declaration keywords comes next, or a field otherwise. In either case, if the item was
+        // parsed successfully, [ref:parse_field_some_advance] or
+        // [ref:parse_declaration_some_advance] guarantees that we will not loop forever.
+        if let Some(attributes) =
+            parse_attributes(source_path, source_contents, tokens, position, errors)
+        {
+            if *position < tokens.len()
+                && matches!(
+                    tokens[*position].variant,
+                    token::Variant::Struct | token::Variant::Choice | token::Variant::Newtype,
+                )
+            {
+                if let Some(declaration) = parse_declaration(
+                    source_path,
+                    source_contents,
+                    tokens,
+                    position,
+                    errors,
+                    start,
+                    comment,
+                    attributes,
+                ) {
+                    declarations.push(declaration);
+
+                    continue;
+                }
+            } else if let Some(field) = parse_field(
+                source_path,
+                source_contents,
+                tokens,
+                position,
+                errors,
+                start,
+                comment,
+                attributes,
+            ) {
+                fields.push(field);
+
+                continue;
+            }
+        }
+
+        // Jump to the closing curly brace, if it exists. Otherwise, jump to the end of the source.
+        while *position < tokens.len() {
+            if let token::Variant::RightCurly = tokens[*position].variant {
+                break;
+            }
+
+            *position += 1;
+        }
+
+        break;
+    }
+
+    // Parse the set of deleted fields.
+    let deleted = parse_deleted(source_path, source_contents, tokens, position, errors);
+
+    // Consume the `}`.
+    consume_token_0!(
+        source_path,
+        source_contents,
+        tokens,
+        &mut *position,
+        errors,
+        RightCurly,
+        None,
+    );
+
+    // Construct the declaration.
+    Some(schema::Declaration {
+        source_range: span_tokens(tokens, start, *position),
+        comment,
+        attributes,
+        variant: match keyword {
+            token::Variant::Struct => schema::DeclarationVariant::Struct,
+            token::Variant::Choice => schema::DeclarationVariant::Choice,
+            _ => {
+                // Impossible due to [ref:keyword_is_declaration_keyword] and the fact that
+                // newtypes are handled above.
+                panic!()
+            }
+        },
+        name,
+        parameters,
+        fields,
+        declarations,
+        deleted,
+    })
+}
+
+// Parse a constant, starting at the `const` keyword. The `start` position and `comment` account for
+// the comment preceding the keyword, if there is one. If this function returns `None`, then at
+// least one error was added to `errors`.
+fn parse_constant(
     source_path: &Path,
     source_contents: &str,
     tokens: &[token::Token],
     position: &mut usize,
     errors: &mut Vec<Error>,
-) -> Option<schema::Type> {
-    let start = *position;
+    start: usize,
+    comment: Vec<String>,
+) -> Option<schema::Constant> {
+    // Consume the `const` keyword.
+    consume_token_0!(
+        source_path,
+        source_contents,
+        tokens,
+        &mut *position,
+        errors,
+        Const,
+        None,
+    );
+
+    // Parse the name.
+    let name = consume_token_1!(
+        source_path,
+        source_contents,
+        tokens,
+        &mut *position,
+        errors,
+        Identifier,
+        "a name for the constant",
+        None,
+    );
+
+    // Consume the `:`.
+    consume_token_0!(
+        source_path,
+        source_contents,
+        tokens,
+        &mut *position,
+        errors,
+        Colon,
+        None,
+    );
 
     // Make sure we have a token to parse next.
     if *position == tokens.len() {
@@ -723,163 +944,1345 @@ fn parse_type(
         return None;
     }
 
-    // Parse the type.
-    if let token::Variant::LeftSquare = tokens[*position].variant {
-        *position += 1;
+    // Parse the type [ref:parse_type_some_advance].
+    let r#type = parse_type(source_path, source_contents, tokens, position, errors)?;
 
-        if let Some(inner_type) = parse_type(source_path, source_contents, tokens, position, errors)
-        {
-            // Consume the right square bracket.
-            consume_token_0!(
+    // Consume the `=`.
+    consume_token_0!(
+        source_path,
+        source_contents,
+        tokens,
+        &mut *position,
+        errors,
+        Equals,
+        None,
+    );
+
+    // Parse the value.
+    let value = parse_literal(
+        source_path,
+        source_contents,
+        tokens,
+        position,
+        errors,
+        "a value",
+    )?;
+
+    // Construct and return the constant.
+    Some(schema::Constant {
+        source_range: span_tokens(tokens, start, *position),
+        comment,
+        name,
+        r#type,
+        value,
+    })
+}
+
+// Parse a literal, e.g., the value of a constant. The `description` is used in the error message
+// if no literal is found. If this function returns `None`, then at least one error was added to
+// `errors`.
+fn parse_literal(
+    source_path: &Path,
+    source_contents: &str,
+    tokens: &[token::Token],
+    position: &mut usize,
+    errors: &mut Vec<Error>,
+    description: &str,
+) -> Option<schema::Literal> {
+    // Parse the sign, if there is one.
+    let negative =
+        if *position < tokens.len() && matches!(tokens[*position].variant, token::Variant::Minus) {
+            *position += 1;
+
+            true
+        } else {
+            false
+        };
+
+    // Parse the value.
+    let value = match tokens.get(*position).map(|token| &token.variant) {
+        Some(token::Variant::Integer(integer)) => {
+            // The `unwrap` is safe since `usize` is at most 64 bits wide on supported platforms.
+            let integer = i128::try_from(*integer).unwrap();
+
+            schema::Literal::Integer(if negative { -integer } else { integer })
+        }
+        Some(token::Variant::Float(float)) => {
+            schema::Literal::Float(if negative { -float } else { *float })
+        }
+        Some(token::Variant::True) if !negative => schema::Literal::Bool(true),
+        Some(token::Variant::False) if !negative => schema::Literal::Bool(false),
+        Some(token::Variant::StringLiteral(string)) if !negative => {
+            schema::Literal::String(string.clone())
+        }
+        Some(token::Variant::BytesLiteral(bytes)) if !negative => {
+            schema::Literal::Bytes(bytes.clone())
+        }
+        _ => {
+            errors.push(unexpected_token(
                 source_path,
                 source_contents,
                 tokens,
-                &mut *position,
-                errors,
-                RightSquare,
-                None,
-            );
+                *position,
+                if negative { "a number" } else { description },
+            ));
 
-            Some(schema::Type {
-                source_range: span_tokens(tokens, start, *position),
-                variant: schema::TypeVariant::Array(Box::new(inner_type)),
-            })
+            return None;
+        }
+    };
+    *position += 1;
+
+    Some(value)
+}
+
+// Parse a service, starting at the `service` keyword. The `start` position and `comment` account
+// for the comment preceding the keyword, if there is one. If this function returns `None`, then at
+// least one error was added to `errors`.
+fn parse_service(
+    source_path: &Path,
+    source_contents: &str,
+    tokens: &[token::Token],
+    position: &mut usize,
+    errors: &mut Vec<Error>,
+    start: usize,
+    comment: Vec<String>,
+) -> Option<schema::Service> {
+    // Consume the `service` keyword.
+    consume_token_0!(
+        source_path,
+        source_contents,
+        tokens,
+        &mut *position,
+        errors,
+        Service,
+        None,
+    );
+
+    // Parse the name.
+    let name = consume_token_1!(
+        source_path,
+        source_contents,
+        tokens,
+        &mut *position,
+        errors,
+        Identifier,
+        "a name for the service",
+        None,
+    );
+
+    // Consume the `{`.
+    consume_token_0!(
+        source_path,
+        source_contents,
+        tokens,
+        &mut *position,
+        errors,
+        LeftCurly,
+        None,
+    );
+
+    // Parse the methods.
+    let mut methods = vec![];
+    while *position < tokens.len() {
+        match tokens[*position].variant {
+            token::Variant::Deleted | token::Variant::RightCurly => break,
+            _ => {}
+        }
+
+        if let Some(method) = parse_method(source_path, source_contents, tokens, position, errors) {
+            // In this case, [ref:parse_method_some_advance] guarantees that we will not loop
+            // forever.
+            methods.push(method);
         } else {
-            // [ref:parse_type_some_advance]
-            None
+            // Jump to the closing curly brace, if it exists. Otherwise, jump to the end of the
+            // source.
+            while *position < tokens.len() {
+                if let token::Variant::RightCurly = tokens[*position].variant {
+                    break;
+                }
+
+                *position += 1;
+            }
+
+            break;
         }
-    } else if let token::Variant::Bool = tokens[*position].variant {
-        *position += 1;
+    }
 
-        Some(schema::Type {
-            source_range: span_tokens(tokens, start, *position),
-            variant: schema::TypeVariant::Bool,
-        })
-    } else if let token::Variant::Bytes = tokens[*position].variant {
-        *position += 1;
+    // Parse the set of deleted methods.
+    let deleted = parse_deleted(source_path, source_contents, tokens, position, errors);
 
-        Some(schema::Type {
-            source_range: span_tokens(tokens, start, *position),
-            variant: schema::TypeVariant::Bytes,
-        })
-    } else if let token::Variant::F64 = tokens[*position].variant {
-        *position += 1;
+    // Consume the `}`.
+    consume_token_0!(
+        source_path,
+        source_contents,
+        tokens,
+        &mut *position,
+        errors,
+        RightCurly,
+        None,
+    );
 
-        Some(schema::Type {
-            source_range: span_tokens(tokens, start, *position),
-            variant: schema::TypeVariant::F64,
-        })
-    } else if let token::Variant::S64 = tokens[*position].variant {
-        *position += 1;
+    // Construct and return the service.
+    Some(schema::Service {
+        source_range: span_tokens(tokens, start, *position),
+        comment,
+        name,
+        methods,
+        deleted,
+    })
+}
 
-        Some(schema::Type {
-            source_range: span_tokens(tokens, start, *position),
-            variant: schema::TypeVariant::S64,
-        })
-    } else if let token::Variant::String = tokens[*position].variant {
-        *position += 1;
+// Parse a method. If this function returns `None`, then at least one error was added to `errors`.
+// Otherwise, the `position` is guaranteed to have advanced [tag:parse_method_some_advance].
+fn parse_method(
+    source_path: &Path,
+    source_contents: &str,
+    tokens: &[token::Token],
+    position: &mut usize,
+    errors: &mut Vec<Error>,
+) -> Option<schema::Method> {
+    let start = *position;
 
-        Some(schema::Type {
-            source_range: span_tokens(tokens, start, *position),
-            variant: schema::TypeVariant::String,
-        })
-    } else if let token::Variant::U64 = tokens[*position].variant {
+    // Parse the comment, if it exists.
+    let comment = if *position == tokens.len() {
+        vec![]
+    } else if let token::Variant::Comment(paragraphs) = &tokens[*position].variant {
         *position += 1;
 
-        Some(schema::Type {
-            source_range: span_tokens(tokens, start, *position),
-            variant: schema::TypeVariant::U64,
-        })
-    } else if let token::Variant::Unit = tokens[*position].variant {
-        *position += 1;
+        paragraphs.clone()
+    } else {
+        vec![]
+    };
+
+    // Parse the name.
+    let name = consume_token_1!(
+        source_path,
+        source_contents,
+        tokens,
+        &mut *position,
+        errors,
+        Identifier,
+        "a method",
+        None,
+    );
+
+    // Consume the `:`.
+    consume_token_0!(
+        source_path,
+        source_contents,
+        tokens,
+        &mut *position,
+        errors,
+        Colon,
+        None,
+    );
+
+    // Parse the input type [ref:parse_type_some_advance].
+    let input_type = parse_type(source_path, source_contents, tokens, position, errors)?;
+
+    // Consume the `->`.
+    consume_token_0!(
+        source_path,
+        source_contents,
+        tokens,
+        &mut *position,
+        errors,
+        Arrow,
+        None,
+    );
+
+    // Parse the output type [ref:parse_type_some_advance].
+    let output_type = parse_type(source_path, source_contents, tokens, position, errors)?;
+
+    // Consume the `=`.
+    consume_token_0!(
+        source_path,
+        source_contents,
+        tokens,
+        &mut *position,
+        errors,
+        Equals,
+        None,
+    );
+
+    // Parse the index.
+    let index = consume_token_1!(
+        source_path,
+        source_contents,
+        tokens,
+        &mut *position,
+        errors,
+        Integer,
+        "an index for the method",
+        None,
+    );
+
+    // Return the method.
+    Some(schema::Method {
+        source_range: span_tokens(tokens, start, *position),
+        comment,
+        name,
+        input_type,
+        output_type,
+        index,
+    })
+}
+
+// Parse the `deleted` keyword followed by a list of indices, if it comes next. Otherwise, return
+// the empty set.
+fn parse_deleted(
+    source_path: &Path,
+    source_contents: &str,
+    tokens: &[token::Token],
+    position: &mut usize,
+    errors: &mut Vec<Error>,
+) -> BTreeSet<usize> {
+    let mut deleted = BTreeSet::new();
+
+    if *position != tokens.len() && matches!(tokens[*position].variant, token::Variant::Deleted) {
+        *position += 1;
+
+        while *position != tokens.len() {
+            if let index_token @ token::Variant::Integer(index) = &tokens[*position].variant {
+                if !deleted.insert(*index) {
+                    errors.push(throw::<Error>(
+                        &format!(
+                            "Index {} is already marked as deleted.",
+                            index_token.to_string().code_str(),
+                        ),
+                        Some(source_path),
+                        Some(&listing(
+                            source_contents,
+                            token_source_range(tokens, *position),
+                        )),
+                        None,
+                    ));
+                }
+
+                *position += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    deleted
+}
+
+// Parse a sequence of attributes, such as `@rust.derive(Hash)` or `@deprecated("Use `x`.")`. If
+// this function returns `None`, then at least one error was added to `errors`.
+fn parse_attributes(
+    source_path: &Path,
+    source_contents: &str,
+    tokens: &[token::Token],
+    position: &mut usize,
+    errors: &mut Vec<Error>,
+) -> Option<Vec<schema::Attribute>> {
+    let mut attributes = vec![];
+
+    while *position < tokens.len() && matches!(tokens[*position].variant, token::Variant::At) {
+        let start = *position;
+        *position += 1;
+
+        // Parse the name, which may be qualified by the code generator it's for.
+        let first_name = consume_token_1!(
+            source_path,
+            source_contents,
+            tokens,
+            &mut *position,
+            errors,
+            Identifier,
+            "a name for the attribute",
+            None,
+        );
+
+        let (target, name) = if *position < tokens.len()
+            && matches!(tokens[*position].variant, token::Variant::Dot)
+        {
+            *position += 1;
+
+            let name = consume_token_1!(
+                source_path,
+                source_contents,
+                tokens,
+                &mut *position,
+                errors,
+                Identifier,
+                "a name for the attribute",
+                None,
+            );
+
+            (Some(first_name), name)
+        } else {
+            (None, first_name)
+        };
+
+        // Parse the arguments, if they exist.
+        let mut arguments = vec![];
+        if *position < tokens.len()
+            && matches!(tokens[*position].variant, token::Variant::LeftParen)
+        {
+            *position += 1;
+
+            loop {
+                match tokens.get(*position).map(|token| &token.variant) {
+                    Some(token::Variant::Identifier(argument)) => {
+                        arguments.push(schema::AttributeArgument::Identifier(argument.clone()));
+                        *position += 1;
+                    }
+                    _ => {
+                        arguments.push(schema::AttributeArgument::Literal(parse_literal(
+                            source_path,
+                            source_contents,
+                            tokens,
+                            position,
+                            errors,
+                            "an argument for the attribute",
+                        )?));
+                    }
+                }
+
+                if *position < tokens.len()
+                    && matches!(tokens[*position].variant, token::Variant::Comma)
+                {
+                    *position += 1;
+                } else {
+                    break;
+                }
+            }
+
+            // Consume the `)`.
+            consume_token_0!(
+                source_path,
+                source_contents,
+                tokens,
+                &mut *position,
+                errors,
+                RightParen,
+                None,
+            );
+        }
+
+        attributes.push(schema::Attribute {
+            source_range: span_tokens(tokens, start, *position),
+            target,
+            name,
+            arguments,
+        });
+    }
+
+    Some(attributes)
+}
+
+// Parse a field, starting after its comment and attributes. The `start` position, `comment`, and
+// `attributes` account for those, if there are any. If this function returns `None`, then at least
+// one error was added to `errors`. Otherwise, the `position` is guaranteed to have advanced
+// [tag:parse_field_some_advance].
+#[allow(clippy::too_many_arguments, clippy::too_many_lines)]
+fn parse_field(
+    source_path: &Path,
+    source_contents: &str,
+    tokens: &[token::Token],
+    position: &mut usize,
+    errors: &mut Vec<Error>,
+    start: usize,
+    comment: Vec<String>,
+    attributes: Vec<schema::Attribute>,
+) -> Option<schema::Field> {
+    // Parse the rule, if it exists.
+    let rule = if *position == tokens.len() {
+        schema::Rule::Required
+    } else {
+        match tokens[*position].variant {
+            token::Variant::Asymmetric => {
+                *position += 1;
+
+                schema::Rule::Asymmetric
+            }
+            token::Variant::Optional => {
+                *position += 1;
+
+                schema::Rule::Optional
+            }
+            _ => schema::Rule::Required,
+        }
+    };
+
+    // Parse the name.
+    let name = consume_token_1!(
+        source_path,
+        source_contents,
+        tokens,
+        &mut *position,
+        errors,
+        Identifier,
+        "a field",
+        None,
+    );
+
+    // Determine if the type was given explicitly.
+    let explicit_type = if *position == tokens.len() {
+        false
+    } else {
+        matches!(tokens[*position].variant, token::Variant::Colon)
+    };
+
+    // Parse the type, if applicable.
+    let r#type = if explicit_type {
+        // Consume the colon.
+        consume_token_0!(
+            source_path,
+            source_contents,
+            tokens,
+            &mut *position,
+            errors,
+            Colon,
+            None,
+        );
+
+        // Make sure we have a token to parse next.
+        if *position == tokens.len() {
+            errors.push(unexpected_token(
+                source_path,
+                source_contents,
+                tokens,
+                *position,
+                "a type",
+            ));
+
+            return None;
+        }
+
+        // Parse the type [ref:parse_type_some_advance].
+        parse_type(source_path, source_contents, tokens, position, errors)?
+    } else {
+        schema::Type {
+            source_range: span_tokens(tokens, *position, *position),
+            variant: schema::TypeVariant::Unit,
+        }
+    };
+
+    // Consume the equals sign.
+    consume_token_0!(
+        source_path,
+        source_contents,
+        tokens,
+        &mut *position,
+        errors,
+        Equals,
+        None,
+    );
+
+    // Parse the index.
+    let index = consume_token_1!(
+        source_path,
+        source_contents,
+        tokens,
+        &mut *position,
+        errors,
+        Integer,
+        "an index for the field",
+        None,
+    );
+
+    // Return the field.
+    Some(schema::Field {
+        source_range: span_tokens(tokens, start, *position),
+        comment,
+        attributes,
+        rule,
+        name,
+        r#type,
+        index,
+    })
+}
+
+// Parse a type. If this function returns `None`, then at least one error was added to `errors`.
+// Otherwise, the `position` is guaranteed to have advanced [tag:parse_type_some_advance].
+#[allow(clippy::too_many_lines)]
+fn parse_type(
+    source_path: &Path,
+    source_contents: &str,
+    tokens: &[token::Token],
+    position: &mut usize,
+    errors: &mut Vec<Error>,
+) -> Option<schema::Type> {
+    let start = *position;
+
+    // Make sure we have a token to parse next.
+    if *position == tokens.len() {
+        errors.push(unexpected_token(
+            source_path,
+            source_contents,
+            tokens,
+            *position,
+            "a type",
+        ));
+
+        return None;
+    }
+
+    // Parse the type.
+    if let token::Variant::LeftSquare = tokens[*position].variant {
+        *position += 1;
+
+        if let Some(inner_type) = parse_type(source_path, source_contents, tokens, position, errors)
+        {
+            // Parse the length, if there is one.
+            let length = if *position < tokens.len()
+                && matches!(tokens[*position].variant, token::Variant::Semicolon)
+            {
+                *position += 1;
+
+                Some(consume_token_1!(
+                    source_path,
+                    source_contents,
+                    tokens,
+                    &mut *position,
+                    errors,
+                    Integer,
+                    "a length for the array",
+                    None,
+                ))
+            } else {
+                None
+            };
+
+            // Consume the right square bracket.
+            consume_token_0!(
+                source_path,
+                source_contents,
+                tokens,
+                &mut *position,
+                errors,
+                RightSquare,
+                None,
+            );
+
+            Some(schema::Type {
+                source_range: span_tokens(tokens, start, *position),
+                variant: schema::TypeVariant::Array(Box::new(inner_type), length),
+            })
+        } else {
+            // [ref:parse_type_some_advance]
+            None
+        }
+    } else if let token::Variant::LeftCurly = tokens[*position].variant {
+        *position += 1;
+
+        if let Some(key_type) = parse_type(source_path, source_contents, tokens, position, errors) {
+            // Consume the colon.
+            consume_token_0!(
+                source_path,
+                source_contents,
+                tokens,
+                &mut *position,
+                errors,
+                Colon,
+                None,
+            );
+
+            if let Some(value_type) =
+                parse_type(source_path, source_contents, tokens, position, errors)
+            {
+                // Consume the right curly brace.
+                consume_token_0!(
+                    source_path,
+                    source_contents,
+                    tokens,
+                    &mut *position,
+                    errors,
+                    RightCurly,
+                    None,
+                );
+
+                Some(schema::Type {
+                    source_range: span_tokens(tokens, start, *position),
+                    variant: schema::TypeVariant::Map(Box::new(key_type), Box::new(value_type)),
+                })
+            } else {
+                // [ref:parse_type_some_advance]
+                None
+            }
+        } else {
+            // [ref:parse_type_some_advance]
+            None
+        }
+    } else if let token::Variant::Bool = tokens[*position].variant {
+        *position += 1;
+
+        Some(schema::Type {
+            source_range: span_tokens(tokens, start, *position),
+            variant: schema::TypeVariant::Bool,
+        })
+    } else if let token::Variant::Bytes = tokens[*position].variant {
+        *position += 1;
+
+        // Parse the length, if there is one.
+        let length = if *position < tokens.len()
+            && matches!(tokens[*position].variant, token::Variant::LeftParen)
+        {
+            *position += 1;
+
+            let length = consume_token_1!(
+                source_path,
+                source_contents,
+                tokens,
+                &mut *position,
+                errors,
+                Integer,
+                "a length for the `Bytes` type",
+                None,
+            );
+
+            consume_token_0!(
+                source_path,
+                source_contents,
+                tokens,
+                &mut *position,
+                errors,
+                RightParen,
+                None,
+            );
+
+            Some(length)
+        } else {
+            None
+        };
+
+        Some(schema::Type {
+            source_range: span_tokens(tokens, start, *position),
+            variant: schema::TypeVariant::Bytes(length),
+        })
+    } else if let token::Variant::F32 = tokens[*position].variant {
+        *position += 1;
+
+        Some(schema::Type {
+            source_range: span_tokens(tokens, start, *position),
+            variant: schema::TypeVariant::F32,
+        })
+    } else if let token::Variant::F64 = tokens[*position].variant {
+        *position += 1;
+
+        Some(schema::Type {
+            source_range: span_tokens(tokens, start, *position),
+            variant: schema::TypeVariant::F64,
+        })
+    } else if let token::Variant::S32 = tokens[*position].variant {
+        *position += 1;
+
+        Some(schema::Type {
+            source_range: span_tokens(tokens, start, *position),
+            variant: schema::TypeVariant::S32,
+        })
+    } else if let token::Variant::S64 = tokens[*position].variant {
+        *position += 1;
+
+        Some(schema::Type {
+            source_range: span_tokens(tokens, start, *position),
+            variant: schema::TypeVariant::S64,
+        })
+    } else if let token::Variant::String = tokens[*position].variant {
+        *position += 1;
+
+        Some(schema::Type {
+            source_range: span_tokens(tokens, start, *position),
+            variant: schema::TypeVariant::String,
+        })
+    } else if let token::Variant::U32 = tokens[*position].variant {
+        *position += 1;
+
+        Some(schema::Type {
+            source_range: span_tokens(tokens, start, *position),
+            variant: schema::TypeVariant::U32,
+        })
+    } else if let token::Variant::U64 = tokens[*position].variant {
+        *position += 1;
+
+        Some(schema::Type {
+            source_range: span_tokens(tokens, start, *position),
+            variant: schema::TypeVariant::U64,
+        })
+    } else if let token::Variant::U8 = tokens[*position].variant {
+        *position += 1;
+
+        Some(schema::Type {
+            source_range: span_tokens(tokens, start, *position),
+            variant: schema::TypeVariant::U8,
+        })
+    } else if let token::Variant::Unit = tokens[*position].variant {
+        *position += 1;
+
+        Some(schema::Type {
+            source_range: span_tokens(tokens, start, *position),
+            variant: schema::TypeVariant::Unit,
+        })
+    } else {
+        let (import_name, mut r#type_name) = if *position < tokens.len() - 2 {
+            if let Some(token::Variant::Dot) = tokens.get(*position + 1).map(|token| &token.variant)
+            {
+                let import = consume_token_1!(
+                    source_path,
+                    source_contents,
+                    tokens,
+                    &mut *position,
+                    errors,
+                    Identifier,
+                    "the name of an import",
+                    None,
+                );
+
+                *position += 1;
+
+                let r#type = consume_token_1!(
+                    source_path,
+                    source_contents,
+                    tokens,
+                    &mut *position,
+                    errors,
+                    Identifier,
+                    "a type",
+                    None,
+                );
+
+                (Some(import), r#type)
+            } else {
+                let r#type = consume_token_1!(
+                    source_path,
+                    source_contents,
+                    tokens,
+                    &mut *position,
+                    errors,
+                    Identifier,
+                    "a type",
+                    None,
+                );
+
+                (None, r#type)
+            }
+        } else {
+            let r#type = consume_token_1!(
+                source_path,
+                source_contents,
+                tokens,
+                &mut *position,
+                errors,
+                Identifier,
+                "a type",
+                None,
+            );
+
+            (None, r#type)
+        };
+
+        // Parse the names of any nested declarations (e.g., `foo.Device.IpAddress`). The first
+        // component of a qualified name is treated as the name of an import for now
+        // [tag:parse_type_qualifier].
+        while *position < tokens.len() - 1
+            && matches!(tokens[*position].variant, token::Variant::Dot)
+        {
+            *position += 1;
+
+            let nested_type = consume_token_1!(
+                source_path,
+                source_contents,
+                tokens,
+                &mut *position,
+                errors,
+                Identifier,
+                "a type",
+                None,
+            );
+
+            r#type_name = r#type_name.qualify(&nested_type);
+        }
+
+        // Parse the type arguments, if they exist.
+        let mut arguments = vec![];
+        if *position != tokens.len()
+            && matches!(tokens[*position].variant, token::Variant::LeftAngle)
+        {
+            *position += 1;
+
+            loop {
+                arguments.push(parse_type(
+                    source_path,
+                    source_contents,
+                    tokens,
+                    position,
+                    errors,
+                )?);
+
+                if *position != tokens.len()
+                    && matches!(tokens[*position].variant, token::Variant::Comma)
+                {
+                    *position += 1;
+                } else {
+                    break;
+                }
+            }
+
+            // Consume the `>`.
+            consume_token_0!(
+                source_path,
+                source_contents,
+                tokens,
+                &mut *position,
+                errors,
+                RightAngle,
+                None,
+            );
+        }
+
+        Some(schema::Type {
+            source_range: span_tokens(tokens, start, *position),
+            variant: schema::TypeVariant::Custom(import_name, r#type_name, arguments),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::{
+            assert_fails, assert_same, error::SourceRange, parser::parse, schema,
+            tokenizer::tokenize,
+        },
+        std::{
+            collections::{BTreeMap, BTreeSet},
+            fmt::Write,
+            path::Path,
+        },
+    };
+
+    #[test]
+    fn parse_empty() {
+        let source_path = Path::new("foo.t");
+        let source = "";
+        let tokens = tokenize(source_path, source).unwrap();
+
+        assert_same!(
+            parse(source_path, source, &tokens[..]),
+            Ok(schema::Schema {
+                comment: vec![],
+                imports: BTreeMap::new(),
+                constants: vec![],
+                declarations: vec![],
+                services: vec![],
+            }),
+        );
+    }
+
+    #[allow(clippy::too_many_lines)]
+    #[test]
+    fn parse_example() {
+        let source_path = Path::new("foo.t");
+        let source = "
+            # This is an example schema.
+
+            import 'baz.t'
+            import 'qux.t' as corge
+
+            # This is a struct.
+            struct Foo {
+                w: baz.Baz = 0
+
+                # This is an optional field.
+                optional x: U64 = 1
+
+                y: [Bool] = 2
+                z = 3
+            }
+
+            # This is a choice.
+            choice Bar {
+                w: corge.Qux = 0
+
+                # This is an asymmetric field.
+                asymmetric x: [Bytes] = 1
+
+                y: F64 = 2
+                z = 3
+            }
+        ";
+        let tokens = tokenize(source_path, source).unwrap();
+
+        let mut imports = BTreeMap::new();
+
+        imports.insert(
+            "baz".into(),
+            schema::Import {
+                source_range: SourceRange { start: 55, end: 69 },
+                path: Path::new("baz.t").to_owned(),
+                namespace: None,
+            },
+        );
+
+        imports.insert(
+            "corge".into(),
+            schema::Import {
+                source_range: SourceRange {
+                    start: 82,
+                    end: 105,
+                },
+                path: Path::new("qux.t").to_owned(),
+                namespace: None,
+            },
+        );
+
+        let declarations = vec![
+            schema::Declaration {
+                source_range: SourceRange {
+                    start: 119,
+                    end: 343,
+                },
+                comment: vec!["This is a struct.".to_owned()],
+                attributes: vec![],
+                variant: schema::DeclarationVariant::Struct,
+                name: "Foo".into(),
+                parameters: vec![],
+                fields: vec![
+                    schema::Field {
+                        source_range: SourceRange {
+                            start: 180,
+                            end: 194,
+                        },
+                        comment: vec![],
+                        attributes: vec![],
+                        rule: schema::Rule::Required,
+                        name: "w".into(),
+                        r#type: schema::Type {
+                            source_range: SourceRange {
+                                start: 183,
+                                end: 190,
+                            },
+                            variant: schema::TypeVariant::Custom(
+                                Some("baz".into()),
+                                "Baz".into(),
+                                vec![],
+                            ),
+                        },
+                        index: 0,
+                    },
+                    schema::Field {
+                        source_range: SourceRange {
+                            start: 212,
+                            end: 276,
+                        },
+                        comment: vec!["This is an optional field.".to_owned()],
+                        attributes: vec![],
+                        rule: schema::Rule::Optional,
+                        name: "x".into(),
+                        r#type: schema::Type {
+                            source_range: SourceRange {
+                                start: 269,
+                                end: 272,
+                            },
+                            variant: schema::TypeVariant::U64,
+                        },
+                        index: 1,
+                    },
+                    schema::Field {
+                        source_range: SourceRange {
+                            start: 294,
+                            end: 307,
+                        },
+                        comment: vec![],
+                        attributes: vec![],
+                        rule: schema::Rule::Required,
+                        name: "y".into(),
+                        r#type: schema::Type {
+                            source_range: SourceRange {
+                                start: 297,
+                                end: 303,
+                            },
+                            variant: schema::TypeVariant::Array(
+                                Box::new(schema::Type {
+                                    source_range: SourceRange {
+                                        start: 298,
+                                        end: 302,
+                                    },
+                                    variant: schema::TypeVariant::Bool,
+                                }),
+                                None,
+                            ),
+                        },
+                        index: 2,
+                    },
+                    schema::Field {
+                        source_range: SourceRange {
+                            start: 324,
+                            end: 329,
+                        },
+                        comment: vec![],
+                        attributes: vec![],
+                        rule: schema::Rule::Required,
+                        name: "z".into(),
+                        r#type: schema::Type {
+                            source_range: SourceRange {
+                                start: 326,
+                                end: 326,
+                            },
+                            variant: schema::TypeVariant::Unit,
+                        },
+                        index: 3,
+                    },
+                ],
+                declarations: vec![],
+                deleted: BTreeSet::new(),
+            },
+            schema::Declaration {
+                source_range: SourceRange {
+                    start: 357,
+                    end: 588,
+                },
+                comment: vec!["This is a choice.".to_owned()],
+                attributes: vec![],
+                variant: schema::DeclarationVariant::Choice,
+                name: "Bar".into(),
+                parameters: vec![],
+                fields: vec![
+                    schema::Field {
+                        source_range: SourceRange {
+                            start: 418,
+                            end: 434,
+                        },
+                        comment: vec![],
+                        attributes: vec![],
+                        rule: schema::Rule::Required,
+                        name: "w".into(),
+                        r#type: schema::Type {
+                            source_range: SourceRange {
+                                start: 421,
+                                end: 430,
+                            },
+                            variant: schema::TypeVariant::Custom(
+                                Some("corge".into()),
+                                "Qux".into(),
+                                vec![],
+                            ),
+                        },
+                        index: 0,
+                    },
+                    schema::Field {
+                        source_range: SourceRange {
+                            start: 452,
+                            end: 524,
+                        },
+                        comment: vec!["This is an asymmetric field.".to_owned()],
+                        attributes: vec![],
+                        rule: schema::Rule::Asymmetric,
+                        name: "x".into(),
+                        r#type: schema::Type {
+                            source_range: SourceRange {
+                                start: 513,
+                                end: 520,
+                            },
+                            variant: schema::TypeVariant::Array(
+                                Box::new(schema::Type {
+                                    source_range: SourceRange {
+                                        start: 514,
+                                        end: 519,
+                                    },
+                                    variant: schema::TypeVariant::Bytes(None),
+                                }),
+                                None,
+                            ),
+                        },
+                        index: 1,
+                    },
+                    schema::Field {
+                        source_range: SourceRange {
+                            start: 542,
+                            end: 552,
+                        },
+                        comment: vec![],
+                        attributes: vec![],
+                        rule: schema::Rule::Required,
+                        name: "y".into(),
+                        r#type: schema::Type {
+                            source_range: SourceRange {
+                                start: 545,
+                                end: 548,
+                            },
+                            variant: schema::TypeVariant::F64,
+                        },
+                        index: 2,
+                    },
+                    schema::Field {
+                        source_range: SourceRange {
+                            start: 569,
+                            end: 574,
+                        },
+                        comment: vec![],
+                        attributes: vec![],
+                        rule: schema::Rule::Required,
+                        name: "z".into(),
+                        r#type: schema::Type {
+                            source_range: SourceRange {
+                                start: 571,
+                                end: 571,
+                            },
+                            variant: schema::TypeVariant::Unit,
+                        },
+                        index: 3,
+                    },
+                ],
+                declarations: vec![],
+                deleted: BTreeSet::new(),
+            },
+        ];
 
-        Some(schema::Type {
-            source_range: span_tokens(tokens, start, *position),
-            variant: schema::TypeVariant::Unit,
-        })
-    } else {
-        let (import_name, r#type_name) = if *position < tokens.len() - 2 {
-            if let Some(token::Variant::Dot) = tokens.get(*position + 1).map(|token| &token.variant)
-            {
-                let import = consume_token_1!(
-                    source_path,
-                    source_contents,
-                    tokens,
-                    &mut *position,
-                    errors,
-                    Identifier,
-                    "the name of an import",
-                    None,
-                );
+        assert_same!(
+            parse(source_path, source, &tokens[..]),
+            Ok(schema::Schema {
+                comment: vec!["This is an example schema.".to_owned()],
+                imports,
+                constants: vec![],
+                declarations,
+                services: vec![],
+            }),
+        );
+    }
 
-                *position += 1;
+    #[test]
+    fn parse_map() {
+        let source_path = Path::new("foo.t");
+        let source = "struct Foo { x: {String: [U64]} = 0 }";
+        let tokens = tokenize(source_path, source).unwrap();
 
-                let r#type = consume_token_1!(
-                    source_path,
-                    source_contents,
-                    tokens,
-                    &mut *position,
-                    errors,
-                    Identifier,
-                    "a type",
-                    None,
-                );
+        assert_same!(
+            parse(source_path, source, &tokens[..]),
+            Ok(schema::Schema {
+                comment: vec![],
+                imports: BTreeMap::new(),
+                constants: vec![],
+                declarations: vec![schema::Declaration {
+                    source_range: SourceRange { start: 0, end: 37 },
+                    comment: vec![],
+                    attributes: vec![],
+                    variant: schema::DeclarationVariant::Struct,
+                    name: "Foo".into(),
+                    parameters: vec![],
+                    fields: vec![schema::Field {
+                        source_range: SourceRange { start: 13, end: 35 },
+                        comment: vec![],
+                        attributes: vec![],
+                        rule: schema::Rule::Required,
+                        name: "x".into(),
+                        r#type: schema::Type {
+                            source_range: SourceRange { start: 16, end: 31 },
+                            variant: schema::TypeVariant::Map(
+                                Box::new(schema::Type {
+                                    source_range: SourceRange { start: 17, end: 23 },
+                                    variant: schema::TypeVariant::String,
+                                }),
+                                Box::new(schema::Type {
+                                    source_range: SourceRange { start: 25, end: 30 },
+                                    variant: schema::TypeVariant::Array(
+                                        Box::new(schema::Type {
+                                            source_range: SourceRange { start: 26, end: 29 },
+                                            variant: schema::TypeVariant::U64,
+                                        }),
+                                        None,
+                                    ),
+                                }),
+                            ),
+                        },
+                        index: 0,
+                    }],
+                    declarations: vec![],
+                    deleted: BTreeSet::new(),
+                }],
+                services: vec![],
+            }),
+        );
+    }
 
-                (Some(import), r#type)
-            } else {
-                let r#type = consume_token_1!(
-                    source_path,
-                    source_contents,
-                    tokens,
-                    &mut *position,
-                    errors,
-                    Identifier,
-                    "a type",
-                    None,
-                );
+    #[test]
+    fn parse_fixed_length() {
+        let source_path = Path::new("foo.t");
+        let source = "struct Foo { x: [U64; 3] = 0 y: Bytes(4) = 1 }";
+        let tokens = tokenize(source_path, source).unwrap();
 
-                (None, r#type)
-            }
-        } else {
-            let r#type = consume_token_1!(
-                source_path,
-                source_contents,
-                tokens,
-                &mut *position,
-                errors,
-                Identifier,
-                "a type",
-                None,
-            );
+        assert_same!(
+            parse(source_path, source, &tokens[..]),
+            Ok(schema::Schema {
+                comment: vec![],
+                imports: BTreeMap::new(),
+                constants: vec![],
+                declarations: vec![schema::Declaration {
+                    source_range: SourceRange { start: 0, end: 46 },
+                    comment: vec![],
+                    attributes: vec![],
+                    variant: schema::DeclarationVariant::Struct,
+                    name: "Foo".into(),
+                    parameters: vec![],
+                    fields: vec![
+                        schema::Field {
+                            source_range: SourceRange { start: 13, end: 28 },
+                            comment: vec![],
+                            attributes: vec![],
+                            rule: schema::Rule::Required,
+                            name: "x".into(),
+                            r#type: schema::Type {
+                                source_range: SourceRange { start: 16, end: 24 },
+                                variant: schema::TypeVariant::Array(
+                                    Box::new(schema::Type {
+                                        source_range: SourceRange { start: 17, end: 20 },
+                                        variant: schema::TypeVariant::U64,
+                                    }),
+                                    Some(3),
+                                ),
+                            },
+                            index: 0,
+                        },
+                        schema::Field {
+                            source_range: SourceRange { start: 29, end: 44 },
+                            comment: vec![],
+                            attributes: vec![],
+                            rule: schema::Rule::Required,
+                            name: "y".into(),
+                            r#type: schema::Type {
+                                source_range: SourceRange { start: 32, end: 40 },
+                                variant: schema::TypeVariant::Bytes(Some(4)),
+                            },
+                            index: 1,
+                        },
+                    ],
+                    declarations: vec![],
+                    deleted: BTreeSet::new(),
+                }],
+                services: vec![],
+            }),
+        );
+    }
 
-            (None, r#type)
-        };
+    #[test]
+    fn parse_fixed_length_array_missing_length() {
+        let source_path = Path::new("foo.t");
+        let source = "struct Foo { x: [U64;] = 0 }";
+        let tokens = tokenize(source_path, source).unwrap();
 
-        Some(schema::Type {
-            source_range: span_tokens(tokens, start, *position),
-            variant: schema::TypeVariant::Custom(import_name, r#type_name),
-        })
+        assert_fails!(
+            parse(source_path, source, &tokens[..]),
+            "Expected a length for the array",
+        );
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use {
-        crate::{
-            assert_fails, assert_same, error::SourceRange, parser::parse, schema,
-            tokenizer::tokenize,
-        },
-        std::{
-            collections::{BTreeMap, BTreeSet},
-            fmt::Write,
-            path::Path,
-        },
-    };
+    #[test]
+    fn parse_fixed_length_bytes_missing_paren() {
+        let source_path = Path::new("foo.t");
+        let source = "struct Foo { x: Bytes(4 = 0 }";
+        let tokens = tokenize(source_path, source).unwrap();
+
+        assert_fails!(parse(source_path, source, &tokens[..]), "Expected `)`");
+    }
 
     #[test]
-    fn parse_empty() {
+    fn parse_narrow_numeric_types() {
         let source_path = Path::new("foo.t");
-        let source = "";
+        let source = "struct Foo { a: U8 = 0 b: U32 = 1 c: S32 = 2 d: F32 = 3 }";
         let tokens = tokenize(source_path, source).unwrap();
 
         assert_same!(
@@ -887,256 +2290,664 @@ mod tests {
             Ok(schema::Schema {
                 comment: vec![],
                 imports: BTreeMap::new(),
-                declarations: vec![],
+                constants: vec![],
+                declarations: vec![schema::Declaration {
+                    source_range: SourceRange { start: 0, end: 57 },
+                    comment: vec![],
+                    attributes: vec![],
+                    variant: schema::DeclarationVariant::Struct,
+                    name: "Foo".into(),
+                    parameters: vec![],
+                    fields: vec![
+                        schema::Field {
+                            source_range: SourceRange { start: 13, end: 22 },
+                            comment: vec![],
+                            attributes: vec![],
+                            rule: schema::Rule::Required,
+                            name: "a".into(),
+                            r#type: schema::Type {
+                                source_range: SourceRange { start: 16, end: 18 },
+                                variant: schema::TypeVariant::U8,
+                            },
+                            index: 0,
+                        },
+                        schema::Field {
+                            source_range: SourceRange { start: 23, end: 33 },
+                            comment: vec![],
+                            attributes: vec![],
+                            rule: schema::Rule::Required,
+                            name: "b".into(),
+                            r#type: schema::Type {
+                                source_range: SourceRange { start: 26, end: 29 },
+                                variant: schema::TypeVariant::U32,
+                            },
+                            index: 1,
+                        },
+                        schema::Field {
+                            source_range: SourceRange { start: 34, end: 44 },
+                            comment: vec![],
+                            attributes: vec![],
+                            rule: schema::Rule::Required,
+                            name: "c".into(),
+                            r#type: schema::Type {
+                                source_range: SourceRange { start: 37, end: 40 },
+                                variant: schema::TypeVariant::S32,
+                            },
+                            index: 2,
+                        },
+                        schema::Field {
+                            source_range: SourceRange { start: 45, end: 55 },
+                            comment: vec![],
+                            attributes: vec![],
+                            rule: schema::Rule::Required,
+                            name: "d".into(),
+                            r#type: schema::Type {
+                                source_range: SourceRange { start: 48, end: 51 },
+                                variant: schema::TypeVariant::F32,
+                            },
+                            index: 3,
+                        },
+                    ],
+                    declarations: vec![],
+                    deleted: BTreeSet::new(),
+                }],
+                services: vec![],
             }),
         );
     }
 
-    #[allow(clippy::too_many_lines)]
     #[test]
-    fn parse_example() {
+    fn parse_generic() {
         let source_path = Path::new("foo.t");
-        let source = "
-            # This is an example schema.
+        let source = "struct Page<T> { x: Foo<T, [U64]> = 0 }";
+        let tokens = tokenize(source_path, source).unwrap();
 
-            import 'baz.t'
-            import 'qux.t' as corge
+        assert_same!(
+            parse(source_path, source, &tokens[..]),
+            Ok(schema::Schema {
+                comment: vec![],
+                imports: BTreeMap::new(),
+                constants: vec![],
+                declarations: vec![schema::Declaration {
+                    source_range: SourceRange { start: 0, end: 39 },
+                    comment: vec![],
+                    attributes: vec![],
+                    variant: schema::DeclarationVariant::Struct,
+                    name: "Page".into(),
+                    parameters: vec!["T".into()],
+                    fields: vec![schema::Field {
+                        source_range: SourceRange { start: 17, end: 37 },
+                        comment: vec![],
+                        attributes: vec![],
+                        rule: schema::Rule::Required,
+                        name: "x".into(),
+                        r#type: schema::Type {
+                            source_range: SourceRange { start: 20, end: 33 },
+                            variant: schema::TypeVariant::Custom(
+                                None,
+                                "Foo".into(),
+                                vec![
+                                    schema::Type {
+                                        source_range: SourceRange { start: 24, end: 25 },
+                                        variant: schema::TypeVariant::Custom(
+                                            None,
+                                            "T".into(),
+                                            vec![],
+                                        ),
+                                    },
+                                    schema::Type {
+                                        source_range: SourceRange { start: 27, end: 32 },
+                                        variant: schema::TypeVariant::Array(
+                                            Box::new(schema::Type {
+                                                source_range: SourceRange { start: 28, end: 31 },
+                                                variant: schema::TypeVariant::U64,
+                                            }),
+                                            None,
+                                        ),
+                                    },
+                                ],
+                            ),
+                        },
+                        index: 0,
+                    }],
+                    declarations: vec![],
+                    deleted: BTreeSet::new(),
+                }],
+                services: vec![],
+            }),
+        );
+    }
 
-            # This is a struct.
-            struct Foo {
-                w: baz.Baz = 0
+    #[test]
+    fn parse_empty_type_parameters() {
+        let source_path = Path::new("foo.t");
+        let source = "struct Page<> { }";
+        let tokens = tokenize(source_path, source).unwrap();
 
-                # This is an optional field.
-                optional x: U64 = 1
+        assert_fails!(
+            parse(source_path, source, &tokens[..]),
+            "Expected a type parameter",
+        );
+    }
 
-                y: [Bool] = 2
-                z = 3
-            }
+    #[test]
+    fn parse_empty_type_arguments() {
+        let source_path = Path::new("foo.t");
+        let source = "struct Foo { x: Page<> = 0 }";
+        let tokens = tokenize(source_path, source).unwrap();
 
-            # This is a choice.
-            choice Bar {
-                w: corge.Qux = 0
+        assert_fails!(parse(source_path, source, &tokens[..]), "Expected a type");
+    }
 
-                # This is an asymmetric field.
-                asymmetric x: [Bytes] = 1
+    #[test]
+    fn parse_newtype() {
+        let source_path = Path::new("foo.t");
+        let source = "newtype Id<T> = [T]";
+        let tokens = tokenize(source_path, source).unwrap();
 
-                y: F64 = 2
-                z = 3
-            }
-        ";
+        assert_same!(
+            parse(source_path, source, &tokens[..]),
+            Ok(schema::Schema {
+                comment: vec![],
+                imports: BTreeMap::new(),
+                constants: vec![],
+                declarations: vec![schema::Declaration {
+                    source_range: SourceRange { start: 0, end: 19 },
+                    comment: vec![],
+                    attributes: vec![],
+                    variant: schema::DeclarationVariant::Newtype(schema::Type {
+                        source_range: SourceRange { start: 16, end: 19 },
+                        variant: schema::TypeVariant::Array(
+                            Box::new(schema::Type {
+                                source_range: SourceRange { start: 17, end: 18 },
+                                variant: schema::TypeVariant::Custom(None, "T".into(), vec![]),
+                            }),
+                            None,
+                        ),
+                    }),
+                    name: "Id".into(),
+                    parameters: vec!["T".into()],
+                    fields: vec![],
+                    declarations: vec![],
+                    deleted: BTreeSet::new(),
+                }],
+                services: vec![],
+            }),
+        );
+    }
+
+    #[test]
+    fn parse_newtype_missing_equals() {
+        let source_path = Path::new("foo.t");
+        let source = "newtype Id U64";
         let tokens = tokenize(source_path, source).unwrap();
 
-        let mut imports = BTreeMap::new();
+        assert_fails!(parse(source_path, source, &tokens[..]), "Expected `=`");
+    }
 
-        imports.insert(
-            "baz".into(),
-            schema::Import {
-                source_range: SourceRange { start: 55, end: 69 },
-                path: Path::new("baz.t").to_owned(),
-                namespace: None,
-            },
-        );
+    #[test]
+    fn parse_newtype_missing_type() {
+        let source_path = Path::new("foo.t");
+        let source = "newtype Id =";
+        let tokens = tokenize(source_path, source).unwrap();
 
-        imports.insert(
-            "corge".into(),
-            schema::Import {
-                source_range: SourceRange {
-                    start: 82,
-                    end: 105,
-                },
-                path: Path::new("qux.t").to_owned(),
-                namespace: None,
-            },
-        );
+        assert_fails!(parse(source_path, source, &tokens[..]), "Expected a type");
+    }
 
-        let declarations = vec![
-            schema::Declaration {
-                source_range: SourceRange {
-                    start: 119,
-                    end: 343,
-                },
-                comment: vec!["This is a struct.".to_owned()],
-                variant: schema::DeclarationVariant::Struct,
-                name: "Foo".into(),
-                fields: vec![
-                    schema::Field {
-                        source_range: SourceRange {
-                            start: 180,
-                            end: 194,
-                        },
+    #[test]
+    #[allow(clippy::too_many_lines)]
+    fn parse_constants() {
+        let source_path = Path::new("foo.t");
+        let source = "
+            const VERSION: U64 = 3
+            # The offset.
+            const OFFSET: S64 = -7
+            const RATIO: F64 = -1.5
+            const ENABLED: Bool = true
+            const GREETING: String = \"hi\"
+            const MAGIC: Bytes = 0x7f45
+        ";
+        let tokens = tokenize(source_path, source).unwrap();
+
+        assert_same!(
+            parse(source_path, source, &tokens[..]),
+            Ok(schema::Schema {
+                comment: vec![],
+                imports: BTreeMap::new(),
+                constants: vec![
+                    schema::Constant {
+                        source_range: SourceRange { start: 13, end: 35 },
                         comment: vec![],
-                        rule: schema::Rule::Required,
-                        name: "w".into(),
+                        name: "VERSION".into(),
                         r#type: schema::Type {
-                            source_range: SourceRange {
-                                start: 183,
-                                end: 190,
-                            },
-                            variant: schema::TypeVariant::Custom(Some("baz".into()), "Baz".into()),
+                            source_range: SourceRange { start: 28, end: 31 },
+                            variant: schema::TypeVariant::U64,
                         },
-                        index: 0,
+                        value: schema::Literal::Integer(3),
                     },
-                    schema::Field {
+                    schema::Constant {
+                        source_range: SourceRange { start: 48, end: 96 },
+                        comment: vec!["The offset.".to_owned()],
+                        name: "OFFSET".into(),
+                        r#type: schema::Type {
+                            source_range: SourceRange { start: 88, end: 91 },
+                            variant: schema::TypeVariant::S64,
+                        },
+                        value: schema::Literal::Integer(-7),
+                    },
+                    schema::Constant {
                         source_range: SourceRange {
-                            start: 212,
-                            end: 276,
+                            start: 109,
+                            end: 132,
                         },
-                        comment: vec!["This is an optional field.".to_owned()],
-                        rule: schema::Rule::Optional,
-                        name: "x".into(),
+                        comment: vec![],
+                        name: "RATIO".into(),
                         r#type: schema::Type {
                             source_range: SourceRange {
-                                start: 269,
-                                end: 272,
+                                start: 122,
+                                end: 125,
                             },
-                            variant: schema::TypeVariant::U64,
+                            variant: schema::TypeVariant::F64,
                         },
-                        index: 1,
+                        value: schema::Literal::Float(-1.5),
                     },
-                    schema::Field {
+                    schema::Constant {
                         source_range: SourceRange {
-                            start: 294,
-                            end: 307,
+                            start: 145,
+                            end: 171,
                         },
                         comment: vec![],
-                        rule: schema::Rule::Required,
-                        name: "y".into(),
+                        name: "ENABLED".into(),
                         r#type: schema::Type {
                             source_range: SourceRange {
-                                start: 297,
-                                end: 303,
+                                start: 160,
+                                end: 164,
                             },
-                            variant: schema::TypeVariant::Array(Box::new(schema::Type {
-                                source_range: SourceRange {
-                                    start: 298,
-                                    end: 302,
-                                },
-                                variant: schema::TypeVariant::Bool,
-                            })),
+                            variant: schema::TypeVariant::Bool,
                         },
-                        index: 2,
+                        value: schema::Literal::Bool(true),
                     },
-                    schema::Field {
+                    schema::Constant {
                         source_range: SourceRange {
-                            start: 324,
-                            end: 329,
+                            start: 184,
+                            end: 213,
                         },
                         comment: vec![],
-                        rule: schema::Rule::Required,
-                        name: "z".into(),
+                        name: "GREETING".into(),
                         r#type: schema::Type {
                             source_range: SourceRange {
-                                start: 326,
-                                end: 326,
+                                start: 200,
+                                end: 206,
                             },
-                            variant: schema::TypeVariant::Unit,
+                            variant: schema::TypeVariant::String,
                         },
-                        index: 3,
+                        value: schema::Literal::String("hi".to_owned()),
                     },
-                ],
-                deleted: BTreeSet::new(),
-            },
-            schema::Declaration {
-                source_range: SourceRange {
-                    start: 357,
-                    end: 588,
-                },
-                comment: vec!["This is a choice.".to_owned()],
-                variant: schema::DeclarationVariant::Choice,
-                name: "Bar".into(),
-                fields: vec![
-                    schema::Field {
+                    schema::Constant {
                         source_range: SourceRange {
-                            start: 418,
-                            end: 434,
+                            start: 226,
+                            end: 253,
                         },
                         comment: vec![],
-                        rule: schema::Rule::Required,
-                        name: "w".into(),
+                        name: "MAGIC".into(),
                         r#type: schema::Type {
                             source_range: SourceRange {
-                                start: 421,
-                                end: 430,
+                                start: 239,
+                                end: 244,
                             },
-                            variant: schema::TypeVariant::Custom(
-                                Some("corge".into()),
-                                "Qux".into(),
-                            ),
+                            variant: schema::TypeVariant::Bytes(None),
                         },
-                        index: 0,
+                        value: schema::Literal::Bytes(vec![0x7f, 0x45]),
                     },
-                    schema::Field {
-                        source_range: SourceRange {
-                            start: 452,
-                            end: 524,
-                        },
-                        comment: vec!["This is an asymmetric field.".to_owned()],
-                        rule: schema::Rule::Asymmetric,
-                        name: "x".into(),
-                        r#type: schema::Type {
+                ],
+                declarations: vec![],
+                services: vec![],
+            }),
+        );
+    }
+
+    #[test]
+    fn parse_constant_missing_value() {
+        let source_path = Path::new("foo.t");
+        let source = "const VERSION: U64 =";
+        let tokens = tokenize(source_path, source).unwrap();
+
+        assert_fails!(parse(source_path, source, &tokens[..]), "Expected a value");
+    }
+
+    #[test]
+    fn parse_constant_negative_string() {
+        let source_path = Path::new("foo.t");
+        let source = "const NAME: String = -\"foo\"";
+        let tokens = tokenize(source_path, source).unwrap();
+
+        assert_fails!(parse(source_path, source, &tokens[..]), "Expected a number");
+    }
+
+    #[test]
+    fn parse_service() {
+        let source_path = Path::new("foo.t");
+        let source = "
+            service Mail {
+                # Send an email.
+                send: Request -> Response = 0
+                ping: Ping -> Pong = 2
+
+                deleted 1
+            }
+        ";
+        let tokens = tokenize(source_path, source).unwrap();
+
+        assert_same!(
+            parse(source_path, source, &tokens[..]),
+            Ok(schema::Schema {
+                comment: vec![],
+                imports: BTreeMap::new(),
+                constants: vec![],
+                declarations: vec![],
+                services: vec![schema::Service {
+                    source_range: SourceRange {
+                        start: 13,
+                        end: 186,
+                    },
+                    comment: vec![],
+                    name: "Mail".into(),
+                    methods: vec![
+                        schema::Method {
                             source_range: SourceRange {
-                                start: 513,
-                                end: 520,
+                                start: 44,
+                                end: 106,
                             },
-                            variant: schema::TypeVariant::Array(Box::new(schema::Type {
+                            comment: vec!["Send an email.".to_owned()],
+                            name: "send".into(),
+                            input_type: schema::Type {
+                                source_range: SourceRange { start: 83, end: 90 },
+                                variant: schema::TypeVariant::Custom(
+                                    None,
+                                    "Request".into(),
+                                    vec![],
+                                ),
+                            },
+                            output_type: schema::Type {
                                 source_range: SourceRange {
-                                    start: 514,
-                                    end: 519,
+                                    start: 94,
+                                    end: 102
                                 },
-                                variant: schema::TypeVariant::Bytes,
-                            })),
+                                variant: schema::TypeVariant::Custom(
+                                    None,
+                                    "Response".into(),
+                                    vec![],
+                                ),
+                            },
+                            index: 0,
                         },
-                        index: 1,
-                    },
-                    schema::Field {
-                        source_range: SourceRange {
-                            start: 542,
-                            end: 552,
+                        schema::Method {
+                            source_range: SourceRange {
+                                start: 123,
+                                end: 145,
+                            },
+                            comment: vec![],
+                            name: "ping".into(),
+                            input_type: schema::Type {
+                                source_range: SourceRange {
+                                    start: 129,
+                                    end: 133,
+                                },
+                                variant: schema::TypeVariant::Custom(None, "Ping".into(), vec![]),
+                            },
+                            output_type: schema::Type {
+                                source_range: SourceRange {
+                                    start: 137,
+                                    end: 141,
+                                },
+                                variant: schema::TypeVariant::Custom(None, "Pong".into(), vec![]),
+                            },
+                            index: 2,
                         },
+                    ],
+                    deleted: BTreeSet::from_iter(vec![1]),
+                }],
+            }),
+        );
+    }
+
+    #[test]
+    fn parse_service_missing_arrow() {
+        let source_path = Path::new("foo.t");
+        let source = "service Mail { send: Request Response = 0 }";
+        let tokens = tokenize(source_path, source).unwrap();
+
+        assert_fails!(parse(source_path, source, &tokens[..]), "Expected `->`");
+    }
+
+    #[test]
+    fn parse_nested() {
+        let source_path = Path::new("foo.t");
+        let source = "struct Foo { x: Foo.Bar = 0 choice Bar { y: U64 = 0 } }";
+        let tokens = tokenize(source_path, source).unwrap();
+
+        assert_same!(
+            parse(source_path, source, &tokens[..]),
+            Ok(schema::Schema {
+                comment: vec![],
+                imports: BTreeMap::new(),
+                constants: vec![],
+                declarations: vec![schema::Declaration {
+                    source_range: SourceRange { start: 0, end: 55 },
+                    comment: vec![],
+                    attributes: vec![],
+                    variant: schema::DeclarationVariant::Struct,
+                    name: "Foo".into(),
+                    parameters: vec![],
+                    fields: vec![schema::Field {
+                        source_range: SourceRange { start: 13, end: 27 },
                         comment: vec![],
+                        attributes: vec![],
                         rule: schema::Rule::Required,
-                        name: "y".into(),
+                        name: "x".into(),
                         r#type: schema::Type {
-                            source_range: SourceRange {
-                                start: 545,
-                                end: 548,
-                            },
-                            variant: schema::TypeVariant::F64,
-                        },
-                        index: 2,
-                    },
-                    schema::Field {
-                        source_range: SourceRange {
-                            start: 569,
-                            end: 574,
+                            source_range: SourceRange { start: 16, end: 23 },
+                            variant: schema::TypeVariant::Custom(None, "Foo.Bar".into(), vec![]),
                         },
+                        index: 0,
+                    }],
+                    declarations: vec![schema::Declaration {
+                        source_range: SourceRange { start: 28, end: 53 },
+                        comment: vec![],
+                        attributes: vec![],
+                        variant: schema::DeclarationVariant::Choice,
+                        name: "Bar".into(),
+                        parameters: vec![],
+                        fields: vec![schema::Field {
+                            source_range: SourceRange { start: 41, end: 51 },
+                            comment: vec![],
+                            attributes: vec![],
+                            rule: schema::Rule::Required,
+                            name: "y".into(),
+                            r#type: schema::Type {
+                                source_range: SourceRange { start: 44, end: 47 },
+                                variant: schema::TypeVariant::U64,
+                            },
+                            index: 0,
+                        }],
+                        declarations: vec![],
+                        deleted: BTreeSet::new(),
+                    }],
+                    deleted: BTreeSet::new(),
+                }],
+                services: vec![],
+            }),
+        );
+    }
+
+    #[test]
+    fn parse_nested_imported() {
+        let source_path = Path::new("foo.t");
+        let source = "import 'bar.t' struct Foo { x: bar.Bar.Baz = 0 }";
+        let tokens = tokenize(source_path, source).unwrap();
+        let schema = parse(source_path, source, &tokens[..]).unwrap();
+
+        let schema::TypeVariant::Custom(import, name, _) =
+            &schema.declarations[0].fields[0].r#type.variant
+        else {
+            panic!();
+        };
+
+        assert_eq!(import, &Some("bar".into()));
+        assert_eq!(name.original(), "Bar.Baz");
+    }
+
+    #[test]
+    fn parse_nested_missing_brace() {
+        let source_path = Path::new("foo.t");
+        let source = "struct Foo { choice Bar { x: U64 = 0 }";
+        let tokens = tokenize(source_path, source).unwrap();
+
+        assert_fails!(parse(source_path, source, &tokens[..]), "Expected `}`");
+    }
+
+    #[test]
+    fn parse_attributes() {
+        let source_path = Path::new("foo.t");
+        let source = "@rust.derive(Hash, Eq) struct Foo { @deprecated(\"Use y.\") x: U64 = 0 }";
+        let tokens = tokenize(source_path, source).unwrap();
+
+        assert_same!(
+            parse(source_path, source, &tokens[..]),
+            Ok(schema::Schema {
+                comment: vec![],
+                imports: BTreeMap::new(),
+                constants: vec![],
+                declarations: vec![schema::Declaration {
+                    source_range: SourceRange { start: 0, end: 70 },
+                    comment: vec![],
+                    attributes: vec![schema::Attribute {
+                        source_range: SourceRange { start: 0, end: 22 },
+                        target: Some("rust".into()),
+                        name: "derive".into(),
+                        arguments: vec![
+                            schema::AttributeArgument::Identifier("Hash".into()),
+                            schema::AttributeArgument::Identifier("Eq".into()),
+                        ],
+                    }],
+                    variant: schema::DeclarationVariant::Struct,
+                    name: "Foo".into(),
+                    parameters: vec![],
+                    fields: vec![schema::Field {
+                        source_range: SourceRange { start: 36, end: 68 },
                         comment: vec![],
+                        attributes: vec![schema::Attribute {
+                            source_range: SourceRange { start: 36, end: 57 },
+                            target: None,
+                            name: "deprecated".into(),
+                            arguments: vec![schema::AttributeArgument::Literal(
+                                schema::Literal::String("Use y.".to_owned(),)
+                            )],
+                        }],
                         rule: schema::Rule::Required,
-                        name: "z".into(),
+                        name: "x".into(),
                         r#type: schema::Type {
-                            source_range: SourceRange {
-                                start: 571,
-                                end: 571,
-                            },
-                            variant: schema::TypeVariant::Unit,
+                            source_range: SourceRange { start: 61, end: 64 },
+                            variant: schema::TypeVariant::U64,
                         },
-                        index: 3,
-                    },
-                ],
-                deleted: BTreeSet::new(),
-            },
-        ];
+                        index: 0,
+                    }],
+                    declarations: vec![],
+                    deleted: BTreeSet::new(),
+                }],
+                services: vec![],
+            }),
+        );
+    }
+
+    #[test]
+    fn parse_attribute_numeric_argument() {
+        let source_path = Path::new("foo.t");
+        let source = "struct Foo { @min(-2) x: S64 = 0 }";
+        let tokens = tokenize(source_path, source).unwrap();
 
         assert_same!(
             parse(source_path, source, &tokens[..]),
             Ok(schema::Schema {
-                comment: vec!["This is an example schema.".to_owned()],
-                imports,
-                declarations,
+                comment: vec![],
+                imports: BTreeMap::new(),
+                constants: vec![],
+                declarations: vec![schema::Declaration {
+                    source_range: SourceRange { start: 0, end: 34 },
+                    comment: vec![],
+                    attributes: vec![],
+                    variant: schema::DeclarationVariant::Struct,
+                    name: "Foo".into(),
+                    parameters: vec![],
+                    fields: vec![schema::Field {
+                        source_range: SourceRange { start: 13, end: 32 },
+                        comment: vec![],
+                        attributes: vec![schema::Attribute {
+                            source_range: SourceRange { start: 13, end: 21 },
+                            target: None,
+                            name: "min".into(),
+                            arguments: vec![schema::AttributeArgument::Literal(
+                                schema::Literal::Integer(-2),
+                            )],
+                        }],
+                        rule: schema::Rule::Required,
+                        name: "x".into(),
+                        r#type: schema::Type {
+                            source_range: SourceRange { start: 25, end: 28 },
+                            variant: schema::TypeVariant::S64,
+                        },
+                        index: 0,
+                    }],
+                    declarations: vec![],
+                    deleted: BTreeSet::new(),
+                }],
+                services: vec![],
             }),
         );
     }
 
+    #[test]
+    fn parse_attribute_bad_argument() {
+        let source_path = Path::new("foo.t");
+        let source = "struct Foo { @min(-x) x: S64 = 0 }";
+        let tokens = tokenize(source_path, source).unwrap();
+
+        assert_fails!(parse(source_path, source, &tokens[..]), "Expected a number");
+    }
+
+    #[test]
+    fn parse_attribute_missing_argument() {
+        let source_path = Path::new("foo.t");
+        let source = "@rust.derive() struct Foo {}";
+        let tokens = tokenize(source_path, source).unwrap();
+
+        assert_fails!(
+            parse(source_path, source, &tokens[..]),
+            "Expected an argument for the attribute",
+        );
+    }
+
+    #[test]
+    fn parse_attribute_on_constant() {
+        let source_path = Path::new("foo.t");
+        let source = "@deprecated const FOO: U64 = 0";
+        let tokens = tokenize(source_path, source).unwrap();
+
+        assert_fails!(
+            parse(source_path, source, &tokens[..]),
+            "Attributes can only be applied to declarations and fields.",
+        );
+    }
+
+    #[test]
+    fn parse_map_missing_colon() {
+        let source_path = Path::new("foo.t");
+        let source = "struct Foo { x: {String U64} = 0 }";
+        let tokens = tokenize(source_path, source).unwrap();
+
+        assert_fails!(parse(source_path, source, &tokens[..]), "Expected `:`");
+    }
+
     #[test]
     fn parse_duplicate_import() {
         let source_path = Path::new("foo.t");
@@ -3,12 +3,15 @@ use {
         error::SourceRange,
         identifier::Identifier,
         token::{
-            ASYMMETRIC_KEYWORD, AS_KEYWORD, BOOL_KEYWORD, BYTES_KEYWORD, CHOICE_KEYWORD,
-            DELETED_KEYWORD, F64_KEYWORD, IMPORT_KEYWORD, OPTIONAL_KEYWORD, S64_KEYWORD,
-            STRING_KEYWORD, STRUCT_KEYWORD, U64_KEYWORD, UNIT_KEYWORD,
+            write_bytes_literal, write_string_literal, ASYMMETRIC_KEYWORD, AS_KEYWORD,
+            BOOL_KEYWORD, BYTES_KEYWORD, CHOICE_KEYWORD, CONST_KEYWORD, DELETED_KEYWORD,
+            F32_KEYWORD, F64_KEYWORD, FALSE_KEYWORD, IMPORT_KEYWORD, NEWTYPE_KEYWORD,
+            OPTIONAL_KEYWORD, S32_KEYWORD, S64_KEYWORD, SERVICE_KEYWORD, STRING_KEYWORD,
+            STRUCT_KEYWORD, TRUE_KEYWORD, U32_KEYWORD, U64_KEYWORD, U8_KEYWORD, UNIT_KEYWORD,
         },
     },
     std::{
+        cmp::Ordering,
         collections::{BTreeMap, BTreeSet},
         fmt::{self, Display, Formatter, Write},
         path::PathBuf,
@@ -18,13 +21,17 @@ use {
 const MAX_COLUMNS: usize = 79;
 
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub struct Schema {
     pub comment: Vec<String>,
     pub imports: BTreeMap<Identifier, Import>,
+    pub constants: Vec<Constant>,
     pub declarations: Vec<Declaration>,
+    pub services: Vec<Service>,
 }
 
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub struct Import {
     pub source_range: SourceRange,
     pub path: PathBuf, // The literal path as it appears in the source file
@@ -32,25 +39,53 @@ pub struct Import {
 }
 
 #[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct Constant {
+    pub source_range: SourceRange,
+    pub comment: Vec<String>,
+    pub name: Identifier,
+    pub r#type: Type,
+    pub value: Literal,
+}
+
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum Literal {
+    Bool(bool),
+    Bytes(Vec<u8>),
+    Float(f64),
+    Integer(i128), // Wide enough for both `U64` and `S64`
+    String(String),
+}
+
+#[derive(Clone, Debug)]
+#[non_exhaustive]
 pub struct Declaration {
     pub source_range: SourceRange,
     pub comment: Vec<String>,
+    pub attributes: Vec<Attribute>,
     pub variant: DeclarationVariant,
     pub name: Identifier,
+    pub parameters: Vec<Identifier>, // Type parameters, if the declaration is generic
     pub fields: Vec<Field>,
+    pub declarations: Vec<Declaration>, // Nested declarations
     pub deleted: BTreeSet<usize>,
 }
 
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum DeclarationVariant {
     Struct,
     Choice,
+    Newtype(Type), // The underlying type
 }
 
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub struct Field {
     pub source_range: SourceRange,
     pub comment: Vec<String>,
+    pub attributes: Vec<Attribute>,
     pub rule: Rule,
     pub name: Identifier,
     pub r#type: Type,
@@ -58,6 +93,44 @@ pub struct Field {
 }
 
 #[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct Attribute {
+    pub source_range: SourceRange,
+    pub target: Option<Identifier>, // The code generator the attribute is for, e.g., `rust`
+    pub name: Identifier,
+    pub arguments: Vec<AttributeArgument>,
+}
+
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum AttributeArgument {
+    Identifier(Identifier),
+    Literal(Literal),
+}
+
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct Service {
+    pub source_range: SourceRange,
+    pub comment: Vec<String>,
+    pub name: Identifier,
+    pub methods: Vec<Method>,
+    pub deleted: BTreeSet<usize>,
+}
+
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct Method {
+    pub source_range: SourceRange,
+    pub comment: Vec<String>,
+    pub name: Identifier,
+    pub input_type: Type,
+    pub output_type: Type,
+    pub index: usize,
+}
+
+#[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum Rule {
     Asymmetric,
     Optional,
@@ -65,21 +138,30 @@ pub enum Rule {
 }
 
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub struct Type {
     pub source_range: SourceRange,
     pub variant: TypeVariant,
 }
 
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum TypeVariant {
-    Array(Box<Type>),
+    Array(Box<Type>, Option<usize>), // (element type, fixed length)
     Bool,
-    Bytes,
-    Custom(Option<Identifier>, Identifier), // (import, name)
+    Bytes(Option<usize>),                              // Fixed length
+    Custom(Option<Identifier>, Identifier, Vec<Type>), // (import, name, type arguments)
+    F32,
     F64,
+    Map(Box<Type>, Box<Type>), // (key, value)
+    // A reference to a newtype, which is only introduced by [ref:monomorphization]
+    Newtype(Option<Identifier>, Identifier, Box<Type>), // (import, name, underlying type)
+    S32,
     S64,
     String,
+    U32,
     U64,
+    U8,
     Unit,
 }
 
@@ -96,6 +178,7 @@ pub struct Namespace {
 // latter. The `usize` in the return value corresponds to the number of `..` that is understood to
 // come before the returned namespace (since namespaces don't have a way to encode this
 // information).
+#[must_use]
 pub fn relativize_namespace(namespace1: &Namespace, namespace2: &Namespace) -> (Namespace, usize) {
     // Compute when the namespaces diverge.
     let mut common_components: usize = 0;
@@ -120,6 +203,249 @@ pub fn relativize_namespace(namespace1: &Namespace, namespace2: &Namespace) -> (
     )
 }
 
+// A map is encoded in the same way as an array of structs of the following form, where `K` and `V`
+// are the key and value types of the map [tag:map_encoding]:
+//
+//     struct Entry {
+//         key: K = 0
+//         value: V = 1
+//     }
+//
+// This function constructs that struct, which is useful for encoding and decoding maps with the
+// same logic that handles structs.
+#[must_use]
+pub fn map_entry(key_type: &Type, value_type: &Type) -> Declaration {
+    Declaration {
+        source_range: SourceRange {
+            start: key_type.source_range.start,
+            end: value_type.source_range.end,
+        },
+        comment: vec![],
+        attributes: vec![],
+        variant: DeclarationVariant::Struct,
+        name: "Entry".into(),
+        parameters: vec![],
+        fields: vec![
+            Field {
+                source_range: key_type.source_range,
+                comment: vec![],
+                attributes: vec![],
+                rule: Rule::Required,
+                name: "key".into(),
+                r#type: key_type.clone(),
+                index: 0,
+            },
+            Field {
+                source_range: value_type.source_range,
+                comment: vec![],
+                attributes: vec![],
+                rule: Rule::Required,
+                name: "value".into(),
+                r#type: value_type.clone(),
+                index: 1,
+            },
+        ],
+        declarations: vec![],
+        deleted: BTreeSet::new(),
+    }
+}
+
+// Nested declarations (e.g., `IpAddress` within `Device`) are equivalent to top-level declarations
+// with qualified names (e.g., `Device.IpAddress`), which the code generators render by
+// concatenating the components (e.g., `DeviceIpAddress`) [tag:nested_declarations]. This function
+// hoists the nested declarations of a schema to the top level, so the rest of the pipeline doesn't
+// need to deal with nesting.
+#[must_use]
+pub fn flatten(schema: &Schema) -> Schema {
+    let mut declarations = vec![];
+    hoist_declarations(&mut declarations, None, &schema.declarations);
+
+    Schema {
+        comment: schema.comment.clone(),
+        imports: schema.imports.clone(),
+        constants: schema.constants.clone(),
+        declarations,
+        services: schema.services.clone(),
+    }
+}
+
+// Append the given declarations and their nested declarations to `output`, qualifying their names
+// with the name of the parent, if there is one.
+fn hoist_declarations(
+    output: &mut Vec<Declaration>,
+    parent: Option<&Identifier>,
+    declarations: &[Declaration],
+) {
+    for declaration in declarations {
+        let name = parent.map_or_else(
+            || declaration.name.clone(),
+            |parent| parent.qualify(&declaration.name),
+        );
+
+        output.push(Declaration {
+            source_range: declaration.source_range,
+            comment: declaration.comment.clone(),
+            attributes: declaration.attributes.clone(),
+            variant: declaration.variant.clone(),
+            name: name.clone(),
+            parameters: declaration.parameters.clone(),
+            fields: declaration.fields.clone(),
+            declarations: vec![],
+            deleted: declaration.deleted.clone(),
+        });
+
+        hoist_declarations(output, Some(&name), &declaration.declarations);
+    }
+}
+
+// A newtype is encoded in the same way as its underlying type [tag:newtype_encoding]. This
+// function strips away any newtypes to find the type which determines the encoding.
+#[must_use]
+pub fn encoding_type(type_variant: &TypeVariant) -> &TypeVariant {
+    match type_variant {
+        TypeVariant::Newtype(_, _, underlying_type) => encoding_type(&underlying_type.variant),
+        _ => type_variant,
+    }
+}
+
+// This function returns the message of the `@deprecated` attribute in a list of attributes, if
+// there is one. The message is empty if none was given. The validator guarantees that attributes
+// have the expected arguments [ref:attribute_arguments].
+#[must_use]
+pub fn deprecation(attributes: &[Attribute]) -> Option<&str> {
+    attributes
+        .iter()
+        .find(|attribute| attribute.is(None, "deprecated"))
+        .map(|attribute| match attribute.arguments.first() {
+            Some(AttributeArgument::Literal(Literal::String(message))) => message.as_str(),
+            Some(AttributeArgument::Identifier(_) | AttributeArgument::Literal(_)) | None => "",
+        })
+}
+
+// This function returns the traits listed by the `@rust.derive` attributes in a list of attributes.
+#[must_use]
+pub fn rust_derives(attributes: &[Attribute]) -> Vec<&str> {
+    attributes
+        .iter()
+        .filter(|attribute| attribute.is(Some("rust"), "derive"))
+        .flat_map(|attribute| &attribute.arguments)
+        .filter_map(|argument| match argument {
+            AttributeArgument::Identifier(name) => Some(name.original()),
+            AttributeArgument::Literal(_) => None,
+        })
+        .collect()
+}
+
+// This function returns the name given by the `rename` attribute for a particular code generator
+// (e.g., `@rust.rename(new_name)`) in a list of attributes, if there is one.
+#[must_use]
+pub fn rename<'a>(attributes: &'a [Attribute], target: &str) -> Option<&'a Identifier> {
+    attributes
+        .iter()
+        .find(|attribute| attribute.is(Some(target), "rename"))
+        .and_then(|attribute| match attribute.arguments.first() {
+            Some(AttributeArgument::Identifier(name)) => Some(name),
+            Some(AttributeArgument::Literal(_)) | None => None,
+        })
+}
+
+// The constraints on the values of a field, as given by its `@min`, `@max`, `@min_length`, and
+// `@max_length` attributes [tag:constraints]. Constraints don't affect the encoding, but the
+// generated code rejects messages which violate them.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct Constraints {
+    pub min: Option<Literal>, // An integer or a float
+    pub max: Option<Literal>, // An integer or a float
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+}
+
+impl Constraints {
+    // This function determines whether there are any constraints at all.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.min.is_none()
+            && self.max.is_none()
+            && self.min_length.is_none()
+            && self.max_length.is_none()
+    }
+}
+
+// This function returns the constraints given by a list of attributes. Attributes with malformed
+// arguments are ignored, since the validator reports them [ref:attribute_arguments].
+#[must_use]
+pub fn constraints(attributes: &[Attribute]) -> Constraints {
+    let argument = |name: &str| {
+        attributes
+            .iter()
+            .find(|attribute| attribute.is(None, name))
+            .and_then(|attribute| match attribute.arguments.as_slice() {
+                [AttributeArgument::Literal(
+                    literal @ (Literal::Integer(_) | Literal::Float(_)),
+                )] => Some(literal),
+                _ => None,
+            })
+    };
+
+    let length = |name: &str| match argument(name) {
+        Some(Literal::Integer(integer)) => usize::try_from(*integer).ok(),
+        _ => None,
+    };
+
+    Constraints {
+        min: argument("min").cloned(),
+        max: argument("max").cloned(),
+        min_length: length("min_length"),
+        max_length: length("max_length"),
+    }
+}
+
+// This function describes a violation of a constraint for the error messages of the generated
+// code (e.g., "Field `age` of `Person` must be at least 18."), so the code generators agree on
+// them.
+#[must_use]
+pub fn constraint_violation(
+    declaration: &Identifier,
+    field: &Identifier,
+    requirement: &str,
+) -> String {
+    format!(
+        "Field `{}` of `{}` must {requirement}.",
+        field.snake_case(),
+        declaration.pascal_case(),
+    )
+}
+
+// This function compares two numeric literals, such as the bounds given by `@min` and `@max`. It
+// returns `None` if either literal isn't a number or if the comparison involves NaN.
+#[must_use]
+pub fn compare_numbers(x: &Literal, y: &Literal) -> Option<Ordering> {
+    // Integers which are too large to be represented exactly as floats are rounded, which is fine
+    // for the purpose of comparing them with floats.
+    #[allow(clippy::cast_precision_loss)]
+    match (x, y) {
+        (Literal::Integer(x), Literal::Integer(y)) => Some(x.cmp(y)),
+        (Literal::Integer(x), Literal::Float(y)) => (*x as f64).partial_cmp(y),
+        (Literal::Float(x), Literal::Integer(y)) => x.partial_cmp(&(*y as f64)),
+        (Literal::Float(x), Literal::Float(y)) => x.partial_cmp(y),
+        _ => None,
+    }
+}
+
+// This function replaces the names of fields with the names given by the `rename` attributes for a
+// particular code generator. Field names don't affect the encoding, so the code generators don't
+// need to agree on them.
+pub fn apply_renames(schema: &mut Schema, target: &str) {
+    for declaration in &mut schema.declarations {
+        for field in &mut declaration.fields {
+            if let Some(name) = rename(&field.attributes, target) {
+                field.name = name.clone();
+            }
+        }
+    }
+}
+
 // Write the paragraphs of a comment separated by line breaks.
 fn write_comment<W: Write>(indentation: &str, paragraphs: &[String], f: &mut W) -> fmt::Result {
     for (i, paragraph) in paragraphs.iter().enumerate() {
@@ -139,7 +465,12 @@ impl Schema {
     fn write<W: Write>(&self, f: &mut W) -> fmt::Result {
         write_comment("", &self.comment, f)?;
 
-        if !self.comment.is_empty() && (!self.imports.is_empty() || !self.declarations.is_empty()) {
+        if !self.comment.is_empty()
+            && (!self.imports.is_empty()
+                || !self.constants.is_empty()
+                || !self.declarations.is_empty()
+                || !self.services.is_empty())
+        {
             writeln!(f)?;
         }
 
@@ -148,6 +479,19 @@ impl Schema {
         }
 
         let mut skip_blank_line = self.imports.is_empty();
+        let mut previous_constant_has_comment = false;
+
+        for (i, constant) in self.constants.iter().enumerate() {
+            if skip_blank_line {
+                skip_blank_line = false;
+            } else if i == 0 || previous_constant_has_comment || !constant.comment.is_empty() {
+                writeln!(f)?;
+            }
+
+            previous_constant_has_comment = !constant.comment.is_empty();
+
+            constant.write(f)?;
+        }
 
         for declaration in &self.declarations {
             if skip_blank_line {
@@ -156,7 +500,17 @@ impl Schema {
                 writeln!(f)?;
             }
 
-            declaration.write(f)?;
+            declaration.write(f, "")?;
+        }
+
+        for service in &self.services {
+            if skip_blank_line {
+                skip_blank_line = false;
+            } else {
+                writeln!(f)?;
+            }
+
+            service.write(f)?;
         }
 
         Ok(())
@@ -186,32 +540,100 @@ impl Import {
     }
 }
 
-impl Declaration {
+impl Constant {
     fn write<W: Write>(&self, f: &mut W) -> fmt::Result {
         write_comment("", &self.comment, f)?;
 
+        write!(
+            f,
+            "{} {}: {} = ",
+            CONST_KEYWORD,
+            self.name.screaming_snake_case(),
+            self.r#type,
+        )?;
+        self.value.write(f)?;
+
+        writeln!(f)
+    }
+}
+
+impl Literal {
+    fn write<W: Write>(&self, f: &mut W) -> fmt::Result {
+        match self {
+            Self::Bool(true) => write!(f, "{TRUE_KEYWORD}"),
+            Self::Bool(false) => write!(f, "{FALSE_KEYWORD}"),
+            Self::Bytes(bytes) => write_bytes_literal(f, bytes),
+            Self::Float(float) => write!(f, "{float:?}"),
+            Self::Integer(integer) => write!(f, "{integer}"),
+            Self::String(string) => write_string_literal(f, string),
+        }
+    }
+}
+
+impl Declaration {
+    fn write<W: Write>(&self, f: &mut W, indentation: &str) -> fmt::Result {
+        write_comment(indentation, &self.comment, f)?;
+
+        for attribute in &self.attributes {
+            attribute.write(f, indentation)?;
+        }
+
+        write!(f, "{indentation}")?;
         self.variant.write(f)?;
 
-        writeln!(f, " {} {{", self.name.pascal_case())?;
+        write!(f, " {}", self.name.pascal_case())?;
+
+        if !self.parameters.is_empty() {
+            write!(
+                f,
+                "<{}>",
+                self.parameters
+                    .iter()
+                    .map(Identifier::pascal_case)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )?;
+        }
+
+        if let DeclarationVariant::Newtype(underlying_type) = &self.variant {
+            write!(f, " = ")?;
+            underlying_type.write(f)?;
+            return writeln!(f);
+        }
+
+        writeln!(f, " {{")?;
 
-        let mut previous_field_has_comment = false;
+        let inner_indentation = format!("{indentation}    ");
+        let mut previous_field_is_long = false;
 
         for (i, field) in self.fields.iter().enumerate() {
-            if (previous_field_has_comment || !field.comment.is_empty()) && i != 0 {
+            // Fields with comments or attributes are separated from their neighbors by blank lines.
+            let field_is_long = !field.comment.is_empty() || !field.attributes.is_empty();
+
+            if (previous_field_is_long || field_is_long) && i != 0 {
                 writeln!(f)?;
             }
 
-            previous_field_has_comment = !field.comment.is_empty();
+            previous_field_is_long = field_is_long;
+
+            field.write(f, &inner_indentation)?;
+        }
+
+        // Nested declarations are separated from the fields and from each other by blank lines.
+        for (i, declaration) in self.declarations.iter().enumerate() {
+            if i != 0 || !self.fields.is_empty() {
+                writeln!(f)?;
+            }
 
-            field.write(f)?;
+            declaration.write(f, &inner_indentation)?;
         }
 
-        if !self.fields.is_empty() && !self.deleted.is_empty() {
+        if (!self.fields.is_empty() || !self.declarations.is_empty()) && !self.deleted.is_empty() {
             writeln!(f)?;
         }
 
         if !self.deleted.is_empty() {
-            write!(f, "    {DELETED_KEYWORD}")?;
+            write!(f, "{inner_indentation}{DELETED_KEYWORD}")?;
 
             for deleted_index in &self.deleted {
                 write!(f, " {deleted_index}")?;
@@ -220,7 +642,7 @@ impl Declaration {
             writeln!(f)?;
         }
 
-        writeln!(f, "}}")
+        writeln!(f, "{indentation}}}")
     }
 }
 
@@ -229,23 +651,28 @@ impl DeclarationVariant {
         match self {
             Self::Struct => write!(f, "{STRUCT_KEYWORD}"),
             Self::Choice => write!(f, "{CHOICE_KEYWORD}"),
+            Self::Newtype(_) => write!(f, "{NEWTYPE_KEYWORD}"),
         }
     }
 }
 
 impl Field {
-    fn write<W: Write>(&self, f: &mut W) -> fmt::Result {
-        write_comment("    ", &self.comment, f)?;
+    fn write<W: Write>(&self, f: &mut W, indentation: &str) -> fmt::Result {
+        write_comment(indentation, &self.comment, f)?;
+
+        for attribute in &self.attributes {
+            attribute.write(f, indentation)?;
+        }
 
         match self.rule {
             Rule::Asymmetric => {
-                write!(f, "    {ASYMMETRIC_KEYWORD} ")?;
+                write!(f, "{indentation}{ASYMMETRIC_KEYWORD} ")?;
             }
             Rule::Optional => {
-                write!(f, "    {OPTIONAL_KEYWORD} ")?;
+                write!(f, "{indentation}{OPTIONAL_KEYWORD} ")?;
             }
             Rule::Required => {
-                write!(f, "    ")?;
+                write!(f, "{indentation}")?;
             }
         }
 
@@ -261,6 +688,97 @@ impl Field {
     }
 }
 
+impl Attribute {
+    // This function determines whether an attribute has the given target and name.
+    #[must_use]
+    pub fn is(&self, target: Option<&str>, name: &str) -> bool {
+        self.target == target.map(Identifier::from) && self.name == name.into()
+    }
+
+    // This function returns the name of an attribute as it appears in a schema (e.g.,
+    // `@rust.derive`).
+    #[must_use]
+    pub fn qualified_name(&self) -> String {
+        if let Some(target) = &self.target {
+            format!("@{}.{}", target.snake_case(), self.name.snake_case())
+        } else {
+            format!("@{}", self.name.snake_case())
+        }
+    }
+
+    fn write<W: Write>(&self, f: &mut W, indentation: &str) -> fmt::Result {
+        write!(f, "{indentation}{}", self.qualified_name())?;
+
+        if !self.arguments.is_empty() {
+            write!(f, "(")?;
+
+            for (i, argument) in self.arguments.iter().enumerate() {
+                if i != 0 {
+                    write!(f, ", ")?;
+                }
+
+                match argument {
+                    AttributeArgument::Identifier(name) => write!(f, "{}", name.original())?,
+                    AttributeArgument::Literal(literal) => literal.write(f)?,
+                }
+            }
+
+            write!(f, ")")?;
+        }
+
+        writeln!(f)
+    }
+}
+
+impl Service {
+    fn write<W: Write>(&self, f: &mut W) -> fmt::Result {
+        write_comment("", &self.comment, f)?;
+
+        writeln!(f, "{} {} {{", SERVICE_KEYWORD, self.name.pascal_case())?;
+
+        let mut previous_method_has_comment = false;
+
+        for (i, method) in self.methods.iter().enumerate() {
+            if (previous_method_has_comment || !method.comment.is_empty()) && i != 0 {
+                writeln!(f)?;
+            }
+
+            previous_method_has_comment = !method.comment.is_empty();
+
+            method.write(f)?;
+        }
+
+        if !self.methods.is_empty() && !self.deleted.is_empty() {
+            writeln!(f)?;
+        }
+
+        if !self.deleted.is_empty() {
+            write!(f, "    {DELETED_KEYWORD}")?;
+
+            for deleted_index in &self.deleted {
+                write!(f, " {deleted_index}")?;
+            }
+
+            writeln!(f)?;
+        }
+
+        writeln!(f, "}}")
+    }
+}
+
+impl Method {
+    fn write<W: Write>(&self, f: &mut W) -> fmt::Result {
+        write_comment("    ", &self.comment, f)?;
+
+        write!(f, "    {}: ", self.name.snake_case())?;
+        self.input_type.write(f)?;
+        write!(f, " -> ")?;
+        self.output_type.write(f)?;
+
+        writeln!(f, " = {}", self.index)
+    }
+}
+
 impl Type {
     fn write<W: Write>(&self, f: &mut W) -> fmt::Result {
         self.variant.write(f)
@@ -270,34 +788,86 @@ impl Type {
 impl TypeVariant {
     fn write<W: Write>(&self, f: &mut W) -> fmt::Result {
         match self {
-            Self::Array(inner_type) => {
-                write!(f, "[{inner_type}]")?;
+            Self::Array(inner_type, length) => {
+                if let Some(length) = length {
+                    write!(f, "[{inner_type}; {length}]")?;
+                } else {
+                    write!(f, "[{inner_type}]")?;
+                }
             }
             Self::Bool => {
                 write!(f, "{BOOL_KEYWORD}")?;
             }
-            Self::Bytes => {
-                write!(f, "{BYTES_KEYWORD}")?;
+            Self::Bytes(length) => {
+                if let Some(length) = length {
+                    write!(f, "{BYTES_KEYWORD}({length})")?;
+                } else {
+                    write!(f, "{BYTES_KEYWORD}")?;
+                }
             }
-            Self::Custom(import, name) => {
+            Self::Custom(import, name, arguments) => {
                 if let Some(import) = import {
-                    write!(f, "{}.{}", import.snake_case(), name.pascal_case())?;
+                    write!(
+                        f,
+                        "{}.{}",
+                        import.snake_case(),
+                        name.qualified_pascal_case()
+                    )?;
                 } else {
-                    write!(f, "{}", name.pascal_case())?;
+                    write!(f, "{}", name.qualified_pascal_case())?;
+                }
+
+                if !arguments.is_empty() {
+                    write!(
+                        f,
+                        "<{}>",
+                        arguments
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    )?;
                 }
             }
+            Self::F32 => {
+                write!(f, "{F32_KEYWORD}")?;
+            }
             Self::F64 => {
                 write!(f, "{F64_KEYWORD}")?;
             }
+            Self::Map(key_type, value_type) => {
+                write!(f, "{{{key_type}: {value_type}}}")?;
+            }
+            Self::Newtype(import, name, _) => {
+                if let Some(import) = import {
+                    write!(
+                        f,
+                        "{}.{}",
+                        import.snake_case(),
+                        name.qualified_pascal_case()
+                    )?;
+                } else {
+                    write!(f, "{}", name.qualified_pascal_case())?;
+                }
+            }
+            Self::S32 => {
+                write!(f, "{S32_KEYWORD}")?;
+            }
             Self::S64 => {
                 write!(f, "{S64_KEYWORD}")?;
             }
             Self::String => {
                 write!(f, "{STRING_KEYWORD}")?;
             }
+            Self::U32 => {
+                write!(f, "{U32_KEYWORD}")?;
+            }
             Self::U64 => {
                 write!(f, "{U64_KEYWORD}")?;
             }
+            Self::U8 => {
+                write!(f, "{U8_KEYWORD}")?;
+            }
             Self::Unit => {
                 write!(f, "{UNIT_KEYWORD}")?;
             }
@@ -327,12 +897,24 @@ impl Display for Schema {
     }
 }
 
+impl Display for Literal {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        self.write(f)
+    }
+}
+
 impl Display for Type {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         self.write(f)
     }
 }
 
+impl Display for TypeVariant {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        self.write(f)
+    }
+}
+
 impl Display for Namespace {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         self.write(f)
@@ -345,9 +927,11 @@ mod tests {
         crate::{
             assert_same,
             error::SourceRange,
+            identifier::Identifier,
             schema::{
-                relativize_namespace, Declaration, DeclarationVariant, Field, Import, Namespace,
-                Rule, Schema, Type, TypeVariant,
+                flatten, relativize_namespace, Attribute, AttributeArgument, Constant, Declaration,
+                DeclarationVariant, Field, Import, Literal, Method, Namespace, Rule, Schema,
+                Service, Type, TypeVariant,
             },
         },
         std::{
@@ -493,7 +1077,9 @@ mod tests {
         let schema = Schema {
             comment: vec![],
             imports: BTreeMap::new(),
+            constants: vec![],
             declarations: vec![],
+            services: vec![],
         };
 
         let expected = "";
@@ -526,7 +1112,9 @@ mod tests {
         let schema = Schema {
             comment: vec![],
             imports,
+            constants: vec![],
             declarations: vec![],
+            services: vec![],
         };
 
         let expected = "\
@@ -543,12 +1131,15 @@ mod tests {
             Declaration {
                 source_range: SourceRange { start: 0, end: 0 },
                 comment: vec![],
+                attributes: vec![],
                 variant: DeclarationVariant::Struct,
                 name: "foo".into(),
+                parameters: vec![],
                 fields: vec![
                     Field {
                         source_range: SourceRange { start: 0, end: 0 },
                         comment: vec![],
+                        attributes: vec![],
                         rule: Rule::Required,
                         name: "X".into(),
                         r#type: Type {
@@ -560,6 +1151,7 @@ mod tests {
                     Field {
                         source_range: SourceRange { start: 0, end: 0 },
                         comment: vec![],
+                        attributes: vec![],
                         rule: Rule::Optional,
                         name: "Y".into(),
                         r#type: Type {
@@ -569,17 +1161,21 @@ mod tests {
                         index: 1,
                     },
                 ],
+                declarations: vec![],
                 deleted: BTreeSet::new(),
             },
             Declaration {
                 source_range: SourceRange { start: 0, end: 0 },
                 comment: vec![],
+                attributes: vec![],
                 variant: DeclarationVariant::Choice,
                 name: "bar".into(),
+                parameters: vec![],
                 fields: vec![
                     Field {
                         source_range: SourceRange { start: 0, end: 0 },
                         comment: vec![],
+                        attributes: vec![],
                         rule: Rule::Required,
                         name: "X".into(),
                         r#type: Type {
@@ -591,6 +1187,7 @@ mod tests {
                     Field {
                         source_range: SourceRange { start: 0, end: 0 },
                         comment: vec![],
+                        attributes: vec![],
                         rule: Rule::Asymmetric,
                         name: "Y".into(),
                         r#type: Type {
@@ -600,6 +1197,7 @@ mod tests {
                         index: 1,
                     },
                 ],
+                declarations: vec![],
                 deleted: BTreeSet::new(),
             },
         ];
@@ -607,7 +1205,9 @@ mod tests {
         let schema = Schema {
             comment: vec![],
             imports: BTreeMap::new(),
+            constants: vec![],
             declarations,
+            services: vec![],
         };
 
         let expected = "\
@@ -627,68 +1227,593 @@ mod tests {
 
     #[allow(clippy::too_many_lines)]
     #[test]
-    fn schema_imports_and_declarations_display() {
-        let mut imports = BTreeMap::new();
-
-        imports.insert(
-            "Foo".into(),
-            Import {
-                source_range: SourceRange { start: 0, end: 0 },
-                path: Path::new("foo.t").to_owned(),
-                namespace: None,
-            },
-        );
-
-        imports.insert(
-            "Qux".into(),
-            Import {
-                source_range: SourceRange { start: 0, end: 0 },
-                path: Path::new("bar.t").to_owned(),
-                namespace: None,
-            },
-        );
-
-        let declarations = vec![
-            Declaration {
+    fn schema_generic_declaration_display() {
+        let schema = Schema {
+            comment: vec![],
+            imports: BTreeMap::new(),
+            constants: vec![],
+            declarations: vec![Declaration {
                 source_range: SourceRange { start: 0, end: 0 },
-                comment: vec!["This is a struct.".to_owned()],
+                comment: vec![],
+                attributes: vec![],
                 variant: DeclarationVariant::Struct,
-                name: "foo".into(),
+                name: "pair".into(),
+                parameters: vec!["a".into(), "b".into()],
                 fields: vec![
                     Field {
                         source_range: SourceRange { start: 0, end: 0 },
-                        comment: vec!["This is a field.".to_owned()],
+                        comment: vec![],
+                        attributes: vec![],
                         rule: Rule::Required,
                         name: "X".into(),
                         r#type: Type {
                             source_range: SourceRange { start: 0, end: 0 },
-                            variant: TypeVariant::Bool,
+                            variant: TypeVariant::Custom(None, "a".into(), vec![]),
                         },
                         index: 0,
                     },
                     Field {
                         source_range: SourceRange { start: 0, end: 0 },
-                        comment: vec!["This is a field.".to_owned()],
-                        rule: Rule::Optional,
+                        comment: vec![],
+                        attributes: vec![],
+                        rule: Rule::Required,
                         name: "Y".into(),
                         r#type: Type {
                             source_range: SourceRange { start: 0, end: 0 },
-                            variant: TypeVariant::U64,
+                            variant: TypeVariant::Custom(None, "b".into(), vec![]),
                         },
                         index: 1,
                     },
                 ],
-                deleted: BTreeSet::from_iter(vec![2, 3, 4]),
-            },
-            Declaration {
-                source_range: SourceRange { start: 0, end: 0 },
-                comment: vec!["This is a choice.".to_owned()],
+                declarations: vec![],
+                deleted: BTreeSet::new(),
+            }],
+            services: vec![],
+        };
+
+        let expected = "\
+            struct Pair<A, B> {\n\
+            \x20   x: A = 0\n\
+            \x20   y: B = 1\n\
+            }\n\
+        ";
+
+        assert_eq!(schema.to_string(), expected);
+    }
+
+    #[test]
+    fn schema_newtype_display() {
+        let schema = Schema {
+            comment: vec![],
+            imports: BTreeMap::new(),
+            constants: vec![],
+            declarations: vec![Declaration {
+                source_range: SourceRange { start: 0, end: 0 },
+                comment: vec!["An identifier".to_owned()],
+                attributes: vec![],
+                variant: DeclarationVariant::Newtype(Type {
+                    source_range: SourceRange { start: 0, end: 0 },
+                    variant: TypeVariant::Array(
+                        Box::new(Type {
+                            source_range: SourceRange { start: 0, end: 0 },
+                            variant: TypeVariant::Custom(None, "t".into(), vec![]),
+                        }),
+                        None,
+                    ),
+                }),
+                name: "id".into(),
+                parameters: vec!["t".into()],
+                fields: vec![],
+                declarations: vec![],
+                deleted: BTreeSet::new(),
+            }],
+            services: vec![],
+        };
+
+        let expected = "\
+            # An identifier\n\
+            newtype Id<T> = [T]\n\
+        ";
+
+        assert_eq!(schema.to_string(), expected);
+    }
+
+    #[test]
+    fn schema_fixed_length_display() {
+        let schema = Schema {
+            comment: vec![],
+            imports: BTreeMap::new(),
+            constants: vec![],
+            declarations: vec![Declaration {
+                source_range: SourceRange { start: 0, end: 0 },
+                comment: vec![],
+                attributes: vec![],
+                variant: DeclarationVariant::Struct,
+                name: "checksum".into(),
+                parameters: vec![],
+                fields: vec![
+                    Field {
+                        source_range: SourceRange { start: 0, end: 0 },
+                        comment: vec![],
+                        attributes: vec![],
+                        rule: Rule::Required,
+                        name: "digest".into(),
+                        r#type: Type {
+                            source_range: SourceRange { start: 0, end: 0 },
+                            variant: TypeVariant::Bytes(Some(32)),
+                        },
+                        index: 0,
+                    },
+                    Field {
+                        source_range: SourceRange { start: 0, end: 0 },
+                        comment: vec![],
+                        attributes: vec![],
+                        rule: Rule::Required,
+                        name: "matrix".into(),
+                        r#type: Type {
+                            source_range: SourceRange { start: 0, end: 0 },
+                            variant: TypeVariant::Array(
+                                Box::new(Type {
+                                    source_range: SourceRange { start: 0, end: 0 },
+                                    variant: TypeVariant::Array(
+                                        Box::new(Type {
+                                            source_range: SourceRange { start: 0, end: 0 },
+                                            variant: TypeVariant::F64,
+                                        }),
+                                        Some(2),
+                                    ),
+                                }),
+                                Some(3),
+                            ),
+                        },
+                        index: 1,
+                    },
+                ],
+                declarations: vec![],
+                deleted: BTreeSet::new(),
+            }],
+            services: vec![],
+        };
+
+        let expected = "\
+            struct Checksum {\n\
+            \x20   digest: Bytes(32) = 0\n\
+            \x20   matrix: [[F64; 2]; 3] = 1\n\
+            }\n\
+        ";
+
+        assert_eq!(schema.to_string(), expected);
+    }
+
+    #[test]
+    fn schema_nested_display() {
+        let schema = Schema {
+            comment: vec![],
+            imports: BTreeMap::new(),
+            constants: vec![],
+            declarations: vec![Declaration {
+                source_range: SourceRange { start: 0, end: 0 },
+                comment: vec![],
+                attributes: vec![],
+                variant: DeclarationVariant::Struct,
+                name: "device".into(),
+                parameters: vec![],
+                fields: vec![Field {
+                    source_range: SourceRange { start: 0, end: 0 },
+                    comment: vec![],
+                    attributes: vec![],
+                    rule: Rule::Required,
+                    name: "address".into(),
+                    r#type: Type {
+                        source_range: SourceRange { start: 0, end: 0 },
+                        variant: TypeVariant::Custom(None, "device.ip_address".into(), vec![]),
+                    },
+                    index: 0,
+                }],
+                declarations: vec![Declaration {
+                    source_range: SourceRange { start: 0, end: 0 },
+                    comment: vec!["An IP address".to_owned()],
+                    attributes: vec![],
+                    variant: DeclarationVariant::Choice,
+                    name: "ip_address".into(),
+                    parameters: vec![],
+                    fields: vec![Field {
+                        source_range: SourceRange { start: 0, end: 0 },
+                        comment: vec![],
+                        attributes: vec![],
+                        rule: Rule::Required,
+                        name: "v4".into(),
+                        r#type: Type {
+                            source_range: SourceRange { start: 0, end: 0 },
+                            variant: TypeVariant::U32,
+                        },
+                        index: 0,
+                    }],
+                    declarations: vec![],
+                    deleted: vec![1].into_iter().collect(),
+                }],
+                deleted: vec![1].into_iter().collect(),
+            }],
+            services: vec![],
+        };
+
+        let expected = "\
+            struct Device {\n\
+            \x20   address: Device.IpAddress = 0\n\
+            \n\
+            \x20   # An IP address\n\
+            \x20   choice IpAddress {\n\
+            \x20       v4: U32 = 0\n\
+            \n\
+            \x20       deleted 1\n\
+            \x20   }\n\
+            \n\
+            \x20   deleted 1\n\
+            }\n\
+        ";
+
+        assert_eq!(schema.to_string(), expected);
+    }
+
+    #[test]
+    fn schema_flatten() {
+        let nested = |name: &str, declarations| Declaration {
+            source_range: SourceRange { start: 0, end: 0 },
+            comment: vec![],
+            attributes: vec![],
+            variant: DeclarationVariant::Struct,
+            name: name.into(),
+            parameters: vec![],
+            fields: vec![],
+            declarations,
+            deleted: BTreeSet::new(),
+        };
+
+        let schema = Schema {
+            comment: vec![],
+            imports: BTreeMap::new(),
+            constants: vec![],
+            declarations: vec![
+                nested("Foo", vec![nested("Bar", vec![nested("Baz", vec![])])]),
+                nested("Qux", vec![]),
+            ],
+            services: vec![],
+        };
+
+        assert_eq!(
+            flatten(&schema)
+                .declarations
+                .iter()
+                .map(|declaration| declaration.name.original().to_owned())
+                .collect::<Vec<_>>(),
+            vec!["Foo", "Foo.Bar", "Foo.Bar.Baz", "Qux"],
+        );
+    }
+
+    #[test]
+    #[allow(clippy::too_many_lines)]
+    fn schema_constants_display() {
+        let constant = |comment: &[&str], name: &str, variant, value| Constant {
+            source_range: SourceRange { start: 0, end: 0 },
+            comment: comment.iter().map(|line| (*line).to_owned()).collect(),
+            name: name.into(),
+            r#type: Type {
+                source_range: SourceRange { start: 0, end: 0 },
+                variant,
+            },
+            value,
+        };
+
+        let mut imports = BTreeMap::new();
+        imports.insert(
+            "foo".into(),
+            Import {
+                source_range: SourceRange { start: 0, end: 0 },
+                path: Path::new("foo.t").to_owned(),
+                namespace: None,
+            },
+        );
+
+        let schema = Schema {
+            comment: vec![],
+            imports,
+            constants: vec![
+                constant(&[], "version", TypeVariant::U64, Literal::Integer(3)),
+                constant(&[], "offset", TypeVariant::S64, Literal::Integer(-7)),
+                constant(
+                    &["The ratio"],
+                    "Ratio",
+                    TypeVariant::F64,
+                    Literal::Float(-1.0),
+                ),
+                constant(&[], "enabled", TypeVariant::Bool, Literal::Bool(false)),
+                constant(
+                    &[],
+                    "greeting",
+                    TypeVariant::String,
+                    Literal::String("say \"hi\"".to_owned()),
+                ),
+                constant(
+                    &[],
+                    "MAGIC",
+                    TypeVariant::Bytes(None),
+                    Literal::Bytes(vec![0x7f, 0x45]),
+                ),
+            ],
+            declarations: vec![Declaration {
+                source_range: SourceRange { start: 0, end: 0 },
+                comment: vec![],
+                attributes: vec![],
+                variant: DeclarationVariant::Struct,
+                name: "bar".into(),
+                parameters: vec![],
+                fields: vec![],
+                declarations: vec![],
+                deleted: BTreeSet::new(),
+            }],
+            services: vec![],
+        };
+
+        let expected = "\
+            import 'foo.t'\n\
+            \n\
+            const VERSION: U64 = 3\n\
+            const OFFSET: S64 = -7\n\
+            \n\
+            # The ratio\n\
+            const RATIO: F64 = -1.0\n\
+            \n\
+            const ENABLED: Bool = false\n\
+            const GREETING: String = \"say \\\"hi\\\"\"\n\
+            const MAGIC: Bytes = 0x7f45\n\
+            \n\
+            struct Bar {\n\
+            }\n\
+        ";
+
+        assert_eq!(schema.to_string(), expected);
+    }
+
+    #[test]
+    fn schema_attributes_display() {
+        let attribute = |target: Option<&str>, name: &str, arguments| Attribute {
+            source_range: SourceRange { start: 0, end: 0 },
+            target: target.map(Identifier::from),
+            name: name.into(),
+            arguments,
+        };
+
+        let field = |attributes, name: &str, index| Field {
+            source_range: SourceRange { start: 0, end: 0 },
+            comment: vec![],
+            attributes,
+            rule: Rule::Required,
+            name: name.into(),
+            r#type: Type {
+                source_range: SourceRange { start: 0, end: 0 },
+                variant: TypeVariant::String,
+            },
+            index,
+        };
+
+        let schema = Schema {
+            comment: vec![],
+            imports: BTreeMap::new(),
+            constants: vec![],
+            declarations: vec![Declaration {
+                source_range: SourceRange { start: 0, end: 0 },
+                comment: vec!["A user".to_owned()],
+                attributes: vec![
+                    attribute(
+                        Some("rust"),
+                        "derive",
+                        vec![
+                            AttributeArgument::Identifier("Hash".into()),
+                            AttributeArgument::Identifier("PartialEq".into()),
+                        ],
+                    ),
+                    attribute(None, "deprecated", vec![]),
+                ],
+                variant: DeclarationVariant::Struct,
+                name: "user".into(),
+                parameters: vec![],
+                fields: vec![
+                    field(vec![], "id", 0),
+                    field(
+                        vec![attribute(
+                            None,
+                            "deprecated",
+                            vec![AttributeArgument::Literal(Literal::String(
+                                "Use \"name\".".to_owned(),
+                            ))],
+                        )],
+                        "full_name",
+                        1,
+                    ),
+                    field(
+                        vec![attribute(
+                            Some("typescript"),
+                            "rename",
+                            vec![AttributeArgument::Identifier("kind".into())],
+                        )],
+                        "type",
+                        2,
+                    ),
+                    field(
+                        vec![attribute(
+                            None,
+                            "max_length",
+                            vec![AttributeArgument::Literal(Literal::Integer(64))],
+                        )],
+                        "name",
+                        3,
+                    ),
+                ],
+                declarations: vec![],
+                deleted: BTreeSet::new(),
+            }],
+            services: vec![],
+        };
+
+        let expected = "\
+            # A user\n\
+            @rust.derive(Hash, PartialEq)\n\
+            @deprecated\n\
+            struct User {\n\
+            \x20   id: String = 0\n\
+            \n\
+            \x20   @deprecated(\"Use \\\"name\\\".\")\n\
+            \x20   full_name: String = 1\n\
+            \n\
+            \x20   @typescript.rename(kind)\n\
+            \x20   type: String = 2\n\
+            \n\
+            \x20   @max_length(64)\n\
+            \x20   name: String = 3\n\
+            }\n\
+        ";
+
+        assert_eq!(schema.to_string(), expected);
+    }
+
+    #[test]
+    fn schema_services_display() {
+        let custom = |name: &str| Type {
+            source_range: SourceRange { start: 0, end: 0 },
+            variant: TypeVariant::Custom(None, name.into(), vec![]),
+        };
+
+        let schema = Schema {
+            comment: vec![],
+            imports: BTreeMap::new(),
+            constants: vec![],
+            declarations: vec![Declaration {
+                source_range: SourceRange { start: 0, end: 0 },
+                comment: vec![],
+                attributes: vec![],
+                variant: DeclarationVariant::Struct,
+                name: "Request".into(),
+                parameters: vec![],
+                fields: vec![],
+                declarations: vec![],
+                deleted: BTreeSet::new(),
+            }],
+            services: vec![Service {
+                source_range: SourceRange { start: 0, end: 0 },
+                comment: vec!["A mail server".to_owned()],
+                name: "mail".into(),
+                methods: vec![
+                    Method {
+                        source_range: SourceRange { start: 0, end: 0 },
+                        comment: vec![],
+                        name: "Send".into(),
+                        input_type: custom("Request"),
+                        output_type: custom("Request"),
+                        index: 0,
+                    },
+                    Method {
+                        source_range: SourceRange { start: 0, end: 0 },
+                        comment: vec!["Check the server".to_owned()],
+                        name: "ping".into(),
+                        input_type: custom("Request"),
+                        output_type: custom("Request"),
+                        index: 2,
+                    },
+                ],
+                deleted: BTreeSet::from_iter(vec![1]),
+            }],
+        };
+
+        let expected = "\
+            struct Request {\n\
+            }\n\
+            \n\
+            # A mail server\n\
+            service Mail {\n\
+            \x20   send: Request -> Request = 0\n\
+            \n\
+            \x20   # Check the server\n\
+            \x20   ping: Request -> Request = 2\n\
+            \n\
+            \x20   deleted 1\n\
+            }\n\
+        ";
+
+        assert_eq!(schema.to_string(), expected);
+    }
+
+    #[test]
+    #[allow(clippy::too_many_lines)]
+    fn schema_imports_and_declarations_display() {
+        let mut imports = BTreeMap::new();
+
+        imports.insert(
+            "Foo".into(),
+            Import {
+                source_range: SourceRange { start: 0, end: 0 },
+                path: Path::new("foo.t").to_owned(),
+                namespace: None,
+            },
+        );
+
+        imports.insert(
+            "Qux".into(),
+            Import {
+                source_range: SourceRange { start: 0, end: 0 },
+                path: Path::new("bar.t").to_owned(),
+                namespace: None,
+            },
+        );
+
+        let declarations = vec![
+            Declaration {
+                source_range: SourceRange { start: 0, end: 0 },
+                comment: vec!["This is a struct.".to_owned()],
+                attributes: vec![],
+                variant: DeclarationVariant::Struct,
+                name: "foo".into(),
+                parameters: vec![],
+                fields: vec![
+                    Field {
+                        source_range: SourceRange { start: 0, end: 0 },
+                        comment: vec!["This is a field.".to_owned()],
+                        attributes: vec![],
+                        rule: Rule::Required,
+                        name: "X".into(),
+                        r#type: Type {
+                            source_range: SourceRange { start: 0, end: 0 },
+                            variant: TypeVariant::Bool,
+                        },
+                        index: 0,
+                    },
+                    Field {
+                        source_range: SourceRange { start: 0, end: 0 },
+                        comment: vec!["This is a field.".to_owned()],
+                        attributes: vec![],
+                        rule: Rule::Optional,
+                        name: "Y".into(),
+                        r#type: Type {
+                            source_range: SourceRange { start: 0, end: 0 },
+                            variant: TypeVariant::U64,
+                        },
+                        index: 1,
+                    },
+                ],
+                declarations: vec![],
+                deleted: BTreeSet::from_iter(vec![2, 3, 4]),
+            },
+            Declaration {
+                source_range: SourceRange { start: 0, end: 0 },
+                comment: vec!["This is a choice.".to_owned()],
+                attributes: vec![],
                 variant: DeclarationVariant::Choice,
                 name: "bar".into(),
+                parameters: vec![],
                 fields: vec![
                     Field {
                         source_range: SourceRange { start: 0, end: 0 },
                         comment: vec!["This is a field.".to_owned()],
+                        attributes: vec![],
                         rule: Rule::Required,
                         name: "X".into(),
                         r#type: Type {
@@ -700,6 +1825,7 @@ mod tests {
                     Field {
                         source_range: SourceRange { start: 0, end: 0 },
                         comment: vec!["This is a field.".to_owned()],
+                        attributes: vec![],
                         rule: Rule::Asymmetric,
                         name: "Y".into(),
                         r#type: Type {
@@ -709,6 +1835,7 @@ mod tests {
                         index: 1,
                     },
                 ],
+                declarations: vec![],
                 deleted: BTreeSet::from_iter(vec![2, 3, 4]),
             },
         ];
@@ -723,7 +1850,9 @@ mod tests {
                 "This is a second paragraph in this comment.".to_owned(),
             ],
             imports,
+            constants: vec![],
             declarations,
+            services: vec![],
         };
 
         let expected = "\
@@ -765,10 +1894,13 @@ mod tests {
     fn type_display_array() {
         let r#type = Type {
             source_range: SourceRange { start: 0, end: 0 },
-            variant: TypeVariant::Array(Box::new(Type {
-                source_range: SourceRange { start: 0, end: 0 },
-                variant: TypeVariant::Bool,
-            })),
+            variant: TypeVariant::Array(
+                Box::new(Type {
+                    source_range: SourceRange { start: 0, end: 0 },
+                    variant: TypeVariant::Bool,
+                }),
+                None,
+            ),
         };
 
         let expected = "[Bool]";
@@ -792,7 +1924,7 @@ mod tests {
     fn type_display_bytes() {
         let r#type = Type {
             source_range: SourceRange { start: 0, end: 0 },
-            variant: TypeVariant::Bytes,
+            variant: TypeVariant::Bytes(None),
         };
 
         let expected = "Bytes";
@@ -804,7 +1936,7 @@ mod tests {
     fn type_display_custom_no_import() {
         let r#type = Type {
             source_range: SourceRange { start: 0, end: 0 },
-            variant: TypeVariant::Custom(None, "Int".into()),
+            variant: TypeVariant::Custom(None, "Int".into(), vec![]),
         };
 
         let expected = "Int";
@@ -816,7 +1948,7 @@ mod tests {
     fn type_display_custom_import() {
         let r#type = Type {
             source_range: SourceRange { start: 0, end: 0 },
-            variant: TypeVariant::Custom(Some("foo".into()), "Int".into()),
+            variant: TypeVariant::Custom(Some("foo".into()), "Int".into(), vec![]),
         };
 
         let expected = "foo.Int";
@@ -824,6 +1956,18 @@ mod tests {
         assert_eq!(r#type.to_string(), expected);
     }
 
+    #[test]
+    fn type_display_f32() {
+        let r#type = Type {
+            source_range: SourceRange { start: 0, end: 0 },
+            variant: TypeVariant::F32,
+        };
+
+        let expected = "F32";
+
+        assert_eq!(r#type.to_string(), expected);
+    }
+
     #[test]
     fn type_display_f64() {
         let r#type = Type {
@@ -836,6 +1980,76 @@ mod tests {
         assert_eq!(r#type.to_string(), expected);
     }
 
+    #[test]
+    fn type_display_custom_type_arguments() {
+        let r#type = Type {
+            source_range: SourceRange { start: 0, end: 0 },
+            variant: TypeVariant::Custom(
+                Some("foo".into()),
+                "page".into(),
+                vec![
+                    Type {
+                        source_range: SourceRange { start: 0, end: 0 },
+                        variant: TypeVariant::Custom(None, "user".into(), vec![]),
+                    },
+                    Type {
+                        source_range: SourceRange { start: 0, end: 0 },
+                        variant: TypeVariant::Array(
+                            Box::new(Type {
+                                source_range: SourceRange { start: 0, end: 0 },
+                                variant: TypeVariant::U64,
+                            }),
+                            None,
+                        ),
+                    },
+                ],
+            ),
+        };
+
+        let expected = "foo.Page<User, [U64]>";
+
+        assert_eq!(r#type.to_string(), expected);
+    }
+
+    #[test]
+    fn type_display_map() {
+        let r#type = Type {
+            source_range: SourceRange { start: 0, end: 0 },
+            variant: TypeVariant::Map(
+                Box::new(Type {
+                    source_range: SourceRange { start: 0, end: 0 },
+                    variant: TypeVariant::String,
+                }),
+                Box::new(Type {
+                    source_range: SourceRange { start: 0, end: 0 },
+                    variant: TypeVariant::Array(
+                        Box::new(Type {
+                            source_range: SourceRange { start: 0, end: 0 },
+                            variant: TypeVariant::U64,
+                        }),
+                        None,
+                    ),
+                }),
+            ),
+        };
+
+        let expected = "{String: [U64]}";
+
+        assert_eq!(r#type.to_string(), expected);
+    }
+
+    #[test]
+    fn type_display_s32() {
+        let r#type = Type {
+            source_range: SourceRange { start: 0, end: 0 },
+            variant: TypeVariant::S32,
+        };
+
+        let expected = "S32";
+
+        assert_eq!(r#type.to_string(), expected);
+    }
+
     #[test]
     fn type_display_s64() {
         let r#type = Type {
@@ -860,6 +2074,18 @@ mod tests {
         assert_eq!(r#type.to_string(), expected);
     }
 
+    #[test]
+    fn type_display_u32() {
+        let r#type = Type {
+            source_range: SourceRange { start: 0, end: 0 },
+            variant: TypeVariant::U32,
+        };
+
+        let expected = "U32";
+
+        assert_eq!(r#type.to_string(), expected);
+    }
+
     #[test]
     fn type_display_u64() {
         let r#type = Type {
@@ -872,6 +2098,18 @@ mod tests {
         assert_eq!(r#type.to_string(), expected);
     }
 
+    #[test]
+    fn type_display_u8() {
+        let r#type = Type {
+            source_range: SourceRange { start: 0, end: 0 },
+            variant: TypeVariant::U8,
+        };
+
+        let expected = "U8";
+
+        assert_eq!(r#type.to_string(), expected);
+    }
+
     #[test]
     fn type_display_unit() {
         let r#type = Type {
@@ -1,14 +1,16 @@
 use {
     crate::{
-        error::{listing, throw, Error},
+        error::{listing, throw, Error, Listing},
         format::CodeStr,
+        json,
         parser::parse,
         schema,
         tokenizer::tokenize,
+        well_known,
     },
     std::{
         borrow::ToOwned,
-        collections::{BTreeMap, HashSet},
+        collections::{BTreeMap, HashMap},
         fs::read_to_string,
         io::{self, ErrorKind},
         path::PathBuf,
@@ -16,9 +18,105 @@ use {
     },
 };
 
+// The name of the configuration file, which is looked for in the directory containing the root
+// schema and then in each of its ancestors [tag:config_file]. The nearest one is used.
+pub const CONFIG_FILE_NAME: &str = "typical.json";
+
+// This trait abstracts over where schema files come from. Paths given to these methods are built
+// from the path of the root schema and the import paths, so they may contain `.` and `..`.
+pub trait SourceProvider {
+    // Resolve a path to a file or directory into a canonical form, such that two paths refer to the
+    // same file if and only if their canonical forms are equal. Fail if there is no such file or
+    // directory.
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf>;
+
+    // Read the contents of a file.
+    fn read(&self, path: &Path) -> io::Result<String>;
+}
+
+// This source provider reads schemas from the file system.
+pub struct FileSystem;
+
+impl SourceProvider for FileSystem {
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        path.canonicalize()
+    }
+
+    fn read(&self, path: &Path) -> io::Result<String> {
+        read_to_string(path)
+    }
+}
+
+// This source provider serves schemas from memory, e.g., for schemas that are stored in a database
+// or embedded in tests. Paths are resolved lexically, and a relative path is relative to the same
+// (virtual) directory regardless of the working directory of the process. Directories exist
+// implicitly as long as they contain at least one file.
+#[derive(Clone, Debug, Default)]
+pub struct InMemory {
+    files: HashMap<PathBuf, String>,
+}
+
+impl InMemory {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Add a file, replacing any existing file with the same path. The path is normalized in the
+    // same way as in `canonicalize`, and any path which escapes the root is ignored.
+    pub fn insert<P: AsRef<Path>, C: Into<String>>(&mut self, path: P, contents: C) {
+        if let Some(path) = normalize(path.as_ref()) {
+            self.files.insert(path, contents.into());
+        }
+    }
+}
+
+impl SourceProvider for InMemory {
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        normalize(path)
+            .filter(|path| self.files.keys().any(|file| file.starts_with(path)))
+            .ok_or_else(|| io::Error::from(ErrorKind::NotFound))
+    }
+
+    fn read(&self, path: &Path) -> io::Result<String> {
+        normalize(path)
+            .and_then(|path| self.files.get(&path).cloned())
+            .ok_or_else(|| io::Error::from(ErrorKind::NotFound))
+    }
+}
+
+// Lexically remove `.` and `..` components from a path. Returns `None` if the path escapes the
+// root.
+#[must_use]
+pub fn normalize(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_)),
+                ) {
+                    return None;
+                }
+
+                normalized.pop();
+            }
+            Component::Normal(_) | Component::RootDir | Component::Prefix(_) => {
+                normalized.push(component);
+            }
+        }
+    }
+
+    Some(normalized)
+}
+
 // Convert a path to a namespace. This function will panic if the path cannot be converted into a
 // namespace (e.g., because it contains `..`).
-fn path_to_namespace(path: &Path) -> schema::Namespace {
+#[must_use]
+pub fn path_to_namespace(path: &Path) -> schema::Namespace {
     let mut path = path.to_owned();
     path.set_extension("");
 
@@ -36,12 +134,60 @@ fn path_to_namespace(path: &Path) -> schema::Namespace {
     }
 }
 
-// Load a schema and its transitive dependencies. The imports in the returned schemas are guaranteed
-// to resolve.
-#[allow(clippy::too_many_lines)]
+// A directory that schemas are resolved against. The namespace of a schema is its path relative to
+// the first root which contains it.
+struct Root {
+    // The path to the directory relative to the base directory
+    path: PathBuf,
+
+    // The canonical path to the directory
+    canonical_path: PathBuf,
+}
+
+// Load a schema and its transitive dependencies from the file system. The imports in the returned
+// schemas are guaranteed to resolve.
 #[allow(clippy::type_complexity)]
 pub fn load_schemas(
     schema_path: &Path,
+) -> Result<BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>, Vec<Error>> {
+    load_schemas_with_includes(schema_path, &[])
+}
+
+// Load a schema and its transitive dependencies from the file system, resolving imports in the
+// given include directories as well [ref:include_paths]. The imports in the returned schemas are
+// guaranteed to resolve.
+#[allow(clippy::type_complexity)]
+pub fn load_schemas_with_includes(
+    schema_path: &Path,
+    include_paths: &[PathBuf],
+) -> Result<BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>, Vec<Error>> {
+    load_schemas_from_with_includes(&FileSystem, schema_path, include_paths)
+}
+
+// Load a schema and its transitive dependencies from the given source provider. The imports in the
+// returned schemas are guaranteed to resolve.
+#[allow(clippy::type_complexity)]
+pub fn load_schemas_from<P: SourceProvider + ?Sized>(
+    provider: &P,
+    schema_path: &Path,
+) -> Result<BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>, Vec<Error>> {
+    load_schemas_from_with_includes(provider, schema_path, &[])
+}
+
+// Load a schema and its transitive dependencies from the given source provider, resolving imports
+// in the given include directories as well. The imports in the returned schemas are guaranteed to
+// resolve.
+//
+// Imports are resolved relative to the importing schema and in each of the include directories
+// [tag:include_paths], which are relative to the directory containing the root schema. The include
+// directories listed in the configuration file, if there is one [ref:config_file], are searched
+// after the given ones. An import which refers to more than one file is an error.
+#[allow(clippy::too_many_lines)]
+#[allow(clippy::type_complexity)]
+pub fn load_schemas_from_with_includes<P: SourceProvider + ?Sized>(
+    provider: &P,
+    schema_path: &Path,
+    include_paths: &[PathBuf],
 ) -> Result<BTreeMap<schema::Namespace, (schema::Schema, PathBuf, String)>, Vec<Error>> {
     // The schema and all its transitive dependencies will end up here.
     let mut schemas = BTreeMap::new();
@@ -71,29 +217,30 @@ pub fn load_schemas(
     // compute this by canonicalizing `base_path`, since `base_path` might have zero components,
     // which is considered invalid for canonicalization. So, instead, we canonicalize `schma_path`
     // and take the parent of the result.
-    let canonical_base_path = match schema_path
-        .canonicalize()
-        .and_then(|canonical_schema_path| {
-            canonical_schema_path
-                .parent()
-                .map(ToOwned::to_owned)
-                .ok_or_else(|| io::Error::from(ErrorKind::Other))
-        }) {
-        Ok(canonical_base_path) => canonical_base_path,
-        Err(error) => {
-            errors.push(throw(
-                &format!(
-                    "{} is not a file.",
-                    schema_path.to_string_lossy().code_str(),
-                ),
-                None,
-                None,
-                Some(error),
-            ));
+    let canonical_base_path =
+        match provider
+            .canonicalize(schema_path)
+            .and_then(|canonical_schema_path| {
+                canonical_schema_path
+                    .parent()
+                    .map(ToOwned::to_owned)
+                    .ok_or_else(|| io::Error::from(ErrorKind::Other))
+            }) {
+            Ok(canonical_base_path) => canonical_base_path,
+            Err(error) => {
+                errors.push(throw(
+                    &format!(
+                        "{} is not a file.",
+                        schema_path.to_string_lossy().code_str(),
+                    ),
+                    None,
+                    None,
+                    Some(error),
+                ));
 
-            return Err(errors);
-        }
-    };
+                return Err(errors);
+            }
+        };
 
     // Relative to the base directory, the path to the schema is the name of the schema file
     // [tag:based_schema_path_is_file_name].
@@ -117,20 +264,55 @@ pub fn load_schemas(
     // [ref:based_schema_path_is_file_name].
     let schema_namespace = path_to_namespace(based_schema_path);
 
+    // Determine the directories that imports are resolved against. The base directory comes first,
+    // followed by the given include directories and then those from the configuration file.
+    let mut roots = vec![Root {
+        path: PathBuf::new(),
+        canonical_path: canonical_base_path.clone(),
+    }];
+    let config_include_paths =
+        load_config(provider, base_path, &canonical_base_path).map_err(|error| vec![error])?;
+    for include_path in include_paths.iter().chain(&config_include_paths) {
+        match provider.canonicalize(&base_path.join(include_path)) {
+            Ok(canonical_path) => roots.push(Root {
+                path: include_path.clone(),
+                canonical_path,
+            }),
+            Err(error) => {
+                errors.push(throw(
+                    &format!(
+                        "Unable to find the include directory {}.",
+                        base_path.join(include_path).to_string_lossy().code_str(),
+                    ),
+                    None,
+                    None,
+                    Some(error),
+                ));
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
     // Initialize the "frontier" with the given path. Paths in the frontier are relative to
     // `base_path` [tag:frontier_paths_based].
     let mut schemas_to_load = vec![(
         schema_namespace.clone(),
         based_schema_path.to_owned(),
-        None as Option<(PathBuf, String)>,
+        None as Option<(PathBuf, Listing)>,
     )];
-    let mut visited_namespaces = HashSet::new();
-    visited_namespaces.insert(schema_namespace);
+    let mut visited_namespaces = HashMap::new();
+    visited_namespaces.insert(schema_namespace, based_schema_path.to_owned());
 
     // Perform a depth-first traversal of the transitive dependencies.
     while let Some((namespace, path, origin)) = schemas_to_load.pop() {
-        // Read the file.
-        let contents = match read_to_string(&base_path.join(&path)) {
+        // Read the file. Well-known schemas are embedded rather than read [ref:well_known_schemas].
+        let contents = match well_known::contents(&path).map_or_else(
+            || provider.read(&base_path.join(&path)),
+            |contents| Ok(contents.to_owned()),
+        ) {
             Ok(contents) => contents,
             Err(error) => {
                 let message = format!("Unable to load {}.", path.to_string_lossy().code_str());
@@ -170,67 +352,61 @@ pub fn load_schemas(
             }
         };
 
-        // Compute the base directory for this schema's dependencies. The `unwrap` is safe due to
-        // [ref:frontier_paths_based].
-        let parent_path = path.parent().unwrap();
-
         // Add the dependencies to the frontier.
         for import in schema.imports.values_mut() {
             // Compute the source listing for this import for error reporting.
             let origin_listing = listing(&contents, import.source_range);
 
-            // Compute the import path.
-            let non_canonical_import_path = base_path.join(parent_path.join(&import.path));
+            // Compute the path of the import relative to the base directory along with the path
+            // from which its namespace is derived. Well-known schemas are imported by the same path
+            // from anywhere [ref:well_known_schemas], and their import paths only have normal
+            // components.
+            let (based_import_path, namespace_path) =
+                if well_known::contents(&import.path).is_some() {
+                    (import.path.clone(), import.path.clone())
+                } else {
+                    match resolve_import(
+                        provider,
+                        base_path,
+                        &roots,
+                        &path,
+                        &origin_listing,
+                        &import.path,
+                    ) {
+                        Ok(paths) => paths,
+                        Err(error) => {
+                            errors.push(error);
+
+                            continue;
+                        }
+                    }
+                };
 
-            // Canonicalize the import path.
-            let canonical_import_path = match non_canonical_import_path.canonicalize() {
-                Ok(canonical_import_path) => canonical_import_path,
-                Err(error) => {
-                    errors.push(throw(
+            // Populate the namespace of the import [tag:namespace_populated]. The
+            // path-to-namespace conversion is safe due to
+            // [ref:based_import_path_only_has_normal_components].
+            let import_namespace = path_to_namespace(&namespace_path);
+            import.namespace = Some(import_namespace.clone());
+
+            // Visit this import if it hasn't been visited already. Files in different roots can
+            // correspond to the same namespace, which is an error.
+            if let Some(visited_path) = visited_namespaces.get(&import_namespace) {
+                if *visited_path != based_import_path {
+                    errors.push(throw::<Error>(
                         &format!(
-                            "Unable to load {}.",
-                            non_canonical_import_path.to_string_lossy().code_str(),
+                            "This import refers to {}, which conflicts with {}, since both \
+                            correspond to the same namespace {}.",
+                            based_import_path.to_string_lossy().code_str(),
+                            visited_path.to_string_lossy().code_str(),
+                            import_namespace.to_string().code_str(),
                         ),
                         Some(&path),
                         Some(&origin_listing),
-                        Some(error),
+                        None,
                     ));
-
-                    continue;
                 }
-            };
-
-            // Strip the base path from the schema path. Since this is computed from two canonical
-            // paths, its guaranteed to contain only normal components
-            // [tag:based_import_path_only_has_normal_components].
-            let based_import_path = if let Ok(based_import_path) =
-                canonical_import_path.strip_prefix(&canonical_base_path)
-            {
-                based_import_path.to_owned()
             } else {
-                errors.push(throw::<Error>(
-                    &format!(
-                        "{} is not a descendant of {}, which is the base directory for this run.",
-                        canonical_import_path.to_string_lossy().code_str(),
-                        canonical_base_path.to_string_lossy().code_str(),
-                    ),
-                    Some(&path),
-                    Some(&origin_listing),
-                    None,
-                ));
-
-                continue;
-            };
-
-            // Populate the namespace of the import [tag:namespace_populated]. The
-            // path-to-namespace conversion is safe due to
-            // [ref:based_import_path_only_has_normal_components].
-            let import_namespace = path_to_namespace(&based_import_path);
-            import.namespace = Some(import_namespace.clone());
-
-            // Visit this import if it hasn't been visited already.
-            if !visited_namespaces.contains(&import_namespace) {
-                visited_namespaces.insert(import_namespace.clone());
+                visited_namespaces.insert(import_namespace.clone(), based_import_path.clone());
                 schemas_to_load.push((
                     import_namespace,
                     based_import_path,
@@ -264,14 +440,213 @@ pub fn load_schemas(
     }
 }
 
+// Resolve an import to the file it refers to. The file is looked for relative to the importing
+// schema and in each of the include directories, and it's an error if the import could refer to
+// more than one file. Returns the path of the file relative to the base directory and the path from
+// which its namespace is derived.
+fn resolve_import<P: SourceProvider + ?Sized>(
+    provider: &P,
+    base_path: &Path,
+    roots: &[Root],
+    path: &Path,
+    origin_listing: &Listing,
+    import_path: &Path,
+) -> Result<(PathBuf, PathBuf), Error> {
+    // Find the files the import could refer to, first relative to the importing schema and then in
+    // each of the include directories. The `unwrap` is safe due to [ref:frontier_paths_based].
+    let non_canonical_import_path = base_path.join(path.parent().unwrap().join(import_path));
+    let mut candidates = vec![];
+    let relative_error = match provider.canonicalize(&non_canonical_import_path) {
+        Ok(canonical_import_path) => {
+            candidates.push(canonical_import_path);
+            None
+        }
+        Err(error) => Some(error),
+    };
+    for root in &roots[1..] {
+        if let Ok(canonical_import_path) =
+            provider.canonicalize(&base_path.join(&root.path).join(import_path))
+        {
+            if !candidates.contains(&canonical_import_path) {
+                candidates.push(canonical_import_path);
+            }
+        }
+    }
+
+    // The import must refer to exactly one file.
+    let canonical_import_path = match candidates.as_slice() {
+        [] => {
+            return Err(throw(
+                &if roots.len() == 1 {
+                    format!(
+                        "Unable to load {}.",
+                        non_canonical_import_path.to_string_lossy().code_str(),
+                    )
+                } else {
+                    format!(
+                        "Unable to load {}, and {} isn't in any of the include directories.",
+                        non_canonical_import_path.to_string_lossy().code_str(),
+                        import_path.to_string_lossy().code_str(),
+                    )
+                },
+                Some(path),
+                Some(origin_listing),
+                relative_error,
+            ));
+        }
+        [canonical_import_path] => canonical_import_path,
+        _ => {
+            return Err(throw::<Error>(
+                &format!(
+                    "The import {} is ambiguous, since it could refer to any of the following \
+                    files: {}.",
+                    import_path.to_string_lossy().code_str(),
+                    candidates
+                        .iter()
+                        .map(|candidate| candidate.to_string_lossy().code_str().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ),
+                Some(path),
+                Some(origin_listing),
+                None,
+            ));
+        }
+    };
+
+    // Strip the path of the innermost root which contains the file. Since this is computed from two
+    // canonical paths, its guaranteed to contain only normal components
+    // [tag:based_import_path_only_has_normal_components].
+    roots
+        .iter()
+        .filter_map(|root| {
+            canonical_import_path
+                .strip_prefix(&root.canonical_path)
+                .ok()
+                .map(|rooted_import_path| (root, rooted_import_path))
+        })
+        .min_by_key(|(_, rooted_import_path)| rooted_import_path.components().count())
+        .map(|(root, rooted_import_path)| {
+            (
+                root.path.join(rooted_import_path),
+                rooted_import_path.to_owned(),
+            )
+        })
+        .ok_or_else(|| {
+            throw::<Error>(
+                &format!(
+                    "{} is not a descendant of {} or any of the include directories, which are \
+                    the base directories for this run.",
+                    canonical_import_path.to_string_lossy().code_str(),
+                    roots[0].canonical_path.to_string_lossy().code_str(),
+                ),
+                Some(path),
+                Some(origin_listing),
+                None,
+            )
+        })
+}
+
+// Find the nearest configuration file [ref:config_file] and return the include directories it
+// lists, relative to the base directory. The configuration file is a JSON object such as
+// `{ "include": ["../common"] }`, in which relative paths are relative to the directory containing
+// the configuration file.
+fn load_config<P: SourceProvider + ?Sized>(
+    provider: &P,
+    base_path: &Path,
+    canonical_base_path: &Path,
+) -> Result<Vec<PathBuf>, Error> {
+    // Walk up from the base directory. The canonical path tells us how many ancestors there are.
+    let mut directory = PathBuf::new();
+    for _ in canonical_base_path.ancestors() {
+        let config_path = directory.join(CONFIG_FILE_NAME);
+
+        match provider.read(&base_path.join(&config_path)) {
+            Ok(contents) => {
+                return Ok(parse_config(&config_path, &contents)?
+                    .into_iter()
+                    .map(|include_path| directory.join(include_path))
+                    .collect());
+            }
+            Err(error) if error.kind() == ErrorKind::NotFound => {}
+            Err(error) => {
+                return Err(throw(
+                    &format!(
+                        "Unable to read {}.",
+                        config_path.to_string_lossy().code_str()
+                    ),
+                    None,
+                    None,
+                    Some(error),
+                ));
+            }
+        }
+
+        directory.push("..");
+    }
+
+    Ok(vec![])
+}
+
+// Parse the contents of a configuration file into the list of include directories.
+fn parse_config(path: &Path, contents: &str) -> Result<Vec<PathBuf>, Error> {
+    let value = json::parse(path, contents)?;
+    let config_error = |message: &str, source_range| {
+        throw::<Error>(
+            message,
+            Some(path),
+            Some(&listing(contents, source_range)),
+            None,
+        )
+    };
+
+    let json::Variant::Object(members) = &value.variant else {
+        return Err(config_error("Expected an object.", value.source_range));
+    };
+
+    let mut include_paths = vec![];
+    for member in members {
+        if member.key != "include" {
+            return Err(config_error(
+                &format!("Unknown option {}.", member.key.code_str()),
+                member.key_source_range,
+            ));
+        }
+
+        let Some(elements) = member.value.as_array() else {
+            return Err(config_error(
+                "Expected an array of paths.",
+                member.value.source_range,
+            ));
+        };
+
+        for element in elements {
+            let Some(include_path) = element.as_str() else {
+                return Err(config_error("Expected a path.", element.source_range));
+            };
+
+            include_paths.push(PathBuf::from(include_path));
+        }
+    }
+
+    Ok(include_paths)
+}
+
 #[cfg(test)]
 mod tests {
     use {
         crate::{
+            assert_fails,
             schema::Namespace,
-            schema_loader::{load_schemas, path_to_namespace},
+            schema_loader::{
+                load_schemas, load_schemas_from, load_schemas_from_with_includes,
+                path_to_namespace, InMemory,
+            },
+        },
+        std::{
+            fmt::Write,
+            path::{Path, PathBuf},
         },
-        std::path::Path,
     };
 
     #[test]
@@ -317,4 +692,222 @@ mod tests {
     fn load_schemas_example() {
         load_schemas(Path::new("integration_tests/types/types.t")).unwrap();
     }
+
+    #[test]
+    fn load_schemas_in_memory() {
+        let mut provider = InMemory::new();
+        provider.insert(
+            "main.t",
+            "import 'foo/bar.t'\n\nstruct Main {\n  bar: bar.Bar = 0\n}\n",
+        );
+        provider.insert(
+            "foo/bar.t",
+            "import '../baz.t'\n\nstruct Bar {\n  baz: baz.Baz = 0\n}\n",
+        );
+        provider.insert("./baz.t", "struct Baz {\n}\n");
+
+        let schemas = load_schemas_from(&provider, Path::new("main.t")).unwrap();
+
+        assert_eq!(
+            schemas.keys().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["baz", "foo.bar", "main"],
+        );
+    }
+
+    #[test]
+    fn load_schemas_in_memory_well_known() {
+        let mut provider = InMemory::new();
+        provider.insert(
+            "main.t",
+            "import 'foo/bar.t'\nimport 'typical/time.t'\n\nstruct Main {\n  bar: bar.Bar = 0\n}\n",
+        );
+        provider.insert(
+            "foo/bar.t",
+            "import 'typical/time.t'\n\nstruct Bar {\n  time: time.Timestamp = 0\n}\n",
+        );
+
+        let schemas = load_schemas_from(&provider, Path::new("main.t")).unwrap();
+
+        assert_eq!(
+            schemas.keys().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["foo.bar", "main", "typical.time"],
+        );
+    }
+
+    #[test]
+    fn load_schemas_in_memory_missing_import() {
+        let mut provider = InMemory::new();
+        provider.insert("main.t", "import 'foo.t'\n");
+
+        assert_fails!(
+            load_schemas_from(&provider, Path::new("main.t")),
+            "Unable to load `foo.t`.",
+        );
+    }
+
+    #[test]
+    fn load_schemas_in_memory_import_outside_root() {
+        let mut provider = InMemory::new();
+        provider.insert("main.t", "import '../foo.t'\n");
+
+        assert_fails!(
+            load_schemas_from(&provider, Path::new("main.t")),
+            "Unable to load `../foo.t`.",
+        );
+    }
+
+    #[test]
+    fn load_schemas_in_memory_include() {
+        let mut provider = InMemory::new();
+        provider.insert(
+            "app/api/main.t",
+            "import 'money.t'\n\nstruct Main {\n  price: money.Money = 0\n}\n",
+        );
+        provider.insert(
+            "common/money.t",
+            "import 'currency.t'\n\nstruct Money {\n  currency: currency.Currency = 0\n}\n",
+        );
+        provider.insert("common/currency.t", "struct Currency {\n}\n");
+
+        let schemas = load_schemas_from_with_includes(
+            &provider,
+            Path::new("app/api/main.t"),
+            &[PathBuf::from("../../common")],
+        )
+        .unwrap();
+
+        assert_eq!(
+            schemas
+                .iter()
+                .map(|(namespace, (_, path, _))| (namespace.to_string(), path.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "currency".to_owned(),
+                    PathBuf::from("../../common/currency.t")
+                ),
+                ("main".to_owned(), PathBuf::from("main.t")),
+                ("money".to_owned(), PathBuf::from("../../common/money.t")),
+            ],
+        );
+    }
+
+    #[test]
+    fn load_schemas_in_memory_include_config() {
+        let mut provider = InMemory::new();
+        provider.insert("typical.json", "{ \"include\": [\"common\"] }");
+        provider.insert(
+            "app/main.t",
+            "import 'util/money.t'\n\nstruct Main {\n  price: money.Money = 0\n}\n",
+        );
+        provider.insert("common/util/money.t", "struct Money {\n}\n");
+
+        let schemas = load_schemas_from(&provider, Path::new("app/main.t")).unwrap();
+
+        assert_eq!(
+            schemas.keys().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["main", "util.money"],
+        );
+    }
+
+    #[test]
+    fn load_schemas_in_memory_include_config_invalid() {
+        let mut provider = InMemory::new();
+        provider.insert("typical.json", "{ \"includes\": [\"common\"] }");
+        provider.insert("main.t", "");
+
+        assert_fails!(
+            load_schemas_from(&provider, Path::new("main.t")),
+            "Unknown option `includes`.",
+        );
+    }
+
+    #[test]
+    fn load_schemas_in_memory_include_ambiguous() {
+        let mut provider = InMemory::new();
+        provider.insert("main.t", "import 'money.t'\n");
+        provider.insert("money.t", "");
+        provider.insert("common/money.t", "");
+
+        assert_fails!(
+            load_schemas_from_with_includes(
+                &provider,
+                Path::new("main.t"),
+                &[PathBuf::from("common")]
+            ),
+            "The import `money.t` is ambiguous",
+        );
+    }
+
+    #[test]
+    fn load_schemas_in_memory_include_nested() {
+        let mut provider = InMemory::new();
+        provider.insert(
+            "main.t",
+            "import 'money.t'\nimport 'common/money.t' as other\n",
+        );
+        provider.insert("common/money.t", "");
+
+        let schemas = load_schemas_from_with_includes(
+            &provider,
+            Path::new("main.t"),
+            &[PathBuf::from("common")],
+        )
+        .unwrap();
+
+        assert_eq!(
+            schemas.keys().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["main", "money"],
+        );
+    }
+
+    #[test]
+    fn load_schemas_in_memory_include_namespace_conflict() {
+        let mut provider = InMemory::new();
+        provider.insert("main.t", "import 'p/q.t'\nimport 'p/r.t'\n");
+        provider.insert("a/p/q.t", "import 'x.t'\n");
+        provider.insert("a/p/x.t", "");
+        provider.insert("b/p/r.t", "import 'x.t'\n");
+        provider.insert("b/p/x.t", "");
+
+        assert_fails!(
+            load_schemas_from_with_includes(
+                &provider,
+                Path::new("main.t"),
+                &[PathBuf::from("a"), PathBuf::from("b")],
+            ),
+            "since both correspond to the same namespace `p.x`.",
+        );
+    }
+
+    #[test]
+    fn load_schemas_in_memory_include_missing() {
+        let mut provider = InMemory::new();
+        provider.insert("main.t", "");
+
+        assert_fails!(
+            load_schemas_from_with_includes(
+                &provider,
+                Path::new("main.t"),
+                &[PathBuf::from("common")]
+            ),
+            "Unable to find the include directory `common`.",
+        );
+    }
+
+    #[test]
+    fn load_schemas_in_memory_include_missing_import() {
+        let mut provider = InMemory::new();
+        provider.insert("main.t", "import 'money.t'\n");
+        provider.insert("common/currency.t", "");
+
+        assert_fails!(
+            load_schemas_from_with_includes(
+                &provider,
+                Path::new("main.t"),
+                &[PathBuf::from("common")]
+            ),
+            "`money.t` isn't in any of the include directories.",
+        );
+    }
 }
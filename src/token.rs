@@ -1,7 +1,7 @@
 use {
     crate::{error::SourceRange, identifier::Identifier},
     std::{
-        fmt::{Display, Formatter, Result},
+        fmt::{Display, Formatter, Result, Write},
         path::PathBuf,
     },
 };
@@ -12,14 +12,23 @@ pub const AS_KEYWORD: &str = "as";
 pub const BOOL_KEYWORD: &str = "Bool";
 pub const BYTES_KEYWORD: &str = "Bytes";
 pub const CHOICE_KEYWORD: &str = "choice";
+pub const CONST_KEYWORD: &str = "const";
 pub const DELETED_KEYWORD: &str = "deleted";
+pub const F32_KEYWORD: &str = "F32";
 pub const F64_KEYWORD: &str = "F64";
+pub const FALSE_KEYWORD: &str = "false";
 pub const IMPORT_KEYWORD: &str = "import";
+pub const NEWTYPE_KEYWORD: &str = "newtype";
 pub const OPTIONAL_KEYWORD: &str = "optional";
+pub const S32_KEYWORD: &str = "S32";
 pub const S64_KEYWORD: &str = "S64";
+pub const SERVICE_KEYWORD: &str = "service";
 pub const STRING_KEYWORD: &str = "String";
 pub const STRUCT_KEYWORD: &str = "struct";
+pub const TRUE_KEYWORD: &str = "true";
+pub const U32_KEYWORD: &str = "U32";
 pub const U64_KEYWORD: &str = "U64";
+pub const U8_KEYWORD: &str = "U8";
 pub const UNIT_KEYWORD: &str = "Unit";
 
 // The first step of compilation is to split the source into a stream of tokens. This struct
@@ -33,30 +42,51 @@ pub struct Token {
 // We assign each token a "variant" describing what kind of token it is.
 #[derive(Clone, Debug)]
 pub enum Variant {
+    Arrow,
     As,
     Asymmetric,
+    At,
     Bool,
     Bytes,
+    BytesLiteral(Vec<u8>),
     Choice,
     Colon,
+    Comma,
     Comment(Vec<String>),
+    Const,
     Deleted,
     Dot,
     Equals,
+    F32,
     F64,
+    False,
+    Float(f64),
     Identifier(Identifier),
     Import,
     Integer(usize),
+    LeftAngle,
     LeftCurly,
+    LeftParen,
     LeftSquare,
+    Minus,
+    Newtype,
     Optional,
     Path(PathBuf),
+    RightAngle,
     RightCurly,
+    RightParen,
     RightSquare,
+    S32,
     S64,
+    Semicolon,
+    Service,
     String,
+    StringLiteral(String),
     Struct,
+    True,
+    U32,
     U64,
+    U8,
     Unit,
 }
 
@@ -69,12 +99,16 @@ impl Display for Token {
 impl Display for Variant {
     fn fmt(&self, f: &mut Formatter) -> Result {
         match self {
+            Self::Arrow => write!(f, "->"),
             Self::As => write!(f, "{AS_KEYWORD}"),
             Self::Asymmetric => write!(f, "{ASYMMETRIC_KEYWORD}"),
+            Self::At => write!(f, "@"),
             Self::Bool => write!(f, "{BOOL_KEYWORD}"),
             Self::Bytes => write!(f, "{BYTES_KEYWORD}"),
+            Self::BytesLiteral(bytes) => write_bytes_literal(f, bytes),
             Self::Choice => write!(f, "{CHOICE_KEYWORD}"),
             Self::Colon => write!(f, ":"),
+            Self::Comma => write!(f, ","),
             Self::Comment(paragraphs) => {
                 for (i, paragraph) in paragraphs.iter().enumerate() {
                     if i != 0 {
@@ -86,28 +120,74 @@ impl Display for Variant {
 
                 Ok(())
             }
+            Self::Const => write!(f, "{CONST_KEYWORD}"),
             Self::Deleted => write!(f, "{DELETED_KEYWORD}"),
             Self::Dot => write!(f, "."),
             Self::Equals => write!(f, "="),
+            Self::F32 => write!(f, "{F32_KEYWORD}"),
             Self::F64 => write!(f, "{F64_KEYWORD}"),
+            Self::False => write!(f, "{FALSE_KEYWORD}"),
+            Self::Float(float) => write!(f, "{float:?}"),
             Self::Identifier(name) => write!(f, "{}", name.original()),
             Self::Import => write!(f, "{IMPORT_KEYWORD}"),
             Self::Integer(integer) => write!(f, "{integer}"),
+            Self::LeftAngle => write!(f, "<"),
             Self::LeftCurly => write!(f, "{{"),
+            Self::LeftParen => write!(f, "("),
             Self::LeftSquare => write!(f, "["),
+            Self::Minus => write!(f, "-"),
+            Self::Newtype => write!(f, "{NEWTYPE_KEYWORD}"),
             Self::Optional => write!(f, "{OPTIONAL_KEYWORD}"),
             Self::Path(path) => write!(f, "'{}'", path.display()),
+            Self::RightAngle => write!(f, ">"),
             Self::RightCurly => write!(f, "}}"),
+            Self::RightParen => write!(f, ")"),
             Self::RightSquare => write!(f, "]"),
+            Self::S32 => write!(f, "{S32_KEYWORD}"),
             Self::S64 => write!(f, "{S64_KEYWORD}"),
+            Self::Semicolon => write!(f, ";"),
+            Self::Service => write!(f, "{SERVICE_KEYWORD}"),
             Self::String => write!(f, "{STRING_KEYWORD}"),
+            Self::StringLiteral(string) => write_string_literal(f, string),
             Self::Struct => write!(f, "{STRUCT_KEYWORD}"),
+            Self::True => write!(f, "{TRUE_KEYWORD}"),
+            Self::U32 => write!(f, "{U32_KEYWORD}"),
             Self::U64 => write!(f, "{U64_KEYWORD}"),
+            Self::U8 => write!(f, "{U8_KEYWORD}"),
             Self::Unit => write!(f, "{UNIT_KEYWORD}"),
         }
     }
 }
 
+// Write a bytes literal as the prefix `0x` followed by two hexadecimal digits per byte.
+pub fn write_bytes_literal<W: Write>(f: &mut W, bytes: &[u8]) -> Result {
+    write!(f, "0x")?;
+
+    for byte in bytes {
+        write!(f, "{byte:02x}")?;
+    }
+
+    Ok(())
+}
+
+// Write a string literal, escaping the characters that the tokenizer treats specially.
+pub fn write_string_literal<W: Write>(f: &mut W, string: &str) -> Result {
+    write!(f, "\"")?;
+
+    for c in string.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            _ => write!(f, "{c}")?,
+        }
+    }
+
+    write!(f, "\"")
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -115,8 +195,10 @@ mod tests {
             error::SourceRange,
             token::{
                 Token, Variant, ASYMMETRIC_KEYWORD, AS_KEYWORD, BOOL_KEYWORD, BYTES_KEYWORD,
-                CHOICE_KEYWORD, DELETED_KEYWORD, F64_KEYWORD, IMPORT_KEYWORD, OPTIONAL_KEYWORD,
-                S64_KEYWORD, STRING_KEYWORD, STRUCT_KEYWORD, U64_KEYWORD, UNIT_KEYWORD,
+                CHOICE_KEYWORD, CONST_KEYWORD, DELETED_KEYWORD, F32_KEYWORD, F64_KEYWORD,
+                FALSE_KEYWORD, IMPORT_KEYWORD, NEWTYPE_KEYWORD, OPTIONAL_KEYWORD, S32_KEYWORD,
+                S64_KEYWORD, SERVICE_KEYWORD, STRING_KEYWORD, STRUCT_KEYWORD, TRUE_KEYWORD,
+                U32_KEYWORD, U64_KEYWORD, U8_KEYWORD, UNIT_KEYWORD,
             },
         },
         std::path::Path,
@@ -136,6 +218,11 @@ mod tests {
         );
     }
 
+    #[test]
+    fn variant_arrow_display() {
+        assert_eq!(format!("{}", Variant::Arrow), "->");
+    }
+
     #[test]
     fn variant_as_display() {
         assert_eq!(format!("{}", Variant::As), AS_KEYWORD);
@@ -146,6 +233,11 @@ mod tests {
         assert_eq!(format!("{}", Variant::Asymmetric), ASYMMETRIC_KEYWORD);
     }
 
+    #[test]
+    fn variant_at_display() {
+        assert_eq!(format!("{}", Variant::At), "@");
+    }
+
     #[test]
     fn variant_bool_display() {
         assert_eq!(format!("{}", Variant::Bool), BOOL_KEYWORD);
@@ -156,6 +248,14 @@ mod tests {
         assert_eq!(format!("{}", Variant::Bytes), BYTES_KEYWORD);
     }
 
+    #[test]
+    fn variant_bytes_literal_display() {
+        assert_eq!(
+            format!("{}", Variant::BytesLiteral(vec![0x00, 0x7f, 0xff])),
+            "0x007fff",
+        );
+    }
+
     #[test]
     fn variant_choice_display() {
         assert_eq!(format!("{}", Variant::Choice), CHOICE_KEYWORD);
@@ -166,6 +266,11 @@ mod tests {
         assert_eq!(format!("{}", Variant::Colon), ":");
     }
 
+    #[test]
+    fn variant_comma_display() {
+        assert_eq!(format!("{}", Variant::Comma), ",");
+    }
+
     #[test]
     fn variant_comment_display() {
         assert_eq!(
@@ -177,6 +282,11 @@ mod tests {
         );
     }
 
+    #[test]
+    fn variant_const_display() {
+        assert_eq!(format!("{}", Variant::Const), CONST_KEYWORD);
+    }
+
     #[test]
     fn variant_deleted_display() {
         assert_eq!(format!("{}", Variant::Deleted), DELETED_KEYWORD);
@@ -192,11 +302,26 @@ mod tests {
         assert_eq!(format!("{}", Variant::Equals), "=");
     }
 
+    #[test]
+    fn variant_f32_display() {
+        assert_eq!(format!("{}", Variant::F32), F32_KEYWORD);
+    }
+
     #[test]
     fn variant_f64_display() {
         assert_eq!(format!("{}", Variant::F64), F64_KEYWORD);
     }
 
+    #[test]
+    fn variant_false_display() {
+        assert_eq!(format!("{}", Variant::False), FALSE_KEYWORD);
+    }
+
+    #[test]
+    fn variant_float_literal_display() {
+        assert_eq!(format!("{}", Variant::Float(1.5)), "1.5");
+    }
+
     #[test]
     fn variant_identifier_display() {
         assert_eq!(format!("{}", Variant::Identifier("foo".into())), "foo");
@@ -212,16 +337,36 @@ mod tests {
         assert_eq!(format!("{}", Variant::Integer(42)), "42");
     }
 
+    #[test]
+    fn variant_left_angle_display() {
+        assert_eq!(format!("{}", Variant::LeftAngle), "<");
+    }
+
     #[test]
     fn variant_left_curly_display() {
         assert_eq!(format!("{}", Variant::LeftCurly), "{");
     }
 
+    #[test]
+    fn variant_left_paren_display() {
+        assert_eq!(format!("{}", Variant::LeftParen), "(");
+    }
+
     #[test]
     fn variant_left_square_display() {
         assert_eq!(format!("{}", Variant::LeftSquare), "[");
     }
 
+    #[test]
+    fn variant_minus_display() {
+        assert_eq!(format!("{}", Variant::Minus), "-");
+    }
+
+    #[test]
+    fn variant_newtype_display() {
+        assert_eq!(format!("{}", Variant::Newtype), NEWTYPE_KEYWORD);
+    }
+
     #[test]
     fn variant_optional_display() {
         assert_eq!(format!("{}", Variant::Optional), OPTIONAL_KEYWORD);
@@ -235,36 +380,84 @@ mod tests {
         );
     }
 
+    #[test]
+    fn variant_right_angle_display() {
+        assert_eq!(format!("{}", Variant::RightAngle), ">");
+    }
+
     #[test]
     fn variant_right_curly_display() {
         assert_eq!(format!("{}", Variant::RightCurly), "}");
     }
 
+    #[test]
+    fn variant_right_paren_display() {
+        assert_eq!(format!("{}", Variant::RightParen), ")");
+    }
+
     #[test]
     fn variant_right_square_display() {
         assert_eq!(format!("{}", Variant::RightSquare), "]");
     }
 
+    #[test]
+    fn variant_s32_display() {
+        assert_eq!(format!("{}", Variant::S32), S32_KEYWORD);
+    }
+
     #[test]
     fn variant_s64_display() {
         assert_eq!(format!("{}", Variant::S64), S64_KEYWORD);
     }
 
+    #[test]
+    fn variant_semicolon_display() {
+        assert_eq!(format!("{}", Variant::Semicolon), ";");
+    }
+
+    #[test]
+    fn variant_service_display() {
+        assert_eq!(format!("{}", Variant::Service), SERVICE_KEYWORD);
+    }
+
     #[test]
     fn variant_string_display() {
         assert_eq!(format!("{}", Variant::String), STRING_KEYWORD);
     }
 
+    #[test]
+    fn variant_string_literal_display() {
+        assert_eq!(
+            format!("{}", Variant::StringLiteral("say \"hi\"\\\n".to_owned())),
+            "\"say \\\"hi\\\"\\\\\\n\"",
+        );
+    }
+
     #[test]
     fn variant_struct_display() {
         assert_eq!(format!("{}", Variant::Struct), STRUCT_KEYWORD);
     }
 
+    #[test]
+    fn variant_true_display() {
+        assert_eq!(format!("{}", Variant::True), TRUE_KEYWORD);
+    }
+
+    #[test]
+    fn variant_u32_display() {
+        assert_eq!(format!("{}", Variant::U32), U32_KEYWORD);
+    }
+
     #[test]
     fn variant_u64_display() {
         assert_eq!(format!("{}", Variant::U64), U64_KEYWORD);
     }
 
+    #[test]
+    fn variant_u8_display() {
+        assert_eq!(format!("{}", Variant::U8), U8_KEYWORD);
+    }
+
     #[test]
     fn variant_unit_display() {
         assert_eq!(format!("{}", Variant::Unit), UNIT_KEYWORD);
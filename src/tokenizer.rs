@@ -4,11 +4,13 @@ use {
         format::CodeStr,
         token::{
             Token, Variant, ASYMMETRIC_KEYWORD, AS_KEYWORD, BOOL_KEYWORD, BYTES_KEYWORD,
-            CHOICE_KEYWORD, DELETED_KEYWORD, F64_KEYWORD, IMPORT_KEYWORD, OPTIONAL_KEYWORD,
-            S64_KEYWORD, STRING_KEYWORD, STRUCT_KEYWORD, U64_KEYWORD, UNIT_KEYWORD,
+            CHOICE_KEYWORD, CONST_KEYWORD, DELETED_KEYWORD, F32_KEYWORD, F64_KEYWORD,
+            FALSE_KEYWORD, IMPORT_KEYWORD, NEWTYPE_KEYWORD, OPTIONAL_KEYWORD, S32_KEYWORD,
+            S64_KEYWORD, SERVICE_KEYWORD, STRING_KEYWORD, STRUCT_KEYWORD, TRUE_KEYWORD,
+            U32_KEYWORD, U64_KEYWORD, U8_KEYWORD, UNIT_KEYWORD,
         },
     },
-    std::path::Path,
+    std::{iter::Peekable, path::Path, str::CharIndices},
     unicode_segmentation::GraphemeCursor,
 };
 
@@ -43,6 +45,24 @@ pub fn tokenize(schema_path: &Path, schema_contents: &str) -> Result<Vec<Token>,
                     variant: Variant::Colon,
                 });
             }
+            '@' => {
+                tokens.push(Token {
+                    source_range: SourceRange {
+                        start: i,
+                        end: i + 1,
+                    },
+                    variant: Variant::At,
+                });
+            }
+            ',' => {
+                tokens.push(Token {
+                    source_range: SourceRange {
+                        start: i,
+                        end: i + 1,
+                    },
+                    variant: Variant::Comma,
+                });
+            }
             '.' => {
                 tokens.push(Token {
                     source_range: SourceRange {
@@ -61,6 +81,15 @@ pub fn tokenize(schema_path: &Path, schema_contents: &str) -> Result<Vec<Token>,
                     variant: Variant::Equals,
                 });
             }
+            '<' => {
+                tokens.push(Token {
+                    source_range: SourceRange {
+                        start: i,
+                        end: i + 1,
+                    },
+                    variant: Variant::LeftAngle,
+                });
+            }
             '{' => {
                 tokens.push(Token {
                     source_range: SourceRange {
@@ -70,6 +99,15 @@ pub fn tokenize(schema_path: &Path, schema_contents: &str) -> Result<Vec<Token>,
                     variant: Variant::LeftCurly,
                 });
             }
+            '(' => {
+                tokens.push(Token {
+                    source_range: SourceRange {
+                        start: i,
+                        end: i + 1,
+                    },
+                    variant: Variant::LeftParen,
+                });
+            }
             '[' => {
                 tokens.push(Token {
                     source_range: SourceRange {
@@ -79,6 +117,35 @@ pub fn tokenize(schema_path: &Path, schema_contents: &str) -> Result<Vec<Token>,
                     variant: Variant::LeftSquare,
                 });
             }
+            '-' if matches!(iter.peek(), Some((_, '>'))) => {
+                iter.next();
+
+                tokens.push(Token {
+                    source_range: SourceRange {
+                        start: i,
+                        end: i + 2,
+                    },
+                    variant: Variant::Arrow,
+                });
+            }
+            '-' => {
+                tokens.push(Token {
+                    source_range: SourceRange {
+                        start: i,
+                        end: i + 1,
+                    },
+                    variant: Variant::Minus,
+                });
+            }
+            '>' => {
+                tokens.push(Token {
+                    source_range: SourceRange {
+                        start: i,
+                        end: i + 1,
+                    },
+                    variant: Variant::RightAngle,
+                });
+            }
             '}' => {
                 tokens.push(Token {
                     source_range: SourceRange {
@@ -88,6 +155,15 @@ pub fn tokenize(schema_path: &Path, schema_contents: &str) -> Result<Vec<Token>,
                     variant: Variant::RightCurly,
                 });
             }
+            ')' => {
+                tokens.push(Token {
+                    source_range: SourceRange {
+                        start: i,
+                        end: i + 1,
+                    },
+                    variant: Variant::RightParen,
+                });
+            }
             ']' => {
                 tokens.push(Token {
                     source_range: SourceRange {
@@ -97,6 +173,15 @@ pub fn tokenize(schema_path: &Path, schema_contents: &str) -> Result<Vec<Token>,
                     variant: Variant::RightSquare,
                 });
             }
+            ';' => {
+                tokens.push(Token {
+                    source_range: SourceRange {
+                        start: i,
+                        end: i + 1,
+                    },
+                    variant: Variant::Semicolon,
+                });
+            }
 
             // If the first code point is alphabetic according to the Unicode derived property,
             // keep reading subsequent alphanumeric code points and underscores to build up an
@@ -138,31 +223,61 @@ pub fn tokenize(schema_path: &Path, schema_contents: &str) -> Result<Vec<Token>,
                         source_range: SourceRange { start: i, end },
                         variant: Variant::Choice,
                     });
+                } else if &schema_contents[i..end] == CONST_KEYWORD {
+                    tokens.push(Token {
+                        source_range: SourceRange { start: i, end },
+                        variant: Variant::Const,
+                    });
                 } else if &schema_contents[i..end] == DELETED_KEYWORD {
                     tokens.push(Token {
                         source_range: SourceRange { start: i, end },
                         variant: Variant::Deleted,
                     });
+                } else if &schema_contents[i..end] == F32_KEYWORD {
+                    tokens.push(Token {
+                        source_range: SourceRange { start: i, end },
+                        variant: Variant::F32,
+                    });
                 } else if &schema_contents[i..end] == F64_KEYWORD {
                     tokens.push(Token {
                         source_range: SourceRange { start: i, end },
                         variant: Variant::F64,
                     });
+                } else if &schema_contents[i..end] == FALSE_KEYWORD {
+                    tokens.push(Token {
+                        source_range: SourceRange { start: i, end },
+                        variant: Variant::False,
+                    });
                 } else if &schema_contents[i..end] == IMPORT_KEYWORD {
                     tokens.push(Token {
                         source_range: SourceRange { start: i, end },
                         variant: Variant::Import,
                     });
+                } else if &schema_contents[i..end] == NEWTYPE_KEYWORD {
+                    tokens.push(Token {
+                        source_range: SourceRange { start: i, end },
+                        variant: Variant::Newtype,
+                    });
                 } else if &schema_contents[i..end] == OPTIONAL_KEYWORD {
                     tokens.push(Token {
                         source_range: SourceRange { start: i, end },
                         variant: Variant::Optional,
                     });
+                } else if &schema_contents[i..end] == S32_KEYWORD {
+                    tokens.push(Token {
+                        source_range: SourceRange { start: i, end },
+                        variant: Variant::S32,
+                    });
                 } else if &schema_contents[i..end] == S64_KEYWORD {
                     tokens.push(Token {
                         source_range: SourceRange { start: i, end },
                         variant: Variant::S64,
                     });
+                } else if &schema_contents[i..end] == SERVICE_KEYWORD {
+                    tokens.push(Token {
+                        source_range: SourceRange { start: i, end },
+                        variant: Variant::Service,
+                    });
                 } else if &schema_contents[i..end] == STRING_KEYWORD {
                     tokens.push(Token {
                         source_range: SourceRange { start: i, end },
@@ -173,11 +288,26 @@ pub fn tokenize(schema_path: &Path, schema_contents: &str) -> Result<Vec<Token>,
                         source_range: SourceRange { start: i, end },
                         variant: Variant::Struct,
                     });
+                } else if &schema_contents[i..end] == TRUE_KEYWORD {
+                    tokens.push(Token {
+                        source_range: SourceRange { start: i, end },
+                        variant: Variant::True,
+                    });
+                } else if &schema_contents[i..end] == U32_KEYWORD {
+                    tokens.push(Token {
+                        source_range: SourceRange { start: i, end },
+                        variant: Variant::U32,
+                    });
                 } else if &schema_contents[i..end] == U64_KEYWORD {
                     tokens.push(Token {
                         source_range: SourceRange { start: i, end },
                         variant: Variant::U64,
                     });
+                } else if &schema_contents[i..end] == U8_KEYWORD {
+                    tokens.push(Token {
+                        source_range: SourceRange { start: i, end },
+                        variant: Variant::U8,
+                    });
                 } else if &schema_contents[i..end] == UNIT_KEYWORD {
                     tokens.push(Token {
                         source_range: SourceRange { start: i, end },
@@ -211,13 +341,15 @@ pub fn tokenize(schema_path: &Path, schema_contents: &str) -> Result<Vec<Token>,
                 }
             }
 
-            // If the first code point is a digit, keep reading subsequent digits to build up an
-            // integer literal.
-            '0'..='9' => {
+            // If the first code point is a zero followed by an `x`, keep reading subsequent
+            // hexadecimal digits to build up a bytes literal.
+            '0' if matches!(iter.peek(), Some((_, 'x'))) => {
+                iter.next();
+
                 let mut end = schema_contents.len();
 
                 while let Some((j, d)) = iter.peek() {
-                    if d.is_ascii_digit() {
+                    if d.is_ascii_hexdigit() {
                         iter.next();
                     } else {
                         end = *j;
@@ -225,28 +357,181 @@ pub fn tokenize(schema_path: &Path, schema_contents: &str) -> Result<Vec<Token>,
                     }
                 }
 
-                // Try to parse the integer.
-                match schema_contents[i..end].parse::<usize>() {
-                    Ok(integer) => {
-                        tokens.push(Token {
-                            source_range: SourceRange { start: i, end },
-                            variant: Variant::Integer(integer),
-                        });
+                let digits = &schema_contents[i + 2..end];
+
+                if digits.len().is_multiple_of(2) {
+                    tokens.push(Token {
+                        source_range: SourceRange { start: i, end },
+                        variant: Variant::BytesLiteral(
+                            (0..digits.len())
+                                .step_by(2)
+                                .map(|k| {
+                                    // The `unwrap` is safe since the digits are ASCII
+                                    // hexadecimal digits.
+                                    u8::from_str_radix(&digits[k..k + 2], 16).unwrap()
+                                })
+                                .collect(),
+                        ),
+                    });
+                } else {
+                    errors.push(throw::<Error>(
+                        &format!(
+                            "Bytes literal {} must have an even number of hexadecimal digits.",
+                            &schema_contents[i..end].code_str(),
+                        ),
+                        Some(schema_path),
+                        Some(&listing(schema_contents, SourceRange { start: i, end })),
+                        None,
+                    ));
+                }
+            }
+
+            // If the first code point is a digit, keep reading subsequent digits to build up an
+            // integer literal. If the digits are followed by a fractional part or an exponent, the
+            // result is a float literal instead.
+            '0'..='9' => {
+                let mut end = consume_digits(&mut iter, schema_contents.len());
+                let mut is_float = false;
+
+                // Read the fractional part, if there is one.
+                let mut lookahead = iter.clone();
+                if matches!(lookahead.next(), Some((_, '.')))
+                    && matches!(lookahead.next(), Some((_, d)) if d.is_ascii_digit())
+                {
+                    iter.next();
+                    end = consume_digits(&mut iter, schema_contents.len());
+                    is_float = true;
+                }
+
+                // Read the exponent, if there is one.
+                let mut lookahead = iter.clone();
+                if matches!(lookahead.next(), Some((_, 'e' | 'E'))) {
+                    if matches!(lookahead.peek(), Some((_, '+' | '-'))) {
+                        lookahead.next();
+                    }
+
+                    if matches!(lookahead.next(), Some((_, d)) if d.is_ascii_digit()) {
+                        iter = lookahead;
+                        end = consume_digits(&mut iter, schema_contents.len());
+                        is_float = true;
+                    }
+                }
+
+                if is_float {
+                    // Try to parse the float. Rust's float parser accepts every string that we
+                    // tokenize as a float literal, but it saturates to infinity when the value is
+                    // too large.
+                    match schema_contents[i..end].parse::<f64>() {
+                        Ok(float) if float.is_finite() => {
+                            tokens.push(Token {
+                                source_range: SourceRange { start: i, end },
+                                variant: Variant::Float(float),
+                            });
+                        }
+                        _ => {
+                            errors.push(throw::<Error>(
+                                &format!(
+                                    "Float {} is too large.",
+                                    &schema_contents[i..end].code_str(),
+                                ),
+                                Some(schema_path),
+                                Some(&listing(schema_contents, SourceRange { start: i, end })),
+                                None,
+                            ));
+                        }
                     }
-                    Err(_) => {
-                        errors.push(throw::<Error>(
-                            &format!(
-                                "Integer {} must be less than 2^64.",
-                                &schema_contents[i..end].code_str(),
-                            ),
-                            Some(schema_path),
-                            Some(&listing(schema_contents, SourceRange { start: i, end })),
-                            None,
-                        ));
+                } else {
+                    // Try to parse the integer.
+                    match schema_contents[i..end].parse::<usize>() {
+                        Ok(integer) => {
+                            tokens.push(Token {
+                                source_range: SourceRange { start: i, end },
+                                variant: Variant::Integer(integer),
+                            });
+                        }
+                        Err(_) => {
+                            errors.push(throw::<Error>(
+                                &format!(
+                                    "Integer {} must be less than 2^64.",
+                                    &schema_contents[i..end].code_str(),
+                                ),
+                                Some(schema_path),
+                                Some(&listing(schema_contents, SourceRange { start: i, end })),
+                                None,
+                            ));
+                        }
                     }
                 }
             }
 
+            // If the first code point is a double quote, keep reading subsequent code points until
+            // an unescaped double quote is reached to build up a string literal.
+            '"' => {
+                let mut end = i;
+                let mut string = String::new();
+
+                while let Some((j, d)) = iter.next() {
+                    match d {
+                        '"' => {
+                            end = j;
+                            break;
+                        }
+                        '\\' => match iter.next() {
+                            Some((_, '"')) => string.push('"'),
+                            Some((_, '\\')) => string.push('\\'),
+                            Some((_, 'n')) => string.push('\n'),
+                            Some((_, 'r')) => string.push('\r'),
+                            Some((_, 't')) => string.push('\t'),
+                            Some((k, e)) => {
+                                errors.push(throw::<Error>(
+                                    &format!(
+                                        "Unknown escape sequence {}.",
+                                        &schema_contents[j..k + e.len_utf8()].code_str(),
+                                    ),
+                                    Some(schema_path),
+                                    Some(&listing(
+                                        schema_contents,
+                                        SourceRange {
+                                            start: j,
+                                            end: k + e.len_utf8(),
+                                        },
+                                    )),
+                                    None,
+                                ));
+                            }
+                            None => break,
+                        },
+                        _ => string.push(d),
+                    }
+                }
+
+                if end == i {
+                    errors.push(throw::<Error>(
+                        &format!(
+                            "String starting here must be terminated by a {}.",
+                            "\"".code_str(),
+                        ),
+                        Some(schema_path),
+                        Some(&listing(
+                            schema_contents,
+                            SourceRange {
+                                start: i,
+                                end: i + 1,
+                            },
+                        )),
+                        None,
+                    ));
+                } else {
+                    tokens.push(Token {
+                        source_range: SourceRange {
+                            start: i,
+                            end: end + 1,
+                        },
+                        variant: Variant::StringLiteral(string),
+                    });
+                }
+            }
+
             // If the first code point is a single quote, keep reading subsequent code points until
             // a second single quote is reached to build up a path.
             '\'' => {
@@ -348,7 +633,7 @@ pub fn tokenize(schema_path: &Path, schema_contents: &str) -> Result<Vec<Token>,
             }
 
             // Skip whitespace.
-            _ if c.is_whitespace() => continue,
+            _ if c.is_whitespace() => {}
 
             // If we made it this far, the input contains something unexpected.
             _ => {
@@ -388,6 +673,20 @@ pub fn tokenize(schema_path: &Path, schema_contents: &str) -> Result<Vec<Token>,
     Ok(tokens)
 }
 
+// Consume a run of ASCII digits and return the index just past them. If the digits extend to the
+// end of the input, `default_end` is returned.
+fn consume_digits(iter: &mut Peekable<CharIndices>, default_end: usize) -> usize {
+    while let Some((j, d)) = iter.peek() {
+        if d.is_ascii_digit() {
+            iter.next();
+        } else {
+            return *j;
+        }
+    }
+
+    default_end
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -396,8 +695,10 @@ mod tests {
             error::SourceRange,
             token::{
                 Token, Variant, ASYMMETRIC_KEYWORD, AS_KEYWORD, BOOL_KEYWORD, BYTES_KEYWORD,
-                CHOICE_KEYWORD, DELETED_KEYWORD, F64_KEYWORD, IMPORT_KEYWORD, OPTIONAL_KEYWORD,
-                S64_KEYWORD, STRING_KEYWORD, STRUCT_KEYWORD, U64_KEYWORD, UNIT_KEYWORD,
+                CHOICE_KEYWORD, CONST_KEYWORD, DELETED_KEYWORD, F32_KEYWORD, F64_KEYWORD,
+                FALSE_KEYWORD, IMPORT_KEYWORD, NEWTYPE_KEYWORD, OPTIONAL_KEYWORD, S32_KEYWORD,
+                S64_KEYWORD, SERVICE_KEYWORD, STRING_KEYWORD, STRUCT_KEYWORD, TRUE_KEYWORD,
+                U32_KEYWORD, U64_KEYWORD, U8_KEYWORD, UNIT_KEYWORD,
             },
             tokenizer::{tokenize, RAW_IDENTIFIER_SIGIL},
         },
@@ -516,6 +817,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tokenize_at() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), "@").unwrap(),
+            vec![Token {
+                source_range: SourceRange { start: 0, end: 1 },
+                variant: Variant::At,
+            }],
+        );
+    }
+
     #[test]
     fn tokenize_bool() {
         assert_same!(
@@ -544,6 +856,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tokenize_bytes_literal_valid() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), "0x007fFF").unwrap(),
+            vec![Token {
+                source_range: SourceRange { start: 0, end: 8 },
+                variant: Variant::BytesLiteral(vec![0x00, 0x7f, 0xff]),
+            }],
+        );
+    }
+
+    #[test]
+    fn tokenize_bytes_literal_empty() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), "0x").unwrap(),
+            vec![Token {
+                source_range: SourceRange { start: 0, end: 2 },
+                variant: Variant::BytesLiteral(vec![]),
+            }],
+        );
+    }
+
+    #[test]
+    fn tokenize_bytes_literal_odd_length() {
+        assert_fails!(
+            tokenize(Path::new("foo.t"), "0x123"),
+            "Bytes literal `0x123` must have an even number of hexadecimal digits.",
+        );
+    }
+
     #[test]
     fn tokenize_choice() {
         assert_same!(
@@ -569,6 +911,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tokenize_comma() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), ",").unwrap(),
+            vec![Token {
+                source_range: SourceRange { start: 0, end: 1 },
+                variant: Variant::Comma,
+            }],
+        );
+    }
+
     #[test]
     fn tokenize_comment_simple() {
         assert_same!(
@@ -598,6 +951,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tokenize_const() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), CONST_KEYWORD).unwrap(),
+            vec![Token {
+                source_range: SourceRange {
+                    start: 0,
+                    end: CONST_KEYWORD.len(),
+                },
+                variant: Variant::Const,
+            }],
+        );
+    }
+
     #[test]
     fn tokenize_deleted() {
         assert_same!(
@@ -634,6 +1001,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tokenize_f32() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), F32_KEYWORD).unwrap(),
+            vec![Token {
+                source_range: SourceRange {
+                    start: 0,
+                    end: F32_KEYWORD.len(),
+                },
+                variant: Variant::F32,
+            }],
+        );
+    }
+
     #[test]
     fn tokenize_f64() {
         assert_same!(
@@ -648,6 +1029,77 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tokenize_false() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), FALSE_KEYWORD).unwrap(),
+            vec![Token {
+                source_range: SourceRange {
+                    start: 0,
+                    end: FALSE_KEYWORD.len(),
+                },
+                variant: Variant::False,
+            }],
+        );
+    }
+
+    #[test]
+    fn tokenize_float_literal_fraction() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), "3.25").unwrap(),
+            vec![Token {
+                source_range: SourceRange { start: 0, end: 4 },
+                variant: Variant::Float(3.25),
+            }],
+        );
+    }
+
+    #[test]
+    fn tokenize_float_literal_exponent() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), "1.5e-3 2E10").unwrap(),
+            vec![
+                Token {
+                    source_range: SourceRange { start: 0, end: 6 },
+                    variant: Variant::Float(1.5e-3),
+                },
+                Token {
+                    source_range: SourceRange { start: 7, end: 11 },
+                    variant: Variant::Float(2e10),
+                },
+            ],
+        );
+    }
+
+    #[test]
+    fn tokenize_float_literal_out_of_range() {
+        assert_fails!(
+            tokenize(Path::new("foo.t"), "1e400"),
+            "Float `1e400` is too large.",
+        );
+    }
+
+    #[test]
+    fn tokenize_integer_followed_by_dot() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), "1.foo").unwrap(),
+            vec![
+                Token {
+                    source_range: SourceRange { start: 0, end: 1 },
+                    variant: Variant::Integer(1),
+                },
+                Token {
+                    source_range: SourceRange { start: 1, end: 2 },
+                    variant: Variant::Dot,
+                },
+                Token {
+                    source_range: SourceRange { start: 2, end: 5 },
+                    variant: Variant::Identifier("foo".into()),
+                },
+            ],
+        );
+    }
+
     #[test]
     fn tokenize_bare_identifier() {
         assert_same!(
@@ -726,6 +1178,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tokenize_left_angle() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), "<").unwrap(),
+            vec![Token {
+                source_range: SourceRange { start: 0, end: 1 },
+                variant: Variant::LeftAngle,
+            }],
+        );
+    }
+
     #[test]
     fn tokenize_left_curly() {
         assert_same!(
@@ -737,6 +1200,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tokenize_left_paren() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), "(").unwrap(),
+            vec![Token {
+                source_range: SourceRange { start: 0, end: 1 },
+                variant: Variant::LeftParen,
+            }],
+        );
+    }
+
     #[test]
     fn tokenize_left_square() {
         assert_same!(
@@ -748,6 +1222,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tokenize_arrow() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), "->").unwrap(),
+            vec![Token {
+                source_range: SourceRange { start: 0, end: 2 },
+                variant: Variant::Arrow,
+            }],
+        );
+    }
+
+    #[test]
+    fn tokenize_minus() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), "-").unwrap(),
+            vec![Token {
+                source_range: SourceRange { start: 0, end: 1 },
+                variant: Variant::Minus,
+            }],
+        );
+    }
+
+    #[test]
+    fn tokenize_newtype() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), NEWTYPE_KEYWORD).unwrap(),
+            vec![Token {
+                source_range: SourceRange {
+                    start: 0,
+                    end: NEWTYPE_KEYWORD.len(),
+                },
+                variant: Variant::Newtype,
+            }],
+        );
+    }
+
     #[test]
     fn tokenize_optional() {
         assert_same!(
@@ -792,6 +1302,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tokenize_right_angle() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), ">").unwrap(),
+            vec![Token {
+                source_range: SourceRange { start: 0, end: 1 },
+                variant: Variant::RightAngle,
+            }],
+        );
+    }
+
     #[test]
     fn tokenize_right_curly() {
         assert_same!(
@@ -803,6 +1324,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tokenize_right_paren() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), ")").unwrap(),
+            vec![Token {
+                source_range: SourceRange { start: 0, end: 1 },
+                variant: Variant::RightParen,
+            }],
+        );
+    }
+
     #[test]
     fn tokenize_right_square() {
         assert_same!(
@@ -814,6 +1346,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tokenize_s32() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), S32_KEYWORD).unwrap(),
+            vec![Token {
+                source_range: SourceRange {
+                    start: 0,
+                    end: S32_KEYWORD.len(),
+                },
+                variant: Variant::S32,
+            }],
+        );
+    }
+
     #[test]
     fn tokenize_s64() {
         assert_same!(
@@ -828,6 +1374,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tokenize_semicolon() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), ";").unwrap(),
+            vec![Token {
+                source_range: SourceRange { start: 0, end: 1 },
+                variant: Variant::Semicolon,
+            }],
+        );
+    }
+
+    #[test]
+    fn tokenize_service() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), SERVICE_KEYWORD).unwrap(),
+            vec![Token {
+                source_range: SourceRange {
+                    start: 0,
+                    end: SERVICE_KEYWORD.len(),
+                },
+                variant: Variant::Service,
+            }],
+        );
+    }
+
     #[test]
     fn tokenize_string() {
         assert_same!(
@@ -842,6 +1413,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tokenize_string_literal_valid() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), "\"say \\\"hi\\\"\\\\\\n\"").unwrap(),
+            vec![Token {
+                source_range: SourceRange { start: 0, end: 16 },
+                variant: Variant::StringLiteral("say \"hi\"\\\n".to_owned()),
+            }],
+        );
+    }
+
+    #[test]
+    fn tokenize_string_literal_empty() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), "\"\"").unwrap(),
+            vec![Token {
+                source_range: SourceRange { start: 0, end: 2 },
+                variant: Variant::StringLiteral(String::new()),
+            }],
+        );
+    }
+
+    #[test]
+    fn tokenize_string_literal_unknown_escape() {
+        assert_fails!(
+            tokenize(Path::new("foo.t"), "\"\\q\""),
+            "Unknown escape sequence `\\q`.",
+        );
+    }
+
+    #[test]
+    fn tokenize_string_literal_non_terminated() {
+        assert_fails!(
+            tokenize(Path::new("foo.t"), "\"bar"),
+            "String starting here must be terminated by a `\"`.",
+        );
+    }
+
     #[test]
     fn tokenize_struct() {
         assert_same!(
@@ -856,6 +1465,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tokenize_true() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), TRUE_KEYWORD).unwrap(),
+            vec![Token {
+                source_range: SourceRange {
+                    start: 0,
+                    end: TRUE_KEYWORD.len(),
+                },
+                variant: Variant::True,
+            }],
+        );
+    }
+
+    #[test]
+    fn tokenize_u32() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), U32_KEYWORD).unwrap(),
+            vec![Token {
+                source_range: SourceRange {
+                    start: 0,
+                    end: U32_KEYWORD.len(),
+                },
+                variant: Variant::U32,
+            }],
+        );
+    }
+
     #[test]
     fn tokenize_u64() {
         assert_same!(
@@ -870,6 +1507,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tokenize_u8() {
+        assert_same!(
+            tokenize(Path::new("foo.t"), U8_KEYWORD).unwrap(),
+            vec![Token {
+                source_range: SourceRange {
+                    start: 0,
+                    end: U8_KEYWORD.len(),
+                },
+                variant: Variant::U8,
+            }],
+        );
+    }
+
     #[test]
     fn tokenize_unit() {
         assert_same!(
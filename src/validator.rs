@@ -1,11 +1,14 @@
 use {
     crate::{
-        error::{listing, throw, Error},
+        count::count,
+        error::{listing, throw, Error, SourceRange},
         format::CodeStr,
         identifier::Identifier,
+        monomorphizer::monomorphize,
         schema,
     },
     std::{
+        cmp::Ordering,
         collections::{BTreeMap, HashMap, HashSet},
         path::{Path, PathBuf},
     },
@@ -23,6 +26,21 @@ pub fn validate(
     // We'll add any errors to this.
     let mut errors: Vec<Error> = vec![];
 
+    // Hoist the nested declarations to the top level [ref:nested_declarations].
+    let schemas = &schemas
+        .iter()
+        .map(|(namespace, (schema, source_path, source_contents))| {
+            (
+                namespace.clone(),
+                (
+                    schema::flatten(schema),
+                    source_path.clone(),
+                    source_contents.clone(),
+                ),
+            )
+        })
+        .collect::<BTreeMap<_, _>>();
+
     // For the purpose of validating types, construct a map from (namespace, name) to
     // (schema, declaration).
     let mut all_types = HashMap::new();
@@ -37,6 +55,27 @@ pub fn validate(
 
     // Validate each file.
     for (namespace, (schema, source_path, source_contents)) in schemas {
+        // Validate the constants in the file.
+        let mut constant_names = HashSet::new();
+
+        for constant in &schema.constants {
+            // Check that the name of the constant is unique within the file.
+            if !constant_names.insert(constant.name.clone()) {
+                errors.push(throw::<Error>(
+                    &format!(
+                        "A constant named {} already exists in this file.",
+                        constant.name.code_str(),
+                    ),
+                    Some(source_path),
+                    Some(&listing(source_contents, constant.source_range)),
+                    None,
+                ));
+            }
+
+            // Check that the value of the constant has the right type.
+            validate_constant(&mut errors, source_path, source_contents, constant);
+        }
+
         // Validate the declarations in the file.
         let mut declaration_names = HashSet::new();
 
@@ -54,6 +93,72 @@ pub fn validate(
                 ));
             }
 
+            // Check that the names of the type parameters are unique within the declaration.
+            let mut parameter_names = HashSet::new();
+            for parameter in &declaration.parameters {
+                if !parameter_names.insert(parameter.clone()) {
+                    errors.push(throw::<Error>(
+                        &format!(
+                            "A type parameter named {} already exists in this declaration.",
+                            parameter.code_str(),
+                        ),
+                        Some(source_path),
+                        Some(&listing(source_contents, declaration.source_range)),
+                        None,
+                    ));
+                }
+            }
+
+            // Validate the attributes of the declaration.
+            validate_attributes(
+                &mut errors,
+                source_path,
+                source_contents,
+                &declaration.attributes,
+                AttributeSite::Declaration,
+            );
+
+            // Validate the underlying type of a newtype. A newtype is encoded in the same way as
+            // its underlying type [ref:newtype_encoding], so the underlying type must be concrete
+            // and carry some information.
+            if let schema::DeclarationVariant::Newtype(underlying_type) = &declaration.variant {
+                match &underlying_type.variant {
+                    schema::TypeVariant::Custom(None, name, _)
+                        if declaration.parameters.contains(name) =>
+                    {
+                        errors.push(throw::<Error>(
+                            "The underlying type of a newtype can't be a type parameter.",
+                            Some(source_path),
+                            Some(&listing(source_contents, underlying_type.source_range)),
+                            None,
+                        ));
+                    }
+                    schema::TypeVariant::Unit => {
+                        errors.push(throw::<Error>(
+                            &format!(
+                                "The underlying type of a newtype can't be {}.",
+                                "Unit".code_str(),
+                            ),
+                            Some(source_path),
+                            Some(&listing(source_contents, underlying_type.source_range)),
+                            None,
+                        ));
+                    }
+                    _ => {}
+                }
+
+                validate_type(
+                    &all_types,
+                    &mut errors,
+                    namespace,
+                    schema,
+                    source_path,
+                    source_contents,
+                    &declaration.parameters,
+                    underlying_type,
+                );
+            }
+
             // Validate the fields in the declaration.
             let mut field_names = HashSet::new();
             let mut field_indices = HashSet::new();
@@ -72,6 +177,15 @@ pub fn validate(
                     ));
                 }
 
+                // Validate the attributes of the field.
+                validate_attributes(
+                    &mut errors,
+                    source_path,
+                    source_contents,
+                    &field.attributes,
+                    AttributeSite::Field,
+                );
+
                 // Check that the index of the field is unique within the declaration.
                 if !field_indices.insert(field.index) {
                     errors.push(throw::<Error>(
@@ -120,10 +234,39 @@ pub fn validate(
                     schema,
                     source_path,
                     source_contents,
+                    &declaration.parameters,
                     &field.r#type,
                 );
             }
 
+            // Check that renaming fields for a particular code generator doesn't cause the field
+            // names to collide. Collisions which don't involve renamed fields are reported above.
+            for target in ATTRIBUTE_TARGETS {
+                let mut renamed_fields = HashMap::new();
+
+                for field in &declaration.fields {
+                    let rename = schema::rename(&field.attributes, target);
+
+                    if let Some(other_renamed) = renamed_fields
+                        .insert(rename.unwrap_or(&field.name).clone(), rename.is_some())
+                    {
+                        if other_renamed || rename.is_some() {
+                            errors.push(throw::<Error>(
+                                &format!(
+                                    "A field named {} already exists in this declaration in the \
+                                        code generated for {}.",
+                                    rename.unwrap_or(&field.name).code_str(),
+                                    target.code_str(),
+                                ),
+                                Some(source_path),
+                                Some(&listing(source_contents, field.source_range)),
+                                None,
+                            ));
+                        }
+                    }
+                }
+            }
+
             // Check that all index gaps are marked as deleted.
             for index in 0..(field_indices.len() + declaration.deleted.len()) {
                 if !field_indices.contains(&index) && !declaration.deleted.contains(&index) {
@@ -139,6 +282,38 @@ pub fn validate(
                 }
             }
         }
+
+        // Validate the services in the file. Services share a namespace with declarations, since
+        // the generated code for a service may be named after it.
+        let mut service_names = HashSet::new();
+
+        for service in &schema.services {
+            // Check that the name of the service is unique within the file.
+            if declaration_names.contains(&service.name)
+                || !service_names.insert(service.name.clone())
+            {
+                errors.push(throw::<Error>(
+                    &format!(
+                        "A declaration or service named {} already exists in this file.",
+                        service.name.code_str(),
+                    ),
+                    Some(source_path),
+                    Some(&listing(source_contents, service.source_range)),
+                    None,
+                ));
+            }
+
+            // Validate the methods in the service.
+            validate_service(
+                &all_types,
+                &mut errors,
+                namespace,
+                schema,
+                source_path,
+                source_contents,
+                service,
+            );
+        }
     }
 
     // Check for cycles if the schemas are otherwise valid
@@ -163,6 +338,26 @@ pub fn validate(
         }
     }
 
+    // Check that the generic declarations can be instantiated if the schemas are otherwise valid
+    // [ref:monomorphization].
+    if errors.is_empty() {
+        match monomorphize(schemas) {
+            Ok(monomorphized_schemas) => {
+                // Check that the constraints on the fields suit their types.
+                for (schema, source_path, source_contents) in monomorphized_schemas.values() {
+                    for declaration in &schema.declarations {
+                        for field in &declaration.fields {
+                            validate_constraints(&mut errors, source_path, source_contents, field);
+                        }
+                    }
+                }
+            }
+            Err(monomorphization_errors) => {
+                errors.extend(monomorphization_errors);
+            }
+        }
+    }
+
     // Return a success or report any errors.
     if errors.is_empty() {
         Ok(())
@@ -171,18 +366,520 @@ pub fn validate(
     }
 }
 
-// This function validates an individual type.
-fn validate_type(
+// This function checks that the value of a constant is a valid inhabitant of its type. Constants
+// are limited to the scalar built-in types, since those are the types which have literals.
+#[allow(clippy::too_many_lines)]
+fn validate_constant(
+    errors: &mut Vec<Error>,
+    source_path: &Path,
+    source_contents: &str,
+    constant: &schema::Constant,
+) {
+    let integer_range = match &constant.r#type.variant {
+        schema::TypeVariant::S32 => Some((i128::from(i32::MIN), i128::from(i32::MAX))),
+        schema::TypeVariant::S64 => Some((i128::from(i64::MIN), i128::from(i64::MAX))),
+        schema::TypeVariant::U32 => Some((0, i128::from(u32::MAX))),
+        schema::TypeVariant::U64 => Some((0, i128::from(u64::MAX))),
+        schema::TypeVariant::U8 => Some((0, i128::from(u8::MAX))),
+        _ => None,
+    };
+
+    match (&constant.r#type.variant, &constant.value) {
+        (schema::TypeVariant::Bytes(Some(length)), schema::Literal::Bytes(bytes))
+            if bytes.len() != *length =>
+        {
+            errors.push(throw::<Error>(
+                &format!(
+                    "The value {} has {}, but {} requires exactly {}.",
+                    constant.value.to_string().code_str(),
+                    count(bytes.len(), "byte"),
+                    constant.r#type.to_string().code_str(),
+                    count(*length, "byte"),
+                ),
+                Some(source_path),
+                Some(&listing(source_contents, constant.source_range)),
+                None,
+            ));
+        }
+        (schema::TypeVariant::Bool, schema::Literal::Bool(_))
+        | (schema::TypeVariant::Bytes(_), schema::Literal::Bytes(_))
+        | (schema::TypeVariant::F64, schema::Literal::Float(_))
+        | (schema::TypeVariant::String, schema::Literal::String(_)) => {}
+        (schema::TypeVariant::F32, schema::Literal::Float(float)) => {
+            if float.abs() > f64::from(f32::MAX) {
+                errors.push(throw::<Error>(
+                    &format!(
+                        "The value {} is out of range for {}.",
+                        constant.value.to_string().code_str(),
+                        constant.r#type.to_string().code_str(),
+                    ),
+                    Some(source_path),
+                    Some(&listing(source_contents, constant.source_range)),
+                    None,
+                ));
+            }
+        }
+        (_, schema::Literal::Integer(integer)) if integer_range.is_some() => {
+            // The `unwrap` is safe due to the match guard.
+            let (min, max) = integer_range.unwrap();
+
+            if *integer < min || *integer > max {
+                errors.push(throw::<Error>(
+                    &format!(
+                        "The value {} is out of range for {}.",
+                        constant.value.to_string().code_str(),
+                        constant.r#type.to_string().code_str(),
+                    ),
+                    Some(source_path),
+                    Some(&listing(source_contents, constant.source_range)),
+                    None,
+                ));
+            }
+        }
+        (
+            schema::TypeVariant::Bool
+            | schema::TypeVariant::Bytes(_)
+            | schema::TypeVariant::F32
+            | schema::TypeVariant::F64
+            | schema::TypeVariant::S32
+            | schema::TypeVariant::S64
+            | schema::TypeVariant::String
+            | schema::TypeVariant::U32
+            | schema::TypeVariant::U64
+            | schema::TypeVariant::U8,
+            _,
+        ) => {
+            errors.push(throw::<Error>(
+                &format!(
+                    "The value {} doesn't have type {}.",
+                    constant.value.to_string().code_str(),
+                    constant.r#type.to_string().code_str(),
+                ),
+                Some(source_path),
+                Some(&listing(source_contents, constant.source_range)),
+                None,
+            ));
+        }
+        (
+            schema::TypeVariant::Array(_, _)
+            | schema::TypeVariant::Custom(_, _, _)
+            | schema::TypeVariant::Map(_, _)
+            | schema::TypeVariant::Newtype(_, _, _)
+            | schema::TypeVariant::Unit,
+            _,
+        ) => {
+            errors.push(throw::<Error>(
+                &format!(
+                    "Constant {} has type {}, but constants must have a scalar built-in type.",
+                    constant.name.code_str(),
+                    constant.r#type.to_string().code_str(),
+                ),
+                Some(source_path),
+                Some(&listing(source_contents, constant.r#type.source_range)),
+                None,
+            ));
+        }
+    }
+}
+
+// The kinds of items which can have attributes
+#[derive(Clone, Copy)]
+enum AttributeSite {
+    Declaration,
+    Field,
+}
+
+// The code generators which can be targeted by attributes such as `@rust.rename`
+const ATTRIBUTE_TARGETS: &[&str] = &["rust", "typescript"];
+
+// This function checks that the attributes of a declaration or field are known, apply to that kind
+// of item, and have the expected arguments [tag:attribute_arguments]:
+//
+// - `@deprecated` takes an optional message and applies to declarations and fields.
+// - `@rust.derive` takes one or more trait names and applies to declarations.
+// - `@rust.rename` and `@typescript.rename` take a name and apply to fields.
+// - `@min` and `@max` take a number and apply to fields [ref:constraints].
+// - `@min_length` and `@max_length` take a non-negative integer and apply to fields.
+//
+// Whether the constraints suit the types of the fields is checked by `validate_constraints`.
+#[allow(clippy::too_many_lines)]
+fn validate_attributes(
+    errors: &mut Vec<Error>,
+    source_path: &Path,
+    source_contents: &str,
+    attributes: &[schema::Attribute],
+    site: AttributeSite,
+) {
+    let mut attribute_names = HashSet::new();
+
+    for attribute in attributes {
+        let qualified_name = attribute.qualified_name();
+
+        // Check that the attribute isn't repeated.
+        if !attribute_names.insert(qualified_name.clone()) {
+            errors.push(throw::<Error>(
+                &format!(
+                    "The {} attribute is already applied here.",
+                    qualified_name.code_str(),
+                ),
+                Some(source_path),
+                Some(&listing(source_contents, attribute.source_range)),
+                None,
+            ));
+        }
+
+        // Determine which kind of item the attribute applies to and whether its arguments are
+        // well-formed.
+        let (expected_site, arguments_valid, expected_arguments) =
+            if attribute.is(None, "deprecated") {
+                (
+                    None,
+                    matches!(
+                        attribute.arguments.as_slice(),
+                        [] | [schema::AttributeArgument::Literal(schema::Literal::String(
+                            _
+                        ))],
+                    ),
+                    "at most one argument, which must be a string",
+                )
+            } else if attribute.is(Some("rust"), "derive") {
+                (
+                    Some(AttributeSite::Declaration),
+                    !attribute.arguments.is_empty()
+                        && attribute.arguments.iter().all(|argument| {
+                            matches!(argument, schema::AttributeArgument::Identifier(_))
+                        }),
+                    "one or more trait names",
+                )
+            } else if ATTRIBUTE_TARGETS
+                .iter()
+                .any(|target| attribute.is(Some(target), "rename"))
+            {
+                (
+                    Some(AttributeSite::Field),
+                    matches!(
+                        attribute.arguments.as_slice(),
+                        [schema::AttributeArgument::Identifier(_)],
+                    ),
+                    "exactly one argument, which must be a name",
+                )
+            } else if attribute.is(None, "min") || attribute.is(None, "max") {
+                (
+                    Some(AttributeSite::Field),
+                    matches!(
+                        attribute.arguments.as_slice(),
+                        [schema::AttributeArgument::Literal(
+                            schema::Literal::Integer(_) | schema::Literal::Float(_),
+                        )],
+                    ),
+                    "exactly one argument, which must be a number",
+                )
+            } else if attribute.is(None, "min_length") || attribute.is(None, "max_length") {
+                (
+                    Some(AttributeSite::Field),
+                    matches!(
+                        attribute.arguments.as_slice(),
+                        [schema::AttributeArgument::Literal(schema::Literal::Integer(length))]
+                            if usize::try_from(*length).is_ok(),
+                    ),
+                    "exactly one argument, which must be a non-negative integer",
+                )
+            } else {
+                errors.push(throw::<Error>(
+                    &format!("Unknown attribute {}.", qualified_name.code_str()),
+                    Some(source_path),
+                    Some(&listing(source_contents, attribute.source_range)),
+                    None,
+                ));
+
+                continue;
+            };
+
+        // Check that the attribute applies to this kind of item.
+        match (expected_site, site) {
+            (None, _)
+            | (Some(AttributeSite::Declaration), AttributeSite::Declaration)
+            | (Some(AttributeSite::Field), AttributeSite::Field) => {}
+            (Some(AttributeSite::Declaration), AttributeSite::Field) => {
+                errors.push(throw::<Error>(
+                    &format!(
+                        "The {} attribute can only be applied to declarations.",
+                        qualified_name.code_str(),
+                    ),
+                    Some(source_path),
+                    Some(&listing(source_contents, attribute.source_range)),
+                    None,
+                ));
+            }
+            (Some(AttributeSite::Field), AttributeSite::Declaration) => {
+                errors.push(throw::<Error>(
+                    &format!(
+                        "The {} attribute can only be applied to fields.",
+                        qualified_name.code_str(),
+                    ),
+                    Some(source_path),
+                    Some(&listing(source_contents, attribute.source_range)),
+                    None,
+                ));
+            }
+        }
+
+        // Check the arguments.
+        if !arguments_valid {
+            errors.push(throw::<Error>(
+                &format!(
+                    "The {} attribute takes {}.",
+                    qualified_name.code_str(),
+                    expected_arguments,
+                ),
+                Some(source_path),
+                Some(&listing(source_contents, attribute.source_range)),
+                None,
+            ));
+        }
+    }
+
+    // Check that the lower bounds don't exceed the upper bounds.
+    let constraints = schema::constraints(attributes);
+
+    if let (Some(min), Some(max)) = (&constraints.min, &constraints.max) {
+        if schema::compare_numbers(min, max) == Some(Ordering::Greater) {
+            errors.push(throw::<Error>(
+                &format!(
+                    "The minimum {} is greater than the maximum {}.",
+                    min.to_string().code_str(),
+                    max.to_string().code_str(),
+                ),
+                Some(source_path),
+                Some(&listing(
+                    source_contents,
+                    attributes_source_range(attributes),
+                )),
+                None,
+            ));
+        }
+    }
+
+    if let (Some(min_length), Some(max_length)) = (constraints.min_length, constraints.max_length) {
+        if min_length > max_length {
+            errors.push(throw::<Error>(
+                &format!(
+                    "The minimum length {} is greater than the maximum length {}.",
+                    min_length.to_string().code_str(),
+                    max_length.to_string().code_str(),
+                ),
+                Some(source_path),
+                Some(&listing(
+                    source_contents,
+                    attributes_source_range(attributes),
+                )),
+                None,
+            ));
+        }
+    }
+}
+
+// This function returns the source range spanning a nonempty list of attributes.
+fn attributes_source_range(attributes: &[schema::Attribute]) -> SourceRange {
+    SourceRange {
+        start: attributes
+            .first()
+            .map_or(0, |attribute| attribute.source_range.start),
+        end: attributes
+            .last()
+            .map_or(0, |attribute| attribute.source_range.end),
+    }
+}
+
+// This function checks that the constraints on a field [ref:constraints] suit its type and that
+// their bounds are within the range of the type. It's applied after monomorphization, since the
+// type of a field in a generic declaration may be a type parameter.
+fn validate_constraints(
+    errors: &mut Vec<Error>,
+    source_path: &Path,
+    source_contents: &str,
+    field: &schema::Field,
+) {
+    let constraints = schema::constraints(&field.attributes);
+
+    if constraints.is_empty() {
+        return;
+    }
+
+    let encoding_type = schema::encoding_type(&field.r#type.variant);
+
+    // Check the bounds given by `@min` and `@max`.
+    for (name, bound) in [("min", &constraints.min), ("max", &constraints.max)] {
+        let Some(bound) = bound else {
+            continue;
+        };
+
+        let integer_range = match encoding_type {
+            schema::TypeVariant::S32 => Some((i128::from(i32::MIN), i128::from(i32::MAX), "S32")),
+            schema::TypeVariant::S64 => Some((i128::from(i64::MIN), i128::from(i64::MAX), "S64")),
+            schema::TypeVariant::U32 => Some((0, i128::from(u32::MAX), "U32")),
+            schema::TypeVariant::U64 => Some((0, i128::from(u64::MAX), "U64")),
+            schema::TypeVariant::U8 => Some((0, i128::from(u8::MAX), "U8")),
+            _ => None,
+        };
+
+        let message = match (encoding_type, bound) {
+            (schema::TypeVariant::F32, schema::Literal::Float(float))
+                if float.abs() > f64::from(f32::MAX) =>
+            {
+                Some(format!(
+                    "The value {} is out of range for {}.",
+                    bound.to_string().code_str(),
+                    "F32".code_str(),
+                ))
+            }
+            (schema::TypeVariant::F32 | schema::TypeVariant::F64, _) => None,
+            (_, schema::Literal::Integer(integer)) if integer_range.is_some() => {
+                // The `unwrap` is safe due to the match guard.
+                let (min, max, type_name) = integer_range.unwrap();
+
+                if *integer < min || *integer > max {
+                    Some(format!(
+                        "The value {} is out of range for {}.",
+                        bound.to_string().code_str(),
+                        type_name.code_str(),
+                    ))
+                } else {
+                    None
+                }
+            }
+            (_, _) if integer_range.is_some() => {
+                // The `unwrap` is safe due to the match guard.
+                let (_, _, type_name) = integer_range.unwrap();
+
+                Some(format!(
+                    "The value {} doesn't have type {}.",
+                    bound.to_string().code_str(),
+                    type_name.code_str(),
+                ))
+            }
+            (_, _) => Some(format!(
+                "The {} attribute can only be applied to fields with numeric types.",
+                format!("@{name}").code_str(),
+            )),
+        };
+
+        if let Some(message) = message {
+            errors.push(throw::<Error>(
+                &message,
+                Some(source_path),
+                Some(&listing(source_contents, field.source_range)),
+                None,
+            ));
+        }
+    }
+
+    // Check that `@min_length` and `@max_length` are applied to types which have lengths.
+    match encoding_type {
+        schema::TypeVariant::Array(_, _)
+        | schema::TypeVariant::Bytes(_)
+        | schema::TypeVariant::Map(_, _)
+        | schema::TypeVariant::String => {}
+        _ => {
+            for (name, length) in [
+                ("min_length", constraints.min_length),
+                ("max_length", constraints.max_length),
+            ] {
+                if length.is_some() {
+                    errors.push(throw::<Error>(
+                        &format!(
+                            "The {} attribute can only be applied to fields of type {}, {}, \
+                                arrays, or maps.",
+                            format!("@{name}").code_str(),
+                            "String".code_str(),
+                            "Bytes".code_str(),
+                        ),
+                        Some(source_path),
+                        Some(&listing(source_contents, field.source_range)),
+                        None,
+                    ));
+                }
+            }
+        }
+    }
+}
+
+// This function validates the methods of a service. Method indices follow the same rules as field
+// indices, since they serve the same purpose: they allow methods to be removed without disturbing
+// the others.
+#[allow(clippy::too_many_lines)]
+fn validate_service(
     all_types: &HashMap<(schema::Namespace, Identifier), (&schema::Schema, &schema::Declaration)>,
     errors: &mut Vec<Error>,
     namespace: &schema::Namespace,
     schema: &schema::Schema,
     source_path: &Path,
     source_contents: &str,
-    r#type: &schema::Type,
+    service: &schema::Service,
 ) {
-    match &r#type.variant {
-        schema::TypeVariant::Array(inner_type) => {
+    let mut method_names = HashSet::new();
+    let mut method_indices = HashSet::new();
+
+    for method in &service.methods {
+        // Check that the name of the method is unique within the service.
+        if !method_names.insert(method.name.clone()) {
+            errors.push(throw::<Error>(
+                &format!(
+                    "A method named {} already exists in this service.",
+                    method.name.code_str(),
+                ),
+                Some(source_path),
+                Some(&listing(source_contents, method.source_range)),
+                None,
+            ));
+        }
+
+        // Check that the index of the method is unique within the service.
+        if !method_indices.insert(method.index) {
+            errors.push(throw::<Error>(
+                &format!(
+                    "A method with index {} already exists in this service.",
+                    method.index.to_string().code_str(),
+                ),
+                Some(source_path),
+                Some(&listing(source_contents, method.source_range)),
+                None,
+            ));
+        }
+
+        // Check that the index of the method isn't marked as deleted.
+        if service.deleted.contains(&method.index) {
+            errors.push(throw::<Error>(
+                &format!(
+                    "Method index {} is marked as deleted in this service.",
+                    method.index.to_string().code_str(),
+                ),
+                Some(source_path),
+                Some(&listing(source_contents, method.source_range)),
+                None,
+            ));
+        }
+
+        // Check that the index isn't too big.
+        if method.index > MAX_FIELD_INDEX {
+            errors.push(throw::<Error>(
+                &format!(
+                    "Method index {} is too large. The maximum method index is {}.",
+                    method.index.to_string().code_str(),
+                    MAX_FIELD_INDEX.to_string().code_str(),
+                ),
+                Some(source_path),
+                Some(&listing(source_contents, method.source_range)),
+                None,
+            ));
+        }
+
+        // Validate the input and output types. Requests and responses are messages in their own
+        // right, so they must be structs or choices.
+        for (r#type, role) in [
+            (&method.input_type, "input"),
+            (&method.output_type, "output"),
+        ] {
             validate_type(
                 all_types,
                 errors,
@@ -190,51 +887,35 @@ fn validate_type(
                 schema,
                 source_path,
                 source_contents,
-                inner_type,
+                &[],
+                r#type,
             );
-        }
-        schema::TypeVariant::Bool
-        | schema::TypeVariant::Bytes
-        | schema::TypeVariant::F64
-        | schema::TypeVariant::S64
-        | schema::TypeVariant::String
-        | schema::TypeVariant::U64
-        | schema::TypeVariant::Unit => {}
-        schema::TypeVariant::Custom(import, name) => {
-            // Determine which file the type is from.
-            let type_namespace = if let Some(import) = import {
-                if let Some(import) = schema.imports.get(import) {
-                    // The `unwrap` is safe due to [ref:namespace_populated].
-                    import.namespace.clone().unwrap()
-                } else {
-                    errors.push(throw::<Error>(
-                        &format!(
-                            "There is no import named {} in this file.",
-                            import.code_str(),
-                        ),
-                        Some(source_path),
-                        Some(&listing(source_contents, r#type.source_range)),
-                        None,
-                    ));
 
-                    return;
-                }
+            let is_message = if let schema::TypeVariant::Custom(import, name, _) = &r#type.variant {
+                // Types which don't resolve have already been reported by `validate_type`.
+                import
+                    .as_ref()
+                    .map_or(Some(namespace.clone()), |import| {
+                        schema
+                            .imports
+                            .get(import)
+                            .and_then(|import| import.namespace.clone())
+                    })
+                    .and_then(|type_namespace| all_types.get(&(type_namespace, name.clone())))
+                    .is_none_or(|(_, declaration)| {
+                        !matches!(declaration.variant, schema::DeclarationVariant::Newtype(_))
+                    })
             } else {
-                namespace.clone()
+                false
             };
 
-            // Check that the type exists in that file.
-            if !all_types.contains_key(&(type_namespace, name.clone())) {
+            if !is_message {
                 errors.push(throw::<Error>(
-                    &if let Some(import) = import {
-                        format!(
-                            "There is no type named {} in import {}.",
-                            name.code_str(),
-                            import.code_str(),
-                        )
-                    } else {
-                        format!("There is no type named {} in this file.", name.code_str())
-                    },
+                    &format!(
+                        "The {} type of method {} must be a struct or a choice.",
+                        role,
+                        method.name.code_str(),
+                    ),
                     Some(source_path),
                     Some(&listing(source_contents, r#type.source_range)),
                     None,
@@ -242,21 +923,255 @@ fn validate_type(
             }
         }
     }
+
+    // Check that all index gaps are marked as deleted.
+    for index in 0..(method_indices.len() + service.deleted.len()) {
+        if !method_indices.contains(&index) && !service.deleted.contains(&index) {
+            errors.push(throw::<Error>(
+                &format!(
+                    "Method index gap found. Unused index {} is not marked as deleted.",
+                    index.to_string().code_str(),
+                ),
+                Some(source_path),
+                Some(&listing(source_contents, service.source_range)),
+                None,
+            ));
+        }
+    }
 }
 
-// This function checks that declarations have no cycles.
-fn check_declaration_for_cycles(
+// This function validates an individual type. The `parameters` are the type parameters of the
+// enclosing declaration.
+#[allow(clippy::too_many_arguments, clippy::too_many_lines)]
+fn validate_type(
     all_types: &HashMap<(schema::Namespace, Identifier), (&schema::Schema, &schema::Declaration)>,
-    types_checked: &mut HashSet<(schema::Namespace, Identifier)>,
-    types_visited_set: &mut HashSet<(schema::Namespace, Identifier)>,
-    types_visited_vec: &mut Vec<(schema::Namespace, Identifier)>,
     errors: &mut Vec<Error>,
     namespace: &schema::Namespace,
-    name: &Identifier,
+    schema: &schema::Schema,
+    source_path: &Path,
+    source_contents: &str,
+    parameters: &[Identifier],
+    r#type: &schema::Type,
 ) {
-    // Compute this once here so we don't have to compute it in multiple places below.
-    let qualified_type = (namespace.clone(), name.clone());
-
+    // Fixed lengths must be positive, since a value with no elements carries no information.
+    if let schema::TypeVariant::Array(_, Some(0)) | schema::TypeVariant::Bytes(Some(0)) =
+        r#type.variant
+    {
+        errors.push(throw::<Error>(
+            &format!(
+                "The length of {} must be positive.",
+                r#type.to_string().code_str(),
+            ),
+            Some(source_path),
+            Some(&listing(source_contents, r#type.source_range)),
+            None,
+        ));
+    }
+
+    match &r#type.variant {
+        schema::TypeVariant::Array(inner_type, _) => {
+            validate_type(
+                all_types,
+                errors,
+                namespace,
+                schema,
+                source_path,
+                source_contents,
+                parameters,
+                inner_type,
+            );
+        }
+        schema::TypeVariant::Map(key_type, value_type) => {
+            // Keys must have a total order and a notion of equality which is shared by all the
+            // supported languages [tag:map_key_types].
+            match key_type.variant {
+                schema::TypeVariant::Bool
+                | schema::TypeVariant::S32
+                | schema::TypeVariant::S64
+                | schema::TypeVariant::String
+                | schema::TypeVariant::U32
+                | schema::TypeVariant::U64
+                | schema::TypeVariant::U8 => {}
+                schema::TypeVariant::Array(_, _)
+                | schema::TypeVariant::Bytes(_)
+                | schema::TypeVariant::Custom(_, _, _)
+                | schema::TypeVariant::F32
+                | schema::TypeVariant::F64
+                | schema::TypeVariant::Map(_, _)
+                | schema::TypeVariant::Newtype(_, _, _)
+                | schema::TypeVariant::Unit => {
+                    errors.push(throw::<Error>(
+                        &format!(
+                            "{} can't be the key type of a map. The key type must be {}, {}, {}, \
+                                {}, {}, {}, or {}.",
+                            key_type.to_string().code_str(),
+                            "Bool".code_str(),
+                            "S32".code_str(),
+                            "S64".code_str(),
+                            "String".code_str(),
+                            "U32".code_str(),
+                            "U64".code_str(),
+                            "U8".code_str(),
+                        ),
+                        Some(source_path),
+                        Some(&listing(source_contents, key_type.source_range)),
+                        None,
+                    ));
+                }
+            }
+
+            validate_type(
+                all_types,
+                errors,
+                namespace,
+                schema,
+                source_path,
+                source_contents,
+                parameters,
+                value_type,
+            );
+        }
+        schema::TypeVariant::Bool
+        | schema::TypeVariant::Bytes(_)
+        | schema::TypeVariant::F32
+        | schema::TypeVariant::F64
+        | schema::TypeVariant::S32
+        | schema::TypeVariant::S64
+        | schema::TypeVariant::String
+        | schema::TypeVariant::U32
+        | schema::TypeVariant::U64
+        | schema::TypeVariant::U8
+        | schema::TypeVariant::Unit => {}
+        schema::TypeVariant::Newtype(_, _, _) => {
+            // Impossible since references to newtypes are only resolved by
+            // [ref:monomorphization].
+            panic!()
+        }
+        schema::TypeVariant::Custom(import, name, arguments) => {
+            // Check if the type is a type parameter.
+            if import.is_none() && parameters.contains(name) {
+                if !arguments.is_empty() {
+                    errors.push(throw::<Error>(
+                        &format!(
+                            "Type parameter {} can't have type arguments.",
+                            name.code_str()
+                        ),
+                        Some(source_path),
+                        Some(&listing(source_contents, r#type.source_range)),
+                        None,
+                    ));
+                }
+
+                return;
+            }
+
+            // Validate the type arguments.
+            for argument in arguments {
+                validate_type(
+                    all_types,
+                    errors,
+                    namespace,
+                    schema,
+                    source_path,
+                    source_contents,
+                    parameters,
+                    argument,
+                );
+            }
+
+            // Determine which file the type is from.
+            let type_namespace = if let Some(import) = import {
+                if let Some(import) = schema.imports.get(import) {
+                    // The `unwrap` is safe due to [ref:namespace_populated].
+                    import.namespace.clone().unwrap()
+                } else {
+                    errors.push(throw::<Error>(
+                        &format!(
+                            "There is no import named {} in this file.",
+                            import.code_str(),
+                        ),
+                        Some(source_path),
+                        Some(&listing(source_contents, r#type.source_range)),
+                        None,
+                    ));
+
+                    return;
+                }
+            } else {
+                namespace.clone()
+            };
+
+            // Check that the type exists in that file and has the right number of type arguments.
+            // Nested declarations must be referred to by their qualified names, even though those
+            // are equal to the concatenations of their components [ref:nested_declarations].
+            if let Some((_, declaration)) =
+                all_types
+                    .get(&(type_namespace, name.clone()))
+                    .filter(|(_, declaration)| {
+                        declaration.name.qualified_pascal_case() == name.qualified_pascal_case()
+                    })
+            {
+                if declaration.parameters.len() != arguments.len() {
+                    errors.push(throw::<Error>(
+                        &format!(
+                            "{} expects {}, but {} provided.",
+                            name.code_str(),
+                            count(declaration.parameters.len(), "type argument"),
+                            if arguments.len() == 1 {
+                                "1 was".to_owned()
+                            } else {
+                                format!("{} were", arguments.len())
+                            },
+                        ),
+                        Some(source_path),
+                        Some(&listing(source_contents, r#type.source_range)),
+                        None,
+                    ));
+                }
+            } else {
+                errors.push(throw::<Error>(
+                    &if let Some(import) = import {
+                        format!(
+                            "There is no type named {} in import {}.",
+                            name.code_str(),
+                            import.code_str(),
+                        )
+                    } else if let Some((qualifier, _)) =
+                        name.original().split_once('.').filter(|(qualifier, _)| {
+                            !all_types.contains_key(&(namespace.clone(), (*qualifier).into()))
+                        })
+                    {
+                        // The parser only treats qualifiers which aren't the names of imports as
+                        // the names of declarations [ref:parse_type_qualifier].
+                        format!(
+                            "There is no import named {} in this file.",
+                            qualifier.code_str(),
+                        )
+                    } else {
+                        format!("There is no type named {} in this file.", name.code_str())
+                    },
+                    Some(source_path),
+                    Some(&listing(source_contents, r#type.source_range)),
+                    None,
+                ));
+            }
+        }
+    }
+}
+
+// This function checks that declarations have no cycles.
+fn check_declaration_for_cycles(
+    all_types: &HashMap<(schema::Namespace, Identifier), (&schema::Schema, &schema::Declaration)>,
+    types_checked: &mut HashSet<(schema::Namespace, Identifier)>,
+    types_visited_set: &mut HashSet<(schema::Namespace, Identifier)>,
+    types_visited_vec: &mut Vec<(schema::Namespace, Identifier)>,
+    errors: &mut Vec<Error>,
+    namespace: &schema::Namespace,
+    name: &Identifier,
+) {
+    // Compute this once here so we don't have to compute it in multiple places below.
+    let qualified_type = (namespace.clone(), name.clone());
+
     // Stop if we've already checked this type.
     if types_checked.contains(&qualified_type) {
         return;
@@ -295,9 +1210,22 @@ fn check_declaration_for_cycles(
         return;
     }
 
-    // Check the type of each field. The `unwrap` is safe due to
-    // [ref:schemas_valid_except_possible_cycles].
+    // Check the underlying type (for newtypes) and the type of each field. The `unwrap` is safe
+    // due to [ref:schemas_valid_except_possible_cycles].
     let (schema, declaration) = all_types.get(&qualified_type).unwrap();
+    if let schema::DeclarationVariant::Newtype(underlying_type) = &declaration.variant {
+        check_type_for_cycles(
+            all_types,
+            types_checked,
+            types_visited_set,
+            types_visited_vec,
+            errors,
+            namespace,
+            schema,
+            &declaration.parameters,
+            underlying_type,
+        );
+    }
     for field in &declaration.fields {
         check_type_for_cycles(
             all_types,
@@ -307,6 +1235,7 @@ fn check_declaration_for_cycles(
             errors,
             namespace,
             schema,
+            &declaration.parameters,
             &field.r#type,
         );
     }
@@ -319,7 +1248,10 @@ fn check_declaration_for_cycles(
     types_checked.insert(qualified_type);
 }
 
-// This function checks that types have no cycles.
+// This function checks that types have no cycles. References to generic declarations are followed
+// through both the declaration and the type arguments, so recursion through type arguments (e.g.,
+// a `Foo` containing a `Page<Foo>`) is also reported. The `parameters` are the type parameters of
+// the enclosing declaration, which don't refer to any declarations.
 #[allow(clippy::too_many_arguments)]
 fn check_type_for_cycles(
     all_types: &HashMap<(schema::Namespace, Identifier), (&schema::Schema, &schema::Declaration)>,
@@ -329,10 +1261,11 @@ fn check_type_for_cycles(
     errors: &mut Vec<Error>,
     namespace: &schema::Namespace,
     schema: &schema::Schema,
+    parameters: &[Identifier],
     r#type: &schema::Type,
 ) {
     match &r#type.variant {
-        schema::TypeVariant::Array(inner_type) => {
+        schema::TypeVariant::Array(inner_type, _) => {
             check_type_for_cycles(
                 all_types,
                 types_checked,
@@ -341,17 +1274,59 @@ fn check_type_for_cycles(
                 errors,
                 namespace,
                 schema,
+                parameters,
                 inner_type,
             );
         }
+        schema::TypeVariant::Map(_, value_type) => {
+            // Key types are never custom types due to [ref:map_key_types].
+            check_type_for_cycles(
+                all_types,
+                types_checked,
+                types_visited_set,
+                types_visited_vec,
+                errors,
+                namespace,
+                schema,
+                parameters,
+                value_type,
+            );
+        }
         schema::TypeVariant::Bool
-        | schema::TypeVariant::Bytes
+        | schema::TypeVariant::Bytes(_)
+        | schema::TypeVariant::F32
         | schema::TypeVariant::F64
+        | schema::TypeVariant::S32
         | schema::TypeVariant::S64
         | schema::TypeVariant::String
+        | schema::TypeVariant::U32
         | schema::TypeVariant::U64
+        | schema::TypeVariant::U8
         | schema::TypeVariant::Unit => {}
-        schema::TypeVariant::Custom(import, name) => {
+        schema::TypeVariant::Newtype(_, _, _) => {
+            // Impossible since references to newtypes are only resolved by
+            // [ref:monomorphization].
+            panic!()
+        }
+        schema::TypeVariant::Custom(import, name, arguments) => {
+            if import.is_none() && parameters.contains(name) {
+                return;
+            }
+
+            for argument in arguments {
+                check_type_for_cycles(
+                    all_types,
+                    types_checked,
+                    types_visited_set,
+                    types_visited_vec,
+                    errors,
+                    namespace,
+                    schema,
+                    parameters,
+                    argument,
+                );
+            }
+
             let type_namespace = import.as_ref().map_or_else(
                 || namespace.clone(),
                 |import|
@@ -436,31 +1411,1243 @@ mod tests {
             }
         "
         .to_owned();
-
-        let foo_tokens = tokenize(&foo_path, &foo_contents).unwrap();
-        let mut foo_schema = parse(&foo_path, &foo_contents, &foo_tokens).unwrap();
-        foo_schema.imports.get_mut(&"bar".into()).unwrap().namespace = Some(bar_namespace.clone());
-
-        let bar_tokens = tokenize(&bar_path, &bar_contents).unwrap();
-        let bar_schema = parse(&bar_path, &bar_contents, &bar_tokens).unwrap();
+
+        let foo_tokens = tokenize(&foo_path, &foo_contents).unwrap();
+        let mut foo_schema = parse(&foo_path, &foo_contents, &foo_tokens).unwrap();
+        foo_schema.imports.get_mut(&"bar".into()).unwrap().namespace = Some(bar_namespace.clone());
+
+        let bar_tokens = tokenize(&bar_path, &bar_contents).unwrap();
+        let bar_schema = parse(&bar_path, &bar_contents, &bar_tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(foo_namespace, (foo_schema, foo_path, foo_contents));
+        schemas.insert(bar_namespace, (bar_schema, bar_path, bar_contents));
+
+        assert_same!(validate(&schemas), Ok(()));
+    }
+
+    #[test]
+    fn validate_duplicate_declaration_names() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            struct foo {
+            }
+
+            struct Foo {
+            }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "A declaration named `Foo` already exists in this file.",
+        );
+    }
+
+    #[test]
+    fn validate_duplicate_struct_field_names() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            struct Foo {
+                X: Bool = 0
+                x: F64 = 1
+            }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "A field named `x` already exists in this declaration.",
+        );
+    }
+
+    #[test]
+    fn validate_duplicate_struct_field_indices() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            struct Foo {
+                x: Bool = 0
+                y: F64 = 0
+            }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "A field with index `0` already exists in this declaration.",
+        );
+    }
+
+    #[test]
+    fn validate_deleted_struct_field_index() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            struct Foo {
+                x: Bool = 0
+
+                deleted 0
+            }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "Field index `0` is marked as deleted in this declaration.",
+        );
+    }
+
+    #[test]
+    fn validate_struct_field_index_gap() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            struct Foo {
+                x: Bool = 0
+                y: Bool = 3
+
+                deleted 1
+            }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "Field index gap found. Unused index `2` is not marked as deleted.",
+        );
+    }
+
+    #[test]
+    fn validate_duplicate_choice_field_names() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            choice Foo {
+                X: Bool = 0
+                x: F64 = 1
+            }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "A field named `x` already exists in this declaration.",
+        );
+    }
+
+    #[test]
+    fn validate_duplicate_choice_field_indices() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            choice Foo {
+                x: Bool = 0
+                y: F64 = 0
+            }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "A field with index `0` already exists in this declaration.",
+        );
+    }
+
+    #[test]
+    fn validate_deleted_choice_field_index() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            choice Foo {
+                x: Bool = 0
+
+                deleted 0
+            }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "Field index `0` is marked as deleted in this declaration.",
+        );
+    }
+
+    #[test]
+    fn validate_choice_field_index_gap() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            choice Foo {
+                x: Bool = 0
+                y: Bool = 3
+
+                deleted 1
+            }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "Field index gap found. Unused index `2` is not marked as deleted.",
+        );
+    }
+
+    #[test]
+    fn validate_non_existent_field_import() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            struct Foo {
+                x: bar.Bar = 0
+            }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "There is no import named `bar` in this file.",
+        );
+    }
+
+    #[test]
+    fn validate_non_existent_field_import_in_array() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            struct Foo {
+                x: [bar.Bar] = 0
+            }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "There is no import named `bar` in this file.",
+        );
+    }
+
+    #[test]
+    fn validate_non_existent_field_type_same_file() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            struct Foo {
+                x: Bar = 0
+            }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "There is no type named `Bar` in this file.",
+        );
+    }
+
+    #[test]
+    fn validate_non_existent_field_type_in_array_same_file() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            struct Foo {
+                x: [Bar] = 0
+            }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "There is no type named `Bar` in this file.",
+        );
+    }
+
+    #[test]
+    fn validate_map() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            struct Foo {
+                x: {String: [{U64: Bool}]} = 0
+                y: {U8: {S32: F32}} = 1
+            }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_same!(validate(&schemas), Ok(()));
+    }
+
+    #[test]
+    fn validate_map_invalid_key_type() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            struct Foo {
+                x: {F64: String} = 0
+            }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(validate(&schemas), "`F64` can't be the key type of a map.",);
+    }
+
+    #[test]
+    fn validate_non_existent_field_type_in_map_same_file() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            struct Foo {
+                x: {String: Bar} = 0
+            }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "There is no type named `Bar` in this file.",
+        );
+    }
+
+    #[test]
+    fn validate_non_existent_field_type_different_file() {
+        let foo_namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let foo_path = Path::new("foo.t").to_owned();
+        let foo_contents = "
+            import 'bar.t'
+
+            struct Foo {
+                x: bar.Bar = 0
+            }
+        "
+        .to_owned();
+
+        let bar_namespace = Namespace {
+            components: vec!["bar".into()],
+        };
+        let bar_path = Path::new("bar.t").to_owned();
+        let bar_contents = "
+            struct Qux {
+            }
+        "
+        .to_owned();
+
+        let foo_tokens = tokenize(&foo_path, &foo_contents).unwrap();
+        let mut foo_schema = parse(&foo_path, &foo_contents, &foo_tokens).unwrap();
+        foo_schema.imports.get_mut(&"bar".into()).unwrap().namespace = Some(bar_namespace.clone());
+
+        let bar_tokens = tokenize(&bar_path, &bar_contents).unwrap();
+        let bar_schema = parse(&bar_path, &bar_contents, &bar_tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(foo_namespace, (foo_schema, foo_path, foo_contents));
+        schemas.insert(bar_namespace, (bar_schema, bar_path, bar_contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "There is no type named `Bar` in import `bar`.",
+        );
+    }
+
+    #[test]
+    fn validate_non_existent_field_type_in_array_different_file() {
+        let foo_namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let foo_path = Path::new("foo.t").to_owned();
+        let foo_contents = "
+            import 'bar.t'
+
+            struct Foo {
+                x: [bar.Bar] = 0
+            }
+        "
+        .to_owned();
+
+        let bar_namespace = Namespace {
+            components: vec!["bar".into()],
+        };
+        let bar_path = Path::new("bar.t").to_owned();
+        let bar_contents = "
+            struct Qux {
+            }
+        "
+        .to_owned();
+
+        let foo_tokens = tokenize(&foo_path, &foo_contents).unwrap();
+        let mut foo_schema = parse(&foo_path, &foo_contents, &foo_tokens).unwrap();
+        foo_schema.imports.get_mut(&"bar".into()).unwrap().namespace = Some(bar_namespace.clone());
+
+        let bar_tokens = tokenize(&bar_path, &bar_contents).unwrap();
+        let bar_schema = parse(&bar_path, &bar_contents, &bar_tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(foo_namespace, (foo_schema, foo_path, foo_contents));
+        schemas.insert(bar_namespace, (bar_schema, bar_path, bar_contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "There is no type named `Bar` in import `bar`.",
+        );
+    }
+
+    #[test]
+    fn validate_cycle() {
+        let foo_namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let foo_path = Path::new("foo.t").to_owned();
+        let foo_contents = "
+            import 'bar.t'
+
+            struct Foo {
+                x: bar.Bar = 0
+            }
+        "
+        .to_owned();
+
+        let bar_namespace = Namespace {
+            components: vec!["bar".into()],
+        };
+        let bar_path = Path::new("bar.t").to_owned();
+        let bar_contents = "
+            import 'foo.t'
+
+            choice Bar {
+                x: foo.Foo = 0
+            }
+        "
+        .to_owned();
+
+        let foo_tokens = tokenize(&foo_path, &foo_contents).unwrap();
+        let mut foo_schema = parse(&foo_path, &foo_contents, &foo_tokens).unwrap();
+        foo_schema.imports.get_mut(&"bar".into()).unwrap().namespace = Some(bar_namespace.clone());
+
+        let bar_tokens = tokenize(&bar_path, &bar_contents).unwrap();
+        let mut bar_schema = parse(&bar_path, &bar_contents, &bar_tokens).unwrap();
+        bar_schema.imports.get_mut(&"foo".into()).unwrap().namespace = Some(foo_namespace.clone());
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(foo_namespace, (foo_schema, foo_path, foo_contents));
+        schemas.insert(bar_namespace, (bar_schema, bar_path, bar_contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "Cycle detected: `bar.Bar` \u{2192} `foo.Foo` \u{2192} `bar.Bar`.",
+        );
+    }
+
+    #[test]
+    fn validate_cycle_in_array() {
+        let foo_namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let foo_path = Path::new("foo.t").to_owned();
+        let foo_contents = "
+            import 'bar.t'
+
+            struct Foo {
+                x: [bar.Bar] = 0
+            }
+        "
+        .to_owned();
+
+        let bar_namespace = Namespace {
+            components: vec!["bar".into()],
+        };
+        let bar_path = Path::new("bar.t").to_owned();
+        let bar_contents = "
+            import 'foo.t'
+
+            choice Bar {
+                x: foo.Foo = 0
+            }
+        "
+        .to_owned();
+
+        let foo_tokens = tokenize(&foo_path, &foo_contents).unwrap();
+        let mut foo_schema = parse(&foo_path, &foo_contents, &foo_tokens).unwrap();
+        foo_schema.imports.get_mut(&"bar".into()).unwrap().namespace = Some(bar_namespace.clone());
+
+        let bar_tokens = tokenize(&bar_path, &bar_contents).unwrap();
+        let mut bar_schema = parse(&bar_path, &bar_contents, &bar_tokens).unwrap();
+        bar_schema.imports.get_mut(&"foo".into()).unwrap().namespace = Some(foo_namespace.clone());
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(foo_namespace, (foo_schema, foo_path, foo_contents));
+        schemas.insert(bar_namespace, (bar_schema, bar_path, bar_contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "Cycle detected: `bar.Bar` \u{2192} `foo.Foo` \u{2192} `bar.Bar`.",
+        );
+    }
+
+    #[test]
+    fn validate_generic() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+                    struct Page<T> {
+                        items: [T] = 0
+                        next: String = 1
+                    }
+
+                    struct Foo {
+                        x: Page<Bar> = 0
+                        y: Page<Page<{String: Bar}>> = 1
+                    }
+
+                    struct Bar {
+                    }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_same!(validate(&schemas), Ok(()));
+    }
+
+    #[test]
+    fn validate_generic_missing_type_arguments() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+                    struct Page<T> {
+                        items: [T] = 0
+                    }
+
+                    struct Foo {
+                        x: Page = 0
+                    }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "`Page` expects 1 type argument, but 0 were provided.",
+        );
+    }
+
+    #[test]
+    fn validate_generic_extra_type_arguments() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+                    struct Foo {
+                        x: Bar<U64> = 0
+                    }
+
+                    struct Bar {
+                    }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "`Bar` expects 0 type arguments, but 1 was provided.",
+        );
+    }
+
+    #[test]
+    fn validate_type_parameter_with_type_arguments() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+                    struct Page<T> {
+                        items: [T<U64>] = 0
+                    }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "Type parameter `T` can't have type arguments.",
+        );
+    }
+
+    #[test]
+    fn validate_duplicate_type_parameters() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+                    struct Pair<T, t> {
+                        x: T = 0
+                    }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "A type parameter named `t` already exists in this declaration.",
+        );
+    }
+
+    #[test]
+    fn validate_cycle_through_type_argument() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+                    struct Page<T> {
+                        items: [T] = 0
+                    }
+
+                    struct Foo {
+                        x: Page<Foo> = 0
+                    }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "Cycle detected: `foo.Foo` \u{2192} `foo.Foo`.",
+        );
+    }
+
+    #[test]
+    fn validate_instantiation_name_conflict() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+                    struct Page<T> {
+                        items: [T] = 0
+                    }
+
+                    struct Foo {
+                        x: Page<Bar> = 0
+                    }
+
+                    struct Bar {
+                    }
+
+                    struct PageBar {
+                    }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "`foo.Page<foo.Bar>` would be named `PageBar`, but a declaration with that name \
+                already exists in `foo`.",
+        );
+    }
+
+    #[test]
+    fn validate_newtype() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+                    newtype Id = U64
+                    newtype Ids<T> = [T]
+
+                    struct Foo {
+                        x: Id = 0
+                        y: Ids<Id> = 1
+                        z: {String: Ids<Bar>} = 2
+                    }
+
+                    struct Bar {
+                    }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_same!(validate(&schemas), Ok(()));
+    }
+
+    #[test]
+    fn validate_newtype_type_parameter() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+                    newtype Id<T> = T
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "The underlying type of a newtype can't be a type parameter.",
+        );
+    }
+
+    #[test]
+    fn validate_newtype_unit() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+                    newtype Nothing = Unit
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "The underlying type of a newtype can't be `Unit`.",
+        );
+    }
+
+    #[test]
+    fn validate_newtype_map_key() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+                    newtype Id = U64
+
+                    struct Foo {
+                        x: {Id: String} = 0
+                    }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(validate(&schemas), "`Id` can't be the key type of a map.");
+    }
+
+    #[test]
+    fn validate_newtype_cycle() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+                    newtype Ids = [Foo]
+
+                    struct Foo {
+                        x: Ids = 0
+                    }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(validate(&schemas), "Cycle detected");
+    }
+
+    #[test]
+    fn validate_fixed_length() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+                    const MAGIC: Bytes(2) = 0x7f45
+
+                    struct Foo {
+                        x: [[U64; 2]; 3] = 0
+                        y: {String: Bytes(32)} = 1
+                    }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_same!(validate(&schemas), Ok(()));
+    }
+
+    #[test]
+    fn validate_fixed_length_zero() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+                    struct Foo {
+                        x: [String; 0] = 0
+                    }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "The length of `[String; 0]` must be positive."
+        );
+    }
+
+    #[test]
+    fn validate_constant_wrong_length() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            const MAGIC: Bytes(4) = 0x7f45
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "The value `0x7f45` has 2 bytes, but `Bytes(4)` requires exactly 4 bytes.",
+        );
+    }
+
+    #[test]
+    fn validate_nested() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+                    struct Device {
+                        address: Device.IpAddress = 0
+                        id: Device.Id = 1
+
+                        choice IpAddress {
+                            v4: Device.IpAddress.V4 = 0
+                            v6: Bytes = 1
+
+                            struct V4 {
+                                octets: Bytes = 0
+                            }
+                        }
+
+                        newtype Id = U64
+                    }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_same!(validate(&schemas), Ok(()));
+    }
+
+    #[test]
+    fn validate_nested_unqualified() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+                    struct Device {
+                        address: IpAddress = 0
+
+                        choice IpAddress {
+                        }
+                    }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "There is no type named `IpAddress` in this file.",
+        );
+    }
+
+    #[test]
+    fn validate_nested_name_conflict() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+                    struct Device {
+                        choice IpAddress {
+                        }
+                    }
+
+                    struct DeviceIpAddress {
+                    }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "A declaration named `DeviceIpAddress` already exists in this file.",
+        );
+    }
+
+    #[test]
+    fn validate_nested_parent_type_parameter() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+                    struct Foo<T> {
+                        struct Bar {
+                            x: T = 0
+                        }
+                    }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "There is no type named `T` in this file.",
+        );
+    }
+
+    #[test]
+    fn validate_constants() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            const MAX_U8: U8 = 255
+            const MIN_S32: S32 = -2147483648
+            const MAX_U64: U64 = 18446744073709551615
+            const MIN_S64: S64 = -9223372036854775808
+            const RATIO: F32 = -1.5
+            const PI: F64 = 3.14
+            const ENABLED: Bool = true
+            const GREETING: String = \"hello\"
+            const MAGIC: Bytes = 0x7f45
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_same!(validate(&schemas), Ok(()));
+    }
+
+    #[test]
+    fn validate_duplicate_constant_names() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            const max_size: U64 = 1
+            const MAX_SIZE: U64 = 2
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "A constant named `MAX_SIZE` already exists in this file.",
+        );
+    }
+
+    #[test]
+    fn validate_constant_type_mismatch() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            const VERSION: U64 = \"3\"
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "The value `\"3\"` doesn't have type `U64`.",
+        );
+    }
+
+    #[test]
+    fn validate_constant_out_of_range() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            const OFFSET: U32 = -1
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "The value `-1` is out of range for `U32`.",
+        );
+    }
+
+    #[test]
+    fn validate_constant_non_scalar_type() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            const SIZES: [U64] = 3
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
+
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "Constant `SIZES` has type `[U64]`, but constants must have a scalar built-in type.",
+        );
+    }
+
+    #[test]
+    fn validate_attributes() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
+        };
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            @rust.derive(Hash, Eq)
+            @deprecated
+            struct Foo {
+                @deprecated(\"Use `y`.\")
+                @rust.rename(z)
+                @typescript.rename(z)
+                x: U64 = 0
+            
+                y: U64 = 1
+            }
+        "
+        .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
 
         let mut schemas = BTreeMap::new();
-        schemas.insert(foo_namespace, (foo_schema, foo_path, foo_contents));
-        schemas.insert(bar_namespace, (bar_schema, bar_path, bar_contents));
+        schemas.insert(namespace, (schema, path, contents));
 
         assert_same!(validate(&schemas), Ok(()));
     }
 
     #[test]
-    fn validate_duplicate_declaration_names() {
+    fn validate_attribute_unknown() {
         let namespace = Namespace {
             components: vec!["foo".into()],
         };
         let path = Path::new("foo.t").to_owned();
         let contents = "
-            struct foo {
-            }
-
+            @rust.serde
             struct Foo {
             }
         "
@@ -471,22 +2658,20 @@ mod tests {
         let mut schemas = BTreeMap::new();
         schemas.insert(namespace, (schema, path, contents));
 
-        assert_fails!(
-            validate(&schemas),
-            "A declaration named `Foo` already exists in this file.",
-        );
+        assert_fails!(validate(&schemas), "Unknown attribute `@rust.serde`.",);
     }
 
     #[test]
-    fn validate_duplicate_struct_field_names() {
+    fn validate_attribute_repeated() {
         let namespace = Namespace {
             components: vec!["foo".into()],
         };
         let path = Path::new("foo.t").to_owned();
         let contents = "
             struct Foo {
-                X: Bool = 0
-                x: F64 = 1
+                @deprecated
+                @deprecated
+                x: U64 = 0
             }
         "
         .to_owned();
@@ -498,20 +2683,20 @@ mod tests {
 
         assert_fails!(
             validate(&schemas),
-            "A field named `x` already exists in this declaration.",
+            "The `@deprecated` attribute is already applied here.",
         );
     }
 
     #[test]
-    fn validate_duplicate_struct_field_indices() {
+    fn validate_attribute_wrong_site() {
         let namespace = Namespace {
             components: vec!["foo".into()],
         };
         let path = Path::new("foo.t").to_owned();
         let contents = "
             struct Foo {
-                x: Bool = 0
-                y: F64 = 0
+                @rust.derive(Hash)
+                x: U64 = 0
             }
         "
         .to_owned();
@@ -523,21 +2708,20 @@ mod tests {
 
         assert_fails!(
             validate(&schemas),
-            "A field with index `0` already exists in this declaration.",
+            "The `@rust.derive` attribute can only be applied to declarations.",
         );
     }
 
     #[test]
-    fn validate_deleted_struct_field_index() {
+    fn validate_attribute_wrong_arguments() {
         let namespace = Namespace {
             components: vec!["foo".into()],
         };
         let path = Path::new("foo.t").to_owned();
         let contents = "
             struct Foo {
-                x: Bool = 0
-
-                deleted 0
+                @rust.rename(\"y\")
+                x: U64 = 0
             }
         "
         .to_owned();
@@ -549,22 +2733,22 @@ mod tests {
 
         assert_fails!(
             validate(&schemas),
-            "Field index `0` is marked as deleted in this declaration.",
+            "The `@rust.rename` attribute takes exactly one argument, which must be a name.",
         );
     }
 
     #[test]
-    fn validate_struct_field_index_gap() {
+    fn validate_attribute_rename_clash() {
         let namespace = Namespace {
             components: vec!["foo".into()],
         };
         let path = Path::new("foo.t").to_owned();
         let contents = "
             struct Foo {
-                x: Bool = 0
-                y: Bool = 3
-
-                deleted 1
+                x: U64 = 0
+            
+                @typescript.rename(x)
+                y: U64 = 1
             }
         "
         .to_owned();
@@ -576,20 +2760,25 @@ mod tests {
 
         assert_fails!(
             validate(&schemas),
-            "Field index gap found. Unused index `2` is not marked as deleted.",
+            "A field named `x` already exists in this declaration in the code generated for \
+                `typescript`.",
         );
     }
 
     #[test]
-    fn validate_duplicate_choice_field_names() {
+    fn validate_constraints() {
         let namespace = Namespace {
             components: vec!["foo".into()],
         };
         let path = Path::new("foo.t").to_owned();
         let contents = "
-            choice Foo {
-                X: Bool = 0
-                x: F64 = 1
+            newtype Name = String
+
+            struct Foo {
+                @min(-128) @max(127.5) x: F32 = 0
+                @min_length(1) @max_length(3) y: [Name] = 1
+                @max_length(16) z: Name = 2
+                @min(0) @max(0) w: U8 = 3
             }
         "
         .to_owned();
@@ -599,22 +2788,18 @@ mod tests {
         let mut schemas = BTreeMap::new();
         schemas.insert(namespace, (schema, path, contents));
 
-        assert_fails!(
-            validate(&schemas),
-            "A field named `x` already exists in this declaration.",
-        );
+        assert_same!(validate(&schemas), Ok(()));
     }
 
     #[test]
-    fn validate_duplicate_choice_field_indices() {
+    fn validate_constraint_wrong_type() {
         let namespace = Namespace {
             components: vec!["foo".into()],
         };
         let path = Path::new("foo.t").to_owned();
         let contents = "
-            choice Foo {
-                x: Bool = 0
-                y: F64 = 0
+            struct Foo {
+                @max_length(3) x: U64 = 0
             }
         "
         .to_owned();
@@ -626,21 +2811,20 @@ mod tests {
 
         assert_fails!(
             validate(&schemas),
-            "A field with index `0` already exists in this declaration.",
+            "The `@max_length` attribute can only be applied to fields of type `String`, `Bytes`, \
+                arrays, or maps.",
         );
     }
 
     #[test]
-    fn validate_deleted_choice_field_index() {
+    fn validate_constraint_out_of_range() {
         let namespace = Namespace {
             components: vec!["foo".into()],
         };
         let path = Path::new("foo.t").to_owned();
         let contents = "
-            choice Foo {
-                x: Bool = 0
-
-                deleted 0
+            struct Foo {
+                @max(256) x: U8 = 0
             }
         "
         .to_owned();
@@ -652,22 +2836,19 @@ mod tests {
 
         assert_fails!(
             validate(&schemas),
-            "Field index `0` is marked as deleted in this declaration.",
+            "The value `256` is out of range for `U8`."
         );
     }
 
     #[test]
-    fn validate_choice_field_index_gap() {
+    fn validate_constraint_min_greater_than_max() {
         let namespace = Namespace {
             components: vec!["foo".into()],
         };
         let path = Path::new("foo.t").to_owned();
         let contents = "
-            choice Foo {
-                x: Bool = 0
-                y: Bool = 3
-
-                deleted 1
+            struct Foo {
+                @min(2) @max(1.5) x: F64 = 0
             }
         "
         .to_owned();
@@ -679,19 +2860,19 @@ mod tests {
 
         assert_fails!(
             validate(&schemas),
-            "Field index gap found. Unused index `2` is not marked as deleted.",
+            "The minimum `2` is greater than the maximum `1.5`."
         );
     }
 
     #[test]
-    fn validate_non_existent_field_import() {
+    fn validate_constraint_wrong_arguments() {
         let namespace = Namespace {
             components: vec!["foo".into()],
         };
         let path = Path::new("foo.t").to_owned();
         let contents = "
             struct Foo {
-                x: bar.Bar = 0
+                @min_length(-1) x: String = 0
             }
         "
         .to_owned();
@@ -703,19 +2884,30 @@ mod tests {
 
         assert_fails!(
             validate(&schemas),
-            "There is no import named `bar` in this file.",
+            "The `@min_length` attribute takes exactly one argument, which must be a \
+                non-negative integer.",
         );
     }
 
     #[test]
-    fn validate_non_existent_field_import_in_array() {
+    fn validate_service() {
         let namespace = Namespace {
             components: vec!["foo".into()],
         };
         let path = Path::new("foo.t").to_owned();
         let contents = "
-            struct Foo {
-                x: [bar.Bar] = 0
+            struct Request {
+            }
+
+            choice Response<T> {
+                value: T = 0
+            }
+
+            service Mail {
+                send: Request -> Response<Request> = 0
+                ping: Request -> Request = 2
+
+                deleted 1
             }
         "
         .to_owned();
@@ -725,21 +2917,20 @@ mod tests {
         let mut schemas = BTreeMap::new();
         schemas.insert(namespace, (schema, path, contents));
 
-        assert_fails!(
-            validate(&schemas),
-            "There is no import named `bar` in this file.",
-        );
+        assert_same!(validate(&schemas), Ok(()));
     }
 
     #[test]
-    fn validate_non_existent_field_type_same_file() {
+    fn validate_service_name_clash() {
         let namespace = Namespace {
             components: vec!["foo".into()],
         };
         let path = Path::new("foo.t").to_owned();
         let contents = "
-            struct Foo {
-                x: Bar = 0
+            struct Mail {
+            }
+
+            service Mail {
             }
         "
         .to_owned();
@@ -751,19 +2942,23 @@ mod tests {
 
         assert_fails!(
             validate(&schemas),
-            "There is no type named `Bar` in this file.",
+            "A declaration or service named `Mail` already exists in this file.",
         );
     }
 
     #[test]
-    fn validate_non_existent_field_type_in_array_same_file() {
+    fn validate_duplicate_method_names() {
         let namespace = Namespace {
             components: vec!["foo".into()],
         };
         let path = Path::new("foo.t").to_owned();
         let contents = "
-            struct Foo {
-                x: [Bar] = 0
+            struct Request {
+            }
+
+            service Mail {
+                send: Request -> Request = 0
+                send: Request -> Request = 1
             }
         "
         .to_owned();
@@ -775,183 +2970,147 @@ mod tests {
 
         assert_fails!(
             validate(&schemas),
-            "There is no type named `Bar` in this file.",
+            "A method named `send` already exists in this service.",
         );
     }
 
     #[test]
-    fn validate_non_existent_field_type_different_file() {
-        let foo_namespace = Namespace {
+    fn validate_duplicate_method_indices() {
+        let namespace = Namespace {
             components: vec!["foo".into()],
         };
-        let foo_path = Path::new("foo.t").to_owned();
-        let foo_contents = "
-            import 'bar.t'
-
-            struct Foo {
-                x: bar.Bar = 0
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            struct Request {
             }
-        "
-        .to_owned();
 
-        let bar_namespace = Namespace {
-            components: vec!["bar".into()],
-        };
-        let bar_path = Path::new("bar.t").to_owned();
-        let bar_contents = "
-            struct Qux {
+            service Mail {
+                send: Request -> Request = 0
+                ping: Request -> Request = 0
             }
         "
         .to_owned();
-
-        let foo_tokens = tokenize(&foo_path, &foo_contents).unwrap();
-        let mut foo_schema = parse(&foo_path, &foo_contents, &foo_tokens).unwrap();
-        foo_schema.imports.get_mut(&"bar".into()).unwrap().namespace = Some(bar_namespace.clone());
-
-        let bar_tokens = tokenize(&bar_path, &bar_contents).unwrap();
-        let bar_schema = parse(&bar_path, &bar_contents, &bar_tokens).unwrap();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
 
         let mut schemas = BTreeMap::new();
-        schemas.insert(foo_namespace, (foo_schema, foo_path, foo_contents));
-        schemas.insert(bar_namespace, (bar_schema, bar_path, bar_contents));
+        schemas.insert(namespace, (schema, path, contents));
 
         assert_fails!(
             validate(&schemas),
-            "There is no type named `Bar` in import `bar`.",
+            "A method with index `0` already exists in this service.",
         );
     }
 
     #[test]
-    fn validate_non_existent_field_type_in_array_different_file() {
-        let foo_namespace = Namespace {
+    fn validate_deleted_method_index() {
+        let namespace = Namespace {
             components: vec!["foo".into()],
         };
-        let foo_path = Path::new("foo.t").to_owned();
-        let foo_contents = "
-            import 'bar.t'
-
-            struct Foo {
-                x: [bar.Bar] = 0
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            struct Request {
             }
-        "
-        .to_owned();
 
-        let bar_namespace = Namespace {
-            components: vec!["bar".into()],
-        };
-        let bar_path = Path::new("bar.t").to_owned();
-        let bar_contents = "
-            struct Qux {
+            service Mail {
+                send: Request -> Request = 0
+
+                deleted 0
             }
         "
         .to_owned();
-
-        let foo_tokens = tokenize(&foo_path, &foo_contents).unwrap();
-        let mut foo_schema = parse(&foo_path, &foo_contents, &foo_tokens).unwrap();
-        foo_schema.imports.get_mut(&"bar".into()).unwrap().namespace = Some(bar_namespace.clone());
-
-        let bar_tokens = tokenize(&bar_path, &bar_contents).unwrap();
-        let bar_schema = parse(&bar_path, &bar_contents, &bar_tokens).unwrap();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
 
         let mut schemas = BTreeMap::new();
-        schemas.insert(foo_namespace, (foo_schema, foo_path, foo_contents));
-        schemas.insert(bar_namespace, (bar_schema, bar_path, bar_contents));
+        schemas.insert(namespace, (schema, path, contents));
 
         assert_fails!(
             validate(&schemas),
-            "There is no type named `Bar` in import `bar`.",
+            "Method index `0` is marked as deleted in this service.",
         );
     }
 
     #[test]
-    fn validate_cycle() {
-        let foo_namespace = Namespace {
+    fn validate_method_index_gap() {
+        let namespace = Namespace {
             components: vec!["foo".into()],
         };
-        let foo_path = Path::new("foo.t").to_owned();
-        let foo_contents = "
-            import 'bar.t'
-
-            struct Foo {
-                x: bar.Bar = 0
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            struct Request {
             }
-        "
-        .to_owned();
 
-        let bar_namespace = Namespace {
-            components: vec!["bar".into()],
-        };
-        let bar_path = Path::new("bar.t").to_owned();
-        let bar_contents = "
-            import 'foo.t'
-
-            choice Bar {
-                x: foo.Foo = 0
+            service Mail {
+                send: Request -> Request = 1
             }
         "
         .to_owned();
-
-        let foo_tokens = tokenize(&foo_path, &foo_contents).unwrap();
-        let mut foo_schema = parse(&foo_path, &foo_contents, &foo_tokens).unwrap();
-        foo_schema.imports.get_mut(&"bar".into()).unwrap().namespace = Some(bar_namespace.clone());
-
-        let bar_tokens = tokenize(&bar_path, &bar_contents).unwrap();
-        let mut bar_schema = parse(&bar_path, &bar_contents, &bar_tokens).unwrap();
-        bar_schema.imports.get_mut(&"foo".into()).unwrap().namespace = Some(foo_namespace.clone());
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
 
         let mut schemas = BTreeMap::new();
-        schemas.insert(foo_namespace, (foo_schema, foo_path, foo_contents));
-        schemas.insert(bar_namespace, (bar_schema, bar_path, bar_contents));
+        schemas.insert(namespace, (schema, path, contents));
 
         assert_fails!(
             validate(&schemas),
-            "Cycle detected: `bar.Bar` \u{2192} `foo.Foo` \u{2192} `bar.Bar`.",
+            "Method index gap found. Unused index `0` is not marked as deleted.",
         );
     }
 
     #[test]
-    fn validate_cycle_in_array() {
-        let foo_namespace = Namespace {
+    fn validate_method_newtype() {
+        let namespace = Namespace {
             components: vec!["foo".into()],
         };
-        let foo_path = Path::new("foo.t").to_owned();
-        let foo_contents = "
-            import 'bar.t'
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            struct Request {
+            }
 
-            struct Foo {
-                x: [bar.Bar] = 0
+            newtype Id = Request
+
+            service Mail {
+                send: Request -> Id = 0
             }
         "
         .to_owned();
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
 
-        let bar_namespace = Namespace {
-            components: vec!["bar".into()],
+        let mut schemas = BTreeMap::new();
+        schemas.insert(namespace, (schema, path, contents));
+
+        assert_fails!(
+            validate(&schemas),
+            "The output type of method `send` must be a struct or a choice.",
+        );
+    }
+
+    #[test]
+    fn validate_method_builtin() {
+        let namespace = Namespace {
+            components: vec!["foo".into()],
         };
-        let bar_path = Path::new("bar.t").to_owned();
-        let bar_contents = "
-            import 'foo.t'
+        let path = Path::new("foo.t").to_owned();
+        let contents = "
+            struct Request {
+            }
 
-            choice Bar {
-                x: foo.Foo = 0
+            service Mail {
+                send: String -> Request = 0
             }
         "
         .to_owned();
-
-        let foo_tokens = tokenize(&foo_path, &foo_contents).unwrap();
-        let mut foo_schema = parse(&foo_path, &foo_contents, &foo_tokens).unwrap();
-        foo_schema.imports.get_mut(&"bar".into()).unwrap().namespace = Some(bar_namespace.clone());
-
-        let bar_tokens = tokenize(&bar_path, &bar_contents).unwrap();
-        let mut bar_schema = parse(&bar_path, &bar_contents, &bar_tokens).unwrap();
-        bar_schema.imports.get_mut(&"foo".into()).unwrap().namespace = Some(foo_namespace.clone());
+        let tokens = tokenize(&path, &contents).unwrap();
+        let schema = parse(&path, &contents, &tokens).unwrap();
 
         let mut schemas = BTreeMap::new();
-        schemas.insert(foo_namespace, (foo_schema, foo_path, foo_contents));
-        schemas.insert(bar_namespace, (bar_schema, bar_path, bar_contents));
+        schemas.insert(namespace, (schema, path, contents));
 
         assert_fails!(
             validate(&schemas),
-            "Cycle detected: `bar.Bar` \u{2192} `foo.Foo` \u{2192} `bar.Bar`.",
+            "The input type of method `send` must be a struct or a choice.",
         );
     }
 }
@@ -0,0 +1,90 @@
+use {
+    crate::{schema, schema_loader::path_to_namespace},
+    std::path::Path,
+};
+
+// The import paths of the well-known schemas. These paths don't depend on the location of the
+// importing schema, and the corresponding files don't need to exist [tag:well_known_schemas].
+pub const DECIMAL_PATH: &str = "typical/decimal.t";
+pub const TIME_PATH: &str = "typical/time.t";
+pub const UUID_PATH: &str = "typical/uuid.t";
+
+// The well-known schemas, which are embedded in the compiler
+const SCHEMAS: &[(&str, &str)] = &[
+    (DECIMAL_PATH, include_str!("well_known/decimal.t")),
+    (TIME_PATH, include_str!("well_known/time.t")),
+    (UUID_PATH, include_str!("well_known/uuid.t")),
+];
+
+// Return the contents of the well-known schema with the given import path, if there is one.
+#[must_use]
+pub fn contents(path: &Path) -> Option<&'static str> {
+    SCHEMAS
+        .iter()
+        .find(|(well_known_path, _)| Path::new(well_known_path) == path)
+        .map(|(_, contents)| *contents)
+}
+
+// Determine whether a namespace is that of the well-known schema with the given import path.
+#[must_use]
+pub fn is(namespace: &schema::Namespace, path: &str) -> bool {
+    *namespace == path_to_namespace(Path::new(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::{
+            schema::Namespace,
+            schema_loader::{load_schemas_from, InMemory},
+            validator::validate,
+            well_known::{contents, is, TIME_PATH, UUID_PATH},
+        },
+        std::path::Path,
+    };
+
+    #[test]
+    fn contents_well_known() {
+        assert!(contents(Path::new("typical/time.t"))
+            .unwrap()
+            .contains("struct Timestamp"));
+    }
+
+    #[test]
+    fn contents_not_well_known() {
+        assert!(contents(Path::new("typical/foo.t")).is_none());
+        assert!(contents(Path::new("time.t")).is_none());
+    }
+
+    #[test]
+    fn is_well_known() {
+        let namespace = Namespace {
+            components: vec!["typical".into(), "time".into()],
+        };
+
+        assert!(is(&namespace, TIME_PATH));
+        assert!(!is(&namespace, UUID_PATH));
+    }
+
+    #[test]
+    fn well_known_schemas_valid() {
+        let mut provider = InMemory::new();
+        provider.insert(
+            "main.t",
+            "\
+                import 'typical/decimal.t'\n\
+                import 'typical/time.t'\n\
+                import 'typical/uuid.t'\n\
+            ",
+        );
+
+        let schemas = load_schemas_from(&provider, Path::new("main.t")).unwrap();
+
+        assert!(validate(&schemas).is_ok());
+
+        // The schemas should be formatted canonically.
+        for (schema, _, contents) in schemas.values() {
+            assert_eq!(schema.to_string(), *contents);
+        }
+    }
+}
@@ -0,0 +1,9 @@
+# This schema contains a well-known type for decimal numbers. Import it with
+# `import 'typical/decimal.t'`.
+
+# An exact decimal number equal to `significand * 10^exponent`. For example,
+# `12.34` is represented with `significand = 1234` and `exponent = -2`.
+struct Decimal {
+    significand: S64 = 0
+    exponent: S32 = 1
+}
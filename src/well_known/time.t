@@ -0,0 +1,23 @@
+# This schema contains well-known types for representing time. Import it with
+# `import 'typical/time.t'`.
+
+# A point in time, represented as a number of seconds since the Unix epoch
+# (1970-01-01T00:00:00Z) plus a number of nanoseconds. Times before the epoch
+# have a negative number of seconds, but the number of nanoseconds always
+# counts forward. For example, 1969-12-31T23:59:59.5Z is represented with
+# `seconds = -1` and `nanos = 500000000`.
+struct Timestamp {
+    seconds: S64 = 0
+
+    @max(999999999)
+    nanos: U32 = 1
+}
+
+# A non-negative span of time, represented as a number of seconds plus a number
+# of nanoseconds
+struct Duration {
+    seconds: U64 = 0
+
+    @max(999999999)
+    nanos: U32 = 1
+}
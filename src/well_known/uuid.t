@@ -0,0 +1,6 @@
+# This schema contains a well-known type for universally unique identifiers.
+# Import it with `import 'typical/uuid.t'`.
+
+# A universally unique identifier (UUID) as defined by RFC 9562, represented by
+# its 16 bytes in network byte order
+newtype Uuid = Bytes(16)